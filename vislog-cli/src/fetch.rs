@@ -0,0 +1,573 @@
+//! Downloads the program and course JSON of a catalog from the catalog API.
+//!
+//! Every downloaded file is recorded in a manifest in the output directory along with the
+//! `ETag`/`Last-Modified` headers it was served with, so that fetching again only downloads the
+//! files that changed since.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use clap::Args;
+use reqwest::{
+    header::{self, HeaderMap},
+    StatusCode, Url,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use tokio::time::Instant;
+
+const DEFAULT_BASE_URL: &str = "https://iq5prod1.smartcatalogiq.com";
+const DEFAULT_CATALOG_PATH: &str =
+    "/sitecore/content/Catalogs/Union-University/2023/Academic-Catalogue-Undergraduate-Catalogue";
+
+/// Hidden so that [Catalog::parse_dir](vislog_core::catalog::Catalog::parse_dir) skips it
+const MANIFEST_FILE: &str = ".vislog-manifest.json";
+const PROGRAMS_FILE: &str = "programs.json";
+const COURSES_FILE: &str = "courses.json";
+/// Directory inside of the output directory for programs downloaded one by one
+const PROGRAMS_DIR: &str = "programs";
+/// Longest wait before retrying a request, whether it comes from the backoff or the server
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Args)]
+pub struct FetchArgs {
+    /// Root URL of the catalog API
+    #[arg(long, default_value = DEFAULT_BASE_URL)]
+    base_url: String,
+
+    /// Path of the catalog to download, as found in the `path` field of programs and courses
+    #[arg(long, default_value = DEFAULT_CATALOG_PATH)]
+    catalog_path: String,
+
+    /// Directory to write the downloaded JSON and the manifest to
+    #[arg(long, default_value = "data")]
+    out: PathBuf,
+
+    /// Also download every program on its own using the `path` of each program in the
+    /// programs dump
+    #[arg(long)]
+    each_program: bool,
+
+    /// Times to retry a request that failed because of the network or the server
+    #[arg(long, default_value_t = 3)]
+    retries: u32,
+
+    /// Minimum delay between two requests in milliseconds
+    #[arg(long, default_value_t = 500)]
+    delay_ms: u64,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("request to {url} failed: {source}")]
+    Http { url: Url, source: reqwest::Error },
+    #[error("request to {url} failed with status {status}")]
+    Status { url: Url, status: StatusCode },
+    #[error("{url} did not return valid JSON: {source}")]
+    Json { url: Url, source: serde_json::Error },
+    #[error("invalid URL {url:?}: {source}")]
+    Url {
+        url: String,
+        source: url::ParseError,
+    },
+    #[error("failed to access {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to read manifest {path:?}: {source}")]
+    Manifest {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("{path:?} is not valid JSON: {source}")]
+    InvalidFile {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("failed to fetch {failed} programs")]
+    Incomplete { failed: usize },
+}
+
+/// Record of every file downloaded into the output directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    /// Keyed by the path of the file relative to the output directory
+    files: BTreeMap<String, ManifestEntry>,
+}
+
+impl Manifest {
+    /// Headers asking the server to only send `file` again if it changed since it was downloaded
+    /// from `url`. Empty when it was downloaded from another URL or is no longer at `path`.
+    fn conditional_headers(&self, file: &str, url: &Url, path: &Path) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+
+        let Some(entry) = self.files.get(file) else {
+            return headers;
+        };
+        if &entry.url != url || !path.is_file() {
+            return headers;
+        }
+
+        let validators = [
+            (header::IF_NONE_MATCH, &entry.etag),
+            (header::IF_MODIFIED_SINCE, &entry.last_modified),
+        ];
+        for (name, value) in validators {
+            if let Some(value) = value.as_deref().and_then(|value| value.parse().ok()) {
+                headers.insert(name, value);
+            }
+        }
+
+        headers
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestEntry {
+    url: Url,
+    /// Seconds since the Unix epoch
+    fetched_at: u64,
+    bytes: usize,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+enum Outcome {
+    Downloaded {
+        bytes: usize,
+    },
+    /// The server reported that the file did not change since it was last downloaded
+    NotModified,
+}
+
+/// Runs the `fetch` subcommand to completion on a new tokio runtime
+pub fn run(args: FetchArgs) -> Result<(), Error> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|source| Error::Io {
+            path: args.out.clone(),
+            source,
+        })?;
+
+    runtime.block_on(fetch(args))
+}
+
+async fn fetch(args: FetchArgs) -> Result<(), Error> {
+    tokio::fs::create_dir_all(&args.out)
+        .await
+        .map_err(|source| Error::Io {
+            path: args.out.clone(),
+            source,
+        })?;
+
+    let mut fetcher = Fetcher::new(&args).await?;
+
+    let programs_url = api_url(&args.base_url, "progAPI", &args.catalog_path)?;
+    let courses_url = api_url(&args.base_url, "courseAPI", &args.catalog_path)?;
+
+    fetcher.download(programs_url, PROGRAMS_FILE).await?;
+    fetcher.download(courses_url, COURSES_FILE).await?;
+
+    if !args.each_program {
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for program_path in program_paths(&args.out.join(PROGRAMS_FILE)).await? {
+        let Some(slug) = program_path
+            .rsplit('/')
+            .next()
+            .filter(|slug| !slug.is_empty())
+        else {
+            continue;
+        };
+        let file = format!("{PROGRAMS_DIR}/{}.json", slug.to_lowercase());
+
+        let result = match api_url(&args.base_url, "progAPI", &program_path) {
+            Ok(url) => fetcher.download(url, &file).await,
+            Err(err) => Err(err),
+        };
+
+        // One program failing shouldn't stop the rest from being fetched
+        if let Err(err) = result {
+            eprintln!("error: {err}");
+            failed += 1;
+        }
+    }
+
+    match failed {
+        0 => Ok(()),
+        failed => Err(Error::Incomplete { failed }),
+    }
+}
+
+/// URL of an endpoint of the catalog API. Ex: `{base_url}/apis/progAPI?path={path}&format=json`
+fn api_url(base_url: &str, api: &str, path: &str) -> Result<Url, Error> {
+    let url = format!("{}/apis/{api}", base_url.trim_end_matches('/'));
+
+    let mut url = Url::parse(&url).map_err(|source| Error::Url { url, source })?;
+    url.query_pairs_mut()
+        .append_pair("path", path)
+        .append_pair("format", "json");
+
+    Ok(url)
+}
+
+/// The `path` of every program in a programs dump
+async fn program_paths(programs_file: &Path) -> Result<Vec<String>, Error> {
+    let json = tokio::fs::read_to_string(programs_file)
+        .await
+        .map_err(|source| Error::Io {
+            path: programs_file.to_owned(),
+            source,
+        })?;
+
+    paths_in(&json).map_err(|source| Error::InvalidFile {
+        path: programs_file.to_owned(),
+        source,
+    })
+}
+
+/// The `path` of every program in the `json` of a programs dump
+fn paths_in(json: &str) -> Result<Vec<String>, serde_json::Error> {
+    let value: Value = serde_json::from_str(json)?;
+
+    let paths = value
+        .pointer("/programs/program")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|program| program.get("path")?.as_str())
+        .map(str::to_owned)
+        .collect();
+
+    Ok(paths)
+}
+
+struct Fetcher {
+    client: reqwest::Client,
+    out: PathBuf,
+    manifest: Manifest,
+    retries: u32,
+    delay: Duration,
+    last_request: Option<Instant>,
+}
+
+impl Fetcher {
+    async fn new(args: &FetchArgs) -> Result<Self, Error> {
+        let manifest_path = args.out.join(MANIFEST_FILE);
+
+        let manifest = match tokio::fs::read_to_string(&manifest_path).await {
+            Ok(json) => serde_json::from_str(&json).map_err(|source| Error::Manifest {
+                path: manifest_path,
+                source,
+            })?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Manifest::default(),
+            Err(source) => {
+                return Err(Error::Io {
+                    path: manifest_path,
+                    source,
+                })
+            }
+        };
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            out: args.out.clone(),
+            manifest,
+            retries: args.retries,
+            delay: Duration::from_millis(args.delay_ms),
+            last_request: None,
+        })
+    }
+
+    /// Downloads `url` into `file`, relative to the output directory, retrying with exponential
+    /// backoff on network errors, rate limiting and server errors
+    async fn download(&mut self, url: Url, file: &str) -> Result<(), Error> {
+        let mut attempt = 0;
+
+        loop {
+            let (err, retry_after) = match self.try_download(&url, file).await {
+                Ok(Outcome::Downloaded { bytes }) => {
+                    eprintln!("fetched {file} ({bytes} bytes)");
+                    return self.save_manifest().await;
+                }
+                Ok(Outcome::NotModified) => {
+                    eprintln!("{file} is up to date");
+                    return Ok(());
+                }
+                Err(RequestError::Retryable { err, retry_after }) => (err, retry_after),
+                Err(RequestError::Fatal(err)) => return Err(err),
+            };
+
+            if attempt >= self.retries {
+                return Err(err);
+            }
+
+            let backoff = backoff(self.delay, attempt, retry_after);
+            eprintln!("warning: {err}, retrying in {}s", backoff.as_secs_f32());
+            tokio::time::sleep(backoff).await;
+
+            attempt += 1;
+        }
+    }
+
+    async fn try_download(&mut self, url: &Url, file: &str) -> Result<Outcome, RequestError> {
+        let path = self.out.join(file);
+
+        // Only ask for changes when the file from the last download is still around
+        let request = self
+            .client
+            .get(url.clone())
+            .headers(self.manifest.conditional_headers(file, url, &path));
+
+        self.throttle().await;
+
+        let http_error = |source| RequestError::Retryable {
+            err: Error::Http {
+                url: url.clone(),
+                source,
+            },
+            retry_after: None,
+        };
+
+        let response = request.send().await.map_err(http_error)?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(Outcome::NotModified);
+        }
+        if !status.is_success() {
+            let err = Error::Status {
+                url: url.clone(),
+                status,
+            };
+
+            return Err(
+                match status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+                    true => RequestError::Retryable {
+                        err,
+                        retry_after: retry_after(response.headers()),
+                    },
+                    false => RequestError::Fatal(err),
+                },
+            );
+        }
+
+        let headers = response.headers().clone();
+        let body = response.bytes().await.map_err(http_error)?;
+
+        // Make sure that an error page never ends up in place of the catalog data
+        if let Err(source) = serde_json::from_slice::<Value>(&body) {
+            return Err(RequestError::Fatal(Error::Json {
+                url: url.clone(),
+                source,
+            }));
+        }
+
+        write_file(&path, &body)
+            .await
+            .map_err(RequestError::Fatal)?;
+
+        let header_value = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned)
+        };
+
+        self.manifest.files.insert(
+            file.to_owned(),
+            ManifestEntry {
+                url: url.clone(),
+                fetched_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                bytes: body.len(),
+                etag: header_value(header::ETAG),
+                last_modified: header_value(header::LAST_MODIFIED),
+            },
+        );
+
+        Ok(Outcome::Downloaded { bytes: body.len() })
+    }
+
+    /// Waits until at least `delay` has passed since the last request
+    async fn throttle(&mut self) {
+        if let Some(last_request) = self.last_request {
+            tokio::time::sleep_until(last_request + self.delay).await;
+        }
+
+        self.last_request = Some(Instant::now());
+    }
+
+    async fn save_manifest(&self) -> Result<(), Error> {
+        let json = serde_json::to_string_pretty(&self.manifest)
+            .expect("Manifest only holds types that can always be serialized");
+
+        write_file(&self.out.join(MANIFEST_FILE), json.as_bytes()).await
+    }
+}
+
+enum RequestError {
+    Retryable {
+        err: Error,
+        /// Delay requested by the server through the `Retry-After` header
+        retry_after: Option<Duration>,
+    },
+    Fatal(Error),
+}
+
+/// Delay before retrying after `attempt` earlier retries: the delay asked for by the server, or
+/// else an exponential backoff starting from `delay`, capped at [MAX_BACKOFF] either way
+fn backoff(delay: Duration, attempt: u32, retry_after: Option<Duration>) -> Duration {
+    let backoff = retry_after.unwrap_or_else(|| {
+        2u32.checked_pow(attempt)
+            .and_then(|factor| delay.max(Duration::from_secs(1)).checked_mul(factor))
+            .unwrap_or(MAX_BACKOFF)
+    });
+
+    backoff.min(MAX_BACKOFF)
+}
+
+/// `Retry-After` header given in seconds. The HTTP date form is not supported.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let seconds = headers
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+
+    Some(Duration::from_secs(seconds))
+}
+
+/// Writes to a temporary file first so that an interrupted fetch never leaves a truncated file
+async fn write_file(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    let to_error = |source| Error::Io {
+        path: path.to_owned(),
+        source,
+    };
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(to_error)?;
+    }
+
+    let tmp_path = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp_path, contents)
+        .await
+        .map_err(to_error)?;
+    tokio::fs::rename(&tmp_path, path).await.map_err(to_error)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(url: &Url) -> ManifestEntry {
+        ManifestEntry {
+            url: url.clone(),
+            fetched_at: 0,
+            bytes: 0,
+            etag: Some("\"abc\"".to_owned()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_owned()),
+        }
+    }
+
+    #[test]
+    fn api_urls_encode_the_path() {
+        let url = api_url(
+            "https://catalog.example.edu/",
+            "progAPI",
+            "/Catalogs/Union University/2023",
+        )
+        .unwrap();
+
+        assert_eq!(
+            url.as_str(),
+            "https://catalog.example.edu/apis/progAPI?path=%2FCatalogs%2FUnion+University%2F2023&format=json"
+        );
+        assert!(matches!(
+            api_url("not a url", "progAPI", "/"),
+            Err(Error::Url { .. })
+        ));
+    }
+
+    #[test]
+    fn retry_after_is_read_in_seconds() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(header::RETRY_AFTER, "120".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+
+        headers.insert(
+            header::RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(header::RETRY_AFTER, "-1".parse().unwrap());
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_a_maximum() {
+        let delay = Duration::from_millis(500);
+
+        assert_eq!(backoff(delay, 0, None), Duration::from_secs(1));
+        assert_eq!(backoff(delay, 3, None), Duration::from_secs(8));
+        // Would overflow both `u32` and `Duration`
+        assert_eq!(backoff(delay, 40, None), MAX_BACKOFF);
+        assert_eq!(backoff(delay, u32::MAX, None), MAX_BACKOFF);
+
+        let asked = Some(Duration::from_secs(30));
+        assert_eq!(backoff(delay, 5, asked), Duration::from_secs(30));
+        assert_eq!(backoff(delay, 0, Some(Duration::MAX)), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn only_files_still_around_from_the_same_url_are_downloaded_conditionally() {
+        let url = api_url(DEFAULT_BASE_URL, "progAPI", DEFAULT_CATALOG_PATH).unwrap();
+        let other_url = api_url(DEFAULT_BASE_URL, "courseAPI", DEFAULT_CATALOG_PATH).unwrap();
+        let mut manifest = Manifest::default();
+        manifest.files.insert(PROGRAMS_FILE.to_owned(), entry(&url));
+        // Any file that exists stands in for the downloaded one
+        let existing = Path::new("Cargo.toml");
+
+        let headers = manifest.conditional_headers(PROGRAMS_FILE, &url, existing);
+        assert_eq!(headers[header::IF_NONE_MATCH], "\"abc\"");
+        assert_eq!(
+            headers[header::IF_MODIFIED_SINCE],
+            "Wed, 21 Oct 2015 07:28:00 GMT"
+        );
+
+        let missing = Path::new("missing.json");
+        assert!(manifest
+            .conditional_headers(PROGRAMS_FILE, &url, missing)
+            .is_empty());
+        assert!(manifest
+            .conditional_headers(PROGRAMS_FILE, &other_url, existing)
+            .is_empty());
+        assert!(manifest
+            .conditional_headers(COURSES_FILE, &url, existing)
+            .is_empty());
+    }
+
+    #[test]
+    fn program_paths_are_read_from_the_dump() {
+        let json =
+            r#"{"programs": {"program": [{"path": "/a"}, {"title": "no path"}, {"path": "/b"}]}}"#;
+        assert_eq!(paths_in(json).unwrap(), ["/a", "/b"]);
+
+        assert!(paths_in("{}").unwrap().is_empty());
+        assert!(paths_in(r#"{"programs": "#).is_err());
+    }
+}
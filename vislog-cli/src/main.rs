@@ -0,0 +1,347 @@
+mod fetch;
+
+use std::{io, path::PathBuf, process::ExitCode};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use fetch::FetchArgs;
+use serde_json::json;
+use thiserror::Error;
+use vislog_core::{
+    canonical,
+    catalog::{Catalog, CatalogError},
+    export::{self, sql::DataFormat, xml::XmlConfig},
+    graph::{self, PrerequisiteGraph},
+    lint::{LintError, Linter, Severity},
+    parsing::drift::DriftReport,
+    references::CourseRefExtractor,
+};
+
+/// Parse, validate and export catalog JSON
+#[derive(Debug, Parser)]
+#[command(name = "vislog", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Parse a program, course or catalog dump and print the result as JSON
+    Parse {
+        path: PathBuf,
+
+        /// Print the JSON on a single line
+        #[arg(long)]
+        compact: bool,
+
+        /// Print canonical JSON, which is byte-for-byte reproducible for committing snapshots
+        #[arg(long, conflicts_with = "compact")]
+        canonical: bool,
+    },
+
+    /// Parse every JSON file in a directory, or a single file, and report every entry that fails
+    /// to parse. Contradictory course requisites and references to unknown courses are reported as
+    /// warnings.
+    Validate {
+        path: PathBuf,
+
+        /// Snapshot of the parsed files of a directory, so that only the files that changed since
+        /// the last run are parsed again
+        #[arg(long)]
+        cache: Option<PathBuf>,
+    },
+
+    /// Export the programs in a file to another format
+    Export {
+        path: PathBuf,
+
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+
+        /// Course catalog dump used for the prerequisite edges of graph formats and the courses of
+        /// SQL exports
+        #[arg(long)]
+        courses: Option<PathBuf>,
+
+        /// Attach the course codes mentioned in the prose of label requirements, so that graph
+        /// formats include them. Codes are resolved to the courses of `--courses`.
+        #[arg(long)]
+        course_refs: bool,
+    },
+
+    /// Download the program and course JSON of a catalog from the catalog API
+    Fetch(FetchArgs),
+
+    /// Tally the fields of the raw JSON in a directory and report the ones that the parser doesn't
+    /// know about or that stopped being sent
+    InspectSchema {
+        dir: PathBuf,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check the programs of a directory or file against the lint rules, failing when a rule at
+    /// the `error` severity finds anything
+    Lint {
+        path: PathBuf,
+
+        /// Change the severity of a rule to off, info, warning or error. Can be repeated.
+        #[arg(long = "rule", value_name = "RULE=SEVERITY", value_parser = parse_rule_severity)]
+        rules: Vec<(String, Severity)>,
+
+        /// Print the findings as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Json,
+    /// Graphviz prerequisite graph of every program
+    Dot,
+    /// One row for every course listed by every program
+    Csv,
+    /// PostgreSQL schema and INSERT statements for the programs and courses
+    Sql,
+    /// Same as `sql` but with COPY blocks, which load faster through psql
+    SqlCopy,
+    /// XML document of the programs and courses
+    Xml,
+    /// RDF triples of the programs, their requirements and courses as Turtle
+    Turtle,
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error(transparent)]
+    Catalog(#[from] CatalogError),
+    #[error(transparent)]
+    Fetch(#[from] fetch::Error),
+    #[error("failed to write output: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to serialize output: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("{count} entries failed to parse")]
+    Invalid { count: usize },
+    #[error("{path:?} does not contain any programs")]
+    NoPrograms { path: PathBuf },
+    #[error("{count} fields drifted from what the parser reads")]
+    Drift { count: usize },
+    #[error(transparent)]
+    Lint(#[from] LintError),
+    #[error("lint rules found {count} errors")]
+    LintFailed { count: usize },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Parse {
+            path,
+            compact,
+            canonical,
+        } => parse(path, compact, canonical),
+        Command::Validate { path, cache } => validate(path, cache),
+        Command::Export {
+            path,
+            format,
+            courses,
+            course_refs,
+        } => export(path, format, courses, course_refs),
+        Command::Fetch(args) => fetch::run(args).map_err(Error::from),
+        Command::InspectSchema { dir, json } => inspect_schema(dir, json),
+        Command::Lint { path, rules, json } => lint(path, rules, json),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse(path: PathBuf, compact: bool, canonical: bool) -> Result<(), Error> {
+    let catalog = parse_file_strict(&path)?;
+
+    // A single program is printed as is rather than wrapped in a catalog
+    let output = match (catalog.programs.as_slice(), catalog.courses.is_empty()) {
+        ([program], true) => serde_json::to_value(program)?,
+        _ => json!({
+            "programs": catalog.programs,
+            "courses": catalog.courses,
+        }),
+    };
+
+    let output = match (compact, canonical) {
+        (_, true) => canonical::to_canonical_json(&output)?,
+        (true, false) => serde_json::to_string(&output)?,
+        (false, false) => serde_json::to_string_pretty(&output)?,
+    };
+    println!("{output}");
+
+    Ok(())
+}
+
+fn validate(path: PathBuf, cache: Option<PathBuf>) -> Result<(), Error> {
+    let (catalog, errors) = match (path.is_dir(), cache) {
+        (true, Some(cache)) => Catalog::load_cached(&path, cache)?,
+        (true, None) => Catalog::parse_dir(&path)?,
+        (false, _) => Catalog::parse_file(&path),
+    };
+
+    report(&errors);
+    for issue in catalog.check_requisites() {
+        eprintln!("warning: {issue}");
+    }
+    // Without courses, every course listed by a program would be reported
+    if !catalog.courses.is_empty() {
+        for line in catalog.check_references().to_string().lines() {
+            eprintln!("warning: {line}");
+        }
+    }
+    eprintln!(
+        "parsed {} programs and {} courses with {} errors",
+        catalog.programs.len(),
+        catalog.courses.len(),
+        errors.len()
+    );
+
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(Error::Invalid {
+            count: errors.len(),
+        }),
+    }
+}
+
+fn export(
+    path: PathBuf,
+    format: ExportFormat,
+    courses: Option<PathBuf>,
+    course_refs: bool,
+) -> Result<(), Error> {
+    let mut catalog = parse_file_strict(&path)?;
+    if catalog.programs.is_empty() {
+        return Err(Error::NoPrograms { path });
+    }
+
+    if let Some(courses) = courses {
+        catalog.courses.extend(parse_file_strict(&courses)?.courses);
+    }
+
+    if course_refs {
+        let extractor = CourseRefExtractor::from_catalog(&catalog);
+        for program in &mut catalog.programs {
+            extractor.attach(program);
+        }
+    }
+
+    match format {
+        ExportFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&catalog.programs)?);
+        }
+        ExportFormat::Dot => {
+            let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+            for program in &catalog.programs {
+                print!("{}", graph::to_dot(program, &graph));
+            }
+        }
+        ExportFormat::Csv => {
+            export::csv::write_course_rows(io::stdout().lock(), &catalog.programs)?;
+        }
+        ExportFormat::Sql => {
+            export::sql::write_sql(io::stdout().lock(), &catalog, DataFormat::Insert)?;
+        }
+        ExportFormat::SqlCopy => {
+            export::sql::write_sql(io::stdout().lock(), &catalog, DataFormat::Copy)?;
+        }
+        ExportFormat::Xml => {
+            export::xml::write_xml(io::stdout().lock(), &catalog, &XmlConfig::default())?;
+        }
+        ExportFormat::Turtle => {
+            export::rdf::write_turtle(io::stdout().lock(), &catalog)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn inspect_schema(dir: PathBuf, json: bool) -> Result<(), Error> {
+    let report = DriftReport::from_dir(&dir)?;
+
+    match json {
+        true => println!("{}", serde_json::to_string_pretty(&report)?),
+        false => print!("{report}"),
+    }
+
+    match report.drift().count() {
+        0 => Ok(()),
+        count => Err(Error::Drift { count }),
+    }
+}
+
+fn lint(path: PathBuf, rules: Vec<(String, Severity)>, json: bool) -> Result<(), Error> {
+    let mut linter = Linter::new();
+    for (name, severity) in rules {
+        linter.set_severity(&name, severity)?;
+    }
+
+    let (catalog, errors) = match path.is_dir() {
+        true => Catalog::parse_dir(&path)?,
+        false => Catalog::parse_file(&path),
+    };
+    report(&errors);
+
+    let report = linter.lint(&catalog);
+    match json {
+        true => println!("{}", serde_json::to_string_pretty(&report)?),
+        false => {
+            for finding in &report.findings {
+                println!("{finding}");
+            }
+        }
+    }
+
+    match report.count(Severity::Error) {
+        0 => Ok(()),
+        count => Err(Error::LintFailed { count }),
+    }
+}
+
+fn parse_rule_severity(arg: &str) -> Result<(String, Severity), String> {
+    let (rule, severity) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected RULE=SEVERITY, got {arg:?}"))?;
+    let severity = severity.parse().map_err(|err: LintError| err.to_string())?;
+
+    Ok((rule.to_owned(), severity))
+}
+
+/// Parses the file at `path`, failing after reporting every error if anything fails to parse
+fn parse_file_strict(path: &PathBuf) -> Result<Catalog, Error> {
+    let (catalog, errors) = Catalog::parse_file(path);
+
+    match errors.as_slice() {
+        [] => Ok(catalog),
+        // Keep the original error when it is the only one, such as the file not existing
+        [_] => Err(errors.into_iter().next().unwrap().into()),
+        _ => {
+            report(&errors);
+            Err(Error::Invalid {
+                count: errors.len(),
+            })
+        }
+    }
+}
+
+fn report(errors: &[CatalogError]) {
+    for err in errors {
+        eprintln!("error: {err}");
+    }
+}
@@ -0,0 +1,84 @@
+//! Benchmarks of the parsing pipeline.
+//!
+//! ```text
+//! cargo bench -p vislog-core                              # run every benchmark
+//! cargo bench -p vislog-core -- program/                  # only run benchmarks starting with a prefix
+//! cargo bench -p vislog-core -- --save-baseline before    # keep the timings to compare with later
+//! cargo bench -p vislog-core -- --baseline before         # report changes since `before`
+//! ```
+//!
+//! There are no fixed performance budgets. Save a baseline with `--save-baseline` before a change
+//! and compare with `--baseline` afterwards, Criterion keeps the timings in `target/criterion`.
+//! `program/value` parses the same JSON into a [serde_json::Value] to compare the custom visitors
+//! with a parse that does no work of its own.
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use vislog_core::{
+    catalog::Catalog,
+    graph::{self, PrerequisiteGraph, ProgramGraph},
+    Program,
+};
+
+const DATA: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../data");
+
+fn program(c: &mut Criterion) {
+    let program_json = std::fs::read_to_string(format!("{DATA}/cs_major.json")).unwrap();
+
+    let mut group = c.benchmark_group("program");
+    group.bench_function("parse", |b| {
+        b.iter(|| serde_json::from_str::<Program>(&program_json).unwrap())
+    });
+    group.bench_function("value", |b| {
+        b.iter(|| serde_json::from_str::<serde_json::Value>(&program_json).unwrap())
+    });
+    group.finish();
+}
+
+fn catalog(c: &mut Criterion) {
+    let mut group = c.benchmark_group("catalog");
+    // Parsing the whole directory takes long enough that the default 100 samples would take minutes
+    group
+        .sample_size(10)
+        .measurement_time(Duration::from_secs(10));
+    group.bench_function("parse", |b| b.iter(|| Catalog::parse_dir(DATA).unwrap()));
+    group.finish();
+}
+
+fn graphs(c: &mut Criterion) {
+    let (catalog, _errors) = Catalog::parse_dir(DATA).unwrap();
+    let program = cs_major(&catalog);
+    let prerequisites = PrerequisiteGraph::from_course_details(&catalog.courses);
+
+    let mut group = c.benchmark_group("graph");
+    group.bench_function("prerequisites", |b| {
+        b.iter(|| PrerequisiteGraph::from_course_details(&catalog.courses))
+    });
+    group.bench_function("program", |b| {
+        b.iter(|| ProgramGraph::new(program, &prerequisites))
+    });
+    group.finish();
+}
+
+fn export(c: &mut Criterion) {
+    let (catalog, _errors) = Catalog::parse_dir(DATA).unwrap();
+    let program = cs_major(&catalog);
+    let prerequisites = PrerequisiteGraph::from_course_details(&catalog.courses);
+
+    let mut group = c.benchmark_group("export");
+    group.bench_function("dot", |b| b.iter(|| graph::to_dot(program, &prerequisites)));
+    group.finish();
+}
+
+fn cs_major(catalog: &Catalog) -> &Program {
+    catalog
+        .programs
+        .iter()
+        .find(|program| program.title.starts_with("Major in Computer Science"))
+        .unwrap()
+}
+
+criterion_group!(parsing, program, catalog);
+criterion_group!(building, graphs, export);
+criterion_main!(parsing, building);
@@ -0,0 +1,14 @@
+fn main() {
+    // Only the `proto` feature needs the generated types
+    #[cfg(feature = "proto")]
+    {
+        println!("cargo:rerun-if-changed=proto/vislog.proto");
+
+        // Use a bundled `protoc` so that building doesn't depend on one being installed
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("protoc should be bundled");
+        std::env::set_var("PROTOC", protoc);
+
+        prost_build::compile_protos(&["proto/vislog.proto"], &["proto"])
+            .expect("Failed to compile proto/vislog.proto");
+    }
+}
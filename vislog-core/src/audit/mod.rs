@@ -0,0 +1,1426 @@
+//! Degree audits of a [Program] against the courses a student has completed.
+//!
+//! Every requirement of the program is checked on its own and reported as [Status::Satisfied],
+//! [Status::Partial] or [Status::Outstanding], along with the courses of the requirement that
+//! were completed and the ones that could still count towards it. A completed course counts
+//! towards every requirement listing it, and courses no requirement lists count towards pools of
+//! electives.
+//!
+//! - `Courses` requirements need every one of their entries
+//! - `And` groups need every one of their entries, `Or` groups need one of them
+//! - `SelectFromCourses` requirements need the number of entries or credit hours named in their
+//!   title, such as "Select two courses" or "Select 6 hours". Titles without a number need one
+//!   entry.
+//! - `ElectivePool` requirements take the completed courses that no other requirement lists, until
+//!   they have the hours they ask for. Pools of electives "to total" a number of hours count the
+//!   credits of every completed course. On their own, [Requirement::evaluate] can't tell which
+//!   courses are left over and reports them as [Status::Unknown].
+//! - Label requirements and label entries that can't be matched to a course need someone to check
+//!   them by hand and are [Status::Unknown]. They don't count for or against a program.
+//!
+//! The [Constraints] of a requirement are checked against the grades of the completed courses.
+//! Courses completed below the `min_grade` of a requirement don't count towards it, and a
+//! requirement that would be satisfied is only [Status::Partial] while the GPA over its completed
+//! courses is below its `min_gpa`. Courses completed without a grade, or passed without a letter
+//! grade, count towards every requirement and are left out of GPAs.
+//!
+//! A [GradePolicy] decides the rest: the lowest letter grade that passes a course, which attempt
+//! of a course taken several times counts, and whether the grades of the other attempts are
+//! forgiven or still weigh on GPAs. Courses that were failed or withdrawn from never count.
+//! [audit] follows the [default](GradePolicy::default) policy, and [audit_with] any other.
+//!
+//! Courses of the transcript can also be [in progress](Progress::InProgress) or
+//! [planned](Progress::Planned). They don't count towards requirements, but [audit] checks what
+//! the program would look like once they are completed too: every requirement gets an
+//! [AuditState], `Complete` when the completed courses satisfy it, `InProgress` when it is only
+//! satisfied with the courses in progress or planned, and `Remaining` otherwise.
+//!
+//! [audit] checks a whole program at once. To build other audit logic, check single requirements
+//! or entries against a [CourseSet] with [Requirement::evaluate] and [CourseEntry::evaluate].
+//!
+//! Degree rules that aren't about the courses of a requirement, such as a number of hours taken in
+//! residence or of upper-division hours, are [PolicyRule](rules::PolicyRule)s checked over the
+//! whole transcript by [audit_with_rules]. See [rules] for the built-in ones.
+//!
+//! Reports render as HTML, Markdown and versioned JSON with [render::AuditDocument].
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{audit::{self, CompletedCourse, Status}, Program};
+//! let program_json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+//! let program: Program = serde_json::from_str(&program_json).unwrap();
+//!
+//! let completed = [
+//!     CompletedCourse::code("CSC", "115", 3),
+//!     CompletedCourse::code("CSC", "215", 3),
+//! ];
+//! let report = audit::audit(&program, &completed);
+//!
+//! assert_eq!(report.status, Status::Partial);
+//! println!("{}", serde_json::to_string_pretty(&report).unwrap());
+//! ```
+
+pub mod render;
+pub mod rules;
+
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
+
+use serde::{Deserialize, Serialize};
+
+use self::rules::{PolicyRule, RuleAudit};
+use crate::{
+    constraints::{Constraints, CourseGrade, Gpa, Grade},
+    electives::ElectiveHours,
+    parsing::guid::Guid,
+    Course, CourseEntries, CourseEntry, Label, Program, Requirement, RequirementModule,
+};
+
+/// How a completed course is identified, either by its GUID or by its subject code and number
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum CourseId {
+    Guid {
+        guid: Guid,
+    },
+    Code {
+        subject_code: String,
+        number: String,
+    },
+}
+
+/// A course on the transcript of a student
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CompletedCourse {
+    #[serde(flatten)]
+    pub id: CourseId,
+    /// Credits earned by completing the course
+    pub credits: u8,
+    #[serde(default)]
+    pub grade: Option<CourseGrade>,
+    #[serde(default)]
+    pub progress: Progress,
+    /// Whether the course was transferred from another institution rather than taken in
+    /// residence
+    #[serde(default)]
+    pub transfer: bool,
+}
+
+/// Whether a course of the transcript is done
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Progress {
+    #[default]
+    Completed,
+    /// Taken in the current term
+    InProgress,
+    /// Planned in a later term
+    Planned,
+}
+
+impl CompletedCourse {
+    pub fn guid(guid: Guid, credits: u8) -> Self {
+        Self {
+            id: CourseId::Guid { guid },
+            credits,
+            grade: None,
+            progress: Progress::Completed,
+            transfer: false,
+        }
+    }
+
+    pub fn code(subject_code: impl Into<String>, number: impl Into<String>, credits: u8) -> Self {
+        Self {
+            id: CourseId::Code {
+                subject_code: subject_code.into(),
+                number: number.into(),
+            },
+            credits,
+            grade: None,
+            progress: Progress::Completed,
+            transfer: false,
+        }
+    }
+
+    pub fn with_grade(self, grade: impl Into<CourseGrade>) -> Self {
+        Self {
+            grade: Some(grade.into()),
+            ..self
+        }
+    }
+
+    pub fn with_progress(self, progress: Progress) -> Self {
+        Self { progress, ..self }
+    }
+
+    /// The course, transferred from another institution
+    pub fn transferred(self) -> Self {
+        Self {
+            transfer: true,
+            ..self
+        }
+    }
+}
+
+/// How the grades of completed courses decide whether they count
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GradePolicy {
+    /// Lowest letter grade that passes a course. Courses completed below it don't count towards
+    /// any requirement, but their grade still weighs on GPAs.
+    pub passing_grade: Grade,
+    pub repeats: RepeatPolicy,
+    pub forgiveness: Forgiveness,
+}
+
+impl Default for GradePolicy {
+    fn default() -> Self {
+        Self {
+            passing_grade: Grade::DMinus,
+            repeats: RepeatPolicy::default(),
+            forgiveness: Forgiveness::default(),
+        }
+    }
+}
+
+/// Which attempt of a course taken several times counts. Attempts that were withdrawn from are
+/// never picked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum RepeatPolicy {
+    /// The last attempt replaces the earlier ones, even when its grade is lower
+    #[default]
+    Latest,
+    /// The attempt with the highest grade counts, the latest one among equal grades. Passing
+    /// without a letter grade ranks like a C.
+    Highest,
+}
+
+/// Whether the letter grades of the attempts of a repeated course that don't count are left out
+/// of GPAs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Forgiveness {
+    /// Only the attempt that counts is in GPAs
+    #[default]
+    All,
+    /// Grades are forgiven for the given number of repeated courses, in the order of the
+    /// transcript, and every attempt of the courses repeated after them is in GPAs
+    FirstRepeats(u32),
+    /// Every attempt is in GPAs
+    Never,
+}
+
+impl GradePolicy {
+    /// The attempt of every course that counts, in the order of the first attempt of the course.
+    /// Courses that were only withdrawn from are left out.
+    pub fn counted(&self, completed: &[CompletedCourse]) -> Vec<CompletedCourse> {
+        self.resolve(completed)
+            .into_iter()
+            .map(|(course, _)| course.clone())
+            .collect()
+    }
+
+    /// Attempt of every course that counts, along with the grades and credits of its other
+    /// attempts that aren't forgiven
+    fn resolve<'a>(
+        &self,
+        completed: &'a [CompletedCourse],
+    ) -> Vec<(&'a CompletedCourse, Vec<(Grade, u8)>)> {
+        let mut order: Vec<CourseId> = vec![];
+        let mut attempts: HashMap<CourseId, Vec<&CompletedCourse>> = HashMap::new();
+        for course in completed {
+            let key = course.id.normalized();
+            if !attempts.contains_key(&key) {
+                order.push(key.clone());
+            }
+            attempts.entry(key).or_default().push(course);
+        }
+
+        let mut repeats = 0;
+        order
+            .iter()
+            .filter_map(|key| {
+                let attempts: Vec<&CompletedCourse> = attempts[key]
+                    .iter()
+                    .filter(|course| course.grade != Some(CourseGrade::Withdrawn))
+                    .copied()
+                    .collect();
+                let counted = match self.repeats {
+                    RepeatPolicy::Latest => attempts.last(),
+                    RepeatPolicy::Highest => attempts.iter().max_by_key(|course| {
+                        let grade = course.grade.unwrap_or(CourseGrade::Pass);
+                        (
+                            grade.passes(self.passing_grade),
+                            grade.letter().unwrap_or(Grade::C),
+                        )
+                    }),
+                }?;
+
+                let forgiven = match self.forgiveness {
+                    Forgiveness::All => true,
+                    Forgiveness::FirstRepeats(forgiven) => attempts.len() < 2 || repeats < forgiven,
+                    Forgiveness::Never => false,
+                };
+                if attempts.len() > 1 {
+                    repeats += 1;
+                }
+                let unforgiven = match forgiven {
+                    true => vec![],
+                    false => attempts
+                        .iter()
+                        .filter(|course| !std::ptr::eq(**course, *counted))
+                        .filter_map(|course| Some((course.grade?.letter()?, course.credits)))
+                        .collect(),
+                };
+
+                Some((*counted, unforgiven))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Status {
+    Satisfied,
+    /// Some but not all of what is needed was completed
+    Partial,
+    /// Nothing that counts towards the requirement was completed
+    Outstanding,
+    /// The requirement can't be checked against courses
+    Unknown,
+}
+
+/// How far along a requirement is, counting the courses in progress or planned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum AuditState {
+    /// Satisfied by the completed courses
+    Complete,
+    /// Satisfied once the courses in progress or planned are completed
+    InProgress,
+    /// Not satisfied even with the courses in progress or planned, or can't be checked
+    Remaining,
+}
+
+impl AuditState {
+    /// State of something with the `status` over the completed courses, and the `expected`
+    /// status once the courses in progress or planned are completed
+    fn new(status: Status, expected: Status) -> Self {
+        match (status, expected) {
+            (Status::Satisfied, _) => AuditState::Complete,
+            (_, Status::Satisfied) => AuditState::InProgress,
+            _ => AuditState::Remaining,
+        }
+    }
+}
+
+/// Result of auditing a whole program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuditReport {
+    pub guid: Guid,
+    pub title: String,
+    /// `Satisfied` when every requirement that can be checked and every rule is satisfied. Only
+    /// one emphasis of a `SelectOneEmphasis` module has to be satisfied.
+    pub status: Status,
+    pub state: AuditState,
+    pub requirements: Vec<RequirementAudit>,
+    /// Results of the [rules](rules::PolicyRule) the program was audited with
+    pub rules: Vec<RuleAudit>,
+    /// Completed courses that don't count towards any requirement of the program
+    pub unused: Vec<CompletedCourse>,
+}
+
+/// Result of auditing a single [Requirement]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RequirementAudit {
+    /// Title of the module containing the requirement
+    pub module: Option<String>,
+    pub title: Option<String>,
+    pub status: Status,
+    pub state: AuditState,
+    /// Courses of the requirement that were completed
+    pub completed: Vec<Guid>,
+    /// Courses of the requirement in progress or planned, which would count towards it once
+    /// completed
+    pub in_progress: Vec<Guid>,
+    /// Courses of the requirement that weren't completed and could still count towards it.
+    /// Empty once the requirement is satisfied.
+    pub remaining: Vec<Guid>,
+    /// Credits earned by the completed courses of the requirement
+    pub credits: u32,
+    /// Credits needed by requirements that ask for a number of hours
+    pub credits_required: Option<u32>,
+    /// GPA over the completed courses of the requirement that have a grade
+    pub gpa: Option<Gpa>,
+    pub constraints: Constraints,
+}
+
+/// Audits every requirement of the `program` against the `completed` courses. Courses in
+/// progress or planned only count towards the [AuditState] of the requirements.
+pub fn audit(program: &Program, completed: &[CompletedCourse]) -> AuditReport {
+    audit_with(program, completed, &GradePolicy::default())
+}
+
+/// Audits the `program` like [audit], deciding which completed courses count by the `policy`
+pub fn audit_with(
+    program: &Program,
+    completed: &[CompletedCourse],
+    policy: &GradePolicy,
+) -> AuditReport {
+    audit_with_rules(program, completed, policy, &[])
+}
+
+/// Audits the `program` like [audit_with], and checks the `rules` over every completed course
+/// that counts. The report is only satisfied once the rules are.
+pub fn audit_with_rules(
+    program: &Program,
+    completed: &[CompletedCourse],
+    policy: &GradePolicy,
+    rules: &[&dyn PolicyRule],
+) -> AuditReport {
+    let done: Vec<CompletedCourse> = completed
+        .iter()
+        .filter(|course| course.progress == Progress::Completed)
+        .cloned()
+        .collect();
+    let mut report = audit_courses(program, &done, policy, rules);
+    if done.len() == completed.len() {
+        return report;
+    }
+
+    let expected = audit_courses(program, completed, policy, rules);
+    for (audit, expected) in report.requirements.iter_mut().zip(expected.requirements) {
+        audit.in_progress = expected
+            .completed
+            .into_iter()
+            .filter(|guid| !audit.completed.contains(guid))
+            .collect();
+        audit.state = AuditState::new(audit.status, expected.status);
+    }
+    for (audit, expected) in report.rules.iter_mut().zip(expected.rules) {
+        audit.state = AuditState::new(audit.status, expected.status);
+    }
+    report.state = AuditState::new(report.status, expected.status);
+
+    report
+}
+
+/// Audits the `program` counting every one of the `courses` as completed
+fn audit_courses(
+    program: &Program,
+    completed: &[CompletedCourse],
+    policy: &GradePolicy,
+    rules: &[&dyn PolicyRule],
+) -> AuditReport {
+    let courses = CourseSet::with_policy(completed, policy);
+    let completed = &policy.counted(completed);
+
+    let mut requirements = vec![];
+    // Requirements of `SelectOneEmphasis` modules, only one of which has to be satisfied
+    let mut emphases: Vec<Range<usize>> = vec![];
+
+    let modules = program
+        .requirements
+        .iter()
+        .flat_map(|requirements| requirements.modules());
+    for module in modules {
+        let start = requirements.len();
+        requirements.extend(module.requirements().iter().map(|requirement| {
+            audit_requirement(module.title(), requirement, requirement.evaluate(&courses))
+        }));
+
+        if let RequirementModule::SelectOneEmphasis { .. } = module {
+            emphases.push(start..requirements.len());
+        }
+    }
+
+    let used: HashSet<Guid> = requirements
+        .iter()
+        .flat_map(|audit| audit.completed.iter().copied())
+        .collect();
+    let mut unused: Vec<CompletedCourse> = completed
+        .iter()
+        .filter(|course| {
+            !program
+                .iter_courses()
+                .any(|listed| used.contains(&listed.guid) && course.id.matches(listed))
+        })
+        .cloned()
+        .collect();
+
+    // Pools asking for a number of hours take their courses before the ones making up a total
+    let (totals, pools): (Vec<_>, Vec<_>) = requirements
+        .iter_mut()
+        .zip(program.iter_requirements())
+        .filter_map(|(audit, requirement)| match requirement {
+            Requirement::ElectivePool { hours, .. } => Some((audit, requirement, *hours)),
+            _ => None,
+        })
+        .partition(|(_, _, hours)| matches!(hours, ElectiveHours::ToTotal(_)));
+    for (audit, requirement, hours) in pools.into_iter().chain(totals) {
+        let satisfaction = fill_pool(
+            hours,
+            requirement.constraints(),
+            policy.passing_grade,
+            completed,
+            &mut unused,
+        );
+        *audit = audit_requirement(audit.module.take().as_deref(), requirement, satisfaction);
+    }
+
+    let in_emphasis = |i: usize| emphases.iter().any(|emphasis| emphasis.contains(&i));
+    let statuses = requirements
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !in_emphasis(*i))
+        .map(|(_, audit)| audit.status)
+        .chain(emphases.iter().map(|emphasis| {
+            any(requirements[emphasis.clone()]
+                .iter()
+                .map(|audit| audit.status))
+        }))
+        .collect::<Vec<_>>();
+
+    let rules = rules::check(rules, program, completed, &unused, policy.passing_grade);
+
+    let status = all(statuses
+        .into_iter()
+        .chain(rules.iter().map(|audit| audit.status)));
+    AuditReport {
+        guid: program.guid,
+        title: program.title.clone(),
+        status,
+        state: AuditState::new(status, status),
+        requirements,
+        rules,
+        unused,
+    }
+}
+
+fn audit_requirement(
+    module: Option<&str>,
+    requirement: &Requirement,
+    satisfaction: Satisfaction,
+) -> RequirementAudit {
+    let gpa = satisfaction.gpa();
+
+    RequirementAudit {
+        module: module.map(str::to_owned),
+        title: requirement.title().map(str::to_owned),
+        status: satisfaction.status,
+        state: AuditState::new(satisfaction.status, satisfaction.status),
+        completed: satisfaction.completed,
+        in_progress: vec![],
+        remaining: satisfaction.remaining,
+        credits: satisfaction.credits,
+        credits_required: satisfaction.credits_required,
+        gpa,
+        constraints: requirement.constraints(),
+    }
+}
+
+/// Counts completed courses towards a pool of electives needing `hours`, taking the ones it needs out
+/// of the `unused` courses. Pools asking for a number of hours take unused courses until they
+/// have enough, while pools making up a total take every unused course and count the credits of
+/// every `completed` course.
+fn fill_pool(
+    hours: ElectiveHours,
+    Constraints { min_grade, min_gpa }: Constraints,
+    passing_grade: Grade,
+    completed: &[CompletedCourse],
+    unused: &mut Vec<CompletedCourse>,
+) -> Satisfaction {
+    let counts =
+        |course: &CompletedCourse| Completion::new(course, passing_grade, vec![]).meets(min_grade);
+
+    let mut taken = vec![];
+    let (credits, required) = match hours {
+        ElectiveHours::Hours(hours) => {
+            let mut credits = 0;
+            unused.retain(|course| {
+                if credits >= hours || !counts(course) {
+                    return true;
+                }
+                credits += course.credits as u32;
+                taken.push(course.clone());
+                false
+            });
+            (credits, hours)
+        }
+        ElectiveHours::ToTotal(total) => {
+            unused.retain(|course| {
+                if counts(course) {
+                    taken.push(course.clone());
+                }
+                !counts(course)
+            });
+            let credits = completed
+                .iter()
+                .filter(|course| counts(course))
+                .map(|course| course.credits as u32)
+                .sum();
+            (credits, total)
+        }
+    };
+
+    let status = if credits >= required {
+        Status::Satisfied
+    } else if credits > 0 {
+        Status::Partial
+    } else {
+        Status::Outstanding
+    };
+
+    Satisfaction {
+        // Courses identified by subject code and number count without being listed
+        completed: taken
+            .iter()
+            .filter_map(|course| match course.id {
+                CourseId::Guid { guid } => Some(guid),
+                CourseId::Code { .. } => None,
+            })
+            .collect(),
+        credits,
+        credits_required: Some(required),
+        graded: taken
+            .iter()
+            .filter_map(|course| Some((course.grade?.letter()?, course.credits)))
+            .collect(),
+        ..Satisfaction::new(status)
+    }
+    .check_gpa(min_gpa)
+}
+
+/// Completed courses that requirements are evaluated against, indexed by GUID and by subject
+/// code and number
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CourseSet {
+    by_guid: HashMap<Guid, Completion>,
+    by_code: HashMap<(String, String), Completion>,
+    policy: GradePolicy,
+}
+
+/// Credits and grade earned for a completed course
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Completion {
+    credits: u8,
+    grade: Option<CourseGrade>,
+    /// Whether the grade passes the course
+    passed: bool,
+    /// Grades and credits of other attempts of the course that weigh on GPAs
+    unforgiven: Vec<(Grade, u8)>,
+}
+
+impl Completion {
+    fn new(course: &CompletedCourse, passing_grade: Grade, unforgiven: Vec<(Grade, u8)>) -> Self {
+        Self {
+            credits: course.credits,
+            grade: course.grade,
+            passed: course.grade.is_none_or(|grade| grade.passes(passing_grade)),
+            unforgiven,
+        }
+    }
+
+    /// Whether the course counts towards a requirement needing the `min_grade`
+    fn meets(&self, min_grade: Option<Grade>) -> bool {
+        match (self.grade.and_then(CourseGrade::letter), min_grade) {
+            (Some(grade), Some(min_grade)) => self.passed && grade >= min_grade,
+            _ => self.passed,
+        }
+    }
+
+    /// Grades and credits of every attempt that weighs on GPAs
+    fn graded(&self) -> Vec<(Grade, u8)> {
+        let grade = self.grade.and_then(CourseGrade::letter);
+        grade
+            .map(|grade| (grade, self.credits))
+            .into_iter()
+            .chain(self.unforgiven.iter().copied())
+            .collect()
+    }
+}
+
+impl CourseSet {
+    /// Courses that count out of the `completed` ones, by the [default](GradePolicy::default)
+    /// policy
+    pub fn new(completed: &[CompletedCourse]) -> Self {
+        Self::with_policy(completed, &GradePolicy::default())
+    }
+
+    /// Courses that count out of the `completed` ones, by the `policy`. Courses taken several
+    /// times keep the attempt the policy picks.
+    pub fn with_policy(completed: &[CompletedCourse], policy: &GradePolicy) -> Self {
+        let mut courses = Self {
+            policy: policy.clone(),
+            ..Self::default()
+        };
+        for (course, unforgiven) in policy.resolve(completed) {
+            let completion = Completion::new(course, policy.passing_grade, unforgiven);
+            courses.insert_completion(&course.id, completion);
+        }
+
+        courses
+    }
+
+    /// Adds the `course`, replacing the credits and grade earned for it if it is already in the set
+    pub fn insert(&mut self, course: &CompletedCourse) {
+        let completion = Completion::new(course, self.policy.passing_grade, vec![]);
+        self.insert_completion(&course.id, completion);
+    }
+
+    fn insert_completion(&mut self, id: &CourseId, completion: Completion) {
+        match id {
+            CourseId::Guid { guid } => {
+                self.by_guid.insert(*guid, completion);
+            }
+            CourseId::Code {
+                subject_code,
+                number,
+            } => {
+                self.by_code
+                    .insert(code_key(subject_code, number), completion);
+            }
+        }
+    }
+
+    /// Credits earned for the `course`, if it was completed
+    pub fn credits_for(&self, course: &Course) -> Option<u8> {
+        self.completion(course).map(|completion| completion.credits)
+    }
+
+    /// Grade earned for the `course`, if it was completed with one
+    pub fn grade_for(&self, course: &Course) -> Option<CourseGrade> {
+        self.completion(course)
+            .and_then(|completion| completion.grade)
+    }
+
+    fn completion(&self, course: &Course) -> Option<&Completion> {
+        self.by_guid.get(&course.guid).or_else(|| {
+            self.by_code
+                .get(&code_key(&course.subject_code, &course.number))
+        })
+    }
+
+    /// `None` when the `label` doesn't stand for a single course
+    fn completion_of_label(&self, label: &Label) -> Option<Option<&Completion>> {
+        match (&label.subject_code, &label.number) {
+            (Some(subject_code), Some(number)) => Some(
+                self.by_guid
+                    .get(&label.guid)
+                    .or_else(|| self.by_code.get(&code_key(subject_code, number))),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl CourseId {
+    /// The id with its subject code and number compared the way [code_key] does
+    fn normalized(&self) -> Self {
+        match self {
+            CourseId::Guid { .. } => self.clone(),
+            CourseId::Code {
+                subject_code,
+                number,
+            } => {
+                let (subject_code, number) = code_key(subject_code, number);
+                CourseId::Code {
+                    subject_code,
+                    number,
+                }
+            }
+        }
+    }
+
+    fn matches(&self, course: &Course) -> bool {
+        match self {
+            CourseId::Guid { guid } => guid == &course.guid,
+            CourseId::Code {
+                subject_code,
+                number,
+            } => code_key(subject_code, number) == code_key(&course.subject_code, &course.number),
+        }
+    }
+}
+
+/// Subject codes and numbers are compared without case or surrounding whitespace
+pub(crate) fn code_key(subject_code: &str, number: &str) -> (String, String) {
+    (
+        subject_code.trim().to_uppercase(),
+        number.trim().to_uppercase(),
+    )
+}
+
+/// How much of a `SelectFromCourses` requirement must be completed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Selection {
+    Count(u32),
+    Credits(u32),
+}
+
+impl Selection {
+    /// Reads the selection from titles like "Select two of the following" or "Select CSC
+    /// Upper-level Elective: 3 hours"
+    fn from_title(title: &str) -> Self {
+        let words: Vec<String> = title
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+
+        let credits = words.windows(2).find_map(|pair| match pair[1].as_str() {
+            "hour" | "hours" | "credit" | "credits" => number(&pair[0]),
+            _ => None,
+        });
+        if let Some(credits) = credits {
+            return Selection::Credits(credits);
+        }
+
+        let count = words
+            .iter()
+            .position(|word| word == "select")
+            .and_then(|i| words.get(i + 1))
+            .and_then(|word| number(word));
+
+        Selection::Count(count.unwrap_or(1))
+    }
+}
+
+fn number(word: &str) -> Option<u32> {
+    const WORDS: [&str; 10] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    ];
+
+    word.parse().ok().or_else(|| {
+        WORDS
+            .iter()
+            .position(|number| *number == word)
+            .map(|i| i as u32 + 1)
+    })
+}
+
+/// What was completed out of a requirement or a group of course entries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Satisfaction {
+    pub status: Status,
+    /// Courses that were completed
+    pub completed: Vec<Guid>,
+    /// Courses that weren't completed and could still count. Empty once satisfied.
+    pub remaining: Vec<Guid>,
+    /// Credits earned by the completed courses
+    pub credits: u32,
+    /// Credits needed by requirements that ask for a number of hours
+    pub credits_required: Option<u32>,
+    /// Grades and credits of the completed courses that have a grade, including the ones below
+    /// the minimum grade
+    #[serde(skip)]
+    graded: Vec<(Grade, u8)>,
+}
+
+impl Satisfaction {
+    pub fn is_satisfied(&self) -> bool {
+        self.status == Status::Satisfied
+    }
+
+    /// Credits still missing to reach `credits_required`. Always 0 when no number of hours is
+    /// asked for.
+    pub fn credit_shortfall(&self) -> u32 {
+        self.credits_required
+            .map_or(0, |required| required.saturating_sub(self.credits))
+    }
+
+    /// GPA over the completed courses that have a grade
+    pub fn gpa(&self) -> Option<Gpa> {
+        Gpa::weighted(self.graded.iter().copied())
+    }
+
+    fn new(status: Status) -> Self {
+        Self {
+            status,
+            completed: vec![],
+            remaining: vec![],
+            credits: 0,
+            credits_required: None,
+            graded: vec![],
+        }
+    }
+
+    /// Every one of the `entries` is needed
+    fn all(entries: &CourseEntries, courses: &CourseSet, min_grade: Option<Grade>) -> Self {
+        let satisfactions: Vec<Satisfaction> = entries
+            .iter()
+            .map(|entry| entry.evaluate_with(courses, min_grade))
+            .collect();
+
+        let mut combined = Satisfaction::new(all(satisfactions.iter().map(|s| s.status)));
+        for satisfaction in satisfactions {
+            combined.absorb(satisfaction);
+        }
+
+        combined
+    }
+
+    /// Enough of the `entries` to make up the `selection` are needed
+    fn select(
+        entries: &CourseEntries,
+        selection: Selection,
+        courses: &CourseSet,
+        min_grade: Option<Grade>,
+    ) -> Self {
+        let satisfactions: Vec<Satisfaction> = entries
+            .iter()
+            .map(|entry| entry.evaluate_with(courses, min_grade))
+            .collect();
+
+        let satisfied = satisfactions.iter().filter(|s| s.is_satisfied()).count() as u32;
+        let credits: u32 = satisfactions.iter().map(|s| s.credits).sum();
+        let is_satisfied = match selection {
+            Selection::Count(count) => satisfied >= count,
+            Selection::Credits(needed) => credits >= needed,
+        };
+
+        let status = if is_satisfied {
+            Status::Satisfied
+        } else if credits > 0
+            || satisfactions
+                .iter()
+                .any(|s| matches!(s.status, Status::Satisfied | Status::Partial))
+        {
+            Status::Partial
+        } else if satisfactions.iter().all(|s| s.status == Status::Unknown) {
+            Status::Unknown
+        } else {
+            Status::Outstanding
+        };
+
+        let mut combined = Satisfaction::new(status);
+        for satisfaction in satisfactions {
+            combined.absorb(satisfaction);
+        }
+        if is_satisfied {
+            combined.remaining.clear();
+        }
+        if let Selection::Credits(credits) = selection {
+            combined.credits_required = Some(credits);
+        }
+
+        combined
+    }
+
+    fn absorb(&mut self, other: Satisfaction) {
+        self.completed.extend(other.completed);
+        self.remaining.extend(other.remaining);
+        self.credits += other.credits;
+        self.graded.extend(other.graded);
+    }
+
+    /// A satisfied requirement is only partially satisfied while its GPA is below the `min_gpa`
+    fn check_gpa(mut self, min_gpa: Option<Gpa>) -> Self {
+        let below = min_gpa.is_some_and(|min_gpa| self.gpa().is_some_and(|gpa| gpa < min_gpa));
+        if self.status == Status::Satisfied && below {
+            self.status = Status::Partial;
+        }
+
+        self
+    }
+}
+
+impl Requirement {
+    /// Checks the requirement against the completed `courses`, the same way [audit] does
+    ///
+    /// # Example
+    /// ```
+    /// # use vislog_core::{audit::{CompletedCourse, CourseSet}, Program};
+    /// let program_json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+    /// let program: Program = serde_json::from_str(&program_json).unwrap();
+    ///
+    /// let courses = CourseSet::new(&[CompletedCourse::code("CSC", "115", 3)]);
+    /// let requirement = program.iter_requirements().next().unwrap();
+    ///
+    /// let satisfaction = requirement.evaluate(&courses);
+    /// assert!(!satisfaction.is_satisfied());
+    /// assert_eq!(satisfaction.credits, 3);
+    /// ```
+    pub fn evaluate(&self, courses: &CourseSet) -> Satisfaction {
+        let Constraints { min_grade, min_gpa } = self.constraints();
+
+        let satisfaction = match self {
+            Requirement::Courses {
+                courses: entries, ..
+            } => Satisfaction::all(entries, courses, min_grade),
+            Requirement::SelectFromCourses {
+                title,
+                courses: entries,
+                ..
+            } => {
+                let selection = Selection::from_title(title);
+                match entries {
+                    Some(entries) => Satisfaction::select(entries, selection, courses, min_grade),
+                    None => Satisfaction {
+                        credits_required: match selection {
+                            Selection::Credits(credits) => Some(credits),
+                            Selection::Count(_) => None,
+                        },
+                        ..Satisfaction::new(Status::Unknown)
+                    },
+                }
+            }
+            Requirement::ElectivePool { hours, .. } => Satisfaction {
+                credits_required: Some(match hours {
+                    ElectiveHours::Hours(hours) | ElectiveHours::ToTotal(hours) => *hours,
+                }),
+                ..Satisfaction::new(Status::Unknown)
+            },
+            Requirement::Label { .. } => Satisfaction::new(Status::Unknown),
+        };
+
+        satisfaction.check_gpa(min_gpa)
+    }
+}
+
+impl CourseEntry {
+    /// Checks the entry against the completed `courses`. `And` groups need every one of their
+    /// entries and `Or` groups need one of them. Grades aren't checked, since minimum grades
+    /// belong to requirements.
+    pub fn evaluate(&self, courses: &CourseSet) -> Satisfaction {
+        self.evaluate_with(courses, None)
+    }
+
+    fn evaluate_with(&self, courses: &CourseSet, min_grade: Option<Grade>) -> Satisfaction {
+        let single = |guid, completion: Option<&Completion>| {
+            let graded = completion.map(Completion::graded).unwrap_or_default();
+
+            match completion {
+                Some(completion) if completion.meets(min_grade) => Satisfaction {
+                    completed: vec![guid],
+                    credits: completion.credits as u32,
+                    graded,
+                    ..Satisfaction::new(Status::Satisfied)
+                },
+                _ => Satisfaction {
+                    remaining: vec![guid],
+                    graded,
+                    ..Satisfaction::new(Status::Outstanding)
+                },
+            }
+        };
+
+        match self {
+            CourseEntry::Course(course) => single(course.guid, courses.completion(course)),
+            CourseEntry::Label(label) => match courses.completion_of_label(label) {
+                Some(completion) => single(label.guid, completion),
+                None => Satisfaction::new(Status::Unknown),
+            },
+            CourseEntry::And(group) => Satisfaction::all(group, courses, min_grade),
+            CourseEntry::Or(group) => {
+                Satisfaction::select(group, Selection::Count(1), courses, min_grade)
+            }
+        }
+    }
+}
+
+/// Status of something needing every one of the `statuses`, ignoring the unknown ones
+fn all(statuses: impl IntoIterator<Item = Status>) -> Status {
+    let known: Vec<Status> = statuses
+        .into_iter()
+        .filter(|status| *status != Status::Unknown)
+        .collect();
+
+    if known.is_empty() {
+        Status::Unknown
+    } else if known.iter().all(|status| *status == Status::Satisfied) {
+        Status::Satisfied
+    } else if known.iter().all(|status| *status == Status::Outstanding) {
+        Status::Outstanding
+    } else {
+        Status::Partial
+    }
+}
+
+/// Status of something needing one of the `statuses`
+fn any(statuses: impl IntoIterator<Item = Status>) -> Status {
+    let statuses: Vec<Status> = statuses.into_iter().collect();
+
+    [Status::Satisfied, Status::Partial, Status::Outstanding]
+        .into_iter()
+        .find(|status| statuses.contains(status))
+        .unwrap_or(Status::Unknown)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::cs_minor;
+
+    fn requirement<'a>(report: &'a AuditReport, title: &str) -> &'a RequirementAudit {
+        report
+            .requirements
+            .iter()
+            .find(|audit| audit.title.as_deref() == Some(title))
+            .unwrap()
+    }
+
+    #[test]
+    fn selections_are_read_from_titles() {
+        assert_eq!(
+            Selection::from_title("Select CSC Upper-level Elective: 3 hours"),
+            Selection::Credits(3)
+        );
+        assert_eq!(
+            Selection::from_title("Select two of the following:"),
+            Selection::Count(2)
+        );
+        assert_eq!(
+            Selection::from_title("Select one track:"),
+            Selection::Count(1)
+        );
+        assert_eq!(
+            Selection::from_title("Electives (choose wisely)"),
+            Selection::Count(1)
+        );
+    }
+
+    #[test]
+    fn nothing_completed_is_outstanding() {
+        let report = audit(&cs_minor(), &[]);
+
+        assert_eq!(report.status, Status::Outstanding);
+        let minor = requirement(&report, "Minor Requirements:");
+        assert_eq!(minor.status, Status::Outstanding);
+        assert!(minor.completed.is_empty());
+        assert_eq!(minor.remaining.len(), 6);
+    }
+
+    #[test]
+    fn or_groups_need_a_single_option() {
+        let program = cs_minor();
+        let mut completed: Vec<CompletedCourse> = ["115", "235", "300"]
+            .into_iter()
+            .map(|number| CompletedCourse::code("csc", number, 3))
+            .collect();
+        let report = audit(&program, &completed);
+        assert_eq!(
+            requirement(&report, "Minor Requirements:").status,
+            Status::Partial
+        );
+
+        // One option of each of the two `Or` groups
+        let minor = program.iter_requirements().next().unwrap();
+        let Some(entries) = minor.course_entries() else {
+            panic!("the first requirement lists courses");
+        };
+        for entry in entries.iter() {
+            if let CourseEntry::Or(group) = entry {
+                let CourseEntry::Course(course) = &group[0] else {
+                    panic!("the groups list courses");
+                };
+                completed.push(CompletedCourse::guid(course.guid, 3));
+            }
+        }
+        let report = audit(&program, &completed);
+        let minor = requirement(&report, "Minor Requirements:");
+
+        assert_eq!(minor.status, Status::Satisfied);
+        assert!(minor.remaining.is_empty());
+        assert_eq!(minor.credits, 12);
+    }
+
+    #[test]
+    fn credit_shortfall_of_hour_selections() {
+        let program = cs_minor();
+        let track = program
+            .iter_requirements()
+            .find(|requirement| requirement.title() == Some("Select one track:"))
+            .unwrap();
+        let requirement = Requirement::SelectFromCourses {
+            title: "Select 9 hours".to_owned(),
+            courses: track.course_entries().cloned(),
+            constraints: Constraints::default(),
+            extra: Default::default(),
+        };
+
+        let option = track
+            .course_entries()
+            .and_then(|entries| entries.iter_courses().next())
+            .unwrap();
+        let mut courses = CourseSet::new(&[CompletedCourse::guid(option.guid, 3)]);
+
+        let satisfaction = requirement.evaluate(&courses);
+        assert_eq!(satisfaction.status, Status::Partial);
+        assert_eq!(satisfaction.credits_required, Some(9));
+        assert_eq!(satisfaction.credit_shortfall(), 6);
+        assert_eq!(satisfaction.completed, vec![option.guid]);
+
+        courses.insert(&CompletedCourse::guid(option.guid, 9));
+        let satisfaction = requirement.evaluate(&courses);
+        assert!(satisfaction.is_satisfied());
+        assert_eq!(satisfaction.credit_shortfall(), 0);
+        assert!(satisfaction.remaining.is_empty());
+    }
+
+    #[test]
+    fn courses_below_the_minimum_grade_dont_count() {
+        let program = cs_minor();
+        let minor = program.iter_requirements().next().unwrap();
+        let requirement = Requirement::Courses {
+            title: None,
+            courses: minor.course_entries().cloned().unwrap(),
+            constraints: Constraints::from_text("with a grade of C or better"),
+            extra: Default::default(),
+        };
+        let csc_115 = Guid::try_from("860AF9C9-EAD9-45AC-AA92-BAF352C5288C").unwrap();
+
+        let courses =
+            CourseSet::new(&[CompletedCourse::guid(csc_115, 3).with_grade(Grade::CMinus)]);
+        let satisfaction = requirement.evaluate(&courses);
+        assert_eq!(satisfaction.status, Status::Outstanding);
+        assert!(satisfaction.remaining.contains(&csc_115));
+        assert_eq!(satisfaction.gpa(), Some(Gpa::from_hundredths(170)));
+
+        let courses = CourseSet::new(&[CompletedCourse::guid(csc_115, 3).with_grade(Grade::C)]);
+        assert_eq!(requirement.evaluate(&courses).completed, vec![csc_115]);
+
+        // Without a grade the course is assumed to be good enough
+        let courses = CourseSet::new(&[CompletedCourse::guid(csc_115, 3)]);
+        assert_eq!(requirement.evaluate(&courses).completed, vec![csc_115]);
+    }
+
+    #[test]
+    fn repeated_courses_follow_the_grade_policy() {
+        let program = cs_minor();
+        let minor = program.iter_requirements().next().unwrap();
+        let requirement = Requirement::Courses {
+            title: None,
+            courses: minor.course_entries().cloned().unwrap(),
+            constraints: Constraints::default(),
+            extra: Default::default(),
+        };
+        let csc_115 = Guid::try_from("860AF9C9-EAD9-45AC-AA92-BAF352C5288C").unwrap();
+        let attempt = |grade: CourseGrade| CompletedCourse::guid(csc_115, 3).with_grade(grade);
+
+        // Failed and withdrawn courses never count
+        for grade in [Grade::F.into(), CourseGrade::Fail, CourseGrade::Withdrawn] {
+            let courses = CourseSet::new(&[attempt(grade)]);
+            assert!(requirement.evaluate(&courses).completed.is_empty());
+        }
+        let strict = GradePolicy {
+            passing_grade: Grade::C,
+            ..Default::default()
+        };
+        let courses = CourseSet::with_policy(&[attempt(Grade::CMinus.into())], &strict);
+        assert!(requirement.evaluate(&courses).completed.is_empty());
+
+        // The latest attempt counts, and the grade of the failed one is forgiven
+        let attempts = [
+            attempt(Grade::F.into()),
+            attempt(Grade::B.into()),
+            attempt(CourseGrade::Withdrawn),
+        ];
+        let satisfaction = requirement.evaluate(&CourseSet::new(&attempts));
+        assert_eq!(satisfaction.completed, vec![csc_115]);
+        assert_eq!(satisfaction.gpa(), Some(Gpa::from_hundredths(300)));
+
+        let never = GradePolicy {
+            forgiveness: Forgiveness::Never,
+            ..Default::default()
+        };
+        let satisfaction = requirement.evaluate(&CourseSet::with_policy(&attempts, &never));
+        assert_eq!(satisfaction.gpa(), Some(Gpa::from_hundredths(150)));
+
+        // A lower grade replaces a higher one, unless the highest grade counts
+        let attempts = [attempt(Grade::B.into()), attempt(Grade::F.into())];
+        let satisfaction = requirement.evaluate(&CourseSet::new(&attempts));
+        assert!(satisfaction.completed.is_empty());
+        let highest = GradePolicy {
+            repeats: RepeatPolicy::Highest,
+            ..Default::default()
+        };
+        let counted = highest.counted(&attempts);
+        assert_eq!(counted, [attempt(Grade::B.into())]);
+    }
+
+    #[test]
+    fn satisfied_requirements_below_the_minimum_gpa_are_partial() {
+        let program = cs_minor();
+        let minor = program.iter_requirements().next().unwrap();
+        let mut requirement = Requirement::Courses {
+            title: None,
+            courses: minor.course_entries().cloned().unwrap(),
+            constraints: Constraints::from_text("minimum 2.5 GPA in the minor"),
+            extra: Default::default(),
+        };
+
+        let completed: Vec<CompletedCourse> = minor
+            .course_entries()
+            .unwrap()
+            .iter_courses()
+            .enumerate()
+            .map(|(i, course)| {
+                let grade = if i == 0 { Grade::A } else { Grade::C };
+                CompletedCourse::guid(course.guid, 3).with_grade(grade)
+            })
+            .collect();
+        let courses = CourseSet::new(&completed);
+
+        let satisfaction = requirement.evaluate(&courses);
+        assert_eq!(satisfaction.status, Status::Partial);
+        assert!(satisfaction.gpa().unwrap() < Gpa::from_hundredths(250));
+
+        let Requirement::Courses { constraints, .. } = &mut requirement else {
+            unreachable!()
+        };
+        *constraints = Constraints::default();
+        assert!(requirement.evaluate(&courses).is_satisfied());
+    }
+
+    #[test]
+    fn unused_courses_are_reported() {
+        let report = audit(
+            &cs_minor(),
+            &[
+                CompletedCourse::code("CSC", "115", 3),
+                CompletedCourse::code("ART", "101", 3),
+            ],
+        );
+
+        assert_eq!(report.unused, vec![CompletedCourse::code("ART", "101", 3)]);
+    }
+
+    #[test]
+    fn elective_pools_take_unused_courses() {
+        let mut program = cs_minor();
+        let pool = |hours| Requirement::ElectivePool {
+            title: Some("Electives".to_owned()),
+            req_narrative: None,
+            hours,
+            constraints: Constraints::from_text("with a grade of C or better"),
+            extra: Default::default(),
+        };
+        let modules = program.requirements.as_mut().unwrap().modules_mut();
+        let RequirementModule::BasicRequirements { requirements, .. } = &mut modules[0] else {
+            panic!("the minor has basic requirements");
+        };
+        requirements.push(pool(ElectiveHours::Hours(6)));
+        requirements.push(pool(ElectiveHours::ToTotal(12)));
+
+        let completed = [
+            CompletedCourse::code("CSC", "115", 3),
+            CompletedCourse::code("ART", "101", 3).with_grade(Grade::D),
+            CompletedCourse::code("ENG", "111", 3),
+            CompletedCourse::code("HIS", "101", 3),
+            CompletedCourse::code("PHY", "231", 4),
+        ];
+        let report = audit(&program, &completed);
+        let pools: Vec<&RequirementAudit> = report
+            .requirements
+            .iter()
+            .filter(|audit| audit.title.as_deref() == Some("Electives"))
+            .collect();
+
+        // ENG 111 and HIS 101 make up the hours, leaving PHY 231 to the total
+        assert_eq!(pools[0].status, Status::Satisfied);
+        assert_eq!(pools[0].credits, 6);
+        assert_eq!(pools[0].credits_required, Some(6));
+        assert_eq!(pools[1].status, Status::Satisfied);
+        assert_eq!(pools[1].credits, 13);
+
+        // ART 101 is below the minimum grade of both pools
+        assert_eq!(report.unused, vec![completed[1].clone()]);
+
+        let report = audit(&program, &completed[..2]);
+        assert_eq!(
+            requirement(&report, "Electives").status,
+            Status::Outstanding
+        );
+    }
+
+    #[test]
+    fn courses_in_progress_only_count_towards_the_state() {
+        let program = cs_minor();
+        // One course of every entry of the minor, the first option of `Or` groups
+        let guids: Vec<Guid> = program
+            .iter_requirements()
+            .next()
+            .and_then(|minor| minor.course_entries())
+            .unwrap()
+            .iter()
+            .map(|entry| match entry {
+                CourseEntry::Course(course) => course.guid,
+                CourseEntry::Or(group) => match &group[0] {
+                    CourseEntry::Course(course) => course.guid,
+                    _ => panic!("the groups list courses"),
+                },
+                _ => panic!("the minor lists courses and groups"),
+            })
+            .collect();
+        let transcript = |progress: &[Progress]| -> Vec<CompletedCourse> {
+            guids
+                .iter()
+                .zip(progress)
+                .map(|(guid, progress)| CompletedCourse::guid(*guid, 3).with_progress(*progress))
+                .collect()
+        };
+
+        let mut progress = vec![Progress::Completed; guids.len()];
+        progress[guids.len() - 1] = Progress::InProgress;
+        let report = audit(&program, &transcript(&progress[..guids.len() - 1]));
+        assert_eq!(report.state, AuditState::Remaining);
+
+        let report = audit(&program, &transcript(&progress));
+        let minor = requirement(&report, "Minor Requirements:");
+        assert_eq!(minor.status, Status::Partial);
+        assert_eq!(minor.state, AuditState::InProgress);
+        assert_eq!(minor.completed.len(), guids.len() - 1);
+        assert_eq!(minor.in_progress, [guids[guids.len() - 1]]);
+
+        // Planned courses count the same
+        progress[0] = Progress::Planned;
+        let minor = requirement(
+            &audit(&program, &transcript(&progress)),
+            "Minor Requirements:",
+        )
+        .clone();
+        assert_eq!(minor.state, AuditState::InProgress);
+        assert_eq!(minor.in_progress.len(), 2);
+        assert_eq!(minor.credits, 3 * (guids.len() as u32 - 2));
+
+        let report = audit(
+            &program,
+            &transcript(&vec![Progress::Completed; guids.len()]),
+        );
+        let minor = requirement(&report, "Minor Requirements:");
+        assert_eq!(minor.state, AuditState::Complete);
+        assert!(minor.in_progress.is_empty());
+    }
+
+    #[test]
+    fn completed_courses_deserialize_from_either_id() {
+        let completed: Vec<CompletedCourse> = serde_json::from_str(
+            r#"[
+                {"guid": "860AF9C9-EAD9-45AC-AA92-BAF352C5288C", "credits": 3},
+                {"subject_code": "CSC", "number": "235", "credits": 4}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            completed,
+            vec![
+                CompletedCourse::guid(
+                    Guid::try_from("860AF9C9-EAD9-45AC-AA92-BAF352C5288C").unwrap(),
+                    3
+                ),
+                CompletedCourse::code("CSC", "235", 4),
+            ]
+        );
+    }
+}
@@ -0,0 +1,556 @@
+//! Renderings of an [AuditReport] for students, advisors and campus systems.
+//!
+//! An [AuditDocument] joins a report to the courses of the audited program, so that courses show
+//! with their subject code, number and name rather than their GUID. It renders as:
+//! - a self-contained HTML page with a checklist of the requirements, where modules and
+//!   requirements can be collapsed and are colored by their [AuditState], followed by the
+//!   [degree rules](super::rules) the program was audited with
+//! - a Markdown summary with a task list of the requirements, for emails and tickets
+//! - JSON, carrying the [AUDIT_FORMAT_VERSION] it was written with so that other systems can
+//!   read audits without scraping the HTML. With the `schema` feature, the JSON Schema of the
+//!   format is in [schemas](crate::schema::schemas).
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{audit::{self, render::AuditDocument, CompletedCourse, Progress}, Program};
+//! let program_json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+//! let program: Program = serde_json::from_str(&program_json).unwrap();
+//!
+//! let completed = [
+//!     CompletedCourse::code("CSC", "115", 3),
+//!     CompletedCourse::code("CSC", "125", 3).with_progress(Progress::InProgress),
+//! ];
+//! let document = AuditDocument::new(audit::audit(&program, &completed), &program);
+//!
+//! assert!(document.to_html().starts_with("<!DOCTYPE html>"));
+//! assert!(document.to_markdown().contains("- [x] CSC 115"));
+//!
+//! let json: serde_json::Value = serde_json::from_str(&document.to_json()).unwrap();
+//! assert_eq!(json["version"], 1);
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+};
+
+use serde::Serialize;
+
+use super::{rules::RuleAudit, AuditReport, AuditState, CourseId, RequirementAudit};
+use crate::{parsing::guid::Guid, visit::Visit, Course, Label, Program};
+
+/// Version of the JSON of [AuditDocument], bumped whenever it changes in a way that breaks the
+/// systems reading it
+pub const AUDIT_FORMAT_VERSION: u32 = 1;
+
+const STYLE: &str = r#"
+body { font-family: Helvetica, Arial, sans-serif; color: #1f2328; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; }
+details { margin: 0.25rem 0 0.25rem 1.25rem; }
+summary { cursor: pointer; padding: 0.2rem 0; }
+details.module > summary { font-weight: bold; font-size: 1.1rem; }
+details.requirement > summary { font-weight: bold; }
+ul { list-style: none; margin: 0.25rem 0 0.25rem 1.25rem; padding: 0; }
+li { padding: 0.15rem 0; }
+a { color: inherit; text-decoration: none; }
+a:hover { text-decoration: underline; }
+.code { font-weight: bold; }
+.details { color: #57606a; font-size: 0.9rem; font-weight: normal; }
+.complete { color: #1a7f37; }
+.in-progress { color: #9a6700; }
+.remaining { color: #cf222e; }
+.state { font-weight: bold; }
+.controls { margin: 1rem 0; }
+"#;
+
+const SCRIPT: &str = r#"
+function setAll(open) {
+  document.querySelectorAll("details").forEach(function (details) { details.open = open; });
+}
+"#;
+
+/// An [AuditReport] with the courses it refers to, see the [module](self) documentation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuditDocument {
+    /// [AUDIT_FORMAT_VERSION] of the document
+    pub version: u32,
+    pub report: AuditReport,
+    /// Every course of the program the requirements of the report refer to, in the order of the
+    /// program
+    pub courses: Vec<AuditCourse>,
+}
+
+/// A course of an [AuditDocument]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuditCourse {
+    pub guid: Guid,
+    pub url: String,
+    pub subject_code: String,
+    pub number: String,
+    pub name: Option<String>,
+}
+
+impl fmt::Display for AuditCourse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.subject_code, self.number)?;
+        if let Some(name) = &self.name {
+            write!(f, " {name}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AuditDocument {
+    /// Joins the `report` to the courses of the `program` it audited
+    pub fn new(report: AuditReport, program: &Program) -> Self {
+        let mut collector = Courses::default();
+        collector.visit_program(program);
+
+        let referred: Vec<&Guid> = report
+            .requirements
+            .iter()
+            .flat_map(|audit| [&audit.completed, &audit.in_progress, &audit.remaining])
+            .flatten()
+            .collect();
+        let courses = collector
+            .courses
+            .into_iter()
+            .filter(|course| referred.contains(&&course.guid))
+            .collect();
+
+        Self {
+            version: AUDIT_FORMAT_VERSION,
+            report,
+            courses,
+        }
+    }
+
+    /// The document as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("audit documents serialize to JSON")
+    }
+
+    /// Renders the report as a self-contained HTML page. Requirements that are complete start
+    /// collapsed.
+    pub fn to_html(&self) -> String {
+        let courses = self.course_index();
+        let title = escape(&self.report.title);
+
+        let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+        html.push_str("<meta charset=\"utf-8\">\n");
+        html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+        let _ = writeln!(html, "<title>Audit of {title}</title>");
+        let _ = writeln!(html, "<style>{STYLE}</style>");
+        let _ = writeln!(html, "<script>{SCRIPT}</script>");
+        html.push_str("</head>\n<body>\n");
+
+        let _ = writeln!(html, "<h1>{title}</h1>");
+        let state = self.report.state;
+        let _ = writeln!(
+            html,
+            "<p class=\"state {}\">{}</p>",
+            class(state),
+            describe(state)
+        );
+        html.push_str(
+            "<div class=\"controls\">\
+             <button type=\"button\" onclick=\"setAll(true)\">Expand all</button> \
+             <button type=\"button\" onclick=\"setAll(false)\">Collapse all</button>\
+             </div>\n",
+        );
+
+        for (module, requirements) in self.modules() {
+            let _ = writeln!(
+                html,
+                "<details class=\"module\" open>\n<summary>{}</summary>",
+                escape(module.unwrap_or("Requirements"))
+            );
+            for audit in requirements {
+                write_requirement(&mut html, audit, &courses);
+            }
+            html.push_str("</details>\n");
+        }
+
+        if !self.report.rules.is_empty() {
+            html.push_str("<h2>Degree rules</h2>\n<ul>\n");
+            for audit in &self.report.rules {
+                let _ = writeln!(
+                    html,
+                    "<li class=\"{}\">{} {} <span class=\"details\">{}</span></li>",
+                    class(audit.state),
+                    mark(audit.state),
+                    escape(&audit.description),
+                    escape(&rule_details(audit))
+                );
+            }
+            html.push_str("</ul>\n");
+        }
+
+        if !self.report.unused.is_empty() {
+            html.push_str("<h2>Courses not counted</h2>\n<ul>\n");
+            for course in &self.report.unused {
+                let _ = writeln!(html, "<li>{}</li>", escape(&course_id(&course.id)));
+            }
+            html.push_str("</ul>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+
+        html
+    }
+
+    /// Renders the report as Markdown, with a task list of the requirements of every module
+    pub fn to_markdown(&self) -> String {
+        let courses = self.course_index();
+
+        let mut markdown = format!("# {}\n\n", escape_markdown(&self.report.title));
+        let _ = writeln!(markdown, "**{}**", describe(self.report.state));
+
+        for (module, requirements) in self.modules() {
+            let _ = writeln!(
+                markdown,
+                "\n## {}\n",
+                escape_markdown(module.unwrap_or("Requirements"))
+            );
+            for audit in requirements {
+                let _ = write!(
+                    markdown,
+                    "- [{}] **{}** ({}",
+                    check(audit.state),
+                    escape_markdown(audit.title.as_deref().unwrap_or("Requirement")),
+                    describe(audit.state)
+                );
+                if let Some(credits) = credits(audit) {
+                    let _ = write!(markdown, ", {credits}");
+                }
+                markdown.push_str(")\n");
+
+                for (guid, state) in course_states(audit) {
+                    let _ = write!(
+                        markdown,
+                        "  - [{}] {}",
+                        check(state),
+                        escape_markdown(&course_label(guid, &courses))
+                    );
+                    if state == AuditState::InProgress {
+                        markdown.push_str(" (in progress)");
+                    }
+                    markdown.push('\n');
+                }
+            }
+        }
+
+        if !self.report.rules.is_empty() {
+            markdown.push_str("\n## Degree rules\n\n");
+            for audit in &self.report.rules {
+                let _ = writeln!(
+                    markdown,
+                    "- [{}] **{}** ({})",
+                    check(audit.state),
+                    escape_markdown(&audit.description),
+                    escape_markdown(&rule_details(audit))
+                );
+            }
+        }
+
+        if !self.report.unused.is_empty() {
+            markdown.push_str("\n## Courses not counted\n\n");
+            for course in &self.report.unused {
+                let _ = writeln!(markdown, "- {}", escape_markdown(&course_id(&course.id)));
+            }
+        }
+
+        markdown
+    }
+
+    fn course_index(&self) -> HashMap<Guid, &AuditCourse> {
+        self.courses
+            .iter()
+            .map(|course| (course.guid, course))
+            .collect()
+    }
+
+    /// Consecutive requirements of the report grouped by the title of their module
+    fn modules(&self) -> Vec<(Option<&str>, Vec<&RequirementAudit>)> {
+        let mut modules: Vec<(Option<&str>, Vec<&RequirementAudit>)> = vec![];
+        for audit in &self.report.requirements {
+            match modules.last_mut() {
+                Some((module, requirements)) if *module == audit.module.as_deref() => {
+                    requirements.push(audit)
+                }
+                _ => modules.push((audit.module.as_deref(), vec![audit])),
+            }
+        }
+
+        modules
+    }
+}
+
+/// Courses and labels of a program standing for a course, in the order of the program
+#[derive(Default)]
+struct Courses {
+    courses: Vec<AuditCourse>,
+}
+
+impl Courses {
+    fn push(&mut self, course: AuditCourse) {
+        if !self.courses.iter().any(|seen| seen.guid == course.guid) {
+            self.courses.push(course);
+        }
+    }
+}
+
+impl<'a> Visit<'a> for Courses {
+    fn visit_course(&mut self, course: &'a Course) {
+        self.push(AuditCourse {
+            guid: course.guid,
+            url: course.url.to_string(),
+            subject_code: course.subject_code.to_string(),
+            number: course.number.clone(),
+            name: course.name.clone(),
+        });
+    }
+
+    fn visit_label(&mut self, label: &'a Label) {
+        if let (Some(subject_code), Some(number)) = (&label.subject_code, &label.number) {
+            self.push(AuditCourse {
+                guid: label.guid,
+                url: label.url.to_string(),
+                subject_code: subject_code.to_string(),
+                number: number.clone(),
+                name: Some(label.name.clone()),
+            });
+        }
+    }
+}
+
+fn write_requirement(
+    html: &mut String,
+    audit: &RequirementAudit,
+    courses: &HashMap<Guid, &AuditCourse>,
+) {
+    let open = if audit.state == AuditState::Complete {
+        ""
+    } else {
+        " open"
+    };
+    let _ = write!(
+        html,
+        "<details class=\"requirement\"{open}>\n<summary><span class=\"{}\">{} {}</span>",
+        class(audit.state),
+        mark(audit.state),
+        escape(audit.title.as_deref().unwrap_or("Requirement"))
+    );
+    let mut details = vec![describe(audit.state).to_owned()];
+    details.extend(credits(audit));
+    if let Some(gpa) = audit.gpa {
+        details.push(format!("GPA {gpa}"));
+    }
+    let _ = writeln!(
+        html,
+        " <span class=\"details\">{}</span></summary>",
+        escape(&details.join(", "))
+    );
+
+    html.push_str("<ul>\n");
+    for (guid, state) in course_states(audit) {
+        let label = escape(&course_label(guid, courses));
+        let label = match courses.get(&guid) {
+            Some(course) if !course.url.is_empty() => {
+                format!("<a href=\"{}\">{label}</a>", escape(&course.url))
+            }
+            _ => label,
+        };
+        let _ = writeln!(
+            html,
+            "<li class=\"{}\">{} {label}</li>",
+            class(state),
+            mark(state)
+        );
+    }
+    html.push_str("</ul>\n</details>\n");
+}
+
+/// Courses of the requirement with their own state, completed ones first
+fn course_states(audit: &RequirementAudit) -> impl Iterator<Item = (Guid, AuditState)> + '_ {
+    let completed = audit
+        .completed
+        .iter()
+        .map(|guid| (*guid, AuditState::Complete));
+    let in_progress = audit
+        .in_progress
+        .iter()
+        .map(|guid| (*guid, AuditState::InProgress));
+    let remaining = audit
+        .remaining
+        .iter()
+        .filter(|guid| !audit.in_progress.contains(guid))
+        .map(|guid| (*guid, AuditState::Remaining));
+
+    completed.chain(in_progress).chain(remaining)
+}
+
+fn course_label(guid: Guid, courses: &HashMap<Guid, &AuditCourse>) -> String {
+    match courses.get(&guid) {
+        Some(course) => course.to_string(),
+        None => guid.to_string(),
+    }
+}
+
+fn course_id(id: &CourseId) -> String {
+    match id {
+        CourseId::Guid { guid } => guid.to_string(),
+        CourseId::Code {
+            subject_code,
+            number,
+        } => format!("{subject_code} {number}"),
+    }
+}
+
+fn credits(audit: &RequirementAudit) -> Option<String> {
+    match audit.credits_required {
+        Some(required) => Some(format!("{} of {required} credits", audit.credits)),
+        None if audit.credits > 0 => Some(format!("{} credits", audit.credits)),
+        None => None,
+    }
+}
+
+fn rule_details(audit: &RuleAudit) -> String {
+    match audit.detail.is_empty() {
+        true => describe(audit.state).to_owned(),
+        false => format!("{}, {}", describe(audit.state), audit.detail),
+    }
+}
+
+fn describe(state: AuditState) -> &'static str {
+    match state {
+        AuditState::Complete => "Complete",
+        AuditState::InProgress => "In progress",
+        AuditState::Remaining => "Remaining",
+    }
+}
+
+fn class(state: AuditState) -> &'static str {
+    match state {
+        AuditState::Complete => "complete",
+        AuditState::InProgress => "in-progress",
+        AuditState::Remaining => "remaining",
+    }
+}
+
+fn mark(state: AuditState) -> &'static str {
+    match state {
+        AuditState::Complete => "&#10003;",
+        AuditState::InProgress => "&#9680;",
+        AuditState::Remaining => "&#9744;",
+    }
+}
+
+fn check(state: AuditState) -> char {
+    match state {
+        AuditState::Complete => 'x',
+        AuditState::InProgress | AuditState::Remaining => ' ',
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escapes the characters Markdown would read as formatting
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::audit::{
+        audit_with_rules, rules::UpperDivisionHours, CompletedCourse, GradePolicy, Progress,
+    };
+    use crate::test_support::cs_minor;
+
+    fn document() -> AuditDocument {
+        let program = cs_minor();
+        let completed = [
+            CompletedCourse::code("CSC", "115", 3),
+            CompletedCourse::code("CSC", "125", 3).with_progress(Progress::InProgress),
+            CompletedCourse::code("ART", "101", 3),
+        ];
+
+        let report = audit_with_rules(
+            &program,
+            &completed,
+            &GradePolicy::default(),
+            &[&UpperDivisionHours::new(9)],
+        );
+        AuditDocument::new(report, &program)
+    }
+
+    #[test]
+    fn checklists_mark_every_course_with_its_state() {
+        let document = document();
+
+        let html = document.to_html();
+        assert!(html.contains("<title>Audit of Minor in Computer Science"));
+        assert!(html.contains("<li class=\"complete\">&#10003; <a href="));
+        assert!(html.contains("<li class=\"in-progress\">&#9680; <a href="));
+        assert!(html.contains("<li>ART 101</li>"));
+        assert!(html.contains("<h2>Degree rules</h2>\n<ul>\n<li class=\"remaining\">"));
+        assert_eq!(
+            html.matches("<details").count(),
+            html.matches("</details>").count()
+        );
+
+        let markdown = document.to_markdown();
+        assert!(markdown.starts_with("# Minor in Computer Science"));
+        assert!(markdown.contains("\n  - [x] CSC 115 "));
+        assert!(markdown.contains(" (in progress)\n"));
+        assert!(markdown.contains(
+            "\n- [ ] **9 upper-division hours** (Remaining, 0 of 9 upper-division hours)\n"
+        ));
+        assert!(markdown.contains("\n## Courses not counted\n\n- ART 101\n"));
+    }
+
+    #[test]
+    fn json_is_versioned_and_names_the_courses() {
+        let document = document();
+        let json: serde_json::Value = serde_json::from_str(&document.to_json()).unwrap();
+
+        assert_eq!(json["version"], AUDIT_FORMAT_VERSION);
+        assert_eq!(json["report"]["state"], "Remaining");
+
+        let guids: Vec<&str> = json["courses"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|course| course["guid"].as_str().unwrap())
+            .collect();
+        for audit in json["report"]["requirements"].as_array().unwrap() {
+            for guid in audit["completed"].as_array().unwrap() {
+                assert!(guids.contains(&guid.as_str().unwrap()));
+            }
+        }
+        assert!(document
+            .courses
+            .iter()
+            .any(|course| course.subject_code == "CSC" && course.number == "115"));
+    }
+
+    #[test]
+    fn markdown_is_escaped() {
+        assert_eq!(escape_markdown("C* [and] C#"), r"C\* \[and\] C\#");
+    }
+}
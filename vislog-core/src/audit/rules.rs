@@ -0,0 +1,412 @@
+//! Degree rules checked over the whole transcript rather than the courses of a requirement.
+//!
+//! Catalogs state rules such as "at least 50% of the hours of the major must be taken at Union"
+//! or "39 upper-division hours" apart from the requirements of their programs. A [PolicyRule]
+//! looks at every completed course that counts and passed, as a [RuleCourse] telling whether it
+//! was transferred, its level and whether it counts towards the program.
+//! [audit_with_rules](super::audit_with_rules) reports every rule as a [RuleAudit] next to the
+//! requirements.
+//!
+//! The built-in rules are [Residency], [UpperDivisionHours] and [TotalHours]. They serialize, so
+//! the rules of an institution can be kept in a configuration file. Implement [PolicyRule] for
+//! rules of your own.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{audit::{self, rules::{HoursScope, Residency, UpperDivisionHours}, CompletedCourse, GradePolicy, Status}, Program};
+//! let program_json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+//! let program: Program = serde_json::from_str(&program_json).unwrap();
+//!
+//! let completed = [
+//!     CompletedCourse::code("CSC", "115", 3).transferred(),
+//!     CompletedCourse::code("CSC", "125", 3),
+//!     CompletedCourse::code("CSC", "321", 3),
+//! ];
+//! let residency = Residency {
+//!     min_percent: 50,
+//!     scope: HoursScope::Program,
+//! };
+//! let upper_division = UpperDivisionHours::new(39);
+//! let report = audit::audit_with_rules(
+//!     &program,
+//!     &completed,
+//!     &GradePolicy::default(),
+//!     &[&residency, &upper_division],
+//! );
+//!
+//! assert_eq!(report.rules[0].status, Status::Satisfied);
+//! assert_eq!(report.rules[1].detail, "3 of 39 upper-division hours");
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::{AuditState, CompletedCourse, CourseId, Status};
+use crate::{constraints::Grade, Course, Program};
+
+/// A degree rule over every course of a transcript
+pub trait PolicyRule: Send + Sync {
+    /// Name of the rule in kebab case. Ex: "residency"
+    fn name(&self) -> &'static str;
+
+    /// The rule as a catalog would state it. Ex: "39 upper-division hours"
+    fn description(&self) -> String;
+
+    /// Checks the rule over the `courses` of a transcript audited against the `program`
+    fn evaluate(&self, program: &Program, courses: &[RuleCourse]) -> RuleOutcome;
+}
+
+/// A completed course as [PolicyRule]s see it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleCourse<'a> {
+    pub course: &'a CompletedCourse,
+    /// Subject code and number of the course, unless it is only known by a GUID that the program
+    /// doesn't list
+    pub code: Option<(&'a str, &'a str)>,
+    /// Whether the course counts towards a requirement of the program
+    pub in_program: bool,
+}
+
+impl RuleCourse<'_> {
+    /// Hundreds of the course number, such as 300 for "CSC 321" and "CSC 321L"
+    pub fn level(&self) -> Option<u32> {
+        let (_, number) = self.code?;
+        let digits: String = number
+            .trim()
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+
+        Some(digits.parse::<u32>().ok()? / 100 * 100)
+    }
+
+    pub fn credits(&self) -> u32 {
+        u32::from(self.course.credits)
+    }
+}
+
+/// What a [PolicyRule] found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleOutcome {
+    pub status: Status,
+    /// What the rule counted, in a few words. Ex: "33 of 39 upper-division hours"
+    pub detail: String,
+}
+
+impl RuleOutcome {
+    /// Outcome of a rule needing `required` hours of `what`, `Partial` while some were earned
+    pub fn hours(earned: u32, required: u32, what: &str) -> Self {
+        let status = if earned >= required {
+            Status::Satisfied
+        } else if earned == 0 {
+            Status::Outstanding
+        } else {
+            Status::Partial
+        };
+
+        Self {
+            status,
+            detail: format!("{earned} of {required} {what}"),
+        }
+    }
+}
+
+/// Result of checking a [PolicyRule] during an audit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RuleAudit {
+    /// [Name](PolicyRule::name) of the rule
+    pub rule: &'static str,
+    pub description: String,
+    pub status: Status,
+    pub state: AuditState,
+    pub detail: String,
+}
+
+/// Which courses the hours of a rule are counted over
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum HoursScope {
+    /// Every course of the transcript
+    #[default]
+    All,
+    /// Courses counting towards a requirement of the program, such as the hours of a major
+    Program,
+}
+
+impl HoursScope {
+    fn includes(self, course: &RuleCourse) -> bool {
+        match self {
+            HoursScope::All => true,
+            HoursScope::Program => course.in_program,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            HoursScope::All => "hours",
+            HoursScope::Program => "program hours",
+        }
+    }
+}
+
+/// A share of the hours taken in residence rather than transferred
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Residency {
+    /// Percentage of the hours in the scope that must be taken in residence
+    pub min_percent: u32,
+    pub scope: HoursScope,
+}
+
+/// A number of hours of courses numbered at or above a level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct UpperDivisionHours {
+    pub min_hours: u32,
+    /// Lowest [level](RuleCourse::level) of upper-division courses
+    pub min_level: u32,
+    pub scope: HoursScope,
+}
+
+impl UpperDivisionHours {
+    /// Rule needing `min_hours` of courses numbered 300 and above
+    pub fn new(min_hours: u32) -> Self {
+        Self {
+            min_hours,
+            min_level: 300,
+            scope: HoursScope::All,
+        }
+    }
+}
+
+/// A number of hours earned in total
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TotalHours {
+    pub min_hours: u32,
+    pub scope: HoursScope,
+}
+
+impl PolicyRule for Residency {
+    fn name(&self) -> &'static str {
+        "residency"
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "{}% of {} in residence",
+            self.min_percent,
+            self.scope.describe()
+        )
+    }
+
+    fn evaluate(&self, _program: &Program, courses: &[RuleCourse]) -> RuleOutcome {
+        let courses = courses.iter().filter(|course| self.scope.includes(course));
+        let total: u32 = courses.clone().map(RuleCourse::credits).sum();
+        let resident: u32 = courses
+            .filter(|course| !course.course.transfer)
+            .map(RuleCourse::credits)
+            .sum();
+
+        let required = (total * self.min_percent).div_ceil(100);
+        RuleOutcome::hours(resident, required, "hours in residence")
+    }
+}
+
+impl PolicyRule for UpperDivisionHours {
+    fn name(&self) -> &'static str {
+        "upper-division-hours"
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "{} upper-division {}",
+            self.min_hours,
+            self.scope.describe()
+        )
+    }
+
+    fn evaluate(&self, _program: &Program, courses: &[RuleCourse]) -> RuleOutcome {
+        let earned = courses
+            .iter()
+            .filter(|course| self.scope.includes(course))
+            .filter(|course| course.level().is_some_and(|level| level >= self.min_level))
+            .map(RuleCourse::credits)
+            .sum();
+
+        RuleOutcome::hours(earned, self.min_hours, "upper-division hours")
+    }
+}
+
+impl PolicyRule for TotalHours {
+    fn name(&self) -> &'static str {
+        "total-hours"
+    }
+
+    fn description(&self) -> String {
+        format!("{} {}", self.min_hours, self.scope.describe())
+    }
+
+    fn evaluate(&self, _program: &Program, courses: &[RuleCourse]) -> RuleOutcome {
+        let earned = courses
+            .iter()
+            .filter(|course| self.scope.includes(course))
+            .map(RuleCourse::credits)
+            .sum();
+
+        RuleOutcome::hours(earned, self.min_hours, self.scope.describe())
+    }
+}
+
+/// Checks the `rules` over the `completed` courses that count and passed, given the courses the
+/// audit left `unused`
+pub(super) fn check(
+    rules: &[&dyn PolicyRule],
+    program: &Program,
+    completed: &[CompletedCourse],
+    unused: &[CompletedCourse],
+    passing_grade: Grade,
+) -> Vec<RuleAudit> {
+    if rules.is_empty() {
+        return vec![];
+    }
+
+    let listed: Vec<&Course> = program.iter_courses().collect();
+    let courses: Vec<RuleCourse> = completed
+        .iter()
+        .filter(|course| course.grade.is_none_or(|grade| grade.passes(passing_grade)))
+        .map(|course| RuleCourse {
+            course,
+            code: match &course.id {
+                CourseId::Code {
+                    subject_code,
+                    number,
+                } => Some((subject_code.as_str(), number.as_str())),
+                CourseId::Guid { guid } => listed
+                    .iter()
+                    .find(|listed| listed.guid == *guid)
+                    .map(|listed| (&*listed.subject_code, listed.number.as_str())),
+            },
+            in_program: !unused.contains(course),
+        })
+        .collect();
+
+    rules
+        .iter()
+        .map(|rule| {
+            let outcome = rule.evaluate(program, &courses);
+            RuleAudit {
+                rule: rule.name(),
+                description: rule.description(),
+                status: outcome.status,
+                state: AuditState::new(outcome.status, outcome.status),
+                detail: outcome.detail,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::audit::{audit_with_rules, GradePolicy, Progress};
+    use crate::test_support::cs_minor;
+
+    /// Needs a course numbered 499
+    struct Capstone;
+
+    impl PolicyRule for Capstone {
+        fn name(&self) -> &'static str {
+            "capstone"
+        }
+
+        fn description(&self) -> String {
+            "A capstone course".to_owned()
+        }
+
+        fn evaluate(&self, _program: &Program, courses: &[RuleCourse]) -> RuleOutcome {
+            let done = courses
+                .iter()
+                .any(|course| matches!(course.code, Some((_, "499"))));
+            RuleOutcome {
+                status: if done {
+                    Status::Satisfied
+                } else {
+                    Status::Outstanding
+                },
+                detail: String::new(),
+            }
+        }
+    }
+
+    #[test]
+    fn rules_count_hours_over_the_whole_transcript() {
+        let program = cs_minor();
+        let completed = [
+            CompletedCourse::code("CSC", "115", 3).transferred(),
+            CompletedCourse::code("CSC", "125", 4).transferred(),
+            CompletedCourse::code("CSC", "321", 3),
+            CompletedCourse::code("ART", "350", 4),
+            CompletedCourse::code("HIS", "301", 3).with_grade(Grade::F),
+            CompletedCourse::code("CSC", "499", 3).with_progress(Progress::InProgress),
+        ];
+        let residency = |scope| Residency {
+            min_percent: 50,
+            scope,
+        };
+        let rules: [&dyn PolicyRule; 5] = [
+            &residency(HoursScope::All),
+            &residency(HoursScope::Program),
+            &UpperDivisionHours::new(9),
+            &TotalHours {
+                min_hours: 14,
+                scope: HoursScope::All,
+            },
+            &Capstone,
+        ];
+
+        let report = audit_with_rules(&program, &completed, &GradePolicy::default(), &rules);
+        let results: Vec<(&str, Status, &str)> = report
+            .rules
+            .iter()
+            .map(|audit| (audit.rule, audit.status, audit.detail.as_str()))
+            .collect();
+        assert_eq!(
+            results,
+            [
+                ("residency", Status::Satisfied, "7 of 7 hours in residence"),
+                ("residency", Status::Partial, "3 of 5 hours in residence"),
+                (
+                    "upper-division-hours",
+                    Status::Partial,
+                    "7 of 9 upper-division hours"
+                ),
+                ("total-hours", Status::Satisfied, "14 of 14 hours"),
+                ("capstone", Status::Outstanding, ""),
+            ]
+        );
+        assert_eq!(
+            report.rules[1].description,
+            "50% of program hours in residence"
+        );
+        assert_eq!(report.rules[4].state, AuditState::InProgress);
+        assert_ne!(report.status, Status::Satisfied);
+    }
+
+    #[test]
+    fn course_levels_are_read_from_numbers() {
+        let course = CompletedCourse::code("CSC", "321L", 3);
+        let level = |number| {
+            RuleCourse {
+                course: &course,
+                code: Some(("CSC", number)),
+                in_program: false,
+            }
+            .level()
+        };
+
+        assert_eq!(level("321L"), Some(300));
+        assert_eq!(level(" 099"), Some(0));
+        assert_eq!(level("TBD"), None);
+    }
+}
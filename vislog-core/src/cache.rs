@@ -0,0 +1,339 @@
+//! Parsing a directory of JSON files again without parsing the files that didn't change.
+//!
+//! [Catalog::load_cached] keeps what every file of the directory parsed to in a binary snapshot,
+//! along with a [content hash](crate::hash) of the file. Later loads only parse the files whose
+//! hash changed, and decode everything else from the snapshot, which is written back with the new
+//! results before returning.
+//!
+//! Errors are kept along with whatever else the file parsed to, and come back from the snapshot
+//! with the same message, but as a [VislogError::Json] whatever their original kind was. A
+//! snapshot written by another [WIRE_VERSION] or that fails to decode is ignored and replaced, and
+//! so is one written with another [dialect](crate::parsing::dialect) or other
+//! [options](crate::parsing::options) than the ones of the current thread.
+//!
+//! # Example
+//! ```no_run
+//! # use vislog_core::catalog::Catalog;
+//! let (catalog, errors) = Catalog::load_cached("../data", "../data/.catalog-cache")?;
+//! # Ok::<(), vislog_core::catalog::CatalogError>(())
+//! ```
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{de::Error as _, Deserialize, Serialize};
+
+use crate::{
+    catalog::{self, Catalog, CatalogError, ParsedFile},
+    error::VislogError,
+    hash::content_hash,
+    metrics::Counter,
+    parsing::{dialect, options},
+    wire::{WireCourseDetails, WireProgram, WIRE_VERSION},
+    CourseDetails, Program,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    /// [WIRE_VERSION] of the types the snapshot was written with
+    version: u32,
+    /// [parsing_fingerprint] of the thread the files were parsed on
+    parsing: u64,
+    files: Vec<CachedFile>,
+}
+
+/// Hash of everything on the current thread that changes what files parse to
+fn parsing_fingerprint() -> u64 {
+    content_hash(&(dialect::current_fingerprint(), options::current()))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedFile {
+    path: PathBuf,
+    /// [content_hash] of the contents of the file
+    hash: u64,
+    programs: Vec<WireProgram>,
+    courses: Vec<WireCourseDetails>,
+    errors: Vec<CachedError>,
+    failures: Option<Counter>,
+}
+
+/// A [CatalogError] with its source reduced to its message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum CachedError {
+    Json { message: String },
+    Entry { index: usize, message: String },
+    UnrecognizedFormat,
+}
+
+impl CachedError {
+    /// `None` for errors that parsing the same file again may not run into
+    fn new(error: &CatalogError) -> Option<Self> {
+        match error {
+            CatalogError::Io { .. } => None,
+            CatalogError::Json { source, .. } => Some(CachedError::Json {
+                message: source.to_string(),
+            }),
+            CatalogError::Entry { index, source, .. } => Some(CachedError::Entry {
+                index: *index,
+                message: source.to_string(),
+            }),
+            CatalogError::UnrecognizedFormat { .. } => Some(CachedError::UnrecognizedFormat),
+        }
+    }
+
+    fn into_error(self, path: &Path) -> CatalogError {
+        let path = path.to_owned();
+        match self {
+            CachedError::Json { message } => CatalogError::Json {
+                path,
+                source: VislogError::from(serde_json::Error::custom(message)),
+            },
+            CachedError::Entry { index, message } => CatalogError::Entry {
+                path,
+                index,
+                source: VislogError::from(serde_json::Error::custom(message)),
+            },
+            CachedError::UnrecognizedFormat => CatalogError::UnrecognizedFormat { path },
+        }
+    }
+}
+
+impl Catalog {
+    /// Same as [Catalog::parse_dir], but files that didn't change since the last call with the
+    /// same `cache_path` are read from the snapshot at `cache_path` instead of being parsed.
+    ///
+    /// Failing to read the snapshot isn't an error since the files are parsed instead, but failing
+    /// to write it is.
+    pub fn load_cached(
+        dir: impl AsRef<Path>,
+        cache_path: impl AsRef<Path>,
+    ) -> Result<(Catalog, Vec<CatalogError>), CatalogError> {
+        let (parsed, _reused) = load_files(dir.as_ref(), cache_path.as_ref())?;
+
+        Ok(catalog::merge(parsed))
+    }
+}
+
+/// Every file of `dir`, either parsed or decoded from the snapshot, and how many were decoded
+fn load_files(dir: &Path, cache_path: &Path) -> Result<(Vec<ParsedFile>, usize), CatalogError> {
+    let files = catalog::json_files_in(dir)?;
+    let mut cached: HashMap<PathBuf, CachedFile> = read_snapshot(cache_path)
+        .map(|snapshot| {
+            snapshot
+                .files
+                .into_iter()
+                .map(|file| (file.path.clone(), file))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut parsed = Vec::with_capacity(files.len());
+    let mut snapshot = Snapshot {
+        version: WIRE_VERSION,
+        parsing: parsing_fingerprint(),
+        files: vec![],
+    };
+    let mut reused = 0;
+
+    for path in files {
+        let json = match fs::read_to_string(&path) {
+            Ok(json) => json,
+            Err(source) => {
+                parsed.push(ParsedFile {
+                    errors: vec![CatalogError::Io { path, source }],
+                    ..Default::default()
+                });
+                continue;
+            }
+        };
+        let hash = content_hash(json.as_bytes());
+
+        let unchanged = cached
+            .remove(&path)
+            .filter(|file| file.hash == hash)
+            .and_then(|file| Some((decode(file.clone())?, file)));
+        match unchanged {
+            Some((file, cached)) => {
+                parsed.push(file);
+                snapshot.files.push(cached);
+                reused += 1;
+            }
+            None => {
+                let file = catalog::parse_json(&path, &json);
+                let errors: Option<Vec<_>> = file.errors.iter().map(CachedError::new).collect();
+                if let Some(errors) = errors {
+                    snapshot.files.push(CachedFile {
+                        path,
+                        hash,
+                        programs: file.programs.iter().map(WireProgram::from).collect(),
+                        courses: file.courses.iter().map(WireCourseDetails::from).collect(),
+                        errors,
+                        failures: file.failures,
+                    });
+                }
+                parsed.push(file);
+            }
+        }
+    }
+
+    write_snapshot(cache_path, &snapshot).map_err(|source| CatalogError::Io {
+        path: cache_path.to_owned(),
+        source,
+    })?;
+
+    Ok((parsed, reused))
+}
+
+fn read_snapshot(cache_path: &Path) -> Option<Snapshot> {
+    let bytes = fs::read(cache_path).ok()?;
+    let snapshot: Snapshot = bincode::deserialize(&bytes).ok()?;
+
+    (snapshot.version == WIRE_VERSION && snapshot.parsing == parsing_fingerprint())
+        .then_some(snapshot)
+}
+
+/// Writes next to `cache_path` first so an interrupted write never leaves half a snapshot behind
+fn write_snapshot(cache_path: &Path, snapshot: &Snapshot) -> io::Result<()> {
+    let bytes = bincode::serialize(snapshot).map_err(io::Error::other)?;
+
+    let mut partial = cache_path.as_os_str().to_owned();
+    partial.push(".partial");
+    fs::write(&partial, bytes)?;
+    fs::rename(&partial, cache_path)
+}
+
+fn decode(file: CachedFile) -> Option<ParsedFile> {
+    let programs = file
+        .programs
+        .into_iter()
+        .map(Program::try_from)
+        .collect::<Result<_, _>>()
+        .ok()?;
+
+    let file = ParsedFile {
+        programs,
+        courses: file.courses.into_iter().map(CourseDetails::from).collect(),
+        errors: file
+            .errors
+            .into_iter()
+            .map(|error| error.into_error(&file.path))
+            .collect(),
+        failures: file.failures,
+    };
+    // Counted on every load like the files that are parsed again
+    file.record_failures();
+
+    Some(file)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A directory holding a copy of some of the files in `../data`
+    fn data_subset(files: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vislog-{}", uuid::Uuid::new_v4()));
+        fs::create_dir(&dir).unwrap();
+        for file in files {
+            fs::copy(Path::new("../data").join(file), dir.join(file)).unwrap();
+        }
+        dir
+    }
+
+    fn messages(errors: &[CatalogError]) -> Vec<String> {
+        errors.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn only_changed_files_are_parsed_again() {
+        let dir = data_subset(&[
+            "cs_major.json",
+            "cs_minor.json",
+            "family_studies_major.json",
+        ]);
+        let cache_path = dir.join(".cache");
+        let (expected, expected_errors) = Catalog::parse_dir(&dir).unwrap();
+
+        let load = || {
+            let (parsed, reused) = load_files(&dir, &cache_path).unwrap();
+            let (catalog, errors) = catalog::merge(parsed);
+            assert_eq!(catalog, expected);
+            assert_eq!(messages(&errors), messages(&expected_errors));
+            reused
+        };
+
+        assert_eq!(load(), 0);
+        // Including `family_studies_major.json`, whose error comes from the snapshot
+        assert_eq!(load(), 3);
+
+        let minor = fs::read_to_string(dir.join("cs_minor.json")).unwrap();
+        fs::write(dir.join("cs_minor.json"), minor + "\n").unwrap();
+        assert_eq!(load(), 2);
+        assert_eq!(load(), 3);
+
+        fs::write(&cache_path, b"not a snapshot").unwrap();
+        assert_eq!(load(), 0);
+        assert_eq!(Catalog::load_cached(&dir, &cache_path).unwrap().0, expected);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn snapshots_of_other_dialects_and_options_are_ignored() {
+        use std::sync::Arc;
+
+        use crate::parsing::{
+            dialect::{self, CatalogDialect, UnionUniversity},
+            guid::{GUIDParsingError, Guid},
+            options::{self, ParseOptions, TextFormat},
+        };
+
+        /// Same spelling as [UnionUniversity] under another name
+        struct Renamed;
+
+        impl CatalogDialect for Renamed {
+            fn parse_bool(&self, s: &str) -> Option<bool> {
+                UnionUniversity.parse_bool(s)
+            }
+
+            fn parse_guid(&self, s: &str) -> Result<Guid, GUIDParsingError> {
+                UnionUniversity.parse_guid(s)
+            }
+
+            fn parse_credits(&self, s: &str) -> anyhow::Result<(u8, Option<u8>)> {
+                UnionUniversity.parse_credits(s)
+            }
+
+            fn parse_credit_hours(&self, s: &str) -> anyhow::Result<u8> {
+                UnionUniversity.parse_credit_hours(s)
+            }
+        }
+
+        let dir = data_subset(&["cs_major.json", "cs_minor.json"]);
+        let cache_path = dir.join(".cache");
+        let reused = || load_files(&dir, &cache_path).unwrap().1;
+        let plain = ParseOptions {
+            text: TextFormat::Plain,
+            ..ParseOptions::default()
+        };
+
+        assert_eq!(reused(), 0);
+        assert_eq!(reused(), 2);
+
+        assert_eq!(options::with_options(plain, reused), 0);
+        assert_eq!(options::with_options(plain, reused), 2);
+        assert_eq!(reused(), 0);
+
+        assert_eq!(dialect::with_dialect(Arc::new(Renamed), reused), 0);
+        assert_eq!(dialect::with_dialect(Arc::new(Renamed), reused), 2);
+        // Installing the default dialect explicitly parses the same way as not installing one
+        assert_eq!(dialect::with_dialect(Arc::new(UnionUniversity), reused), 0);
+        assert_eq!(reused(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
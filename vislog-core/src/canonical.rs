@@ -0,0 +1,126 @@
+//! Canonical JSON output, reproducible byte-for-byte across runs and platforms so that snapshots of
+//! it can be committed and diffed.
+//!
+//! Canonical JSON is compact, the keys of every object are sorted, and every string is in Unicode
+//! normalization form C with `\n` line endings. Arrays keep their order, which is the order things
+//! appear in the catalog.
+
+use serde::Serialize;
+use serde_json::Value;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{CourseDetails, Program};
+
+/// Serializes `value` as canonical JSON. Fails when `value` can't be represented as JSON, such as
+/// maps with non-string keys.
+pub fn to_canonical_json<T: Serialize + ?Sized>(value: &T) -> serde_json::Result<String> {
+    let value = serde_json::to_value(value)?;
+
+    let mut canonical = String::new();
+    write_value(&value, &mut canonical);
+
+    Ok(canonical)
+}
+
+impl Program {
+    /// The program as canonical JSON, see [to_canonical_json]
+    pub fn to_canonical_json(&self) -> String {
+        to_canonical_json(self).expect("Programs should always serialize to JSON")
+    }
+}
+
+impl CourseDetails {
+    /// The course as canonical JSON, see [to_canonical_json]
+    pub fn to_canonical_json(&self) -> String {
+        to_canonical_json(self).expect("Courses should always serialize to JSON")
+    }
+}
+
+fn write_value(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            // Keys are normalized before sorting so that the order doesn't depend on how they
+            // were encoded
+            let mut entries: Vec<(String, &Value)> = map
+                .iter()
+                .map(|(key, value)| (normalize(key), value))
+                .collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            out.push('{');
+            for (idx, (key, value)) in entries.into_iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                write_string(&key, out);
+                out.push(':');
+                write_value(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(values) => {
+            out.push('[');
+            for (idx, value) in values.iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                write_value(value, out);
+            }
+            out.push(']');
+        }
+        Value::String(s) => write_string(&normalize(s), out),
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push_str(&Value::from(s).to_string());
+}
+
+/// NFC with `\r\n` and `\r` line endings replaced by `\n`
+fn normalize(s: &str) -> String {
+    s.replace("\r\n", "\n").replace('\r', "\n").nfc().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn keys_are_sorted_and_output_is_compact() {
+        let value = json!({ "b": [3, 1, 2], "a": { "d": null, "c": true } });
+
+        assert_eq!(
+            to_canonical_json(&value).unwrap(),
+            r#"{"a":{"c":true,"d":null},"b":[3,1,2]}"#
+        );
+    }
+
+    #[test]
+    fn strings_are_normalized() {
+        // "é" as "e" followed by a combining acute accent
+        let value = json!({ "title": "Caf\u{65}\u{301}\r\nMenu\r" });
+
+        assert_eq!(
+            to_canonical_json(&value).unwrap(),
+            "{\"title\":\"Caf\u{e9}\\nMenu\\n\"}"
+        );
+    }
+
+    #[test]
+    fn program_output_is_reproducible() {
+        let json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+        let first: Program = serde_json::from_str(&json).unwrap();
+        let second: Program = serde_json::from_str(&json).unwrap();
+
+        let canonical = first.to_canonical_json();
+        assert_eq!(canonical, second.to_canonical_json());
+        assert!(canonical.starts_with(r#"{"bottom_content":"#));
+
+        // Canonical JSON is still the same program
+        let value: Value = serde_json::from_str(&canonical).unwrap();
+        assert_eq!(value, serde_json::to_value(&first).unwrap());
+    }
+}
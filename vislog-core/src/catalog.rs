@@ -0,0 +1,371 @@
+use std::{
+    collections::HashSet,
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    error::{self, VislogError},
+    metrics::{self, Counter},
+    shared::SharedModule,
+    CourseDetails, Program,
+};
+
+/// All the programs and courses of a catalog
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Catalog {
+    pub programs: Vec<Program>,
+    pub courses: Vec<CourseDetails>,
+    /// Modules that programs refer to with a [RequirementModule::Reference](crate::RequirementModule::Reference).
+    /// Empty until [Catalog::resolve_references] is called.
+    pub shared_modules: Vec<SharedModule>,
+}
+
+#[derive(Debug, Error)]
+pub enum CatalogError {
+    #[error("failed to read {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("failed to parse {path:?}: {source}")]
+    Json {
+        path: PathBuf,
+        #[source]
+        source: VislogError,
+    },
+    /// A single program or course inside of a catalog dump failed to parse
+    #[error("failed to parse entry {index} of {path:?}: {source}")]
+    Entry {
+        path: PathBuf,
+        index: usize,
+        #[source]
+        source: VislogError,
+    },
+    #[error("{path:?} is not a program, course or catalog dump")]
+    UnrecognizedFormat { path: PathBuf },
+}
+
+/// Everything that was successfully parsed out of a single file
+#[derive(Debug, Default)]
+pub(crate) struct ParsedFile {
+    pub(crate) programs: Vec<Program>,
+    pub(crate) courses: Vec<CourseDetails>,
+    pub(crate) errors: Vec<CatalogError>,
+    /// Counter of the kind of item the file holds, `None` when that isn't known
+    pub(crate) failures: Option<Counter>,
+}
+
+impl ParsedFile {
+    /// Records every program or course of the file that failed to parse in the [metrics]
+    pub(crate) fn record_failures(&self) {
+        let Some(counter) = self.failures else {
+            return;
+        };
+
+        for error in &self.errors {
+            if let CatalogError::Json { .. } | CatalogError::Entry { .. } = error {
+                metrics::global().increment(counter);
+            }
+        }
+    }
+}
+
+impl Catalog {
+    /// Parses every `.json` file directly inside of `path`, other than hidden files, into a single
+    /// `Catalog`.
+    ///
+    /// A file can either hold a single program, a single course, a JSON array of programs, or a
+    /// whole catalog dump as returned by the catalog API (`{"programs": {"program": [...]}}` or
+    /// `{"courses": {"course": [...]}}`).
+    ///
+    /// Files and entries that fail to parse do not stop the rest of the directory from being
+    /// parsed. Their errors are returned alongside the `Catalog` instead. Programs and courses
+    /// that appear in more than one file are only kept once, with files being read in order of
+    /// their path.
+    pub fn parse_dir(path: impl AsRef<Path>) -> Result<(Catalog, Vec<CatalogError>), CatalogError> {
+        let files = json_files_in(path.as_ref())?;
+        let parsed = files.iter().map(|file| parse_file(file));
+
+        Ok(merge(parsed))
+    }
+
+    /// Parses a single file in any of the formats accepted by [Catalog::parse_dir]. Errors,
+    /// including failing to read the file, are returned alongside whatever could be parsed.
+    pub fn parse_file(path: impl AsRef<Path>) -> (Catalog, Vec<CatalogError>) {
+        merge([parse_file(path.as_ref())])
+    }
+
+    /// Parses JSON in any of the formats accepted by [Catalog::parse_dir] that did not come from a
+    /// file, such as the body of a response. `source` stands in for the path of the file in
+    /// errors.
+    pub fn parse_json(source: impl AsRef<Path>, json: &str) -> (Catalog, Vec<CatalogError>) {
+        merge([parse_json(source.as_ref(), json)])
+    }
+
+    /// Same as [Catalog::parse_dir] but files are parsed across all the threads of the global
+    /// rayon thread pool. The resulting `Catalog` and errors are in the same order as the ones
+    /// returned by [Catalog::parse_dir], and the [dialect](crate::parsing::dialect) and
+    /// [options](crate::parsing::options) of the calling thread are used on every thread.
+    #[cfg(feature = "rayon")]
+    pub fn parse_dir_parallel(
+        path: impl AsRef<Path>,
+    ) -> Result<(Catalog, Vec<CatalogError>), CatalogError> {
+        use rayon::prelude::*;
+
+        use crate::parsing::{dialect, options};
+
+        let files = json_files_in(path.as_ref())?;
+        let current = dialect::current();
+        let options = options::current();
+        let parsed: Vec<ParsedFile> = files
+            .par_iter()
+            .map(|file| {
+                options::with_options(options, || match &current {
+                    Some(current) => dialect::with_dialect(current.clone(), || parse_file(file)),
+                    None => parse_file(file),
+                })
+            })
+            .collect();
+
+        Ok(merge(parsed))
+    }
+}
+
+/// Sorted paths of all the `.json` files directly inside of `dir`, leaving out hidden files
+pub(crate) fn json_files_in(dir: &Path) -> Result<Vec<PathBuf>, CatalogError> {
+    let to_catalog_error = |source| CatalogError::Io {
+        path: dir.to_owned(),
+        source,
+    };
+
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir).map_err(to_catalog_error)? {
+        let path = entry.map_err(to_catalog_error)?.path();
+        let is_hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+
+        if path.is_file() && !is_hidden && path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+fn parse_file(path: &Path) -> ParsedFile {
+    match std::fs::read_to_string(path) {
+        Ok(json) => parse_json(path, &json),
+        Err(source) => ParsedFile {
+            errors: vec![CatalogError::Io {
+                path: path.to_owned(),
+                source,
+            }],
+            ..Default::default()
+        },
+    }
+}
+
+pub(crate) fn parse_json(path: &Path, json: &str) -> ParsedFile {
+    let mut parsed = ParsedFile::default();
+
+    let value: Value = match serde_json::from_str(json) {
+        Ok(value) => value,
+        Err(source) => {
+            parsed.errors.push(CatalogError::Json {
+                path: path.to_owned(),
+                source: source.into(),
+            });
+            return parsed;
+        }
+    };
+
+    if let Some(programs) = value.pointer("/programs/program").and_then(Value::as_array) {
+        parsed.failures = Some(Counter::ProgramParseFailures);
+        parsed.programs = parse_entries(path, programs, &mut parsed.errors);
+    } else if let Some(courses) = value.pointer("/courses/course").and_then(Value::as_array) {
+        parsed.failures = Some(Counter::CourseParseFailures);
+        parsed.courses = parse_entries(path, courses, &mut parsed.errors);
+    } else if let Some(programs) = value.as_array() {
+        parsed.failures = Some(Counter::ProgramParseFailures);
+        parsed.programs = parse_entries(path, programs, &mut parsed.errors);
+    } else if value.get("subject_code").is_some() {
+        parsed.failures = Some(Counter::CourseParseFailures);
+        // Parse from the original string since the deserializers borrow from their input
+        match error::from_str::<CourseDetails>(json) {
+            Ok(course) => parsed.courses.push(course),
+            Err(source) => parsed.errors.push(CatalogError::Json {
+                path: path.to_owned(),
+                source,
+            }),
+        }
+    } else if value.get("title").is_some() {
+        parsed.failures = Some(Counter::ProgramParseFailures);
+        match error::from_str::<Program>(json) {
+            Ok(program) => parsed.programs.push(program),
+            Err(source) => parsed.errors.push(CatalogError::Json {
+                path: path.to_owned(),
+                source,
+            }),
+        }
+    } else {
+        parsed.errors.push(CatalogError::UnrecognizedFormat {
+            path: path.to_owned(),
+        });
+    }
+
+    parsed.record_failures();
+    parsed
+}
+
+fn parse_entries<T>(path: &Path, entries: &[Value], errors: &mut Vec<CatalogError>) -> Vec<T>
+where
+    T: for<'de> serde::Deserialize<'de>,
+{
+    let mut parsed = Vec::with_capacity(entries.len());
+
+    for (index, entry) in entries.iter().enumerate() {
+        // `Value`s can't be deserialized from directly since the deserializers borrow from their
+        // input, so go through a string instead
+        match error::from_str::<T>(&entry.to_string()) {
+            Ok(item) => parsed.push(item),
+            Err(source) => errors.push(CatalogError::Entry {
+                path: path.to_owned(),
+                index,
+                source,
+            }),
+        }
+    }
+
+    parsed
+}
+
+/// Merges the results of every parsed file into one [interned](crate::intern) `Catalog`, skipping
+/// programs and courses already seen in an earlier file
+pub(crate) fn merge<I>(parsed_files: I) -> (Catalog, Vec<CatalogError>)
+where
+    I: IntoIterator<Item = ParsedFile>,
+{
+    let mut catalog = Catalog::default();
+    let mut errors = vec![];
+
+    let mut seen_programs = HashSet::new();
+    let mut seen_courses = HashSet::new();
+
+    for parsed in parsed_files {
+        catalog.programs.extend(
+            parsed
+                .programs
+                .into_iter()
+                .filter(|program| seen_programs.insert(program.guid)),
+        );
+        catalog.courses.extend(
+            parsed
+                .courses
+                .into_iter()
+                .filter(|course| seen_courses.insert(course.guid)),
+        );
+        errors.extend(parsed.errors);
+    }
+    catalog.intern_courses();
+
+    (catalog, errors)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn can_parse_data_dir() {
+        let (catalog, errors) = Catalog::parse_dir("../data").expect("Failed to read data dir");
+
+        assert!(catalog.programs.len() >= 200);
+        assert!(catalog.courses.len() >= 1800);
+        assert!(catalog
+            .programs
+            .iter()
+            .any(|program| program.title == "Major in Computer Science—42 hours"));
+
+        // Programs in both their own file and `programs.json` are only kept once
+        let guids: HashSet<_> = catalog
+            .programs
+            .iter()
+            .map(|program| program.guid)
+            .collect();
+        assert_eq!(guids.len(), catalog.programs.len());
+
+        // `family_studies_major.json` is known to fail to parse
+        assert!(errors
+            .iter()
+            .any(|err| matches!(err, CatalogError::Json { path, .. } if path.ends_with("family_studies_major.json"))));
+    }
+
+    #[test]
+    fn error_when_dir_does_not_exist() {
+        assert!(matches!(
+            Catalog::parse_dir("../data/does-not-exist"),
+            Err(CatalogError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_json_reports_errors_against_the_source() {
+        let json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+
+        let (catalog, errors) = Catalog::parse_json("cs_major", &json);
+        assert_eq!(catalog.programs.len(), 1);
+        assert!(errors.is_empty());
+
+        let (catalog, errors) = Catalog::parse_json("input", "{}");
+        assert_eq!(catalog, Catalog::default());
+        assert!(
+            matches!(&errors[..], [CatalogError::UnrecognizedFormat { path }] if path.ends_with("input"))
+        );
+    }
+
+    #[test]
+    fn failures_are_counted() {
+        let metrics = metrics::global();
+        metrics.enable();
+        // Other tests parse at the same time, so the counters only ever grow by at least as much
+        let count = |counter| metrics.snapshot().counter(counter);
+        let (programs, courses) = (
+            count(Counter::ProgramParseFailures),
+            count(Counter::CourseParseFailures),
+        );
+
+        let (_, errors) = Catalog::parse_file("../data/family_studies_major.json");
+        assert_eq!(errors.len(), 1);
+        assert!(count(Counter::ProgramParseFailures) > programs);
+
+        let (_, errors) = Catalog::parse_json("input", r#"{"courses": {"course": [{}, {}]}}"#);
+        assert_eq!(errors.len(), 2);
+        assert!(count(Counter::CourseParseFailures) >= courses + 2);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_parsing_matches_sequential_parsing() {
+        let (sequential, sequential_errors) = Catalog::parse_dir("../data").unwrap();
+        let (parallel, parallel_errors) = Catalog::parse_dir_parallel("../data").unwrap();
+
+        assert_eq!(sequential, parallel);
+        assert_eq!(
+            sequential_errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            parallel_errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        );
+    }
+}
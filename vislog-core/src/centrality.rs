@@ -0,0 +1,250 @@
+//! How central every course of a catalog is to its [prerequisite graph](PrerequisiteGraph).
+//!
+//! Courses that many others require, directly or down a chain of prerequisites, hold students
+//! back the most when they aren't offered often enough. [course_centrality] measures this for
+//! every course of a catalog:
+//! - the in degree and out degree of the course, its direct prerequisites and the courses
+//!   requiring it directly
+//! - the number of courses downstream of it, which require it directly or through others
+//! - a gateway score, the [betweenness centrality] of the course, which is the number of
+//!   shortest chains of prerequisites between two other courses that go through it
+//!
+//! [write_csv] writes the metrics as a CSV that opens in spreadsheet software.
+//!
+//! [betweenness centrality]: https://en.wikipedia.org/wiki/Betweenness_centrality
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, centrality};
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//!
+//! let mut metrics = centrality::course_centrality(&catalog.courses);
+//! metrics.sort_by(|a, b| b.dependents.cmp(&a.dependents));
+//!
+//! let mut output = vec![];
+//! centrality::write_csv(&mut output, &metrics).unwrap();
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io::{self, Write},
+};
+
+use serde::Serialize;
+
+use crate::{
+    export::csv::write_record, graph::PrerequisiteGraph, parsing::guid::Guid, CourseDetails,
+};
+
+/// Column names of [write_csv], in order
+pub const HEADER: [&str; 8] = [
+    "guid",
+    "subject",
+    "number",
+    "name",
+    "in_degree",
+    "out_degree",
+    "dependents",
+    "gateway_score",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CourseCentrality {
+    pub guid: Guid,
+    pub subject_code: String,
+    pub number: String,
+    pub name: String,
+    /// Number of direct prerequisites of the course
+    pub in_degree: usize,
+    /// Number of courses requiring the course directly
+    pub out_degree: usize,
+    /// Number of courses requiring the course directly or through other courses
+    pub dependents: usize,
+    /// Number of shortest chains of prerequisites between two other courses going through the
+    /// course. When there are several shortest chains between two courses, each one counts for
+    /// its share.
+    pub gateway_score: f64,
+}
+
+impl CourseCentrality {
+    /// Values of the metrics in the order of [HEADER]
+    pub fn fields(&self) -> [String; 8] {
+        [
+            self.guid.to_string(),
+            self.subject_code.clone(),
+            self.number.clone(),
+            self.name.clone(),
+            self.in_degree.to_string(),
+            self.out_degree.to_string(),
+            self.dependents.to_string(),
+            self.gateway_score.to_string(),
+        ]
+    }
+}
+
+/// Metrics of every course, in the order of the `courses`. Prerequisites that aren't part of the
+/// `courses` still connect the courses requiring them, but don't have metrics of their own.
+pub fn course_centrality(courses: &[CourseDetails]) -> Vec<CourseCentrality> {
+    let graph = PrerequisiteGraph::from_course_details(courses);
+
+    let mut guids: Vec<Guid> = vec![];
+    let mut seen = HashSet::new();
+    for course in courses {
+        for guid in [Some(course.guid), course.prerequisite]
+            .into_iter()
+            .flatten()
+        {
+            if seen.insert(guid) {
+                guids.push(guid);
+            }
+        }
+    }
+
+    let (dependents, gateway_scores) = brandes(&guids, &graph);
+
+    courses
+        .iter()
+        .map(|course| CourseCentrality {
+            guid: course.guid,
+            subject_code: course.subject_code.to_string(),
+            number: course.number.clone(),
+            name: course.name.clone(),
+            in_degree: graph.prerequisites_of(&course.guid).len(),
+            out_degree: graph.unlocked_by(&course.guid).len(),
+            dependents: dependents[&course.guid],
+            gateway_score: gateway_scores[&course.guid],
+        })
+        .collect()
+}
+
+/// Number of courses reachable from every course, and its betweenness centrality, with
+/// [Brandes' algorithm](https://doi.org/10.1080/0022250X.2001.9990249) over the edges from
+/// prerequisites to the courses requiring them
+fn brandes(
+    guids: &[Guid],
+    graph: &PrerequisiteGraph,
+) -> (HashMap<Guid, usize>, HashMap<Guid, f64>) {
+    let mut dependents = HashMap::new();
+    let mut scores: HashMap<Guid, f64> = guids.iter().map(|guid| (*guid, 0.0)).collect();
+
+    for source in guids {
+        // Courses in the order they are reached, with the number of shortest chains to them
+        let mut order = vec![];
+        let mut paths: HashMap<Guid, f64> = HashMap::from([(*source, 1.0)]);
+        let mut distances: HashMap<Guid, usize> = HashMap::from([(*source, 0)]);
+        let mut predecessors: HashMap<Guid, Vec<Guid>> = HashMap::new();
+
+        let mut queue = VecDeque::from([*source]);
+        while let Some(guid) = queue.pop_front() {
+            order.push(guid);
+            let distance = distances[&guid];
+
+            for next in graph.unlocked_by(&guid) {
+                if !distances.contains_key(next) {
+                    distances.insert(*next, distance + 1);
+                    queue.push_back(*next);
+                }
+                if distances[next] == distance + 1 {
+                    *paths.entry(*next).or_default() += paths[&guid];
+                    predecessors.entry(*next).or_default().push(guid);
+                }
+            }
+        }
+
+        dependents.insert(*source, order.len() - 1);
+
+        // Share of the shortest chains from the source through every course
+        let mut shares: HashMap<Guid, f64> = HashMap::new();
+        for guid in order.iter().rev() {
+            let share = shares.get(guid).copied().unwrap_or_default();
+            for predecessor in predecessors.get(guid).into_iter().flatten() {
+                *shares.entry(*predecessor).or_default() +=
+                    paths[predecessor] / paths[guid] * (1.0 + share);
+            }
+            if guid != source {
+                *scores.entry(*guid).or_default() += share;
+            }
+        }
+    }
+
+    (dependents, scores)
+}
+
+/// Writes the [HEADER] followed by the metrics of every course
+pub fn write_csv<W: Write>(mut writer: W, metrics: &[CourseCentrality]) -> io::Result<()> {
+    write_record(&mut writer, HEADER)?;
+    for course in metrics {
+        write_record(&mut writer, course.fields())?;
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::catalog::Catalog;
+
+    fn course(n: u8, prerequisite: Option<u8>) -> CourseDetails {
+        let guid =
+            |n: u8| Guid::try_from(format!("00000000-0000-0000-0000-0000000000{n:02}").as_str());
+        CourseDetails {
+            url: Default::default(),
+            guid: guid(n).unwrap(),
+            path: Default::default(),
+            subject_code: "CSC".into(),
+            subject_name: None,
+            number: format!("{n}00"),
+            name: String::new(),
+            credits_min: 3,
+            credits_max: None,
+            description: String::new(),
+            prerequisite_narrative: None,
+            prerequisite: prerequisite.map(|n| guid(n).unwrap()),
+            corequisite_narrative: None,
+            corequisite: None,
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    fn gateways_sit_between_other_courses() {
+        // 1 -> 2 -> 3 and 2 -> 4
+        let courses = [
+            course(1, None),
+            course(2, Some(1)),
+            course(3, Some(2)),
+            course(4, Some(2)),
+        ];
+        let metrics = course_centrality(&courses);
+
+        let summary: Vec<_> = metrics
+            .iter()
+            .map(|m| (m.in_degree, m.out_degree, m.dependents, m.gateway_score))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (0, 1, 3, 0.0),
+                (1, 2, 2, 2.0),
+                (1, 0, 0, 0.0),
+                (1, 0, 0, 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn metrics_of_the_catalog_are_written_as_csv() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let metrics = course_centrality(&catalog.courses);
+        assert_eq!(metrics.len(), catalog.courses.len());
+        assert!(metrics.iter().any(|course| course.dependents > 0));
+
+        let mut output = vec![];
+        write_csv(&mut output, &metrics).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with(&format!("{}\r\n", HEADER.join(","))));
+        assert_eq!(output.matches("\r\n").count(), metrics.len() + 1);
+    }
+}
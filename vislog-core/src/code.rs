@@ -0,0 +1,233 @@
+//! Course codes broken down into their parts.
+//!
+//! The catalog API gives the code of a course as a `subject_code`, such as `"CSC"`, and a
+//! `number`, such as `"115"`. Numbers can have a suffix for labs (`"115L"`), honors sections
+//! (`"115H"`) or other variants of a course (`"150IG"`), and courses cross-listed under other
+//! variants list them after slashes (`"129K/P"`). [CourseCode] parses all of them so that codes
+//! can be compared by their numeric level instead of as strings.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, code::CourseCode};
+//! let code: CourseCode = "CSC 115L".parse().unwrap();
+//! assert_eq!(code.level(), 100);
+//! assert!(code.is_lab());
+//!
+//! let (catalog, _errors) = Catalog::parse_file("../data/courses.json");
+//! let upper_level = catalog
+//!     .courses
+//!     .iter()
+//!     .filter(|course| course.code().is_ok_and(|code| code.level() >= 300));
+//! # assert!(upper_level.count() > 0);
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{Course, CourseDetails};
+
+/// The code of a course. Ordered by subject, then number, then suffix, so that `CSC 115` comes
+/// before `CSC 115L`, which comes before `CSC 125`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CourseCode {
+    /// Ex: "CSC"
+    pub subject: String,
+    /// Numeric part of the number. Ex: 115
+    pub number: u16,
+    /// Letters following the numeric part. Ex: "L" for labs, "H" for honors sections
+    pub suffix: Option<String>,
+    /// Variants the course is cross-listed under, in the order they follow the number. Ex: `["P"]`
+    /// for "129K/P"
+    pub cross_listings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum CourseCodeError {
+    #[error("course codes need a subject code")]
+    MissingSubject,
+    #[error("course number {0:?} doesn't start with digits")]
+    InvalidNumber(String),
+    #[error("course number {0:?} has a suffix that isn't letters and digits")]
+    InvalidSuffix(String),
+}
+
+impl CourseCode {
+    /// Parses the `subject_code` and `number` of a course, as the catalog API gives them
+    pub fn new(subject_code: &str, number: &str) -> Result<Self, CourseCodeError> {
+        let subject = subject_code.trim();
+        if subject.is_empty() {
+            return Err(CourseCodeError::MissingSubject);
+        }
+
+        let number_str = number.trim();
+        let mut parts = number_str.split('/');
+        let first = parts.next().unwrap_or_default();
+        let digits = first
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(first.len());
+        let number = first[..digits]
+            .parse()
+            .map_err(|_| CourseCodeError::InvalidNumber(number_str.to_owned()))?;
+
+        let is_suffix = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric());
+        let suffix = match &first[digits..] {
+            "" => None,
+            suffix if is_suffix(suffix) => Some(suffix.to_owned()),
+            _ => return Err(CourseCodeError::InvalidSuffix(number_str.to_owned())),
+        };
+        let cross_listings = parts
+            .map(|part| match is_suffix(part) {
+                true => Ok(part.to_owned()),
+                false => Err(CourseCodeError::InvalidSuffix(number_str.to_owned())),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            subject: subject.to_owned(),
+            number,
+            suffix,
+            cross_listings,
+        })
+    }
+
+    /// Hundreds of the number, so "CSC 315L" is a 300 level course
+    pub fn level(&self) -> u16 {
+        self.number / 100 * 100
+    }
+
+    pub fn is_lab(&self) -> bool {
+        self.suffix.as_deref() == Some("L")
+    }
+
+    pub fn is_honors(&self) -> bool {
+        self.suffix.as_deref() == Some("H")
+    }
+}
+
+/// A subject code and a number separated by whitespace. Ex: "CSC 115L"
+impl FromStr for CourseCode {
+    type Err = CourseCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (subject, number) = s
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or(CourseCodeError::MissingSubject)?;
+
+        Self::new(subject, number)
+    }
+}
+
+/// Numbers have at least three digits, like in the catalog. Ex: "MUS 129K/P", "MUS 000"
+impl fmt::Display for CourseCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:03}", self.subject, self.number)?;
+        if let Some(suffix) = &self.suffix {
+            write!(f, "{suffix}")?;
+        }
+        for cross_listing in &self.cross_listings {
+            write!(f, "/{cross_listing}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Course {
+    pub fn code(&self) -> Result<CourseCode, CourseCodeError> {
+        CourseCode::new(&self.subject_code, &self.number)
+    }
+
+    /// Subject code and number as written in the catalog. Ex: "CSC 310". Unlike
+    /// [code](Course::code), numbers aren't parsed so it can't fail.
+    pub fn code_label(&self) -> String {
+        format!("{} {}", self.subject_code, self.number)
+    }
+}
+
+impl CourseDetails {
+    pub fn code(&self) -> Result<CourseCode, CourseCodeError> {
+        CourseCode::new(&self.subject_code, &self.number)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn code(s: &str) -> CourseCode {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn codes_are_parsed_into_their_parts() {
+        assert_eq!(
+            code("MUS 129IG/P"),
+            CourseCode {
+                subject: "MUS".to_owned(),
+                number: 129,
+                suffix: Some("IG".to_owned()),
+                cross_listings: vec!["P".to_owned()],
+            }
+        );
+        assert_eq!(code("CSC 115").suffix, None);
+        assert!(code("BIO 221L").is_lab());
+        assert!(code("ENG 111H").is_honors());
+        assert_eq!(code("CSC 495").level(), 400);
+
+        assert_eq!(
+            "CSC".parse::<CourseCode>(),
+            Err(CourseCodeError::MissingSubject)
+        );
+        assert_eq!(
+            CourseCode::new("CSC", "L115"),
+            Err(CourseCodeError::InvalidNumber("L115".to_owned()))
+        );
+        assert_eq!(
+            CourseCode::new("CSC", "115-L"),
+            Err(CourseCodeError::InvalidSuffix("115-L".to_owned()))
+        );
+        assert_eq!(
+            CourseCode::new("MUS", "150C/"),
+            Err(CourseCodeError::InvalidSuffix("150C/".to_owned()))
+        );
+    }
+
+    #[test]
+    fn suffixes_dont_change_the_number() {
+        assert_eq!(code("MUS 250S").number, 250);
+        assert_eq!(code("CSC 115").number, 115);
+        assert!(CourseCode::new("MUS", "S").is_err());
+    }
+
+    #[test]
+    fn codes_are_ordered_by_number() {
+        let mut codes = [
+            code("CSC 125"),
+            code("CSC 115L"),
+            code("CSC 99"),
+            code("CSC 115"),
+        ];
+        codes.sort();
+        let codes: Vec<String> = codes.iter().map(CourseCode::to_string).collect();
+        assert_eq!(codes, ["CSC 099", "CSC 115", "CSC 115L", "CSC 125"]);
+    }
+
+    #[test]
+    fn every_course_of_the_catalog_has_a_code() {
+        let (catalog, _) = crate::catalog::Catalog::parse_dir("../data").unwrap();
+        for course in &catalog.courses {
+            let code = course.code().unwrap();
+            assert_eq!(code.subject, &*course.subject_code);
+            assert_eq!(code.to_string().parse(), Ok(code));
+        }
+        assert!(catalog
+            .programs
+            .iter()
+            .flat_map(|program| program.iter_courses())
+            .all(|course| course.code().is_ok()));
+    }
+}
@@ -0,0 +1,298 @@
+//! Several programs taken together, such as a double major or a major with a minor.
+//!
+//! [Catalog::combine] merges the requirement trees of the programs and finds the courses listed
+//! by more than one of them. Depending on the [DoubleCounting] rules of the institution, the
+//! credits of those courses count toward several programs at once, which is how much adding a
+//! minor saves over taking it on its own.
+//!
+//! Credits are estimates in the same way as the ones of [ProgramStats](crate::stats::ProgramStats),
+//! and assume that every shared course is taken, including the ones that are only one of several
+//! options in a program.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, combine::DoubleCounting};
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//! let major = catalog.programs.iter().find(|p| p.title.starts_with("Major in Computer Science"));
+//! let minor = catalog.programs.iter().find(|p| p.title.starts_with("Minor in Mathematics—"));
+//!
+//! let combined = catalog.combine([major.unwrap(), minor.unwrap()], DoubleCounting::MaxCredits(6));
+//!
+//! println!("Adding the minor takes {} more hours", combined.credits.added.min);
+//! ```
+
+use std::{collections::HashMap, ops::Add};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    catalog::Catalog, parsing::guid::Guid, stats::CreditRange, Course, Program, RequirementModule,
+    Requirements,
+};
+
+/// How many of the credits of courses shared between programs count toward more than one of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum DoubleCounting {
+    /// Shared courses count toward every program listing them
+    Unlimited,
+    /// Every program needs courses of its own
+    Forbidden,
+    /// At most this many credits of shared courses count toward more than one program
+    MaxCredits(u32),
+}
+
+/// Programs taken together
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CombinedProgram {
+    pub programs: Vec<CombinedPart>,
+    pub rules: DoubleCounting,
+    /// Requirement modules of every program in order. The `modules` of each part tell which
+    /// program they come from.
+    pub requirements: Vec<RequirementModule>,
+    /// Courses listed by more than one program, in the order they first appear
+    pub overlaps: Vec<Overlap>,
+    pub credits: CombinedCredits,
+}
+
+/// One of the programs of a [CombinedProgram]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CombinedPart {
+    pub guid: Guid,
+    pub title: String,
+    /// Credits of the program taken on its own
+    pub credits: CreditRange,
+    /// Number of requirement modules of the program
+    pub modules: usize,
+}
+
+/// A course listed by more than one program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Overlap {
+    pub guid: Guid,
+    pub subject_code: String,
+    pub number: String,
+    pub name: Option<String>,
+    /// Minimum credits of the course, preferably as given by the course catalog
+    pub credits: u8,
+    /// GUIDs of the programs listing the course
+    pub programs: Vec<Guid>,
+    /// Whether every program listing the course requires it, instead of offering it as one of
+    /// several options
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CombinedCredits {
+    /// Sum of the credits of the programs taken on their own
+    pub separate: CreditRange,
+    /// Credits of the shared courses, counted once for every program beyond the first listing them
+    pub shared: u32,
+    /// Part of the `shared` credits that the rules allow to count toward more than one program
+    pub double_counted: u32,
+    /// Credits of taking all the programs together
+    pub combined: CreditRange,
+    /// Credits the programs after the first add to it
+    pub added: CreditRange,
+}
+
+impl Catalog {
+    /// Combines the `programs`, the first of which is the one the others are added to
+    pub fn combine<'a>(
+        &self,
+        programs: impl IntoIterator<Item = &'a Program>,
+        rules: DoubleCounting,
+    ) -> CombinedProgram {
+        let programs: Vec<&Program> = programs.into_iter().collect();
+        let catalog_credits: HashMap<Guid, u8> = self
+            .courses
+            .iter()
+            .map(|course| (course.guid, course.credits_min))
+            .collect();
+
+        let mut overlaps: Vec<Overlap> = vec![];
+        let mut indices: HashMap<Guid, usize> = HashMap::new();
+        for program in &programs {
+            // Distinct courses of the program, required if any of their listings isn't a choice
+            let mut courses: Vec<(&Course, bool)> = vec![];
+            let mut positions: HashMap<Guid, usize> = HashMap::new();
+            for flattened in program.flattened_courses() {
+                match positions.get(&flattened.course.guid) {
+                    Some(i) => courses[*i].1 |= !flattened.is_choice,
+                    None => {
+                        positions.insert(flattened.course.guid, courses.len());
+                        courses.push((flattened.course, !flattened.is_choice));
+                    }
+                }
+            }
+
+            for (course, required) in courses {
+                let index = *indices.entry(course.guid).or_insert_with(|| {
+                    overlaps.push(Overlap {
+                        guid: course.guid,
+                        subject_code: course.subject_code.to_string(),
+                        number: course.number.clone(),
+                        name: course.name.clone(),
+                        credits: catalog_credits
+                            .get(&course.guid)
+                            .copied()
+                            .unwrap_or(course.credits.0),
+                        programs: vec![],
+                        required: true,
+                    });
+                    overlaps.len() - 1
+                });
+
+                let overlap = &mut overlaps[index];
+                overlap.programs.push(program.guid);
+                overlap.required &= required;
+            }
+        }
+        overlaps.retain(|overlap| overlap.programs.len() > 1);
+
+        let parts: Vec<CombinedPart> = programs
+            .iter()
+            .map(|program| CombinedPart {
+                guid: program.guid,
+                title: program.title.clone(),
+                credits: program.stats().credits,
+                modules: program
+                    .requirements
+                    .as_ref()
+                    .map_or(0, |requirements| requirements.modules().len()),
+            })
+            .collect();
+
+        let separate = parts
+            .iter()
+            .map(|part| part.credits)
+            .fold(CreditRange::default(), Add::add);
+        let shared = overlaps
+            .iter()
+            .map(|overlap| overlap.credits as u32 * (overlap.programs.len() as u32 - 1))
+            .sum();
+        let double_counted = match rules {
+            DoubleCounting::Unlimited => shared,
+            DoubleCounting::Forbidden => 0,
+            DoubleCounting::MaxCredits(max) => shared.min(max),
+        };
+        let combined = CreditRange {
+            min: separate.min.saturating_sub(double_counted),
+            max: separate.max.saturating_sub(double_counted),
+        };
+        let first = parts.first().map(|part| part.credits).unwrap_or_default();
+
+        CombinedProgram {
+            requirements: programs
+                .iter()
+                .flat_map(|program| program.requirements.iter().flat_map(Requirements::modules))
+                .cloned()
+                .collect(),
+            programs: parts,
+            rules,
+            overlaps,
+            credits: CombinedCredits {
+                separate,
+                shared,
+                double_counted,
+                combined,
+                added: CreditRange {
+                    min: combined.min.saturating_sub(first.min),
+                    max: combined.max.saturating_sub(first.max),
+                },
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn programs(catalog: &Catalog, prefixes: &[&str]) -> Vec<Program> {
+        prefixes
+            .iter()
+            .map(|prefix| {
+                catalog
+                    .programs
+                    .iter()
+                    .find(|program| program.title.starts_with(prefix))
+                    .cloned()
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn courses_of_both_programs_are_overlaps() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let programs = programs(
+            &catalog,
+            &[
+                "Major in Computer Science",
+                "Minor in Computer Information Systems",
+            ],
+        );
+
+        let combined = catalog.combine(&programs, DoubleCounting::Unlimited);
+        assert_eq!(combined.programs.len(), 2);
+        assert_eq!(
+            combined.requirements.len(),
+            combined
+                .programs
+                .iter()
+                .map(|part| part.modules)
+                .sum::<usize>()
+        );
+
+        let major: HashSet<Guid> = programs[0].iter_courses().map(|c| c.guid).collect();
+        let minor: HashSet<Guid> = programs[1].iter_courses().map(|c| c.guid).collect();
+        let overlaps: HashSet<Guid> = combined.overlaps.iter().map(|o| o.guid).collect();
+        assert!(!overlaps.is_empty());
+        assert_eq!(overlaps, &major & &minor);
+        assert!(combined
+            .overlaps
+            .iter()
+            .all(|overlap| overlap.programs == [programs[0].guid, programs[1].guid]));
+
+        let credits = combined.credits;
+        assert_eq!(
+            credits.shared,
+            combined
+                .overlaps
+                .iter()
+                .map(|o| o.credits as u32)
+                .sum::<u32>()
+        );
+        assert_eq!(credits.double_counted, credits.shared);
+        assert_eq!(credits.combined.min, credits.separate.min - credits.shared);
+        assert_eq!(
+            credits.added.min,
+            credits.combined.min - programs[0].stats().credits.min
+        );
+    }
+
+    #[test]
+    fn rules_limit_the_double_counted_credits() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let programs = programs(
+            &catalog,
+            &["Major in Computer Science", "Minor in Computer Science"],
+        );
+
+        let forbidden = catalog.combine(&programs, DoubleCounting::Forbidden);
+        assert_eq!(forbidden.credits.double_counted, 0);
+        assert_eq!(forbidden.credits.combined, forbidden.credits.separate);
+        assert_eq!(forbidden.credits.added, programs[1].stats().credits);
+
+        let limited = catalog.combine(&programs, DoubleCounting::MaxCredits(6));
+        assert!(limited.credits.shared > 6);
+        assert_eq!(limited.credits.double_counted, 6);
+        assert_eq!(
+            limited.credits.combined.min,
+            limited.credits.separate.min - 6
+        );
+    }
+}
@@ -0,0 +1,526 @@
+//! Grade and GPA conditions that catalogs only spell out in prose, such as "with a grade of C or
+//! better" or "minimum 2.5 GPA in the major".
+//!
+//! [Constraints::from_text] is run over the title and narrative of every [Requirement] as it is
+//! parsed, and the result is kept in its `constraints` field for the audit to use.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::constraints::{Constraints, Gpa, Grade};
+//! let constraints = Constraints::from_text("Each course must be passed with a grade of C or better.");
+//! assert_eq!(constraints.min_grade, Some(Grade::C));
+//!
+//! let constraints = Constraints::from_text("Students must have a minimum 2.5 GPA in the major.");
+//! assert_eq!(constraints.min_gpa, Some(Gpa::from_hundredths(250)));
+//! ```
+//!
+//! [Requirement]: crate::Requirement
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+/// Grade and GPA conditions of a requirement
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Constraints {
+    /// Lowest grade a course has to be completed with to count towards the requirement
+    pub min_grade: Option<Grade>,
+    /// Lowest GPA needed over the courses of the requirement
+    pub min_gpa: Option<Gpa>,
+}
+
+impl Constraints {
+    pub fn is_empty(&self) -> bool {
+        self.min_grade.is_none() && self.min_gpa.is_none()
+    }
+
+    /// Extracts the constraints written out in `text`, which may contain HTML. Parts of the text
+    /// that don't clearly state a grade or GPA are ignored, so course levels such as "MAT 111 or
+    /// higher" aren't mistaken for grades.
+    pub fn from_text(text: &str) -> Self {
+        let words = words(text);
+
+        Self {
+            min_grade: min_grade(&words),
+            min_gpa: min_gpa(&words),
+        }
+    }
+
+    /// Constraints found in either `self` or `other`, keeping the stricter one when both have it
+    pub fn merge(self, other: Constraints) -> Self {
+        Self {
+            min_grade: self.min_grade.max(other.min_grade),
+            min_gpa: self.min_gpa.max(other.min_gpa),
+        }
+    }
+}
+
+/// Letter grade, ordered from lowest to highest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Grade {
+    F,
+    #[serde(rename = "D-")]
+    DMinus,
+    D,
+    #[serde(rename = "D+")]
+    DPlus,
+    #[serde(rename = "C-")]
+    CMinus,
+    C,
+    #[serde(rename = "C+")]
+    CPlus,
+    #[serde(rename = "B-")]
+    BMinus,
+    B,
+    #[serde(rename = "B+")]
+    BPlus,
+    #[serde(rename = "A-")]
+    AMinus,
+    A,
+    #[serde(rename = "A+")]
+    APlus,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("{0:?} is not a letter grade")]
+pub struct GradeParsingError(String);
+
+impl Grade {
+    /// Grade points on a 4.0 scale
+    pub fn points(self) -> Gpa {
+        let hundredths = match self {
+            Grade::APlus | Grade::A => 400,
+            Grade::AMinus => 370,
+            Grade::BPlus => 330,
+            Grade::B => 300,
+            Grade::BMinus => 270,
+            Grade::CPlus => 230,
+            Grade::C => 200,
+            Grade::CMinus => 170,
+            Grade::DPlus => 130,
+            Grade::D => 100,
+            Grade::DMinus => 70,
+            Grade::F => 0,
+        };
+
+        Gpa(hundredths)
+    }
+}
+
+impl FromStr for Grade {
+    type Err = GradeParsingError;
+
+    /// Parses letter grades such as "B", "c+" or "A-"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let grade = match s.trim().to_uppercase().as_str() {
+            "A+" => Grade::APlus,
+            "A" => Grade::A,
+            "A-" => Grade::AMinus,
+            "B+" => Grade::BPlus,
+            "B" => Grade::B,
+            "B-" => Grade::BMinus,
+            "C+" => Grade::CPlus,
+            "C" => Grade::C,
+            "C-" => Grade::CMinus,
+            "D+" => Grade::DPlus,
+            "D" => Grade::D,
+            "D-" => Grade::DMinus,
+            "F" => Grade::F,
+            _ => return Err(GradeParsingError(s.to_owned())),
+        };
+
+        Ok(grade)
+    }
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let grade = match self {
+            Grade::APlus => "A+",
+            Grade::A => "A",
+            Grade::AMinus => "A-",
+            Grade::BPlus => "B+",
+            Grade::B => "B",
+            Grade::BMinus => "B-",
+            Grade::CPlus => "C+",
+            Grade::C => "C",
+            Grade::CMinus => "C-",
+            Grade::DPlus => "D+",
+            Grade::D => "D",
+            Grade::DMinus => "D-",
+            Grade::F => "F",
+        };
+
+        f.write_str(grade)
+    }
+}
+
+/// Grade recorded for an attempt of a course: a letter grade, a pass or fail without a letter, or
+/// a withdrawal. Serialized as a string such as `"B+"`, `"P"`, `"NP"` or `"W"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CourseGrade {
+    Letter(Grade),
+    Pass,
+    Fail,
+    Withdrawn,
+}
+
+impl CourseGrade {
+    pub fn letter(self) -> Option<Grade> {
+        match self {
+            CourseGrade::Letter(grade) => Some(grade),
+            _ => None,
+        }
+    }
+
+    /// Whether the attempt earns credit, given the lowest letter grade that passes a course
+    pub fn passes(self, passing_grade: Grade) -> bool {
+        match self {
+            CourseGrade::Letter(grade) => grade >= passing_grade,
+            CourseGrade::Pass => true,
+            CourseGrade::Fail | CourseGrade::Withdrawn => false,
+        }
+    }
+}
+
+impl From<Grade> for CourseGrade {
+    fn from(grade: Grade) -> Self {
+        CourseGrade::Letter(grade)
+    }
+}
+
+impl FromStr for CourseGrade {
+    type Err = GradeParsingError;
+
+    /// Parses letter grades, or the codes registrars use for passing ("P", "S" or "CR"), failing
+    /// ("NP", "U" or "NC") and withdrawing ("W", "WP", "WF" or "WD") without a letter grade
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(grade) = s.parse() {
+            return Ok(CourseGrade::Letter(grade));
+        }
+
+        let grade = match s.trim().to_uppercase().as_str() {
+            "P" | "S" | "CR" | "PASS" => CourseGrade::Pass,
+            "NP" | "U" | "NC" | "FAIL" => CourseGrade::Fail,
+            "W" | "WP" | "WF" | "WD" => CourseGrade::Withdrawn,
+            _ => return Err(GradeParsingError(s.to_owned())),
+        };
+
+        Ok(grade)
+    }
+}
+
+impl fmt::Display for CourseGrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CourseGrade::Letter(grade) => write!(f, "{grade}"),
+            CourseGrade::Pass => f.write_str("P"),
+            CourseGrade::Fail => f.write_str("NP"),
+            CourseGrade::Withdrawn => f.write_str("W"),
+        }
+    }
+}
+
+impl Serialize for CourseGrade {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CourseGrade {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let grade = String::deserialize(deserializer)?;
+        grade.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for CourseGrade {
+    fn schema_name() -> String {
+        "CourseGrade".to_owned()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
+/// Grade point average, kept in hundredths of a point so that it can be compared and hashed
+/// exactly. Serialized as a number such as `3.5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Gpa(u16);
+
+impl Gpa {
+    pub fn from_hundredths(hundredths: u16) -> Self {
+        Self(hundredths)
+    }
+
+    pub fn hundredths(self) -> u16 {
+        self.0
+    }
+
+    /// Average of the grade points of `grades`, weighted by the credits each grade was earned
+    /// for. `None` if there are no credits to average over.
+    pub fn weighted(grades: impl IntoIterator<Item = (Grade, u8)>) -> Option<Self> {
+        let (points, credits) =
+            grades
+                .into_iter()
+                .fold((0u32, 0u32), |(points, credits), (grade, grade_credits)| {
+                    (
+                        points + grade.points().0 as u32 * grade_credits as u32,
+                        credits + grade_credits as u32,
+                    )
+                });
+
+        (credits > 0).then(|| Self((points / credits) as u16))
+    }
+
+    fn from_f64(gpa: f64) -> Option<Self> {
+        (0.0..=5.0)
+            .contains(&gpa)
+            .then(|| Self((gpa * 100.0).round() as u16))
+    }
+}
+
+impl fmt::Display for Gpa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+impl Serialize for Gpa {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.0 as f64 / 100.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Gpa {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let gpa = f64::deserialize(deserializer)?;
+
+        Gpa::from_f64(gpa)
+            .ok_or_else(|| serde::de::Error::custom(format!("{gpa} is not a GPA between 0 and 5")))
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for Gpa {
+    fn schema_name() -> String {
+        "Gpa".to_owned()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        f64::json_schema(gen)
+    }
+}
+
+/// Lowercased words of `text` with HTML tags, entities and surrounding punctuation removed.
+/// Signs stay attached so that grades like "C-" and numbers like "2.5" are kept whole.
+fn words(text: &str) -> Vec<String> {
+    let mut plain = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                plain.push(' ');
+            }
+            c if !in_tag => plain.push(c),
+            _ => {}
+        }
+    }
+
+    let plain = plain
+        .replace("&quot;", " ")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+
+    plain
+        .split(|c: char| c.is_whitespace() || c == '/' || c == '—')
+        .map(|word| {
+            word.trim_matches(|c: char| !(c.is_alphanumeric() || c == '+' || c == '-'))
+                .trim_start_matches(['+', '-'])
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Finds "grade of C", "grade of at least a C", "C or better" and their variations
+fn min_grade(words: &[String]) -> Option<Grade> {
+    let after_grade = words
+        .iter()
+        .enumerate()
+        .filter(|(_, word)| word.as_str() == "grade")
+        .find_map(|(i, _)| {
+            words[i + 1..]
+                .iter()
+                .take(5)
+                .take_while(|word| is_filler(word) || grade(word).is_some())
+                .filter(|word| !is_filler(word))
+                .filter_map(|word| grade(word))
+                .last()
+        });
+
+    let before_or_better = words.windows(3).find_map(|window| {
+        let is_or_better =
+            window[1] == "or" && matches!(window[2].as_str(), "better" | "higher" | "above");
+        if is_or_better {
+            grade(&window[0])
+        } else {
+            None
+        }
+    });
+
+    after_grade.or(before_or_better)
+}
+
+/// Words that can come between "grade" and the grade itself. "a" is always read as an article
+/// there, so "grade of A" isn't found, but "A or better" still is.
+fn is_filler(word: &str) -> bool {
+    matches!(word, "of" | "at" | "least" | "a" | "an")
+}
+
+/// Grades in prose are single letters, optionally signed
+fn grade(word: &str) -> Option<Grade> {
+    if word.len() > 2 {
+        return None;
+    }
+
+    word.parse().ok()
+}
+
+/// Finds a GPA within a few words of "GPA", such as "minimum GPA of 3.5" or "cumulative 3.5 GPA"
+fn min_gpa(words: &[String]) -> Option<Gpa> {
+    let i = words.iter().position(|word| word == "gpa")?;
+
+    let after = words[i + 1..].iter().take(3);
+    let before = words[i.saturating_sub(3)..i].iter().rev();
+
+    after.chain(before).find_map(|word| {
+        if !word.contains('.') {
+            return None;
+        }
+
+        word.parse().ok().and_then(Gpa::from_f64)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn grades_are_found_in_narratives() {
+        let cases = [
+            ("with a grade of C or better", Some(Grade::C)),
+            (
+                "must earn a &quot;C&quot; or higher on natural science",
+                Some(Grade::C),
+            ),
+            ("Must earn a C or higher to apply to majors", Some(Grade::C)),
+            (
+                "completed with a grade of B (3.0 on a 4.0 scale) or better",
+                Some(Grade::B),
+            ),
+            ("minimum grade of C- in each course", Some(Grade::CMinus)),
+            (
+                "earn a final grade of at least a B in HIS 497",
+                Some(Grade::B),
+            ),
+            ("Take 2 MAT courses: MAT 111 or higher.", None),
+            ("The remaining must be 205 or higher", None),
+            (
+                "<p>CSC 105 and CSC 106 may be taken in place of CSC 115.</p>",
+                None,
+            ),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(Constraints::from_text(text).min_grade, expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn gpas_are_found_in_narratives() {
+        let cases = [
+            (
+                "Minimum GPA of 3.5 in all required education courses",
+                Some(350),
+            ),
+            ("Students must maintain a 3.50 GPA overall.", Some(350)),
+            ("minimum BIO GPA of 2.0.", Some(200)),
+            ("who have a cumulative GPA of 3.5 or higher", Some(350)),
+            ("minimum 2.5 GPA in the major", Some(250)),
+            (
+                "(1) overall GPA, (2) success in prerequisite coursework",
+                None,
+            ),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(
+                Constraints::from_text(text).min_gpa,
+                expected.map(Gpa::from_hundredths),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn gpas_are_weighted_by_credits() {
+        let gpa = Gpa::weighted([(Grade::A, 3), (Grade::C, 1)]).unwrap();
+
+        assert_eq!(gpa, Gpa::from_hundredths(350));
+        assert_eq!(gpa.to_string(), "3.50");
+        assert_eq!(serde_json::to_string(&gpa).unwrap(), "3.5");
+        assert_eq!(Gpa::weighted([]), None);
+    }
+
+    #[test]
+    fn grades_round_trip_through_strings() {
+        for grade in ["A+", "A", "A-", "B+", "C", "D-", "F"] {
+            let parsed: Grade = grade.parse().unwrap();
+            assert_eq!(parsed.to_string(), grade);
+            assert_eq!(
+                serde_json::to_string(&parsed).unwrap(),
+                format!("\"{grade}\"")
+            );
+        }
+
+        assert!(Grade::BPlus > Grade::B);
+        assert!("E".parse::<Grade>().is_err());
+    }
+
+    #[test]
+    fn course_grades_pass_by_the_passing_grade() {
+        let grades: Vec<CourseGrade> = ["B-", "cr", "NC", "WF"]
+            .into_iter()
+            .map(|grade| grade.parse().unwrap())
+            .collect();
+        assert_eq!(
+            grades,
+            [
+                CourseGrade::Letter(Grade::BMinus),
+                CourseGrade::Pass,
+                CourseGrade::Fail,
+                CourseGrade::Withdrawn
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&grades).unwrap(),
+            r#"["B-","P","NP","W"]"#
+        );
+
+        let passing: Vec<bool> = grades.iter().map(|grade| grade.passes(Grade::C)).collect();
+        assert_eq!(passing, [true, true, false, false]);
+        assert!(!CourseGrade::Letter(Grade::D).passes(Grade::C));
+        assert!(CourseGrade::Letter(Grade::DMinus).passes(Grade::DMinus));
+    }
+}
@@ -0,0 +1,452 @@
+//! Courses cross-listed under more than one code.
+//!
+//! A course offered by two departments, such as `CSC 310` and `MAT 310`, is in the catalog once
+//! per code, each with its own GUID. Requirements listing either code mean the same course, so
+//! [CrossListings] groups the GUIDs of a course together under the first of them, its canonical
+//! GUID. Groups come from the `crosslist_narrative` of the courses of the catalog, kept with the
+//! `preserve-unknown` feature, or from a mapping of your own.
+//!
+//! [Catalog::merge_cross_listings] then replaces every cross-listed course of the catalog by its
+//! canonical course, so that the [PrerequisiteGraph](crate::graph::PrerequisiteGraph) built from
+//! the catalog has a single node for it and [audits](crate::audit) count it once. Completed
+//! courses go through [CrossListings::normalize_completed] before being audited against the merged
+//! catalog.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{audit::{self, CompletedCourse}, catalog::Catalog, crosslist::CrossListings};
+//! let (mut catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//!
+//! let mut listings = catalog.cross_listings();
+//! let csc = catalog.courses[0].guid;
+//! let mat = catalog.courses[1].guid;
+//! listings.add([csc, mat]);
+//! catalog.merge_cross_listings(&listings);
+//!
+//! let completed = listings.normalize_completed(&[CompletedCourse::guid(mat, 3)]);
+//! assert_eq!(completed, [CompletedCourse::guid(csc, 3)]);
+//! let report = audit::audit(&catalog.programs[0], &completed);
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::{
+    audit::{CompletedCourse, CourseId},
+    catalog::Catalog,
+    code::CourseCode,
+    parsing::guid::Guid,
+    CourseDetails, CourseEntries, CourseEntry, Requirement, RequirementModule,
+};
+
+/// Groups of GUIDs of the same course, each under its canonical GUID
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CrossListings {
+    /// Canonical GUID of every cross-listed course that isn't canonical itself
+    canonical: HashMap<Guid, Guid>,
+    /// Canonical GUID of every cross-listed course whose code is known
+    codes: HashMap<CourseCode, Guid>,
+}
+
+impl CrossListings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cross-listings named in the `crosslist_narrative` of the `courses`, such as "Cross-listed
+    /// as MAT 310". Each course is grouped with the courses whose code its narrative names.
+    /// Narratives are only kept with the `preserve-unknown` feature.
+    pub fn from_course_details(courses: &[CourseDetails]) -> Self {
+        let by_code: HashMap<CourseCode, Guid> = courses
+            .iter()
+            .filter_map(|course| Some((course.code().ok()?, course.guid)))
+            .collect();
+
+        let mut listings = Self::new();
+        for course in courses {
+            let Some(narrative) = course
+                .extra
+                .get("crosslist_narrative")
+                .and_then(|narrative| narrative.as_str())
+            else {
+                continue;
+            };
+
+            let listed = codes_in(narrative)
+                .into_iter()
+                .filter_map(|code| by_code.get(&code).copied());
+            listings.add([course.guid].into_iter().chain(listed));
+        }
+
+        for (code, guid) in by_code {
+            if listings.is_cross_listed(guid) {
+                let canonical = listings.canonical(guid);
+                listings.codes.insert(code, canonical);
+            }
+        }
+
+        listings
+    }
+
+    /// Lists the `courses` as the same course. The first of them is canonical, unless one of them
+    /// is already cross-listed, in which case the groups are merged under the existing canonical
+    /// GUID.
+    pub fn add(&mut self, courses: impl IntoIterator<Item = Guid>) {
+        let courses: Vec<Guid> = courses.into_iter().collect();
+        let Some(&first) = courses.first() else {
+            return;
+        };
+        let canonical = courses
+            .iter()
+            .find(|guid| self.is_cross_listed(**guid))
+            .map(|guid| self.canonical(*guid))
+            .unwrap_or(first);
+
+        for guid in courses {
+            let previous = self.canonical(guid);
+            // Courses of a group being merged follow their canonical course
+            for target in self.canonical.values_mut() {
+                if *target == previous {
+                    *target = canonical;
+                }
+            }
+            for target in [guid, previous] {
+                if target != canonical {
+                    self.canonical.insert(target, canonical);
+                }
+            }
+        }
+        for target in self.codes.values_mut() {
+            *target = self.canonical.get(target).copied().unwrap_or(*target);
+        }
+    }
+
+    /// The GUID standing for every listing of the course with the `guid`, which is the `guid` of
+    /// courses that aren't cross-listed
+    pub fn canonical(&self, guid: Guid) -> Guid {
+        self.canonical.get(&guid).copied().unwrap_or(guid)
+    }
+
+    pub fn is_cross_listed(&self, guid: Guid) -> bool {
+        self.canonical.contains_key(&guid) || self.canonical.values().any(|target| *target == guid)
+    }
+
+    /// Number of courses that are listed under another course
+    pub fn len(&self) -> usize {
+        self.canonical.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.canonical.is_empty()
+    }
+
+    /// The `completed` courses with their canonical GUIDs. Courses completed under their code
+    /// are identified by GUID when the code is known to be cross-listed. Only the first of the
+    /// completed listings of the same course is kept.
+    pub fn normalize_completed(&self, completed: &[CompletedCourse]) -> Vec<CompletedCourse> {
+        let mut seen = HashSet::new();
+        completed
+            .iter()
+            .map(|course| {
+                let guid = match &course.id {
+                    CourseId::Guid { guid } => Some(self.canonical(*guid)),
+                    CourseId::Code {
+                        subject_code,
+                        number,
+                    } => CourseCode::new(subject_code, number)
+                        .ok()
+                        .and_then(|code| self.codes.get(&code).copied()),
+                };
+                match guid {
+                    Some(guid) => CompletedCourse {
+                        id: CourseId::Guid { guid },
+                        ..course.clone()
+                    },
+                    None => course.clone(),
+                }
+            })
+            .filter(|course| seen.insert(course.id.clone()))
+            .collect()
+    }
+}
+
+impl Catalog {
+    /// Cross-listings named by the [courses](Catalog::courses) of the catalog. See
+    /// [CrossListings::from_course_details].
+    pub fn cross_listings(&self) -> CrossListings {
+        CrossListings::from_course_details(&self.courses)
+    }
+
+    /// Replaces every cross-listed course of the catalog by its canonical course:
+    /// - Course entries of programs and shared modules are given the canonical GUID, and left out
+    ///   of lists that already have the canonical course. Their [details](crate::Course::details)
+    ///   are dropped, so attach them again after merging.
+    /// - Requisites of courses are given the canonical GUID
+    /// - [Courses](Catalog::courses) that aren't canonical are removed, their requisites going to
+    ///   the canonical course when it doesn't have any
+    ///
+    /// Returns the number of course entries that were replaced.
+    pub fn merge_cross_listings(&mut self, listings: &CrossListings) -> usize {
+        if listings.is_empty() {
+            return 0;
+        }
+
+        let modules = self
+            .programs
+            .iter_mut()
+            .filter_map(|program| program.requirements.as_mut())
+            .flat_map(|requirements| requirements.modules_mut())
+            .chain(
+                self.shared_modules
+                    .iter_mut()
+                    .map(|shared| &mut shared.module),
+            );
+        let mut merged = 0;
+        for module in modules {
+            merged += merge_module(module, listings);
+        }
+
+        let mut requisites: HashMap<Guid, (Option<Guid>, Option<Guid>)> = HashMap::new();
+        for course in &mut self.courses {
+            course.prerequisite = course.prerequisite.map(|guid| listings.canonical(guid));
+            course.corequisite = course.corequisite.map(|guid| listings.canonical(guid));
+            let canonical = listings.canonical(course.guid);
+            if canonical != course.guid {
+                let (prerequisite, corequisite) = requisites.entry(canonical).or_default();
+                *prerequisite = prerequisite.or(course.prerequisite);
+                *corequisite = corequisite.or(course.corequisite);
+            }
+        }
+        self.courses
+            .retain(|course| listings.canonical(course.guid) == course.guid);
+        for course in &mut self.courses {
+            if let Some((prerequisite, corequisite)) = requisites.get(&course.guid) {
+                course.prerequisite = course.prerequisite.or(*prerequisite);
+                course.corequisite = course.corequisite.or(*corequisite);
+            }
+        }
+
+        merged
+    }
+}
+
+fn merge_module(module: &mut RequirementModule, listings: &CrossListings) -> usize {
+    module
+        .requirements_mut()
+        .iter_mut()
+        .map(|requirement| match requirement {
+            Requirement::Courses { courses, .. }
+            | Requirement::SelectFromCourses {
+                courses: Some(courses),
+                ..
+            } => merge_entries(courses, listings),
+            Requirement::SelectFromCourses { courses: None, .. }
+            | Requirement::Label { .. }
+            | Requirement::ElectivePool { .. } => 0,
+        })
+        .sum()
+}
+
+fn merge_entries(entries: &mut CourseEntries, listings: &CrossListings) -> usize {
+    // Canonical courses of the list, so that listings of them aren't counted twice
+    let mut listed: HashSet<Guid> = entries
+        .iter()
+        .filter_map(|entry| match entry {
+            CourseEntry::Course(course) if listings.canonical(course.guid) == course.guid => {
+                Some(course.guid)
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut merged = 0;
+    entries.retain_mut(|entry| match entry {
+        CourseEntry::And(entries) | CourseEntry::Or(entries) => {
+            merged += merge_entries(entries, listings);
+            true
+        }
+        CourseEntry::Label(_) => true,
+        CourseEntry::Course(course) => {
+            let canonical = listings.canonical(course.guid);
+            if canonical == course.guid {
+                return true;
+            }
+
+            merged += 1;
+            let course = Arc::make_mut(course);
+            course.guid = canonical;
+            course.details = None;
+            listed.insert(canonical)
+        }
+    });
+
+    merged
+}
+
+/// Course codes named in a narrative, such as the `MAT 310` of "Cross-listed as MAT 310."
+fn codes_in(narrative: &str) -> Vec<CourseCode> {
+    let words: Vec<&str> = narrative
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')'))
+        .map(|word| word.trim_end_matches('.'))
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    words
+        .windows(2)
+        .filter(|pair| pair[0].len() >= 2 && pair[0].chars().all(|c| c.is_ascii_uppercase()))
+        .filter_map(|pair| CourseCode::new(pair[0], pair[1]).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::audit::{self, Status};
+
+    fn guid(s: &str) -> Guid {
+        Guid::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn codes_are_found_in_narratives() {
+        let codes: Vec<String> = codes_in("Cross-listed as MAT 310, PHY 310L (and SOC 215).")
+            .iter()
+            .map(CourseCode::to_string)
+            .collect();
+        assert_eq!(codes, ["MAT 310", "PHY 310L", "SOC 215"]);
+        assert!(codes_in("Also offered in the Spring").is_empty());
+    }
+
+    #[test]
+    fn narratives_name_cross_listings() {
+        let (mut catalog, _) = Catalog::parse_file("../data/courses.json");
+        assert!(catalog.cross_listings().is_empty());
+
+        let other = &catalog.courses[1];
+        let (guid, subject_code, number) =
+            (other.guid, other.subject_code.clone(), other.number.clone());
+        catalog.courses[0].extra.insert(
+            "crosslist_narrative".to_owned(),
+            format!("Cross-listed as {subject_code} {number}.").into(),
+        );
+
+        let listings = catalog.cross_listings();
+        let canonical = catalog.courses[0].guid;
+        assert_eq!(listings.canonical(guid), canonical);
+        assert_eq!(
+            listings.normalize_completed(&[
+                CompletedCourse::code(&*subject_code, number, 3),
+                CompletedCourse::guid(canonical, 3),
+            ]),
+            [CompletedCourse::guid(canonical, 3)]
+        );
+    }
+
+    #[test]
+    fn groups_are_merged() {
+        let a = guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C");
+        let b = guid("13A1385C-81AC-493D-ACE8-AA8AB37D2C81");
+        let c = guid("BF3CF399-6D63-43AA-8064-2A86789B5A4E");
+        let d = guid("F17E3997-3E59-4B70-B662-E5AB1A40ADAF");
+
+        let mut listings = CrossListings::new();
+        listings.add([a, b]);
+        listings.add([c, d]);
+        listings.add([d, b]);
+
+        assert!([a, b, c, d]
+            .into_iter()
+            .all(|guid| listings.canonical(guid) == c));
+        assert_eq!(listings.len(), 3);
+    }
+
+    #[test]
+    fn cross_listed_courses_are_merged() {
+        let (mut catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let program = catalog
+            .programs
+            .iter()
+            .position(|program| program.title.starts_with("Minor in Computer Science"))
+            .unwrap();
+        let listed = catalog.programs[program]
+            .iter_courses()
+            .next()
+            .unwrap()
+            .clone();
+        let completed = [CompletedCourse::guid(listed.guid, 3)];
+        let before = audit::audit(&catalog.programs[program], &completed);
+
+        // `listed` is cross-listed under a course no program lists
+        let listed_guids: HashSet<Guid> = catalog
+            .programs
+            .iter()
+            .flat_map(|program| program.iter_courses())
+            .map(|course| course.guid)
+            .collect();
+        let canonical = catalog
+            .courses
+            .iter()
+            .find(|course| !listed_guids.contains(&course.guid))
+            .unwrap()
+            .guid;
+        let mut listings = CrossListings::new();
+        listings.add([canonical, listed.guid]);
+
+        assert!(catalog.merge_cross_listings(&listings) > 0);
+        assert!(catalog
+            .programs
+            .iter()
+            .flat_map(|program| program.iter_courses())
+            .all(|course| course.guid != listed.guid));
+        assert!(catalog
+            .courses
+            .iter()
+            .all(|course| course.guid != listed.guid));
+
+        let completed = listings.normalize_completed(&completed);
+        let after = audit::audit(&catalog.programs[program], &completed);
+        let statuses = |report: &audit::AuditReport| -> Vec<Status> {
+            report
+                .requirements
+                .iter()
+                .map(|audit| audit.status)
+                .collect()
+        };
+        assert_eq!(statuses(&before), statuses(&after));
+    }
+
+    #[test]
+    fn listings_of_the_same_course_count_once() {
+        let (catalog, _) = Catalog::parse_file("../data/courses.json");
+        let code = |details: &CourseDetails| crate::Course {
+            url: Default::default(),
+            path: Default::default(),
+            guid: details.guid,
+            name: Some(details.name.clone()),
+            number: details.number.clone(),
+            subject_name: None,
+            subject_code: details.subject_code.clone(),
+            credits: (details.credits_min, details.credits_max),
+            extra: Default::default(),
+            details: None,
+        };
+        let (csc, mat) = (&catalog.courses[0], &catalog.courses[1]);
+
+        let mut entries = CourseEntries::from(vec![
+            code(mat).into(),
+            CourseEntry::Or(CourseEntries::from(vec![
+                code(csc).into(),
+                code(mat).into(),
+            ])),
+            code(csc).into(),
+        ]);
+        let mut listings = CrossListings::new();
+        listings.add([csc.guid, mat.guid]);
+
+        assert_eq!(merge_entries(&mut entries, &listings), 2);
+        let guids: Vec<Guid> = entries.iter_courses().map(|course| course.guid).collect();
+        assert_eq!(guids, [csc.guid, csc.guid]);
+    }
+}
@@ -0,0 +1,246 @@
+//! GUIDs that don't resolve to any course of the catalog.
+//!
+//! Programs list their courses, and courses their prerequisite and corequisite, by GUID. When the
+//! feed drops a course or changes its GUID, those references are left pointing at nothing, which
+//! is the most common data-quality bug in the catalog. [Catalog::check_references] reports every
+//! such reference, grouping the courses listed by programs by program.
+//!
+//! Courses of [shared modules](crate::shared) are reported for every program referring to them.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::catalog::Catalog;
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//!
+//! let report = catalog.check_references();
+//! for program in &report.programs {
+//!     for course in &program.courses {
+//!         println!("{}: {course} isn't a known course", program.title);
+//!     }
+//! }
+//! ```
+
+use std::{collections::HashSet, fmt};
+
+use serde::Serialize;
+
+use crate::{
+    catalog::Catalog, parsing::guid::Guid, requisites::NamedCourse, Program, Requirement,
+    RequirementModule,
+};
+
+/// Every dangling reference of a catalog
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ReferenceReport {
+    /// Programs listing at least one unknown course, in the order of the catalog
+    pub programs: Vec<ProgramReferences>,
+    /// Requisites of courses that are unknown courses, in the order of the catalog
+    pub requisites: Vec<DanglingRequisite>,
+}
+
+/// The unknown courses listed by a program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ProgramReferences {
+    pub guid: Guid,
+    pub title: String,
+    /// Each unknown course once, in the order the program lists them, named by the code the
+    /// program gives them
+    pub courses: Vec<NamedCourse>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum RequisiteKind {
+    Prerequisite,
+    Corequisite,
+}
+
+/// A requisite of a course that isn't a known course
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DanglingRequisite {
+    pub course: NamedCourse,
+    pub kind: RequisiteKind,
+    pub guid: Guid,
+}
+
+impl ReferenceReport {
+    /// Number of dangling references in the report
+    pub fn len(&self) -> usize {
+        self.programs
+            .iter()
+            .map(|program| program.courses.len())
+            .sum::<usize>()
+            + self.requisites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty() && self.requisites.is_empty()
+    }
+}
+
+impl fmt::Display for RequisiteKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequisiteKind::Prerequisite => write!(f, "prerequisite"),
+            RequisiteKind::Corequisite => write!(f, "corequisite"),
+        }
+    }
+}
+
+impl fmt::Display for DanglingRequisite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the {} {} of {} isn't a known course",
+            self.kind, self.guid, self.course
+        )
+    }
+}
+
+/// One line per dangling reference
+impl fmt::Display for ReferenceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for program in &self.programs {
+            for course in &program.courses {
+                writeln!(f, "{}: {course} isn't a known course", program.title)?;
+            }
+        }
+        for requisite in &self.requisites {
+            writeln!(f, "{requisite}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Catalog {
+    /// Every course listed by the programs, and every requisite of the courses, whose GUID isn't
+    /// one of the [courses](Catalog::courses) of the catalog
+    pub fn check_references(&self) -> ReferenceReport {
+        let known: HashSet<Guid> = self.courses.iter().map(|course| course.guid).collect();
+
+        let programs = self
+            .programs
+            .iter()
+            .filter_map(|program| {
+                let courses = self.dangling_courses(program, &known);
+                (!courses.is_empty()).then(|| ProgramReferences {
+                    guid: program.guid,
+                    title: program.title.clone(),
+                    courses,
+                })
+            })
+            .collect();
+
+        let requisites = self
+            .courses
+            .iter()
+            .flat_map(|course| {
+                [
+                    (RequisiteKind::Prerequisite, course.prerequisite),
+                    (RequisiteKind::Corequisite, course.corequisite),
+                ]
+                .into_iter()
+                .filter_map(move |(kind, guid)| Some((course, kind, guid?)))
+            })
+            .filter(|(_, _, guid)| !known.contains(guid))
+            .map(|(course, kind, guid)| DanglingRequisite {
+                course: NamedCourse {
+                    guid: course.guid,
+                    code: format!("{} {}", course.subject_code, course.number),
+                },
+                kind,
+                guid,
+            })
+            .collect();
+
+        ReferenceReport {
+            programs,
+            requisites,
+        }
+    }
+
+    fn dangling_courses(&self, program: &Program, known: &HashSet<Guid>) -> Vec<NamedCourse> {
+        let mut seen = HashSet::new();
+        program
+            .requirements
+            .iter()
+            .flat_map(|requirements| requirements.modules())
+            .map(|module| self.resolve(module))
+            .flat_map(RequirementModule::requirements)
+            .filter_map(Requirement::course_entries)
+            .flat_map(|entries| entries.iter_courses())
+            .filter(|course| !known.contains(&course.guid) && seen.insert(course.guid))
+            .map(|course| NamedCourse {
+                guid: course.guid,
+                code: format!("{} {}", course.subject_code, course.number),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn every_dangling_reference_is_reported() {
+        let (mut catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let report = catalog.check_references();
+        let known: HashSet<Guid> = catalog.courses.iter().map(|course| course.guid).collect();
+
+        for program in &catalog.programs {
+            let dangling: HashSet<Guid> = program
+                .iter_courses()
+                .map(|course| course.guid)
+                .filter(|guid| !known.contains(guid))
+                .collect();
+            let reported = report
+                .programs
+                .iter()
+                .find(|references| references.guid == program.guid)
+                .map(|references| {
+                    references
+                        .courses
+                        .iter()
+                        .map(|course| course.guid)
+                        .collect()
+                })
+                .unwrap_or_default();
+            assert_eq!(dangling, reported, "{}", program.title);
+        }
+        assert!(report
+            .requisites
+            .iter()
+            .all(|requisite| !known.contains(&requisite.guid)));
+
+        // Shared modules still count for every program referring to them
+        catalog.resolve_references();
+        assert_eq!(catalog.check_references(), report);
+    }
+
+    #[test]
+    fn catalogs_without_courses_dangle_everywhere() {
+        let (mut catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let listed: HashSet<Guid> = catalog
+            .programs
+            .iter()
+            .flat_map(|program| program.iter_courses())
+            .map(|course| course.guid)
+            .collect();
+        catalog.courses.clear();
+
+        let report = catalog.check_references();
+        assert!(report.requisites.is_empty());
+        let reported: HashSet<Guid> = report
+            .programs
+            .iter()
+            .flat_map(|program| &program.courses)
+            .map(|course| course.guid)
+            .collect();
+        assert_eq!(reported, listed);
+    }
+}
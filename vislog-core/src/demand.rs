@@ -0,0 +1,248 @@
+//! Seats needed in every course and term by the incoming cohorts of the programs of a catalog.
+//!
+//! Every student of a cohort is assumed to follow the [layered plan](crate::planner::layered_plan)
+//! of their program from its first term, so a course planned in the third term of a program with
+//! a cohort of 40 students needs 40 seats in the third term. [forecast] plans every program with
+//! a cohort and adds up the seats of the courses planned by several programs in the same term.
+//! Courses that plans leave [unscheduled](crate::planner::LayeredPlan::unscheduled) or
+//! [unoffered](crate::planner::LayeredPlan::unoffered) don't need seats in any term.
+//!
+//! Rows are joined to the [course index](CourseDetailsIndex) for the subject code, number and
+//! name of their course, and [write_csv] writes them as a CSV that opens in spreadsheet software.
+//!
+//! # Example
+//! ```
+//! # use std::collections::HashMap;
+//! # use vislog_core::{catalog::Catalog, demand, graph::PrerequisiteGraph, schedule::{OfferingCalendar, Season}};
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//! let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+//! let calendar = OfferingCalendar::new("Fall 2025".parse().unwrap(), [Season::Fall, Season::Spring]);
+//!
+//! let cohorts: HashMap<_, _> = catalog.programs.iter().map(|program| (program.guid, 30)).collect();
+//! let rows = demand::forecast(&catalog, &cohorts, &graph, 15, Some(&calendar));
+//!
+//! let mut output = vec![];
+//! demand::write_csv(&mut output, &rows).unwrap();
+//! assert!(output.starts_with(b"term,subject,number,"));
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+};
+
+use serde::Serialize;
+
+use crate::{
+    catalog::Catalog,
+    details::CourseDetailsIndex,
+    export::csv::write_record,
+    graph::PrerequisiteGraph,
+    parsing::guid::Guid,
+    planner::{self, LayeredPlan},
+    schedule::{OfferingCalendar, Term},
+};
+
+/// Column names of [write_csv], in order
+pub const HEADER: [&str; 7] = [
+    "term", "subject", "number", "name", "seats", "programs", "guid",
+];
+
+/// Seats needed in a course in a term
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SeatDemand {
+    /// Index of the term in the plans, from 0 for the first term of the cohorts
+    pub term_index: usize,
+    /// Term of the calendar the plans follow, if any
+    pub term: Option<Term>,
+    pub guid: Guid,
+    pub subject_code: String,
+    pub number: String,
+    pub name: Option<String>,
+    /// Students of every cohort planning the course in the term
+    pub seats: u32,
+    /// Number of programs planning the course in the term
+    pub programs: u32,
+}
+
+impl SeatDemand {
+    /// Values of the row in the order of [HEADER]. Terms without a calendar are numbered from 1.
+    pub fn fields(&self) -> [String; 7] {
+        [
+            match self.term {
+                Some(term) => term.to_string(),
+                None => format!("Term {}", self.term_index + 1),
+            },
+            self.subject_code.clone(),
+            self.number.clone(),
+            self.name.clone().unwrap_or_default(),
+            self.seats.to_string(),
+            self.programs.to_string(),
+            self.guid.to_string(),
+        ]
+    }
+}
+
+/// Plans every program of the `catalog` with a cohort in `cohorts`, by the GUID of the program,
+/// and adds up the seats their courses need in every term. See [planner::layered_plan] for
+/// `max_credits` and the `calendar`.
+pub fn forecast(
+    catalog: &Catalog,
+    cohorts: &HashMap<Guid, u32>,
+    graph: &PrerequisiteGraph,
+    max_credits: u32,
+    calendar: Option<&OfferingCalendar>,
+) -> Vec<SeatDemand> {
+    let plans: Vec<(LayeredPlan, u32)> = catalog
+        .programs
+        .iter()
+        .filter_map(|program| {
+            let students = *cohorts.get(&program.guid)?;
+            let plan = planner::layered_plan(program, graph, max_credits, calendar);
+            Some((plan, students))
+        })
+        .collect();
+
+    seat_demand(
+        plans.iter().map(|(plan, students)| (plan, *students)),
+        &CourseDetailsIndex::new(&catalog.courses),
+    )
+}
+
+/// Adds up the seats needed by cohorts of students following the `plans`, each given with the
+/// number of students following it. Rows are ordered by term, then by subject code and number.
+pub fn seat_demand<'a>(
+    plans: impl IntoIterator<Item = (&'a LayeredPlan, u32)>,
+    index: &CourseDetailsIndex,
+) -> Vec<SeatDemand> {
+    let mut rows: BTreeMap<(usize, Option<Term>, String, String, String), SeatDemand> =
+        BTreeMap::new();
+
+    for (plan, students) in plans {
+        for (term_index, term) in plan.terms.iter().enumerate() {
+            for course in &term.courses {
+                let (subject_code, number, name) = match index.get(course.guid) {
+                    Some(details) => (
+                        details.subject_code.to_string(),
+                        details.number.clone(),
+                        Some(details.name.clone()),
+                    ),
+                    None => {
+                        let (subject_code, number) =
+                            course.label.split_once(' ').unwrap_or((&course.label, ""));
+                        (
+                            subject_code.to_owned(),
+                            number.to_owned(),
+                            course.name.clone(),
+                        )
+                    }
+                };
+
+                let key = (
+                    term_index,
+                    term.term,
+                    subject_code.clone(),
+                    number.clone(),
+                    course.guid.to_string(),
+                );
+                let row = rows.entry(key).or_insert_with(|| SeatDemand {
+                    term_index,
+                    term: term.term,
+                    guid: course.guid,
+                    subject_code,
+                    number,
+                    name,
+                    seats: 0,
+                    programs: 0,
+                });
+                row.seats += students;
+                row.programs += 1;
+            }
+        }
+    }
+
+    rows.into_values().collect()
+}
+
+/// Writes the [HEADER] followed by every row
+pub fn write_csv<W: Write>(mut writer: W, rows: &[SeatDemand]) -> io::Result<()> {
+    write_record(&mut writer, HEADER)?;
+    for row in rows {
+        write_record(&mut writer, row.fields())?;
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schedule::Season;
+
+    #[test]
+    fn seats_of_programs_sharing_courses_add_up() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+        let cohorts: HashMap<Guid, u32> = catalog
+            .programs
+            .iter()
+            .enumerate()
+            .map(|(i, program)| (program.guid, 10 * (i as u32 + 1)))
+            .collect();
+
+        let rows = forecast(&catalog, &cohorts, &graph, 15, None);
+
+        let plans: Vec<_> = catalog
+            .programs
+            .iter()
+            .map(|program| {
+                let plan = planner::layered_plan(program, &graph, 15, None);
+                (plan, cohorts[&program.guid])
+            })
+            .collect();
+        let planned_seats: u32 = plans
+            .iter()
+            .map(|(plan, students)| {
+                plan.terms
+                    .iter()
+                    .map(|term| term.courses.len() as u32 * students)
+                    .sum::<u32>()
+            })
+            .sum();
+        assert_eq!(rows.iter().map(|row| row.seats).sum::<u32>(), planned_seats);
+        assert!(rows
+            .windows(2)
+            .all(|pair| pair[0].term_index <= pair[1].term_index));
+        assert!(rows.iter().all(|row| row.term.is_none()));
+
+        // Programs without a cohort aren't planned
+        let first = catalog.programs[0].guid;
+        let only_first = forecast(&catalog, &HashMap::from([(first, 5)]), &graph, 15, None);
+        assert!(only_first
+            .iter()
+            .all(|row| row.seats == 5 && row.programs == 1));
+    }
+
+    #[test]
+    fn rows_are_written_with_their_terms() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+        let calendar =
+            OfferingCalendar::new("Fall 2025".parse().unwrap(), [Season::Fall, Season::Spring]);
+        let cohorts = HashMap::from([(catalog.programs[0].guid, 25)]);
+
+        let rows = forecast(&catalog, &cohorts, &graph, 15, Some(&calendar));
+        assert_eq!(rows[0].term, "Fall 2025".parse().ok());
+
+        let mut output = vec![];
+        write_csv(&mut output, &rows).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let first_row = output.split("\r\n").nth(1).unwrap();
+        assert!(first_row.starts_with(&format!(
+            "Fall 2025,{},{},",
+            rows[0].subject_code, rows[0].number
+        )));
+        assert!(first_row.ends_with(&format!(",25,1,{}", rows[0].guid)));
+    }
+}
@@ -0,0 +1,187 @@
+//! The [CourseDetails] of the courses listed by requirements.
+//!
+//! Requirements only list the code, name and credits of their courses, while the description and
+//! requisites are in the course catalog. [Catalog::attach_details] looks up the details of every
+//! course entry by GUID once and keeps them in [Course::details], so that renderers can show them
+//! along with the requirement without looking them up again.
+//!
+//! Details are shared through an [Arc], and attaching them keeps the courses of the catalog
+//! [interned](crate::intern). They are left out of the [wire](crate::wire), [proto](crate::proto)
+//! and [store](crate::store) formats, so attach them again after loading a catalog.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::catalog::Catalog;
+//! let (mut catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//! assert!(catalog.attach_details() > 0);
+//!
+//! let course = catalog.programs[0].iter_courses().next().unwrap();
+//! if let Some(details) = &course.details {
+//!     println!("{}: {}", course.number, details.description);
+//! }
+//! ```
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    catalog::Catalog, parsing::guid::Guid, Course, CourseDetails, CourseEntries, CourseEntry,
+    Requirement, RequirementModule,
+};
+
+/// [CourseDetails] by GUID
+#[derive(Debug, Clone, Default)]
+pub struct CourseDetailsIndex {
+    courses: HashMap<Guid, Arc<CourseDetails>>,
+}
+
+impl CourseDetailsIndex {
+    /// Keeps the first of the `courses` with the same GUID
+    pub fn new<'a>(courses: impl IntoIterator<Item = &'a CourseDetails>) -> Self {
+        let mut index = Self::default();
+        for course in courses {
+            index
+                .courses
+                .entry(course.guid)
+                .or_insert_with(|| Arc::new(course.clone()));
+        }
+
+        index
+    }
+
+    pub fn get(&self, guid: Guid) -> Option<&Arc<CourseDetails>> {
+        self.courses.get(&guid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.courses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.courses.is_empty()
+    }
+}
+
+impl Course {
+    /// Sets the [details](Course::details) of the course to the ones in the `index`, or `None` when
+    /// the index doesn't have the course. Returns whether the course has details.
+    pub fn enrich(&mut self, index: &CourseDetailsIndex) -> bool {
+        self.details = index.get(self.guid).cloned();
+        self.details.is_some()
+    }
+}
+
+impl Catalog {
+    /// Sets the [details](Course::details) of every course listed by the programs and
+    /// [shared modules](Catalog::shared_modules) of the catalog to the [courses](Catalog::courses)
+    /// with the same GUID. Returns the number of course entries that have details.
+    pub fn attach_details(&mut self) -> usize {
+        let index = CourseDetailsIndex::new(&self.courses);
+        let mut attached = 0;
+
+        let modules = self
+            .programs
+            .iter_mut()
+            .filter_map(|program| program.requirements.as_mut())
+            .flat_map(|requirements| requirements.modules_mut())
+            .chain(
+                self.shared_modules
+                    .iter_mut()
+                    .map(|shared| &mut shared.module),
+            );
+        for module in modules {
+            attached += attach_to_module(module, &index);
+        }
+
+        // Courses that were shared were copied by `Arc::make_mut`
+        self.intern_courses();
+
+        attached
+    }
+}
+
+fn attach_to_module(module: &mut RequirementModule, index: &CourseDetailsIndex) -> usize {
+    module
+        .requirements_mut()
+        .iter_mut()
+        .map(|requirement| match requirement {
+            Requirement::Courses { courses, .. }
+            | Requirement::SelectFromCourses {
+                courses: Some(courses),
+                ..
+            } => attach_to_entries(courses, index),
+            Requirement::SelectFromCourses { courses: None, .. }
+            | Requirement::Label { .. }
+            | Requirement::ElectivePool { .. } => 0,
+        })
+        .sum()
+}
+
+fn attach_to_entries(entries: &mut CourseEntries, index: &CourseDetailsIndex) -> usize {
+    entries
+        .iter_mut()
+        .map(|entry| match entry {
+            CourseEntry::And(entries) | CourseEntry::Or(entries) => {
+                attach_to_entries(entries, index)
+            }
+            CourseEntry::Label(_) => 0,
+            CourseEntry::Course(course) => {
+                let details = index.get(course.guid);
+                // Leave courses that already have the same details alone so they stay shared
+                if course.details.as_ref().map(Arc::as_ptr) != details.map(Arc::as_ptr) {
+                    Arc::make_mut(course).details = details.cloned();
+                }
+                usize::from(details.is_some())
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn details_are_attached_by_guid() {
+        let (mut catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let courses = catalog
+            .programs
+            .iter()
+            .flat_map(|program| program.iter_courses())
+            .count();
+
+        let attached = catalog.attach_details();
+        assert!(attached > 0 && attached <= courses);
+
+        let index = CourseDetailsIndex::new(&catalog.courses);
+        for course in catalog
+            .programs
+            .iter()
+            .flat_map(|program| program.iter_courses())
+        {
+            assert_eq!(
+                course.details.as_deref(),
+                index.get(course.guid).map(|d| &**d)
+            );
+        }
+
+        // Every entry for the same course still shares one copy, and attaching again changes
+        // nothing
+        assert_eq!(catalog.intern_courses(), 0);
+        let before = catalog.clone();
+        assert_eq!(catalog.attach_details(), attached);
+        assert_eq!(catalog, before);
+    }
+
+    #[test]
+    fn enriching_a_course_without_details_clears_them() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let index = CourseDetailsIndex::new(&catalog.courses);
+        let mut course = catalog.programs[0].iter_courses().next().unwrap().clone();
+
+        let found = course.enrich(&index);
+        assert_eq!(found, course.details.is_some());
+
+        assert!(!course.enrich(&CourseDetailsIndex::default()));
+        assert_eq!(course.details, None);
+    }
+}
@@ -0,0 +1,847 @@
+//! Year-over-year differences between two [Catalog]s, keyed by `Guid`, and between two versions
+//! of a single [Program]
+//!
+//! Programs, modules and requirements are compared with [SemanticEq], so a course whose page moved
+//! in the catalog isn't reported as a change.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use serde::Serialize;
+
+use crate::{
+    catalog::Catalog, parsing::guid::Guid, semantic::SemanticEq, Course, CourseDetails,
+    CourseEntry, Program, Requirement, RequirementModule, Requirements,
+};
+
+/// Everything that changed between two catalogs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CatalogDiff {
+    pub added_programs: Vec<ProgramSummary>,
+    pub removed_programs: Vec<ProgramSummary>,
+    pub renamed_programs: Vec<RenamedProgram>,
+    /// Programs whose requirements changed in any way
+    pub changed_requirements: Vec<RequirementsChange>,
+    pub added_courses: Vec<CourseSummary>,
+    pub removed_courses: Vec<CourseSummary>,
+    pub credit_changes: Vec<CreditChange>,
+    pub prerequisite_changes: Vec<PrerequisiteChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct ProgramSummary {
+    pub guid: Guid,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct RenamedProgram {
+    pub guid: Guid,
+    pub old_title: String,
+    pub new_title: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct RequirementsChange {
+    pub guid: Guid,
+    /// Title of the program in the new catalog
+    pub title: String,
+    /// Courses listed in the new requirements but not in the old ones
+    pub added_courses: Vec<Guid>,
+    /// Courses listed in the old requirements but not in the new ones
+    pub removed_courses: Vec<Guid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct CourseSummary {
+    pub guid: Guid,
+    pub subject_code: String,
+    pub number: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct CreditChange {
+    pub course: CourseSummary,
+    /// Minimum and, for variable credit courses, maximum credits
+    pub old_credits: (u8, Option<u8>),
+    pub new_credits: (u8, Option<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct PrerequisiteChange {
+    pub course: CourseSummary,
+    pub old_narrative: Option<String>,
+    pub new_narrative: Option<String>,
+    pub old_prerequisite: Option<Guid>,
+    pub new_prerequisite: Option<Guid>,
+}
+
+impl CatalogDiff {
+    /// Whether nothing changed between the two catalogs
+    pub fn is_empty(&self) -> bool {
+        self == &CatalogDiff::default()
+    }
+}
+
+impl From<&Program> for ProgramSummary {
+    fn from(program: &Program) -> Self {
+        Self {
+            guid: program.guid,
+            title: program.title.clone(),
+        }
+    }
+}
+
+impl From<&CourseDetails> for CourseSummary {
+    fn from(course: &CourseDetails) -> Self {
+        Self {
+            guid: course.guid,
+            subject_code: course.subject_code.to_string(),
+            number: course.number.clone(),
+            name: course.name.clone(),
+        }
+    }
+}
+
+/// Compares the `old` and `new` catalogs. Programs and courses are matched up by their `Guid`,
+/// and every list in the returned [CatalogDiff] follows the order of the catalog it came from.
+pub fn diff_catalogs(old: &Catalog, new: &Catalog) -> CatalogDiff {
+    let mut diff = CatalogDiff::default();
+
+    diff_programs(&old.programs, &new.programs, &mut diff);
+    diff_courses(&old.courses, &new.courses, &mut diff);
+
+    diff
+}
+
+fn diff_programs(old: &[Program], new: &[Program], diff: &mut CatalogDiff) {
+    let old_by_guid: HashMap<Guid, &Program> = old.iter().map(|p| (p.guid, p)).collect();
+    let new_guids: HashSet<Guid> = new.iter().map(|p| p.guid).collect();
+
+    diff.removed_programs = old
+        .iter()
+        .filter(|program| !new_guids.contains(&program.guid))
+        .map(ProgramSummary::from)
+        .collect();
+
+    for new_program in new {
+        let Some(old_program) = old_by_guid.get(&new_program.guid) else {
+            diff.added_programs.push(new_program.into());
+            continue;
+        };
+
+        if old_program.title != new_program.title {
+            diff.renamed_programs.push(RenamedProgram {
+                guid: new_program.guid,
+                old_title: old_program.title.clone(),
+                new_title: new_program.title.clone(),
+            });
+        }
+
+        if !old_program
+            .requirements
+            .semantic_eq(&new_program.requirements)
+        {
+            let old_courses = course_guids(old_program);
+            let new_courses = course_guids(new_program);
+
+            diff.changed_requirements.push(RequirementsChange {
+                guid: new_program.guid,
+                title: new_program.title.clone(),
+                added_courses: difference(&new_courses, &old_courses),
+                removed_courses: difference(&old_courses, &new_courses),
+            });
+        }
+    }
+}
+
+fn diff_courses(old: &[CourseDetails], new: &[CourseDetails], diff: &mut CatalogDiff) {
+    let old_by_guid: HashMap<Guid, &CourseDetails> = old.iter().map(|c| (c.guid, c)).collect();
+    let new_guids: HashSet<Guid> = new.iter().map(|c| c.guid).collect();
+
+    diff.removed_courses = old
+        .iter()
+        .filter(|course| !new_guids.contains(&course.guid))
+        .map(CourseSummary::from)
+        .collect();
+
+    for new_course in new {
+        let Some(old_course) = old_by_guid.get(&new_course.guid) else {
+            diff.added_courses.push(new_course.into());
+            continue;
+        };
+
+        let old_credits = (old_course.credits_min, old_course.credits_max);
+        let new_credits = (new_course.credits_min, new_course.credits_max);
+        if old_credits != new_credits {
+            diff.credit_changes.push(CreditChange {
+                course: new_course.into(),
+                old_credits,
+                new_credits,
+            });
+        }
+
+        if old_course.prerequisite_narrative != new_course.prerequisite_narrative
+            || old_course.prerequisite != new_course.prerequisite
+        {
+            diff.prerequisite_changes.push(PrerequisiteChange {
+                course: new_course.into(),
+                old_narrative: old_course.prerequisite_narrative.clone(),
+                new_narrative: new_course.prerequisite_narrative.clone(),
+                old_prerequisite: old_course.prerequisite,
+                new_prerequisite: new_course.prerequisite,
+            });
+        }
+    }
+}
+
+/// `Guid`s of the courses of a program without duplicates, in the order they first appear
+fn course_guids(program: &Program) -> Vec<Guid> {
+    let mut seen = HashSet::new();
+
+    program
+        .iter_courses()
+        .map(|course| course.guid)
+        .filter(|guid| seen.insert(*guid))
+        .collect()
+}
+
+/// Elements of `a` that are not in `b`
+fn difference(a: &[Guid], b: &[Guid]) -> Vec<Guid> {
+    let b: HashSet<&Guid> = b.iter().collect();
+
+    a.iter().filter(|guid| !b.contains(guid)).copied().collect()
+}
+
+/// Tree of the changes between two versions of the same [Program]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProgramDiff {
+    pub old_title: String,
+    pub new_title: String,
+    pub modules: Vec<ModuleChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum ModuleChange {
+    Added {
+        title: Option<String>,
+    },
+    Removed {
+        title: Option<String>,
+    },
+    Modified {
+        title: Option<String>,
+        requirements: Vec<RequirementChange>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum RequirementChange {
+    Added {
+        title: Option<String>,
+    },
+    Removed {
+        title: Option<String>,
+    },
+    /// Same courses under a different title
+    Reworded {
+        old_title: Option<String>,
+        new_title: Option<String>,
+    },
+    /// Same title with different contents. `courses` is empty when only the narratives changed.
+    Modified {
+        title: Option<String>,
+        courses: Vec<CourseChange>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum CourseChange {
+    Added {
+        course: CourseSummary,
+        /// Whether the course is inside of an `Or` group
+        in_choice: bool,
+    },
+    Removed {
+        course: CourseSummary,
+        in_choice: bool,
+    },
+    /// A course replaced by another one in the same group
+    Swapped {
+        old: CourseSummary,
+        new: CourseSummary,
+        in_choice: bool,
+    },
+}
+
+impl From<&Course> for CourseSummary {
+    fn from(course: &Course) -> Self {
+        Self {
+            guid: course.guid,
+            subject_code: course.subject_code.to_string(),
+            number: course.number.clone(),
+            name: course.name.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl Program {
+    /// Changes needed to turn this program into `other`.
+    ///
+    /// Requirement modules are matched up by title, and requirements by title first and by their
+    /// courses second so that a reworded requirement is not reported as removed and added again.
+    /// Courses are compared within each group of the requirement, where a course removed from a
+    /// group and another added to the same group are reported as swapped.
+    pub fn diff(&self, other: &Program) -> ProgramDiff {
+        let old_modules = self.requirements.iter().flat_map(Requirements::modules);
+        let new_modules = other.requirements.iter().flat_map(Requirements::modules);
+
+        ProgramDiff {
+            old_title: self.title.clone(),
+            new_title: other.title.clone(),
+            modules: diff_modules(
+                &old_modules.collect::<Vec<_>>(),
+                &new_modules.collect::<Vec<_>>(),
+            ),
+        }
+    }
+}
+
+impl ProgramDiff {
+    pub fn is_empty(&self) -> bool {
+        self.old_title == self.new_title && self.modules.is_empty()
+    }
+
+    /// Renders the changes as a nested Markdown list under a heading with the program's title
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("## {}\n\n", self.new_title);
+        self.render(&mut markdown, Style::Markdown)
+            .expect("Writing to a String never fails");
+
+        markdown
+    }
+
+    fn render(&self, f: &mut impl fmt::Write, style: Style) -> fmt::Result {
+        if self.is_empty() {
+            return style.line(f, 0, ' ', "No changes");
+        }
+
+        if self.old_title != self.new_title {
+            style.line(
+                f,
+                0,
+                '~',
+                &format!(
+                    "Renamed from {} to {}",
+                    style.title(Some(&self.old_title)),
+                    style.title(Some(&self.new_title))
+                ),
+            )?;
+        }
+
+        for module in &self.modules {
+            match module {
+                ModuleChange::Added { title } => style.line(
+                    f,
+                    0,
+                    '+',
+                    &format!("Added module {}", style.title(title.as_deref())),
+                )?,
+                ModuleChange::Removed { title } => style.line(
+                    f,
+                    0,
+                    '-',
+                    &format!("Removed module {}", style.title(title.as_deref())),
+                )?,
+                ModuleChange::Modified {
+                    title,
+                    requirements,
+                } => {
+                    style.line(
+                        f,
+                        0,
+                        '~',
+                        &format!("Changed module {}", style.title(title.as_deref())),
+                    )?;
+                    for requirement in requirements {
+                        render_requirement_change(f, style, requirement)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for ProgramDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.new_title)?;
+        self.render(f, Style::Plain)
+    }
+}
+
+fn render_requirement_change(
+    f: &mut impl fmt::Write,
+    style: Style,
+    change: &RequirementChange,
+) -> fmt::Result {
+    match change {
+        RequirementChange::Added { title } => style.line(
+            f,
+            1,
+            '+',
+            &format!("Added requirement {}", style.title(title.as_deref())),
+        ),
+        RequirementChange::Removed { title } => style.line(
+            f,
+            1,
+            '-',
+            &format!("Removed requirement {}", style.title(title.as_deref())),
+        ),
+        RequirementChange::Reworded {
+            old_title,
+            new_title,
+        } => style.line(
+            f,
+            1,
+            '~',
+            &format!(
+                "Reworded requirement {} to {}",
+                style.title(old_title.as_deref()),
+                style.title(new_title.as_deref())
+            ),
+        ),
+        RequirementChange::Modified { title, courses } => {
+            style.line(
+                f,
+                1,
+                '~',
+                &format!("Changed requirement {}", style.title(title.as_deref())),
+            )?;
+
+            for course in courses {
+                let (marker, line, in_choice) = match course {
+                    CourseChange::Added { course, in_choice } => {
+                        ('+', format!("Added {}", style.course(course)), in_choice)
+                    }
+                    CourseChange::Removed { course, in_choice } => {
+                        ('-', format!("Removed {}", style.course(course)), in_choice)
+                    }
+                    CourseChange::Swapped {
+                        old,
+                        new,
+                        in_choice,
+                    } => (
+                        '~',
+                        format!("Swapped {} for {}", style.course(old), style.course(new)),
+                        in_choice,
+                    ),
+                };
+
+                let line = match in_choice {
+                    true => format!("{line} (choice)"),
+                    false => line,
+                };
+                style.line(f, 2, marker, &line)?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Style {
+    /// Indented lines prefixed with `+`, `-` or `~`
+    Plain,
+    Markdown,
+}
+
+impl Style {
+    fn line(self, f: &mut impl fmt::Write, depth: usize, marker: char, text: &str) -> fmt::Result {
+        let indent = "  ".repeat(depth + 1);
+
+        match self {
+            Style::Plain => writeln!(f, "{indent}{marker} {text}"),
+            Style::Markdown => writeln!(f, "{}- {text}", &indent[2..]),
+        }
+    }
+
+    fn title(self, title: Option<&str>) -> String {
+        let title = title.unwrap_or("(untitled)").trim();
+
+        match self {
+            Style::Plain => format!("\"{title}\""),
+            Style::Markdown => format!("**{title}**"),
+        }
+    }
+
+    fn course(self, course: &CourseSummary) -> String {
+        let code = format!("{} {}", course.subject_code, course.number);
+
+        match (self, course.name.is_empty()) {
+            (Style::Plain, true) => code,
+            (Style::Plain, false) => format!("{code} {}", course.name),
+            (Style::Markdown, true) => format!("`{code}`"),
+            (Style::Markdown, false) => format!("`{code}` {}", course.name),
+        }
+    }
+}
+
+/// Pairs up the elements of `old` and `new` that are the `same`, each element being used at most
+/// once. Returns the pairs of indices, followed by the indices left over in `old` and in `new`.
+fn pair_by<T>(
+    old: &[T],
+    new: &[T],
+    same: impl Fn(&T, &T) -> bool,
+) -> (Vec<(usize, usize)>, Vec<usize>, Vec<usize>) {
+    let mut unmatched_old: Vec<usize> = (0..old.len()).collect();
+    let mut pairs = vec![];
+    let mut unmatched_new = vec![];
+
+    for (new_idx, new_item) in new.iter().enumerate() {
+        match unmatched_old
+            .iter()
+            .position(|old_idx| same(&old[*old_idx], new_item))
+        {
+            Some(position) => pairs.push((unmatched_old.remove(position), new_idx)),
+            None => unmatched_new.push(new_idx),
+        }
+    }
+
+    (pairs, unmatched_old, unmatched_new)
+}
+
+fn diff_modules(old: &[&RequirementModule], new: &[&RequirementModule]) -> Vec<ModuleChange> {
+    let (pairs, removed, added) = pair_by(old, new, |a, b| a.title() == b.title());
+    let title = |module: &RequirementModule| module.title().map(str::to_string);
+
+    let mut changes = vec![];
+
+    for (old_idx, new_idx) in pairs {
+        let (old_module, new_module) = (old[old_idx], new[new_idx]);
+        if old_module.semantic_eq(new_module) {
+            continue;
+        }
+
+        changes.push(ModuleChange::Modified {
+            title: title(new_module),
+            requirements: diff_requirements(old_module.requirements(), new_module.requirements()),
+        });
+    }
+
+    changes.extend(removed.into_iter().map(|idx| ModuleChange::Removed {
+        title: title(old[idx]),
+    }));
+    changes.extend(added.into_iter().map(|idx| ModuleChange::Added {
+        title: title(new[idx]),
+    }));
+
+    changes
+}
+
+fn diff_requirements(old: &[Requirement], new: &[Requirement]) -> Vec<RequirementChange> {
+    let title = |requirement: &Requirement| requirement.title().map(str::to_string);
+    let mut changes = vec![];
+
+    let (pairs, removed, added) = pair_by(old, new, |a, b| a.title() == b.title());
+    for (old_idx, new_idx) in pairs {
+        let (old_requirement, new_requirement) = (&old[old_idx], &new[new_idx]);
+        if old_requirement.semantic_eq(new_requirement) {
+            continue;
+        }
+
+        let mut courses = vec![];
+        diff_entries(
+            old_requirement
+                .course_entries()
+                .map_or(&[], |entries| entries),
+            new_requirement
+                .course_entries()
+                .map_or(&[], |entries| entries),
+            false,
+            &mut courses,
+        );
+
+        changes.push(RequirementChange::Modified {
+            title: title(new_requirement),
+            courses,
+        });
+    }
+
+    // Requirements left over with the same courses but a different title were reworded
+    let leftover_old: Vec<&Requirement> = removed.into_iter().map(|idx| &old[idx]).collect();
+    let leftover_new: Vec<&Requirement> = added.into_iter().map(|idx| &new[idx]).collect();
+    let (reworded, removed, added) = pair_by(&leftover_old, &leftover_new, |a, b| {
+        a.course_entries().semantic_eq(&b.course_entries())
+    });
+
+    changes.extend(
+        reworded
+            .into_iter()
+            .map(|(old_idx, new_idx)| RequirementChange::Reworded {
+                old_title: title(leftover_old[old_idx]),
+                new_title: title(leftover_new[new_idx]),
+            }),
+    );
+    changes.extend(removed.into_iter().map(|idx| RequirementChange::Removed {
+        title: title(leftover_old[idx]),
+    }));
+    changes.extend(added.into_iter().map(|idx| RequirementChange::Added {
+        title: title(leftover_new[idx]),
+    }));
+
+    changes
+}
+
+/// Compares the courses directly in `old` and `new`, then the groups in them pairwise in order
+fn diff_entries<'a>(
+    old: &'a [CourseEntry],
+    new: &'a [CourseEntry],
+    in_choice: bool,
+    changes: &mut Vec<CourseChange>,
+) {
+    let courses = |entries: &[CourseEntry]| -> Vec<CourseSummary> {
+        entries
+            .iter()
+            .filter_map(|entry| match entry {
+                CourseEntry::Course(course) => Some(course.as_ref().into()),
+                _ => None,
+            })
+            .collect()
+    };
+
+    let (old_courses, new_courses) = (courses(old), courses(new));
+    let (_, removed, added) = pair_by(&old_courses, &new_courses, |a, b| a.guid == b.guid);
+
+    let swapped = removed.len().min(added.len());
+    for (old_idx, new_idx) in removed.iter().zip(&added) {
+        changes.push(CourseChange::Swapped {
+            old: old_courses[*old_idx].clone(),
+            new: new_courses[*new_idx].clone(),
+            in_choice,
+        });
+    }
+    for idx in &removed[swapped..] {
+        changes.push(CourseChange::Removed {
+            course: old_courses[*idx].clone(),
+            in_choice,
+        });
+    }
+    for idx in &added[swapped..] {
+        changes.push(CourseChange::Added {
+            course: new_courses[*idx].clone(),
+            in_choice,
+        });
+    }
+
+    // Groups along with whether they are `Or` groups
+    let groups = |entries: &'a [CourseEntry]| -> Vec<(&'a [CourseEntry], bool)> {
+        entries
+            .iter()
+            .filter_map(|entry| match entry {
+                CourseEntry::And(group) => Some((group.as_slice(), false)),
+                CourseEntry::Or(group) => Some((group.as_slice(), true)),
+                _ => None,
+            })
+            .collect()
+    };
+
+    let (old_groups, new_groups) = (groups(old), groups(new));
+
+    for idx in 0..old_groups.len().max(new_groups.len()) {
+        let old_group = old_groups.get(idx);
+        let new_group = new_groups.get(idx);
+        let is_or = new_group.or(old_group).is_some_and(|(_, is_or)| *is_or);
+
+        diff_entries(
+            old_group.map_or(&[], |(group, _)| group),
+            new_group.map_or(&[], |(group, _)| group),
+            in_choice || is_or,
+            changes,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::read_program;
+    use crate::Requirements;
+
+    fn test_catalog() -> Catalog {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        catalog
+    }
+
+    #[test]
+    fn identical_catalogs_have_no_diff() {
+        let catalog = test_catalog();
+
+        assert!(diff_catalogs(&catalog, &catalog).is_empty());
+    }
+
+    #[test]
+    fn program_changes() {
+        let cs_major = read_program("cs_major.json");
+        let cs_minor = read_program("cs_minor.json");
+        let zoology = read_program("zoology_major.json");
+
+        let old = Catalog {
+            programs: vec![cs_major.clone(), cs_minor.clone()],
+            ..Default::default()
+        };
+
+        let mut renamed_major = cs_major.clone();
+        renamed_major.title = "Major in Computing".to_string();
+
+        // Swap out the requirements of the minor for the ones of the zoology major
+        let mut changed_minor = cs_minor.clone();
+        changed_minor.requirements = zoology.requirements.clone();
+
+        let new = Catalog {
+            programs: vec![renamed_major, changed_minor, zoology.clone()],
+            ..Default::default()
+        };
+
+        let diff = diff_catalogs(&old, &new);
+
+        assert_eq!(diff.added_programs, [ProgramSummary::from(&zoology)]);
+        assert!(diff.removed_programs.is_empty());
+        assert_eq!(
+            diff.renamed_programs,
+            [RenamedProgram {
+                guid: cs_major.guid,
+                old_title: cs_major.title.clone(),
+                new_title: "Major in Computing".to_string(),
+            }]
+        );
+
+        assert_eq!(diff.changed_requirements.len(), 1);
+        let change = &diff.changed_requirements[0];
+        assert_eq!(change.guid, cs_minor.guid);
+        assert_eq!(change.added_courses, course_guids(&zoology));
+        assert_eq!(change.removed_courses, course_guids(&cs_minor));
+
+        let removed = diff_catalogs(&new, &Catalog::default());
+        assert_eq!(removed.removed_programs.len(), 3);
+        assert!(removed.changed_requirements.is_empty());
+    }
+
+    #[test]
+    fn course_changes() {
+        let catalog = test_catalog();
+        let mut new = catalog.clone();
+
+        let removed = new.courses.remove(0);
+        new.courses[0].credits_max = Some(new.courses[0].credits_min + 1);
+        new.courses[1].prerequisite_narrative = Some("Consent of instructor".to_string());
+        new.programs[0].requirements = Some(Requirements::SelectTrack);
+
+        let diff = diff_catalogs(&catalog, &new);
+
+        assert_eq!(diff.removed_courses, [CourseSummary::from(&removed)]);
+        assert!(diff.added_courses.is_empty());
+
+        assert_eq!(diff.credit_changes.len(), 1);
+        assert_eq!(diff.credit_changes[0].course.guid, new.courses[0].guid);
+
+        assert_eq!(diff.prerequisite_changes.len(), 1);
+        assert_eq!(
+            diff.prerequisite_changes[0].new_narrative.as_deref(),
+            Some("Consent of instructor")
+        );
+
+        assert_eq!(diff.changed_requirements.len(), 1);
+        assert!(diff.changed_requirements[0].added_courses.is_empty());
+    }
+
+    #[test]
+    fn identical_programs_have_no_diff() {
+        let program = read_program("cs_major.json");
+        let diff = program.diff(&program);
+
+        assert!(diff.is_empty());
+        assert!(diff.to_string().contains("No changes"));
+    }
+
+    #[test]
+    fn program_diff_is_a_tree_of_changes() {
+        let old = read_program("cs_minor.json");
+
+        let mut json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string("../data/cs_minor.json").unwrap())
+                .unwrap();
+        let requirements = &mut json["requirements"]["requirement_list"];
+        requirements[1]["title"] = "Select an upper-level CSC elective".into();
+        // Swap CSC 365 out of its `Or` group for CSC 425 from one of the tracks
+        requirements[0]["course"][5] = requirements[2]["course"][14].clone();
+        let new: Program = serde_json::from_str(&json.to_string()).unwrap();
+
+        let diff = old.diff(&new);
+
+        let [ModuleChange::Modified {
+            title,
+            requirements,
+        }] = diff.modules.as_slice()
+        else {
+            panic!("Expected a single modified module: {diff:#?}");
+        };
+        assert_eq!(title.as_deref(), Some("Degree Requirements"));
+
+        let [RequirementChange::Modified { title, courses }, RequirementChange::Reworded {
+            old_title,
+            new_title,
+        }] = requirements.as_slice()
+        else {
+            panic!("Expected a modified and a reworded requirement: {requirements:#?}");
+        };
+        assert_eq!(title.as_deref(), Some("Minor Requirements:"));
+        assert_eq!(
+            old_title.as_deref(),
+            Some("Select CSC Upper-level Elective: 3 hours")
+        );
+        assert_eq!(
+            new_title.as_deref(),
+            Some("Select an upper-level CSC elective")
+        );
+
+        let [CourseChange::Swapped {
+            old,
+            new,
+            in_choice: true,
+        }] = courses.as_slice()
+        else {
+            panic!("Expected a single swap in an `Or` group: {courses:#?}");
+        };
+        assert_eq!((old.number.as_str(), new.number.as_str()), ("365", "425"));
+
+        let report = diff.to_string();
+        assert!(report.contains("~ Swapped CSC 365 Data Communications and Networking for CSC 425"));
+
+        let markdown = diff.to_markdown();
+        assert!(markdown.starts_with("## Minor in Computer Science"));
+        assert!(markdown.contains("- Changed module **Degree Requirements**"));
+    }
+
+    #[test]
+    fn removed_requirements_remove_modules() {
+        let old = read_program("cs_minor.json");
+        let mut new = old.clone();
+        new.requirements = None;
+
+        assert_eq!(
+            old.diff(&new).modules,
+            [ModuleChange::Removed {
+                title: Some("Degree Requirements".to_string())
+            }]
+        );
+    }
+}
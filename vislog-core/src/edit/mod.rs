@@ -0,0 +1,409 @@
+//! Editing the requirements of a [Program] without breaking the shape of its requirement tree.
+//!
+//! A curriculum editor changing programs through their fields directly can leave behind `Or`
+//! groups without any course, requirements without a list of courses to add to, or modules
+//! without requirements. The methods here make the same changes while keeping the tree the way
+//! parsing makes it:
+//! - `And` and `Or` groups are never empty, and are removed along with their last course
+//! - A course is listed at most once by the same requirement
+//! - Modules with requirements never lose all of them, they are removed along with their last
+//!   requirement
+//!
+//! Every edit returns an [EditOutcome] with the [credits](crate::stats::ProgramStats::credits) of
+//! the program after the edit, so that editors can show them without recomputing them.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{edit::RequirementIndex, Program};
+//! let program_json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+//! let mut program: Program = serde_json::from_str(&program_json).unwrap();
+//!
+//! let first = program.iter_courses().next().unwrap().clone();
+//! let outcome = program.remove_course(first.guid).unwrap();
+//! assert!(outcome.changed > 0);
+//!
+//! program
+//!     .add_course_to_requirement(RequirementIndex::new(0, 0), first)
+//!     .unwrap();
+//! println!("The program now needs {} credits", outcome.credits.min);
+//! ```
+
+use std::{collections::HashSet, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    parsing::guid::Guid, stats::CreditRange, Course, CourseEntries, CourseEntry, Program,
+    Requirement, RequirementModule, Requirements,
+};
+
+pub mod patch;
+pub mod session;
+
+/// Position of a requirement in a program: the index of its module in the
+/// [modules](Requirements::modules) of the program, and its index in the
+/// [requirements](RequirementModule::requirements) of the module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RequirementIndex {
+    pub module: usize,
+    pub requirement: usize,
+}
+
+/// What an edit changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct EditOutcome {
+    /// Number of course entries or requirements that were added, replaced or removed
+    pub changed: usize,
+    /// Credits of the program after the edit
+    pub credits: CreditRange,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum EditError {
+    #[error("no requirement at module {}, requirement {}", .0.module, .0.requirement)]
+    NoSuchRequirement(RequirementIndex),
+    #[error("the requirement at module {}, requirement {} doesn't list courses", .0.module, .0.requirement)]
+    NoCourseList(RequirementIndex),
+    #[error("course {0} is already listed by the requirement")]
+    AlreadyListed(Guid),
+    #[error("course {0} isn't listed by the program")]
+    NotListed(Guid),
+    #[error("program {0} isn't in the catalog")]
+    NoSuchProgram(Guid),
+}
+
+impl RequirementIndex {
+    pub fn new(module: usize, requirement: usize) -> Self {
+        Self {
+            module,
+            requirement,
+        }
+    }
+}
+
+impl Program {
+    /// Lists the `course` at the end of the requirement at `index`. Only `Courses` and
+    /// `SelectFromCourses` requirements list courses.
+    pub fn add_course_to_requirement(
+        &mut self,
+        index: RequirementIndex,
+        course: Course,
+    ) -> Result<EditOutcome, EditError> {
+        let requirement = self
+            .requirement_mut(index)
+            .ok_or(EditError::NoSuchRequirement(index))?;
+        let courses = match requirement {
+            Requirement::Courses { courses, .. } => courses,
+            Requirement::SelectFromCourses { courses, .. } => {
+                courses.get_or_insert_with(CourseEntries::new)
+            }
+            Requirement::Label { .. } | Requirement::ElectivePool { .. } => {
+                return Err(EditError::NoCourseList(index))
+            }
+        };
+        if courses
+            .iter_courses()
+            .any(|listed| listed.guid == course.guid)
+        {
+            return Err(EditError::AlreadyListed(course.guid));
+        }
+
+        courses.push(course.into());
+        Ok(self.outcome(1))
+    }
+
+    /// Lists the `course` in place of every listing of the course with the `guid`. Requirements
+    /// already listing the `course` drop the listing of the replaced course instead.
+    pub fn replace_course(&mut self, guid: Guid, course: Course) -> Result<EditOutcome, EditError> {
+        let course = Arc::new(course);
+        let changed = self.edit_entries(|entries| replace_in(entries, guid, &course));
+
+        match changed {
+            0 => Err(EditError::NotListed(guid)),
+            changed => Ok(self.outcome(changed)),
+        }
+    }
+
+    /// Removes every listing of the course with the `guid`. Requirements left without courses
+    /// are removed.
+    pub fn remove_course(&mut self, guid: Guid) -> Result<EditOutcome, EditError> {
+        let mut emptied = HashSet::new();
+        let mut changed = 0;
+        for (module, requirements) in self.modules_mut().iter_mut().enumerate() {
+            for (requirement, entries) in requirements
+                .requirements_mut()
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(i, requirement)| Some((i, requirement.course_entries_mut()?)))
+            {
+                let removed = remove_from(entries, guid);
+                if removed > 0 && entries.is_empty() {
+                    emptied.insert(RequirementIndex::new(module, requirement));
+                }
+                changed += removed;
+            }
+        }
+        if changed == 0 {
+            return Err(EditError::NotListed(guid));
+        }
+
+        // Later requirements first so that the indices of the others stay the same
+        let mut emptied: Vec<RequirementIndex> = emptied.into_iter().collect();
+        emptied.sort_by(|a, b| {
+            b.module
+                .cmp(&a.module)
+                .then(b.requirement.cmp(&a.requirement))
+        });
+        for index in emptied {
+            self.remove_requirement(index)?;
+        }
+
+        Ok(self.outcome(changed))
+    }
+
+    /// Removes the requirement at `index`, and its module along with it when it was the last
+    /// requirement of the module. Requirements and modules after it move up by one.
+    pub fn remove_requirement(
+        &mut self,
+        index: RequirementIndex,
+    ) -> Result<EditOutcome, EditError> {
+        let module = self
+            .modules_mut()
+            .get_mut(index.module)
+            .ok_or(EditError::NoSuchRequirement(index))?;
+
+        let is_empty = match module {
+            RequirementModule::SingleBasicRequirement { .. } if index.requirement == 0 => true,
+            RequirementModule::BasicRequirements { requirements, .. }
+            | RequirementModule::SelectOneEmphasis {
+                emphases: requirements,
+            } if index.requirement < requirements.len() => {
+                requirements.remove(index.requirement);
+                requirements.is_empty()
+            }
+            _ => return Err(EditError::NoSuchRequirement(index)),
+        };
+        if is_empty {
+            let mut i = 0;
+            self.retain_modules(|_| {
+                i += 1;
+                i - 1 != index.module
+            });
+        }
+
+        Ok(self.outcome(1))
+    }
+
+    fn modules_mut(&mut self) -> &mut [RequirementModule] {
+        match &mut self.requirements {
+            Some(requirements) => requirements.modules_mut(),
+            None => &mut [],
+        }
+    }
+
+    fn requirement_mut(&mut self, index: RequirementIndex) -> Option<&mut Requirement> {
+        self.modules_mut()
+            .get_mut(index.module)?
+            .requirements_mut()
+            .get_mut(index.requirement)
+    }
+
+    /// Runs `edit` on the course list of every requirement, adding up what it returns
+    fn edit_entries(&mut self, mut edit: impl FnMut(&mut CourseEntries) -> usize) -> usize {
+        self.requirements
+            .iter_mut()
+            .flat_map(Requirements::modules_mut)
+            .flat_map(RequirementModule::requirements_mut)
+            .filter_map(Requirement::course_entries_mut)
+            .map(&mut edit)
+            .sum()
+    }
+
+    fn outcome(&self, changed: usize) -> EditOutcome {
+        EditOutcome {
+            changed,
+            credits: self.stats().credits,
+        }
+    }
+}
+
+/// Replaces the listings of `guid` in the `entries` of a requirement
+fn replace_in(entries: &mut CourseEntries, guid: Guid, course: &Arc<Course>) -> usize {
+    if !entries.iter_courses().any(|listed| listed.guid == guid) {
+        return 0;
+    }
+    if course.guid != guid
+        && entries
+            .iter_courses()
+            .any(|listed| listed.guid == course.guid)
+    {
+        return remove_from(entries, guid);
+    }
+
+    replace_entries(entries, guid, course)
+}
+
+fn replace_entries(entries: &mut CourseEntries, guid: Guid, course: &Arc<Course>) -> usize {
+    entries
+        .iter_mut()
+        .map(|entry| match entry {
+            CourseEntry::And(group) | CourseEntry::Or(group) => {
+                replace_entries(group, guid, course)
+            }
+            CourseEntry::Course(listed) if listed.guid == guid => {
+                *listed = course.clone();
+                1
+            }
+            CourseEntry::Course(_) | CourseEntry::Label(_) => 0,
+        })
+        .sum()
+}
+
+/// Removes the listings of `guid` from the `entries`, along with the groups they leave empty
+fn remove_from(entries: &mut CourseEntries, guid: Guid) -> usize {
+    let mut removed = 0;
+    entries.retain_mut(|entry| match entry {
+        CourseEntry::And(group) | CourseEntry::Or(group) => {
+            removed += remove_from(group, guid);
+            !group.is_empty()
+        }
+        CourseEntry::Course(listed) if listed.guid == guid => {
+            removed += 1;
+            false
+        }
+        CourseEntry::Course(_) | CourseEntry::Label(_) => true,
+    });
+
+    removed
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::{cs_major, cs_minor};
+
+    fn has_empty_groups(entries: &CourseEntries) -> bool {
+        entries.iter().any(|entry| match entry {
+            CourseEntry::And(group) | CourseEntry::Or(group) => {
+                group.is_empty() || has_empty_groups(group)
+            }
+            _ => false,
+        })
+    }
+
+    /// Every course of an `Or` group in the program, and the program without them
+    fn without_an_or_group() -> (Program, Vec<Guid>) {
+        let program = cs_minor();
+        let group: Vec<Guid> = program
+            .iter_requirements()
+            .filter_map(Requirement::course_entries)
+            .flat_map(|entries| entries.iter())
+            .find_map(|entry| match entry {
+                CourseEntry::Or(group) => {
+                    Some(group.iter_courses().map(|course| course.guid).collect())
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        let mut edited = program;
+        for guid in &group {
+            edited.remove_course(*guid).unwrap();
+        }
+
+        (edited, group)
+    }
+
+    #[test]
+    fn groups_are_never_left_empty() {
+        let (program, group) = without_an_or_group();
+        assert!(program
+            .iter_courses()
+            .all(|course| !group.contains(&course.guid)));
+        assert!(!program
+            .iter_requirements()
+            .filter_map(Requirement::course_entries)
+            .any(has_empty_groups));
+        assert_eq!(
+            program.clone().remove_course(group[0]),
+            Err(EditError::NotListed(group[0]))
+        );
+    }
+
+    #[test]
+    fn courses_are_added_and_replaced() {
+        let mut program = cs_minor();
+        let credits = program.stats().credits;
+        let first = program.iter_courses().next().unwrap().clone();
+        let index = RequirementIndex::new(0, 0);
+
+        assert_eq!(
+            program.add_course_to_requirement(index, first.clone()),
+            Err(EditError::AlreadyListed(first.guid))
+        );
+
+        let mut other = first.clone();
+        other.guid = Guid::try_from("3F2C8A5E-7D41-4B9A-9E6F-1C0B2D3A4E5F").unwrap();
+        other.credits = (first.credits.0 + 1, None);
+        let outcome = program.replace_course(first.guid, other.clone()).unwrap();
+        assert!(outcome.changed > 0);
+        assert!(outcome.credits.max >= credits.max);
+        assert!(program
+            .iter_courses()
+            .all(|course| course.guid != first.guid));
+
+        // Replacing a course with one the requirement already lists removes it instead
+        program
+            .add_course_to_requirement(index, first.clone())
+            .unwrap();
+        program.replace_course(first.guid, other.clone()).unwrap();
+        let listed = program
+            .requirement_mut(index)
+            .unwrap()
+            .course_entries()
+            .unwrap();
+        assert_eq!(
+            listed
+                .iter_courses()
+                .filter(|course| course.guid == other.guid)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn requirements_are_removed_with_their_module() {
+        let mut program = cs_major();
+        let requirements = program.iter_requirements().count();
+        assert_eq!(
+            program
+                .requirements
+                .iter()
+                .flat_map(Requirements::modules)
+                .count(),
+            1
+        );
+
+        let missing = RequirementIndex::new(0, requirements);
+        assert_eq!(
+            program.remove_requirement(missing),
+            Err(EditError::NoSuchRequirement(missing))
+        );
+
+        let first = RequirementIndex::new(0, 0);
+        program.remove_requirement(first).unwrap();
+        assert_eq!(program.iter_requirements().count(), requirements - 1);
+
+        // Removing the last requirement of the only module leaves the program without modules
+        for _ in 1..requirements {
+            program.remove_requirement(first).unwrap();
+        }
+        assert_eq!(program.requirements, None);
+        assert_eq!(
+            program.remove_requirement(first),
+            Err(EditError::NoSuchRequirement(first))
+        );
+    }
+}
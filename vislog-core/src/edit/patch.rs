@@ -0,0 +1,467 @@
+//! Reviewable patches of the requirements of a [Program].
+//!
+//! A [ProgramPatch] lists the operations turning a program into a proposed version of it, such as
+//! adding a requirement, swapping a course of a requirement for another or changing the credits
+//! of a course. Unlike full program dumps, patches only hold what changed, so that proposals can
+//! be reviewed and kept under version control on their own. [generate_patch] makes the patch
+//! between two versions of a program and [apply_patch] applies it.
+//!
+//! Requirements and courses are written as their [wire](crate::wire) types so that patches can be
+//! read back from JSON. Like them, patches don't carry the [details](crate::Course::details) of
+//! courses. Patches only cover the [requirements](Program::requirements) of a program.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{edit::patch::{apply_patch, generate_patch, ProgramPatch}, Program};
+//! let program_json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+//! let program: Program = serde_json::from_str(&program_json).unwrap();
+//!
+//! let mut proposed = program.clone();
+//! let course = proposed.iter_courses().next().unwrap().guid;
+//! proposed.remove_course(course).unwrap();
+//!
+//! let patch = generate_patch(&program, &proposed);
+//! let json = serde_json::to_string_pretty(&patch).unwrap();
+//!
+//! let patch: ProgramPatch = serde_json::from_str(&json).unwrap();
+//! let mut patched = program.clone();
+//! apply_patch(&mut patched, &patch).unwrap();
+//! assert_eq!(patched, proposed);
+//! ```
+
+use std::{collections::HashMap, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    parsing::guid::Guid,
+    wire::{WireCourse, WireError, WireRequirement, WireRequirements},
+    Course, CourseEntries, CourseEntry, Program, Requirement, RequirementModule, Requirements,
+};
+
+use super::{replace_entries, EditError, RequirementIndex};
+
+/// Operations turning a program into another version of it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramPatch {
+    /// GUID of the patched program
+    pub program: Guid,
+    /// Applied in order, each to the program left by the ones before it
+    pub operations: Vec<PatchOperation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum PatchOperation {
+    /// Inserts the `requirement` at `index`, moving the requirements after it down by one. Only
+    /// modules with a list of requirements can get new ones.
+    AddRequirement {
+        index: RequirementIndex,
+        requirement: WireRequirement,
+    },
+    /// Removes the requirement at `index`, moving the requirements after it up by one. Unlike
+    /// [Program::remove_requirement], the module is kept even when it is left empty.
+    RemoveRequirement { index: RequirementIndex },
+    ReplaceRequirement {
+        index: RequirementIndex,
+        requirement: WireRequirement,
+    },
+    /// Lists the `course` in place of the course with the `guid` in the requirement at `index`
+    SwapCourse {
+        index: RequirementIndex,
+        guid: Guid,
+        course: WireCourse,
+    },
+    /// Changes the credits of every listing of the course with the `guid`
+    ChangeCredits {
+        guid: Guid,
+        credits: (u8, Option<u8>),
+    },
+    /// Replaces all the requirements, for versions of a program whose modules changed
+    ReplaceRequirements {
+        requirements: Option<WireRequirements>,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum PatchError {
+    #[error("the patch is for program {patch}, not {program}")]
+    WrongProgram { patch: Guid, program: Guid },
+    #[error(transparent)]
+    Edit(#[from] EditError),
+    #[error(transparent)]
+    Wire(#[from] WireError),
+}
+
+/// Applies the operations of the `patch` to the `program`. The program is left as it was if any
+/// of them fails.
+pub fn apply_patch(program: &mut Program, patch: &ProgramPatch) -> Result<(), PatchError> {
+    if patch.program != program.guid {
+        return Err(PatchError::WrongProgram {
+            patch: patch.program,
+            program: program.guid,
+        });
+    }
+
+    let mut patched = program.clone();
+    for operation in &patch.operations {
+        operation.apply_to(&mut patched)?;
+    }
+    *program = patched;
+
+    Ok(())
+}
+
+/// Patch turning the requirements of the `old` program into the ones of the `new` program
+pub fn generate_patch(old: &Program, new: &Program) -> ProgramPatch {
+    let mut patch = ProgramPatch {
+        program: old.guid,
+        operations: vec![],
+    };
+    if !same_modules(&old.requirements, &new.requirements) {
+        patch.operations.push(PatchOperation::ReplaceRequirements {
+            requirements: new.requirements.as_ref().map(WireRequirements::from),
+        });
+        return patch;
+    }
+
+    // Operations are made to `patched` as they are generated so that the next ones are generated
+    // against the program they will be applied to
+    let mut patched = old.clone();
+    let mut push = |operation: PatchOperation, patched: &mut Program| {
+        operation
+            .apply_to(patched)
+            .expect("generated operations apply");
+        patch.operations.push(operation);
+    };
+
+    for (guid, credits) in changed_credits(old, new) {
+        push(
+            PatchOperation::ChangeCredits { guid, credits },
+            &mut patched,
+        );
+    }
+
+    let new_modules = new.requirements.iter().flat_map(Requirements::modules);
+    for (module, new_module) in new_modules.enumerate() {
+        let old_requirements = patched.modules_mut()[module].requirements().to_vec();
+        let new_requirements = new_module.requirements();
+
+        let prefix = old_requirements
+            .iter()
+            .zip(new_requirements)
+            .take_while(|(old, new)| old == new)
+            .count();
+        let suffix = old_requirements[prefix..]
+            .iter()
+            .rev()
+            .zip(new_requirements[prefix..].iter().rev())
+            .take_while(|(old, new)| old == new)
+            .count();
+        let old_changed = &old_requirements[prefix..old_requirements.len() - suffix];
+        let new_changed = &new_requirements[prefix..new_requirements.len() - suffix];
+
+        for (i, (old, new)) in old_changed.iter().zip(new_changed).enumerate() {
+            let index = RequirementIndex::new(module, prefix + i);
+            push(requirement_change(index, old, new), &mut patched);
+        }
+        for _ in new_changed.len()..old_changed.len() {
+            let index = RequirementIndex::new(module, prefix + new_changed.len());
+            push(PatchOperation::RemoveRequirement { index }, &mut patched);
+        }
+        for (i, new) in new_changed.iter().enumerate().skip(old_changed.len()) {
+            let operation = PatchOperation::AddRequirement {
+                index: RequirementIndex::new(module, prefix + i),
+                requirement: new.into(),
+            };
+            push(operation, &mut patched);
+        }
+    }
+
+    patch
+}
+
+impl PatchOperation {
+    pub fn apply_to(&self, program: &mut Program) -> Result<(), PatchError> {
+        match self {
+            PatchOperation::AddRequirement { index, requirement } => {
+                let requirements = requirement_list(program, *index)
+                    .filter(|requirements| index.requirement <= requirements.len())
+                    .ok_or(EditError::NoSuchRequirement(*index))?;
+                requirements.insert(index.requirement, requirement.clone().into());
+            }
+            PatchOperation::RemoveRequirement { index } => {
+                let requirements = requirement_list(program, *index)
+                    .filter(|requirements| index.requirement < requirements.len())
+                    .ok_or(EditError::NoSuchRequirement(*index))?;
+                requirements.remove(index.requirement);
+            }
+            PatchOperation::ReplaceRequirement { index, requirement } => {
+                *program
+                    .requirement_mut(*index)
+                    .ok_or(EditError::NoSuchRequirement(*index))? = requirement.clone().into();
+            }
+            PatchOperation::SwapCourse {
+                index,
+                guid,
+                course,
+            } => {
+                let requirement = program
+                    .requirement_mut(*index)
+                    .ok_or(EditError::NoSuchRequirement(*index))?;
+                let entries = requirement
+                    .course_entries_mut()
+                    .ok_or(EditError::NoCourseList(*index))?;
+                let course = Arc::new(Course::from(course.clone()));
+                if replace_entries(entries, *guid, &course) == 0 {
+                    return Err(EditError::NotListed(*guid).into());
+                }
+            }
+            PatchOperation::ChangeCredits { guid, credits } => {
+                let changed = program.edit_entries(|entries| set_credits(entries, *guid, *credits));
+                if changed == 0 {
+                    return Err(EditError::NotListed(*guid).into());
+                }
+            }
+            PatchOperation::ReplaceRequirements { requirements } => {
+                program.requirements = requirements.clone().map(TryInto::try_into).transpose()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The requirements of the module at `index` when they are a list that can grow and shrink
+fn requirement_list(
+    program: &mut Program,
+    index: RequirementIndex,
+) -> Option<&mut Vec<Requirement>> {
+    match program.modules_mut().get_mut(index.module)? {
+        RequirementModule::BasicRequirements { requirements, .. }
+        | RequirementModule::SelectOneEmphasis {
+            emphases: requirements,
+        } => Some(requirements),
+        _ => None,
+    }
+}
+
+/// Whether the programs have the same modules, apart from their requirements
+fn same_modules(old: &Option<Requirements>, new: &Option<Requirements>) -> bool {
+    let same_module = |old: &RequirementModule, new: &RequirementModule| match (old, new) {
+        (
+            RequirementModule::SingleBasicRequirement { title: old, .. },
+            RequirementModule::SingleBasicRequirement { title: new, .. },
+        )
+        | (
+            RequirementModule::BasicRequirements { title: old, .. },
+            RequirementModule::BasicRequirements { title: new, .. },
+        ) => old == new,
+        (
+            RequirementModule::SelectOneEmphasis { .. },
+            RequirementModule::SelectOneEmphasis { .. },
+        ) => true,
+        (old, new) => old == new,
+    };
+
+    match (old, new) {
+        (Some(Requirements::Single(old)), Some(Requirements::Single(new))) => same_module(old, new),
+        (Some(Requirements::Many(old)), Some(Requirements::Many(new))) => {
+            old.len() == new.len() && old.iter().zip(new).all(|(old, new)| same_module(old, new))
+        }
+        (old, new) => old == new,
+    }
+}
+
+/// Credits of the courses listed by both programs that the `new` program lists with other
+/// credits, when it lists all of them with the same credits
+fn changed_credits(old: &Program, new: &Program) -> Vec<(Guid, (u8, Option<u8>))> {
+    let mut new_credits = HashMap::new();
+    for course in new.iter_courses() {
+        new_credits
+            .entry(course.guid)
+            .and_modify(|credits| {
+                if *credits != Some(course.credits) {
+                    *credits = None;
+                }
+            })
+            .or_insert(Some(course.credits));
+    }
+
+    let mut changed = vec![];
+    for course in old.iter_courses() {
+        if let Some(Some(credits)) = new_credits.get(&course.guid) {
+            if *credits != course.credits && !changed.contains(&(course.guid, *credits)) {
+                changed.push((course.guid, *credits));
+            }
+        }
+    }
+
+    changed
+}
+
+/// Operation turning the `old` requirement at `index` into the `new` one, swapping a course when
+/// that is all that changed
+fn requirement_change(
+    index: RequirementIndex,
+    old: &Requirement,
+    new: &Requirement,
+) -> PatchOperation {
+    let replace = PatchOperation::ReplaceRequirement {
+        index,
+        requirement: new.into(),
+    };
+    let (Some(old_entries), Some(new_entries)) = (old.course_entries(), new.course_entries())
+    else {
+        return replace;
+    };
+
+    let removed: Vec<&Course> = old_entries
+        .iter_courses()
+        .filter(|course| {
+            !new_entries
+                .iter_courses()
+                .any(|new| new.guid == course.guid)
+        })
+        .collect();
+    let added: Vec<&Course> = new_entries
+        .iter_courses()
+        .filter(|course| {
+            !old_entries
+                .iter_courses()
+                .any(|old| old.guid == course.guid)
+        })
+        .collect();
+    let ([removed], [added]) = (removed.as_slice(), added.as_slice()) else {
+        return replace;
+    };
+
+    let mut swapped = old.clone();
+    if let Some(entries) = swapped.course_entries_mut() {
+        replace_entries(entries, removed.guid, &Arc::new((*added).clone()));
+    }
+    match swapped == *new {
+        true => PatchOperation::SwapCourse {
+            index,
+            guid: removed.guid,
+            course: (*added).into(),
+        },
+        false => replace,
+    }
+}
+
+fn set_credits(entries: &mut CourseEntries, guid: Guid, credits: (u8, Option<u8>)) -> usize {
+    entries
+        .iter_mut()
+        .map(|entry| match entry {
+            CourseEntry::And(group) | CourseEntry::Or(group) => set_credits(group, guid, credits),
+            CourseEntry::Course(course) if course.guid == guid => {
+                Arc::make_mut(course).credits = credits;
+                1
+            }
+            CourseEntry::Course(_) | CourseEntry::Label(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::cs_major;
+
+    /// Applies the patch from `old` to `new` after a round trip through JSON
+    fn round_trip(old: &Program, new: &Program) -> ProgramPatch {
+        let patch = generate_patch(old, new);
+        let json = serde_json::to_string(&patch).unwrap();
+        let patch: ProgramPatch = serde_json::from_str(&json).unwrap();
+
+        let mut patched = old.clone();
+        apply_patch(&mut patched, &patch).unwrap();
+        assert_eq!(&patched, new);
+
+        patch
+    }
+
+    #[test]
+    fn patches_hold_only_what_changed() {
+        let program = cs_major();
+        assert_eq!(round_trip(&program, &program).operations, []);
+
+        let mut courses = program.iter_courses().cloned();
+        let (first, second) = (courses.next().unwrap(), courses.next().unwrap());
+        let index = RequirementIndex::new(0, 0);
+
+        let mut credits = program.clone();
+        let mut changed = first.clone();
+        changed.credits = (5, None);
+        credits.replace_course(first.guid, changed).unwrap();
+        assert_eq!(
+            round_trip(&program, &credits).operations,
+            [PatchOperation::ChangeCredits {
+                guid: first.guid,
+                credits: (5, None)
+            }]
+        );
+
+        let mut swapped = program.clone();
+        let mut course = second.clone();
+        course.guid = Guid::try_from("00000000-0000-0000-0000-000000000001").unwrap();
+        swapped.replace_course(first.guid, course.clone()).unwrap();
+        let patch = round_trip(&program, &swapped);
+        assert!(patch
+            .operations
+            .iter()
+            .all(|operation| matches!(operation, PatchOperation::SwapCourse { .. })));
+
+        let mut added = program.clone();
+        let requirement = added.requirement_mut(index).unwrap().clone();
+        let Some(RequirementModule::BasicRequirements { requirements, .. }) =
+            added.modules_mut().first_mut()
+        else {
+            panic!("the major has a list of requirements");
+        };
+        requirements.push(requirement.clone());
+        let len = requirements.len();
+        assert_eq!(
+            round_trip(&program, &added).operations,
+            [PatchOperation::AddRequirement {
+                index: RequirementIndex::new(0, len - 1),
+                requirement: (&requirement).into(),
+            }]
+        );
+        round_trip(&added, &program);
+    }
+
+    #[test]
+    fn restructured_programs_replace_their_requirements() {
+        let program = cs_major();
+        let mut emptied = program.clone();
+        emptied.requirements = None;
+        assert!(matches!(
+            round_trip(&program, &emptied).operations[..],
+            [PatchOperation::ReplaceRequirements { requirements: None }]
+        ));
+        round_trip(&emptied, &program);
+
+        let mut other = program.clone();
+        other.guid = Guid::try_from("00000000-0000-0000-0000-000000000001").unwrap();
+        let patch = generate_patch(&program, &emptied);
+        assert!(matches!(
+            apply_patch(&mut other, &patch),
+            Err(PatchError::WrongProgram { .. })
+        ));
+
+        // Failed patches leave the program as it was
+        let mut patch = generate_patch(&program, &program);
+        patch.operations.push(PatchOperation::RemoveRequirement {
+            index: RequirementIndex::new(0, 0),
+        });
+        patch.operations.push(PatchOperation::RemoveRequirement {
+            index: RequirementIndex::new(7, 0),
+        });
+        let mut patched = program.clone();
+        assert!(apply_patch(&mut patched, &patch).is_err());
+        assert_eq!(patched, program);
+    }
+}
@@ -0,0 +1,323 @@
+//! Undoable edits of a [Program], recorded in a [ChangeLog] that outlives the catalog it was made
+//! against.
+//!
+//! Curriculum proposals are worked on for months, during which the catalog is imported again.
+//! An [EditSession] records every [Change] made to its program so that the changes can be undone,
+//! redone, saved as JSON, and [replayed](EditSession::replay) on the same program of a newer
+//! catalog. Replaying picks up the courses of the newer catalog, and changes that no longer
+//! apply are reported as [Conflict]s instead of stopping the replay.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{edit::session::{Change, EditSession}, Program};
+//! let program_json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+//! let program: Program = serde_json::from_str(&program_json).unwrap();
+//! let course = program.iter_courses().next().unwrap().guid;
+//!
+//! let mut session = EditSession::new(program.clone());
+//! session.apply(Change::RemoveCourse { guid: course }).unwrap();
+//! assert_ne!(session.program(), &program);
+//!
+//! session.undo();
+//! assert_eq!(session.program(), &program);
+//! session.redo();
+//!
+//! let json = serde_json::to_string(&session.log()).unwrap();
+//! # let log = serde_json::from_str(&json).unwrap();
+//! # let (replayed, conflicts) = EditSession::replay(program, &log);
+//! # assert_eq!(replayed.program(), session.program());
+//! # assert!(conflicts.is_empty());
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::{catalog::Catalog, parsing::guid::Guid, Course, Program};
+
+use super::{EditError, EditOutcome, RequirementIndex};
+
+/// An edit of a program, with everything needed to make it again
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", content = "data")]
+pub enum Change {
+    /// See [Program::add_course_to_requirement]
+    AddCourse {
+        requirement: RequirementIndex,
+        course: Course,
+    },
+    /// See [Program::replace_course]
+    ReplaceCourse { guid: Guid, course: Course },
+    /// See [Program::remove_course]
+    RemoveCourse { guid: Guid },
+    /// See [Program::remove_requirement]
+    RemoveRequirement { requirement: RequirementIndex },
+}
+
+/// The changes made to a program in an [EditSession], in the order they were made
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ChangeLog {
+    /// GUID of the edited program
+    pub program: Guid,
+    pub changes: Vec<Change>,
+}
+
+/// A change of a [ChangeLog] that couldn't be replayed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// Index of the change in the log
+    pub index: usize,
+    pub change: Change,
+    pub error: EditError,
+}
+
+/// A program along with the changes made to it
+#[derive(Debug, Clone)]
+pub struct EditSession {
+    program: Program,
+    /// Changes made, along with the program as it was before each of them
+    done: Vec<(Change, Program)>,
+    /// Changes undone, along with the program as it was after each of them. The last one was
+    /// undone last.
+    undone: Vec<(Change, Program)>,
+}
+
+impl Change {
+    /// Makes the change to the `program`
+    pub fn apply_to(&self, program: &mut Program) -> Result<EditOutcome, EditError> {
+        match self.clone() {
+            Change::AddCourse {
+                requirement,
+                course,
+            } => program.add_course_to_requirement(requirement, course),
+            Change::ReplaceCourse { guid, course } => program.replace_course(guid, course),
+            Change::RemoveCourse { guid } => program.remove_course(guid),
+            Change::RemoveRequirement { requirement } => program.remove_requirement(requirement),
+        }
+    }
+
+    /// The course the change lists, if any
+    fn course_mut(&mut self) -> Option<&mut Course> {
+        match self {
+            Change::AddCourse { course, .. } | Change::ReplaceCourse { course, .. } => Some(course),
+            Change::RemoveCourse { .. } | Change::RemoveRequirement { .. } => None,
+        }
+    }
+}
+
+impl EditSession {
+    pub fn new(program: Program) -> Self {
+        Self {
+            program,
+            done: vec![],
+            undone: vec![],
+        }
+    }
+
+    /// Makes the `change` to the program, forgetting the changes undone so far. Changes that fail
+    /// leave the program as it was and aren't recorded.
+    pub fn apply(&mut self, change: Change) -> Result<EditOutcome, EditError> {
+        let before = self.program.clone();
+        let outcome = change.apply_to(&mut self.program)?;
+        self.done.push((change, before));
+        self.undone.clear();
+
+        Ok(outcome)
+    }
+
+    /// Undoes the last change, returning it. `None` if there is nothing to undo.
+    pub fn undo(&mut self) -> Option<&Change> {
+        let (change, before) = self.done.pop()?;
+        let after = std::mem::replace(&mut self.program, before);
+        self.undone.push((change, after));
+
+        self.undone.last().map(|(change, _)| change)
+    }
+
+    /// Makes the last undone change again, returning it. `None` if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<&Change> {
+        let (change, after) = self.undone.pop()?;
+        let before = std::mem::replace(&mut self.program, after);
+        self.done.push((change, before));
+
+        self.done.last().map(|(change, _)| change)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// The program with the changes made so far
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    pub fn into_program(self) -> Program {
+        self.program
+    }
+
+    /// Changes made so far, without the ones undone
+    pub fn changes(&self) -> impl Iterator<Item = &Change> {
+        self.done.iter().map(|(change, _)| change)
+    }
+
+    pub fn log(&self) -> ChangeLog {
+        ChangeLog {
+            program: self.program.guid,
+            changes: self.changes().cloned().collect(),
+        }
+    }
+
+    /// Makes the changes of the `log` to the `program`, in order. The changes that fail are
+    /// skipped and returned as [Conflict]s, the others are recorded in the session.
+    pub fn replay(program: Program, log: &ChangeLog) -> (Self, Vec<Conflict>) {
+        let mut session = Self::new(program);
+        let mut conflicts = vec![];
+        for (index, change) in log.changes.iter().enumerate() {
+            if let Err(error) = session.apply(change.clone()) {
+                conflicts.push(Conflict {
+                    index,
+                    change: change.clone(),
+                    error,
+                });
+            }
+        }
+
+        (session, conflicts)
+    }
+
+    /// [Replays](Self::replay) the `log` on its program in the `catalog`, updating the courses it
+    /// lists to the way the catalog lists them. Courses no longer listed by any program of the
+    /// catalog are listed the way they were when the change was made.
+    pub fn replay_on_catalog(
+        catalog: &Catalog,
+        log: &ChangeLog,
+    ) -> Result<(Self, Vec<Conflict>), EditError> {
+        let program = catalog
+            .programs
+            .iter()
+            .find(|program| program.guid == log.program)
+            .ok_or(EditError::NoSuchProgram(log.program))?;
+
+        let mut log = log.clone();
+        for course in log.changes.iter_mut().filter_map(Change::course_mut) {
+            if let Some(current) = catalog
+                .programs
+                .iter()
+                .flat_map(Program::iter_courses)
+                .find(|current| current.guid == course.guid)
+            {
+                *course = current.clone();
+            }
+        }
+
+        Ok(Self::replay(program.clone(), &log))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::cs_major;
+
+    #[test]
+    fn changes_are_undone_and_redone() {
+        let program = cs_major();
+        let mut courses = program.iter_courses().map(|course| course.guid);
+        let (first, second) = (courses.next().unwrap(), courses.next().unwrap());
+
+        let mut session = EditSession::new(program.clone());
+        assert_eq!(session.undo(), None);
+        session.apply(Change::RemoveCourse { guid: first }).unwrap();
+        let removed = session.program().clone();
+        session
+            .apply(Change::RemoveCourse { guid: second })
+            .unwrap();
+
+        // Failed changes aren't recorded
+        assert_eq!(
+            session.apply(Change::RemoveCourse { guid: first }),
+            Err(EditError::NotListed(first))
+        );
+        assert_eq!(session.changes().count(), 2);
+
+        assert_eq!(session.undo(), Some(&Change::RemoveCourse { guid: second }));
+        assert_eq!(session.program(), &removed);
+        session.undo();
+        assert_eq!(session.program(), &program);
+        assert!(!session.can_undo());
+
+        session.redo();
+        assert_eq!(session.program(), &removed);
+        assert!(session.can_redo());
+
+        // Making a change forgets the undone ones
+        session
+            .apply(Change::RemoveRequirement {
+                requirement: RequirementIndex::new(0, 0),
+            })
+            .unwrap();
+        assert!(!session.can_redo());
+        assert_eq!(session.redo(), None);
+    }
+
+    #[test]
+    fn logs_are_replayed_on_newer_catalogs() {
+        let program = cs_major();
+        let mut session = EditSession::new(program.clone());
+        let mut courses = program.iter_courses().cloned();
+        let (first, second) = (courses.next().unwrap(), courses.next().unwrap());
+        session
+            .apply(Change::RemoveCourse { guid: first.guid })
+            .unwrap();
+        session
+            .apply(Change::AddCourse {
+                requirement: RequirementIndex::new(0, 0),
+                course: first.clone(),
+            })
+            .unwrap();
+
+        let json = serde_json::to_string(&session.log()).unwrap();
+        let log: ChangeLog = serde_json::from_str(&json).unwrap();
+        assert_eq!(log, session.log());
+
+        // The newer catalog renamed the course and dropped the second one
+        let mut newer = program.clone();
+        newer.remove_course(second.guid).unwrap();
+        let mut renamed = first.clone();
+        renamed.name = Some("Renamed".to_owned());
+        newer.replace_course(first.guid, renamed.clone()).unwrap();
+        let catalog = Catalog {
+            programs: vec![newer],
+            courses: vec![],
+            shared_modules: vec![],
+        };
+
+        let mut log = log;
+        log.changes.push(Change::RemoveCourse { guid: second.guid });
+        let (replayed, conflicts) = EditSession::replay_on_catalog(&catalog, &log).unwrap();
+        assert_eq!(
+            conflicts,
+            [Conflict {
+                index: 2,
+                change: Change::RemoveCourse { guid: second.guid },
+                error: EditError::NotListed(second.guid),
+            }]
+        );
+        assert_eq!(replayed.changes().count(), 2);
+        assert!(replayed
+            .program()
+            .iter_courses()
+            .any(|course| *course == renamed));
+
+        log.program = first.guid;
+        assert_eq!(
+            EditSession::replay_on_catalog(&catalog, &log).err(),
+            Some(EditError::NoSuchProgram(first.guid))
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Hours of electives that programs leave up to the student, such as "General Electives—18 hours"
+//! or "Electives to total 128 hours".
+//!
+//! The catalog API has no list of courses for these requirements, so they only exist in the title
+//! and `req_narrative` of a requirement. Parsing turns requirements without courses whose text
+//! names a number of elective hours, other than "Select" requirements, into [Requirement::ElectivePool](crate::Requirement), which
+//! [ProgramStats](crate::stats::ProgramStats) and [audits](crate::audit) count towards the hours of
+//! the program.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::electives::ElectiveHours;
+//! assert_eq!(
+//!     ElectiveHours::from_text("General Electives—18 hours:"),
+//!     Some(ElectiveHours::Hours(18))
+//! );
+//! assert_eq!(
+//!     ElectiveHours::from_text("Electives to total 128 hours"),
+//!     Some(ElectiveHours::ToTotal(128))
+//! );
+//! assert_eq!(ElectiveHours::from_text("Applied Music for Elective"), None);
+//! ```
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// How many hours of electives a pool needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", content = "data")]
+pub enum ElectiveHours {
+    /// A number of hours of electives. Ex: "Select 6 hours of SW electives"
+    Hours(u32),
+    /// As many hours of electives as it takes for the program to add up to a total number of
+    /// hours. Ex: "Electives to total 128 hours"
+    ToTotal(u32),
+}
+
+impl ElectiveHours {
+    /// The hours of electives in `text`, `None` when it isn't about electives or doesn't say how
+    /// many hours of them are needed. A range of hours such as "13–14 hours" needs the lower bound.
+    pub fn from_text(text: &str) -> Option<Self> {
+        static ELECTIVES: OnceLock<Regex> = OnceLock::new();
+        static TO_TOTAL: OnceLock<Regex> = OnceLock::new();
+        static HOURS: OnceLock<Regex> = OnceLock::new();
+
+        let text = plain_text(text);
+
+        let electives = ELECTIVES.get_or_init(|| {
+            Regex::new(r"\belectives?\b").expect("Electives pattern should be valid")
+        });
+        if !electives.is_match(&text) {
+            return None;
+        }
+
+        let to_total = TO_TOTAL.get_or_init(|| {
+            Regex::new(
+                r"\bto (?:a )?total (?:of )?(\d+) (?:credit |semester )?(?:hours|hrs|credits)\b",
+            )
+            .expect("Total hours pattern should be valid")
+        });
+        if let Some(captures) = to_total.captures(&text) {
+            return captures[1].parse().ok().map(ElectiveHours::ToTotal);
+        }
+
+        let hours = HOURS.get_or_init(|| {
+            Regex::new(
+                r"\b(\d+)(?: ?(?:-|–|or|to) ?\d+)? (?:credit |semester |elective )?(?:hours|hrs|credits)\b",
+            )
+            .expect("Hours pattern should be valid")
+        });
+        hours
+            .captures(&text)
+            .and_then(|captures| captures[1].parse().ok())
+            .map(ElectiveHours::Hours)
+    }
+}
+
+/// Lowercased `text` without HTML tags, with dashes between words and numbers spaced out and
+/// whitespace collapsed
+fn plain_text(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                plain.push(' ');
+            }
+            _ if in_tag => {}
+            // "Electives—18 hours"
+            '—' | ':' | '(' | ')' | ',' => plain.push(' '),
+            _ => plain.extend(c.to_lowercase()),
+        }
+    }
+
+    plain.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hours_of_catalog_titles() {
+        let cases = [
+            (
+                "General Electives—18 hours:",
+                Some(ElectiveHours::Hours(18)),
+            ),
+            ("ART Electives: 9 hours", Some(ElectiveHours::Hours(9))),
+            ("SOC Electives-12 hours", Some(ElectiveHours::Hours(12))),
+            (
+                "Select 31 hours of General Electives",
+                Some(ElectiveHours::Hours(31)),
+            ),
+            (
+                "Select 24 elective hours from audits",
+                Some(ElectiveHours::Hours(24)),
+            ),
+            (
+                "Lower-Level Electives (100/200 level courses): 13–14 hours",
+                Some(ElectiveHours::Hours(13)),
+            ),
+            (
+                "<p>Free electives to a total of 120 credit hours</p>",
+                Some(ElectiveHours::ToTotal(120)),
+            ),
+            ("Excluded from electives:", None),
+            ("Select 6 hours from the following", None),
+            ("Upper-level MUS elective", None),
+        ];
+
+        for (text, hours) in cases {
+            assert_eq!(ElectiveHours::from_text(text), hours, "{text}");
+        }
+    }
+}
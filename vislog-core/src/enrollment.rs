@@ -0,0 +1,219 @@
+//! Audits of students against the catalog year they follow for each of their programs.
+//!
+//! Students follow the catalog of the year they matriculated in, unless they declared a program
+//! later under a newer catalog: a student who started in 2021 can follow the 2021-2022 catalog
+//! for their major and the 2023-2024 catalog for a minor declared in 2023. Each [Declaration] of
+//! a [Student] names the year it follows, defaulting to the year the student matriculated in, and
+//! [CatalogHistory::audit_student] audits each program as it was in the [catalog in
+//! effect](CatalogHistory::catalog_as_of) that year.
+//!
+//! Programs that didn't exist yet in that year are followed in the first later catalog that has
+//! them, like a student declaring a program as soon as it is offered.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{
+//! #     audit::CompletedCourse,
+//! #     catalog::Catalog,
+//! #     enrollment::{Declaration, Student},
+//! #     history::{CatalogHistory, CatalogYear},
+//! # };
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//! let minor = catalog.programs[0].guid;
+//!
+//! let mut history = CatalogHistory::new();
+//! history.insert(CatalogYear::new(2021), catalog.clone());
+//! history.insert(CatalogYear::new(2023), catalog);
+//!
+//! let student = Student {
+//!     matriculation: CatalogYear::new(2021),
+//!     programs: vec![Declaration::following(minor, CatalogYear::new(2023))],
+//!     completed: vec![CompletedCourse::code("CSC", "115", 3)],
+//! };
+//! let audits = history.audit_student(&student).unwrap();
+//! assert_eq!(audits[0].catalog_year, CatalogYear::new(2023));
+//! ```
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    audit::{self, AuditReport, CompletedCourse},
+    history::{CatalogHistory, CatalogYear},
+    parsing::guid::Guid,
+    Program,
+};
+
+/// A program a student is in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Declaration {
+    pub program: Guid,
+    /// Year of the catalog the student follows for the program. `None` for the year they
+    /// matriculated in.
+    #[serde(default)]
+    pub catalog_year: Option<CatalogYear>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Student {
+    /// Year the student started in
+    pub matriculation: CatalogYear,
+    pub programs: Vec<Declaration>,
+    pub completed: Vec<CompletedCourse>,
+}
+
+/// Audit of a program of a student
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProgramAudit {
+    /// Year of the catalog the program was audited against
+    pub catalog_year: CatalogYear,
+    pub report: AuditReport,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum EnrollmentError {
+    #[error("program {program} isn't in the catalog of {year} or any later one")]
+    NoSuchProgram { program: Guid, year: CatalogYear },
+}
+
+impl Declaration {
+    /// Declaration of a program following the catalog of the year the student matriculated in
+    pub fn new(program: Guid) -> Self {
+        Self {
+            program,
+            catalog_year: None,
+        }
+    }
+
+    /// Declaration of a program following the catalog of the `year`
+    pub fn following(program: Guid, year: CatalogYear) -> Self {
+        Self {
+            program,
+            catalog_year: Some(year),
+        }
+    }
+}
+
+impl Student {
+    /// Year of the catalog the student follows for the `declaration`
+    pub fn catalog_year(&self, declaration: &Declaration) -> CatalogYear {
+        declaration.catalog_year.unwrap_or(self.matriculation)
+    }
+}
+
+impl CatalogHistory {
+    /// The program with the `guid` as a student following the catalog of the `year` has to
+    /// complete it, along with the year of the catalog it is from
+    pub fn effective_program(
+        &self,
+        year: CatalogYear,
+        guid: Guid,
+    ) -> Result<(CatalogYear, &Program), EnrollmentError> {
+        let as_of = self.catalog_as_of(year).and_then(|(year, catalog)| {
+            Some((
+                year,
+                catalog
+                    .programs
+                    .iter()
+                    .find(|program| program.guid == guid)?,
+            ))
+        });
+        let later = || {
+            self.years()
+                .filter(|later| *later > year)
+                .find_map(|later| {
+                    let catalog = self.catalog(later)?;
+                    Some((
+                        later,
+                        catalog
+                            .programs
+                            .iter()
+                            .find(|program| program.guid == guid)?,
+                    ))
+                })
+        };
+
+        as_of.or_else(later).ok_or(EnrollmentError::NoSuchProgram {
+            program: guid,
+            year,
+        })
+    }
+
+    /// Audits every program of the `student` against the catalog they follow for it, in the order
+    /// of their declarations
+    pub fn audit_student(&self, student: &Student) -> Result<Vec<ProgramAudit>, EnrollmentError> {
+        student
+            .programs
+            .iter()
+            .map(|declaration| {
+                let (catalog_year, program) =
+                    self.effective_program(student.catalog_year(declaration), declaration.program)?;
+                Ok(ProgramAudit {
+                    catalog_year,
+                    report: audit::audit(program, &student.completed),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::catalog::Catalog;
+
+    #[test]
+    fn each_program_follows_its_own_catalog() {
+        let (minor_catalog, _) = Catalog::parse_file("../data/cs_minor.json");
+        let (major_catalog, _) = Catalog::parse_file("../data/cs_major.json");
+        let minor = minor_catalog.programs[0].guid;
+        let major = major_catalog.programs[0].guid;
+
+        // The minor changed in 2023, and the major was only added then
+        let mut changed_minor = minor_catalog.clone();
+        changed_minor.programs[0].title = "Minor in Computing".to_owned();
+        changed_minor.programs.extend(major_catalog.programs);
+        let mut history = CatalogHistory::new();
+        history.insert(CatalogYear::new(2021), minor_catalog);
+        history.insert(CatalogYear::new(2023), changed_minor);
+
+        let student = Student {
+            matriculation: CatalogYear::new(2021),
+            programs: vec![
+                Declaration::new(major),
+                Declaration::new(minor),
+                Declaration::following(minor, CatalogYear::new(2023)),
+            ],
+            completed: vec![CompletedCourse::code("CSC", "115", 3)],
+        };
+        let audits = history.audit_student(&student).unwrap();
+        let years: Vec<CatalogYear> = audits.iter().map(|audit| audit.catalog_year).collect();
+        assert_eq!(
+            years,
+            [
+                CatalogYear::new(2023),
+                CatalogYear::new(2021),
+                CatalogYear::new(2023)
+            ]
+        );
+        assert!(audits[1]
+            .report
+            .title
+            .starts_with("Minor in Computer Science"));
+        assert_eq!(audits[2].report.title, "Minor in Computing");
+
+        let json = serde_json::to_string(&student).unwrap();
+        assert_eq!(serde_json::from_str::<Student>(&json).unwrap(), student);
+
+        let unknown = Guid::try_from("00000000-0000-0000-0000-000000000001").unwrap();
+        assert_eq!(
+            history
+                .effective_program(CatalogYear::new(2021), unknown)
+                .err(),
+            Some(EnrollmentError::NoSuchProgram {
+                program: unknown,
+                year: CatalogYear::new(2021)
+            })
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! Structured errors for JSON that fails to parse.
+//!
+//! `Deserialize` implementations can only report errors through the error type of the
+//! deserializer, which for [serde_json] is a message. The visitors of [parsing](crate::parsing)
+//! also keep the [VislogError] behind the message on the current thread, and [from_str] hands it
+//! back along with the path in the JSON where it happened, so that callers can match on what went
+//! wrong instead of on messages. Parsing with `serde_json` directly still works and gives the same
+//! messages.
+//!
+//! [Catalog](crate::catalog::Catalog) parsing reports its errors as [VislogError]s.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{error::{self, VislogError}, Program};
+//! let json = std::fs::read_to_string("../data/cs_minor.json")
+//!     .unwrap()
+//!     .replacen(r#""credits": "3""#, r#""credits": "three""#, 1);
+//!
+//! match error::from_str::<Program>(&json) {
+//!     Err(VislogError::CreditParse { path, input, .. }) => {
+//!         assert_eq!(input, "three");
+//!         assert!(path.starts_with("requirements."));
+//!     }
+//!     other => panic!("unexpected result: {other:?}"),
+//! }
+//! ```
+
+use std::cell::RefCell;
+
+use serde::{de, Deserialize};
+use thiserror::Error;
+
+use crate::parsing::{courses::ParseCoursesError, guid::GUIDParsingError};
+
+/// Why a program or course failed to parse, and where in its JSON
+///
+/// `path` is the path from the root of the parsed JSON to the value that failed to parse, such as
+/// `requirements.requirement_list[2].course[0].credits`. It is empty for errors that didn't come
+/// out of [from_str].
+#[derive(Debug, Error)]
+pub enum VislogError {
+    #[error("invalid credits {input:?}: {reason}{}", at(.path))]
+    CreditParse {
+        path: String,
+        input: String,
+        reason: String,
+    },
+    #[error("invalid GUID {input:?}: {source}{}", at(.path))]
+    GuidParse {
+        path: String,
+        input: String,
+        #[source]
+        source: GUIDParsingError,
+    },
+    /// The `And`, `Or` and group markers of a list of course entries don't make up valid groups
+    #[error("{source}{}", at(.path))]
+    OperatorGrouping {
+        path: String,
+        #[source]
+        source: Box<ParseCoursesError>,
+    },
+    /// A field that is required is either missing or `null`
+    #[error("missing field `{field}`{}", at(.path))]
+    MissingField { path: String, field: String },
+    /// A boolean flag, such as `is_narrative`, that isn't spelled the way the
+    /// [dialect](crate::parsing::dialect) spells booleans
+    #[error("expected a boolean, got {input:?}{}", at(.path))]
+    BoolParse { path: String, input: String },
+    /// Anything else, such as invalid JSON, values of the wrong type or duplicate fields
+    #[error("{source}")]
+    Json {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+fn at(path: &str) -> String {
+    match path {
+        "" | "." => String::new(),
+        path => format!(" at {path}"),
+    }
+}
+
+thread_local! {
+    /// Last error raised by a visitor on the current thread
+    static PENDING: RefCell<Option<VislogError>> = const { RefCell::new(None) };
+}
+
+impl VislogError {
+    /// Path in the JSON to the value that failed to parse
+    pub fn path(&self) -> &str {
+        match self {
+            VislogError::CreditParse { path, .. }
+            | VislogError::GuidParse { path, .. }
+            | VislogError::OperatorGrouping { path, .. }
+            | VislogError::MissingField { path, .. }
+            | VislogError::BoolParse { path, .. }
+            | VislogError::Json { path, .. } => path,
+        }
+    }
+
+    fn with_path(mut self, new_path: String) -> Self {
+        match &mut self {
+            VislogError::CreditParse { path, .. }
+            | VislogError::GuidParse { path, .. }
+            | VislogError::OperatorGrouping { path, .. }
+            | VislogError::MissingField { path, .. }
+            | VislogError::BoolParse { path, .. }
+            | VislogError::Json { path, .. } => *path = new_path,
+        }
+        self
+    }
+
+    pub(crate) fn credits(input: &str, reason: impl ToString) -> Self {
+        VislogError::CreditParse {
+            path: String::new(),
+            input: input.to_owned(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub(crate) fn guid(input: &str, source: GUIDParsingError) -> Self {
+        VislogError::GuidParse {
+            path: String::new(),
+            input: input.to_owned(),
+            source,
+        }
+    }
+
+    pub(crate) fn grouping(source: ParseCoursesError) -> Self {
+        VislogError::OperatorGrouping {
+            path: String::new(),
+            source: Box::new(source),
+        }
+    }
+
+    pub(crate) fn missing(field: &str) -> Self {
+        VislogError::MissingField {
+            path: String::new(),
+            field: field.to_owned(),
+        }
+    }
+
+    pub(crate) fn bool(input: &str) -> Self {
+        VislogError::BoolParse {
+            path: String::new(),
+            input: input.to_owned(),
+        }
+    }
+
+    /// Turns the error of a visitor into the error of its deserializer, keeping it for [from_str]
+    pub(crate) fn raise<E: de::Error>(self) -> E {
+        let error = E::custom(&self);
+        PENDING.with(|pending| *pending.borrow_mut() = Some(self));
+        error
+    }
+
+    fn from_path_error(error: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        let path = error.path().to_string();
+        let source = error.into_inner();
+
+        match PENDING.with(RefCell::take) {
+            Some(pending) => pending.with_path(path),
+            None => VislogError::Json { path, source },
+        }
+    }
+}
+
+/// Forgets the error raised by an earlier visitor. Every visitor that raises errors calls this
+/// when it starts, so that errors of visitors whose failure was recovered from, such as a
+/// variant that was tried first, are never reported for a later failure.
+pub(crate) fn clear_pending() {
+    PENDING.with(|pending| pending.borrow_mut().take());
+}
+
+impl From<serde_json::Error> for VislogError {
+    fn from(source: serde_json::Error) -> Self {
+        VislogError::Json {
+            path: String::new(),
+            source,
+        }
+    }
+}
+
+/// Errors of single entries keep the [VislogError] they were raised with
+impl From<ParseCoursesError> for VislogError {
+    fn from(error: ParseCoursesError) -> Self {
+        match error {
+            ParseCoursesError::ParsingError(error) => match error.downcast::<VislogError>() {
+                Ok(error) => error,
+                Err(error) => VislogError::grouping(ParseCoursesError::ParsingError(error)),
+            },
+            error => VislogError::grouping(error),
+        }
+    }
+}
+
+/// Parses `json` into a `T`, failing with the [VislogError] behind the failure
+pub fn from_str<'de, T: Deserialize<'de>>(json: &'de str) -> Result<T, VislogError> {
+    clear_pending();
+
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let value = serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(VislogError::from_path_error)?;
+    deserializer.end()?;
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CourseDetails, Program};
+
+    fn cs_minor() -> String {
+        std::fs::read_to_string("../data/cs_minor.json").unwrap()
+    }
+
+    #[test]
+    fn errors_are_structured() {
+        let json = cs_minor().replacen(r#""guid": "{"#, r#""guid": "{XYZ"#, 1);
+        assert!(matches!(
+            from_str::<Program>(&json),
+            Err(VislogError::GuidParse {
+                source: GUIDParsingError::TooLong,
+                ..
+            })
+        ));
+
+        let json = cs_minor().replacen(r#""url""#, r#""link""#, 1);
+        match from_str::<Program>(&json) {
+            Err(VislogError::MissingField { path, field }) => {
+                assert_eq!(field, "url");
+                assert_eq!(path, ".");
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        let json = cs_minor().replacen(r#""title": "#, r#""title": 42, "x": "#, 1);
+        assert!(matches!(
+            from_str::<Program>(&json),
+            Err(VislogError::Json { .. })
+        ));
+    }
+
+    #[test]
+    fn messages_match_serde_json() {
+        let json = cs_minor().replacen(r#""credits": "3""#, r#""credits": "three""#, 1);
+        let structured = from_str::<Program>(&json).unwrap_err();
+        let plain = serde_json::from_str::<Program>(&json).unwrap_err();
+
+        assert!(plain.to_string().starts_with(&format!(
+            "invalid credits \"three\": {}",
+            match &structured {
+                VislogError::CreditParse { reason, .. } => reason,
+                other => panic!("unexpected error: {other:?}"),
+            }
+        )));
+        assert!(structured.to_string().contains(" at requirements."));
+    }
+
+    #[test]
+    fn stale_errors_are_not_reported() {
+        // A failed GUID of an earlier parse doesn't leak into the next one
+        let json = cs_minor().replacen(r#""guid": "{"#, r#""guid": "{XYZ"#, 1);
+        assert!(from_str::<Program>(&json).is_err());
+
+        let course = std::fs::read_to_string("../data/courses.json").unwrap();
+        assert!(matches!(
+            from_str::<CourseDetails>(&course),
+            Err(VislogError::Json { .. } | VislogError::MissingField { .. })
+        ));
+    }
+
+    #[test]
+    fn errors_of_skipped_entries_are_not_reported() {
+        use crate::parsing::options::{self, ParseOptions};
+
+        // The GUID error of the last entry, which is skipped, is recovered from, unlike the URL
+        // after the requirements
+        let mut json = cs_minor();
+        let guid = json.rfind(r#""guid": "{"#).unwrap() + r#""guid": "{"#.len();
+        json.insert_str(guid, "XYZ");
+        let end = json.rfind('}').unwrap();
+        json.insert_str(end, r#", "url": 42"#);
+        let options = ParseOptions {
+            skip_invalid_entries: true,
+            ..ParseOptions::default()
+        };
+
+        let result = options::with_options(options, || from_str::<Program>(&json));
+        assert!(
+            matches!(result, Err(VislogError::Json { .. })),
+            "{result:?}"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! CSV export with one row for every occurrence of a course in the requirements of a program.
+//!
+//! The output follows RFC 4180, with CRLF line endings and fields quoted only when needed, so it
+//! opens as is in spreadsheet software.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{export::csv, Program};
+//! let program_json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+//! let program: Program = serde_json::from_str(&program_json).unwrap();
+//!
+//! let mut output = vec![];
+//! csv::write_course_rows(&mut output, [&program]).unwrap();
+//!
+//! let output = String::from_utf8(output).unwrap();
+//! assert!(output.starts_with("program,module,requirement,group_type,"));
+//! ```
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::{
+    parsing::guid::Guid,
+    visit::{self, Visit},
+    Course, CourseEntry, Program, Requirement, RequirementModule,
+};
+
+/// Column names of the rows, in order
+pub const HEADER: [&str; 10] = [
+    "program",
+    "module",
+    "requirement",
+    "group_type",
+    "subject",
+    "number",
+    "name",
+    "credits_min",
+    "credits_max",
+    "guid",
+];
+
+/// Operator group directly containing a course
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum GroupType {
+    And,
+    Or,
+}
+
+impl GroupType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GroupType::And => "And",
+            GroupType::Or => "Or",
+        }
+    }
+}
+
+/// A single occurrence of a course in the requirements of a program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CourseRow<'a> {
+    /// Title of the program
+    pub program: &'a str,
+    /// Title of the requirement module containing the course
+    pub module: Option<&'a str>,
+    /// Title of the requirement containing the course
+    pub requirement: Option<&'a str>,
+    /// Innermost operator group containing the course, `None` when the course is listed directly
+    /// in the requirement
+    pub group_type: Option<GroupType>,
+    pub subject: &'a str,
+    pub number: &'a str,
+    pub name: Option<&'a str>,
+    pub credits_min: u8,
+    pub credits_max: Option<u8>,
+    pub guid: Guid,
+}
+
+impl CourseRow<'_> {
+    /// Values of the row in the order of [HEADER]
+    pub fn fields(&self) -> [String; 10] {
+        [
+            self.program.to_owned(),
+            self.module.unwrap_or_default().to_owned(),
+            self.requirement.unwrap_or_default().to_owned(),
+            self.group_type
+                .map(GroupType::as_str)
+                .unwrap_or_default()
+                .to_owned(),
+            self.subject.to_owned(),
+            self.number.to_owned(),
+            self.name.unwrap_or_default().to_owned(),
+            self.credits_min.to_string(),
+            self.credits_max
+                .map(|credits| credits.to_string())
+                .unwrap_or_default(),
+            self.guid.to_string(),
+        ]
+    }
+}
+
+/// Every course occurrence of the `program`, in the order they appear in the catalog
+pub fn course_rows(program: &Program) -> Vec<CourseRow<'_>> {
+    let mut collector = RowCollector {
+        program,
+        module: None,
+        requirement: None,
+        groups: vec![],
+        rows: vec![],
+    };
+    collector.visit_program(program);
+
+    collector.rows
+}
+
+/// Writes the [HEADER] followed by the course rows of every program
+pub fn write_course_rows<'a, W, I>(mut writer: W, programs: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a Program>,
+{
+    write_record(&mut writer, HEADER)?;
+
+    for program in programs {
+        for row in course_rows(program) {
+            write_record(&mut writer, row.fields())?;
+        }
+    }
+
+    writer.flush()
+}
+
+pub(crate) fn write_record<W, I, S>(writer: &mut W, fields: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    for (idx, field) in fields.into_iter().enumerate() {
+        if idx > 0 {
+            writer.write_all(b",")?;
+        }
+        writer.write_all(escape(field.as_ref()).as_bytes())?;
+    }
+
+    writer.write_all(b"\r\n")
+}
+
+/// Quotes the field if it contains a delimiter, a quote or a line break, doubling any quotes
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+struct RowCollector<'a> {
+    program: &'a Program,
+    module: Option<&'a str>,
+    requirement: Option<&'a str>,
+    groups: Vec<GroupType>,
+    rows: Vec<CourseRow<'a>>,
+}
+
+impl<'a> Visit<'a> for RowCollector<'a> {
+    fn visit_requirement_module(&mut self, module: &'a RequirementModule) {
+        self.module = module.title();
+        visit::walk_requirement_module(self, module);
+    }
+
+    fn visit_requirement(&mut self, requirement: &'a Requirement) {
+        self.requirement = requirement.title();
+        visit::walk_requirement(self, requirement);
+    }
+
+    fn visit_course_entry(&mut self, entry: &'a CourseEntry) {
+        let group = match entry {
+            CourseEntry::And(_) => Some(GroupType::And),
+            CourseEntry::Or(_) => Some(GroupType::Or),
+            CourseEntry::Label(_) | CourseEntry::Course(_) => None,
+        };
+
+        if let Some(group) = group {
+            self.groups.push(group);
+        }
+        visit::walk_course_entry(self, entry);
+        if group.is_some() {
+            self.groups.pop();
+        }
+    }
+
+    fn visit_course(&mut self, course: &'a Course) {
+        self.rows.push(CourseRow {
+            program: &self.program.title,
+            module: self.module,
+            requirement: self.requirement,
+            group_type: self.groups.last().copied(),
+            subject: &course.subject_code,
+            number: &course.number,
+            name: course.name.as_deref(),
+            credits_min: course.credits.0,
+            credits_max: course.credits.1,
+            guid: course.guid,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::read_program;
+
+    #[test]
+    fn rows_carry_titles_and_group_types() {
+        let program = read_program("cs_minor.json");
+
+        let rows = course_rows(&program);
+        assert_eq!(rows.len(), program.iter_courses().count());
+
+        let csc_115 = &rows[0];
+        assert_eq!(csc_115.program, "Minor in Computer Science—21 or 22 hours");
+        assert_eq!(csc_115.module, Some("Degree Requirements"));
+        assert_eq!(csc_115.requirement, Some("Minor Requirements:"));
+        assert_eq!(csc_115.group_type, None);
+        assert_eq!((csc_115.subject, csc_115.number), ("CSC", "115"));
+
+        let csc_321 = rows.iter().find(|row| row.number == "321").unwrap();
+        assert_eq!(csc_321.group_type, Some(GroupType::Or));
+    }
+
+    #[test]
+    fn fields_are_escaped() {
+        assert_eq!(escape("CSC"), "CSC");
+        assert_eq!(escape("Ethics, Law"), "\"Ethics, Law\"");
+        assert_eq!(escape("The \"Core\""), "\"The \"\"Core\"\"\"");
+        assert_eq!(escape("a\nb"), "\"a\nb\"");
+    }
+
+    #[test]
+    fn writes_header_and_one_line_per_row() {
+        let program = read_program("cs_minor.json");
+
+        let mut output = vec![];
+        write_course_rows(&mut output, [&program]).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let mut lines = output.split_terminator("\r\n");
+        assert_eq!(lines.next(), Some(HEADER.join(",").as_str()));
+        assert_eq!(
+            lines.next(),
+            Some(
+                "Minor in Computer Science—21 or 22 hours,Degree Requirements,Minor Requirements:,,\
+                 CSC,115,Computer Science: Introduction and Overview,3,,\
+                 860AF9C9-EAD9-45AC-AA92-BAF352C5288C"
+            )
+        );
+        assert_eq!(lines.count(), course_rows(&program).len() - 1);
+    }
+}
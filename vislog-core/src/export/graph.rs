@@ -0,0 +1,558 @@
+//! Requirement and prerequisite graph of a [Program] in standard graph formats, for loading it
+//! into Gephi, Cytoscape or D3 without going through DOT.
+//!
+//! A [RequirementGraph] has a node for every distinct course of the program and for every
+//! requirement listing courses. Edges are typed:
+//! - [EdgeKind::Prerequisite] and [EdgeKind::Corequisite] go from a prerequisite or corequisite
+//!   to the course requiring it, like the edges of a [ProgramGraph](crate::graph::ProgramGraph)
+//! - [EdgeKind::MemberOf] go from a course to a requirement listing it
+//!
+//! Like in a [ProgramGraph](crate::graph::ProgramGraph), requisites outside of the program are not
+//! part of the graph. The graph is written as GraphML with [write_graphml], as GEXF with
+//! [write_gexf] and in the [JSON Graph Format](https://jsongraphformat.info) with
+//! [write_json_graph].
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, export::graph::{self, RequirementGraph}};
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//! let graph = RequirementGraph::new(&catalog.programs[0], &catalog.courses);
+//!
+//! let mut output = vec![];
+//! graph::write_graphml(&mut output, &graph).unwrap();
+//!
+//! let output = String::from_utf8(output).unwrap();
+//! assert!(output.contains(r#"<key id="kind" for="edge" attr.name="kind" attr.type="string"/>"#));
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, Write},
+};
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    graph::{depth_map, PrerequisiteGraph},
+    parsing::guid::Guid,
+    tags::TagMapping,
+    text::clean_narrative,
+    CourseDetails, Program, Requirement,
+};
+
+use super::xml::escape;
+
+/// Courses and requirements of a program, and the typed edges between them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RequirementGraph {
+    pub title: String,
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Node {
+    /// GUID of a course, or "requirement-" followed by the position of a requirement in
+    /// [Program::iter_requirements]
+    pub id: String,
+    pub kind: NodeKind,
+    /// Subject code and number of a course, or the title of a requirement. Ex: "CSC 125"
+    pub label: String,
+    pub name: Option<String>,
+    pub subject_code: Option<String>,
+    /// Ex: 300 for "CSC 315L"
+    pub level: Option<u16>,
+    pub credits_min: Option<u8>,
+    pub credits_max: Option<u8>,
+    /// Prerequisite depth of a course, see [PrerequisiteGraph::depth_of]
+    pub depth: Option<u32>,
+    /// Tags of the course or requirement, see [RequirementGraph::with_tags]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum NodeKind {
+    Course,
+    Requirement,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Edge {
+    pub source: String,
+    pub target: String,
+    pub kind: EdgeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum EdgeKind {
+    Prerequisite,
+    Corequisite,
+    MemberOf,
+}
+
+/// Attributes of the nodes, with their GraphML type. Tags are joined with commas in GraphML and
+/// GEXF.
+const NODE_ATTRIBUTES: [(&str, &str); 9] = [
+    ("kind", "string"),
+    ("label", "string"),
+    ("name", "string"),
+    ("subject_code", "string"),
+    ("level", "int"),
+    ("credits_min", "int"),
+    ("credits_max", "int"),
+    ("depth", "int"),
+    ("tags", "string"),
+];
+
+impl NodeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NodeKind::Course => "course",
+            NodeKind::Requirement => "requirement",
+        }
+    }
+}
+
+impl EdgeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EdgeKind::Prerequisite => "prerequisite",
+            EdgeKind::Corequisite => "corequisite",
+            EdgeKind::MemberOf => "member-of",
+        }
+    }
+}
+
+impl Node {
+    /// Values of the [NODE_ATTRIBUTES] the node has
+    fn attributes(&self) -> Vec<(&'static str, Value)> {
+        let values: [Option<Value>; 9] = [
+            Some(self.kind.as_str().into()),
+            Some(self.label.as_str().into()),
+            self.name.as_deref().map(Value::from),
+            self.subject_code.as_deref().map(Value::from),
+            self.level.map(Value::from),
+            self.credits_min.map(Value::from),
+            self.credits_max.map(Value::from),
+            self.depth.map(Value::from),
+            (!self.tags.is_empty()).then(|| Value::from(self.tags.clone())),
+        ];
+
+        NODE_ATTRIBUTES
+            .iter()
+            .zip(values)
+            .filter_map(|((name, _), value)| Some((*name, value?)))
+            .collect()
+    }
+}
+
+/// Text of an attribute value in XML
+fn attribute_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(values) => values
+            .iter()
+            .map(attribute_text)
+            .collect::<Vec<_>>()
+            .join(","),
+        value => value.to_string(),
+    }
+}
+
+impl RequirementGraph {
+    /// Builds the graph of the `program`, with the requisites of its courses found in the
+    /// catalog `courses`
+    pub fn new(program: &Program, courses: &[CourseDetails]) -> Self {
+        let prerequisites = PrerequisiteGraph::from_course_details(courses);
+        let depths = depth_map(program, &prerequisites);
+        let details: HashMap<Guid, &CourseDetails> =
+            courses.iter().map(|course| (course.guid, course)).collect();
+
+        let mut nodes = vec![];
+        let mut edges = vec![];
+        let mut seen = HashSet::new();
+        let mut guids = vec![];
+        for course in program.iter_courses() {
+            if !seen.insert(course.guid) {
+                continue;
+            }
+            guids.push(course.guid);
+            let code = course.code().ok();
+            nodes.push(Node {
+                id: course.guid.to_string(),
+                kind: NodeKind::Course,
+                label: format!("{} {}", course.subject_code, course.number),
+                name: course.name.as_deref().map(clean_narrative),
+                subject_code: Some(course.subject_code.to_string()),
+                level: code.map(|code| code.level()),
+                credits_min: Some(course.credits.0),
+                credits_max: course.credits.1,
+                depth: depths.get(&course.guid).copied(),
+                tags: vec![],
+            });
+        }
+
+        for guid in &guids {
+            let Some(course) = details.get(guid) else {
+                continue;
+            };
+            let requisites = [
+                (course.prerequisite, EdgeKind::Prerequisite),
+                (course.corequisite, EdgeKind::Corequisite),
+            ];
+            for (requisite, kind) in requisites {
+                if let Some(requisite) = requisite.filter(|requisite| seen.contains(requisite)) {
+                    edges.push(Edge {
+                        source: requisite.to_string(),
+                        target: guid.to_string(),
+                        kind,
+                    });
+                }
+            }
+        }
+
+        for (i, requirement) in program.iter_requirements().enumerate() {
+            let Some(entries) = requirement.course_entries() else {
+                continue;
+            };
+            let id = format!("requirement-{i}");
+            let title = requirement
+                .title()
+                .or(requirement.req_narrative())
+                .map(clean_narrative)
+                .unwrap_or_else(|| format!("Requirement {}", i + 1));
+            nodes.push(Node {
+                id: id.clone(),
+                kind: NodeKind::Requirement,
+                label: title,
+                name: None,
+                subject_code: None,
+                level: None,
+                credits_min: None,
+                credits_max: None,
+                depth: None,
+                tags: vec![],
+            });
+
+            let mut members = HashSet::new();
+            for course in entries.iter_courses() {
+                if members.insert(course.guid) {
+                    edges.push(Edge {
+                        source: course.guid.to_string(),
+                        target: id.clone(),
+                        kind: EdgeKind::MemberOf,
+                    });
+                }
+            }
+        }
+
+        Self {
+            title: program.title.clone(),
+            nodes,
+            edges,
+        }
+    }
+
+    /// Tags the nodes of the graph of the `program` with the `tags` of their course or
+    /// requirement
+    pub fn with_tags(mut self, program: &Program, tags: &TagMapping) -> Self {
+        let requirements: Vec<&Requirement> = program.iter_requirements().collect();
+        for node in &mut self.nodes {
+            let found = match node.kind {
+                NodeKind::Course => match Guid::try_from(node.id.as_str()) {
+                    Ok(guid) => tags.course_tags(&guid),
+                    Err(_) => vec![],
+                },
+                NodeKind::Requirement => node
+                    .id
+                    .strip_prefix("requirement-")
+                    .and_then(|i| requirements.get(i.parse::<usize>().ok()?))
+                    .map(|requirement| tags.tags_of(program, requirement))
+                    .unwrap_or_default(),
+            };
+            node.tags = found.into_iter().map(str::to_owned).collect();
+        }
+
+        self
+    }
+
+    /// The graph in version 2 of the JSON Graph Format, with the attributes of the nodes and the
+    /// kind of the edges as their metadata
+    pub fn to_json_graph(&self) -> Value {
+        let nodes: Map<String, Value> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let metadata: Map<String, Value> = node
+                    .attributes()
+                    .into_iter()
+                    .filter(|(name, _)| *name != "label")
+                    .map(|(name, value)| (name.to_owned(), value))
+                    .collect();
+                (
+                    node.id.clone(),
+                    json!({ "label": node.label, "metadata": metadata }),
+                )
+            })
+            .collect();
+        let edges: Vec<Value> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                json!({
+                    "source": edge.source,
+                    "target": edge.target,
+                    "relation": edge.kind.as_str(),
+                    "directed": true,
+                })
+            })
+            .collect();
+
+        json!({
+            "graph": {
+                "label": self.title,
+                "directed": true,
+                "nodes": nodes,
+                "edges": edges,
+            }
+        })
+    }
+}
+
+/// Writes the `graph` as GraphML, with a `<key>` for every attribute of the nodes and the `kind`
+/// of the edges
+pub fn write_graphml<W: Write>(mut writer: W, graph: &RequirementGraph) -> io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    for (name, kind) in NODE_ATTRIBUTES {
+        writeln!(
+            writer,
+            r#"  <key id="{name}" for="node" attr.name="{name}" attr.type="{kind}"/>"#
+        )?;
+    }
+    writeln!(
+        writer,
+        r#"  <key id="kind" for="edge" attr.name="kind" attr.type="string"/>"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <graph id="{}" edgedefault="directed">"#,
+        escape(&graph.title, true)
+    )?;
+
+    for node in &graph.nodes {
+        writeln!(writer, r#"    <node id="{}">"#, escape(&node.id, true))?;
+        for (name, value) in node.attributes() {
+            writeln!(
+                writer,
+                r#"      <data key="{name}">{}</data>"#,
+                escape(&attribute_text(&value), false)
+            )?;
+        }
+        writeln!(writer, "    </node>")?;
+    }
+    for edge in &graph.edges {
+        writeln!(
+            writer,
+            r#"    <edge source="{}" target="{}"><data key="kind">{}</data></edge>"#,
+            escape(&edge.source, true),
+            escape(&edge.target, true),
+            edge.kind.as_str()
+        )?;
+    }
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")
+}
+
+/// Writes the `graph` as GEXF 1.3, with the attributes of the nodes and the `kind` of the edges as
+/// attribute values
+pub fn write_gexf<W: Write>(mut writer: W, graph: &RequirementGraph) -> io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<gexf xmlns="http://gexf.net/1.3" version="1.3">"#
+    )?;
+    writeln!(
+        writer,
+        "  <meta>\n    <description>{}</description>\n  </meta>",
+        escape(&graph.title, false)
+    )?;
+    writeln!(writer, r#"  <graph defaultedgetype="directed">"#)?;
+
+    writeln!(writer, r#"    <attributes class="node">"#)?;
+    for (name, kind) in NODE_ATTRIBUTES.iter().filter(|(name, _)| *name != "label") {
+        let kind = match *kind {
+            "int" => "integer",
+            kind => kind,
+        };
+        writeln!(
+            writer,
+            r#"      <attribute id="{name}" title="{name}" type="{kind}"/>"#
+        )?;
+    }
+    writeln!(writer, "    </attributes>")?;
+    writeln!(
+        writer,
+        "    <attributes class=\"edge\">\n      <attribute id=\"kind\" title=\"kind\" type=\"string\"/>\n    </attributes>"
+    )?;
+
+    writeln!(writer, "    <nodes>")?;
+    for node in &graph.nodes {
+        writeln!(
+            writer,
+            r#"      <node id="{}" label="{}">"#,
+            escape(&node.id, true),
+            escape(&node.label, true)
+        )?;
+        writeln!(writer, "        <attvalues>")?;
+        for (name, value) in node.attributes() {
+            if name != "label" {
+                writeln!(
+                    writer,
+                    r#"          <attvalue for="{name}" value="{}"/>"#,
+                    escape(&attribute_text(&value), true)
+                )?;
+            }
+        }
+        writeln!(writer, "        </attvalues>\n      </node>")?;
+    }
+    writeln!(writer, "    </nodes>")?;
+
+    writeln!(writer, "    <edges>")?;
+    for (i, edge) in graph.edges.iter().enumerate() {
+        writeln!(
+            writer,
+            r#"      <edge id="{i}" source="{}" target="{}"><attvalues><attvalue for="kind" value="{}"/></attvalues></edge>"#,
+            escape(&edge.source, true),
+            escape(&edge.target, true),
+            edge.kind.as_str()
+        )?;
+    }
+    writeln!(writer, "    </edges>")?;
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</gexf>")
+}
+
+/// Writes the `graph` in the JSON Graph Format. See [RequirementGraph::to_json_graph].
+pub fn write_json_graph<W: Write>(writer: W, graph: &RequirementGraph) -> io::Result<()> {
+    serde_json::to_writer_pretty(writer, &graph.to_json_graph()).map_err(io::Error::from)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::catalog::Catalog;
+
+    fn cs_major() -> RequirementGraph {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let program = catalog
+            .programs
+            .iter()
+            .find(|program| program.title.starts_with("Major in Computer Science"))
+            .unwrap();
+
+        RequirementGraph::new(program, &catalog.courses)
+    }
+
+    #[test]
+    fn nodes_have_attributes_and_edges_are_typed() {
+        let graph = cs_major();
+        let csc_125 = graph
+            .nodes
+            .iter()
+            .find(|node| node.label == "CSC 125")
+            .unwrap();
+        assert_eq!(csc_125.kind, NodeKind::Course);
+        assert_eq!(csc_125.level, Some(100));
+        assert_eq!(csc_125.subject_code.as_deref(), Some("CSC"));
+        assert!(csc_125.credits_min.is_some());
+
+        let ids: HashSet<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(ids.len(), graph.nodes.len());
+        for edge in &graph.edges {
+            assert!(ids.contains(edge.source.as_str()) && ids.contains(edge.target.as_str()));
+        }
+        for kind in [EdgeKind::Prerequisite, EdgeKind::MemberOf] {
+            assert!(graph.edges.iter().any(|edge| edge.kind == kind), "{kind:?}");
+        }
+        assert!(graph
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == EdgeKind::MemberOf)
+            .all(|edge| edge.target.starts_with("requirement-")));
+    }
+
+    #[test]
+    fn graphs_are_written_in_every_format() {
+        let graph = cs_major();
+
+        let mut graphml = vec![];
+        write_graphml(&mut graphml, &graph).unwrap();
+        let graphml = String::from_utf8(graphml).unwrap();
+        assert_eq!(graphml.matches("<node ").count(), graph.nodes.len());
+        assert_eq!(graphml.matches("<edge ").count(), graph.edges.len());
+        assert!(graphml.contains(r#"<data key="kind">prerequisite</data>"#));
+
+        let mut gexf = vec![];
+        write_gexf(&mut gexf, &graph).unwrap();
+        let gexf = String::from_utf8(gexf).unwrap();
+        assert_eq!(gexf.matches("<node ").count(), graph.nodes.len());
+        assert!(gexf.contains(r#"<attvalue for="kind" value="member-of"/>"#));
+
+        let mut json = vec![];
+        write_json_graph(&mut json, &graph).unwrap();
+        let json: Value = serde_json::from_slice(&json).unwrap();
+        let nodes = json["graph"]["nodes"].as_object().unwrap();
+        assert_eq!(nodes.len(), graph.nodes.len());
+        let node = &nodes[&graph.nodes[0].id];
+        assert_eq!(node["metadata"]["kind"], "course");
+        assert!(node["metadata"]["level"].is_u64());
+        assert_eq!(
+            json["graph"]["edges"].as_array().unwrap().len(),
+            graph.edges.len()
+        );
+    }
+
+    #[test]
+    fn tags_are_node_attributes() {
+        let (catalog, _) = Catalog::parse_file("../data/cs_minor.json");
+        let program = &catalog.programs[0];
+        let csc_115 = Guid::try_from("860AF9C9-EAD9-45AC-AA92-BAF352C5288C").unwrap();
+        let mut tags = TagMapping::new();
+        tags.tag_course(csc_115, "intro");
+        tags.tag_course(csc_115, "writing-intensive");
+        tags.tag_requirement(program.guid, "Select one track:", "capstone");
+
+        let graph = RequirementGraph::new(program, &catalog.courses).with_tags(program, &tags);
+        let course = graph
+            .nodes
+            .iter()
+            .find(|node| node.id == csc_115.to_string())
+            .unwrap();
+        assert_eq!(course.tags, ["intro", "writing-intensive"]);
+        assert!(graph
+            .nodes
+            .iter()
+            .any(|node| node.label == "Select one track:" && node.tags == ["capstone"]));
+
+        let mut graphml = vec![];
+        write_graphml(&mut graphml, &graph).unwrap();
+        let graphml = String::from_utf8(graphml).unwrap();
+        assert!(graphml.contains(r#"<data key="tags">intro,writing-intensive</data>"#));
+
+        let json = graph.to_json_graph();
+        let metadata = &json["graph"]["nodes"][&course.id]["metadata"];
+        assert_eq!(metadata["tags"], json!(["intro", "writing-intensive"]));
+    }
+}
@@ -0,0 +1,10 @@
+//! Exports of parsed catalogs to formats other tools can load. Shared modules are written into
+//! every program referring to them, see [shared](crate::shared).
+
+pub mod csv;
+pub mod graph;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+pub mod rdf;
+pub mod sql;
+pub mod xml;
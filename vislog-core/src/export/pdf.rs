@@ -0,0 +1,368 @@
+//! Printable advising sheet of a [Program], as a PDF.
+//!
+//! The sheet lists the requirements of the program with a checkbox next to every course, followed
+//! by a [semester plan](crate::planner) when one is given, so that advisors can check courses off
+//! with a student on paper. Text is set in the standard Helvetica fonts, which every PDF reader
+//! has, and wraps onto as many US Letter pages as needed.
+//!
+//! Names and narratives are [cleaned](crate::text::clean_narrative) of their HTML.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, export::pdf, graph::PrerequisiteGraph, planner};
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//! let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+//! let program = &catalog.programs[0];
+//! let plan = planner::layered_plan(program, &graph, 15, None);
+//!
+//! let mut output = vec![];
+//! pdf::write_advising_sheet(&mut output, program, Some(&plan)).unwrap();
+//!
+//! assert!(output.starts_with(b"%PDF-"));
+//! ```
+
+use std::io::{self, Write};
+
+use printpdf::{
+    BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
+};
+
+use crate::{
+    planner::{LayeredPlan, PlannedCourse},
+    text::clean_narrative,
+    viz::html::credits,
+    CourseEntries, CourseEntry, Program, Requirement, RequirementModule,
+};
+
+/// US Letter
+const PAGE_WIDTH: f32 = 215.9;
+const PAGE_HEIGHT: f32 = 279.4;
+const MARGIN: f32 = 18.0;
+/// Indentation of each level of nesting
+const INDENT: f32 = 6.0;
+/// Millimeters in a point
+const POINT: f32 = 0.3528;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Title,
+    Heading,
+    Subheading,
+    Body,
+    Note,
+}
+
+impl Style {
+    /// Font size in points
+    fn size(self) -> f32 {
+        match self {
+            Style::Title => 16.0,
+            Style::Heading => 13.0,
+            Style::Subheading => 11.0,
+            Style::Body => 10.0,
+            Style::Note => 8.5,
+        }
+    }
+
+    fn is_bold(self) -> bool {
+        matches!(self, Style::Title | Style::Heading | Style::Subheading)
+    }
+}
+
+/// Writes the advising sheet of the `program` as a PDF, with the `plan` of its courses if any
+pub fn write_advising_sheet<W: Write>(
+    mut writer: W,
+    program: &Program,
+    plan: Option<&LayeredPlan>,
+) -> io::Result<()> {
+    let bytes = advising_sheet(program, plan).map_err(io::Error::other)?;
+    writer.write_all(&bytes)
+}
+
+fn advising_sheet(
+    program: &Program,
+    plan: Option<&LayeredPlan>,
+) -> Result<Vec<u8>, printpdf::Error> {
+    let title = clean_narrative(&program.title);
+    let mut sheet = Sheet::new(&title)?;
+
+    sheet.line(&title, Style::Title, 0);
+    sheet.line(
+        "Student: ______________________   Advisor: ______________________   Date: ____________",
+        Style::Body,
+        0,
+    );
+    if let Some(content) = &program.content {
+        sheet.gap();
+        sheet.line(&clean_narrative(content), Style::Note, 0);
+    }
+
+    let modules = program
+        .requirements
+        .iter()
+        .flat_map(|requirements| requirements.modules());
+    for module in modules {
+        write_module(&mut sheet, module);
+    }
+
+    if let Some(plan) = plan {
+        write_plan(&mut sheet, plan);
+    }
+
+    if let Some(bottom_content) = &program.bottom_content {
+        sheet.gap();
+        sheet.line(&clean_narrative(bottom_content), Style::Note, 0);
+    }
+
+    sheet.doc.save_to_bytes()
+}
+
+fn write_module(sheet: &mut Sheet, module: &RequirementModule) {
+    let title = match module {
+        RequirementModule::Unimplemented(_) => return,
+        RequirementModule::SelectOneEmphasis { .. } => {
+            module.title().unwrap_or("Select one emphasis")
+        }
+        _ => module.title().unwrap_or("Requirements"),
+    };
+
+    sheet.gap();
+    sheet.line(&clean_narrative(title), Style::Heading, 0);
+    for requirement in module.requirements() {
+        write_requirement(sheet, requirement);
+    }
+}
+
+fn write_requirement(sheet: &mut Sheet, requirement: &Requirement) {
+    let title = requirement.title().unwrap_or("Requirement");
+    sheet.line(&clean_narrative(title), Style::Subheading, 1);
+
+    if let Some(narrative) = requirement.req_narrative() {
+        sheet.line(&clean_narrative(narrative), Style::Note, 2);
+    }
+    if let Some(entries) = requirement.course_entries() {
+        write_entries(sheet, entries, 2);
+    }
+}
+
+fn write_entries(sheet: &mut Sheet, entries: &CourseEntries, indent: usize) {
+    for entry in entries.iter() {
+        match entry {
+            CourseEntry::Course(course) => {
+                let mut line = format!("[  ] {} {}", course.subject_code, course.number);
+                if let Some(name) = &course.name {
+                    line.push_str("  ");
+                    line.push_str(&clean_narrative(name));
+                }
+                line.push_str(&format!("  ({})", credits(course.credits)));
+                sheet.line(&line, Style::Body, indent);
+            }
+            CourseEntry::Label(label) => {
+                sheet.line(
+                    &format!("[  ] {}", clean_narrative(&label.name)),
+                    Style::Body,
+                    indent,
+                );
+            }
+            CourseEntry::And(group) => {
+                sheet.line("All of:", Style::Note, indent);
+                write_entries(sheet, group, indent + 1);
+            }
+            CourseEntry::Or(group) => {
+                sheet.line("One of:", Style::Note, indent);
+                write_entries(sheet, group, indent + 1);
+            }
+        }
+    }
+}
+
+fn write_plan(sheet: &mut Sheet, plan: &LayeredPlan) {
+    sheet.gap();
+    sheet.line(
+        &format!(
+            "Semester plan (at most {} credits per term)",
+            plan.max_credits
+        ),
+        Style::Heading,
+        0,
+    );
+
+    for (i, term) in plan.terms.iter().enumerate() {
+        let name = match term.term {
+            Some(term) => term.to_string(),
+            None => format!("Term {}", i + 1),
+        };
+        sheet.line(
+            &format!("{name}: {} credits", term.credits),
+            Style::Subheading,
+            1,
+        );
+        if term.courses.is_empty() {
+            sheet.line("No courses", Style::Note, 2);
+        }
+        for course in &term.courses {
+            sheet.line(&planned_course(course), Style::Body, 2);
+        }
+    }
+
+    for (title, courses) in [
+        (
+            "Not planned, because of a prerequisite cycle",
+            &plan.unscheduled,
+        ),
+        ("Never offered by the calendar", &plan.unoffered),
+    ] {
+        if courses.is_empty() {
+            continue;
+        }
+        sheet.line(title, Style::Subheading, 1);
+        for course in courses {
+            sheet.line(&planned_course(course), Style::Body, 2);
+        }
+    }
+}
+
+fn planned_course(course: &PlannedCourse) -> String {
+    let mut line = format!("[  ] {}", course.label);
+    if let Some(name) = &course.name {
+        line.push_str("  ");
+        line.push_str(&clean_narrative(name));
+    }
+    line.push_str(&format!("  ({})", credits((course.credits, None))));
+
+    line
+}
+
+/// PDF being written from the top of its first page down
+struct Sheet {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    /// Height of the next line on the page, from the bottom
+    y: f32,
+}
+
+impl Sheet {
+    fn new(title: &str) -> Result<Self, printpdf::Error> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Sheet");
+        let layer = doc.get_page(page).get_layer(layer);
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+
+        Ok(Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            y: PAGE_HEIGHT - MARGIN,
+        })
+    }
+
+    /// Writes the `text` in the `style`, wrapped to the width of the page, moving on to a new
+    /// page when the current one is full
+    fn line(&mut self, text: &str, style: Style, indent: usize) {
+        let x = MARGIN + INDENT * indent as f32;
+        let height = style.size() * 1.35 * POINT;
+        let font = match style.is_bold() {
+            true => &self.bold,
+            false => &self.regular,
+        };
+
+        for line in wrap(text, max_chars(style, PAGE_WIDTH - MARGIN - x)) {
+            if self.y - height < MARGIN {
+                let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Sheet");
+                self.layer = self.doc.get_page(page).get_layer(layer);
+                self.y = PAGE_HEIGHT - MARGIN;
+            }
+
+            self.y -= height;
+            self.layer
+                .use_text(line, style.size(), Mm(x), Mm(self.y), font);
+        }
+    }
+
+    /// Leaves a blank line before what comes next
+    fn gap(&mut self) {
+        self.y -= Style::Body.size() * POINT;
+    }
+}
+
+/// Number of characters of the `style` that fit in `width` millimeters. Helvetica characters are
+/// about half as wide as they are tall on average.
+fn max_chars(style: Style, width: f32) -> usize {
+    let char_width = style.size() * 0.5 * POINT;
+    ((width / char_width) as usize).max(1)
+}
+
+/// Lines of at most `max_chars` characters, broken between words. Words longer than a line are
+/// broken where they go over.
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        let line_len = line.chars().count();
+        if line_len > 0 && line_len + 1 + word.len() > max_chars {
+            lines.push(std::mem::take(&mut line));
+        }
+        while word.len() > max_chars {
+            let rest = word.split_off(max_chars);
+            lines.push(word.into_iter().collect());
+            word = rest;
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.extend(word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{catalog::Catalog, graph::PrerequisiteGraph, planner};
+
+    #[test]
+    fn lines_wrap_between_words() {
+        assert_eq!(wrap("one two three", 7), ["one two", "three"]);
+        assert_eq!(wrap("  ", 7), [""]);
+        assert_eq!(wrap("a abcdefghij", 4), ["a", "abcd", "efgh", "ij"]);
+        assert!(max_chars(Style::Body, PAGE_WIDTH - 2.0 * MARGIN) > 80);
+    }
+
+    #[test]
+    fn sheets_span_as_many_pages_as_needed() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+        let sheet = |program: &Program, plan: Option<&LayeredPlan>| {
+            let mut output = vec![];
+            write_advising_sheet(&mut output, program, plan).unwrap();
+            assert!(output.starts_with(b"%PDF-"));
+            // Page objects are written uncompressed
+            String::from_utf8_lossy(&output)
+                .matches("/Type/Page/")
+                .count()
+        };
+
+        let minor = catalog
+            .programs
+            .iter()
+            .find(|program| program.title.starts_with("Minor in Computer Science"))
+            .unwrap();
+        assert_eq!(sheet(minor, None), 1);
+
+        let major = catalog
+            .programs
+            .iter()
+            .max_by_key(|program| program.iter_courses().count())
+            .unwrap();
+        let plan = planner::layered_plan(major, &graph, 15, None);
+        assert!(sheet(major, Some(&plan)) > sheet(major, None));
+    }
+}
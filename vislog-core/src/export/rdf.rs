@@ -0,0 +1,391 @@
+//! RDF export of a [Catalog] as [Turtle](https://www.w3.org/TR/turtle/), for linked-data consumers.
+//!
+//! Programs and courses are identified by `urn:uuid:` IRIs built from their GUIDs, so the same
+//! course gets the same IRI in every export. Programs and courses are described with
+//! [schema.org](https://schema.org/) terms, while the requirement tree uses the terms of [VOCAB]:
+//!
+//! ```text
+//! program --vl:hasRequirement--> requirement --vl:includesCourse--> course
+//! ```
+//!
+//! Requirements are blank nodes carrying the title of their module and their own title. Courses
+//! inside of `And` and `Or` groups hang off of `vl:AllOf` and `vl:AnyOf` blank nodes linked with
+//! `vl:includesGroup`. Every course is described once, using the course catalog when it has the
+//! course and what the program lists otherwise.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, export::rdf};
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//!
+//! let mut output = vec![];
+//! rdf::write_turtle(&mut output, &catalog).unwrap();
+//!
+//! let output = String::from_utf8(output).unwrap();
+//! assert!(output.contains("<urn:uuid:814de35b-2cf0-458f-99d4-9100c9d2ca69> a schema:EducationalOccupationalProgram"));
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, Write},
+};
+
+use crate::{
+    catalog::Catalog, electives::ElectiveHours, parsing::guid::Guid, Course, CourseDetails,
+    CourseEntries, CourseEntry, Program, Requirement,
+};
+
+/// Namespace of the terms vislog defines for the requirement tree, bound to the `vl:` prefix
+pub const VOCAB: &str = "https://github.com/UUCompSci/vislog/vocab#";
+
+const PREFIXES: [(&str, &str); 2] = [("schema", "https://schema.org/"), ("vl", VOCAB)];
+
+/// Writes the programs of the `catalog` followed by every course they list and every course of
+/// the course catalog
+pub fn write_turtle<W: Write>(mut writer: W, catalog: &Catalog) -> io::Result<()> {
+    let catalog = &*catalog.inlined();
+    for (prefix, iri) in PREFIXES {
+        writeln!(writer, "@prefix {prefix}: <{iri}> .")?;
+    }
+    writeln!(writer)?;
+
+    let details: HashMap<Guid, &CourseDetails> = catalog
+        .courses
+        .iter()
+        .map(|course| (course.guid, course))
+        .collect();
+    let mut described = HashSet::new();
+
+    for program in &catalog.programs {
+        write_program(&mut writer, program)?;
+
+        for course in program.iter_courses() {
+            if !described.insert(course.guid) {
+                continue;
+            }
+
+            match details.get(&course.guid) {
+                Some(details) => write_course_details(&mut writer, details)?,
+                None => write_course(&mut writer, course)?,
+            }
+        }
+    }
+
+    for course in &catalog.courses {
+        if described.insert(course.guid) {
+            write_course_details(&mut writer, course)?;
+        }
+    }
+
+    writer.flush()
+}
+
+fn write_program<W: Write>(writer: &mut W, program: &Program) -> io::Result<()> {
+    writeln!(
+        writer,
+        "{} a schema:EducationalOccupationalProgram ;",
+        guid_iri(program.guid)
+    )?;
+    writeln!(
+        writer,
+        "    schema:identifier {} ;",
+        literal(&program.guid.to_string())
+    )?;
+    writeln!(writer, "    schema:name {} ;", literal(&program.title))?;
+    if let Some(content) = &program.content {
+        writeln!(writer, "    schema:description {} ;", literal(content))?;
+    }
+
+    let modules = program
+        .requirements
+        .iter()
+        .flat_map(|requirements| requirements.modules());
+    for module in modules {
+        for requirement in module.requirements() {
+            write!(writer, "    vl:hasRequirement ")?;
+            write_requirement(writer, module.title(), requirement, 1)?;
+            writeln!(writer, " ;")?;
+        }
+    }
+
+    writeln!(writer, "    schema:url {} .", iri(&program.url))?;
+    writeln!(writer)
+}
+
+/// Writes the `requirement` as a blank node property list, indented one level deeper than `depth`
+fn write_requirement<W: Write>(
+    writer: &mut W,
+    module_title: Option<&str>,
+    requirement: &Requirement,
+    depth: usize,
+) -> io::Result<()> {
+    let kind = match requirement {
+        Requirement::Courses { .. } => "vl:CoursesRequirement",
+        Requirement::SelectFromCourses { .. } => "vl:SelectFromCoursesRequirement",
+        Requirement::Label { .. } => "vl:LabelRequirement",
+        Requirement::ElectivePool { .. } => "vl:ElectivePoolRequirement",
+    };
+    let indent = "    ".repeat(depth + 1);
+
+    write!(writer, "[\n{indent}a {kind}")?;
+    if let Some(title) = module_title {
+        write!(writer, " ;\n{indent}vl:moduleTitle {}", literal(title))?;
+    }
+    if let Some(title) = requirement.title() {
+        write!(writer, " ;\n{indent}schema:name {}", literal(title))?;
+    }
+    if let Requirement::Label {
+        req_narrative: Some(narrative),
+        ..
+    }
+    | Requirement::ElectivePool {
+        req_narrative: Some(narrative),
+        ..
+    } = requirement
+    {
+        write!(
+            writer,
+            " ;\n{indent}schema:description {}",
+            literal(narrative)
+        )?;
+    }
+    if let Requirement::ElectivePool { hours, .. } = requirement {
+        match hours {
+            ElectiveHours::Hours(hours) => {
+                write!(writer, " ;\n{indent}vl:electiveHours {hours}")?;
+            }
+            ElectiveHours::ToTotal(total) => {
+                write!(writer, " ;\n{indent}vl:electivesToTotal {total}")?;
+            }
+        }
+    }
+    if let Some(entries) = requirement.course_entries() {
+        write_entries(writer, entries, depth + 1)?;
+    }
+
+    write!(writer, "\n{}]", "    ".repeat(depth))
+}
+
+/// Writes the predicates linking a requirement or group to its `entries`
+fn write_entries<W: Write>(
+    writer: &mut W,
+    entries: &CourseEntries,
+    depth: usize,
+) -> io::Result<()> {
+    let indent = "    ".repeat(depth);
+
+    for entry in entries.iter() {
+        match entry {
+            CourseEntry::Course(course) => {
+                write!(
+                    writer,
+                    " ;\n{indent}vl:includesCourse {}",
+                    guid_iri(course.guid)
+                )?;
+            }
+            CourseEntry::Label(label) => {
+                write!(writer, " ;\n{indent}vl:note {}", literal(&label.name))?;
+            }
+            CourseEntry::And(group) | CourseEntry::Or(group) => {
+                let kind = if matches!(entry, CourseEntry::And(_)) {
+                    "vl:AllOf"
+                } else {
+                    "vl:AnyOf"
+                };
+                let inner_indent = "    ".repeat(depth + 1);
+
+                write!(
+                    writer,
+                    " ;\n{indent}vl:includesGroup [\n{inner_indent}a {kind}"
+                )?;
+                write_entries(writer, group, depth + 1)?;
+                write!(writer, "\n{indent}]")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Describes a course that is only known from the requirements of a program
+fn write_course<W: Write>(writer: &mut W, course: &Course) -> io::Result<()> {
+    writeln!(writer, "{} a schema:Course ;", guid_iri(course.guid))?;
+    writeln!(
+        writer,
+        "    schema:identifier {} ;",
+        literal(&course.guid.to_string())
+    )?;
+    writeln!(
+        writer,
+        "    schema:courseCode {} ;",
+        literal(&format!("{} {}", course.subject_code, course.number))
+    )?;
+    if let Some(name) = &course.name {
+        writeln!(writer, "    schema:name {} ;", literal(name))?;
+    }
+    write_credits(writer, course.credits)?;
+    writeln!(writer, "    schema:url {} .", iri(&course.url))?;
+    writeln!(writer)
+}
+
+fn write_course_details<W: Write>(writer: &mut W, course: &CourseDetails) -> io::Result<()> {
+    writeln!(writer, "{} a schema:Course ;", guid_iri(course.guid))?;
+    writeln!(
+        writer,
+        "    schema:identifier {} ;",
+        literal(&course.guid.to_string())
+    )?;
+    writeln!(
+        writer,
+        "    schema:courseCode {} ;",
+        literal(&format!("{} {}", course.subject_code, course.number))
+    )?;
+    writeln!(writer, "    schema:name {} ;", literal(&course.name))?;
+    writeln!(
+        writer,
+        "    schema:description {} ;",
+        literal(&course.description)
+    )?;
+    write_credits(writer, (course.credits_min, course.credits_max))?;
+
+    match (course.prerequisite, &course.prerequisite_narrative) {
+        (Some(guid), _) => writeln!(
+            writer,
+            "    schema:coursePrerequisites {} ;",
+            guid_iri(guid)
+        )?,
+        (None, Some(narrative)) => writeln!(
+            writer,
+            "    schema:coursePrerequisites {} ;",
+            literal(narrative)
+        )?,
+        (None, None) => {}
+    }
+    if let Some(guid) = course.corequisite {
+        writeln!(writer, "    vl:corequisite {} ;", guid_iri(guid))?;
+    }
+
+    writeln!(writer, "    schema:url {} .", iri(&course.url))?;
+    writeln!(writer)
+}
+
+/// `schema:numberOfCredits` for a fixed number of credits, and the bounds otherwise
+fn write_credits<W: Write>(writer: &mut W, credits: (u8, Option<u8>)) -> io::Result<()> {
+    match credits {
+        (credits, None) => writeln!(writer, "    schema:numberOfCredits {credits} ;"),
+        (min, Some(max)) => {
+            writeln!(writer, "    vl:minCredits {min} ;")?;
+            writeln!(writer, "    vl:maxCredits {max} ;")
+        }
+    }
+}
+
+/// IRI of the program or course with the `guid`, following RFC 4122 in using lowercase hex digits
+fn guid_iri(guid: Guid) -> String {
+    format!("<urn:uuid:{}>", guid.to_string().to_lowercase())
+}
+
+/// Percent-encodes the characters that can't appear in an IRI reference
+fn iri(iri: &str) -> String {
+    let mut escaped = String::with_capacity(iri.len() + 2);
+    escaped.push('<');
+
+    for c in iri.chars() {
+        match c {
+            '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\' | '\0'..=' ' => {
+                escaped.push_str(&format!("%{:02X}", c as u32));
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('>');
+    escaped
+}
+
+/// Quoted string literal
+fn literal(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::cs_minor_catalog;
+
+    fn to_turtle(catalog: &Catalog) -> String {
+        let mut output = vec![];
+        write_turtle(&mut output, catalog).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn terms_are_escaped() {
+        assert_eq!(literal("The \"Core\"\n"), r#""The \"Core\"\n""#);
+        assert_eq!(literal("a\\b"), r#""a\\b""#);
+        assert_eq!(
+            iri("https://example.com/a b{c}"),
+            "<https://example.com/a%20b%7Bc%7D>"
+        );
+    }
+
+    #[test]
+    fn requirements_link_programs_to_courses() {
+        let (catalog, _) = Catalog::parse_file("../data/cs_minor.json");
+        let output = to_turtle(&catalog);
+
+        assert_eq!(
+            output.matches("vl:hasRequirement [").count(),
+            catalog.programs[0].iter_requirements().count()
+        );
+        assert!(output.contains(
+            "        vl:includesCourse <urn:uuid:860af9c9-ead9-45ac-aa92-baf352c5288c> ;"
+        ));
+        assert!(output.contains("        vl:includesGroup [\n            a vl:AnyOf ;"));
+
+        // Without a course catalog, courses are described from the program
+        assert!(output.contains(
+            "<urn:uuid:860af9c9-ead9-45ac-aa92-baf352c5288c> a schema:Course ;\n    \
+             schema:identifier \"860AF9C9-EAD9-45AC-AA92-BAF352C5288C\" ;\n    \
+             schema:courseCode \"CSC 115\" ;"
+        ));
+    }
+
+    #[test]
+    fn every_course_is_described_once() {
+        let catalog = cs_minor_catalog();
+        let output = to_turtle(&catalog);
+
+        assert_eq!(
+            output.matches(" a schema:Course ;").count(),
+            catalog.courses.len()
+        );
+        assert_eq!(
+            output
+                .matches(" a schema:EducationalOccupationalProgram ;")
+                .count(),
+            1
+        );
+
+        // Every statement is terminated
+        let blocks: Vec<&str> = output.split("\n\n").filter(|b| !b.is_empty()).collect();
+        assert!(blocks[1..]
+            .iter()
+            .all(|block| block.ends_with(" .") || block.ends_with(" .\n")));
+    }
+}
@@ -0,0 +1,578 @@
+//! SQL export of a [Catalog] into a normalized relational schema, in the PostgreSQL dialect.
+//!
+//! [SCHEMA] creates the tables and [write_data] fills them, either with `INSERT` statements or
+//! with `COPY ... FROM stdin` blocks that `psql` loads much faster. Rows of `requirement_modules`,
+//! `requirements` and `course_entries` get sequential ids in catalog order, so exporting the same
+//! catalog always produces the same output. Course entries keep the subject, number, name and
+//! credits the program lists them with since not every course of a program is in the course
+//! catalog.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, export::sql::{self, DataFormat}};
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//!
+//! let mut output = vec![];
+//! sql::write_sql(&mut output, &catalog, DataFormat::Copy).unwrap();
+//!
+//! let output = String::from_utf8(output).unwrap();
+//! assert!(output.contains("COPY programs (guid, title, url, content, bottom_content) FROM stdin;"));
+//! ```
+
+use std::io::{self, Write};
+
+use crate::{
+    catalog::Catalog, symbol::Symbol, CourseEntries, CourseEntry, Program, Requirement,
+    RequirementModule,
+};
+
+/// `CREATE TABLE` statements for every table of the export
+pub const SCHEMA: &str = "\
+CREATE TABLE programs (
+    guid uuid PRIMARY KEY,
+    title text NOT NULL,
+    url text NOT NULL,
+    content text,
+    bottom_content text
+);
+
+CREATE TABLE requirement_modules (
+    id integer PRIMARY KEY,
+    program_guid uuid NOT NULL REFERENCES programs (guid),
+    position integer NOT NULL,
+    kind text NOT NULL,
+    title text
+);
+
+CREATE TABLE requirements (
+    id integer PRIMARY KEY,
+    module_id integer NOT NULL REFERENCES requirement_modules (id),
+    position integer NOT NULL,
+    kind text NOT NULL,
+    title text,
+    narrative text
+);
+
+CREATE TABLE course_entries (
+    id integer PRIMARY KEY,
+    requirement_id integer NOT NULL REFERENCES requirements (id),
+    -- The `And` or `Or` group containing the entry
+    parent_id integer REFERENCES course_entries (id),
+    position integer NOT NULL,
+    kind text NOT NULL,
+    course_guid uuid,
+    subject_code text,
+    number text,
+    name text,
+    credits_min smallint,
+    credits_max smallint
+);
+
+CREATE TABLE courses (
+    guid uuid PRIMARY KEY,
+    subject_code text NOT NULL,
+    subject_name text,
+    number text NOT NULL,
+    name text NOT NULL,
+    credits_min smallint NOT NULL,
+    credits_max smallint,
+    description text NOT NULL,
+    url text NOT NULL,
+    path text NOT NULL
+);
+
+CREATE TABLE requisites (
+    course_guid uuid NOT NULL REFERENCES courses (guid),
+    -- `prerequisite` or `corequisite`
+    kind text NOT NULL,
+    requisite_guid uuid,
+    narrative text,
+    PRIMARY KEY (course_guid, kind)
+);
+";
+
+/// How rows are written by [write_data]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    /// Multi-row `INSERT` statements, which any client can run
+    Insert,
+    /// `COPY ... FROM stdin` blocks, which `psql` loads much faster
+    Copy,
+}
+
+/// Rows inserted per `INSERT` statement
+const INSERT_BATCH_SIZE: usize = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SqlValue {
+    Null,
+    Int(i64),
+    Text(String),
+}
+
+impl<T: Into<SqlValue>> From<Option<T>> for SqlValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(SqlValue::Null, Into::into)
+    }
+}
+
+impl From<&str> for SqlValue {
+    fn from(value: &str) -> Self {
+        SqlValue::Text(value.to_owned())
+    }
+}
+
+impl From<&String> for SqlValue {
+    fn from(value: &String) -> Self {
+        SqlValue::Text(value.clone())
+    }
+}
+
+impl From<&Symbol> for SqlValue {
+    fn from(value: &Symbol) -> Self {
+        SqlValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for SqlValue {
+    fn from(value: String) -> Self {
+        SqlValue::Text(value)
+    }
+}
+
+impl From<u8> for SqlValue {
+    fn from(value: u8) -> Self {
+        SqlValue::Int(value.into())
+    }
+}
+
+impl From<usize> for SqlValue {
+    fn from(value: usize) -> Self {
+        SqlValue::Int(value as i64)
+    }
+}
+
+struct Table {
+    name: &'static str,
+    columns: &'static [&'static str],
+    rows: Vec<Vec<SqlValue>>,
+}
+
+impl Table {
+    fn new(name: &'static str, columns: &'static [&'static str]) -> Self {
+        Self {
+            name,
+            columns,
+            rows: vec![],
+        }
+    }
+
+    fn push(&mut self, row: Vec<SqlValue>) {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+}
+
+/// Writes [SCHEMA] followed by the data of the `catalog`, all inside of a single transaction
+pub fn write_sql<W: Write>(mut writer: W, catalog: &Catalog, format: DataFormat) -> io::Result<()> {
+    writeln!(writer, "BEGIN;\n")?;
+    writeln!(writer, "{SCHEMA}")?;
+    write_data(&mut writer, catalog, format)?;
+    writeln!(writer, "COMMIT;")?;
+
+    writer.flush()
+}
+
+/// Writes the rows of every table for the `catalog`, in an order that satisfies the foreign keys
+/// of [SCHEMA]
+pub fn write_data<W: Write>(
+    mut writer: W,
+    catalog: &Catalog,
+    format: DataFormat,
+) -> io::Result<()> {
+    for table in tables(&catalog.inlined()) {
+        if table.rows.is_empty() {
+            continue;
+        }
+
+        match format {
+            DataFormat::Insert => write_inserts(&mut writer, &table)?,
+            DataFormat::Copy => write_copy(&mut writer, &table)?,
+        }
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
+
+fn write_inserts<W: Write>(writer: &mut W, table: &Table) -> io::Result<()> {
+    for batch in table.rows.chunks(INSERT_BATCH_SIZE) {
+        writeln!(
+            writer,
+            "INSERT INTO {} ({}) VALUES",
+            table.name,
+            table.columns.join(", ")
+        )?;
+
+        for (idx, row) in batch.iter().enumerate() {
+            let values: Vec<String> = row.iter().map(sql_literal).collect();
+            let terminator = if idx + 1 == batch.len() { ";" } else { "," };
+            writeln!(writer, "    ({}){terminator}", values.join(", "))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_copy<W: Write>(writer: &mut W, table: &Table) -> io::Result<()> {
+    writeln!(
+        writer,
+        "COPY {} ({}) FROM stdin;",
+        table.name,
+        table.columns.join(", ")
+    )?;
+
+    for row in &table.rows {
+        let values: Vec<String> = row.iter().map(copy_field).collect();
+        writeln!(writer, "{}", values.join("\t"))?;
+    }
+
+    writeln!(writer, "\\.")
+}
+
+/// Literal for `INSERT` statements. Relies on `standard_conforming_strings`, the default since
+/// PostgreSQL 9.1, so that backslashes are not escapes.
+fn sql_literal(value: &SqlValue) -> String {
+    match value {
+        SqlValue::Null => "NULL".to_owned(),
+        SqlValue::Int(value) => value.to_string(),
+        SqlValue::Text(value) => format!("'{}'", value.replace('\'', "''")),
+    }
+}
+
+/// Field of the text format of `COPY`
+fn copy_field(value: &SqlValue) -> String {
+    match value {
+        SqlValue::Null => "\\N".to_owned(),
+        SqlValue::Int(value) => value.to_string(),
+        SqlValue::Text(value) => {
+            let mut escaped = String::with_capacity(value.len());
+            for c in value.chars() {
+                match c {
+                    '\\' => escaped.push_str("\\\\"),
+                    '\t' => escaped.push_str("\\t"),
+                    '\n' => escaped.push_str("\\n"),
+                    '\r' => escaped.push_str("\\r"),
+                    c => escaped.push(c),
+                }
+            }
+            escaped
+        }
+    }
+}
+
+/// Every table of the schema filled with the rows of the `catalog`, in dependency order
+fn tables(catalog: &Catalog) -> Vec<Table> {
+    let mut rows = Rows {
+        programs: Table::new(
+            "programs",
+            &["guid", "title", "url", "content", "bottom_content"],
+        ),
+        modules: Table::new(
+            "requirement_modules",
+            &["id", "program_guid", "position", "kind", "title"],
+        ),
+        requirements: Table::new(
+            "requirements",
+            &["id", "module_id", "position", "kind", "title", "narrative"],
+        ),
+        entries: Table::new(
+            "course_entries",
+            &[
+                "id",
+                "requirement_id",
+                "parent_id",
+                "position",
+                "kind",
+                "course_guid",
+                "subject_code",
+                "number",
+                "name",
+                "credits_min",
+                "credits_max",
+            ],
+        ),
+    };
+
+    for program in &catalog.programs {
+        rows.push_program(program);
+    }
+
+    let mut courses = Table::new(
+        "courses",
+        &[
+            "guid",
+            "subject_code",
+            "subject_name",
+            "number",
+            "name",
+            "credits_min",
+            "credits_max",
+            "description",
+            "url",
+            "path",
+        ],
+    );
+    let mut requisites = Table::new(
+        "requisites",
+        &["course_guid", "kind", "requisite_guid", "narrative"],
+    );
+
+    for course in &catalog.courses {
+        courses.push(vec![
+            course.guid.to_string().into(),
+            (&course.subject_code).into(),
+            course.subject_name.as_ref().into(),
+            (&course.number).into(),
+            (&course.name).into(),
+            course.credits_min.into(),
+            course.credits_max.into(),
+            (&course.description).into(),
+            (&course.url).into(),
+            (&course.path).into(),
+        ]);
+
+        let kinds = [
+            (
+                "prerequisite",
+                course.prerequisite,
+                &course.prerequisite_narrative,
+            ),
+            (
+                "corequisite",
+                course.corequisite,
+                &course.corequisite_narrative,
+            ),
+        ];
+        for (kind, guid, narrative) in kinds {
+            if guid.is_none() && narrative.is_none() {
+                continue;
+            }
+
+            requisites.push(vec![
+                course.guid.to_string().into(),
+                kind.into(),
+                guid.map(|guid| guid.to_string()).into(),
+                narrative.as_ref().into(),
+            ]);
+        }
+    }
+
+    vec![
+        rows.programs,
+        rows.modules,
+        rows.requirements,
+        rows.entries,
+        courses,
+        requisites,
+    ]
+}
+
+/// Tables built from the programs, whose ids are the 1-based row numbers
+struct Rows {
+    programs: Table,
+    modules: Table,
+    requirements: Table,
+    entries: Table,
+}
+
+impl Rows {
+    fn push_program(&mut self, program: &Program) {
+        let program_guid = program.guid.to_string();
+
+        self.programs.push(vec![
+            program_guid.clone().into(),
+            (&program.title).into(),
+            (&program.url).into(),
+            program.content.as_ref().into(),
+            program.bottom_content.as_ref().into(),
+        ]);
+
+        let modules = program
+            .requirements
+            .iter()
+            .flat_map(|requirements| requirements.modules());
+
+        for (position, module) in modules.enumerate() {
+            let module_id = self.modules.rows.len() + 1;
+            self.modules.push(vec![
+                module_id.into(),
+                program_guid.clone().into(),
+                position.into(),
+                module_kind(module).into(),
+                module.title().into(),
+            ]);
+
+            for (position, requirement) in module.requirements().iter().enumerate() {
+                self.push_requirement(module_id, position, requirement);
+            }
+        }
+    }
+
+    fn push_requirement(&mut self, module_id: usize, position: usize, requirement: &Requirement) {
+        let requirement_id = self.requirements.rows.len() + 1;
+
+        let (kind, narrative) = match requirement {
+            Requirement::Courses { .. } => ("Courses", None),
+            Requirement::SelectFromCourses { .. } => ("SelectFromCourses", None),
+            Requirement::Label { req_narrative, .. } => ("Label", req_narrative.as_ref()),
+            Requirement::ElectivePool { req_narrative, .. } => {
+                ("ElectivePool", req_narrative.as_ref())
+            }
+        };
+
+        self.requirements.push(vec![
+            requirement_id.into(),
+            module_id.into(),
+            position.into(),
+            kind.into(),
+            requirement.title().into(),
+            narrative.into(),
+        ]);
+
+        if let Some(entries) = requirement.course_entries() {
+            self.push_entries(requirement_id, None, entries);
+        }
+    }
+
+    fn push_entries(
+        &mut self,
+        requirement_id: usize,
+        parent_id: Option<usize>,
+        entries: &CourseEntries,
+    ) {
+        for (position, entry) in entries.iter().enumerate() {
+            let entry_id = self.entries.rows.len() + 1;
+
+            let mut row = vec![
+                entry_id.into(),
+                requirement_id.into(),
+                parent_id.into(),
+                position.into(),
+            ];
+
+            match entry {
+                CourseEntry::And(_) | CourseEntry::Or(_) => {
+                    let kind = if matches!(entry, CourseEntry::And(_)) {
+                        "And"
+                    } else {
+                        "Or"
+                    };
+                    row.push(kind.into());
+                    row.extend(std::iter::repeat_n(SqlValue::Null, 6));
+                }
+                CourseEntry::Course(course) => row.extend([
+                    "Course".into(),
+                    course.guid.to_string().into(),
+                    (&course.subject_code).into(),
+                    (&course.number).into(),
+                    course.name.as_ref().into(),
+                    course.credits.0.into(),
+                    course.credits.1.into(),
+                ]),
+                CourseEntry::Label(label) => row.extend([
+                    "Label".into(),
+                    SqlValue::Null,
+                    label.subject_code.as_ref().into(),
+                    label.number.as_ref().into(),
+                    (&label.name).into(),
+                    label.credits.0.into(),
+                    label.credits.1.into(),
+                ]),
+            }
+
+            self.entries.push(row);
+
+            if let CourseEntry::And(group) | CourseEntry::Or(group) = entry {
+                self.push_entries(requirement_id, Some(entry_id), group);
+            }
+        }
+    }
+}
+
+fn module_kind(module: &RequirementModule) -> &'static str {
+    match module {
+        RequirementModule::SingleBasicRequirement { .. } => "SingleBasicRequirement",
+        RequirementModule::BasicRequirements { .. } => "BasicRequirements",
+        RequirementModule::SelectOneEmphasis { .. } => "SelectOneEmphasis",
+        RequirementModule::Label { .. } => "Label",
+        RequirementModule::Unimplemented(_) => "Unimplemented",
+        RequirementModule::Reference(_) => "Reference",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::cs_minor_catalog;
+
+    #[test]
+    fn literals_are_escaped() {
+        assert_eq!(sql_literal(&"O'Neil".into()), "'O''Neil'");
+        assert_eq!(sql_literal(&SqlValue::Null), "NULL");
+        assert_eq!(copy_field(&"a\tb\\c\nd".into()), "a\\tb\\\\c\\nd");
+        assert_eq!(copy_field(&SqlValue::Null), "\\N");
+    }
+
+    #[test]
+    fn entries_reference_their_group_and_requirement() {
+        let catalog = cs_minor_catalog();
+        let tables = tables(&catalog);
+        let entries = tables.iter().find(|t| t.name == "course_entries").unwrap();
+
+        // CSC 115 is listed directly in the first requirement
+        assert_eq!(entries.rows[0][1], SqlValue::Int(1));
+        assert_eq!(entries.rows[0][2], SqlValue::Null);
+        assert_eq!(entries.rows[0][7], SqlValue::Text("115".to_owned()));
+
+        // Followed by CSC 235 and then the `Or` group of CSC 321 and CSC 365
+        assert_eq!(entries.rows[2][4], SqlValue::Text("Or".to_owned()));
+        assert_eq!(entries.rows[3][2], SqlValue::Int(3));
+        assert_eq!(entries.rows[3][7], SqlValue::Text("321".to_owned()));
+
+        let course_entries = entries
+            .rows
+            .iter()
+            .filter(|row| row[4] == SqlValue::Text("Course".to_owned()))
+            .count();
+        assert_eq!(course_entries, catalog.programs[0].iter_courses().count());
+    }
+
+    #[test]
+    fn every_format_writes_every_row() {
+        let catalog = cs_minor_catalog();
+        let row_count: usize = tables(&catalog).iter().map(|t| t.rows.len()).sum();
+
+        let mut copy = vec![];
+        write_data(&mut copy, &catalog, DataFormat::Copy).unwrap();
+        let copy = String::from_utf8(copy).unwrap();
+        let copy_rows = copy
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with("COPY") && *line != "\\.")
+            .count();
+        assert_eq!(copy_rows, row_count);
+
+        let mut insert = vec![];
+        write_sql(&mut insert, &catalog, DataFormat::Insert).unwrap();
+        let insert = String::from_utf8(insert).unwrap();
+        assert!(insert.starts_with("BEGIN;"));
+        assert!(insert.trim_end().ends_with("COMMIT;"));
+        assert_eq!(
+            insert
+                .lines()
+                .filter(|line| line.starts_with("    ("))
+                .count(),
+            row_count
+        );
+    }
+}
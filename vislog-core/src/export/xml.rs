@@ -0,0 +1,477 @@
+//! XML export of a [Catalog] for systems that ingest IMS-style curriculum data.
+//!
+//! The names of the outer elements and the namespaces are set through [XmlConfig] so that the
+//! output can match what the receiving system expects. Elements are written to the writer as the
+//! catalog is walked, so the document is never held in memory as a whole.
+//!
+//! ```xml
+//! <catalog xmlns="...">
+//!   <programs>
+//!     <program guid="...">
+//!       <title>...</title>
+//!       <url>...</url>
+//!       <requirements kind="Single">
+//!         <module kind="BasicRequirements" title="...">
+//!           <requirement kind="Courses" title="...">
+//!             <course guid="..." subject_code="CSC" number="115" credits_min="3">...</course>
+//!             <or>...</or>
+//!           </requirement>
+//!         </module>
+//!       </requirements>
+//!     </program>
+//!   </programs>
+//!   <courses>
+//!     <course guid="...">...</course>
+//!   </courses>
+//! </catalog>
+//! ```
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, export::xml::{self, Namespace, XmlConfig}};
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//!
+//! let config = XmlConfig {
+//!     namespace: Some(Namespace {
+//!         prefix: Some("cur".to_owned()),
+//!         uri: "urn:example:curriculum".to_owned(),
+//!     }),
+//!     ..Default::default()
+//! };
+//!
+//! let mut output = vec![];
+//! xml::write_xml(&mut output, &catalog, &config).unwrap();
+//!
+//! let output = String::from_utf8(output).unwrap();
+//! assert!(output.contains(r#"<cur:catalog xmlns:cur="urn:example:curriculum">"#));
+//! ```
+
+use std::io::{self, Write};
+
+use crate::{
+    catalog::Catalog, CourseDetails, CourseEntries, CourseEntry, Program, Requirement,
+    RequirementModule, Requirements,
+};
+
+/// A namespace declared on the root element
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Namespace {
+    /// Prefix bound to the namespace. `None` makes it the default namespace.
+    pub prefix: Option<String>,
+    pub uri: String,
+}
+
+/// Names and namespaces of the elements written by [write_xml]. Names must be valid XML names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlConfig {
+    /// Namespace of every element written
+    pub namespace: Option<Namespace>,
+    /// Additional namespaces declared on the root element, such as the ones of schemas the
+    /// receiving system expects to be in scope
+    pub extra_namespaces: Vec<Namespace>,
+    pub root: String,
+    pub programs: String,
+    pub program: String,
+    pub courses: String,
+    pub course: String,
+}
+
+impl Default for XmlConfig {
+    fn default() -> Self {
+        Self {
+            namespace: None,
+            extra_namespaces: vec![],
+            root: "catalog".to_owned(),
+            programs: "programs".to_owned(),
+            program: "program".to_owned(),
+            courses: "courses".to_owned(),
+            course: "course".to_owned(),
+        }
+    }
+}
+
+/// Attributes of an element. Attributes without a value are left out.
+type Attributes<'a> = [(&'a str, Option<String>)];
+
+/// Writes elements as they come, keeping only the names of the open elements
+struct XmlWriter<W> {
+    writer: W,
+    prefix: Option<String>,
+    open: Vec<String>,
+}
+
+impl<W: Write> XmlWriter<W> {
+    fn new(writer: W, namespace: Option<&Namespace>) -> Self {
+        Self {
+            writer,
+            prefix: namespace.and_then(|namespace| namespace.prefix.clone()),
+            open: vec![],
+        }
+    }
+
+    fn qualified(&self, name: &str) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{prefix}:{name}"),
+            None => name.to_owned(),
+        }
+    }
+
+    fn write_tag(&mut self, name: &str, attributes: &Attributes) -> io::Result<()> {
+        write!(
+            self.writer,
+            "{:indent$}<{name}",
+            "",
+            indent = self.open.len() * 2
+        )?;
+        for (key, value) in attributes {
+            if let Some(value) = value {
+                write!(self.writer, " {key}=\"{}\"", escape(value, true))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn start(&mut self, name: &str, attributes: &Attributes) -> io::Result<()> {
+        let name = self.qualified(name);
+        self.write_tag(&name, attributes)?;
+        writeln!(self.writer, ">")?;
+        self.open.push(name);
+
+        Ok(())
+    }
+
+    fn end(&mut self) -> io::Result<()> {
+        let name = self.open.pop().expect("There should be an open element");
+        writeln!(
+            self.writer,
+            "{:indent$}</{name}>",
+            "",
+            indent = self.open.len() * 2
+        )
+    }
+
+    /// An element with only text content, which is left out entirely when there's no text and no
+    /// attributes
+    fn leaf(&mut self, name: &str, attributes: &Attributes, text: Option<&str>) -> io::Result<()> {
+        let has_attributes = attributes.iter().any(|(_, value)| value.is_some());
+        if text.is_none() && !has_attributes {
+            return Ok(());
+        }
+
+        let name = self.qualified(name);
+        self.write_tag(&name, attributes)?;
+        match text {
+            Some(text) => writeln!(self.writer, ">{}</{name}>", escape(text, false)),
+            None => writeln!(self.writer, "/>"),
+        }
+    }
+}
+
+/// Writes the whole `catalog` as a single XML document shaped by the `config`
+pub fn write_xml<W: Write>(writer: W, catalog: &Catalog, config: &XmlConfig) -> io::Result<()> {
+    let catalog = &*catalog.inlined();
+    let mut xml = XmlWriter::new(writer, config.namespace.as_ref());
+
+    writeln!(xml.writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+
+    let declarations: Vec<(String, Option<String>)> = config
+        .namespace
+        .iter()
+        .chain(&config.extra_namespaces)
+        .map(|namespace| {
+            let attribute = match &namespace.prefix {
+                Some(prefix) => format!("xmlns:{prefix}"),
+                None => "xmlns".to_owned(),
+            };
+            (attribute, Some(namespace.uri.clone()))
+        })
+        .collect();
+    let declarations: Vec<(&str, Option<String>)> = declarations
+        .iter()
+        .map(|(attribute, uri)| (attribute.as_str(), uri.clone()))
+        .collect();
+
+    xml.start(&config.root, &declarations)?;
+
+    xml.start(&config.programs, &[])?;
+    for program in &catalog.programs {
+        write_program(&mut xml, program, config)?;
+    }
+    xml.end()?;
+
+    xml.start(&config.courses, &[])?;
+    for course in &catalog.courses {
+        write_course_details(&mut xml, course, config)?;
+    }
+    xml.end()?;
+
+    xml.end()?;
+
+    xml.writer.flush()
+}
+
+fn write_program<W: Write>(
+    xml: &mut XmlWriter<W>,
+    program: &Program,
+    config: &XmlConfig,
+) -> io::Result<()> {
+    xml.start(&config.program, &[("guid", Some(program.guid.to_string()))])?;
+
+    xml.leaf("title", &[], Some(&program.title))?;
+    xml.leaf("url", &[], Some(&program.url))?;
+    xml.leaf("content", &[], program.content.as_deref())?;
+    xml.leaf("bottom_content", &[], program.bottom_content.as_deref())?;
+
+    if let Some(requirements) = &program.requirements {
+        let kind = match requirements {
+            Requirements::Single(_) => "Single",
+            Requirements::Many(_) => "Many",
+            Requirements::SelectTrack => "SelectTrack",
+        };
+
+        xml.start("requirements", &[("kind", Some(kind.to_owned()))])?;
+        for module in requirements.modules() {
+            write_module(xml, module)?;
+        }
+        xml.end()?;
+    }
+
+    xml.end()
+}
+
+fn write_module<W: Write>(xml: &mut XmlWriter<W>, module: &RequirementModule) -> io::Result<()> {
+    let kind = match module {
+        RequirementModule::SingleBasicRequirement { .. } => "SingleBasicRequirement",
+        RequirementModule::BasicRequirements { .. } => "BasicRequirements",
+        RequirementModule::SelectOneEmphasis { .. } => "SelectOneEmphasis",
+        RequirementModule::Label { .. } => "Label",
+        RequirementModule::Unimplemented(_) => "Unimplemented",
+        RequirementModule::Reference(_) => "Reference",
+    };
+
+    xml.start(
+        "module",
+        &[
+            ("kind", Some(kind.to_owned())),
+            ("title", module.title().map(str::to_owned)),
+        ],
+    )?;
+    for requirement in module.requirements() {
+        write_requirement(xml, requirement)?;
+    }
+    xml.end()
+}
+
+fn write_requirement<W: Write>(
+    xml: &mut XmlWriter<W>,
+    requirement: &Requirement,
+) -> io::Result<()> {
+    let (kind, narrative) = match requirement {
+        Requirement::Courses { .. } => ("Courses", None),
+        Requirement::SelectFromCourses { .. } => ("SelectFromCourses", None),
+        Requirement::Label { req_narrative, .. } => ("Label", req_narrative.as_deref()),
+        Requirement::ElectivePool { req_narrative, .. } => {
+            ("ElectivePool", req_narrative.as_deref())
+        }
+    };
+
+    xml.start(
+        "requirement",
+        &[
+            ("kind", Some(kind.to_owned())),
+            ("title", requirement.title().map(str::to_owned)),
+        ],
+    )?;
+    xml.leaf("narrative", &[], narrative)?;
+    if let Some(entries) = requirement.course_entries() {
+        write_entries(xml, entries)?;
+    }
+    xml.end()
+}
+
+fn write_entries<W: Write>(xml: &mut XmlWriter<W>, entries: &CourseEntries) -> io::Result<()> {
+    for entry in entries.iter() {
+        match entry {
+            CourseEntry::And(group) | CourseEntry::Or(group) => {
+                let name = if matches!(entry, CourseEntry::And(_)) {
+                    "and"
+                } else {
+                    "or"
+                };
+                xml.start(name, &[])?;
+                write_entries(xml, group)?;
+                xml.end()?;
+            }
+            CourseEntry::Course(course) => xml.leaf(
+                "course",
+                &[
+                    ("guid", Some(course.guid.to_string())),
+                    ("subject_code", Some(course.subject_code.to_string())),
+                    ("number", Some(course.number.clone())),
+                    ("credits_min", Some(course.credits.0.to_string())),
+                    ("credits_max", course.credits.1.map(|max| max.to_string())),
+                ],
+                course.name.as_deref(),
+            )?,
+            CourseEntry::Label(label) => xml.leaf(
+                "label",
+                &[
+                    ("guid", Some(label.guid.to_string())),
+                    (
+                        "subject_code",
+                        label.subject_code.as_deref().map(str::to_owned),
+                    ),
+                    ("number", label.number.clone()),
+                    ("credits_min", Some(label.credits.0.to_string())),
+                    ("credits_max", label.credits.1.map(|max| max.to_string())),
+                ],
+                Some(&label.name),
+            )?,
+        }
+    }
+
+    Ok(())
+}
+
+fn write_course_details<W: Write>(
+    xml: &mut XmlWriter<W>,
+    course: &CourseDetails,
+    config: &XmlConfig,
+) -> io::Result<()> {
+    xml.start(&config.course, &[("guid", Some(course.guid.to_string()))])?;
+
+    xml.leaf("subject_code", &[], Some(&course.subject_code))?;
+    xml.leaf("subject_name", &[], course.subject_name.as_deref())?;
+    xml.leaf("number", &[], Some(&course.number))?;
+    xml.leaf("name", &[], Some(&course.name))?;
+    xml.leaf(
+        "credits",
+        &[
+            ("min", Some(course.credits_min.to_string())),
+            ("max", course.credits_max.map(|max| max.to_string())),
+        ],
+        None,
+    )?;
+    xml.leaf("description", &[], Some(&course.description))?;
+
+    let requisites = [
+        (
+            "prerequisite",
+            course.prerequisite,
+            &course.prerequisite_narrative,
+        ),
+        (
+            "corequisite",
+            course.corequisite,
+            &course.corequisite_narrative,
+        ),
+    ];
+    for (name, guid, narrative) in requisites {
+        xml.leaf(
+            name,
+            &[("guid", guid.map(|guid| guid.to_string()))],
+            narrative.as_deref(),
+        )?;
+    }
+
+    xml.leaf("url", &[], Some(&course.url))?;
+    xml.leaf("path", &[], Some(&course.path))?;
+
+    xml.end()
+}
+
+/// Escapes markup characters and drops characters that XML 1.0 does not allow. Whitespace other
+/// than spaces is escaped in attributes so that it survives attribute value normalization.
+pub(crate) fn escape(s: &str, is_attribute: bool) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if is_attribute => escaped.push_str("&quot;"),
+            '\t' if is_attribute => escaped.push_str("&#9;"),
+            '\n' if is_attribute => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' | '\n' => escaped.push(c),
+            c if c < ' ' || c == '\u{FFFE}' || c == '\u{FFFF}' => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::cs_minor_catalog;
+
+    fn to_xml(catalog: &Catalog, config: &XmlConfig) -> String {
+        let mut output = vec![];
+        write_xml(&mut output, catalog, config).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn text_and_attributes_are_escaped() {
+        assert_eq!(escape("A & B <C>", false), "A &amp; B &lt;C&gt;");
+        assert_eq!(escape("\"a\"\tb\r\n", false), "\"a\"\tb&#13;\n");
+        assert_eq!(escape("\"a\"\tb\n", true), "&quot;a&quot;&#9;b&#10;");
+        assert_eq!(escape("a\u{0}\u{8}b", false), "ab");
+    }
+
+    #[test]
+    fn every_element_is_closed() {
+        let catalog = cs_minor_catalog();
+        let output = to_xml(&catalog, &XmlConfig::default());
+
+        for name in ["program", "requirements", "module", "requirement", "or"] {
+            assert_eq!(
+                output.matches(&format!("<{name}>")).count()
+                    + output.matches(&format!("<{name} ")).count(),
+                output.matches(&format!("</{name}>")).count(),
+                "<{name}> is not closed"
+            );
+        }
+
+        assert!(output.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<catalog>\n"));
+        assert!(output.ends_with("  </courses>\n</catalog>\n"));
+        assert_eq!(
+            output.matches("\n    <course guid=").count(),
+            catalog.courses.len()
+        );
+    }
+
+    #[test]
+    fn names_and_namespaces_come_from_the_config() {
+        let catalog = cs_minor_catalog();
+        let config = XmlConfig {
+            namespace: Some(Namespace {
+                prefix: Some("cur".to_owned()),
+                uri: "urn:example:curriculum".to_owned(),
+            }),
+            extra_namespaces: vec![Namespace {
+                prefix: Some("xsi".to_owned()),
+                uri: "http://www.w3.org/2001/XMLSchema-instance".to_owned(),
+            }],
+            program: "curriculum".to_owned(),
+            ..Default::default()
+        };
+        let output = to_xml(&catalog, &config);
+
+        assert!(output.contains(
+            "<cur:catalog xmlns:cur=\"urn:example:curriculum\" \
+             xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">"
+        ));
+        assert!(output.contains("<cur:curriculum guid=\""));
+        assert!(output.contains(
+            "<cur:course guid=\"860AF9C9-EAD9-45AC-AA92-BAF352C5288C\" subject_code=\"CSC\" \
+             number=\"115\" credits_min=\"3\">"
+        ));
+        assert!(!output.contains("<program"));
+        assert!(!output.contains("</course"));
+    }
+}
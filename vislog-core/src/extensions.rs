@@ -0,0 +1,203 @@
+//! Fields of the catalog API that vislog doesn't parse yet.
+//!
+//! The catalog API sends more than the data model reads, such as the `credits` of a program or the
+//! `offered` terms of a course, and the vendor adds new fields from time to time. With the
+//! `preserve-unknown` feature, every field that a [Program](crate::Program),
+//! [Requirement](crate::Requirement), [Course](crate::Course) or [CourseDetails](crate::CourseDetails)
+//! doesn't know about is kept in its `extra` [Extensions], so that no
+//! data is lost and new fields can be looked at before vislog supports them. Without the feature,
+//! unknown fields are skipped and `extra` is always empty.
+//!
+//! Extensions are kept by the [wire](crate::wire), [proto](crate::proto) and
+//! [store](crate::store) formats, and left out of JSON output when empty.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::Program;
+//! let json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+//! let program: Program = serde_json::from_str(&json).unwrap();
+//!
+//! // `path` is sent by the catalog API but not part of `Program`
+//! if cfg!(feature = "preserve-unknown") {
+//!     assert!(program.extra["path"].as_str().unwrap().starts_with("/sitecore"));
+//! } else {
+//!     assert!(program.extra.is_empty());
+//! }
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    hash::{Hash, Hasher},
+    ops::{Deref, DerefMut},
+};
+
+use serde::{
+    de::{self, MapAccess},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::Value;
+
+use crate::parsing::options;
+
+/// Unknown fields of an object by name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Extensions(BTreeMap<String, Value>);
+
+impl Extensions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Single line JSON of the fields, or an empty string when there are none. Used by formats
+    /// that can't hold a [Value] as is.
+    #[cfg(any(feature = "proto", feature = "sqlite", test))]
+    pub(crate) fn to_json(&self) -> String {
+        match self.0.is_empty() {
+            true => String::new(),
+            false => serde_json::to_string(&self.0).unwrap_or_default(),
+        }
+    }
+
+    /// Reverse of [Extensions::to_json]
+    #[cfg(any(feature = "proto", feature = "sqlite", test))]
+    pub(crate) fn from_json(json: &str) -> serde_json::Result<Self> {
+        match json.is_empty() {
+            true => Ok(Self::default()),
+            false => serde_json::from_str(json).map(Self),
+        }
+    }
+}
+
+impl Deref for Extensions {
+    type Target = BTreeMap<String, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Extensions {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<BTreeMap<String, Value>> for Extensions {
+    fn from(fields: BTreeMap<String, Value>) -> Self {
+        Self(fields)
+    }
+}
+
+impl FromIterator<(String, Value)> for Extensions {
+    fn from_iter<I: IntoIterator<Item = (String, Value)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// [Value] isn't `Hash`, so values are hashed through their JSON
+impl Hash for Extensions {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.len().hash(state);
+        for (name, value) in &self.0 {
+            name.hash(state);
+            value.to_string().hash(state);
+        }
+    }
+}
+
+impl Serialize for Extensions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Extensions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        BTreeMap::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for Extensions {
+    fn schema_name() -> String {
+        "Extensions".to_owned()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        BTreeMap::<String, Value>::json_schema(gen)
+    }
+}
+
+/// Reads the value of the unknown field `key` into `extra` with the `preserve-unknown` feature,
+/// and skips it otherwise. Fails in [strict](crate::parsing::options::ParseOptions::strict_unknown_fields)
+/// mode when `key` isn't one of the `expected` fields of the catalog API.
+pub(crate) fn next_unknown<'de, A>(
+    map: &mut A,
+    extra: &mut Extensions,
+    key: String,
+    expected: &'static [&'static str],
+) -> Result<(), A::Error>
+where
+    A: MapAccess<'de>,
+{
+    options::check_unknown(&key, expected)?;
+
+    if cfg!(feature = "preserve-unknown") {
+        extra.insert(key, map.next_value()?);
+    } else {
+        map.next_value::<de::IgnoredAny>()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extensions_survive_json() {
+        let extra: Extensions = [
+            ("credits".to_string(), Value::from("42")),
+            ("offered".to_string(), serde_json::json!({"term": ["Fall"]})),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(Extensions::from_json(&extra.to_json()).unwrap(), extra);
+        assert_eq!(Extensions::new().to_json(), "");
+        assert_eq!(Extensions::from_json("").unwrap(), Extensions::new());
+    }
+
+    #[cfg(feature = "preserve-unknown")]
+    #[test]
+    fn unknown_fields_are_kept() {
+        use crate::{catalog::Catalog, Program};
+
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+
+        assert!(catalog
+            .programs
+            .iter()
+            .all(|program| program.extra.contains_key("path")));
+        assert!(catalog
+            .courses
+            .iter()
+            .all(|course| course.extra.contains_key("offered")));
+        assert!(catalog
+            .programs
+            .iter()
+            .flat_map(Program::iter_requirements)
+            .any(|requirement| requirement.extra().contains_key("req_note")));
+        // Every field of a course entry is known
+        assert!(catalog
+            .programs
+            .iter()
+            .flat_map(Program::iter_courses)
+            .all(|course| course.extra.is_empty()));
+    }
+}
@@ -0,0 +1,194 @@
+//! Pruned copies of the requirement tree of a [Program].
+//!
+//! Visualizations focusing on part of a program, such as only the math courses of an engineering
+//! major, need the program without everything else but with the structure of what is left intact:
+//! the same modules, requirements and `And`/`Or` groups, minus the parts that don't match.
+//! [Program::filter] keeps the requirements matching a predicate, and [Program::retain_courses]
+//! the courses matching one.
+//!
+//! Modules left without requirements are removed, and programs left without modules are left
+//! without [requirements](Program::requirements). [References](RequirementModule::Reference) to
+//! shared modules are always kept since their requirements aren't part of the program, so filter
+//! the programs of an [inlined](crate::catalog::Catalog::inlined) catalog to filter those too.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::Program;
+//! let program_json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+//! let program: Program = serde_json::from_str(&program_json).unwrap();
+//!
+//! let mut math = program.clone();
+//! math.retain_courses(|course| &*course.subject_code == "MAT");
+//! assert!(math.iter_courses().all(|course| &*course.subject_code == "MAT"));
+//!
+//! let titled = program.filter(|requirement| requirement.title().is_some());
+//! assert!(titled.iter_requirements().all(|requirement| requirement.title().is_some()));
+//! ```
+
+use crate::{
+    Course, CourseEntries, CourseEntry, Program, Requirement, RequirementModule, Requirements,
+};
+
+impl Program {
+    /// Copy of the program with only the requirements for which `keep` returns `true`
+    pub fn filter(&self, keep: impl FnMut(&Requirement) -> bool) -> Program {
+        let mut program = self.clone();
+        program.retain_requirements(keep);
+
+        program
+    }
+
+    /// Removes the requirements for which `keep` returns `false`
+    pub fn retain_requirements(&mut self, mut keep: impl FnMut(&Requirement) -> bool) {
+        self.retain_modules(|module| retain_in_module(module, &mut keep));
+    }
+
+    /// Removes the course entries for which `keep` returns `false`, along with the `And` and `Or`
+    /// groups left empty. Requirements left without a course are removed, including the ones
+    /// that never had a list of courses, and so are [Label](RequirementModule::Label) modules.
+    pub fn retain_courses(&mut self, mut keep: impl FnMut(&Course) -> bool) {
+        for module in self
+            .requirements
+            .iter_mut()
+            .flat_map(Requirements::modules_mut)
+        {
+            for requirement in module.requirements_mut() {
+                if let Requirement::Courses { courses, .. }
+                | Requirement::SelectFromCourses {
+                    courses: Some(courses),
+                    ..
+                } = requirement
+                {
+                    retain_entries(courses, &mut keep);
+                }
+            }
+        }
+
+        self.retain_requirements(|requirement| {
+            requirement
+                .course_entries()
+                .is_some_and(|entries| entries.iter_courses().next().is_some())
+        });
+        self.retain_modules(|module| {
+            !matches!(
+                module,
+                RequirementModule::Label { .. } | RequirementModule::Unimplemented(_)
+            )
+        });
+    }
+
+    /// Removes the modules for which `keep` returns `false`, leaving programs without modules
+    /// without [requirements](Program::requirements)
+    pub(crate) fn retain_modules(&mut self, mut keep: impl FnMut(&mut RequirementModule) -> bool) {
+        let Some(requirements) = &mut self.requirements else {
+            return;
+        };
+
+        let is_empty = match requirements {
+            Requirements::Single(module) => !keep(module),
+            Requirements::Many(modules) => {
+                modules.retain_mut(keep);
+                modules.is_empty()
+            }
+            Requirements::SelectTrack => false,
+        };
+        if is_empty {
+            self.requirements = None;
+        }
+    }
+}
+
+/// Whether the `module` is kept. Modules that never had requirements are.
+fn retain_in_module(
+    module: &mut RequirementModule,
+    keep: &mut impl FnMut(&Requirement) -> bool,
+) -> bool {
+    match module {
+        RequirementModule::SingleBasicRequirement { requirement, .. } => keep(requirement),
+        RequirementModule::BasicRequirements { requirements, .. }
+        | RequirementModule::SelectOneEmphasis {
+            emphases: requirements,
+        } => {
+            let had_requirements = !requirements.is_empty();
+            requirements.retain(|requirement| keep(requirement));
+            !requirements.is_empty() || !had_requirements
+        }
+        RequirementModule::Label { .. }
+        | RequirementModule::Unimplemented(_)
+        | RequirementModule::Reference(_) => true,
+    }
+}
+
+fn retain_entries(entries: &mut CourseEntries, keep: &mut impl FnMut(&Course) -> bool) {
+    entries.retain_mut(|entry| match entry {
+        CourseEntry::And(group) | CourseEntry::Or(group) => {
+            retain_entries(group, keep);
+            !group.is_empty()
+        }
+        CourseEntry::Label(_) => true,
+        CourseEntry::Course(course) => keep(course),
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::cs_major;
+
+    #[test]
+    fn structure_is_preserved() {
+        let program = cs_major();
+        assert_eq!(program.filter(|_| true), program);
+        let nothing = program.filter(|_| false);
+        assert!(nothing
+            .requirements
+            .iter()
+            .flat_map(Requirements::modules)
+            .all(|module| matches!(
+                module,
+                RequirementModule::Label { .. } | RequirementModule::Unimplemented(_)
+            )));
+
+        let mut everything = program.clone();
+        everything.retain_courses(|_| true);
+        assert_eq!(
+            everything.iter_courses().count(),
+            program.iter_courses().count()
+        );
+
+        // Kept requirements are the same as in the program, in the same order
+        let courses_only = program.filter(|requirement| requirement.course_entries().is_some());
+        let expected: Vec<&Requirement> = program
+            .iter_requirements()
+            .filter(|requirement| requirement.course_entries().is_some())
+            .collect();
+        assert!(courses_only.iter_requirements().eq(expected));
+    }
+
+    #[test]
+    fn only_matching_courses_are_kept() {
+        let program = cs_major();
+        let mut math = program.clone();
+        math.retain_courses(|course| &*course.subject_code == "MAT");
+
+        let expected: Vec<&Course> = program
+            .iter_courses()
+            .filter(|course| &*course.subject_code == "MAT")
+            .collect();
+        assert!(!expected.is_empty());
+        assert!(math.iter_courses().eq(expected));
+        assert!(math.iter_requirements().all(|requirement| requirement
+            .course_entries()
+            .is_some_and(|entries| entries.iter_courses().next().is_some())));
+        assert!(math.iter_requirements().count() < program.iter_requirements().count());
+        assert!(math
+            .requirements
+            .iter()
+            .flat_map(Requirements::modules)
+            .all(|module| !module.requirements().is_empty()));
+
+        let mut none = program;
+        none.retain_courses(|_| false);
+        assert_eq!(none.requirements, None);
+    }
+}
@@ -0,0 +1,125 @@
+use serde::Serialize;
+
+use crate::{
+    visit::{self, Visit},
+    Course, CourseEntry, Program, Requirement, RequirementModule,
+};
+
+/// A [Course] of a [Program] along with where in the requirement tree it came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FlattenedCourse<'a> {
+    pub course: &'a Course,
+    /// Titles of the requirement module and requirement containing the course, outermost first.
+    /// Modules and requirements without a title are left out.
+    pub path: Vec<&'a str>,
+    /// Whether the course is one of several options rather than strictly required, which is the
+    /// case for courses inside of an `Or` group, a `SelectFromCourses` requirement, or a
+    /// `SelectOneEmphasis` module
+    pub is_choice: bool,
+}
+
+impl Program {
+    /// Every [Course] of the program in the order they appear in the catalog, along with the
+    /// titles of the requirements they came from
+    pub fn flattened_courses(&self) -> Vec<FlattenedCourse<'_>> {
+        let mut flattener = Flattener::default();
+        flattener.visit_program(self);
+
+        flattener.courses
+    }
+}
+
+#[derive(Default)]
+struct Flattener<'a> {
+    path: Vec<&'a str>,
+    /// Number of choice groups enclosing the node currently being visited
+    choice_depth: usize,
+    courses: Vec<FlattenedCourse<'a>>,
+}
+
+impl<'a> Flattener<'a> {
+    fn enter(&mut self, title: Option<&'a str>, is_choice: bool, walk: impl FnOnce(&mut Self)) {
+        if let Some(title) = title {
+            self.path.push(title);
+        }
+        if is_choice {
+            self.choice_depth += 1;
+        }
+
+        walk(self);
+
+        if is_choice {
+            self.choice_depth -= 1;
+        }
+        if title.is_some() {
+            self.path.pop();
+        }
+    }
+}
+
+impl<'a> Visit<'a> for Flattener<'a> {
+    fn visit_requirement_module(&mut self, module: &'a RequirementModule) {
+        let is_choice = matches!(module, RequirementModule::SelectOneEmphasis { .. });
+
+        self.enter(module.title(), is_choice, |flattener| {
+            visit::walk_requirement_module(flattener, module)
+        });
+    }
+
+    fn visit_requirement(&mut self, requirement: &'a Requirement) {
+        let is_choice = matches!(requirement, Requirement::SelectFromCourses { .. });
+
+        self.enter(requirement.title(), is_choice, |flattener| {
+            visit::walk_requirement(flattener, requirement)
+        });
+    }
+
+    fn visit_course_entry(&mut self, entry: &'a CourseEntry) {
+        let is_choice = matches!(entry, CourseEntry::Or(_));
+
+        self.enter(None, is_choice, |flattener| {
+            visit::walk_course_entry(flattener, entry)
+        });
+    }
+
+    fn visit_course(&mut self, course: &'a Course) {
+        self.courses.push(FlattenedCourse {
+            course,
+            path: self.path.clone(),
+            is_choice: self.choice_depth > 0,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_support::read_program;
+
+    #[test]
+    fn flattened_courses_carry_requirement_titles() {
+        let program = read_program("cs_minor.json");
+
+        let flattened = program.flattened_courses();
+
+        assert_eq!(flattened.len(), program.iter_courses().count());
+
+        let first = &flattened[0];
+        assert_eq!(first.path, ["Degree Requirements", "Minor Requirements:"]);
+        assert!(!first.is_choice);
+    }
+
+    #[test]
+    fn courses_under_select_or_or_groups_are_choices() {
+        let program = read_program("cs_minor.json");
+
+        let tracks: Vec<_> = program
+            .flattened_courses()
+            .into_iter()
+            .filter(|flattened| flattened.path.last() == Some(&"Select one track:"))
+            .collect();
+
+        assert!(!tracks.is_empty());
+        assert!(tracks.iter().all(|flattened| flattened.is_choice));
+    }
+}
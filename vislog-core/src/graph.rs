@@ -0,0 +1,779 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::Write,
+};
+
+use serde::Serialize;
+
+use crate::{
+    parsing::guid::Guid, subjects::Subjects, viz::render::RenderOptions, Course, CourseDetails,
+    Program,
+};
+
+/// Directed graph of the prerequisite relationships between courses in the catalog, keyed by the
+/// `Guid` of each course.
+///
+/// An edge from course `A` to course `B` means that `B` must be completed before `A` can be taken.
+//
+// NOTE: Corequisites are intentionally left out since they can be taken in the same semester and
+// do not add any lead time to a course
+#[derive(Debug, Clone, Default)]
+pub struct PrerequisiteGraph {
+    prerequisites: HashMap<Guid, Vec<Guid>>,
+    /// The reverse edges: courses by the GUID of a prerequisite they require
+    unlocks: HashMap<Guid, Vec<Guid>>,
+}
+
+impl PrerequisiteGraph {
+    /// Build the graph from the `prerequisite` field of every given [CourseDetails]
+    pub fn from_course_details<'a, I>(courses: I) -> Self
+    where
+        I: IntoIterator<Item = &'a CourseDetails>,
+    {
+        let mut prerequisites: HashMap<Guid, Vec<Guid>> = HashMap::new();
+        let mut unlocks: HashMap<Guid, Vec<Guid>> = HashMap::new();
+
+        for course in courses {
+            if let Some(prerequisite) = course.prerequisite {
+                prerequisites
+                    .entry(course.guid)
+                    .or_default()
+                    .push(prerequisite);
+                unlocks.entry(prerequisite).or_default().push(course.guid);
+            }
+        }
+
+        Self {
+            prerequisites,
+            unlocks,
+        }
+    }
+
+    /// The direct prerequisites of the course with the given `guid`
+    pub fn prerequisites_of(&self, guid: &Guid) -> &[Guid] {
+        self.prerequisites
+            .get(guid)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// The courses that have the course with the given `guid` as a direct prerequisite, in the
+    /// order they were given to the graph
+    pub fn unlocked_by(&self, guid: &Guid) -> &[Guid] {
+        self.unlocks.get(guid).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// The prerequisite depth of a course, which is the number of semesters of lead time needed
+    /// before the course can be taken. A course without prerequisites has a depth of 0.
+    ///
+    /// Courses in a prerequisite cycle all get the same depth, counting only the prerequisites
+    /// outside of the cycle.
+    pub fn depth_of(&self, guid: &Guid) -> u32 {
+        self.depth_of_memoized(guid, &mut Depths::default())
+    }
+
+    fn depth_of_memoized(&self, guid: &Guid, depths: &mut Depths) -> u32 {
+        if !depths.index.contains_key(guid) {
+            self.connect(*guid, depths);
+        }
+
+        depths.finished[guid]
+    }
+
+    /// Visits the courses required by the course with `guid` with Tarjan's strongly connected
+    /// components algorithm. Components are completed after every component they require, so the
+    /// depth of a whole prerequisite cycle is known as soon as it is completed.
+    fn connect(&self, guid: Guid, depths: &mut Depths) {
+        let index = depths.index.len();
+        depths.index.insert(guid, index);
+        depths.low_link.insert(guid, index);
+        depths.stack.push(guid);
+        depths.on_stack.insert(guid);
+
+        for prerequisite in self.prerequisites_of(&guid) {
+            let low_link = if !depths.index.contains_key(prerequisite) {
+                self.connect(*prerequisite, depths);
+                depths.low_link[prerequisite]
+            } else if depths.on_stack.contains(prerequisite) {
+                depths.index[prerequisite]
+            } else {
+                continue;
+            };
+            let current = depths.low_link.get_mut(&guid).expect("visited above");
+            *current = (*current).min(low_link);
+        }
+
+        if depths.low_link[&guid] != index {
+            return;
+        }
+
+        // `guid` is the first course of its component to be visited, and every course above it on
+        // the stack belongs to the same component
+        let start = depths
+            .stack
+            .iter()
+            .rposition(|visited| *visited == guid)
+            .expect("courses stay on the stack until their component is completed");
+        let component = depths.stack.split_off(start);
+        for course in &component {
+            depths.on_stack.remove(course);
+        }
+
+        let depth = component
+            .iter()
+            .flat_map(|course| self.prerequisites_of(course))
+            .filter(|prerequisite| !component.contains(prerequisite))
+            .map(|prerequisite| depths.finished[prerequisite] + 1)
+            .max()
+            .unwrap_or(0);
+        for course in component {
+            depths.finished.insert(course, depth);
+        }
+    }
+}
+
+/// Bookkeeping of [PrerequisiteGraph::connect], shared between courses so that common
+/// prerequisites are only traversed once
+#[derive(Default)]
+struct Depths {
+    /// Depths of the courses whose component is completed
+    finished: HashMap<Guid, u32>,
+    /// Order in which the courses were visited
+    index: HashMap<Guid, usize>,
+    /// Lowest index reachable from each course without leaving its component
+    low_link: HashMap<Guid, usize>,
+    stack: Vec<Guid>,
+    on_stack: HashSet<Guid>,
+}
+
+/// Computes the prerequisite depth (see [PrerequisiteGraph::depth_of]) of every course required
+/// by the `program`, including the ones its requirements reference by GUID in prose.
+///
+/// Prerequisites outside of the program still count towards the depth of a course since they
+/// need to be taken beforehand all the same.
+pub fn depth_map(program: &Program, graph: &PrerequisiteGraph) -> HashMap<Guid, u32> {
+    let program_guids: BTreeSet<Guid> = program
+        .iter_courses()
+        .map(|course| course.guid)
+        .chain(
+            program
+                .iter_course_refs()
+                .filter_map(|course_ref| course_ref.guid),
+        )
+        .collect();
+
+    let mut depths = Depths::default();
+
+    program_guids
+        .into_iter()
+        .map(|guid| (guid, graph.depth_of_memoized(&guid, &mut depths)))
+        .collect()
+}
+
+/// The courses of a [Program] and the prerequisite edges between them, ready to be drawn
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ProgramGraph {
+    pub title: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GraphNode {
+    pub guid: Guid,
+    /// Subject code and number of the course. Ex: "CSC 125"
+    pub label: String,
+    pub name: Option<String>,
+    /// Prerequisite depth of the course, see [PrerequisiteGraph::depth_of]
+    pub depth: u32,
+    pub subject_code: String,
+    pub number: String,
+    /// Fill of the node. `None` until [ProgramGraph::color_by_department] is called.
+    pub color: Option<String>,
+    /// Courses merged into the node by [CollapseOr](crate::simplify::CollapseOr), empty for nodes
+    /// of a single course
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<Guid>,
+}
+
+/// Edge from a prerequisite to the course requiring it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GraphEdge {
+    pub from: Guid,
+    pub to: Guid,
+}
+
+impl ProgramGraph {
+    /// Builds the graph of every distinct course of the `program`, followed by the courses that
+    /// its requirements reference by GUID in prose (see [references](crate::references)).
+    /// Prerequisites outside of the program are not part of the graph, but still count towards
+    /// the depth of the courses.
+    pub fn new(program: &Program, graph: &PrerequisiteGraph) -> Self {
+        let depths = depth_map(program, graph);
+
+        let mut seen = HashSet::new();
+        let courses: Vec<(Guid, &str, &str, Option<String>)> = program
+            .iter_courses()
+            .filter(|course| seen.insert(course.guid))
+            .map(|course: &Course| {
+                (
+                    course.guid,
+                    &*course.subject_code,
+                    course.number.as_str(),
+                    course.name.clone(),
+                )
+            })
+            .collect();
+        let referenced: Vec<(Guid, &str, &str, Option<String>)> = program
+            .iter_course_refs()
+            .filter_map(|course_ref| {
+                let guid = course_ref.guid.filter(|guid| seen.insert(*guid))?;
+                Some((
+                    guid,
+                    course_ref.subject_code.as_str(),
+                    course_ref.number.as_str(),
+                    None,
+                ))
+            })
+            .collect();
+        let courses = [courses, referenced].concat();
+
+        let nodes = courses
+            .iter()
+            .map(|(guid, subject_code, number, name)| GraphNode {
+                guid: *guid,
+                label: format!("{subject_code} {number}"),
+                name: name.clone(),
+                depth: depths.get(guid).copied().unwrap_or(0),
+                subject_code: (*subject_code).to_owned(),
+                number: (*number).to_owned(),
+                color: None,
+                alternatives: vec![],
+            })
+            .collect();
+
+        let edges = courses
+            .iter()
+            .flat_map(|(guid, _, _, _)| {
+                graph
+                    .prerequisites_of(guid)
+                    .iter()
+                    .filter(|prerequisite| seen.contains(prerequisite))
+                    .map(|prerequisite| GraphEdge {
+                        from: *prerequisite,
+                        to: *guid,
+                    })
+            })
+            .collect();
+
+        Self {
+            title: program.title.clone(),
+            nodes,
+            edges,
+        }
+    }
+
+    /// Colors every node with the [color](Subjects::color) of its subject, so that courses of
+    /// the same department have the same color in the graphs of every program
+    pub fn color_by_department(&mut self, subjects: &Subjects) {
+        for node in &mut self.nodes {
+            node.color = Some(subjects.color(&node.subject_code).to_owned());
+        }
+    }
+
+    /// Renders the graph as a Graphviz DOT digraph with the `depth` of every node as an attribute
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&RenderOptions::default())
+    }
+
+    /// Renders the graph as a Graphviz DOT digraph like [ProgramGraph::to_dot], with nodes
+    /// labeled by the [course_label](RenderOptions::course_label) of the `options`, except for the
+    /// ones merged by [CollapseOr](crate::simplify::CollapseOr). Nodes are filled by the
+    /// [colors](RenderOptions::colors) of the `options` when they color by subject, and by their
+    /// [color](GraphNode::color) otherwise, since the graph doesn't know which requirement lists
+    /// a course.
+    pub fn to_dot_with(&self, options: &RenderOptions) -> String {
+        let mut dot = format!("digraph {} {{\n", dot_string(&self.title));
+
+        for node in &self.nodes {
+            let color = options
+                .colors
+                .fill(&node.subject_code, None)
+                .or(node.color.as_deref());
+            let fill = match color {
+                Some(color) => format!(", style=filled, fillcolor={}", dot_string(color)),
+                None => String::new(),
+            };
+            // Merged nodes keep the codes of all of their courses
+            let label = match node.alternatives.is_empty() {
+                true => options
+                    .course_label
+                    .render(|placeholder| match placeholder {
+                        "subject_code" => Some(node.subject_code.clone()),
+                        "number" => Some(node.number.clone()),
+                        "name" => Some(node.name.clone().unwrap_or_default()),
+                        "guid" => Some(node.guid.to_string()),
+                        "depth" => Some(node.depth.to_string()),
+                        _ => None,
+                    }),
+                false => node.label.clone(),
+            };
+            let _ = writeln!(
+                dot,
+                "    {} [label={}, tooltip={}, depth={}{fill}];",
+                dot_string(&node.guid.to_string()),
+                dot_string(&label),
+                dot_string(node.name.as_deref().unwrap_or_default()),
+                node.depth,
+            );
+        }
+
+        for edge in &self.edges {
+            let _ = writeln!(
+                dot,
+                "    {} -> {};",
+                dot_string(&edge.from.to_string()),
+                dot_string(&edge.to.to_string()),
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Renders the courses of the `program` and the prerequisite edges between them as a Graphviz DOT
+/// digraph. Shorthand for [ProgramGraph::to_dot].
+pub fn to_dot(program: &Program, graph: &PrerequisiteGraph) -> String {
+    ProgramGraph::new(program, graph).to_dot()
+}
+
+/// Longest chain of prerequisites between the courses of a [Program], see [critical_path]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CriticalPath {
+    /// Semesters it takes to complete the longest chain, taking a course of it every semester
+    pub semesters: u32,
+    /// Courses of the longest chain, from the first one to take to the last
+    pub chain: Vec<Guid>,
+    /// Every course of the program, in the order of the nodes of its [ProgramGraph]
+    pub courses: Vec<CourseSlack>,
+}
+
+/// When a course of a program can be taken without pushing back the end of its [CriticalPath].
+/// Semesters are counted from 0, the first semester of the program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CourseSlack {
+    pub guid: Guid,
+    /// Subject code and number of the course. Ex: "CSC 125"
+    pub label: String,
+    /// First semester the course can be taken in, once its prerequisites are completed
+    pub earliest: u32,
+    /// Last semester the course can be taken in, leaving enough semesters for the courses
+    /// requiring it
+    pub latest: u32,
+    /// Semesters the course can be delayed by. Delaying a course without slack delays graduation.
+    pub slack: u32,
+}
+
+impl CriticalPath {
+    /// Courses without slack on chains of more than one semester, which push back graduation
+    /// when they are delayed
+    pub fn bottlenecks(&self) -> impl Iterator<Item = &CourseSlack> {
+        self.courses
+            .iter()
+            .filter(|course| course.slack == 0 && self.semesters > 1)
+    }
+}
+
+/// Computes the longest chain of prerequisites between the courses of the `program`, in
+/// semesters, along with the slack of every course of the program.
+///
+/// Unlike [depth_map], only the prerequisites that are part of the program make chains longer,
+/// since the others may have been completed before the program was started. Prerequisite cycles
+/// are broken like for [PrerequisiteGraph::depth_of].
+pub fn critical_path(program: &Program, graph: &PrerequisiteGraph) -> CriticalPath {
+    let program_graph = ProgramGraph::new(program, graph);
+
+    let mut prerequisites: HashMap<Guid, Vec<Guid>> = HashMap::new();
+    let mut dependents: HashMap<Guid, Vec<Guid>> = HashMap::new();
+    for edge in &program_graph.edges {
+        prerequisites.entry(edge.to).or_default().push(edge.from);
+        dependents.entry(edge.from).or_default().push(edge.to);
+    }
+
+    let mut earliest = HashMap::new();
+    let mut remaining = HashMap::new();
+    for node in &program_graph.nodes {
+        longest_chain(
+            &node.guid,
+            &prerequisites,
+            &mut earliest,
+            &mut HashSet::new(),
+        );
+        longest_chain(&node.guid, &dependents, &mut remaining, &mut HashSet::new());
+    }
+
+    let semesters = earliest
+        .values()
+        .map(|earliest| earliest + 1)
+        .max()
+        .unwrap_or(0);
+
+    let courses: Vec<CourseSlack> = program_graph
+        .nodes
+        .iter()
+        .map(|node| {
+            let earliest = earliest[&node.guid];
+            let latest = (semesters - 1).saturating_sub(remaining[&node.guid]);
+            CourseSlack {
+                guid: node.guid,
+                label: node.label.clone(),
+                earliest,
+                latest,
+                slack: latest.saturating_sub(earliest),
+            }
+        })
+        .collect();
+
+    // Walk the chain back from its last course through prerequisites without slack
+    let mut chain = vec![];
+    let mut next = courses
+        .iter()
+        .find(|course| course.earliest + 1 == semesters)
+        .map(|course| course.guid);
+    while let Some(guid) = next.filter(|guid| !chain.contains(guid)) {
+        chain.push(guid);
+        next = prerequisites
+            .get(&guid)
+            .into_iter()
+            .flatten()
+            .find(|prerequisite| earliest[*prerequisite] + 1 == earliest[&guid])
+            .copied();
+    }
+    chain.reverse();
+
+    CriticalPath {
+        semesters,
+        chain,
+        courses,
+    }
+}
+
+/// Number of edges of the longest path from the course with the `guid` through the `next`
+/// courses, memoized in `lengths`. `None` for the edge closing a cycle.
+fn longest_chain(
+    guid: &Guid,
+    next: &HashMap<Guid, Vec<Guid>>,
+    lengths: &mut HashMap<Guid, u32>,
+    visiting: &mut HashSet<Guid>,
+) -> Option<u32> {
+    if let Some(length) = lengths.get(guid) {
+        return Some(*length);
+    }
+    if !visiting.insert(*guid) {
+        return None;
+    }
+
+    let length = next
+        .get(guid)
+        .into_iter()
+        .flatten()
+        .filter_map(|next_guid| longest_chain(next_guid, next, lengths, visiting))
+        .map(|length| length + 1)
+        .max()
+        .unwrap_or(0);
+
+    visiting.remove(guid);
+    lengths.insert(*guid, length);
+
+    Some(length)
+}
+
+/// Quoted DOT string literal
+fn dot_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        catalog::Catalog,
+        references::CourseRefExtractor,
+        viz::render::{ColorScheme, LabelTemplate},
+        Requirement, RequirementModule, Requirements,
+    };
+
+    fn load_course_details() -> Vec<CourseDetails> {
+        let courses_json = std::fs::read_to_string("../data/courses.json").unwrap();
+        let courses_json: Value = serde_json::from_str(&courses_json).unwrap();
+
+        courses_json["courses"]["course"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|v| serde_json::from_str(&v.to_string()).ok())
+            .collect()
+    }
+
+    fn guid(s: &str) -> Guid {
+        Guid::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn course_without_prerequisites_has_depth_of_zero() {
+        let graph = PrerequisiteGraph::default();
+
+        assert_eq!(
+            graph.depth_of(&guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C")),
+            0
+        );
+    }
+
+    #[test]
+    fn depth_follows_chain_of_prerequisites() {
+        let a = guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C");
+        let b = guid("13A1385C-81AC-493D-ACE8-AA8AB37D2C81");
+        let c = guid("BF3CF399-6D63-43AA-8064-2A86789B5A4E");
+
+        let mut graph = PrerequisiteGraph::default();
+        graph.prerequisites.insert(b, vec![a]);
+        graph.prerequisites.insert(c, vec![b]);
+
+        assert_eq!(graph.depth_of(&a), 0);
+        assert_eq!(graph.depth_of(&b), 1);
+        assert_eq!(graph.depth_of(&c), 2);
+    }
+
+    #[test]
+    fn depth_terminates_on_prerequisite_cycles() {
+        let a = guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C");
+        let b = guid("13A1385C-81AC-493D-ACE8-AA8AB37D2C81");
+
+        let mut graph = PrerequisiteGraph::default();
+        graph.prerequisites.insert(a, vec![b]);
+        graph.prerequisites.insert(b, vec![a]);
+
+        assert_eq!(graph.depth_of(&a), 0);
+        assert_eq!(graph.depth_of(&b), 0);
+    }
+
+    #[test]
+    fn prerequisites_of_a_cycle_count_for_all_of_it() {
+        let a = guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C");
+        let b = guid("13A1385C-81AC-493D-ACE8-AA8AB37D2C81");
+        let c = guid("BF3CF399-6D63-43AA-8064-2A86789B5A4E");
+        let d = guid("897BFC12-7790-43C7-8466-9DDAA5B239BD");
+
+        // `c` -> (`a` <-> `b`) -> `d`
+        let mut graph = PrerequisiteGraph::default();
+        graph.prerequisites.insert(a, vec![b, c]);
+        graph.prerequisites.insert(b, vec![a]);
+        graph.prerequisites.insert(d, vec![b]);
+
+        assert_eq!(graph.depth_of(&c), 0);
+        assert_eq!(graph.depth_of(&a), 1);
+        assert_eq!(graph.depth_of(&b), 1);
+        assert_eq!(graph.depth_of(&d), 2);
+    }
+
+    #[test]
+    fn depth_map_of_cs_major() {
+        let program_json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+        let program: Program = serde_json::from_str(&program_json).unwrap();
+
+        let course_details = load_course_details();
+        let graph = PrerequisiteGraph::from_course_details(&course_details);
+
+        let depths = depth_map(&program, &graph);
+
+        // CSC 115
+        assert_eq!(
+            depths.get(&guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C")),
+            Some(&0)
+        );
+        // CSC 125 requires CSC 115
+        assert_eq!(
+            depths.get(&guid("13A1385C-81AC-493D-ACE8-AA8AB37D2C81")),
+            Some(&1)
+        );
+        assert!(depths.values().all(|depth| *depth <= 1));
+    }
+
+    #[test]
+    fn courses_in_a_prerequisite_cycle_share_their_depth() {
+        let program_json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+        let program: Program = serde_json::from_str(&program_json).unwrap();
+
+        let csc_115 = guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C");
+        let csc_125 = guid("13A1385C-81AC-493D-ACE8-AA8AB37D2C81");
+
+        let course_details = load_course_details();
+        let mut graph = PrerequisiteGraph::from_course_details(&course_details);
+        // CSC 125 already requires CSC 115
+        graph.prerequisites.insert(csc_115, vec![csc_125]);
+
+        let depths = depth_map(&program, &graph);
+        assert_eq!(depths[&csc_115], 0);
+        assert_eq!(depths[&csc_125], 0);
+
+        // Every map iterates its GUIDs in a different order
+        for _ in 0..16 {
+            assert_eq!(depth_map(&program, &graph), depths);
+        }
+    }
+
+    #[test]
+    fn dot_export_has_depth_attributes_and_edges() {
+        let program_json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+        let program: Program = serde_json::from_str(&program_json).unwrap();
+
+        let course_details = load_course_details();
+        let graph = PrerequisiteGraph::from_course_details(&course_details);
+
+        let dot = to_dot(&program, &graph);
+
+        assert!(dot.starts_with("digraph \"Major in Computer Science—42 hours\" {"));
+        assert!(dot.contains(
+            "\"13A1385C-81AC-493D-ACE8-AA8AB37D2C81\" [label=\"CSC 125\", \
+             tooltip=\"Computer Science I: Programming in Java\", depth=1];"
+        ));
+        // CSC 115 -> CSC 125
+        assert!(dot.contains(
+            "\"860AF9C9-EAD9-45AC-AA92-BAF352C5288C\" -> \"13A1385C-81AC-493D-ACE8-AA8AB37D2C81\";"
+        ));
+
+        let mut subjects = Subjects::new();
+        subjects.merge(Subjects::from_json(r#"[{"code": "CSC", "department": "CS"}]"#).unwrap());
+        let mut colored = ProgramGraph::new(&program, &graph);
+        colored.color_by_department(&subjects);
+        let color = subjects.color("CSC");
+        assert!(colored.to_dot().contains(&format!(
+            "tooltip=\"Computer Science I: Programming in Java\", depth=1, style=filled, \
+             fillcolor=\"{color}\"];"
+        )));
+
+        let options = RenderOptions {
+            colors: ColorScheme::Subject(subjects),
+            course_label: LabelTemplate::new("{number} (depth {depth})"),
+            ..Default::default()
+        };
+        let restyled = ProgramGraph::new(&program, &graph).to_dot_with(&options);
+        assert!(restyled.contains(&format!(
+            "[label=\"125 (depth 1)\", tooltip=\"Computer Science I: Programming in Java\", \
+             depth=1, style=filled, fillcolor=\"{color}\"];"
+        )));
+    }
+
+    #[test]
+    fn critical_path_follows_prerequisites_within_the_program() {
+        let program_json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+        let program: Program = serde_json::from_str(&program_json).unwrap();
+        let graph = PrerequisiteGraph::from_course_details(&load_course_details());
+
+        let path = critical_path(&program, &graph);
+
+        // CSC 115 -> CSC 125
+        assert_eq!(path.semesters, 2);
+        assert_eq!(path.chain.len(), 2);
+        assert!(graph
+            .prerequisites_of(&path.chain[1])
+            .contains(&path.chain[0]));
+
+        let csc_115 = path
+            .courses
+            .iter()
+            .find(|course| course.guid == guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C"))
+            .unwrap();
+        assert_eq!((csc_115.earliest, csc_115.latest, csc_115.slack), (0, 0, 0));
+        assert!(path.bottlenecks().any(|course| course.guid == csc_115.guid));
+        assert!(path
+            .courses
+            .iter()
+            .filter(|course| course.slack == 1)
+            .all(|course| course.earliest == 0 && course.latest == 1));
+        assert!(path.bottlenecks().count() < path.courses.len());
+    }
+
+    #[test]
+    fn critical_path_of_a_cycle_terminates() {
+        let program_json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+        let program: Program = serde_json::from_str(&program_json).unwrap();
+        let a = guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C");
+        let b = guid("13A1385C-81AC-493D-ACE8-AA8AB37D2C81");
+
+        let mut graph = PrerequisiteGraph::default();
+        graph.prerequisites.insert(a, vec![b]);
+        graph.prerequisites.insert(b, vec![a]);
+
+        let path = critical_path(&program, &graph);
+        assert_eq!(path.semesters, 2);
+        assert_eq!(path.chain.len(), 2);
+    }
+
+    #[test]
+    fn referenced_courses_are_part_of_the_graph() {
+        let program_json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+        let mut program: Program = serde_json::from_str(&program_json).unwrap();
+
+        // A course outside of the minor requiring CSC 115, which the minor lists
+        let csc_115 = guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C");
+        let capstone = CourseDetails {
+            url: String::new(),
+            guid: guid("3F2C8A5E-7D41-4B9A-9E6F-1C0B2D3A4E5F"),
+            path: String::new(),
+            subject_code: "CSC".into(),
+            subject_name: None,
+            number: "499".to_owned(),
+            name: "Capstone".to_owned(),
+            credits_min: 3,
+            credits_max: None,
+            description: String::new(),
+            prerequisite_narrative: None,
+            prerequisite: Some(csc_115),
+            corequisite_narrative: None,
+            corequisite: None,
+            extra: Default::default(),
+        };
+        let catalog = Catalog {
+            courses: vec![capstone.clone()],
+            ..Default::default()
+        };
+        let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+
+        let Some(Requirements::Single(RequirementModule::BasicRequirements {
+            requirements, ..
+        })) = &mut program.requirements
+        else {
+            panic!("the minor has a single module of requirements");
+        };
+        requirements.push(Requirement::Label {
+            title: None,
+            req_narrative: Some("Students must complete CSC 499 prior to graduation.".to_owned()),
+            constraints: Default::default(),
+            course_refs: vec![],
+            extra: Default::default(),
+        });
+        let before = ProgramGraph::new(&program, &graph);
+
+        CourseRefExtractor::from_catalog(&catalog).attach(&mut program);
+        let after = ProgramGraph::new(&program, &graph);
+
+        assert_eq!(after.nodes.len(), before.nodes.len() + 1);
+        let node = after.nodes.last().unwrap();
+        assert_eq!(node.guid, capstone.guid);
+        assert_eq!(node.label, "CSC 499");
+        assert_eq!(node.depth, 1);
+        assert!(after.edges.contains(&GraphEdge {
+            from: csc_115,
+            to: capstone.guid,
+        }));
+    }
+}
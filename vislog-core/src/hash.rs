@@ -0,0 +1,140 @@
+//! Content hashes of the data model that stay the same across runs, platforms and Rust versions,
+//! unlike the ones of [DefaultHasher](std::collections::hash_map::DefaultHasher). Useful as
+//! `ETag`s or cache keys.
+
+use std::hash::{Hash, Hasher};
+
+use crate::{parsing::guid::Guid, CourseDetails, Program, RequirementModule};
+
+/// 64-bit FNV-1a hasher
+#[derive(Debug, Clone, Copy)]
+pub struct StableHasher(u64);
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    // Lengths of collections are hashed as `usize`, which would otherwise differ between 32 and
+    // 64-bit platforms
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+}
+
+/// Hash of the `value` computed with a [StableHasher]
+pub fn content_hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = StableHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Deterministic GUID of something without one, such as a scraped program or a shared module,
+/// made out of two [StableHasher] hashes of the `value`
+pub(crate) fn derived_guid<T: Hash + ?Sized>(value: &T) -> Guid {
+    let mut high = StableHasher::default();
+    value.hash(&mut high);
+    let mut low = high;
+    low.write_u8(0xff);
+
+    Guid::try_from(format!("{:016X}{:016X}", high.finish(), low.finish()).as_str())
+        .expect("32 hexadecimal digits should be a valid GUID")
+}
+
+impl Program {
+    /// Stable hash of everything in the program, see [content_hash]
+    pub fn content_hash(&self) -> u64 {
+        content_hash(self)
+    }
+}
+
+impl CourseDetails {
+    /// Stable hash of everything in the course, see [content_hash]
+    pub fn content_hash(&self) -> u64 {
+        content_hash(self)
+    }
+}
+
+// Implemented by hand since `serde_json::Value` in `Unimplemented` doesn't implement `Hash`
+impl Hash for RequirementModule {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+
+        match self {
+            RequirementModule::SingleBasicRequirement { title, requirement } => {
+                title.hash(state);
+                requirement.hash(state);
+            }
+            RequirementModule::BasicRequirements {
+                title,
+                requirements,
+            } => {
+                title.hash(state);
+                requirements.hash(state);
+            }
+            RequirementModule::SelectOneEmphasis { emphases } => emphases.hash(state),
+            RequirementModule::Label { title } => title.hash(state),
+            // Objects are serialized with their keys in sorted order, so equal values always end
+            // up with the same string
+            RequirementModule::Unimplemented(value) => value.to_string().hash(state),
+            RequirementModule::Reference(guid) => guid.hash(state),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::read_program;
+
+    #[test]
+    fn hasher_is_fnv_1a() {
+        // A `str` is hashed as its bytes followed by `0xff`
+        assert_eq!(content_hash(""), 0xaf64_724c_8602_eb6e);
+    }
+
+    #[test]
+    fn equal_programs_have_equal_hashes() {
+        let program = read_program("cs_major.json");
+
+        assert_eq!(program.content_hash(), program.clone().content_hash());
+        assert_ne!(
+            program.content_hash(),
+            read_program("cs_minor.json").content_hash()
+        );
+    }
+
+    #[test]
+    fn any_change_changes_the_hash() {
+        let program = read_program("cs_minor.json");
+
+        let mut renamed = program.clone();
+        renamed.title.push('!');
+
+        let mut without_requirements = program.clone();
+        without_requirements.requirements = None;
+
+        assert_ne!(program.content_hash(), renamed.content_hash());
+        assert_ne!(program.content_hash(), without_requirements.content_hash());
+    }
+}
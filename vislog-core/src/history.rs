@@ -0,0 +1,327 @@
+//! Catalogs of several years, for questions spanning more than one of them.
+//!
+//! Each academic year has its own catalog, and programs and courses keep their GUID from one year
+//! to the next. A [CatalogHistory] holds the catalog of every imported [CatalogYear] so that a
+//! program can be looked up as it was in a given year, and so that the changes of a course can be
+//! followed across the years without comparing the catalogs two at a time like a
+//! [CatalogDiff](crate::diff::CatalogDiff) does.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, history::CatalogHistory};
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//! let guid = catalog.programs[0].guid;
+//!
+//! let mut history = CatalogHistory::new();
+//! history.insert("2022-2023".parse().unwrap(), catalog);
+//!
+//! // Years without a catalog of their own follow the latest catalog before them
+//! assert!(history.program_as_of("2023-2024", guid).is_some());
+//! assert!(history.program_as_of("2021-2022", guid).is_none());
+//! ```
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::{catalog::Catalog, parsing::guid::Guid, CourseDetails, Program};
+
+/// An academic year, such as "2022-2023", named after the year it starts in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CatalogYear {
+    pub start: u16,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("catalog year {0:?} isn't two consecutive years such as \"2022-2023\"")]
+pub struct CatalogYearError(String);
+
+/// Catalogs by the year they are for
+#[derive(Debug, Clone, Default)]
+pub struct CatalogHistory {
+    catalogs: BTreeMap<CatalogYear, Catalog>,
+}
+
+/// The value of something in a year, for the years it changed in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimelineEntry<T> {
+    pub year: CatalogYear,
+    /// `None` from the years the course isn't in the catalog anymore
+    pub value: Option<T>,
+}
+
+/// The prerequisite of a course, as a [CourseDetails] gives it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Prerequisite {
+    pub narrative: Option<String>,
+    pub course: Option<Guid>,
+}
+
+impl CatalogYear {
+    pub fn new(start: u16) -> Self {
+        Self { start }
+    }
+}
+
+impl From<u16> for CatalogYear {
+    fn from(start: u16) -> Self {
+        Self::new(start)
+    }
+}
+
+impl FromStr for CatalogYear {
+    type Err = CatalogYearError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || CatalogYearError(s.to_owned());
+        let (start, end) = s.trim().split_once('-').ok_or_else(error)?;
+        let start: u16 = start.parse().map_err(|_| error())?;
+        let end: u16 = end.parse().map_err(|_| error())?;
+        if start.checked_add(1) != Some(end) {
+            return Err(error());
+        }
+
+        Ok(Self::new(start))
+    }
+}
+
+impl TryFrom<&str> for CatalogYear {
+    type Error = CatalogYearError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for CatalogYear {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, u32::from(self.start) + 1)
+    }
+}
+
+impl Serialize for CatalogYear {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CatalogYear {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+impl CatalogHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the `catalog` of the `year`, returning the catalog it replaces if there was one
+    pub fn insert(&mut self, year: CatalogYear, catalog: Catalog) -> Option<Catalog> {
+        self.catalogs.insert(year, catalog)
+    }
+
+    /// Years with a catalog, from the oldest
+    pub fn years(&self) -> impl Iterator<Item = CatalogYear> + '_ {
+        self.catalogs.keys().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.catalogs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.catalogs.is_empty()
+    }
+
+    /// The catalog of exactly the `year`
+    pub fn catalog(&self, year: CatalogYear) -> Option<&Catalog> {
+        self.catalogs.get(&year)
+    }
+
+    /// The catalog in effect in the `year`: the catalog of the year, or the latest catalog before
+    /// it for years that don't have one. `None` for years that can't be parsed.
+    pub fn catalog_as_of(
+        &self,
+        year: impl TryInto<CatalogYear>,
+    ) -> Option<(CatalogYear, &Catalog)> {
+        let year = year.try_into().ok()?;
+        self.catalogs
+            .range(..=year)
+            .next_back()
+            .map(|(year, catalog)| (*year, catalog))
+    }
+
+    /// The program with the `guid` in the catalog in effect in the `year`. See
+    /// [CatalogHistory::catalog_as_of].
+    pub fn program_as_of(&self, year: impl TryInto<CatalogYear>, guid: Guid) -> Option<&Program> {
+        let (_, catalog) = self.catalog_as_of(year)?;
+        catalog.programs.iter().find(|program| program.guid == guid)
+    }
+
+    /// The course with the `guid` in the catalog in effect in the `year`. See
+    /// [CatalogHistory::catalog_as_of].
+    pub fn course_as_of(
+        &self,
+        year: impl TryInto<CatalogYear>,
+        guid: Guid,
+    ) -> Option<&CourseDetails> {
+        let (_, catalog) = self.catalog_as_of(year)?;
+        catalog.courses.iter().find(|course| course.guid == guid)
+    }
+
+    /// The credits of the course with the `guid`, minimum and maximum, for the year the course
+    /// first appears and every year they change after that
+    pub fn credit_timeline(&self, guid: Guid) -> Vec<TimelineEntry<(u8, Option<u8>)>> {
+        self.timeline(guid, |course| (course.credits_min, course.credits_max))
+    }
+
+    /// The prerequisite of the course with the `guid`, for the year the course first appears and
+    /// every year it changes after that
+    pub fn prerequisite_timeline(&self, guid: Guid) -> Vec<TimelineEntry<Prerequisite>> {
+        self.timeline(guid, |course| Prerequisite {
+            narrative: course.prerequisite_narrative.clone(),
+            course: course.prerequisite,
+        })
+    }
+
+    fn timeline<T: PartialEq>(
+        &self,
+        guid: Guid,
+        value: impl Fn(&CourseDetails) -> T,
+    ) -> Vec<TimelineEntry<T>> {
+        let mut timeline: Vec<TimelineEntry<T>> = vec![];
+        for (year, catalog) in &self.catalogs {
+            let current = catalog
+                .courses
+                .iter()
+                .find(|course| course.guid == guid)
+                .map(&value);
+            let changed = match timeline.last() {
+                Some(last) => last.value != current,
+                None => current.is_some(),
+            };
+            if changed {
+                timeline.push(TimelineEntry {
+                    year: *year,
+                    value: current,
+                });
+            }
+        }
+
+        timeline
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn courses() -> Catalog {
+        let (catalog, _) = Catalog::parse_file("../data/courses.json");
+        catalog
+    }
+
+    #[test]
+    fn years_are_parsed_and_ordered() {
+        let year: CatalogYear = "2022-2023".parse().unwrap();
+        assert_eq!(year, CatalogYear::new(2022));
+        assert_eq!(year.to_string(), "2022-2023");
+        assert_eq!(serde_json::to_string(&year).unwrap(), r#""2022-2023""#);
+        assert!(CatalogYear::new(2021) < year);
+
+        for invalid in ["2022", "2022-2024", "2023-2022", "twenty-two"] {
+            assert_eq!(
+                invalid.parse::<CatalogYear>(),
+                Err(CatalogYearError(invalid.to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn programs_are_looked_up_in_the_catalog_in_effect() {
+        let (old, _) = Catalog::parse_file("../data/cs_minor.json");
+        let guid = old.programs[0].guid;
+        let mut new = old.clone();
+        new.programs[0].title = "Minor in Computing".to_owned();
+
+        let mut history = CatalogHistory::new();
+        history.insert(CatalogYear::new(2021), old);
+        history.insert(CatalogYear::new(2023), new);
+        assert_eq!(history.len(), 2);
+
+        let title = |year| {
+            history
+                .program_as_of(year, guid)
+                .map(|program| program.title.as_str())
+        };
+        assert_eq!(title("2020-2021"), None);
+        assert!(title("2022-2023")
+            .unwrap()
+            .starts_with("Minor in Computer Science"));
+        assert_eq!(title("2023-2024"), Some("Minor in Computing"));
+        assert_eq!(title("2030-2031"), Some("Minor in Computing"));
+        assert_eq!(title("not a year"), None);
+        assert_eq!(
+            history.program_as_of(CatalogYear::new(2021), guid),
+            history.program_as_of("2022-2023", guid)
+        );
+    }
+
+    #[test]
+    fn timelines_only_hold_changes() {
+        let old = courses();
+        let course = old.courses[0].clone();
+        let mut changed = old.clone();
+        changed.courses[0].credits_min += 1;
+        changed.courses[0].prerequisite_narrative = Some("Consent of instructor".to_owned());
+        let mut removed = changed.clone();
+        removed.courses.remove(0);
+
+        let mut history = CatalogHistory::new();
+        history.insert(CatalogYear::new(2020), old.clone());
+        history.insert(CatalogYear::new(2021), old);
+        history.insert(CatalogYear::new(2022), changed);
+        history.insert(CatalogYear::new(2023), removed);
+
+        let credits = |min| Some((min, course.credits_max));
+        assert_eq!(
+            history.credit_timeline(course.guid),
+            [
+                TimelineEntry {
+                    year: CatalogYear::new(2020),
+                    value: credits(course.credits_min)
+                },
+                TimelineEntry {
+                    year: CatalogYear::new(2022),
+                    value: credits(course.credits_min + 1)
+                },
+                TimelineEntry {
+                    year: CatalogYear::new(2023),
+                    value: None
+                },
+            ]
+        );
+
+        let prerequisites = history.prerequisite_timeline(course.guid);
+        assert_eq!(prerequisites.len(), 3);
+        assert_eq!(
+            prerequisites[1]
+                .value
+                .as_ref()
+                .unwrap()
+                .narrative
+                .as_deref(),
+            Some("Consent of instructor")
+        );
+        assert_eq!(
+            history
+                .course_as_of("2022-2023", course.guid)
+                .unwrap()
+                .credits_min,
+            course.credits_min + 1
+        );
+    }
+}
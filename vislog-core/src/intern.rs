@@ -0,0 +1,194 @@
+//! A single copy of every course listed by several requirements.
+//!
+//! The catalog API repeats the whole course, URL and names included, everywhere a requirement
+//! lists it, so courses such as "ENG 111" or "MAT 211" are parsed hundreds of times over a whole
+//! catalog. [CourseEntry::Course] holds an [Arc], and [Catalog::intern_courses] makes equal courses
+//! point to the same one.
+//!
+//! Catalogs read with [Catalog::parse_dir] and its siblings, loaded from a
+//! [store](crate::store) or decoded from the [wire](crate::wire) or [proto](crate::proto) formats
+//! are already interned. Catalogs built or changed by hand can be interned again at any time.
+//!
+//! Code that matched on `CourseEntry::Course(course)` keeps working since `course` derefs to a
+//! [Course]. Build entries with `CourseEntry::from(course)` and change courses through
+//! [Arc::make_mut], which copies a shared course before changing it so the other requirements
+//! listing it are left alone.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::catalog::Catalog;
+//! let (mut catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//!
+//! // Already interned, so there is nothing left to share
+//! assert_eq!(catalog.intern_courses(), 0);
+//! ```
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    catalog::Catalog, Course, CourseEntries, CourseEntry, Program, Requirement, RequirementModule,
+};
+
+impl Catalog {
+    /// Makes every course entry equal to an earlier one, in the programs or the
+    /// [shared modules](Catalog::shared_modules) of the catalog, share its [Arc]. Returns the
+    /// number of entries that stopped holding a copy of their own.
+    pub fn intern_courses(&mut self) -> usize {
+        let mut interner = Interner::default();
+        for program in &mut self.programs {
+            interner.program(program);
+        }
+        for shared in &mut self.shared_modules {
+            interner.module(&mut shared.module);
+        }
+
+        interner.interned
+    }
+}
+
+#[derive(Default)]
+struct Interner {
+    courses: HashMap<Course, Arc<Course>>,
+    interned: usize,
+}
+
+impl Interner {
+    fn program(&mut self, program: &mut Program) {
+        if let Some(requirements) = &mut program.requirements {
+            for module in requirements.modules_mut() {
+                self.module(module);
+            }
+        }
+    }
+
+    fn module(&mut self, module: &mut RequirementModule) {
+        for requirement in module.requirements_mut() {
+            match requirement {
+                Requirement::Courses { courses, .. }
+                | Requirement::SelectFromCourses {
+                    courses: Some(courses),
+                    ..
+                } => self.entries(courses),
+                Requirement::SelectFromCourses { courses: None, .. }
+                | Requirement::Label { .. }
+                | Requirement::ElectivePool { .. } => {}
+            }
+        }
+    }
+
+    fn entries(&mut self, entries: &mut CourseEntries) {
+        for entry in entries.iter_mut() {
+            match entry {
+                CourseEntry::And(entries) | CourseEntry::Or(entries) => self.entries(entries),
+                CourseEntry::Label(_) => {}
+                CourseEntry::Course(course) => match self.courses.get(course.as_ref()) {
+                    Some(interned) => {
+                        if !Arc::ptr_eq(interned, course) {
+                            *course = interned.clone();
+                            self.interned += 1;
+                        }
+                    }
+                    None => {
+                        self.courses.insert(Course::clone(course), course.clone());
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn course_arcs(catalog: &Catalog) -> Vec<&Arc<Course>> {
+        fn collect<'a>(entries: &'a CourseEntries, arcs: &mut Vec<&'a Arc<Course>>) {
+            for entry in entries.iter() {
+                match entry {
+                    CourseEntry::And(entries) | CourseEntry::Or(entries) => collect(entries, arcs),
+                    CourseEntry::Label(_) => {}
+                    CourseEntry::Course(course) => arcs.push(course),
+                }
+            }
+        }
+
+        let mut arcs = vec![];
+        for requirement in catalog.programs.iter().flat_map(Program::iter_requirements) {
+            if let Some(entries) = requirement.course_entries() {
+                collect(entries, &mut arcs);
+            }
+        }
+        arcs
+    }
+
+    #[test]
+    fn equal_courses_share_one_copy() {
+        let (mut catalog, _) = Catalog::parse_dir("../data").unwrap();
+        assert_eq!(catalog.intern_courses(), 0);
+
+        let arcs = course_arcs(&catalog);
+        let distinct: HashMap<&Course, &Arc<Course>> =
+            arcs.iter().map(|arc| (arc.as_ref(), *arc)).collect();
+        assert!(distinct.len() < arcs.len());
+        assert!(arcs
+            .iter()
+            .all(|arc| Arc::ptr_eq(arc, distinct[arc.as_ref()])));
+
+        // Copies made by hand are shared again
+        let mut copied = catalog.clone();
+        let mut copies = 0;
+        for program in &mut copied.programs {
+            for module in program
+                .requirements
+                .iter_mut()
+                .flat_map(|r| r.modules_mut())
+            {
+                for requirement in module.requirements_mut() {
+                    if let Requirement::Courses { courses, .. } = requirement {
+                        for entry in courses.iter_mut() {
+                            if let CourseEntry::Course(course) = entry {
+                                *course = Arc::new(Course::clone(course));
+                                copies += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        assert!(copies > 0);
+        assert!(copied.intern_courses() > 0);
+        assert_eq!(copied.intern_courses(), 0);
+        assert_eq!(copied, catalog);
+    }
+
+    #[test]
+    fn changing_a_shared_course_copies_it() {
+        let (mut catalog, _) = Catalog::parse_dir("../data").unwrap();
+
+        let entry = catalog
+            .programs
+            .iter_mut()
+            .filter_map(|program| program.requirements.as_mut())
+            .flat_map(|requirements| requirements.modules_mut())
+            .flat_map(|module| module.requirements_mut())
+            .find_map(|requirement| match requirement {
+                Requirement::Courses { courses, .. } => {
+                    courses.iter_mut().find_map(|entry| match entry {
+                        CourseEntry::Course(course) if Arc::strong_count(course) > 1 => {
+                            Some(course)
+                        }
+                        _ => None,
+                    })
+                }
+                _ => None,
+            })
+            .unwrap();
+        let shared = Arc::clone(entry);
+        let count = Arc::strong_count(&shared);
+
+        Arc::make_mut(entry).name = Some("Renamed".to_string());
+
+        assert_eq!(Arc::strong_count(&shared), count - 1);
+        assert_ne!(shared.name.as_deref(), Some("Renamed"));
+    }
+}
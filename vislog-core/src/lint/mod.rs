@@ -0,0 +1,354 @@
+//! Rules for the quality of the requirements of a catalog.
+//!
+//! Programs that parse fine can still be written in ways that confuse students and the tools
+//! built on vislog, such as requirements listing no courses or "or" groups with a single course.
+//! A [Linter] runs a set of [Rule]s over every program of a catalog and reports what they find as
+//! [Finding]s, which serialize to JSON for other tools to consume.
+//!
+//! Every rule has a [Severity] that can be changed, or set to [Severity::Off] to turn the rule off.
+//! The built-in rules are in [rules], and [Linter::new] runs all of them at their default severity.
+//! Add rules of your own with [Linter::with_rule].
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, lint::{Linter, Severity}};
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//!
+//! let mut linter = Linter::new();
+//! linter.set_severity("single-member-or", Severity::Error).unwrap();
+//! linter.set_severity("narrative-only-module", Severity::Off).unwrap();
+//!
+//! let report = linter.lint(&catalog);
+//! for finding in &report.findings {
+//!     println!("{finding}");
+//! }
+//! println!("{}", serde_json::to_string_pretty(&report).unwrap());
+//! ```
+
+pub mod rules;
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{catalog::Catalog, parsing::guid::Guid, Program};
+
+/// How much a [Finding] of a rule matters. Ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The rule doesn't run
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+/// Where in a program a rule found something
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Location {
+    /// Index of the module in the requirements of the program, `None` for the program itself
+    pub module: Option<usize>,
+    /// Index of the requirement in the module, `None` for the module itself
+    pub requirement: Option<usize>,
+}
+
+/// Something a [Rule] found in a program, before the [Linter] gives it a severity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub location: Location,
+    pub message: String,
+}
+
+/// A check of the quality of a program
+pub trait Rule: Send + Sync {
+    /// Name of the rule in kebab case, used to configure its severity. Ex: "single-member-or"
+    fn name(&self) -> &'static str;
+
+    /// What the rule looks for, in a sentence
+    fn description(&self) -> &'static str;
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    /// Every violation of the rule in the program
+    fn check(&self, program: &Program) -> Vec<Violation>;
+}
+
+/// A [Violation] found by a [Linter], with the rule that found it and the program it is in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Finding {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub program: Guid,
+    pub program_title: String,
+    pub location: Location,
+    pub message: String,
+}
+
+/// Every [Finding] of a [Linter] over a catalog, in the order of the programs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LintReport {
+    pub findings: Vec<Finding>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LintError {
+    #[error("unknown lint rule {0:?}")]
+    UnknownRule(String),
+    #[error("unknown severity {0:?}, expected one of off, info, warning or error")]
+    UnknownSeverity(String),
+}
+
+/// Runs [Rule]s over programs, each at its own [Severity]
+pub struct Linter {
+    rules: Vec<(Box<dyn Rule>, Severity)>,
+}
+
+impl Linter {
+    /// Linter running every built-in [rule](rules::builtin) at its default severity
+    pub fn new() -> Self {
+        rules::builtin()
+            .into_iter()
+            .fold(Self::empty(), |linter, rule| linter.with_boxed_rule(rule))
+    }
+
+    /// Linter without any rules
+    pub fn empty() -> Self {
+        Self { rules: vec![] }
+    }
+
+    /// Adds the `rule` at its default severity, replacing any rule with the same name
+    pub fn with_rule(self, rule: impl Rule + 'static) -> Self {
+        self.with_boxed_rule(Box::new(rule))
+    }
+
+    fn with_boxed_rule(mut self, rule: Box<dyn Rule>) -> Self {
+        self.rules
+            .retain(|(existing, _)| existing.name() != rule.name());
+        let severity = rule.default_severity();
+        self.rules.push((rule, severity));
+        self
+    }
+
+    /// Changes the severity of the rule named `name`
+    pub fn set_severity(&mut self, name: &str, severity: Severity) -> Result<(), LintError> {
+        let (_, current) = self
+            .rules
+            .iter_mut()
+            .find(|(rule, _)| rule.name() == name)
+            .ok_or_else(|| LintError::UnknownRule(name.to_owned()))?;
+        *current = severity;
+
+        Ok(())
+    }
+
+    /// The rules of the linter along with their severity
+    pub fn rules(&self) -> impl Iterator<Item = (&dyn Rule, Severity)> {
+        self.rules
+            .iter()
+            .map(|(rule, severity)| (&**rule, *severity))
+    }
+
+    /// Every finding of the rules that aren't [off](Severity::Off) in the `program`, in the order
+    /// of the rules
+    pub fn lint_program(&self, program: &Program) -> Vec<Finding> {
+        self.rules
+            .iter()
+            .filter(|(_, severity)| *severity != Severity::Off)
+            .flat_map(|(rule, severity)| {
+                rule.check(program).into_iter().map(|violation| Finding {
+                    rule: rule.name(),
+                    severity: *severity,
+                    program: program.guid,
+                    program_title: program.title.clone(),
+                    location: violation.location,
+                    message: violation.message,
+                })
+            })
+            .collect()
+    }
+
+    /// Lints every program of the `catalog`. Modules shared by several programs are linted in
+    /// every program referring to them.
+    pub fn lint(&self, catalog: &Catalog) -> LintReport {
+        let catalog = catalog.inlined();
+        LintReport {
+            findings: catalog
+                .programs
+                .iter()
+                .flat_map(|program| self.lint_program(program))
+                .collect(),
+        }
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Linter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.rules().map(|(rule, severity)| (rule.name(), severity)))
+            .finish()
+    }
+}
+
+impl LintReport {
+    /// Number of findings of the `severity`
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .count()
+    }
+
+    /// Most severe finding of the report, `None` when there are none
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.findings.iter().map(|finding| finding.severity).max()
+    }
+}
+
+impl Violation {
+    pub fn new(location: Location, message: impl Into<String>) -> Self {
+        Self {
+            location,
+            message: message.into(),
+        }
+    }
+}
+
+impl Location {
+    pub fn module(module: usize) -> Self {
+        Self {
+            module: Some(module),
+            requirement: None,
+        }
+    }
+
+    pub fn requirement(module: usize, requirement: usize) -> Self {
+        Self {
+            module: Some(module),
+            requirement: Some(requirement),
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = LintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Severity::Off),
+            "info" => Ok(Severity::Info),
+            "warning" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            _ => Err(LintError::UnknownSeverity(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Severity::Off => "off",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Modules and requirements are numbered from 1
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.module, self.requirement) {
+            (None, _) => write!(f, "program"),
+            (Some(module), None) => write!(f, "module {}", module + 1),
+            (Some(module), Some(requirement)) => {
+                write!(f, "module {}, requirement {}", module + 1, requirement + 1)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}: {} ({})",
+            self.severity, self.rule, self.program_title, self.message, self.location
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct EveryProgram;
+
+    impl Rule for EveryProgram {
+        fn name(&self) -> &'static str {
+            "every-program"
+        }
+
+        fn description(&self) -> &'static str {
+            "Reports every program"
+        }
+
+        fn check(&self, _program: &Program) -> Vec<Violation> {
+            vec![Violation::new(Location::default(), "found")]
+        }
+    }
+
+    #[test]
+    fn severities_are_configurable() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let mut linter = Linter::empty().with_rule(EveryProgram);
+
+        let report = linter.lint(&catalog);
+        assert_eq!(report.findings.len(), catalog.programs.len());
+        assert_eq!(report.max_severity(), Some(Severity::Warning));
+
+        linter
+            .set_severity("every-program", Severity::Error)
+            .unwrap();
+        assert_eq!(
+            linter.lint(&catalog).count(Severity::Error),
+            catalog.programs.len()
+        );
+
+        linter.set_severity("every-program", Severity::Off).unwrap();
+        assert_eq!(linter.lint(&catalog), LintReport::default());
+
+        assert_eq!(
+            linter.set_severity("no-such-rule", Severity::Info),
+            Err(LintError::UnknownRule("no-such-rule".to_owned()))
+        );
+    }
+
+    #[test]
+    fn findings_serialize_with_their_rule() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let report = Linter::new().lint(&catalog);
+        assert!(!report.findings.is_empty());
+
+        let json = serde_json::to_value(&report).unwrap();
+        let first = &json["findings"][0];
+        assert_eq!(first["rule"], report.findings[0].rule);
+        assert_eq!(first["severity"], report.findings[0].severity.to_string());
+        assert_eq!("warning".parse(), Ok(Severity::Warning));
+        assert!("loud".parse::<Severity>().is_err());
+    }
+}
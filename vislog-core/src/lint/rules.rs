@@ -0,0 +1,422 @@
+//! The built-in lint [Rule]s.
+
+use std::collections::HashMap;
+
+use super::{Location, Rule, Severity, Violation};
+use crate::{
+    parsing::guid::Guid, CourseEntries, CourseEntry, Program, Requirement, RequirementModule,
+    Requirements,
+};
+
+/// Every built-in rule
+pub fn builtin() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(EmptyRequirementList),
+        Box::new(ZeroCreditRequirement),
+        Box::new(SingleMemberOr),
+        Box::new(NarrativeOnlyModule),
+        Box::new(DuplicateCourse),
+    ]
+}
+
+/// Modules without requirements, and requirements or groups listing no courses
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmptyRequirementList;
+
+/// Requirements whose courses are all worth no credits
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZeroCreditRequirement;
+
+/// "Or" groups with a single course, which leave nothing to choose from
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SingleMemberOr;
+
+/// Modules made only of prose, without any course or hours of electives
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NarrativeOnlyModule;
+
+/// Courses listed more than once by the same requirement
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DuplicateCourse;
+
+impl Rule for EmptyRequirementList {
+    fn name(&self) -> &'static str {
+        "empty-requirement-list"
+    }
+
+    fn description(&self) -> &'static str {
+        "Modules without requirements, and requirements or groups listing no courses"
+    }
+
+    fn check(&self, program: &Program) -> Vec<Violation> {
+        let mut violations = vec![];
+        for (index, module) in modules(program).iter().enumerate() {
+            let empty_module = match module {
+                RequirementModule::BasicRequirements { requirements, .. } => {
+                    requirements.is_empty()
+                }
+                RequirementModule::SelectOneEmphasis { emphases } => emphases.is_empty(),
+                _ => false,
+            };
+            if empty_module {
+                violations.push(Violation::new(
+                    Location::module(index),
+                    format!("{} has no requirements", module_name(module)),
+                ));
+            }
+        }
+
+        for (location, requirement) in requirements(program) {
+            let Some(entries) = requirement.course_entries() else {
+                continue;
+            };
+            if entries.is_empty() {
+                violations.push(Violation::new(
+                    location,
+                    format!("{} lists no courses", requirement_name(requirement)),
+                ));
+            } else if has_empty_group(entries) {
+                violations.push(Violation::new(
+                    location,
+                    format!("{} has an empty group", requirement_name(requirement)),
+                ));
+            }
+        }
+
+        violations
+    }
+}
+
+impl Rule for ZeroCreditRequirement {
+    fn name(&self) -> &'static str {
+        "zero-credit-requirement"
+    }
+
+    fn description(&self) -> &'static str {
+        "Requirements whose courses are all worth no credits"
+    }
+
+    fn check(&self, program: &Program) -> Vec<Violation> {
+        requirements(program)
+            .filter(|(_, requirement)| {
+                let Some(entries) = requirement.course_entries() else {
+                    return false;
+                };
+                let mut credits = entries_credits(entries).peekable();
+                credits.peek().is_some() && credits.all(|credits| credits == (0, None))
+            })
+            .map(|(location, requirement)| {
+                Violation::new(
+                    location,
+                    format!(
+                        "{} only lists courses worth no credits",
+                        requirement_name(requirement)
+                    ),
+                )
+            })
+            .collect()
+    }
+}
+
+impl Rule for SingleMemberOr {
+    fn name(&self) -> &'static str {
+        "single-member-or"
+    }
+
+    fn description(&self) -> &'static str {
+        "\"Or\" groups with a single course, which leave nothing to choose from"
+    }
+
+    fn check(&self, program: &Program) -> Vec<Violation> {
+        requirements(program)
+            .flat_map(|(location, requirement)| {
+                let groups = requirement
+                    .course_entries()
+                    .map(count_single_member_or)
+                    .unwrap_or_default();
+                (0..groups).map(move |_| {
+                    Violation::new(
+                        location,
+                        format!(
+                            "{} has an \"or\" group with a single entry",
+                            requirement_name(requirement)
+                        ),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+impl Rule for NarrativeOnlyModule {
+    fn name(&self) -> &'static str {
+        "narrative-only-module"
+    }
+
+    fn description(&self) -> &'static str {
+        "Modules made only of prose, without any course or hours of electives"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, program: &Program) -> Vec<Violation> {
+        modules(program)
+            .iter()
+            .enumerate()
+            .filter(|(_, module)| match module {
+                RequirementModule::Label { .. } => true,
+                _ => {
+                    let requirements = module.requirements();
+                    !requirements.is_empty()
+                        && requirements
+                            .iter()
+                            .all(|requirement| matches!(requirement, Requirement::Label { .. }))
+                }
+            })
+            .map(|(index, module)| {
+                Violation::new(
+                    Location::module(index),
+                    format!("{} only has prose", module_name(module)),
+                )
+            })
+            .collect()
+    }
+}
+
+impl Rule for DuplicateCourse {
+    fn name(&self) -> &'static str {
+        "duplicate-course"
+    }
+
+    fn description(&self) -> &'static str {
+        "Courses listed more than once by the same requirement"
+    }
+
+    fn check(&self, program: &Program) -> Vec<Violation> {
+        let mut violations = vec![];
+        for (location, requirement) in requirements(program) {
+            let Some(entries) = requirement.course_entries() else {
+                continue;
+            };
+
+            // Counts of the courses in the order they are first listed
+            let mut counts: Vec<(String, usize)> = vec![];
+            let mut indices: HashMap<Guid, usize> = HashMap::new();
+            for course in entries.iter_courses() {
+                match indices.get(&course.guid) {
+                    Some(&index) => counts[index].1 += 1,
+                    None => {
+                        indices.insert(course.guid, counts.len());
+                        let code = format!("{} {}", course.subject_code, course.number);
+                        counts.push((code, 1));
+                    }
+                }
+            }
+
+            for (code, count) in counts.into_iter().filter(|(_, count)| *count > 1) {
+                violations.push(Violation::new(
+                    location,
+                    format!(
+                        "{} lists {code} {count} times",
+                        requirement_name(requirement)
+                    ),
+                ));
+            }
+        }
+
+        violations
+    }
+}
+
+fn modules(program: &Program) -> &[RequirementModule] {
+    program
+        .requirements
+        .as_ref()
+        .map(Requirements::modules)
+        .unwrap_or_default()
+}
+
+/// Every requirement of the program along with its location
+fn requirements(program: &Program) -> impl Iterator<Item = (Location, &Requirement)> {
+    modules(program).iter().enumerate().flat_map(|(module, m)| {
+        m.requirements()
+            .iter()
+            .enumerate()
+            .map(move |(index, requirement)| (Location::requirement(module, index), requirement))
+    })
+}
+
+fn module_name(module: &RequirementModule) -> String {
+    match module.title() {
+        Some(title) => format!("module {title:?}"),
+        None => "untitled module".to_owned(),
+    }
+}
+
+fn requirement_name(requirement: &Requirement) -> String {
+    match requirement.title() {
+        Some(title) => format!("requirement {title:?}"),
+        None => "untitled requirement".to_owned(),
+    }
+}
+
+fn has_empty_group(entries: &CourseEntries) -> bool {
+    entries.iter().any(|entry| match entry {
+        CourseEntry::And(group) | CourseEntry::Or(group) => {
+            group.is_empty() || has_empty_group(group)
+        }
+        CourseEntry::Label(_) | CourseEntry::Course(_) => false,
+    })
+}
+
+fn count_single_member_or(entries: &CourseEntries) -> usize {
+    entries
+        .iter()
+        .map(|entry| match entry {
+            CourseEntry::Or(group) => usize::from(group.len() == 1) + count_single_member_or(group),
+            CourseEntry::And(group) => count_single_member_or(group),
+            CourseEntry::Label(_) | CourseEntry::Course(_) => 0,
+        })
+        .sum()
+}
+
+/// Credits of every course and label in the entries
+fn entries_credits(entries: &CourseEntries) -> impl Iterator<Item = (u8, Option<u8>)> + '_ {
+    entries
+        .iter()
+        .flat_map(|entry| -> Box<dyn Iterator<Item = _>> {
+            match entry {
+                CourseEntry::And(group) | CourseEntry::Or(group) => {
+                    Box::new(entries_credits(group))
+                }
+                CourseEntry::Label(label) => Box::new(std::iter::once(label.credits)),
+                CourseEntry::Course(course) => Box::new(std::iter::once(course.credits)),
+            }
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{catalog::Catalog, constraints::Constraints, Course};
+
+    fn course(guid: &str, number: &str, credits: u8) -> CourseEntry {
+        CourseEntry::Course(Arc::new(Course {
+            url: Default::default(),
+            path: Default::default(),
+            guid: Guid::try_from(guid).unwrap(),
+            name: None,
+            number: number.to_owned(),
+            subject_name: None,
+            subject_code: "CSC".into(),
+            credits: (credits, None),
+            extra: Default::default(),
+            details: None,
+        }))
+    }
+
+    fn program(requirements: Vec<Requirement>) -> Program {
+        Program {
+            url: String::new(),
+            guid: Guid::try_from("860AF9C9-EAD9-45AC-AA92-BAF352C5288C").unwrap(),
+            title: "Minor in Testing".to_owned(),
+            content: None,
+            bottom_content: None,
+            requirements: Some(Requirements::Single(RequirementModule::BasicRequirements {
+                title: Some("Core".to_owned()),
+                requirements,
+            })),
+            extra: Default::default(),
+        }
+    }
+
+    fn courses(entries: Vec<CourseEntry>) -> Requirement {
+        Requirement::Courses {
+            title: Some("Required".to_owned()),
+            courses: CourseEntries::from(entries),
+            constraints: Constraints::default(),
+            extra: Default::default(),
+        }
+    }
+
+    const A: &str = "13A1385C-81AC-493D-ACE8-AA8AB37D2C81";
+    const B: &str = "BF3CF399-6D63-43AA-8064-2A86789B5A4E";
+
+    #[test]
+    fn rules_find_what_they_describe() {
+        let program = program(vec![
+            courses(vec![]),
+            courses(vec![course(A, "115", 0), course(B, "215", 0)]),
+            courses(vec![
+                CourseEntry::Or(CourseEntries::from(vec![course(A, "115", 3)])),
+                course(A, "115", 3),
+            ]),
+            Requirement::Label {
+                title: Some("Talk to your advisor".to_owned()),
+                req_narrative: None,
+                constraints: Constraints::default(),
+                course_refs: vec![],
+                extra: Default::default(),
+            },
+        ]);
+
+        let messages = |rule: &dyn Rule| -> Vec<(Location, String)> {
+            rule.check(&program)
+                .into_iter()
+                .map(|violation| (violation.location, violation.message))
+                .collect()
+        };
+
+        assert_eq!(
+            messages(&EmptyRequirementList),
+            [(
+                Location::requirement(0, 0),
+                "requirement \"Required\" lists no courses".to_owned()
+            )]
+        );
+        assert_eq!(
+            messages(&ZeroCreditRequirement),
+            [(
+                Location::requirement(0, 1),
+                "requirement \"Required\" only lists courses worth no credits".to_owned()
+            )]
+        );
+        assert_eq!(
+            messages(&SingleMemberOr),
+            [(
+                Location::requirement(0, 2),
+                "requirement \"Required\" has an \"or\" group with a single entry".to_owned()
+            )]
+        );
+        assert_eq!(
+            messages(&DuplicateCourse),
+            [(
+                Location::requirement(0, 2),
+                "requirement \"Required\" lists CSC 115 2 times".to_owned()
+            )]
+        );
+        // One of the requirements lists courses
+        assert_eq!(messages(&NarrativeOnlyModule), []);
+    }
+
+    #[test]
+    fn builtin_rules_have_unique_names() {
+        let rules = builtin();
+        let mut names: Vec<_> = rules.iter().map(|rule| rule.name()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), rules.len());
+
+        // Every rule runs over the whole catalog without panicking
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        for program in &catalog.programs {
+            for rule in &rules {
+                rule.check(program);
+            }
+        }
+    }
+}
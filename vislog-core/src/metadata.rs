@@ -0,0 +1,292 @@
+//! What kind of program a [Program] is and who offers it.
+//!
+//! The catalog API only gives a program its title and URL, but both say more than that. Titles
+//! start with the degree the program leads to ("Bachelor of Music with Major in Composition",
+//! "Minor in Computer Science"), and URLs follow the organization of the catalog, from the school
+//! down to the department offering the program
+//! (".../college-of-arts-and-sciences/department-of-computer-science/major-in-computer-science").
+//! [ProgramMetadata::of] reads them out of a program.
+//!
+//! Anything the catalog doesn't have, such as the CIP code of a program, or anything it gets
+//! wrong, comes from a [MetadataMapping] kept next to the catalog. The metadata can be used to
+//! narrow down [queries](crate::catalog::Catalog::query) to the courses of some programs.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{metadata::{Degree, MetadataMapping, ProgramMetadata}, Program};
+//! let program_json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+//! let program: Program = serde_json::from_str(&program_json).unwrap();
+//!
+//! let metadata = ProgramMetadata::of(&program);
+//! assert_eq!(metadata.degree, Some(Degree::Major));
+//! assert_eq!(metadata.department.as_deref(), Some("Department of Computer Science"));
+//!
+//! let mapping = MetadataMapping::from_json(
+//!     r#"{ "5B72AC3A-9A84-4CF5-B1BE-B3E0B48163A5": { "cip_code": "11.0701" } }"#,
+//! )
+//! .unwrap();
+//! assert_eq!(mapping.metadata(&program).cip_code.as_deref(), Some("11.0701"));
+//! ```
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{parsing::guid::Guid, Program};
+
+/// What completing a program earns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Degree {
+    Associate,
+    BachelorOfArts,
+    BachelorOfFineArts,
+    BachelorOfMusic,
+    BachelorOfScience,
+    /// Other bachelor's degrees. Ex: "Bachelor in Applied Psychology"
+    Bachelor,
+    Major,
+    Minor,
+    Certificate,
+    Licensure,
+}
+
+/// Metadata of a program. Every field is optional so that a [MetadataMapping] can set only
+/// some of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ProgramMetadata {
+    pub degree: Option<Degree>,
+    /// College or school offering the program. Ex: "College of Arts and Sciences"
+    pub school: Option<String>,
+    /// Ex: "Department of Computer Science"
+    pub department: Option<String>,
+    /// Classification of Instructional Programs code. Ex: "11.0701"
+    pub cip_code: Option<String>,
+    pub url: Option<String>,
+}
+
+/// Metadata of programs by their GUID, such as a JSON file maintained next to the catalog
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MetadataMapping(HashMap<Guid, ProgramMetadata>);
+
+impl Degree {
+    /// The degree named at the start of the `title` of a program, or anywhere in it for
+    /// certificates and licensures
+    pub fn from_title(title: &str) -> Option<Degree> {
+        const PREFIXES: &[(&str, Degree)] = &[
+            ("Associate", Degree::Associate),
+            ("Bachelor of Fine Arts", Degree::BachelorOfFineArts),
+            ("Bachelor of Art", Degree::BachelorOfArts),
+            ("B.A.", Degree::BachelorOfArts),
+            ("Bachelor of Music", Degree::BachelorOfMusic),
+            ("Bachelor of Science", Degree::BachelorOfScience),
+            ("Bachelor", Degree::Bachelor),
+            ("Major", Degree::Major),
+            ("Minor", Degree::Minor),
+        ];
+
+        let title = title.trim();
+        let degree = PREFIXES
+            .iter()
+            .find(|(prefix, _)| title.starts_with(prefix))
+            .map(|(_, degree)| *degree);
+
+        degree.or_else(|| {
+            if title.contains("Certificat") {
+                Some(Degree::Certificate)
+            } else if title.contains("Licensure") {
+                Some(Degree::Licensure)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl ProgramMetadata {
+    /// Metadata found in the title and URL of the `program`
+    pub fn of(program: &Program) -> Self {
+        let units = organizational_units(&program.url);
+
+        Self {
+            degree: Degree::from_title(&program.title),
+            school: units.first().map(|unit| title_case(unit)),
+            department: units
+                .iter()
+                .find(|unit| unit.starts_with("department-of-"))
+                .map(|unit| title_case(unit)),
+            cip_code: None,
+            url: Some(program.url.clone()).filter(|url| !url.is_empty()),
+        }
+    }
+
+    /// The metadata with the fields set by `other` replaced
+    pub fn merge(self, other: &ProgramMetadata) -> Self {
+        Self {
+            degree: other.degree.or(self.degree),
+            school: other.school.clone().or(self.school),
+            department: other.department.clone().or(self.department),
+            cip_code: other.cip_code.clone().or(self.cip_code),
+            url: other.url.clone().or(self.url),
+        }
+    }
+}
+
+impl MetadataMapping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a JSON object of metadata by program GUID. Ex:
+    /// `{"5B72AC3A-9A84-4CF5-B1BE-B3E0B48163A5": {"cip_code": "11.0701"}}`
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn insert(&mut self, guid: Guid, metadata: ProgramMetadata) -> Option<ProgramMetadata> {
+        self.0.insert(guid, metadata)
+    }
+
+    /// Metadata the mapping has for the program with the `guid`
+    pub fn get(&self, guid: &Guid) -> Option<&ProgramMetadata> {
+        self.0.get(guid)
+    }
+
+    /// Metadata of the `program`, with the fields from the mapping taking precedence over the
+    /// ones found in the program
+    pub fn metadata(&self, program: &Program) -> ProgramMetadata {
+        let metadata = ProgramMetadata::of(program);
+        match self.get(&program.guid) {
+            Some(mapped) => metadata.merge(mapped),
+            None => metadata,
+        }
+    }
+}
+
+/// Path segments of the URL between the catalog and the program, from the school down
+fn organizational_units(url: &str) -> Vec<&str> {
+    let path = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let Some(catalog) = segments
+        .iter()
+        .rposition(|segment| segment.contains("catalogue"))
+    else {
+        return vec![];
+    };
+
+    segments[catalog + 1..segments.len().saturating_sub(1).max(catalog + 1)].to_vec()
+}
+
+/// "department-of-computer-science" to "Department of Computer Science"
+fn title_case(segment: &str) -> String {
+    const MINOR_WORDS: &[&str] = &["of", "and", "the", "for", "in", "by"];
+
+    segment
+        .split('-')
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(|(i, word)| match i > 0 && MINOR_WORDS.contains(&word) {
+            true => word.to_owned(),
+            false => {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn degrees_are_read_from_titles() {
+        let cases = [
+            (
+                "Bachelor of Fine Arts in Studio Art—76 hours",
+                Some(Degree::BachelorOfFineArts),
+            ),
+            (
+                "Bachelor of Art in Pre-Professional Art Therapy",
+                Some(Degree::BachelorOfArts),
+            ),
+            (
+                "B.A. in Worship Leadership—46 to 47 hours",
+                Some(Degree::BachelorOfArts),
+            ),
+            ("Bachelor in Applied Psychology", Some(Degree::Bachelor)),
+            (
+                "Majors in Accounting, Business Administration",
+                Some(Degree::Major),
+            ),
+            (
+                "Minor in Computer Science—21 or 22 hours",
+                Some(Degree::Minor),
+            ),
+            (
+                "EDGE Certificate Requirement—48 Hours",
+                Some(Degree::Certificate),
+            ),
+            (
+                "Teacher Licensure for History (Grades 6–12)",
+                Some(Degree::Licensure),
+            ),
+            ("Course Offerings in Computer Science", None),
+        ];
+        for (title, degree) in cases {
+            assert_eq!(Degree::from_title(title), degree, "{title}");
+        }
+    }
+
+    #[test]
+    fn metadata_is_read_from_every_program() {
+        let (catalog, _) = crate::catalog::Catalog::parse_file("../data/programs.json");
+        for program in &catalog.programs {
+            let metadata = ProgramMetadata::of(program);
+            assert!(metadata.school.is_some(), "{}", program.url);
+            assert_eq!(metadata.url.as_deref(), Some(program.url.as_str()));
+        }
+
+        let urban = catalog
+            .programs
+            .iter()
+            .find(|program| program.title == "Bachelor in Urban Theological Studies")
+            .unwrap();
+        let metadata = ProgramMetadata::of(urban);
+        assert_eq!(
+            metadata.school.as_deref(),
+            Some("Memphis College of Urban and Theological Studies")
+        );
+        assert_eq!(metadata.department, None);
+    }
+
+    #[test]
+    fn mappings_take_precedence() {
+        let (catalog, _) = crate::catalog::Catalog::parse_file("../data/cs_minor.json");
+        let program = &catalog.programs[0];
+
+        let mut mapping = MetadataMapping::new();
+        mapping.insert(
+            program.guid,
+            ProgramMetadata {
+                degree: Some(Degree::Certificate),
+                cip_code: Some("11.0701".to_owned()),
+                ..Default::default()
+            },
+        );
+        let metadata = mapping.metadata(program);
+        assert_eq!(metadata.degree, Some(Degree::Certificate));
+        assert_eq!(metadata.cip_code.as_deref(), Some("11.0701"));
+        assert_eq!(metadata.department, ProgramMetadata::of(program).department);
+
+        let json = serde_json::to_string(&mapping).unwrap();
+        assert_eq!(MetadataMapping::from_json(&json).unwrap(), mapping);
+    }
+}
@@ -0,0 +1,271 @@
+//! Opt-in telemetry counters for monitoring the health of catalog parsing and queries over time.
+//!
+//! Nothing is recorded until [Metrics::enable] is called, so users of the library that don't care
+//! about telemetry only pay for a single atomic load per recording.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+static GLOBAL: Metrics = Metrics::new();
+
+/// The process wide [Metrics] that vislog-core records into
+pub fn global() -> &'static Metrics {
+    &GLOBAL
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Counter {
+    /// A `Program` failed to parse
+    ProgramParseFailures,
+    /// A `CourseDetails` failed to parse
+    CourseParseFailures,
+    /// A `Requirement` was classified as a `Requirement::Label` because no courses were found in
+    /// it instead of being positively identified as one
+    FallbackClassifications,
+    CacheHits,
+    CacheMisses,
+}
+
+impl Counter {
+    pub const ALL: [Counter; 5] = [
+        Counter::ProgramParseFailures,
+        Counter::CourseParseFailures,
+        Counter::FallbackClassifications,
+        Counter::CacheHits,
+        Counter::CacheMisses,
+    ];
+
+    /// Name of the counter following the Prometheus naming conventions
+    pub fn name(&self) -> &'static str {
+        match self {
+            Counter::ProgramParseFailures => "vislog_program_parse_failures_total",
+            Counter::CourseParseFailures => "vislog_course_parse_failures_total",
+            Counter::FallbackClassifications => "vislog_fallback_classifications_total",
+            Counter::CacheHits => "vislog_cache_hits_total",
+            Counter::CacheMisses => "vislog_cache_misses_total",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Counter::ProgramParseFailures => "Number of programs that failed to parse",
+            Counter::CourseParseFailures => "Number of courses that failed to parse",
+            Counter::FallbackClassifications => {
+                "Number of requirements classified as a label because no courses were found"
+            }
+            Counter::CacheHits => "Number of lookups served from a cache",
+            Counter::CacheMisses => "Number of lookups that had to populate a cache",
+        }
+    }
+}
+
+/// Running totals of the latencies recorded for a single kind of query
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub total_seconds: f64,
+    pub max_seconds: f64,
+}
+
+pub struct Metrics {
+    enabled: AtomicBool,
+    counters: [AtomicU64; Counter::ALL.len()],
+    latencies: Mutex<BTreeMap<String, LatencySummary>>,
+}
+
+impl Metrics {
+    pub const fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            counters: [const { AtomicU64::new(0) }; Counter::ALL.len()],
+            latencies: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn increment(&self, counter: Counter) {
+        if self.is_enabled() {
+            self.counters[counter as usize].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Records how long a single `query` took
+    pub fn record_latency(&self, query: &str, latency: Duration) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let seconds = latency.as_secs_f64();
+        let mut latencies = self.latencies.lock().unwrap_or_else(|err| err.into_inner());
+        let summary = latencies.entry(query.to_owned()).or_default();
+
+        summary.count += 1;
+        summary.total_seconds += seconds;
+        summary.max_seconds = summary.max_seconds.max(seconds);
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let counters = Counter::ALL
+            .iter()
+            .map(|counter| {
+                let value = self.counters[*counter as usize].load(Ordering::Relaxed);
+                (*counter, value)
+            })
+            .collect();
+
+        let latencies = self
+            .latencies
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone();
+
+        MetricsSnapshot {
+            counters,
+            latencies,
+        }
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Point in time copy of all the values in [Metrics]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricsSnapshot {
+    pub counters: Vec<(Counter, u64)>,
+    pub latencies: BTreeMap<String, LatencySummary>,
+}
+
+impl MetricsSnapshot {
+    pub fn counter(&self, counter: Counter) -> u64 {
+        self.counters
+            .iter()
+            .find(|(c, _)| *c == counter)
+            .map(|(_, value)| *value)
+            .unwrap_or(0)
+    }
+
+    /// Renders the snapshot in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut output = String::new();
+
+        for (counter, value) in &self.counters {
+            let name = counter.name();
+            let _ = writeln!(output, "# HELP {name} {}", counter.description());
+            let _ = writeln!(output, "# TYPE {name} counter");
+            let _ = writeln!(output, "{name} {value}");
+        }
+
+        let name = "vislog_query_latency_seconds";
+        let _ = writeln!(output, "# HELP {name} Time taken to answer queries");
+        let _ = writeln!(output, "# TYPE {name} summary");
+        for (query, summary) in &self.latencies {
+            let query = escape_label(query);
+            let _ = writeln!(
+                output,
+                "{name}_sum{{query=\"{query}\"}} {}",
+                summary.total_seconds
+            );
+            let _ = writeln!(
+                output,
+                "{name}_count{{query=\"{query}\"}} {}",
+                summary.count
+            );
+        }
+
+        let name = "vislog_query_latency_max_seconds";
+        let _ = writeln!(output, "# HELP {name} Longest time taken to answer a query");
+        let _ = writeln!(output, "# TYPE {name} gauge");
+        for (query, summary) in &self.latencies {
+            let query = escape_label(query);
+            let _ = writeln!(
+                output,
+                "{name}{{query=\"{query}\"}} {}",
+                summary.max_seconds
+            );
+        }
+
+        output
+    }
+}
+
+/// Escapes a label value of the Prometheus text exposition format
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nothing_is_recorded_until_enabled() {
+        let metrics = Metrics::new();
+
+        metrics.increment(Counter::CacheHits);
+        metrics.record_latency("programs", Duration::from_millis(5));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.counter(Counter::CacheHits), 0);
+        assert!(snapshot.latencies.is_empty());
+    }
+
+    #[test]
+    fn counters_and_latencies_are_recorded_when_enabled() {
+        let metrics = Metrics::new();
+        metrics.enable();
+
+        metrics.increment(Counter::CacheHits);
+        metrics.increment(Counter::CacheHits);
+        metrics.increment(Counter::ProgramParseFailures);
+        metrics.record_latency("programs", Duration::from_millis(10));
+        metrics.record_latency("programs", Duration::from_millis(30));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.counter(Counter::CacheHits), 2);
+        assert_eq!(snapshot.counter(Counter::ProgramParseFailures), 1);
+        assert_eq!(snapshot.counter(Counter::CacheMisses), 0);
+
+        let summary = snapshot.latencies["programs"];
+        assert_eq!(summary.count, 2);
+        assert!((summary.total_seconds - 0.04).abs() < 1e-9);
+        assert!((summary.max_seconds - 0.03).abs() < 1e-9);
+    }
+
+    #[test]
+    fn renders_prometheus_text_format() {
+        let metrics = Metrics::new();
+        metrics.enable();
+        metrics.increment(Counter::CacheMisses);
+        metrics.record_latency("/api/programs", Duration::from_secs(1));
+
+        let output = metrics.snapshot().to_prometheus();
+
+        assert!(output.contains("# TYPE vislog_cache_misses_total counter\n"));
+        assert!(output.contains("vislog_cache_misses_total 1\n"));
+        assert!(output.contains("vislog_cache_hits_total 0\n"));
+        assert!(output.contains("vislog_query_latency_seconds_sum{query=\"/api/programs\"} 1\n"));
+        assert!(output.contains("vislog_query_latency_seconds_count{query=\"/api/programs\"} 1\n"));
+        assert!(output.contains("# TYPE vislog_query_latency_max_seconds gauge\n"));
+        assert!(output.contains("vislog_query_latency_max_seconds{query=\"/api/programs\"} 1\n"));
+    }
+}
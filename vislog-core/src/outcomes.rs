@@ -0,0 +1,359 @@
+//! Matrices of the courses of programs against learning outcomes, for accreditation reports.
+//!
+//! Accreditors such as ABET ask which courses of a program address each of its student outcomes.
+//! The catalog doesn't know about outcomes, so the outcome codes of every course come from an
+//! [OutcomeMapping] kept next to the catalog, either as JSON:
+//!
+//! ```json
+//! { "860AF9C9-EAD9-45AC-AA92-BAF352C5288C": ["SO1", "SO6"] }
+//! ```
+//!
+//! or as CSV with a `guid` and an `outcome` column, which takes one row per course and outcome or
+//! several outcomes separated by semicolons:
+//!
+//! ```csv
+//! guid,outcome
+//! 860AF9C9-EAD9-45AC-AA92-BAF352C5288C,SO1;SO6
+//! ```
+//!
+//! An [OutcomeMatrix] cross-tabulates every course a program lists against every outcome of the
+//! mapping, so that courses without outcomes and outcomes that no course of the program addresses
+//! show up as gaps. [write_csv] writes it for spreadsheet software and
+//! [OutcomeMatrix::to_markdown] as a table for reports.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, outcomes::{self, OutcomeMapping}};
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//!
+//! let csv = "guid,outcome\n860AF9C9-EAD9-45AC-AA92-BAF352C5288C,SO1;SO6\n";
+//! let mapping = OutcomeMapping::from_csv(csv.as_bytes()).unwrap();
+//!
+//! let matrices = outcomes::matrices(&catalog, &mapping);
+//! assert_eq!(matrices[0].outcomes, ["SO1", "SO6"]);
+//! assert!(matrices[0].to_markdown().contains("| CSC 115 | "));
+//!
+//! let mut output = vec![];
+//! outcomes::write_csv(&mut output, &matrices[0]).unwrap();
+//! assert!(output.starts_with(b"course,name,SO1,SO6\r\n"));
+//! ```
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::Write as _,
+    io::{self, Read, Write},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    catalog::Catalog, export::csv::write_record, parsing::guid::Guid, text::clean_narrative,
+    Program,
+};
+
+/// Outcome codes of courses by their GUID, such as a file maintained next to the catalog
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OutcomeMapping(HashMap<Guid, BTreeSet<String>>);
+
+#[derive(Debug, Error)]
+pub enum OutcomeError {
+    #[error("failed to read CSV outcome mapping: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("the header {0:?} doesn't have a guid and an outcome column")]
+    MissingColumns(Vec<String>),
+    #[error("row {row}: {value:?} is not a valid GUID")]
+    Guid {
+        /// Row of the CSV, from 1 for the header row
+        row: u64,
+        value: String,
+    },
+}
+
+/// Courses of a program against every outcome of an [OutcomeMapping]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OutcomeMatrix {
+    pub program: Guid,
+    pub title: String,
+    /// Columns of the matrix, every outcome of the mapping in alphabetical order
+    pub outcomes: Vec<String>,
+    pub rows: Vec<OutcomeRow>,
+}
+
+/// A course of an [OutcomeMatrix]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OutcomeRow {
+    pub guid: Guid,
+    /// Subject code and number of the course. Ex: "CSC 115"
+    pub code: String,
+    pub name: Option<String>,
+    /// Whether the course addresses each of the [outcomes](OutcomeMatrix::outcomes), in order
+    pub addresses: Vec<bool>,
+}
+
+impl OutcomeMapping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a JSON object of outcome codes by course GUID. Ex:
+    /// `{"860AF9C9-EAD9-45AC-AA92-BAF352C5288C": ["SO1", "SO6"]}`
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Reads a CSV with a `guid` and an `outcome` column, in any order and among other columns.
+    /// Outcomes of a row are separated by semicolons, and rows without an outcome are skipped.
+    pub fn from_csv(reader: impl Read) -> Result<Self, OutcomeError> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+        let header = reader.headers()?.clone();
+        let column = |name: &str| {
+            header
+                .iter()
+                .position(|column| column.trim().eq_ignore_ascii_case(name))
+        };
+        let (Some(guid), Some(outcome)) = (column("guid"), column("outcome")) else {
+            return Err(OutcomeError::MissingColumns(
+                header.iter().map(Into::into).collect(),
+            ));
+        };
+
+        let mut mapping = Self::new();
+        for record in reader.records() {
+            let record = record?;
+            let field = |index: usize| record.get(index).unwrap_or_default().trim();
+            let outcomes: Vec<&str> = field(outcome)
+                .split(';')
+                .map(str::trim)
+                .filter(|outcome| !outcome.is_empty())
+                .collect();
+            if outcomes.is_empty() {
+                continue;
+            }
+
+            let course = Guid::try_from(field(guid)).map_err(|_| OutcomeError::Guid {
+                row: record.position().map_or(0, |position| position.line()),
+                value: field(guid).to_owned(),
+            })?;
+            for outcome in outcomes {
+                mapping.insert(course, outcome);
+            }
+        }
+
+        Ok(mapping)
+    }
+
+    /// Maps the course with the `guid` to the `outcome`
+    pub fn insert(&mut self, guid: Guid, outcome: impl Into<String>) {
+        self.0.entry(guid).or_default().insert(outcome.into());
+    }
+
+    /// Outcomes of the course with the `guid`, in alphabetical order
+    pub fn get(&self, guid: &Guid) -> Option<&BTreeSet<String>> {
+        self.0.get(guid)
+    }
+
+    /// Every outcome of the mapping, in alphabetical order
+    pub fn outcomes(&self) -> BTreeSet<&str> {
+        self.0.values().flatten().map(String::as_str).collect()
+    }
+}
+
+impl OutcomeMatrix {
+    /// Cross-tabulates every course the `program` lists, in the order it lists them, against the
+    /// outcomes of the `mapping`
+    pub fn new(program: &Program, mapping: &OutcomeMapping) -> Self {
+        let outcomes: Vec<String> = mapping.outcomes().into_iter().map(str::to_owned).collect();
+
+        let mut seen = HashSet::new();
+        let rows = program
+            .iter_courses()
+            .filter(|course| seen.insert(course.guid))
+            .map(|course| {
+                let addressed = mapping.get(&course.guid);
+                OutcomeRow {
+                    guid: course.guid,
+                    code: format!("{} {}", course.subject_code, course.number),
+                    name: course.name.as_deref().map(clean_narrative),
+                    addresses: outcomes
+                        .iter()
+                        .map(|outcome| {
+                            addressed.is_some_and(|addressed| addressed.contains(outcome))
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            program: program.guid,
+            title: program.title.clone(),
+            outcomes,
+            rows,
+        }
+    }
+
+    /// Outcomes that no course of the program addresses
+    pub fn uncovered(&self) -> Vec<&str> {
+        self.outcomes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.rows.iter().any(|row| row.addresses[*i]))
+            .map(|(_, outcome)| outcome.as_str())
+            .collect()
+    }
+
+    /// The matrix as a Markdown table under a heading with the title of the program, with an "X"
+    /// for every outcome a course addresses
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("## {}\n\n", escape_markdown(&self.title));
+
+        let _ = write!(markdown, "| Course | Name |");
+        for outcome in &self.outcomes {
+            let _ = write!(markdown, " {} |", escape_markdown(outcome));
+        }
+        markdown.push_str("\n| --- | --- |");
+        markdown.push_str(&" :-: |".repeat(self.outcomes.len()));
+        markdown.push('\n');
+
+        for row in &self.rows {
+            let _ = write!(
+                markdown,
+                "| {} | {} |",
+                escape_markdown(&row.code),
+                escape_markdown(row.name.as_deref().unwrap_or_default())
+            );
+            for addresses in &row.addresses {
+                markdown.push_str(if *addresses { " X |" } else { "  |" });
+            }
+            markdown.push('\n');
+        }
+
+        markdown
+    }
+}
+
+/// Matrices of every program of the `catalog`, with shared modules written into every program
+/// referring to them
+pub fn matrices(catalog: &Catalog, mapping: &OutcomeMapping) -> Vec<OutcomeMatrix> {
+    catalog
+        .inlined()
+        .programs
+        .iter()
+        .map(|program| OutcomeMatrix::new(program, mapping))
+        .collect()
+}
+
+/// Writes the `matrix` as CSV, with a `course` and a `name` column followed by a column for every
+/// outcome holding an "X" for the outcomes a course addresses
+pub fn write_csv<W: Write>(mut writer: W, matrix: &OutcomeMatrix) -> io::Result<()> {
+    let header = ["course", "name"]
+        .into_iter()
+        .chain(matrix.outcomes.iter().map(String::as_str));
+    write_record(&mut writer, header)?;
+
+    for row in &matrix.rows {
+        let cells = row
+            .addresses
+            .iter()
+            .map(|addresses| if *addresses { "X" } else { "" });
+        let fields = [row.code.as_str(), row.name.as_deref().unwrap_or_default()]
+            .into_iter()
+            .chain(cells);
+        write_record(&mut writer, fields)?;
+    }
+
+    writer.flush()
+}
+
+/// Escapes the characters that would break a cell of a Markdown table or format its text
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '|' | '*' | '_' | '`' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn guid(s: &str) -> Guid {
+        Guid::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn mappings_are_read_from_csv_and_json() {
+        let csv = "\
+Course,GUID,Outcome
+CSC 115,860AF9C9-EAD9-45AC-AA92-BAF352C5288C,SO1; SO6
+CSC 115,860AF9C9-EAD9-45AC-AA92-BAF352C5288C,SO2
+CSC 999,,
+";
+        let mapping = OutcomeMapping::from_csv(csv.as_bytes()).unwrap();
+        let csc_115 = guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C");
+        assert_eq!(
+            mapping.get(&csc_115).unwrap().iter().collect::<Vec<_>>(),
+            ["SO1", "SO2", "SO6"]
+        );
+
+        let json = serde_json::to_string(&mapping).unwrap();
+        assert_eq!(OutcomeMapping::from_json(&json).unwrap(), mapping);
+
+        let error = OutcomeMapping::from_csv("guid,outcome\nCSC 115,SO1\n".as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "row 2: \"CSC 115\" is not a valid GUID");
+        assert!(matches!(
+            OutcomeMapping::from_csv("guid,criteria\n".as_bytes()),
+            Err(OutcomeError::MissingColumns(_))
+        ));
+    }
+
+    #[test]
+    fn courses_are_cross_tabulated_against_every_outcome() {
+        let (catalog, _) = Catalog::parse_file("../data/cs_minor.json");
+        let program = &catalog.programs[0];
+        let csc_115 = guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C");
+        let mut mapping = OutcomeMapping::new();
+        mapping.insert(csc_115, "SO1");
+        mapping.insert(csc_115, "SO6");
+        // Outcome of a course the program doesn't list
+        mapping.insert(guid("00000000-0000-0000-0000-000000000001"), "SO3");
+
+        let matrix = OutcomeMatrix::new(program, &mapping);
+        assert_eq!(matrix.outcomes, ["SO1", "SO3", "SO6"]);
+        assert_eq!(matrix.uncovered(), ["SO3"]);
+        assert_eq!(
+            matrix.rows.len(),
+            program
+                .iter_courses()
+                .map(|course| course.guid)
+                .collect::<HashSet<_>>()
+                .len()
+        );
+        let row = matrix.rows.iter().find(|row| row.guid == csc_115).unwrap();
+        assert_eq!(row.code, "CSC 115");
+        assert_eq!(row.addresses, [true, false, true]);
+
+        let markdown = matrix.to_markdown();
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines[2], "| Course | Name | SO1 | SO3 | SO6 |");
+        assert_eq!(lines[3], "| --- | --- | :-: | :-: | :-: |");
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("| CSC 115 | ") && line.ends_with(" | X |  | X |")));
+
+        let mut output = vec![];
+        write_csv(&mut output, &matrix).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), matrix.rows.len() + 1);
+        assert!(output.contains("\r\nCSC 115,") && output.contains(",X,,X\r\n"));
+    }
+}
@@ -0,0 +1,541 @@
+//! A [Program] borrowing its text from the JSON it was parsed from.
+//!
+//! Most of the time spent parsing a program goes into allocating strings for its HTML content,
+//! titles and narratives. Pipelines that parse a program, analyze it and throw it away can parse a
+//! [ProgramRef] instead, whose strings point into the input buffer. Strings with JSON escapes,
+//! such as `\"` or `\u2014`, can't be borrowed as is and are the only ones still allocated.
+//!
+//! Requirements are classified the same way as the ones of a [Program], and the courses of a
+//! requirement are the usual [CourseEntries], whose codes and URLs are [symbols](crate::symbol)
+//! anyway. [ProgramRef::into_owned] turns a `ProgramRef` into the [Program] that parsing the same
+//! JSON would have given.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{parsing::borrowed::ProgramRef, Program};
+//! let json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+//!
+//! let program: ProgramRef = serde_json::from_str(&json).unwrap();
+//! let credits: u32 = program.iter_courses().map(|course| course.credits.0 as u32).sum();
+//! assert!(credits > 0);
+//!
+//! let owned: Program = serde_json::from_str(&json).unwrap();
+//! assert_eq!(program.into_owned(), owned);
+//! ```
+
+use std::{borrow::Cow, fmt};
+
+use serde::{de, Deserialize, Deserializer};
+
+use crate::{
+    constraints::Constraints,
+    electives::ElectiveHours,
+    extensions::Extensions,
+    parsing::{
+        guid::{deserialize_catalog_guid, Guid},
+        requirement_constraints, RequirementKind,
+    },
+    Course, CourseEntries, Program, Requirement, RequirementModule, Requirements,
+};
+
+/// Borrowed counterpart of [Program]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ProgramRef<'a> {
+    #[serde(borrow, deserialize_with = "borrow_str")]
+    pub url: Cow<'a, str>,
+    #[serde(deserialize_with = "deserialize_catalog_guid")]
+    #[serde(alias = "GUID")]
+    pub guid: Guid,
+    #[serde(borrow, deserialize_with = "borrow_str")]
+    pub title: Cow<'a, str>,
+    #[serde(borrow, default, deserialize_with = "borrow_optional_str")]
+    pub content: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "borrow_optional_str")]
+    pub bottom_content: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    pub requirements: Option<RequirementsRef<'a>>,
+    #[cfg_attr(feature = "preserve-unknown", serde(flatten))]
+    #[cfg_attr(not(feature = "preserve-unknown"), serde(skip))]
+    pub extra: Extensions,
+}
+
+/// Borrowed counterpart of [Requirements]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequirementsRef<'a> {
+    Single(RequirementModuleRef<'a>),
+    Many(Vec<RequirementModuleRef<'a>>),
+}
+
+/// Borrowed counterpart of the [RequirementModule]s found in the catalog API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequirementModuleRef<'a> {
+    SingleBasicRequirement {
+        title: Option<Cow<'a, str>>,
+        requirement: RequirementRef<'a>,
+    },
+    BasicRequirements {
+        title: Option<Cow<'a, str>>,
+        requirements: Vec<RequirementRef<'a>>,
+    },
+}
+
+/// Borrowed counterpart of [Requirement]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequirementRef<'a> {
+    Courses {
+        title: Option<Cow<'a, str>>,
+        courses: CourseEntries,
+        constraints: Constraints,
+        extra: Extensions,
+    },
+    SelectFromCourses {
+        title: Cow<'a, str>,
+        courses: Option<CourseEntries>,
+        constraints: Constraints,
+        extra: Extensions,
+    },
+    Label {
+        title: Option<Cow<'a, str>>,
+        req_narrative: Option<Cow<'a, str>>,
+        constraints: Constraints,
+        extra: Extensions,
+    },
+    ElectivePool {
+        title: Option<Cow<'a, str>>,
+        req_narrative: Option<Cow<'a, str>>,
+        hours: ElectiveHours,
+        constraints: Constraints,
+        extra: Extensions,
+    },
+}
+
+impl ProgramRef<'_> {
+    /// Every requirement of the program in the order they appear in the catalog
+    pub fn iter_requirements(&self) -> impl Iterator<Item = &RequirementRef<'_>> {
+        self.requirements
+            .iter()
+            .flat_map(RequirementsRef::modules)
+            .flat_map(RequirementModuleRef::requirements)
+    }
+
+    /// Every [Course] of the program in the order they appear in the catalog, including the ones
+    /// nested inside of operator groups
+    pub fn iter_courses(&self) -> impl Iterator<Item = &Course> {
+        self.iter_requirements()
+            .filter_map(RequirementRef::course_entries)
+            .flat_map(CourseEntries::iter_courses)
+    }
+
+    /// The [Program] owning copies of the borrowed strings
+    pub fn into_owned(self) -> Program {
+        Program {
+            url: self.url.into_owned(),
+            guid: self.guid,
+            title: self.title.into_owned(),
+            content: self.content.map(Cow::into_owned),
+            bottom_content: self.bottom_content.map(Cow::into_owned),
+            requirements: self.requirements.map(RequirementsRef::into_owned),
+            extra: self.extra,
+        }
+    }
+}
+
+impl<'a> RequirementsRef<'a> {
+    pub fn modules(&self) -> &[RequirementModuleRef<'a>] {
+        match self {
+            RequirementsRef::Single(module) => std::slice::from_ref(module),
+            RequirementsRef::Many(modules) => modules,
+        }
+    }
+
+    pub fn into_owned(self) -> Requirements {
+        match self {
+            RequirementsRef::Single(module) => Requirements::Single(module.into_owned()),
+            RequirementsRef::Many(modules) => Requirements::Many(
+                modules
+                    .into_iter()
+                    .map(RequirementModuleRef::into_owned)
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl<'a> RequirementModuleRef<'a> {
+    pub fn title(&self) -> Option<&str> {
+        match self {
+            RequirementModuleRef::SingleBasicRequirement { title, .. }
+            | RequirementModuleRef::BasicRequirements { title, .. } => title.as_deref(),
+        }
+    }
+
+    pub fn requirements(&self) -> &[RequirementRef<'a>] {
+        match self {
+            RequirementModuleRef::SingleBasicRequirement { requirement, .. } => {
+                std::slice::from_ref(requirement)
+            }
+            RequirementModuleRef::BasicRequirements { requirements, .. } => requirements,
+        }
+    }
+
+    pub fn into_owned(self) -> RequirementModule {
+        match self {
+            RequirementModuleRef::SingleBasicRequirement { title, requirement } => {
+                RequirementModule::SingleBasicRequirement {
+                    title: title.map(Cow::into_owned),
+                    requirement: requirement.into_owned(),
+                }
+            }
+            RequirementModuleRef::BasicRequirements {
+                title,
+                requirements,
+            } => RequirementModule::BasicRequirements {
+                title: title.map(Cow::into_owned),
+                requirements: requirements
+                    .into_iter()
+                    .map(RequirementRef::into_owned)
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl RequirementRef<'_> {
+    pub fn title(&self) -> Option<&str> {
+        match self {
+            RequirementRef::Courses { title, .. }
+            | RequirementRef::Label { title, .. }
+            | RequirementRef::ElectivePool { title, .. } => title.as_deref(),
+            RequirementRef::SelectFromCourses { title, .. } => Some(title),
+        }
+    }
+
+    pub fn constraints(&self) -> Constraints {
+        match self {
+            RequirementRef::Courses { constraints, .. }
+            | RequirementRef::SelectFromCourses { constraints, .. }
+            | RequirementRef::Label { constraints, .. }
+            | RequirementRef::ElectivePool { constraints, .. } => *constraints,
+        }
+    }
+
+    pub fn course_entries(&self) -> Option<&CourseEntries> {
+        match self {
+            RequirementRef::Courses { courses, .. } => Some(courses),
+            RequirementRef::SelectFromCourses { courses, .. } => courses.as_ref(),
+            RequirementRef::Label { .. } | RequirementRef::ElectivePool { .. } => None,
+        }
+    }
+
+    pub fn into_owned(self) -> Requirement {
+        match self {
+            RequirementRef::Courses {
+                title,
+                courses,
+                constraints,
+                extra,
+            } => Requirement::Courses {
+                title: title.map(Cow::into_owned),
+                courses,
+                constraints,
+                extra,
+            },
+            RequirementRef::SelectFromCourses {
+                title,
+                courses,
+                constraints,
+                extra,
+            } => Requirement::SelectFromCourses {
+                title: title.into_owned(),
+                courses,
+                constraints,
+                extra,
+            },
+            RequirementRef::Label {
+                title,
+                req_narrative,
+                constraints,
+                extra,
+            } => Requirement::Label {
+                title: title.map(Cow::into_owned),
+                req_narrative: req_narrative.map(Cow::into_owned),
+                constraints,
+                course_refs: vec![],
+                extra,
+            },
+            RequirementRef::ElectivePool {
+                title,
+                req_narrative,
+                hours,
+                constraints,
+                extra,
+            } => Requirement::ElectivePool {
+                title: title.map(Cow::into_owned),
+                req_narrative: req_narrative.map(Cow::into_owned),
+                hours,
+                constraints,
+                extra,
+            },
+        }
+    }
+}
+
+/// Fields of a requirement in the catalog API
+#[derive(Deserialize)]
+struct RawRequirement<'a> {
+    #[serde(borrow, default, deserialize_with = "borrow_optional_str")]
+    title: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "borrow_optional_str")]
+    req_narrative: Option<Cow<'a, str>>,
+    #[serde(default)]
+    course: Option<CourseEntries>,
+    /// Fields left over by the others, with the `preserve-unknown` feature
+    #[cfg_attr(feature = "preserve-unknown", serde(flatten))]
+    #[cfg_attr(not(feature = "preserve-unknown"), serde(skip))]
+    extra: Extensions,
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for RequirementRef<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RawRequirement {
+            title,
+            req_narrative,
+            course,
+            extra,
+        } = RawRequirement::deserialize(deserializer)?;
+
+        let constraints = requirement_constraints(title.as_deref(), req_narrative.as_deref());
+        let kind =
+            RequirementKind::classify(title.as_deref(), req_narrative.as_deref(), course.is_some());
+
+        let requirement = match (kind, title, course) {
+            (RequirementKind::Select, Some(title), courses) => RequirementRef::SelectFromCourses {
+                title,
+                courses,
+                constraints,
+                extra,
+            },
+            (RequirementKind::ElectivePool(hours), title, _) => RequirementRef::ElectivePool {
+                title,
+                req_narrative,
+                hours,
+                constraints,
+                extra,
+            },
+            (RequirementKind::Courses, title, Some(courses)) => RequirementRef::Courses {
+                title,
+                courses,
+                constraints,
+                extra,
+            },
+            (_, title, _) => RequirementRef::Label {
+                title,
+                req_narrative,
+                constraints,
+                extra,
+            },
+        };
+
+        Ok(requirement)
+    }
+}
+
+/// Fields of a requirement module in the catalog API
+#[derive(Deserialize)]
+struct RawModule<'a> {
+    #[serde(borrow, default, deserialize_with = "borrow_optional_str")]
+    title: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    requirement_list: RawRequirementList<'a>,
+}
+
+/// The `requirement_list` of a module, which is an object when the module has a single
+/// requirement
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawRequirementList<'a> {
+    /// A requirement with a single course object instead of an array of them
+    SingleCourse {
+        #[serde(borrow, default, deserialize_with = "borrow_optional_str")]
+        title: Option<Cow<'a, str>>,
+        course: Course,
+    },
+    Single(#[serde(borrow)] RequirementRef<'a>),
+    Many(#[serde(borrow)] Vec<RequirementRef<'a>>),
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for RequirementModuleRef<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RawModule {
+            title,
+            requirement_list,
+        } = RawModule::deserialize(deserializer)?;
+
+        let module = match requirement_list {
+            RawRequirementList::SingleCourse {
+                title: req_title,
+                course,
+            } => RequirementModuleRef::SingleBasicRequirement {
+                title,
+                requirement: RequirementRef::Courses {
+                    constraints: requirement_constraints(req_title.as_deref(), None),
+                    title: req_title,
+                    courses: CourseEntries::from(vec![course.into()]),
+                    extra: Extensions::default(),
+                },
+            },
+            RawRequirementList::Single(requirement) => {
+                RequirementModuleRef::SingleBasicRequirement { title, requirement }
+            }
+            RawRequirementList::Many(requirements) => RequirementModuleRef::BasicRequirements {
+                title,
+                requirements,
+            },
+        };
+
+        Ok(module)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for RequirementsRef<'a> {
+    /// A single module as an object, or an array of modules that each hold several requirements
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Modules of an array always have an array of requirements
+        #[derive(Deserialize)]
+        struct RawListModule<'a> {
+            #[serde(borrow, default, deserialize_with = "borrow_optional_str")]
+            title: Option<Cow<'a, str>>,
+            #[serde(borrow)]
+            requirement_list: Vec<RequirementRef<'a>>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawRequirements<'a> {
+            Single(#[serde(borrow)] RequirementModuleRef<'a>),
+            Many(#[serde(borrow)] Vec<RawListModule<'a>>),
+        }
+
+        let requirements = match RawRequirements::deserialize(deserializer)? {
+            RawRequirements::Single(module) => RequirementsRef::Single(module),
+            RawRequirements::Many(modules) => RequirementsRef::Many(
+                modules
+                    .into_iter()
+                    .map(|module| RequirementModuleRef::BasicRequirements {
+                        title: module.title,
+                        requirements: module.requirement_list,
+                    })
+                    .collect(),
+            ),
+        };
+
+        Ok(requirements)
+    }
+}
+
+/// Borrows the string from the input when it has no escapes
+fn borrow_str<'de: 'a, 'a, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Cow<'a, str>, D::Error> {
+    struct CowVisitor;
+
+    impl<'de> de::Visitor<'de> for CowVisitor {
+        type Value = Cow<'de, str>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a string")
+        }
+
+        fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+            Ok(Cow::Borrowed(v))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Ok(Cow::Owned(v.to_owned()))
+        }
+
+        fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+            Ok(Cow::Owned(v))
+        }
+    }
+
+    deserializer.deserialize_str(CowVisitor)
+}
+
+fn borrow_optional_str<'de: 'a, 'a, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Cow<'a, str>>, D::Error> {
+    struct OptionVisitor;
+
+    impl<'de> de::Visitor<'de> for OptionVisitor {
+        type Value = Option<Cow<'de, str>>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a string or null")
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            borrow_str(deserializer).map(Some)
+        }
+    }
+
+    deserializer.deserialize_option(OptionVisitor)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn borrowed_programs_match_owned_ones() {
+        let json = std::fs::read_to_string("../data/programs.json").unwrap();
+        let dump: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let programs = dump["programs"]["program"].as_array().unwrap();
+
+        let mut parsed = 0;
+        for program in programs {
+            let json = program.to_string();
+            match (
+                serde_json::from_str::<ProgramRef>(&json),
+                serde_json::from_str::<Program>(&json),
+            ) {
+                (Ok(borrowed), Ok(owned)) => {
+                    assert!(borrowed.iter_courses().eq(owned.iter_courses()));
+                    assert_eq!(borrowed.into_owned(), owned);
+                    parsed += 1;
+                }
+                (Err(_), Err(_)) => {}
+                (borrowed, owned) => panic!(
+                    "{}: borrowed {:?}, owned {:?}",
+                    program["title"],
+                    borrowed.err(),
+                    owned.err()
+                ),
+            }
+        }
+        assert!(parsed > programs.len() / 2);
+    }
+
+    #[test]
+    fn strings_without_escapes_are_borrowed() {
+        let json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+        let program: ProgramRef = serde_json::from_str(&json).unwrap();
+
+        assert!(matches!(program.url, Cow::Borrowed(_)));
+        assert!(program
+            .iter_requirements()
+            .filter_map(RequirementRef::title)
+            .any(|title| json.as_bytes().as_ptr_range().contains(&title.as_ptr())));
+    }
+}
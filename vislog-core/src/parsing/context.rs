@@ -0,0 +1,231 @@
+//! Anomalies in the JSON that parsing recovers from.
+//!
+//! Some input doesn't quite follow the catalog API but can still be parsed, such as credits of
+//! `"1.5"` that are truncated to 1. Parsing goes on without failing, but the [Warning]s raised
+//! along the way are collected by a [ParseContext] for callers that want to surface them. Like the
+//! [dialect](super::dialect), the `Deserialize` implementations of the data model can't take
+//! arguments, so warnings are only collected on the current thread for the duration of
+//! [ParseContext::collect]. Warnings raised outside of it are dropped.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{parsing::context::Warning, Program};
+//! let json = std::fs::read_to_string("../data/cs_minor.json")
+//!     .unwrap()
+//!     .replacen(r#""credits": "3""#, r#""credits": "1.0-3.5""#, 1);
+//!
+//! let (program, warnings) = Program::parse_with_warnings(&json).unwrap();
+//! assert_eq!(
+//!     warnings,
+//!     [Warning::CreditsTruncated {
+//!         input: "3.5".to_owned(),
+//!         credits: 3
+//!     }]
+//! );
+//! ```
+
+use std::cell::RefCell;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{self, VislogError},
+    parsing::guid::Guid,
+    CourseDetails, Program,
+};
+
+/// Something that didn't follow the catalog API but was parsed anyway
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", content = "data")]
+pub enum Warning {
+    /// Credits with a fraction, truncated to whole credits
+    CreditsTruncated { input: String, credits: u8 },
+    /// A GUID without the curly braces that surround it in the catalog API
+    GuidWithoutBraces { input: String },
+    /// A narrative course entry without a name, which is parsed as a course instead of a
+    /// [Label](crate::Label)
+    UnnamedNarrative { guid: Guid },
+    /// A module, requirement or course entry that failed to parse and was left out of its list
+    /// because of [skip_invalid_entries](super::options::ParseOptions::skip_invalid_entries)
+    SkippedEntry {
+        /// Index of the entry in its list, counting the entries skipped before it
+        index: usize,
+        reason: String,
+    },
+}
+
+/// Collects the [Warning]s raised while parsing
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseContext {
+    warnings: Vec<Warning>,
+}
+
+thread_local! {
+    /// Warnings raised on the current thread, `None` outside of [ParseContext::collect]
+    static WARNINGS: RefCell<Option<Vec<Warning>>> = const { RefCell::new(None) };
+}
+
+impl ParseContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses everything in `f`, adding the warnings raised on the current thread to the context.
+    /// Contexts collecting in `f` keep the warnings raised inside of them to themselves.
+    pub fn collect<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        /// Restores the previous collection even if `f` panics
+        struct Restore<'a> {
+            previous: Option<Vec<Warning>>,
+            context: &'a mut ParseContext,
+        }
+
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                let collected = WARNINGS.with(|current| current.replace(self.previous.take()));
+                self.context.warnings.extend(collected.unwrap_or_default());
+            }
+        }
+
+        let _restore = Restore {
+            previous: WARNINGS.with(|current| current.replace(Some(vec![]))),
+            context: self,
+        };
+        f()
+    }
+
+    /// Warnings collected so far, in the order they were raised
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    pub fn into_warnings(self) -> Vec<Warning> {
+        self.warnings
+    }
+}
+
+/// Raises the `warning` to the [ParseContext] collecting on the current thread, if any
+pub(crate) fn warn(warning: Warning) {
+    WARNINGS.with(|current| {
+        if let Some(warnings) = current.borrow_mut().as_mut() {
+            warnings.push(warning);
+        }
+    });
+}
+
+/// Runs `f`, dropping the warnings and the [VislogError] it raised if it fails. For input that is
+/// tried more than once or skipped, so that only the warnings of the attempt that succeeded are
+/// kept and a later failure isn't reported as this one.
+pub(crate) fn attempt<T, E>(f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    let raised = WARNINGS.with(|current| current.borrow().as_ref().map(Vec::len));
+    let result = f();
+    if result.is_err() {
+        error::clear_pending();
+    }
+    if let (Err(_), Some(raised)) = (&result, raised) {
+        WARNINGS.with(|current| {
+            if let Some(warnings) = current.borrow_mut().as_mut() {
+                warnings.truncate(raised);
+            }
+        });
+    }
+
+    result
+}
+
+fn parse_with_warnings<'de, T: Deserialize<'de>>(
+    json: &'de str,
+) -> Result<(T, Vec<Warning>), VislogError> {
+    let mut context = ParseContext::new();
+    let value = context.collect(|| error::from_str(json))?;
+
+    Ok((value, context.into_warnings()))
+}
+
+impl Program {
+    /// Parses a program like [error::from_str], along with the [Warning]s raised while parsing it
+    pub fn parse_with_warnings(json: &str) -> Result<(Program, Vec<Warning>), VislogError> {
+        parse_with_warnings(json)
+    }
+}
+
+impl CourseDetails {
+    /// Parses a course like [error::from_str], along with the [Warning]s raised while parsing it
+    pub fn parse_with_warnings(json: &str) -> Result<(CourseDetails, Vec<Warning>), VislogError> {
+        parse_with_warnings(json)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cs_minor() -> String {
+        std::fs::read_to_string("../data/cs_minor.json").unwrap()
+    }
+
+    #[test]
+    fn catalog_data_parses_without_warnings() {
+        let (program, warnings) = Program::parse_with_warnings(&cs_minor()).unwrap();
+        assert_eq!(warnings, []);
+        assert_eq!(program, serde_json::from_str(&cs_minor()).unwrap());
+    }
+
+    #[test]
+    fn anomalies_are_warned_about() {
+        let guid = "860AF9C9-EAD9-45AC-AA92-BAF352C5288C";
+        let json = cs_minor().replacen(&format!("{{{guid}}}"), guid, 1);
+        let (_, warnings) = Program::parse_with_warnings(&json).unwrap();
+        assert_eq!(
+            warnings,
+            [Warning::GuidWithoutBraces {
+                input: guid.to_owned()
+            }]
+        );
+
+        // Warnings of programs that fail to parse aren't returned, and don't leak into the next
+        let json = cs_minor()
+            .replacen(r#""credits": "3""#, r#""credits": "2.5-3""#, 1)
+            .replacen(r#""url""#, r#""link""#, 1);
+        assert!(Program::parse_with_warnings(&json).is_err());
+        assert_eq!(Program::parse_with_warnings(&cs_minor()).unwrap().1, []);
+
+        // Outside of a context, warnings are dropped
+        let json = cs_minor().replacen(r#""credits": "3""#, r#""credits": "2.5-3.5""#, 1);
+        let program: Program = serde_json::from_str(&json).unwrap();
+        let mut context = ParseContext::new();
+        assert_eq!(
+            context.collect(|| error::from_str(&json)).ok(),
+            Some(program)
+        );
+        assert_eq!(
+            context.warnings(),
+            [
+                Warning::CreditsTruncated {
+                    input: "2.5".to_owned(),
+                    credits: 2
+                },
+                Warning::CreditsTruncated {
+                    input: "3.5".to_owned(),
+                    credits: 3
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn unnamed_narratives_are_warned_about() {
+        let programs = std::fs::read_to_string("../data/programs.json").unwrap();
+        let mut context = ParseContext::new();
+        let (catalog, _) =
+            context.collect(|| crate::catalog::Catalog::parse_json("programs.json", &programs));
+
+        let unnamed = context
+            .warnings()
+            .iter()
+            .filter(|warning| matches!(warning, Warning::UnnamedNarrative { .. }))
+            .count();
+        assert!(unnamed > 0);
+        assert!(!catalog.programs.is_empty());
+    }
+}
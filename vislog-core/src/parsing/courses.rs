@@ -1,12 +1,20 @@
-use core::panic;
 use std::mem;
+use std::sync::Arc;
 
 use anyhow::anyhow;
 use anyhow::Error as AnyhowError;
-use serde::Deserialize;
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer,
+};
 use thiserror::Error;
 
-use crate::parsing::guid::Guid;
+use crate::error::{self, VislogError};
+use crate::extensions::{self, Extensions};
+use crate::parsing::context::{self, Warning};
+use crate::parsing::dialect;
+use crate::parsing::{options, ScalarString, COURSE_ENTRY_FIELDS};
+use crate::symbol::Symbol;
 use crate::Label;
 use crate::{Course, CourseEntries, CourseEntry};
 
@@ -32,10 +40,70 @@ pub enum ParseCoursesState {
     NestedTerminatingBlankRead,
 }
 
+/// State machine that groups a flat list of course entries into a tree of [CourseEntry]s based on
+/// the `And`/`Or` operator entries in between them.
+///
+/// The catalog API represents operators as narrative entries named `"And"` and `"Or"`, and uses
+/// blank narrative entries (named `""`) to delimit operator groups. The entries are grouped as
+/// follows:
+///
+/// - Courses and labels without an operator in between them are kept as-is:
+///   `A B` becomes `[A, B]`
+/// - Courses and labels joined by the same operator form a group:
+///   `A Or B Or C` becomes `[Or(A, B, C)]`
+/// - A blank entry terminates the current group. If it is followed by an operator and another
+///   blank entry, the terminated group is nested inside of a group of that operator:
+///   `A And B _ Or _ C And D` becomes `[Or(And(A, B), And(C, D))]`
+/// - Nesting groups can be chained with the same operator, and a different operator nests the
+///   whole nesting group one level deeper:
+///   `A And B _ Or _ C And D _ And _ E Or F` becomes
+///   `[And(Or(And(A, B), And(C, D)), Or(E, F))]`
+/// - A blank entry followed by a course or label ends the group and continues on:
+///   `A Or B _ C` becomes `[Or(A, B), C]`
+/// - Entries between `(` and `)` markers are grouped on their own, to any depth, and the group
+///   takes the place of a single course: `A Or ( B And C )` becomes `[Or(A, And(B, C))]`. A
+///   group holding more than one entry without an operator becomes an `And` group.
+///
+/// Entries can't start with an operator and an operator can't be followed by another operator.
+///
+/// Use [CourseEntries::from_raw_entries] to group entries coming straight from the catalog API, or
+/// [CoursesParser::parse_entry] and [CoursesParser::finish] to feed already parsed entries one at a
+/// time.
+///
+/// # Example
+/// ```
+/// # use vislog_core::{parsing::courses::RawCourseEntry, CourseEntries, CourseEntry};
+/// let raw_entries: Vec<RawCourseEntry> = serde_json::from_value(serde_json::json!([
+///     {
+///         "url": "", "path": "", "guid": "{860AF9C9-EAD9-45AC-AA92-BAF352C5288C}",
+///         "name": "Computer Science I", "number": "115", "subject_name": "Computer Science",
+///         "subject_code": "CSC", "credits": "3", "is_narrative": "False"
+///     },
+///     {
+///         "url": "", "path": "", "guid": "{1002D167-9303-4865-8E0A-CE2B3E2BFF95}",
+///         "name": "Or", "number": null, "subject_name": null,
+///         "subject_code": null, "credits": "0", "is_narrative": "True"
+///     },
+///     {
+///         "url": "", "path": "", "guid": "{13A1385C-81AC-493D-ACE8-AA8AB37D2C81}",
+///         "name": "Computer Science II", "number": "125", "subject_name": "Computer Science",
+///         "subject_code": "CSC", "credits": "3", "is_narrative": "False"
+///     }
+/// ]))
+/// .unwrap();
+///
+/// let entries = CourseEntries::from_raw_entries(raw_entries).unwrap();
+///
+/// assert_eq!(entries.len(), 1);
+/// assert!(matches!(&entries[0], CourseEntry::Or(group) if group.len() == 2));
+/// ```
 pub struct CoursesParser {
     raw_entries: Vec<RawCourseEntry>,
     state: ParseCoursesState,
     parsing_state: ParsingState,
+    /// State of the groups enclosing the group currently being parsed, innermost last. Groups are
+    /// opened and closed by [ParsedCourseEntry::BeginGroup] and [ParsedCourseEntry::EndGroup]
+    enclosing_groups: Vec<(ParseCoursesState, ParsingState)>,
 }
 
 /// Stores the `CourseEntry`s and other information currently/already parsed by the `CourseParser`
@@ -66,6 +134,7 @@ impl CoursesParser {
             raw_entries,
             state: ParseCoursesState::InitialState,
             parsing_state: ParsingState::initial(),
+            enclosing_groups: vec![],
         }
     }
 
@@ -74,6 +143,7 @@ impl CoursesParser {
     ///
     /// NOTE: The `parse` method consumes the `CoursesParser` to avoid having inconsistent statese being
     /// represented and `parse` or `finish` being called in those states
+    #[allow(clippy::result_large_err)]
     pub fn parse(mut self) -> Result<CourseEntries, ParseCoursesError> {
         // process entries
         for raw_entry in mem::take(&mut self.raw_entries) {
@@ -86,832 +156,370 @@ impl CoursesParser {
         self.finish()
     }
 
+    /// Feeds a single entry into the state machine. Call [CoursesParser::finish] once all the
+    /// entries have been fed to get the resulting `CourseEntries`.
+    #[allow(clippy::result_large_err)]
     pub fn parse_entry(&mut self, entry: ParsedCourseEntry) -> Result<(), ParseCoursesError> {
+        match entry {
+            ParsedCourseEntry::BeginGroup => {
+                // Park the enclosing group and parse the new group from a clean slate
+                let state = mem::replace(&mut self.state, ParseCoursesState::InitialState);
+                let parsing_state = mem::take(&mut self.parsing_state);
+                self.enclosing_groups.push((state, parsing_state));
+
+                Ok(())
+            }
+            ParsedCourseEntry::EndGroup => {
+                let (state, parsing_state) = self
+                    .enclosing_groups
+                    .pop()
+                    .ok_or(ParseCoursesError::UnmatchedGroupEnd)?;
+
+                let group = self.finish_group()?;
+
+                self.state = state;
+                self.parsing_state = parsing_state;
+
+                // The whole group takes the place of a single course in the enclosing group
+                let group = match <[CourseEntry; 1]>::try_from(group.into_vec()) {
+                    Ok([entry]) => entry,
+                    Err(entries) => CourseEntry::And(CourseEntries::from(entries)),
+                };
+
+                self.parse_operand(group)
+            }
+            ParsedCourseEntry::And => self.parse_operator(Operator::And),
+            ParsedCourseEntry::Or => self.parse_operator(Operator::Or),
+            ParsedCourseEntry::Blank => self.parse_blank(),
+            ParsedCourseEntry::Label(label) => self.parse_operand(CourseEntry::Label(label)),
+            ParsedCourseEntry::Course(course) => self.parse_operand(course.into()),
+            ParsedCourseEntry::Group(group) => self.parse_operand(group),
+        }
+    }
+
+    /// Handles entries that can be an element of an operator group, which are courses, labels,
+    /// and groups that have already been parsed
+    #[allow(clippy::result_large_err)]
+    fn parse_operand(&mut self, entry: CourseEntry) -> Result<(), ParseCoursesError> {
         use ParseCoursesError::*;
         use ParseCoursesState::*;
 
         match self.state {
-            InitialState => match entry {
-                ParsedCourseEntry::And | ParsedCourseEntry::Or => return Err(InvalidEntry(entry)),
-                ParsedCourseEntry::Blank => {
-                    self.state = InitialBlankRead;
-                    Ok(())
-                }
-                ParsedCourseEntry::Label(label) => {
-                    self.parsing_state
-                        .course_buffer
-                        .get_or_insert(vec![])
-                        .push(CourseEntry::Label(label));
-                    self.state = CourseDetection;
-                    Ok(())
-                }
-                ParsedCourseEntry::Course(course) => {
-                    self.parsing_state
-                        .course_buffer
-                        .get_or_insert(vec![])
-                        .push(CourseEntry::Course(course));
-                    self.state = CourseDetection;
-                    Ok(())
-                }
-            },
-            CourseDetection => match entry {
-                ParsedCourseEntry::And => {
-                    let _ = self.parsing_state.operator.insert(Operator::And);
-                    self.state = OperatorRead;
-                    Ok(())
-                }
-                ParsedCourseEntry::Or => {
-                    let _ = self.parsing_state.operator.insert(Operator::Or);
-                    self.state = OperatorRead;
-                    Ok(())
-                }
-                ParsedCourseEntry::Blank => {
-                    self.state = InitialBlankRead;
-                    Ok(())
-                }
-                ParsedCourseEntry::Label(label) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        buf.push(CourseEntry::Label(label));
-                        Ok(())
-                    }
-                    None => Err(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    ))),
-                },
-                ParsedCourseEntry::Course(course) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        buf.push(CourseEntry::Course(course));
-                        Ok(())
-                    }
-                    None => Err(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    ))),
-                },
-            },
-            InitialBlankRead => match entry {
-                ParsedCourseEntry::And | ParsedCourseEntry::Or | ParsedCourseEntry::Blank => {
-                    Err(InvalidEntry(entry))
-                }
-                ParsedCourseEntry::Label(label) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        // Swap the memory between the new operator group and the free courses in
-                        // the `state.course_buffer` and assign the free courses originally in the
-                        // `state.course_buffer` to `free_courses`
-                        let free_courses = {
-                            let mut new_operator_group = vec![CourseEntry::Label(label)];
-                            mem::swap(buf, &mut new_operator_group);
-                            new_operator_group
-                        };
-                        // Convert courses currently in the coure_buffer that are not part of an operator
-                        // group into `CourseEntry`(s) and push into `state.entries`
-                        self.parsing_state.entries.extend(free_courses);
-
-                        self.state = ReadCourseNoOp;
-                        Ok(())
-                    }
-                    None => {
-                        self.state = ReadCourseNoOp;
-                        Ok(())
-                    }
-                },
-                ParsedCourseEntry::Course(course) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        // Swap the memory between the new operator group and the free courses in
-                        // the `state.course_buffer` and assign the free courses originally in the
-                        // `state.course_buffer` to `free_courses`
-                        let free_courses = {
-                            let mut new_operator_group = vec![CourseEntry::Course(course)];
-                            mem::swap(buf, &mut new_operator_group);
-                            new_operator_group
-                        };
-                        // Convert courses currently in the coure_buffer that are not part of an operator
-                        // group into `CourseEntry`(s) and push into `state.entries`
-                        self.parsing_state.entries.extend(free_courses);
-
-                        self.state = ReadCourseNoOp;
-                        Ok(())
-                    }
-                    None => {
-                        self.state = ReadCourseNoOp;
-                        Ok(())
-                    }
-                },
-            },
-            ReadCourseNoOp => match entry {
-                ParsedCourseEntry::And => {
-                    if let Some(operator) = self.parsing_state.operator {
-                        Err(ParsingError(anyhow!(
-                            "`operator` should be None at state: {:?}. Got: {:?}",
-                            self.state,
-                            operator
-                        )))
-                    } else {
-                        let _ = self.parsing_state.operator.insert(Operator::And);
-                        self.state = OperatorRead;
-                        Ok(())
-                    }
-                }
-                ParsedCourseEntry::Or => {
-                    if let Some(operator) = self.parsing_state.operator {
-                        Err(ParsingError(anyhow!(
-                            "`operator` should be None at state: {:?}. Got: {:?}",
-                            self.state,
-                            operator
-                        )))
-                    } else {
-                        let _ = self.parsing_state.operator.insert(Operator::Or);
-                        self.state = OperatorRead;
-                        Ok(())
-                    }
-                }
-                ParsedCourseEntry::Blank => Err(InvalidEntry(entry)),
-                ParsedCourseEntry::Label(label) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        buf.push(CourseEntry::Label(label));
-                        self.state = ReadCourseNoOp;
-                        Ok(())
-                    }
-                    None => Err(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    ))),
-                },
-                ParsedCourseEntry::Course(course) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        buf.push(CourseEntry::Course(course));
-                        self.state = ReadCourseNoOp;
-                        Ok(())
-                    }
-                    None => Err(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    ))),
-                },
-            },
-            OperatorRead => match entry {
-                ParsedCourseEntry::And | ParsedCourseEntry::Or | ParsedCourseEntry::Blank => {
-                    Err(InvalidEntry(entry))
-                }
-                ParsedCourseEntry::Label(label) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        buf.push(CourseEntry::Label(label));
-                        self.state = ReadCourseWithOp;
-                        Ok(())
-                    }
-                    None => Err(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    ))),
-                },
-                ParsedCourseEntry::Course(course) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        buf.push(CourseEntry::Course(course));
-                        self.state = ReadCourseWithOp;
-                        Ok(())
-                    }
-                    None => Err(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    ))),
-                },
-            },
-            ReadCourseWithOp => match entry {
-                ParsedCourseEntry::And | ParsedCourseEntry::Or => {
-                    let current_operator = self.parsing_state.operator.ok_or(ParsingError(
-                        anyhow!("`operator` should not be None at state: {:?}", self.state),
-                    ))?;
-
-                    let new_operator = match entry {
-                        ParsedCourseEntry::And => Operator::And,
-                        ParsedCourseEntry::Or => Operator::Or,
-                        _ => panic!("This should not happen because the enclosing match condition gurantees that"),
-                    };
-
-                    if new_operator == current_operator {
-                        self.state = OperatorRead;
-                        Ok(())
-                    } else {
-                        Err(ParsingError(anyhow!(
-                            "Expected {:?}, Got {:?}.",
-                            current_operator,
-                            new_operator
-                        )))
-                    }
-                }
-                ParsedCourseEntry::Blank => {
-                    self.state = TerminatingBlankRead;
-                    Ok(())
-                }
-                ParsedCourseEntry::Label(label) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        buf.push(CourseEntry::Label(label));
-                        Ok(())
-                    }
-                    None => Err(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    ))),
-                },
-                ParsedCourseEntry::Course(course) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        buf.push(CourseEntry::Course(course));
-                        Ok(())
-                    }
-                    None => Err(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    ))),
-                },
-            },
+            InitialState => {
+                self.parsing_state
+                    .course_buffer
+                    .get_or_insert(vec![])
+                    .push(entry);
+                self.state = CourseDetection;
+            }
+            CourseDetection | ReadCourseNoOp | ReadCourseWithOp | NestedReadCourseWithOp => {
+                self.course_buffer()?.push(entry);
+            }
+            InitialBlankRead => {
+                // Courses currently in the `course_buffer` are not part of the operator group
+                // that is about to start, so move them into `entries` as-is
+                let free_courses = self
+                    .parsing_state
+                    .course_buffer
+                    .replace(vec![entry])
+                    .unwrap_or_default();
+                self.parsing_state.entries.extend(free_courses);
+
+                self.state = ReadCourseNoOp;
+            }
+            OperatorRead => {
+                self.course_buffer()?.push(entry);
+                self.state = ReadCourseWithOp;
+            }
             TerminatingBlankRead => {
-                match entry {
-                    ParsedCourseEntry::And | ParsedCourseEntry::Or => {
-                        let buf = self.parsing_state.course_buffer.take().ok_or(ParsingError(
-                            anyhow!("`course_buf` should not be None at state: {:?}", self.state),
-                        ))?;
-
-                        let courses = CourseEntries(buf);
-
-                        let operator =
-                            self.parsing_state
-                                .operator
-                                .take()
-                                .ok_or(ParsingError(anyhow!(
-                                    "`operator` should not be None at state: {:?}",
-                                    self.state
-                                )))?;
-
-                        let operator_entry = match operator {
-                            Operator::And => CourseEntry::And(courses),
-                            Operator::Or => CourseEntry::Or(courses),
-                        };
-
-                        let nesting_entry = match entry {
-                        ParsedCourseEntry::And => {
-                            CourseEntry::And(CourseEntries(vec![operator_entry]))
-                        }
-                        ParsedCourseEntry::Or => {
-                            CourseEntry::Or(CourseEntries(vec![operator_entry]))
-                        },
-                        invalid_entry => panic!("entry should be either the `ParsedCourseEntry::And` or `ParsedCourseEntry::Or` variants. Got: {:?}", invalid_entry),
-                    };
+                let operator_group = self.take_operator_group()?;
+                self.parsing_state.entries.push(operator_group);
 
-                        self.parsing_state.entries.push(nesting_entry);
+                let _ = self.parsing_state.course_buffer.insert(vec![entry]);
+                self.state = CourseDetection;
+            }
+            NestingOperatorRead => return Err(InvalidEntry(entry.into())),
+            NestedInitialBlankRead | NestedReadCourseNoOp => {
+                self.parsing_state
+                    .course_buffer
+                    .get_or_insert(vec![])
+                    .push(entry);
+                self.state = NestedReadCourseNoOp;
+            }
+            NestedOperatorRead => {
+                self.course_buffer()?.push(entry);
+                self.state = NestedReadCourseWithOp;
+            }
+            NestedTerminatingBlankRead => {
+                let operator_group = self.take_operator_group()?;
+                self.push_into_nesting_group(operator_group)?;
 
-                        self.state = NestingOperatorRead;
-                        Ok(())
-                    }
-                    ParsedCourseEntry::Blank => Err(InvalidEntry(entry)),
-                    ParsedCourseEntry::Label(label) => {
-                        // Append parsed Operator group to `state.entries`
-                        let buf = self.parsing_state.course_buffer.take().ok_or(ParsingError(
-                            anyhow!("`course_buf` should not be None at state: {:?}", self.state),
-                        ))?;
-                        let courses = CourseEntries(buf);
-                        let operator =
-                            self.parsing_state
-                                .operator
-                                .take()
-                                .ok_or(ParsingError(anyhow!(
-                                    "`operator` should not be None at state: {:?}",
-                                    self.state
-                                )))?;
-                        let operator_entry = match operator {
-                            Operator::And => CourseEntry::And(courses),
-                            Operator::Or => CourseEntry::Or(courses),
-                        };
-                        self.parsing_state.entries.push(operator_entry);
-
-                        // Append new course to new `state.course_buffer`
-                        self.parsing_state
-                            .course_buffer
-                            .insert(Vec::new())
-                            .push(CourseEntry::Label(label));
+                let _ = self.parsing_state.course_buffer.insert(vec![entry]);
+                self.state = CourseDetection;
+            }
+        }
 
-                        self.state = CourseDetection;
-                        Ok(())
-                    }
-                    ParsedCourseEntry::Course(course) => {
-                        // Append parsed Operator group to `state.entries`
-                        let buf = self.parsing_state.course_buffer.take().ok_or(ParsingError(
-                            anyhow!("`course_buf` should not be None at state: {:?}", self.state),
-                        ))?;
-                        let courses = CourseEntries(buf);
-                        let operator =
-                            self.parsing_state
-                                .operator
-                                .take()
-                                .ok_or(ParsingError(anyhow!(
-                                    "`operator` should not be None at state: {:?}",
-                                    self.state
-                                )))?;
-                        let operator_entry = match operator {
-                            Operator::And => CourseEntry::And(courses),
-                            Operator::Or => CourseEntry::Or(courses),
-                        };
-                        self.parsing_state.entries.push(operator_entry);
-
-                        // Append new course to new `state.course_buffer`
-                        self.parsing_state
-                            .course_buffer
-                            .insert(Vec::new())
-                            .push(CourseEntry::Course(course));
+        Ok(())
+    }
 
-                        self.state = CourseDetection;
-                        Ok(())
-                    }
-                }
+    #[allow(clippy::result_large_err)]
+    fn parse_operator(&mut self, operator: Operator) -> Result<(), ParseCoursesError> {
+        use ParseCoursesError::*;
+        use ParseCoursesState::*;
+
+        match self.state {
+            InitialState
+            | InitialBlankRead
+            | OperatorRead
+            | NestingOperatorRead
+            | NestedInitialBlankRead
+            | NestedOperatorRead => return Err(InvalidEntry(operator.into())),
+            CourseDetection => {
+                let _ = self.parsing_state.operator.insert(operator);
+                self.state = OperatorRead;
             }
-            NestingOperatorRead => match entry {
-                ParsedCourseEntry::And | ParsedCourseEntry::Or => Err(InvalidEntry(entry)),
-                ParsedCourseEntry::Blank => {
-                    self.state = NestedInitialBlankRead;
-                    Ok(())
+            ReadCourseNoOp => {
+                if let Some(current_operator) = self.parsing_state.operator {
+                    return Err(ParsingError(anyhow!(
+                        "`operator` should be None at state: {:?}. Got: {:?}",
+                        self.state,
+                        current_operator
+                    )));
                 }
-                ParsedCourseEntry::Label(_) => Err(InvalidEntry(entry)),
-                ParsedCourseEntry::Course(_) => Err(InvalidEntry(entry)),
-            },
-            NestedInitialBlankRead => match entry {
-                ParsedCourseEntry::And | ParsedCourseEntry::Or => Err(InvalidEntry(entry)),
-                ParsedCourseEntry::Blank => Err(InvalidEntry(entry)),
-                ParsedCourseEntry::Label(label) => {
-                    self.parsing_state
-                        .course_buffer
-                        .get_or_insert(Vec::new())
-                        .push(CourseEntry::Label(label));
 
-                    self.state = NestedReadCourseNoOp;
-                    Ok(())
-                }
-                ParsedCourseEntry::Course(course) => {
-                    self.parsing_state
-                        .course_buffer
-                        .get_or_insert(Vec::new())
-                        .push(CourseEntry::Course(course));
-
-                    self.state = NestedReadCourseNoOp;
-                    Ok(())
-                }
-            },
-            NestedReadCourseNoOp => match entry {
-                ParsedCourseEntry::And => {
-                    let _ = self.parsing_state.operator.insert(Operator::And);
-                    self.state = NestedOperatorRead;
-                    Ok(())
-                }
-                ParsedCourseEntry::Or => {
-                    let _ = self.parsing_state.operator.insert(Operator::Or);
-                    self.state = NestedOperatorRead;
-                    Ok(())
+                let _ = self.parsing_state.operator.insert(operator);
+                self.state = OperatorRead;
+            }
+            NestedReadCourseNoOp => {
+                let _ = self.parsing_state.operator.insert(operator);
+                self.state = NestedOperatorRead;
+            }
+            ReadCourseWithOp | NestedReadCourseWithOp => {
+                // Operators can't be mixed within the same group without blanks delimiting them
+                let current_operator = self.parsing_state.operator.ok_or(ParsingError(anyhow!(
+                    "`operator` should not be None at state: {:?}",
+                    self.state
+                )))?;
+
+                if current_operator != operator {
+                    return Err(ParsingError(anyhow!(
+                        "Expected {:?}, Got {:?}.",
+                        current_operator,
+                        operator
+                    )));
                 }
-                ParsedCourseEntry::Blank => Err(InvalidEntry(entry)),
-                ParsedCourseEntry::Label(label) => {
-                    self.parsing_state
-                        .course_buffer
-                        .get_or_insert(Vec::new())
-                        .push(CourseEntry::Label(label));
 
-                    Ok(())
-                }
-                ParsedCourseEntry::Course(course) => {
-                    self.parsing_state
-                        .course_buffer
-                        .get_or_insert(Vec::new())
-                        .push(CourseEntry::Course(course));
+                self.state = match self.state {
+                    ReadCourseWithOp => OperatorRead,
+                    _ => NestedOperatorRead,
+                };
+            }
+            TerminatingBlankRead => {
+                // Start a nesting operator group with the group that was just terminated as its
+                // first element
+                let operator_group = self.take_operator_group()?;
+                let nesting_group = operator.group(CourseEntries::from(vec![operator_group]));
+                self.parsing_state.entries.push(nesting_group);
 
-                    Ok(())
-                }
-            },
-            NestedOperatorRead => match entry {
-                ParsedCourseEntry::And | ParsedCourseEntry::Or | ParsedCourseEntry::Blank => {
-                    Err(InvalidEntry(entry))
-                }
-                ParsedCourseEntry::Label(label) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        buf.push(CourseEntry::Label(label));
-                        self.state = NestedReadCourseWithOp;
-                        Ok(())
-                    }
-                    None => Err(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    ))),
-                },
-                ParsedCourseEntry::Course(course) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        buf.push(CourseEntry::Course(course));
-                        self.state = NestedReadCourseWithOp;
-                        Ok(())
-                    }
-                    None => Err(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    ))),
-                },
-            },
-            NestedReadCourseWithOp => match entry {
-                ParsedCourseEntry::And | ParsedCourseEntry::Or => Err(InvalidEntry(entry)),
-                ParsedCourseEntry::Blank => {
-                    self.state = NestedTerminatingBlankRead;
-                    Ok(())
-                }
-                ParsedCourseEntry::Label(label) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        buf.push(CourseEntry::Label(label));
-                        Ok(())
-                    }
-                    None => Err(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    ))),
-                },
-                ParsedCourseEntry::Course(course) => match self.parsing_state.course_buffer {
-                    Some(ref mut buf) => {
-                        buf.push(CourseEntry::Course(course));
-                        Ok(())
-                    }
-                    None => Err(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    ))),
-                },
-            },
+                self.state = NestingOperatorRead;
+            }
             NestedTerminatingBlankRead => {
-                match entry {
-                    ParsedCourseEntry::And | ParsedCourseEntry::Or => {
-                        // Create operator group for courses in the `state.course_buffer` and add it to
-                        // the current nesting operator group
-                        let buf = self.parsing_state.course_buffer.take().ok_or(ParsingError(
-                            anyhow!("`course_buf` should not be None at state: {:?}", self.state),
-                        ))?;
-
-                        let courses = CourseEntries(buf);
-
-                        let operator =
-                            self.parsing_state
-                                .operator
-                                .take()
-                                .ok_or(ParsingError(anyhow!(
-                                    "`operator` should not be None at state: {:?}",
-                                    self.state
-                                )))?;
-
-                        let operator_entry = match operator {
-                            Operator::And => CourseEntry::And(courses),
-                            Operator::Or => CourseEntry::Or(courses),
-                        };
-
-                        let nesting_operator_group =
-                            self.parsing_state
-                                .entries
-                                .last_mut()
-                                .ok_or(ParsingError(anyhow!(
-                                    "there should be at least one entry in `entries`",
-                                )))?;
-
-                        // Push `operator_entry` into `nesting_operator_group` and get the
-                        // `nesting_operator` at the same time
-                        let nesting_operator = match nesting_operator_group {
-                            CourseEntry::And(group) => {
-                                group.push(operator_entry);
-                                Operator::And
-                            }
-                            CourseEntry::Or(group) => {
-                                group.push(operator_entry);
-                                Operator::Or
-                            }
-                            invalid_course_entry => {
-                                return Err(ParsingError(anyhow!("Got invalid `CourseEntry` when getting nesting operator group: {:?}", invalid_course_entry)));
-                            }
-                        };
-
-                        // Determine whether to continue to add to the current nesting operator group
-                        // or double nesting has occurred (continue if `nesting_operator` ==
-                        // new_operator, double nesting if they differ)
-                        let new_operator = match entry {
-                            ParsedCourseEntry::And => Operator::And,
-                            ParsedCourseEntry::Or => Operator::Or,
-                            _ => panic!("`entry` should always be either `ParsedCourseEntry::And` or `ParsedCourseEntry::Or` "),
-                        };
-
-                        if nesting_operator == new_operator {
-                            self.state = NestingOperatorRead;
-                            Ok(())
-                        } else {
-                            Err(DoubleNesting)
-                        }
-                    }
-                    ParsedCourseEntry::Blank => Err(InvalidEntry(entry)),
-                    // TODO: Find a way to eliminate the consistent repeating of parsing logic
-                    // between `Label` and `Course`
-                    ParsedCourseEntry::Label(label) => {
-                        match self.parsing_state.course_buffer {
-                            Some(ref mut buf) => {
-                                // // Swap the memory between the new buffer and the operator group in
-                                // // the `state.course_buffer` and assign the operator_group originally in the
-                                // // `state.course_buffer` to `operator_group`
-                                let operator_group_courses = {
-                                    let mut new_buffer = vec![CourseEntry::Label(label)];
-                                    mem::swap(buf, &mut new_buffer);
-                                    new_buffer
-                                };
-
-                                let courses = CourseEntries(operator_group_courses);
-
-                                let operator = self.parsing_state.operator.take().ok_or(
-                                    ParsingError(anyhow!(
-                                        "`operator` should not be None at state: {:?}",
-                                        self.state
-                                    )),
-                                )?;
-
-                                let operator_entry = match operator {
-                                    Operator::And => CourseEntry::And(courses),
-                                    Operator::Or => CourseEntry::Or(courses),
-                                };
-
-                                let nesting_operator_group =
-                                    self.parsing_state.entries.last_mut().ok_or(ParsingError(
-                                        anyhow!("there should be at least one entry in `entries`",),
-                                    ))?;
-
-                                // Push `operator_entry` into `nesting_operator_group`
-                                match nesting_operator_group {
-                                    CourseEntry::And(group) => {
-                                        group.push(operator_entry);
-                                    }
-                                    CourseEntry::Or(group) => {
-                                        group.push(operator_entry);
-                                    }
-                                    invalid_course_entry => {
-                                        return Err(ParsingError(anyhow!("Got invalid `CourseEntry` when getting nesting operator group: {:?}", invalid_course_entry)));
-                                    }
-                                };
-
-                                self.state = CourseDetection;
-                                Ok(())
-                            }
-                            None => Err(ParsingError(anyhow!(
-                                "`course_buf` should not be None at state: {:?}",
-                                self.state
-                            ))),
-                        }
-                    }
-                    ParsedCourseEntry::Course(course) => {
-                        match self.parsing_state.course_buffer {
-                            Some(ref mut buf) => {
-                                // // Swap the memory between the new buffer and the operator group in
-                                // // the `state.course_buffer` and assign the operator_group originally in the
-                                // // `state.course_buffer` to `operator_group`
-                                let operator_group_courses = {
-                                    let mut new_buffer = vec![CourseEntry::Course(course)];
-                                    mem::swap(buf, &mut new_buffer);
-                                    new_buffer
-                                };
-
-                                let courses = CourseEntries(operator_group_courses);
-
-                                let operator = self.parsing_state.operator.take().ok_or(
-                                    ParsingError(anyhow!(
-                                        "`operator` should not be None at state: {:?}",
-                                        self.state
-                                    )),
-                                )?;
-
-                                let operator_entry = match operator {
-                                    Operator::And => CourseEntry::And(courses),
-                                    Operator::Or => CourseEntry::Or(courses),
-                                };
-
-                                let nesting_operator_group =
-                                    self.parsing_state.entries.last_mut().ok_or(ParsingError(
-                                        anyhow!("there should be at least one entry in `entries`",),
-                                    ))?;
-
-                                // Push `operator_entry` into `nesting_operator_group`
-                                match nesting_operator_group {
-                                    CourseEntry::And(group) => {
-                                        group.push(operator_entry);
-                                    }
-                                    CourseEntry::Or(group) => {
-                                        group.push(operator_entry);
-                                    }
-                                    invalid_course_entry => {
-                                        return Err(ParsingError(anyhow!("Got invalid `CourseEntry` when getting nesting operator group: {:?}", invalid_course_entry)));
-                                    }
-                                };
-
-                                self.state = CourseDetection;
-                                Ok(())
-                            }
-                            None => Err(ParsingError(anyhow!(
-                                "`course_buf` should not be None at state: {:?}",
-                                self.state
-                            ))),
-                        }
-                    }
+                let operator_group = self.take_operator_group()?;
+                let nesting_operator = self.push_into_nesting_group(operator_group)?;
+
+                // A different operator nests the current nesting group one level deeper, so
+                // `(A & B) | (C & D) & (E & F)` becomes `((A & B) | (C & D)) & (E & F)`
+                if nesting_operator != operator {
+                    let nesting_group =
+                        self.parsing_state
+                            .entries
+                            .pop()
+                            .ok_or(ParsingError(anyhow!(
+                                "there should be at least one entry in `entries`"
+                            )))?;
+                    self.parsing_state
+                        .entries
+                        .push(operator.group(CourseEntries::from(vec![nesting_group])));
                 }
+
+                self.state = NestingOperatorRead;
             }
         }
+
+        Ok(())
     }
 
-    /// Call this method when there are no more `RawCourseEntry`s to be processed
-    fn finish(mut self) -> Result<CourseEntries, ParseCoursesError> {
+    #[allow(clippy::result_large_err)]
+    fn parse_blank(&mut self) -> Result<(), ParseCoursesError> {
         use ParseCoursesError::*;
         use ParseCoursesState::*;
 
-        let entries = match self.state {
-            // Invalid finishing states
-            InitialState
-            | InitialBlankRead
+        self.state = match self.state {
+            InitialState | CourseDetection => InitialBlankRead,
+            ReadCourseWithOp => TerminatingBlankRead,
+            NestingOperatorRead => NestedInitialBlankRead,
+            NestedReadCourseWithOp => NestedTerminatingBlankRead,
+            InitialBlankRead
             | ReadCourseNoOp
             | OperatorRead
-            | NestingOperatorRead
+            | TerminatingBlankRead
             | NestedInitialBlankRead
             | NestedReadCourseNoOp
-            | NestedOperatorRead => Err(InvalidFinish(self.state)),
+            | NestedOperatorRead
+            | NestedTerminatingBlankRead => return Err(InvalidEntry(ParsedCourseEntry::Blank)),
+        };
 
-            // Valid finishing states
-            CourseDetection => {
-                let buf = self
-                    .parsing_state
-                    .course_buffer
-                    .take()
-                    .ok_or(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    )))?;
+        Ok(())
+    }
 
-                let entries = &mut self.parsing_state.entries;
-                entries.extend(buf);
+    /// The `course_buffer` of states where it is expected to be populated
+    #[allow(clippy::result_large_err)]
+    fn course_buffer(&mut self) -> Result<&mut Vec<CourseEntry>, ParseCoursesError> {
+        self.parsing_state
+            .course_buffer
+            .as_mut()
+            .ok_or(ParseCoursesError::ParsingError(anyhow!(
+                "`course_buf` should not be None at state: {:?}",
+                self.state
+            )))
+    }
 
-                Ok(CourseEntries(mem::take(entries)))
-            }
-            ReadCourseWithOp => {
-                let operator = self
-                    .parsing_state
-                    .operator
-                    .take()
-                    .ok_or(ParsingError(anyhow!(
-                        "`operator` should not e None at state: {:?}",
-                        self.state
-                    )))?;
-
-                let buf = self
-                    .parsing_state
-                    .course_buffer
-                    .take()
-                    .ok_or(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    )))?;
-
-                let operator_entry = match operator {
-                    Operator::And => CourseEntry::And(CourseEntries(buf)),
-                    Operator::Or => CourseEntry::Or(CourseEntries(buf)),
-                };
+    /// Turns the `course_buffer` and `operator` into an operator group, leaving both empty
+    #[allow(clippy::result_large_err)]
+    fn take_operator_group(&mut self) -> Result<CourseEntry, ParseCoursesError> {
+        use ParseCoursesError::*;
 
-                let entries = &mut self.parsing_state.entries;
-                entries.push(operator_entry);
+        let operator = self
+            .parsing_state
+            .operator
+            .take()
+            .ok_or(ParsingError(anyhow!(
+                "`operator` should not be None at state: {:?}",
+                self.state
+            )))?;
+
+        let buf = self
+            .parsing_state
+            .course_buffer
+            .take()
+            .ok_or(ParsingError(anyhow!(
+                "`course_buf` should not be None at state: {:?}",
+                self.state
+            )))?;
+
+        Ok(operator.group(CourseEntries::from(buf)))
+    }
 
-                Ok(CourseEntries(mem::take(entries)))
+    /// Pushes `entry` into the nesting operator group at the end of `entries` and returns the
+    /// operator of that group
+    #[allow(clippy::result_large_err)]
+    fn push_into_nesting_group(
+        &mut self,
+        entry: CourseEntry,
+    ) -> Result<Operator, ParseCoursesError> {
+        use ParseCoursesError::*;
+
+        let nesting_operator_group =
+            self.parsing_state
+                .entries
+                .last_mut()
+                .ok_or(ParsingError(anyhow!(
+                    "there should be at least one entry in `entries`",
+                )))?;
+
+        match nesting_operator_group {
+            CourseEntry::And(group) => {
+                group.push(entry);
+                Ok(Operator::And)
             }
-            TerminatingBlankRead => {
-                let operator = self
-                    .parsing_state
-                    .operator
-                    .take()
-                    .ok_or(ParsingError(anyhow!(
-                        "`operator` should not e None at state: {:?}",
-                        self.state
-                    )))?;
-
-                match operator {
-                    Operator::And => {
-                        let and_entries = CourseEntry::And(CourseEntries(mem::take(
-                            &mut self.parsing_state.entries,
-                        )));
-                        Ok(CourseEntries(vec![and_entries]))
-                    }
-                    Operator::Or => {
-                        let or_entries = CourseEntry::Or(CourseEntries(mem::take(
-                            &mut self.parsing_state.entries,
-                        )));
-                        Ok(CourseEntries(vec![or_entries]))
-                    }
-                }
+            CourseEntry::Or(group) => {
+                group.push(entry);
+                Ok(Operator::Or)
             }
-            NestedReadCourseWithOp => {
-                let operator = self
-                    .parsing_state
-                    .operator
-                    .take()
-                    .ok_or(ParsingError(anyhow!(
-                        "`operator` should not e None at state: {:?}",
-                        self.state
-                    )))?;
-
-                let buf = self
-                    .parsing_state
-                    .course_buffer
-                    .take()
-                    .ok_or(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    )))?;
+            invalid_course_entry => Err(ParsingError(anyhow!(
+                "Got invalid `CourseEntry` when getting nesting operator group: {:?}",
+                invalid_course_entry
+            ))),
+        }
+    }
 
-                let courses = CourseEntries(buf);
+    /// Call this method when there are no more `RawCourseEntry`s to be processed
+    #[allow(clippy::result_large_err)]
+    pub fn finish(mut self) -> Result<CourseEntries, ParseCoursesError> {
+        if !self.enclosing_groups.is_empty() {
+            return Err(ParseCoursesError::UnclosedGroups(
+                self.enclosing_groups.len(),
+            ));
+        }
 
-                let operator_entry = match operator {
-                    Operator::And => CourseEntry::And(courses),
-                    Operator::Or => CourseEntry::Or(courses),
-                };
+        self.finish_group()
+    }
 
-                let nesting_operator_group =
-                    self.parsing_state
-                        .entries
-                        .last_mut()
-                        .ok_or(ParsingError(anyhow!(
-                            "there should be at least one entry in `entries`",
-                        )))?;
-
-                match nesting_operator_group {
-                    CourseEntry::And(group) => {
-                        group.push(operator_entry);
-                        Operator::And
-                    }
-                    CourseEntry::Or(group) => {
-                        group.push(operator_entry);
-                        Operator::Or
-                    }
-                    invalid_course_entry => {
-                        return Err(ParsingError(anyhow!(
-                            "Got invalid `CourseEntry` when getting nesting operator group: {:?}",
-                            invalid_course_entry
-                        )));
-                    }
-                };
+    /// Collects the `CourseEntries` of the group currently being parsed
+    #[allow(clippy::result_large_err)]
+    fn finish_group(&mut self) -> Result<CourseEntries, ParseCoursesError> {
+        use ParseCoursesState::*;
+
+        let state = mem::replace(&mut self.state, InitialState);
+
+        match state {
+            // Invalid finishing states
+            InitialState
+            | InitialBlankRead
+            | ReadCourseNoOp
+            | OperatorRead
+            | NestingOperatorRead
+            | NestedInitialBlankRead
+            | NestedReadCourseNoOp
+            | NestedOperatorRead => return Err(ParseCoursesError::InvalidFinish(state)),
 
-                Ok(CourseEntries(mem::take(&mut self.parsing_state.entries)))
+            // Valid finishing states
+            CourseDetection => {
+                self.state = state;
+                let buf = self.course_buffer().map(mem::take)?;
+                self.parsing_state.entries.extend(buf);
             }
-            NestedTerminatingBlankRead => {
-                let operator = self
-                    .parsing_state
-                    .operator
-                    .take()
-                    .ok_or(ParsingError(anyhow!(
-                        "`operator` should not e None at state: {:?}",
-                        self.state
-                    )))?;
-
-                let buf = self
-                    .parsing_state
-                    .course_buffer
-                    .take()
-                    .ok_or(ParsingError(anyhow!(
-                        "`course_buf` should not be None at state: {:?}",
-                        self.state
-                    )))?;
+            ReadCourseWithOp | TerminatingBlankRead => {
+                self.state = state;
+                let operator_group = self.take_operator_group()?;
+                self.parsing_state.entries.push(operator_group);
+            }
+            NestedReadCourseWithOp | NestedTerminatingBlankRead => {
+                self.state = state;
+                let operator_group = self.take_operator_group()?;
+                self.push_into_nesting_group(operator_group)?;
+            }
+        }
 
-                let courses = CourseEntries(buf);
+        self.state = InitialState;
+        let parsing_state = mem::take(&mut self.parsing_state);
 
-                let operator_entry = match operator {
-                    Operator::And => CourseEntry::And(courses),
-                    Operator::Or => CourseEntry::Or(courses),
-                };
+        Ok(CourseEntries::from(parsing_state.entries))
+    }
+}
 
-                let nesting_operator_group =
-                    self.parsing_state
-                        .entries
-                        .last_mut()
-                        .ok_or(ParsingError(anyhow!(
-                            "there should be at least one entry in `entries`",
-                        )))?;
-
-                match nesting_operator_group {
-                    CourseEntry::And(group) => {
-                        group.push(operator_entry);
-                        Operator::And
-                    }
-                    CourseEntry::Or(group) => {
-                        group.push(operator_entry);
-                        Operator::Or
-                    }
-                    invalid_course_entry => {
-                        return Err(ParsingError(anyhow!(
-                            "Got invalid `CourseEntry` when getting nesting operator group: {:?}",
-                            invalid_course_entry
-                        )));
-                    }
-                };
+impl CourseEntries {
+    /// Groups the raw entries of a requirement into `CourseEntries` using a [CoursesParser]
+    #[allow(clippy::result_large_err)]
+    pub fn from_raw_entries(
+        raw_entries: Vec<RawCourseEntry>,
+    ) -> Result<CourseEntries, ParseCoursesError> {
+        CoursesParser::new(raw_entries).parse()
+    }
 
-                Ok(CourseEntries(mem::take(&mut self.parsing_state.entries)))
-            }
-        };
+    /// Same as [CourseEntries::from_raw_entries] but for entries that have already been converted
+    /// into [ParsedCourseEntry]s
+    #[allow(clippy::result_large_err)]
+    pub fn from_parsed_entries<I>(entries: I) -> Result<CourseEntries, ParseCoursesError>
+    where
+        I: IntoIterator<Item = ParsedCourseEntry>,
+    {
+        let mut parser = CoursesParser::new(vec![]);
+        for entry in entries {
+            parser.parse_entry(entry)?;
+        }
 
-        entries
+        parser.finish()
     }
 }
 
@@ -921,26 +529,130 @@ pub enum Operator {
     Or,
 }
 
-#[derive(Debug, Deserialize)]
+impl Operator {
+    /// The operator group of this operator containing `entries`
+    pub fn group(self, entries: CourseEntries) -> CourseEntry {
+        match self {
+            Operator::And => CourseEntry::And(entries),
+            Operator::Or => CourseEntry::Or(entries),
+        }
+    }
+}
+
+/// A course entry as found in the `course` list of a requirement in the catalog API
+#[derive(Debug, Clone)]
 pub struct RawCourseEntry {
-    url: String,
-    path: String,
-    guid: String,
-    name: Option<String>,
-    number: Option<String>,
-    subject_name: Option<String>,
-    subject_code: Option<String>,
-    credits: String,
-    is_narrative: String,
+    pub url: String,
+    pub path: String,
+    /// GUID surrounded by curly braces. Ex: `{860AF9C9-EAD9-45AC-AA92-BAF352C5288C}`
+    pub guid: String,
+    /// `"And"`, `"Or"` or `""` for operator and blank entries
+    pub name: Option<String>,
+    pub number: Option<String>,
+    pub subject_name: Option<Symbol>,
+    pub subject_code: Option<Symbol>,
+    pub credits: String,
+    /// `"True"` for labels, operators and blank entries, `"False"` for courses
+    pub is_narrative: String,
+    pub extra: Extensions,
+}
+
+impl<'de> Deserialize<'de> for RawCourseEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RawCourseEntryVisitor;
+
+        impl<'de> Visitor<'de> for RawCourseEntryVisitor {
+            type Value = RawCourseEntry;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a JSON object representing a course entry of a requirement")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                error::clear_pending();
+
+                let mut url: Option<String> = None;
+                let mut path: Option<String> = None;
+                let mut guid: Option<String> = None;
+                let mut name: Option<Option<String>> = None;
+                let mut number: Option<Option<ScalarString>> = None;
+                let mut subject_name: Option<Option<Symbol>> = None;
+                let mut subject_code: Option<Option<Symbol>> = None;
+                let mut credits: Option<ScalarString> = None;
+                let mut is_narrative: Option<ScalarString> = None;
+                let mut extra = Extensions::default();
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "url" => options::next_field(&mut map, &mut url, "url")?,
+                        "path" => options::next_field(&mut map, &mut path, "path")?,
+                        "guid" => options::next_field(&mut map, &mut guid, "guid")?,
+                        "name" => options::next_field(&mut map, &mut name, "name")?,
+                        "number" => options::next_field(&mut map, &mut number, "number")?,
+                        "subject_name" => {
+                            options::next_field(&mut map, &mut subject_name, "subject_name")?
+                        }
+                        "subject_code" => {
+                            options::next_field(&mut map, &mut subject_code, "subject_code")?
+                        }
+                        "credits" => options::next_field(&mut map, &mut credits, "credits")?,
+                        "is_narrative" => {
+                            options::next_field(&mut map, &mut is_narrative, "is_narrative")?
+                        }
+                        _ => extensions::next_unknown(
+                            &mut map,
+                            &mut extra,
+                            key,
+                            COURSE_ENTRY_FIELDS,
+                        )?,
+                    }
+                }
+
+                let ScalarString(credits) =
+                    credits.ok_or_else(|| VislogError::missing("credits").raise())?;
+                let ScalarString(is_narrative) =
+                    is_narrative.ok_or_else(|| VislogError::missing("is_narrative").raise())?;
+
+                Ok(RawCourseEntry {
+                    url: url.ok_or_else(|| VislogError::missing("url").raise())?,
+                    path: path.ok_or_else(|| VislogError::missing("path").raise())?,
+                    guid: guid.ok_or_else(|| VislogError::missing("guid").raise())?,
+                    name: name.flatten().map(options::clean_text),
+                    number: number.flatten().map(|ScalarString(number)| number),
+                    subject_name: subject_name.flatten(),
+                    subject_code: subject_code.flatten(),
+                    credits,
+                    is_narrative,
+                    extra,
+                })
+            }
+        }
+
+        deserializer.deserialize_map(RawCourseEntryVisitor)
+    }
 }
 
 #[derive(Debug)]
 pub enum ParsedCourseEntry {
     And,
     Or,
+    /// Delimits operator groups
     Blank,
+    /// Opens a group that is closed by the matching [ParsedCourseEntry::EndGroup], similar to an
+    /// opening parenthesis. Represented by a narrative entry named `"("` in the catalog API
+    BeginGroup,
+    /// Represented by a narrative entry named `")"` in the catalog API
+    EndGroup,
     Label(Label),
     Course(Course),
+    /// An already parsed group that is treated like a single course
+    Group(CourseEntry),
 }
 
 impl ParsedCourseEntry {
@@ -949,34 +661,56 @@ impl ParsedCourseEntry {
             ParsedCourseEntry::And => "And",
             ParsedCourseEntry::Or => "Or",
             ParsedCourseEntry::Blank => "Blank",
+            ParsedCourseEntry::BeginGroup => "BeginGroup",
+            ParsedCourseEntry::EndGroup => "EndGroup",
+            ParsedCourseEntry::Group(_) => "Group",
             ParsedCourseEntry::Label(_) => "Label",
             ParsedCourseEntry::Course(_) => "Course",
         }
     }
 }
 
+impl From<Operator> for ParsedCourseEntry {
+    fn from(operator: Operator) -> Self {
+        match operator {
+            Operator::And => ParsedCourseEntry::And,
+            Operator::Or => ParsedCourseEntry::Or,
+        }
+    }
+}
+
+impl From<CourseEntry> for ParsedCourseEntry {
+    fn from(entry: CourseEntry) -> Self {
+        match entry {
+            CourseEntry::Course(course) => ParsedCourseEntry::Course(Arc::unwrap_or_clone(course)),
+            CourseEntry::Label(label) => ParsedCourseEntry::Label(label),
+            group @ (CourseEntry::And(_) | CourseEntry::Or(_)) => ParsedCourseEntry::Group(group),
+        }
+    }
+}
+
 impl TryFrom<RawCourseEntry> for ParsedCourseEntry {
     type Error = AnyhowError;
 
     fn try_from(entry: RawCourseEntry) -> Result<Self, Self::Error> {
-        if entry.name.is_some() && entry.is_narrative == "True" {
-            let parsed_entry = match entry.name.as_ref().unwrap().as_str() {
+        let is_narrative = dialect::parse_bool(&entry.is_narrative) == Some(true);
+        if let (Some(name), true) = (entry.name.as_deref(), is_narrative) {
+            let parsed_entry = match name {
                 "And" => Self::And,
                 "Or" => Self::Or,
                 "" => Self::Blank,
+                "(" => Self::BeginGroup,
+                ")" => Self::EndGroup,
                 _ => {
-                    let guid = {
-                        let guid = entry.guid.as_str();
-                        let guid = &guid[1..guid.len() - 1];
+                    let guid = dialect::parse_guid(&entry.guid)
+                        .map_err(|err| VislogError::guid(&entry.guid, err))?;
 
-                        Guid::try_from(guid)?
-                    };
-
-                    let credits = parse_course_credits(entry.credits.as_str())?;
+                    let credits = parse_course_credits(&entry.credits)
+                        .map_err(|err| VislogError::credits(&entry.credits, err))?;
                     Self::Label(Label {
                         url: entry.url,
                         guid,
-                        name: entry.name.unwrap(),
+                        name: name.to_owned(),
                         subject_code: entry.subject_code,
                         credits,
                         number: entry.number,
@@ -987,19 +721,16 @@ impl TryFrom<RawCourseEntry> for ParsedCourseEntry {
             return Ok(parsed_entry);
         }
 
-        let guid = {
-            let guid = entry.guid.as_str();
-            let guid = &guid[1..guid.len() - 1];
-
-            Guid::try_from(guid)?
-        };
+        let guid =
+            dialect::parse_guid(&entry.guid).map_err(|err| VislogError::guid(&entry.guid, err))?;
+        if is_narrative {
+            context::warn(Warning::UnnamedNarrative { guid });
+        }
 
-        let number = entry
-            .number
-            .ok_or(anyhow!("missing course number"))?
-            .parse()?;
+        let number = entry.number.ok_or_else(|| VislogError::missing("number"))?;
 
-        let credits = parse_course_credits(entry.credits.as_str())?;
+        let credits = parse_course_credits(&entry.credits)
+            .map_err(|err| VislogError::credits(&entry.credits, err))?;
 
         Ok(Self::Course(Course {
             url: entry.url,
@@ -1008,28 +739,24 @@ impl TryFrom<RawCourseEntry> for ParsedCourseEntry {
             name: entry.name,
             number,
             subject_name: entry.subject_name,
-            subject_code: entry.subject_code.ok_or(anyhow!("missing subject code"))?,
+            subject_code: entry
+                .subject_code
+                .ok_or_else(|| VislogError::missing("subject_code"))?,
             credits,
+            extra: entry.extra,
+            details: None,
         }))
     }
 }
 
 /// Parse `credits` field for `Course` struct from a `&str` containing a valid representation of
-/// numbers of credits.
+/// numbers of credits in the current [dialect].
 ///
 /// ### Examples:
 /// - "1"
 /// - "1.0-3.0"
 pub(crate) fn parse_course_credits(credits_str: &str) -> Result<(u8, Option<u8>), AnyhowError> {
-    let splits = credits_str.split_once('-');
-
-    if let Some((lower, upper)) = splits {
-        let lower = lower.parse::<f32>()?;
-        let upper = upper.parse::<f32>()?;
-        Ok((lower.floor() as u8, Some(upper.floor() as u8)))
-    } else {
-        Ok((credits_str.parse()?, None))
-    }
+    dialect::parse_credits(credits_str)
 }
 
 #[cfg(test)]
@@ -1092,16 +819,16 @@ mod parse_courses_test {
             panic!("program should have `BasicRequirements` variant of `RequirementModule`");
         };
 
-        if let Requirement::Courses { title, courses } = &requirements[0] {
+        if let Requirement::Courses { title, courses, .. } = &requirements[0] {
             assert_eq!(title.as_ref().unwrap().as_str(), "Prerequisites:");
-            assert_eq!(courses.0.len(), 2);
+            assert_eq!(courses.len(), 2);
         } else {
             panic!("program requirements[0] should be `Requirement::Courses`");
         }
 
-        if let Requirement::Courses { title, courses } = &requirements[1] {
+        if let Requirement::Courses { title, courses, .. } = &requirements[1] {
             assert_eq!(title.as_ref().unwrap().as_str(), "Major Courses:");
-            assert_eq!(courses.0.len(), 20);
+            assert_eq!(courses.len(), 20);
         } else {
             panic!("program requirements[1] should be `Requirement::Courses`");
         }
@@ -1140,7 +867,7 @@ mod parse_courses_test {
             panic!("program should have `SingleBasicRequirement` variant of `RequirementModule`");
         };
 
-        if let Requirement::Courses { title, courses } = &requirement {
+        if let Requirement::Courses { title, courses, .. } = &requirement {
             assert_eq!(title.as_ref().unwrap().as_str(), "Minor Requirements:");
             assert_eq!(courses.len(), 6);
         } else {
@@ -1181,7 +908,7 @@ mod parse_courses_test {
         };
 
         match &requirements[0] {
-            Requirement::Courses { title, courses } => {
+            Requirement::Courses { title, courses, .. } => {
                 assert_eq!(title.as_ref().unwrap().as_str(), "Minor Requirements:");
                 assert_eq!(courses.len(), 4);
             }
@@ -1192,7 +919,7 @@ mod parse_courses_test {
         }
 
         match &requirements[1] {
-            Requirement::SelectFromCourses { title, courses } => {
+            Requirement::SelectFromCourses { title, courses, .. } => {
                 assert_eq!(title.as_str(), "Select CSC Upper-level Elective: 3 hours");
                 assert_eq!(courses, &None);
             }
@@ -1203,7 +930,7 @@ mod parse_courses_test {
         }
 
         match &requirements[2] {
-            Requirement::SelectFromCourses { title, courses } => {
+            Requirement::SelectFromCourses { title, courses, .. } => {
                 assert_eq!(title.as_str(), "Select one track:");
                 assert_eq!(courses.as_ref().unwrap().len(), 1);
                 match &courses.as_ref().unwrap()[0] {
@@ -1251,7 +978,7 @@ mod parse_courses_test {
             );
         };
 
-        if let Requirement::Courses { title, courses } = req_with_chained_operator {
+        if let Requirement::Courses { title, courses, .. } = req_with_chained_operator {
             assert_eq!(
                 title.as_ref().unwrap().as_str(),
                 "Intercultural Studies Major or Minor with Communication Studies Major:"
@@ -1268,12 +995,168 @@ mod parse_courses_test {
     }
 }
 
+#[cfg(test)]
+mod from_parsed_entries_test {
+    use super::{ParseCoursesError, ParsedCourseEntry};
+    use crate::{parsing::guid::Guid, Course, CourseEntries, CourseEntry};
+
+    fn course(number: &str) -> ParsedCourseEntry {
+        ParsedCourseEntry::Course(Course {
+            url: String::new(),
+            path: String::new(),
+            guid: Guid::try_from("860AF9C9-EAD9-45AC-AA92-BAF352C5288C").unwrap(),
+            name: None,
+            number: number.to_owned(),
+            subject_name: None,
+            subject_code: "CSC".into(),
+            credits: (3, None),
+            details: None,
+            extra: Default::default(),
+        })
+    }
+
+    fn numbers(entries: &CourseEntries) -> Vec<&str> {
+        entries
+            .iter()
+            .filter_map(|entry| match entry {
+                CourseEntry::Course(course) => Some(course.number.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn entries_without_operators_are_kept_as_is() {
+        let entries = CourseEntries::from_parsed_entries([course("115"), course("125")]).unwrap();
+
+        assert_eq!(numbers(&entries), vec!["115", "125"]);
+    }
+
+    #[test]
+    fn blank_entries_nest_operator_groups() {
+        use ParsedCourseEntry::{And, Blank, Or};
+
+        let entries = CourseEntries::from_parsed_entries([
+            course("115"),
+            And,
+            course("125"),
+            Blank,
+            Or,
+            Blank,
+            course("160"),
+            And,
+            course("270"),
+        ])
+        .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        let CourseEntry::Or(groups) = &entries[0] else {
+            panic!("Expected `CourseEntry::Or`. Got: {:?}", entries[0]);
+        };
+        assert_eq!(groups.len(), 2);
+        assert!(matches!(&groups[0], CourseEntry::And(group) if numbers(group) == ["115", "125"]));
+        assert!(matches!(&groups[1], CourseEntry::And(group) if numbers(group) == ["160", "270"]));
+    }
+
+    #[test]
+    fn different_operator_after_nesting_group_nests_deeper() {
+        use ParsedCourseEntry::{And, Blank, Or};
+
+        let entries = CourseEntries::from_parsed_entries([
+            course("115"),
+            And,
+            course("125"),
+            Blank,
+            Or,
+            Blank,
+            course("160"),
+            And,
+            course("270"),
+            Blank,
+            And,
+            Blank,
+            course("321"),
+            Or,
+            course("347"),
+        ])
+        .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        let CourseEntry::And(outer) = &entries[0] else {
+            panic!("Expected `CourseEntry::And`. Got: {:?}", entries[0]);
+        };
+        assert_eq!(outer.len(), 2);
+        assert!(matches!(&outer[0], CourseEntry::Or(groups) if groups.len() == 2));
+        assert!(matches!(&outer[1], CourseEntry::Or(group) if numbers(group) == ["321", "347"]));
+    }
+
+    #[test]
+    fn group_markers_nest_to_any_depth() {
+        use ParsedCourseEntry::{And, BeginGroup, EndGroup, Or};
+
+        // 115 Or (125 And (160 Or 270))
+        let entries = CourseEntries::from_parsed_entries([
+            course("115"),
+            Or,
+            BeginGroup,
+            course("125"),
+            And,
+            BeginGroup,
+            course("160"),
+            Or,
+            course("270"),
+            EndGroup,
+            EndGroup,
+        ])
+        .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        let CourseEntry::Or(or_group) = &entries[0] else {
+            panic!("Expected `CourseEntry::Or`. Got: {:?}", entries[0]);
+        };
+        assert_eq!(numbers(or_group), ["115"]);
+        let CourseEntry::And(and_group) = &or_group[1] else {
+            panic!("Expected `CourseEntry::And`. Got: {:?}", or_group[1]);
+        };
+        assert_eq!(numbers(and_group), ["125"]);
+        assert!(
+            matches!(&and_group[1], CourseEntry::Or(group) if numbers(group) == ["160", "270"])
+        );
+    }
+
+    #[test]
+    fn error_when_group_markers_are_unbalanced() {
+        use ParsedCourseEntry::{BeginGroup, EndGroup};
+
+        let unclosed = CourseEntries::from_parsed_entries([BeginGroup, course("115")]);
+        let unopened = CourseEntries::from_parsed_entries([course("115"), EndGroup]);
+
+        assert!(matches!(
+            unclosed,
+            Err(ParseCoursesError::UnclosedGroups(1))
+        ));
+        assert!(matches!(
+            unopened,
+            Err(ParseCoursesError::UnmatchedGroupEnd)
+        ));
+    }
+
+    #[test]
+    fn error_when_starting_with_an_operator() {
+        let result = CourseEntries::from_parsed_entries([ParsedCourseEntry::Or, course("115")]);
+
+        assert!(matches!(result, Err(ParseCoursesError::InvalidEntry(_))));
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ParseCoursesError {
     #[error("parse entries terminated at an unexpected state: {0:?}")]
     InvalidFinish(ParseCoursesState),
-    #[error("double nesting detected and is not supported")]
-    DoubleNesting,
+    #[error("end of group found without a matching beginning of group")]
+    UnmatchedGroupEnd,
+    #[error("{0} group(s) were never ended")]
+    UnclosedGroups(usize),
     #[error("invalid entry found: {}", ParsedCourseEntry::name(.0))]
     InvalidEntry(ParsedCourseEntry),
     #[error("parser has exhausted all input")]
@@ -0,0 +1,300 @@
+//! Conventions of the JSON written by a catalog vendor for a single institution.
+//!
+//! The catalog API used by Union University quotes booleans as `"True"` and `"False"`, surrounds
+//! GUIDs with curly braces and writes credits as strings of floats such as `"3.0"` or
+//! `"1.0-3.0"`. Other schools using the same vendor can spell these values slightly differently,
+//! so the parser reads them through a [CatalogDialect] instead of assuming Union's spelling.
+//!
+//! The `Deserialize` implementations of the data model can't take arguments, so the dialect is
+//! installed for the duration of a closure with [with_dialect]. Everything parsed outside of
+//! [with_dialect] uses [UnionUniversity].
+//!
+//! # Example
+//! ```
+//! # use std::sync::Arc;
+//! # use vislog_core::{
+//! #     catalog::Catalog,
+//! #     parsing::{dialect::{self, CatalogDialect, UnionUniversity}, guid::{GUIDParsingError, Guid}},
+//! # };
+//! /// A school whose catalog spells booleans in lowercase
+//! struct Lowercase;
+//!
+//! impl CatalogDialect for Lowercase {
+//!     fn parse_bool(&self, s: &str) -> Option<bool> {
+//!         s.parse().ok()
+//!     }
+//!
+//!     fn parse_guid(&self, s: &str) -> Result<Guid, GUIDParsingError> {
+//!         UnionUniversity.parse_guid(s)
+//!     }
+//!
+//!     fn parse_credits(&self, s: &str) -> anyhow::Result<(u8, Option<u8>)> {
+//!         UnionUniversity.parse_credits(s)
+//!     }
+//!
+//!     fn parse_credit_hours(&self, s: &str) -> anyhow::Result<u8> {
+//!         UnionUniversity.parse_credit_hours(s)
+//!     }
+//! }
+//!
+//! let json = std::fs::read_to_string("../data/cs_minor.json")
+//!     .unwrap()
+//!     .replace(r#""True""#, r#""true""#)
+//!     .replace(r#""False""#, r#""false""#);
+//!
+//! let (catalog, errors) =
+//!     dialect::with_dialect(Arc::new(Lowercase), || Catalog::parse_json("cs_minor.json", &json));
+//!
+//! assert!(errors.is_empty());
+//! let (expected, _) = Catalog::parse_file("../data/cs_minor.json");
+//! assert!(catalog.programs[0]
+//!     .iter_courses()
+//!     .eq(expected.programs[0].iter_courses()));
+//! ```
+
+use std::{cell::RefCell, sync::Arc};
+
+use anyhow::{anyhow, Result};
+
+use super::{
+    context::{self, Warning},
+    guid::{GUIDParsingError, Guid},
+};
+
+/// How an institution spells the values that JSON has no dedicated type for
+pub trait CatalogDialect: Send + Sync {
+    /// Reads a boolean flag such as `is_narrative`. `None` when `s` isn't a boolean.
+    fn parse_bool(&self, s: &str) -> Option<bool>;
+
+    /// Reads the GUID of a program or course
+    fn parse_guid(&self, s: &str) -> Result<Guid, GUIDParsingError>;
+
+    /// Reads the credits of a course entry in a requirement, either a single number of credits or
+    /// an inclusive range
+    fn parse_credits(&self, s: &str) -> Result<(u8, Option<u8>)>;
+
+    /// Reads a single number of credits, such as the `credits_min` of a course
+    fn parse_credit_hours(&self, s: &str) -> Result<u8>;
+
+    /// Tells the dialect apart from others in a [cache](crate::cache) snapshot, so that files
+    /// parsed with another dialect are parsed again. The name of the type by default, dialects
+    /// that can be configured should add their configuration.
+    fn fingerprint(&self) -> String {
+        std::any::type_name::<Self>().to_owned()
+    }
+}
+
+/// The conventions of the catalog API used by Union University
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnionUniversity;
+
+impl CatalogDialect for UnionUniversity {
+    /// `"True"` or `"False"`
+    fn parse_bool(&self, s: &str) -> Option<bool> {
+        match s {
+            "True" => Some(true),
+            "False" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// GUIDs with or without surrounding curly braces. Ex: `{860AF9C9-EAD9-45AC-AA92-BAF352C5288C}`
+    fn parse_guid(&self, s: &str) -> Result<Guid, GUIDParsingError> {
+        let trimmed = trim_curly_braces(s);
+        let guid = Guid::try_from(trimmed)?;
+        if trimmed.len() == s.len() {
+            context::warn(Warning::GuidWithoutBraces {
+                input: s.to_owned(),
+            });
+        }
+
+        Ok(guid)
+    }
+
+    /// Whole numbers such as `"3"` or ranges of floats such as `"1.0-3.0"`
+    fn parse_credits(&self, s: &str) -> Result<(u8, Option<u8>)> {
+        match s.split_once('-') {
+            Some((lower, upper)) => {
+                let lower = lower.parse::<f32>()?;
+                let upper = upper.parse::<f32>()?;
+                Ok((truncate(lower), Some(truncate(upper))))
+            }
+            None => Ok((s.parse()?, None)),
+        }
+    }
+
+    /// Floats such as `"3.0"`, truncated to whole credits
+    fn parse_credit_hours(&self, s: &str) -> Result<u8> {
+        let float: f32 = s.parse()?;
+        if float > u8::MAX as f32 {
+            return Err(anyhow!("{float} credits exceeded `u8::MAX` (255)"));
+        }
+
+        Ok(truncate(float))
+    }
+}
+
+/// Whole credits of `float`, warning about any fraction that is lost
+fn truncate(float: f32) -> u8 {
+    let credits = float.trunc() as u8;
+    if float.fract() != 0.0 {
+        context::warn(Warning::CreditsTruncated {
+            input: float.to_string(),
+            credits,
+        });
+    }
+
+    credits
+}
+
+/// Leaves out the curly braces surrounding GUIDs in the catalog API. Ex: `{860AF9C9-...}`
+pub(crate) fn trim_curly_braces(s: &str) -> &str {
+    s.strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .unwrap_or(s)
+}
+
+thread_local! {
+    static DIALECT: RefCell<Option<Arc<dyn CatalogDialect>>> = const { RefCell::new(None) };
+}
+
+/// Parses everything in `f` with the `dialect` on the current thread, restoring the previous
+/// dialect afterwards
+pub fn with_dialect<R>(dialect: Arc<dyn CatalogDialect>, f: impl FnOnce() -> R) -> R {
+    /// Restores the previous dialect even if `f` panics
+    struct Restore(Option<Arc<dyn CatalogDialect>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            DIALECT.with(|current| *current.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(DIALECT.with(|current| current.replace(Some(dialect))));
+    f()
+}
+
+/// The dialect installed by [with_dialect] on the current thread, if any. Used to carry the
+/// dialect over to other threads.
+pub fn current() -> Option<Arc<dyn CatalogDialect>> {
+    DIALECT.with(|current| current.borrow().clone())
+}
+
+/// [Fingerprint](CatalogDialect::fingerprint) of the dialect used on the current thread
+pub(crate) fn current_fingerprint() -> String {
+    with_current(|dialect| dialect.fingerprint())
+}
+
+fn with_current<R>(f: impl FnOnce(&dyn CatalogDialect) -> R) -> R {
+    DIALECT.with(|current| match current.borrow().as_deref() {
+        Some(dialect) => f(dialect),
+        None => f(&UnionUniversity),
+    })
+}
+
+/// Booleans left unquoted in hand-written YAML or TOML reach the dialect spelled `"True"` or
+/// `"False"`, so that spelling is accepted whatever the dialect
+pub(crate) fn parse_bool(s: &str) -> Option<bool> {
+    with_current(|dialect| dialect.parse_bool(s)).or_else(|| UnionUniversity.parse_bool(s))
+}
+
+pub(crate) fn parse_guid(s: &str) -> Result<Guid, GUIDParsingError> {
+    with_current(|dialect| dialect.parse_guid(s))
+}
+
+pub(crate) fn parse_credits(s: &str) -> Result<(u8, Option<u8>)> {
+    with_current(|dialect| dialect.parse_credits(s))
+}
+
+pub(crate) fn parse_credit_hours(s: &str) -> Result<u8> {
+    with_current(|dialect| dialect.parse_credit_hours(s))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Program;
+
+    /// GUIDs without braces and credits as whole numbers joined by "to"
+    struct Other;
+
+    impl CatalogDialect for Other {
+        fn parse_bool(&self, s: &str) -> Option<bool> {
+            match s {
+                "Y" => Some(true),
+                "N" => Some(false),
+                _ => None,
+            }
+        }
+
+        fn parse_guid(&self, s: &str) -> Result<Guid, GUIDParsingError> {
+            Guid::try_from(s)
+        }
+
+        fn parse_credits(&self, s: &str) -> Result<(u8, Option<u8>)> {
+            match s.split_once(" to ") {
+                Some((lower, upper)) => Ok((lower.parse()?, Some(upper.parse()?))),
+                None => Ok((s.parse()?, None)),
+            }
+        }
+
+        fn parse_credit_hours(&self, s: &str) -> Result<u8> {
+            Ok(s.parse()?)
+        }
+    }
+
+    const PROGRAM: &str = r#"{
+        "url": "https://example.edu/major",
+        "guid": "5B72AC3A-9A84-4CF5-B1BE-B3E0B48163A5",
+        "title": "Major",
+        "content": null,
+        "bottom_content": null,
+        "requirements": {
+            "title": null,
+            "requirement_list": {
+                "title": "Core",
+                "course": [
+                    {"url": "", "path": "", "guid": "860AF9C9-EAD9-45AC-AA92-BAF352C5288C",
+                     "name": "Intro", "number": "115", "subject_name": null,
+                     "subject_code": "CSC", "credits": "3", "is_narrative": "N"},
+                    {"url": "", "path": "", "guid": "13A1385C-81AC-493D-ACE8-AA8AB37D2C81",
+                     "name": "Or", "number": null, "subject_name": null,
+                     "subject_code": null, "credits": "0", "is_narrative": "Y"},
+                    {"url": "", "path": "", "guid": "BF3CF399-6D63-43AA-8064-2A86789B5A4E",
+                     "name": "Research", "number": "495", "subject_name": null,
+                     "subject_code": "CSC", "credits": "1 to 3", "is_narrative": "N"}
+                ]
+            }
+        }
+    }"#;
+
+    #[test]
+    fn union_university_conventions() {
+        let union = UnionUniversity;
+
+        assert_eq!(union.parse_bool("True"), Some(true));
+        assert_eq!(union.parse_bool("true"), None);
+        assert_eq!(
+            union.parse_guid("{860AF9C9-EAD9-45AC-AA92-BAF352C5288C}"),
+            Guid::try_from("860AF9C9-EAD9-45AC-AA92-BAF352C5288C")
+        );
+        assert_eq!(union.parse_credits("1.0-3.0").unwrap(), (1, Some(3)));
+        assert_eq!(union.parse_credit_hours("4.0").unwrap(), 4);
+        assert!(union.parse_credit_hours("300.0").is_err());
+    }
+
+    #[test]
+    fn programs_parse_with_the_installed_dialect() {
+        assert!(serde_json::from_str::<Program>(PROGRAM).is_err());
+
+        let program: Program =
+            with_dialect(Arc::new(Other), || serde_json::from_str(PROGRAM)).unwrap();
+        let courses: Vec<_> = program.iter_courses().collect();
+        assert_eq!(courses.len(), 2);
+        assert_eq!(courses[1].credits, (1, Some(3)));
+
+        // The dialect only applies inside of the closure
+        assert!(current().is_none());
+        assert!(serde_json::from_str::<Program>(PROGRAM).is_err());
+    }
+}
@@ -0,0 +1,406 @@
+//! Changes in the format of the catalog API, found by comparing raw JSON with what the parser reads.
+//!
+//! A [DriftReport] tallies every field of every kind of object in a set of JSON files, along with
+//! how often it is null and which JSON types it holds, and gives it a [FieldStatus] by comparing it
+//! with the fields that the parser reads and the other fields that the API is known to send. A new
+//! field, such as the vendor adding `min_grade` to requirements, shows up as
+//! [FieldStatus::Unknown], and a field that the parser reads but that stopped being sent shows up
+//! as [FieldStatus::Missing].
+//!
+//! Files are recognized the same way as [Catalog::parse_dir](crate::catalog::Catalog::parse_dir),
+//! without parsing them, so files that fail to parse are inspected too.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::parsing::drift::DriftReport;
+//! let report = DriftReport::from_dir("../data").unwrap();
+//!
+//! for (kind, field, report) in report.drift() {
+//!     println!("{kind}: `{field}` in {} objects is {:?}", report.seen, report.status);
+//! }
+//! ```
+
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::catalog::{self, CatalogError};
+
+use super::{
+    COURSE_DETAILS_FIELDS, COURSE_ENTRY_FIELDS, MODULE_FIELDS, PROGRAM_FIELDS, REQUIREMENT_FIELDS,
+};
+
+/// The kinds of objects found in catalog API JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum ObjectKind {
+    Program,
+    Module,
+    Requirement,
+    CourseEntry,
+    CourseDetails,
+}
+
+impl ObjectKind {
+    /// Fields whose values the parser uses. Fields with more than one name, like the GUID of a
+    /// program, are grouped together.
+    fn parsed_fields(self) -> &'static [&'static [&'static str]] {
+        match self {
+            ObjectKind::Program => &[
+                &["url"],
+                &["GUID", "guid"],
+                &["title"],
+                &["content"],
+                &["bottom_content"],
+                &["requirements"],
+            ],
+            ObjectKind::Module => &[&["title"], &["requirement_list"]],
+            ObjectKind::Requirement => &[&["title"], &["req_narrative"], &["course"]],
+            ObjectKind::CourseEntry => &[
+                &["url"],
+                &["path"],
+                &["guid"],
+                &["name"],
+                &["number"],
+                &["subject_name"],
+                &["subject_code"],
+                &["credits"],
+                &["is_narrative"],
+            ],
+            ObjectKind::CourseDetails => &[
+                &["url"],
+                &["GUID"],
+                &["path"],
+                &["subject_code"],
+                &["subject_name"],
+                &["number"],
+                &["name"],
+                &["credits_min"],
+                &["credits_max"],
+                &["description"],
+                &["prerequisite_narrative"],
+                &["prerequisite"],
+                &["corequisite_narrative"],
+                &["corequisite"],
+            ],
+        }
+    }
+
+    /// Every field the catalog API is known to send, parsed or not
+    fn known_fields(self) -> &'static [&'static str] {
+        match self {
+            ObjectKind::Program => PROGRAM_FIELDS,
+            ObjectKind::Module => MODULE_FIELDS,
+            ObjectKind::Requirement => REQUIREMENT_FIELDS,
+            ObjectKind::CourseEntry => COURSE_ENTRY_FIELDS,
+            ObjectKind::CourseDetails => COURSE_DETAILS_FIELDS,
+        }
+    }
+
+    fn status(self, field: &str) -> FieldStatus {
+        if self
+            .parsed_fields()
+            .iter()
+            .any(|names| names.contains(&field))
+        {
+            FieldStatus::Parsed
+        } else if self.known_fields().contains(&field) {
+            FieldStatus::Ignored
+        } else {
+            FieldStatus::Unknown
+        }
+    }
+}
+
+impl fmt::Display for ObjectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ObjectKind::Program => "program",
+            ObjectKind::Module => "requirement module",
+            ObjectKind::Requirement => "requirement",
+            ObjectKind::CourseEntry => "course entry",
+            ObjectKind::CourseDetails => "course details",
+        })
+    }
+}
+
+/// How a field compares with what the parser reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum FieldStatus {
+    /// Read by the parser
+    Parsed,
+    /// Sent by the catalog API but not read by the parser
+    Ignored,
+    /// Not known to be sent by the catalog API
+    Unknown,
+    /// Read by the parser but in none of the objects
+    Missing,
+}
+
+impl FieldStatus {
+    /// Whether the field is a sign that the format of the API changed
+    pub fn is_drift(self) -> bool {
+        matches!(self, FieldStatus::Unknown | FieldStatus::Missing)
+    }
+}
+
+/// What was found in a single field of a kind of object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldReport {
+    pub status: FieldStatus,
+    /// Number of objects with the field
+    pub seen: usize,
+    /// Number of objects where the field is null
+    pub nulls: usize,
+    /// Number of values of every JSON type the field held, by the name of the type such as
+    /// `string`. Nulls are left out.
+    pub types: BTreeMap<&'static str, usize>,
+}
+
+impl FieldReport {
+    fn new(status: FieldStatus) -> Self {
+        Self {
+            status,
+            seen: 0,
+            nulls: 0,
+            types: BTreeMap::new(),
+        }
+    }
+
+    /// Share of the objects with the field where it is null, between 0 and 1
+    pub fn null_rate(&self) -> f64 {
+        match self.seen {
+            0 => 0.0,
+            seen => self.nulls as f64 / seen as f64,
+        }
+    }
+}
+
+/// Every field found in a kind of object
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct KindReport {
+    /// Number of objects of the kind
+    pub objects: usize,
+    pub fields: BTreeMap<String, FieldReport>,
+}
+
+/// Fields found in catalog API JSON, compared with what the parser reads
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DriftReport {
+    pub kinds: BTreeMap<ObjectKind, KindReport>,
+    /// Number of JSON documents that weren't recognized as programs or courses
+    pub unrecognized: usize,
+}
+
+impl DriftReport {
+    /// Inspects every JSON file in `dir`, failing on the first file that can't be read or isn't
+    /// JSON
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self, CatalogError> {
+        let mut report = DriftReport::default();
+
+        for path in catalog::json_files_in(dir.as_ref())? {
+            let json = std::fs::read_to_string(&path).map_err(|source| CatalogError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            let value: Value =
+                serde_json::from_str(&json).map_err(|source| CatalogError::Json {
+                    path,
+                    source: source.into(),
+                })?;
+            report.add_document(&value);
+        }
+        report.add_missing();
+
+        Ok(report)
+    }
+
+    /// Inspects JSON documents, each holding what a catalog file would
+    pub fn from_values<'a>(documents: impl IntoIterator<Item = &'a Value>) -> Self {
+        let mut report = DriftReport::default();
+        for document in documents {
+            report.add_document(document);
+        }
+        report.add_missing();
+
+        report
+    }
+
+    /// Every field that is a sign that the format of the API changed
+    pub fn drift(&self) -> impl Iterator<Item = (ObjectKind, &str, &FieldReport)> {
+        self.kinds.iter().flat_map(|(kind, report)| {
+            report
+                .fields
+                .iter()
+                .filter(|(_, field)| field.status.is_drift())
+                .map(|(name, field)| (*kind, name.as_str(), field))
+        })
+    }
+
+    pub fn has_drift(&self) -> bool {
+        self.drift().next().is_some()
+    }
+
+    /// Recognizes documents the same way as parsing a catalog file
+    fn add_document(&mut self, value: &Value) {
+        if let Some(programs) = value.pointer("/programs/program").and_then(Value::as_array) {
+            programs
+                .iter()
+                .for_each(|program| self.add_program(program));
+        } else if let Some(courses) = value.pointer("/courses/course").and_then(Value::as_array) {
+            courses
+                .iter()
+                .for_each(|course| self.add_object(ObjectKind::CourseDetails, course));
+        } else if let Some(programs) = value.as_array() {
+            programs
+                .iter()
+                .for_each(|program| self.add_program(program));
+        } else if value.get("subject_code").is_some() {
+            self.add_object(ObjectKind::CourseDetails, value);
+        } else if value.get("title").is_some() {
+            self.add_program(value);
+        } else {
+            self.unrecognized += 1;
+        }
+    }
+
+    fn add_program(&mut self, program: &Value) {
+        self.add_object(ObjectKind::Program, program);
+
+        for module in one_or_many(&program["requirements"]) {
+            self.add_object(ObjectKind::Module, module);
+
+            for requirement in one_or_many(&module["requirement_list"]) {
+                self.add_object(ObjectKind::Requirement, requirement);
+
+                for entry in one_or_many(&requirement["course"]) {
+                    self.add_object(ObjectKind::CourseEntry, entry);
+                }
+            }
+        }
+    }
+
+    fn add_object(&mut self, kind: ObjectKind, value: &Value) {
+        let Some(object) = value.as_object() else {
+            return;
+        };
+
+        let report = self.kinds.entry(kind).or_default();
+        report.objects += 1;
+
+        for (name, value) in object {
+            let field = report
+                .fields
+                .entry(name.clone())
+                .or_insert_with(|| FieldReport::new(kind.status(name)));
+            field.seen += 1;
+            match value {
+                Value::Null => field.nulls += 1,
+                value => *field.types.entry(json_type(value)).or_default() += 1,
+            }
+        }
+    }
+
+    /// Adds the parsed fields of every kind of object found that none of the objects had
+    fn add_missing(&mut self) {
+        for (kind, report) in &mut self.kinds {
+            for names in kind.parsed_fields() {
+                if !names.iter().any(|name| report.fields.contains_key(*name)) {
+                    report
+                        .fields
+                        .insert(names[0].to_owned(), FieldReport::new(FieldStatus::Missing));
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for DriftReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (kind, report) in &self.kinds {
+            writeln!(f, "{kind} ({} objects)", report.objects)?;
+
+            for (name, field) in &report.fields {
+                let status = match field.status {
+                    FieldStatus::Parsed => "parsed",
+                    FieldStatus::Ignored => "ignored",
+                    FieldStatus::Unknown => "UNKNOWN",
+                    FieldStatus::Missing => "MISSING",
+                };
+                let types = field
+                    .types
+                    .iter()
+                    .map(|(name, count)| format!("{name} {count}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                writeln!(
+                    f,
+                    "  {name:<24} {status:<8} {:>6} seen {:>6.1}% null  {types}",
+                    field.seen,
+                    field.null_rate() * 100.0,
+                )?;
+            }
+        }
+
+        if self.unrecognized > 0 {
+            writeln!(f, "{} documents weren't recognized", self.unrecognized)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The objects of a field holding either one object or an array of them
+fn one_or_many(value: &Value) -> &[Value] {
+    match value {
+        Value::Array(values) => values,
+        Value::Null => &[],
+        value => std::slice::from_ref(value),
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn the_data_matches_the_parser() {
+        let report = DriftReport::from_dir("../data").unwrap();
+
+        assert!(!report.has_drift(), "{report}");
+
+        let programs = &report.kinds[&ObjectKind::Program];
+        assert_eq!(programs.fields["title"].seen, programs.objects);
+        assert_eq!(programs.fields["path"].status, FieldStatus::Ignored);
+    }
+
+    #[test]
+    fn new_fields_are_unknown() {
+        let json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+        let mut program: Value = serde_json::from_str(&json).unwrap();
+        program["requirements"]["requirement_list"][0]["min_grade"] = "C".into();
+        program["requirements"]["requirement_list"][1]["min_grade"] = Value::Null;
+
+        let report = DriftReport::from_values([&program]);
+        let min_grade = &report.kinds[&ObjectKind::Requirement].fields["min_grade"];
+        assert_eq!(min_grade.status, FieldStatus::Unknown);
+        assert_eq!(min_grade.seen, 2);
+        assert_eq!(min_grade.null_rate(), 0.5);
+        assert_eq!(min_grade.types, BTreeMap::from([("string", 1)]));
+        assert!(report
+            .drift()
+            .any(|(kind, field, _)| kind == ObjectKind::Requirement && field == "min_grade"));
+    }
+}
@@ -4,7 +4,10 @@ use serde::{
 };
 use thiserror::Error;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+use crate::error::VislogError;
+
+/// Ordered by its bytes, which is the order of the hexadecimal strings it is displayed as
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Guid {
     inner: [u8; 16],
 }
@@ -20,13 +23,12 @@ impl std::fmt::Debug for Guid {
         let hex_chars = self
             .inner
             .iter()
-            .map(|byte| {
+            .flat_map(|byte| {
                 let first_half = byte >> 4;
                 let second_half = byte & 0b00001111;
 
                 [format!("{:X}", first_half), format!("{:X}", second_half)]
             })
-            .flatten()
             .fold(String::new(), |mut acc, c| {
                 acc.push_str(&c);
                 acc
@@ -79,7 +81,7 @@ impl TryFrom<&str> for Guid {
 
         let mut inner = [0u8; 16];
 
-        for i in 0..16 {
+        for slot in inner.iter_mut() {
             let mut byte = 0u8;
             let mut byte_index = 0;
             while byte_index < 2 {
@@ -104,7 +106,7 @@ impl TryFrom<&str> for Guid {
                 }
             }
 
-            inner[i] = byte;
+            *slot = byte;
         }
 
         Ok(Self { inner })
@@ -139,11 +141,11 @@ impl<'de> Deserialize<'de> for Guid {
             where
                 E: serde::de::Error,
             {
-                Guid::try_from(v).map_err(|e| de::Error::custom(e))
+                Guid::try_from(v).map_err(de::Error::custom)
             }
         }
 
-        deserializer.deserialize_any(GuidVisitor)
+        deserializer.deserialize_str(GuidVisitor)
     }
 }
 
@@ -166,16 +168,14 @@ fn hex_to_num(c: char) -> Option<u8> {
     Some(n as u8)
 }
 
-pub(crate) fn deserialize_guid_with_curly_braces<'de, D>(deserializer: D) -> Result<Guid, D::Error>
+/// Reads a GUID spelled the way the current [dialect](super::dialect) spells them
+pub(crate) fn deserialize_catalog_guid<'de, D>(deserializer: D) -> Result<Guid, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let mut s: &str = Deserialize::deserialize(deserializer)?;
-
-    // Ommit the curly braces in the source when parsing
-    s = &s[1..s.len() - 1];
+    let s: String = Deserialize::deserialize(deserializer)?;
 
-    Guid::try_from(s).map_err(serde::de::Error::custom)
+    super::dialect::parse_guid(&s).map_err(|err| VislogError::guid(&s, err).raise())
 }
 
 #[cfg(test)]
@@ -0,0 +1,189 @@
+//! A [Program] whose requirements are only parsed once they are asked for.
+//!
+//! Classifying requirements and parsing their courses is where most of the time of parsing a
+//! program goes. A server loading the whole catalog at startup only shows a handful of programs
+//! before the next refresh, so a [LazyProgram] parses the title, URL and content of the program
+//! right away and keeps its requirements as a [Value] until [LazyProgram::requirements] is first
+//! called. The parsed requirements are kept, so later calls don't parse them again.
+//!
+//! Errors in the requirements only show up when they are parsed, and aren't kept: every call to
+//! [LazyProgram::requirements] on a program whose requirements don't parse returns the error again.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{parsing::lazy::LazyProgram, Program};
+//! let json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+//!
+//! let lazy: LazyProgram = serde_json::from_str(&json).unwrap();
+//! assert!(!lazy.is_parsed());
+//!
+//! let requirements = lazy.requirements().unwrap();
+//! assert!(requirements.is_some());
+//! assert!(lazy.is_parsed());
+//!
+//! let program: Program = serde_json::from_str(&json).unwrap();
+//! assert_eq!(lazy.into_program().unwrap(), program);
+//! ```
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    extensions::Extensions,
+    parsing::guid::{deserialize_catalog_guid, Guid},
+    Program, Requirements,
+};
+
+/// A [Program] parsed up to its requirements
+#[derive(Debug, Clone, Deserialize)]
+pub struct LazyProgram {
+    /// Link to the official catalog
+    pub url: String,
+
+    /// GUID given by the system
+    #[serde(deserialize_with = "deserialize_catalog_guid")]
+    #[serde(alias = "GUID")]
+    pub guid: Guid,
+
+    /// Name of the program
+    pub title: String,
+
+    /// Introductory information about the program
+    pub content: Option<String>,
+
+    /// Last information on the page about the program
+    pub bottom_content: Option<String>,
+
+    /// Requirements as found in the JSON payload, `Null` when the program has none
+    #[serde(default, rename = "requirements")]
+    raw_requirements: Value,
+
+    #[serde(skip)]
+    requirements: OnceLock<Option<Requirements>>,
+
+    /// Fields of the catalog API that vislog doesn't parse yet. See [extensions](crate::extensions)
+    #[cfg_attr(feature = "preserve-unknown", serde(flatten))]
+    #[cfg_attr(not(feature = "preserve-unknown"), serde(skip))]
+    pub extra: Extensions,
+}
+
+impl LazyProgram {
+    /// Parses the requirements of the program the first time it is called, and returns the same
+    /// requirements afterwards
+    pub fn requirements(&self) -> serde_json::Result<Option<&Requirements>> {
+        if let Some(requirements) = self.requirements.get() {
+            return Ok(requirements.as_ref());
+        }
+
+        let parsed = Option::<Requirements>::deserialize(&self.raw_requirements)?;
+        // Another thread may have parsed them in the meantime, in which case both are the same
+        Ok(self.requirements.get_or_init(|| parsed).as_ref())
+    }
+
+    /// Whether the requirements were already parsed
+    pub fn is_parsed(&self) -> bool {
+        self.requirements.get().is_some()
+    }
+
+    /// The requirements as found in the JSON payload
+    pub fn raw_requirements(&self) -> &Value {
+        &self.raw_requirements
+    }
+
+    /// Parses the requirements if they weren't already, giving the [Program] that parsing the
+    /// same JSON would have given
+    pub fn into_program(mut self) -> serde_json::Result<Program> {
+        let requirements = match self.requirements.take() {
+            Some(requirements) => requirements,
+            None => Option::<Requirements>::deserialize(self.raw_requirements)?,
+        };
+
+        Ok(Program {
+            url: self.url,
+            guid: self.guid,
+            title: self.title,
+            content: self.content,
+            bottom_content: self.bottom_content,
+            requirements,
+            extra: self.extra,
+        })
+    }
+}
+
+/// Serializes as the [Program] it stands for, parsing its requirements if needed
+impl Serialize for LazyProgram {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct ProgramSer<'a> {
+            url: &'a str,
+            guid: Guid,
+            title: &'a str,
+            content: &'a Option<String>,
+            bottom_content: &'a Option<String>,
+            requirements: Option<&'a Requirements>,
+            #[serde(skip_serializing_if = "Extensions::is_empty")]
+            extra: &'a Extensions,
+        }
+
+        let requirements = self.requirements().map_err(serde::ser::Error::custom)?;
+        ProgramSer {
+            url: &self.url,
+            guid: self.guid,
+            title: &self.title,
+            content: &self.content,
+            bottom_content: &self.bottom_content,
+            requirements,
+            extra: &self.extra,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn dump() -> Vec<Value> {
+        let json = std::fs::read_to_string("../data/programs.json").unwrap();
+        let dump: Value = serde_json::from_str(&json).unwrap();
+        dump["programs"]["program"].as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn lazy_programs_match_parsed_ones() {
+        for value in dump() {
+            let lazy = LazyProgram::deserialize(&value).unwrap();
+            match Program::deserialize(&value) {
+                Ok(program) => {
+                    assert_eq!(lazy.requirements().unwrap(), program.requirements.as_ref());
+                    assert_eq!(
+                        serde_json::to_value(&lazy).unwrap(),
+                        serde_json::to_value(&program).unwrap()
+                    );
+                    assert_eq!(lazy.into_program().unwrap(), program);
+                }
+                Err(_) => {
+                    assert!(lazy.requirements().is_err());
+                    assert!(!lazy.is_parsed());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn requirements_are_parsed_once() {
+        let json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+        let lazy: LazyProgram = serde_json::from_str(&json).unwrap();
+        assert!(!lazy.is_parsed());
+
+        let first = lazy.requirements().unwrap().unwrap() as *const Requirements;
+        let second = lazy.requirements().unwrap().unwrap() as *const Requirements;
+        assert!(lazy.is_parsed());
+        assert_eq!(first, second);
+
+        // Clones keep what was already parsed
+        assert!(lazy.clone().is_parsed());
+    }
+}
@@ -1,23 +1,184 @@
 use std::str::FromStr;
 
+use crate::{
+    constraints::Constraints,
+    electives::ElectiveHours,
+    error::{self, VislogError},
+    extensions::{self, Extensions},
+    metrics::{self, Counter},
+    symbol::Symbol,
+    Course, CourseDetails, CourseEntries, CourseEntry, Label, Program, Requirement,
+    RequirementModule, Requirements,
+};
 use serde::{
-    de::{self, Visitor},
+    de::{self, value::MapDeserializer, Visitor},
     Deserialize, Deserializer,
 };
 use serde_json::Value;
 
-use crate::{
-    Course, CourseDetails, CourseEntries, CourseEntry, Label, Requirement, RequirementModule,
-    Requirements,
-};
-
 use self::{
-    courses::{parse_course_credits, CoursesParser, RawCourseEntry},
+    courses::{parse_course_credits, RawCourseEntry},
     guid::Guid,
 };
 
+pub mod borrowed;
+pub mod context;
 pub mod courses;
+pub mod dialect;
+pub mod drift;
 pub mod guid;
+pub mod lazy;
+pub mod options;
+pub mod stream;
+
+/// Fields the catalog API sends in a program, whether vislog parses them or not. Any other field is
+/// unknown to [strict_unknown_fields](options::ParseOptions::strict_unknown_fields).
+const PROGRAM_FIELDS: &[&str] = &[
+    "url",
+    "guid",
+    "GUID",
+    "path",
+    "title",
+    "content",
+    "bottom_content",
+    "requirements",
+    "credits",
+    "credits_min",
+    "credits_max",
+    "auto_credits",
+    "in_program_list",
+];
+
+/// Fields the catalog API sends in a requirement module
+const MODULE_FIELDS: &[&str] = &[
+    "title",
+    "req_narrative",
+    "content",
+    "requirement_list",
+    "credits",
+    "credits_min",
+    "credits_max",
+    "auto_credits",
+];
+
+/// Fields the catalog API sends in a requirement
+const REQUIREMENT_FIELDS: &[&str] = &[
+    "title",
+    "req_narrative",
+    "req_note",
+    "course",
+    "credits",
+    "credits_min",
+    "credits_max",
+    "auto_credits",
+];
+
+/// Fields the catalog API sends in a course entry of a requirement
+pub(crate) const COURSE_ENTRY_FIELDS: &[&str] = &[
+    "url",
+    "path",
+    "guid",
+    "name",
+    "number",
+    "subject_name",
+    "subject_code",
+    "credits",
+    "is_narrative",
+];
+
+/// Fields the catalog API sends in the details of a course
+const COURSE_DETAILS_FIELDS: &[&str] = &[
+    "url",
+    "GUID",
+    "path",
+    "subject_code",
+    "subject_name",
+    "number",
+    "name",
+    "credits",
+    "credits_min",
+    "credits_max",
+    "credits_narrative",
+    "description",
+    "prerequisite_narrative",
+    "prerequisite",
+    "corequisite_narrative",
+    "corequisite",
+    "crosslist_narrative",
+    "distribution",
+    "instructor",
+    "notes",
+    "offered",
+];
+
+impl<'de> Deserialize<'de> for Program {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ProgramVisitor;
+
+        impl<'de> Visitor<'de> for ProgramVisitor {
+            type Value = Program;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a JSON object representing a `Program` struct")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                error::clear_pending();
+
+                let mut url: Option<String> = None;
+                let mut guid: Option<Guid> = None;
+                let mut title: Option<String> = None;
+                let mut content: Option<Option<String>> = None;
+                let mut bottom_content: Option<Option<String>> = None;
+                let mut requirements: Option<Option<Requirements>> = None;
+                let mut extra = Extensions::default();
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "url" => options::next_field(&mut map, &mut url, "url")?,
+                        "guid" | "GUID" => {
+                            options::read_field(&mut map, &mut guid, "guid", |map| {
+                                let guid_str = map.next_value::<String>()?;
+
+                                dialect::parse_guid(&guid_str)
+                                    .map_err(|err| VislogError::guid(&guid_str, err).raise())
+                            })?
+                        }
+                        "title" => options::next_field(&mut map, &mut title, "title")?,
+                        "content" => options::next_field(&mut map, &mut content, "content")?,
+                        "bottom_content" => {
+                            options::next_field(&mut map, &mut bottom_content, "bottom_content")?
+                        }
+                        "requirements" => {
+                            options::next_field(&mut map, &mut requirements, "requirements")?
+                        }
+                        _ => extensions::next_unknown(&mut map, &mut extra, key, PROGRAM_FIELDS)?,
+                    }
+                }
+
+                Ok(Program {
+                    url: url.ok_or_else(|| VislogError::missing("url").raise())?,
+                    guid: guid.ok_or_else(|| VislogError::missing("guid").raise())?,
+                    title: options::clean_text(
+                        title.ok_or_else(|| VislogError::missing("title").raise())?,
+                    ),
+                    content: content.flatten().map(options::clean_text),
+                    bottom_content: bottom_content.flatten().map(options::clean_text),
+                    requirements: requirements.flatten(),
+                    extra,
+                })
+            }
+        }
+
+        deserializer.deserialize_map(ProgramVisitor)
+    }
+}
 
 impl<'de> Deserialize<'de> for Requirements {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -38,57 +199,32 @@ impl<'de> Deserialize<'de> for Requirements {
             where
                 A: de::MapAccess<'de>,
             {
-                /// Intermediate struct used to determine if `requirement_list` is a JSON object or array.
-                #[derive(Debug, Deserialize)]
-                #[serde(untagged)]
-                enum RawRequirement {
-                    /// Case where the `RequirementModule` only has a single `Course` JSON object in field
-                    /// `course`
-                    SingleCourseRequirement(SingleCourseRequirement),
-                    Single(Requirement),
-                    Many(Vec<Requirement>),
-                }
-
-                #[derive(Debug, Deserialize)]
-                struct SingleCourseRequirement {
-                    title: Option<String>,
-                    course: Course,
-                }
+                error::clear_pending();
 
                 let mut title: Option<Option<String>> = None;
                 let mut req_narrative: Option<Option<String>> = None;
                 let mut requirement_list: Option<RawRequirement> = None;
 
-                while let Ok(Some(key)) = map.next_key::<String>() {
+                while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
-                        "title" => {
-                            if title.is_some() {
-                                return Err(de::Error::duplicate_field("title"));
-                            }
-                            title = Some(map.next_value()?);
-                        }
+                        "title" => options::next_field(&mut map, &mut title, "title")?,
                         "req_narrative" => {
-                            if req_narrative.is_some() {
-                                return Err(de::Error::duplicate_field("req_narrative"));
-                            }
-                            req_narrative = Some(map.next_value()?);
-                        }
-                        "requirement_list" => {
-                            if requirement_list.is_some() {
-                                return Err(de::Error::duplicate_field("requirement_list"));
-                            }
-                            requirement_list = Some(map.next_value()?);
-                        }
-                        _ => {
-                            let _ = map.next_value::<de::IgnoredAny>();
+                            options::next_field(&mut map, &mut req_narrative, "req_narrative")?
                         }
+                        "requirement_list" => options::next_field(
+                            &mut map,
+                            &mut requirement_list,
+                            "requirement_list",
+                        )?,
+                        _ => options::skip_unknown(&mut map, &key, MODULE_FIELDS)?,
                     }
                 }
 
-                let title = title.ok_or_else(|| de::Error::missing_field("title"))?;
+                // Formats without null, like TOML, leave out the fields instead
+                let title = title.flatten().map(options::clean_text);
 
                 let requirements = requirement_list
-                    .ok_or_else(|| de::Error::missing_field("requirements_list"))?;
+                    .ok_or_else(|| VislogError::missing("requirements_list").raise())?;
 
                 let requirement_module = match requirements {
                     RawRequirement::Single(requirement) => {
@@ -102,9 +238,19 @@ impl<'de> Deserialize<'de> for Requirements {
                         title: req_title,
                         course,
                     }) => {
+                        let req_title = req_title.map(options::clean_text);
+                        let course = Course {
+                            name: course.name.map(options::clean_text),
+                            ..course
+                        };
                         let requirement = Requirement::Courses {
+                            constraints: req_title
+                                .as_deref()
+                                .map(Constraints::from_text)
+                                .unwrap_or_default(),
                             title: req_title,
-                            courses: CourseEntries(vec![CourseEntry::Course(course)]),
+                            courses: CourseEntries::from(vec![course.into()]),
+                            extra: Extensions::default(),
                         };
                         RequirementModule::SingleBasicRequirement { title, requirement }
                     }
@@ -114,16 +260,13 @@ impl<'de> Deserialize<'de> for Requirements {
             }
 
             /// Case for [Requirements::Many] variant
-            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
             where
                 A: de::SeqAccess<'de>,
             {
-                let mut modules = Vec::new();
-                while let Ok(Some(module)) = seq.next_element() {
-                    modules.push(module);
-                }
+                error::clear_pending();
 
-                Ok(Requirements::Many(modules))
+                Ok(Requirements::Many(options::elements(seq)?))
             }
         }
 
@@ -131,6 +274,76 @@ impl<'de> Deserialize<'de> for Requirements {
     }
 }
 
+/// Intermediate enum used to determine if `requirement_list` is a JSON object or array
+#[derive(Debug)]
+enum RawRequirement {
+    /// Case where the `RequirementModule` only has a single `Course` JSON object in field
+    /// `course`
+    SingleCourseRequirement(SingleCourseRequirement),
+    Single(Requirement),
+    Many(Vec<Requirement>),
+}
+
+#[derive(Debug, Deserialize)]
+struct SingleCourseRequirement {
+    title: Option<String>,
+    course: Course,
+}
+
+// NOTE: Not `#[serde(untagged)]`, which replaces the error of every variant with "data did not
+// match any variant". Arrays are parsed in place so that their errors keep their path and
+// [VislogError], and only objects are buffered to try both of their variants.
+impl<'de> Deserialize<'de> for RawRequirement {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RawRequirementVisitor;
+
+        impl<'de> Visitor<'de> for RawRequirementVisitor {
+            type Value = RawRequirement;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a JSON object or array representing requirements")
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                error::clear_pending();
+
+                Ok(RawRequirement::Many(options::elements(seq)?))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                error::clear_pending();
+
+                // Entries rather than a `Value` so that duplicated fields are still seen as such
+                let mut entries: Vec<(String, Value)> = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                let buffered = || MapDeserializer::new(entries.iter().cloned());
+
+                if let Ok(single) =
+                    context::attempt(|| SingleCourseRequirement::deserialize(buffered()))
+                {
+                    return Ok(RawRequirement::SingleCourseRequirement(single));
+                }
+                Requirement::deserialize(buffered())
+                    .map(RawRequirement::Single)
+                    .map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(RawRequirementVisitor)
+    }
+}
+
 impl<'de> Deserialize<'de> for RequirementModule {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -150,32 +363,24 @@ impl<'de> Deserialize<'de> for RequirementModule {
             where
                 A: serde::de::MapAccess<'de>,
             {
+                error::clear_pending();
+
                 let mut title: Option<Option<String>> = None;
                 let mut requirements: Option<Vec<Requirement>> = None;
 
-                while let Ok(Some(key)) = map.next_key::<String>() {
+                while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
-                        "title" => {
-                            if title.is_some() {
-                                return Err(de::Error::duplicate_field("title"));
-                            }
-                            title = Some(map.next_value()?);
-                        }
+                        "title" => options::next_field(&mut map, &mut title, "title")?,
                         "requirement_list" => {
-                            if requirements.is_some() {
-                                return Err(de::Error::duplicate_field("requirement_list"));
-                            }
-                            requirements = Some(map.next_value()?);
-                        }
-                        _ => {
-                            let _ = map.next_value::<de::IgnoredAny>();
+                            options::next_field(&mut map, &mut requirements, "requirement_list")?
                         }
+                        _ => options::skip_unknown(&mut map, &key, MODULE_FIELDS)?,
                     }
                 }
 
-                let title = title.ok_or_else(|| de::Error::missing_field("title"))?;
+                let title = title.flatten().map(options::clean_text);
                 let requirements =
-                    requirements.ok_or_else(|| de::Error::missing_field("requirements"))?;
+                    requirements.ok_or_else(|| VislogError::missing("requirements").raise())?;
 
                 Ok(RequirementModule::BasicRequirements {
                     title,
@@ -184,7 +389,7 @@ impl<'de> Deserialize<'de> for RequirementModule {
             }
         }
 
-        deserializer.deserialize_any(RequirementModuleVisitor)
+        deserializer.deserialize_map(RequirementModuleVisitor)
     }
 }
 
@@ -206,54 +411,67 @@ impl<'de> Deserialize<'de> for Requirement {
             where
                 A: de::MapAccess<'de>,
             {
+                error::clear_pending();
+
                 let mut title: Option<Option<String>> = None;
                 let mut req_narrative: Option<Option<String>> = None;
                 let mut courses = None;
+                let mut extra = Extensions::default();
 
-                while let Ok(Some(key)) = map.next_key::<String>() {
+                while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
-                        "title" => {
-                            if title.is_some() {
-                                return Err(de::Error::duplicate_field("title"));
-                            }
-
-                            title = Some(map.next_value()?);
-                        }
+                        "title" => options::next_field(&mut map, &mut title, "title")?,
                         "req_narrative" => {
-                            if req_narrative.is_some() {
-                                return Err(de::Error::duplicate_field("req_narrative"));
-                            }
-
-                            req_narrative = Some(map.next_value()?);
-                        }
-                        "course" => {
-                            if courses.is_some() {
-                                return Err(de::Error::duplicate_field("course"));
-                            }
-
-                            courses = Some(map.next_value()?);
+                            options::next_field(&mut map, &mut req_narrative, "req_narrative")?
                         }
+                        "course" => options::next_field(&mut map, &mut courses, "course")?,
                         _ => {
-                            let _ = map.next_value::<de::IgnoredAny>();
+                            extensions::next_unknown(&mut map, &mut extra, key, REQUIREMENT_FIELDS)?
                         }
                     }
                 }
 
-                let title = title.ok_or_else(|| de::Error::missing_field("title"))?;
-                let req_narrative =
-                    req_narrative.ok_or_else(|| de::Error::missing_field("req_narrative"))?;
-
-                let requirement = match (title, courses) {
-                    (Some(title), courses) if title.contains("Select") => {
-                        Requirement::SelectFromCourses { title, courses }
+                let title = title.flatten().map(options::clean_text);
+                let req_narrative = req_narrative.flatten().map(options::clean_text);
+
+                let constraints =
+                    requirement_constraints(title.as_deref(), req_narrative.as_deref());
+                let kind = RequirementKind::classify(
+                    title.as_deref(),
+                    req_narrative.as_deref(),
+                    courses.is_some(),
+                );
+
+                let requirement = match (kind, title, courses) {
+                    (RequirementKind::Select, Some(title), courses) => {
+                        Requirement::SelectFromCourses {
+                            title,
+                            courses,
+                            constraints,
+                            extra,
+                        }
                     }
-                    (title, Some(course_entries)) => Requirement::Courses {
+                    (RequirementKind::ElectivePool(hours), title, _) => Requirement::ElectivePool {
                         title,
-                        courses: course_entries,
+                        req_narrative,
+                        hours,
+                        constraints,
+                        extra,
                     },
-                    (title, None) => Requirement::Label {
+                    (RequirementKind::Courses, title, Some(course_entries)) => {
+                        Requirement::Courses {
+                            title,
+                            courses: course_entries,
+                            constraints,
+                            extra,
+                        }
+                    }
+                    (_, title, _) => Requirement::Label {
                         title,
                         req_narrative,
+                        constraints,
+                        course_refs: vec![],
+                        extra,
                     },
                 };
 
@@ -261,7 +479,56 @@ impl<'de> Deserialize<'de> for Requirement {
             }
         }
 
-        deserializer.deserialize_any(RequirementVisitor)
+        deserializer.deserialize_map(RequirementVisitor)
+    }
+}
+
+/// Grade and GPA conditions found in the title and `req_narrative` of a requirement
+pub(crate) fn requirement_constraints(
+    title: Option<&str>,
+    req_narrative: Option<&str>,
+) -> Constraints {
+    [title, req_narrative]
+        .into_iter()
+        .flatten()
+        .map(Constraints::from_text)
+        .fold(Constraints::default(), Constraints::merge)
+}
+
+/// Which variant of [Requirement] a requirement of the catalog API is parsed into
+pub(crate) enum RequirementKind {
+    Select,
+    ElectivePool(ElectiveHours),
+    Courses,
+    Label,
+}
+
+impl RequirementKind {
+    pub(crate) fn classify(
+        title: Option<&str>,
+        req_narrative: Option<&str>,
+        has_courses: bool,
+    ) -> Self {
+        if title.is_some_and(|title| title.contains("Select")) {
+            return RequirementKind::Select;
+        }
+        if has_courses {
+            return RequirementKind::Courses;
+        }
+
+        // Only requirements without courses can be pools of electives. "Select" titles stay
+        // selections, since they usually narrow down the electives to a subject.
+        let hours = [title, req_narrative]
+            .into_iter()
+            .flatten()
+            .find_map(ElectiveHours::from_text);
+        match hours {
+            Some(hours) => RequirementKind::ElectivePool(hours),
+            None => {
+                metrics::global().increment(Counter::FallbackClassifications);
+                RequirementKind::Label
+            }
+        }
     }
 }
 
@@ -280,19 +547,16 @@ impl<'de> Deserialize<'de> for CourseEntries {
             }
 
             // Normal code path for `Requirement`s with a JSON array of `Course` objects in `course` field
-            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
             where
                 A: de::SeqAccess<'de>,
             {
-                let mut raw_entries = Vec::with_capacity(seq.size_hint().unwrap_or(4));
+                error::clear_pending();
 
-                while let Ok(Some(raw_entry)) = seq.next_element::<RawCourseEntry>() {
-                    raw_entries.push(raw_entry)
-                }
+                let raw_entries: Vec<RawCourseEntry> = options::elements(seq)?;
 
-                let course_entries = CoursesParser::new(raw_entries)
-                    .parse()
-                    .map_err(de::Error::custom)?;
+                let course_entries = CourseEntries::from_raw_entries(raw_entries)
+                    .map_err(|err| VislogError::from(err).raise())?;
 
                 Ok(course_entries)
             }
@@ -302,124 +566,78 @@ impl<'de> Deserialize<'de> for CourseEntries {
             where
                 A: de::MapAccess<'de>,
             {
+                error::clear_pending();
+
                 let mut url: Option<String> = None;
                 let mut path: Option<String> = None;
                 let mut guid: Option<Guid> = None;
                 let mut name: Option<Option<String>> = None;
-                let mut number: Option<Option<String>> = None;
-                let mut subject_name: Option<Option<String>> = None;
-                let mut subject_code: Option<Option<String>> = None;
+                let mut number: Option<Option<ScalarString>> = None;
+                let mut subject_name: Option<Option<Symbol>> = None;
+                let mut subject_code: Option<Option<Symbol>> = None;
                 let mut credits: Option<(u8, Option<u8>)> = None;
                 let mut is_narrative: Option<bool> = None;
+                let mut extra = Extensions::default();
 
-                while let Ok(Some(key)) = map.next_key::<String>() {
+                while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
-                        "url" => {
-                            if url.is_some() {
-                                return Err(de::Error::duplicate_field("url"));
-                            }
-
-                            url = Some(map.next_value()?);
-                        }
-                        "path" => {
-                            if path.is_some() {
-                                return Err(de::Error::duplicate_field("path"));
-                            }
-
-                            path = Some(map.next_value()?);
-                        }
-                        "guid" => {
-                            if guid.is_some() {
-                                return Err(de::Error::duplicate_field("guid"));
-                            }
-
-                            let guid_str_with_braces = map.next_value::<&str>()?;
-
-                            let guid_str_trimmed =
-                                &guid_str_with_braces[1..guid_str_with_braces.len() - 1];
-
-                            guid = Some(Guid::try_from(guid_str_trimmed).map_err(|e| {
-                                de::Error::custom(format!("error parsing guid: {}", e))
-                            })?);
-                        }
-                        "name" => {
-                            if name.is_some() {
-                                return Err(de::Error::duplicate_field("name"));
-                            }
-
-                            name = Some(map.next_value()?);
-                        }
-                        "number" => {
-                            if number.is_some() {
-                                return Err(de::Error::duplicate_field("number"));
-                            }
-
-                            number = Some(map.next_value()?);
-                        }
+                        "url" => options::next_field(&mut map, &mut url, "url")?,
+                        "path" => options::next_field(&mut map, &mut path, "path")?,
+                        "guid" => options::read_field(&mut map, &mut guid, "guid", |map| {
+                            let guid_str = map.next_value::<String>()?;
+
+                            dialect::parse_guid(&guid_str)
+                                .map_err(|err| VislogError::guid(&guid_str, err).raise())
+                        })?,
+                        "name" => options::next_field(&mut map, &mut name, "name")?,
+                        "number" => options::next_field(&mut map, &mut number, "number")?,
                         "subject_name" => {
-                            if subject_name.is_some() {
-                                return Err(de::Error::duplicate_field("subject_name"));
-                            }
-
-                            subject_name = Some(map.next_value()?);
+                            options::next_field(&mut map, &mut subject_name, "subject_name")?
                         }
                         "subject_code" => {
-                            if subject_code.is_some() {
-                                return Err(de::Error::duplicate_field("subject_code"));
-                            }
-
-                            subject_code = Some(map.next_value()?);
+                            options::next_field(&mut map, &mut subject_code, "subject_code")?
                         }
                         "credits" => {
-                            if credits.is_some() {
-                                return Err(de::Error::duplicate_field("credits"));
-                            }
+                            options::read_field(&mut map, &mut credits, "credits", |map| {
+                                let ScalarString(credits_str) = map.next_value()?;
 
-                            let credits_str = map.next_value::<&str>()?;
-                            credits =
-                                Some(parse_course_credits(credits_str).map_err(de::Error::custom)?);
-                        }
-                        "is_narrative" => {
-                            if is_narrative.is_some() {
-                                return Err(de::Error::duplicate_field("is_narrative"));
-                            }
-
-                            let is_narrative_str = map.next_value::<&str>()?;
-
-                            is_narrative = Some(match is_narrative_str {
-                                "True" => true,
-                                "False" => false,
-                                invalid_str => {
-                                    return Err(de::Error::custom(format!(
-                                        r#"Expected "True" or "False". Got: {}"#,
-                                        invalid_str
-                                    )))
-                                }
-                            });
-                        }
-                        _ => {
-                            let _ = map.next_value::<de::IgnoredAny>();
+                                parse_course_credits(&credits_str)
+                                    .map_err(|err| VislogError::credits(&credits_str, err).raise())
+                            })?
                         }
+                        "is_narrative" => options::read_field(
+                            &mut map,
+                            &mut is_narrative,
+                            "is_narrative",
+                            |map| {
+                                let ScalarString(is_narrative_str) = map.next_value()?;
+
+                                dialect::parse_bool(&is_narrative_str)
+                                    .ok_or_else(|| VislogError::bool(&is_narrative_str).raise())
+                            },
+                        )?,
+                        _ => extensions::next_unknown(
+                            &mut map,
+                            &mut extra,
+                            key,
+                            COURSE_ENTRY_FIELDS,
+                        )?,
                     }
                 }
 
-                let url = url.ok_or_else(|| de::Error::missing_field("url"))?;
-                let path = path.ok_or_else(|| de::Error::missing_field("path"))?;
-                let guid = guid.ok_or_else(|| de::Error::missing_field("guid"))?;
-                let name = name.ok_or_else(|| de::Error::missing_field("name"))?;
-                let number = number.ok_or_else(|| de::Error::missing_field("number"))?;
-                let subject_name =
-                    subject_name.ok_or_else(|| de::Error::missing_field("subject_name"))?;
-                let subject_code =
-                    subject_code.ok_or_else(|| de::Error::missing_field("subject_code"))?;
-                let credits = credits.ok_or_else(|| de::Error::missing_field("credits"))?;
+                let url = url.ok_or_else(|| VislogError::missing("url").raise())?;
+                let path = path.ok_or_else(|| VislogError::missing("path").raise())?;
+                let guid = guid.ok_or_else(|| VislogError::missing("guid").raise())?;
+                let name = name.flatten().map(options::clean_text);
+                let number = number.flatten().map(|ScalarString(number)| number);
+                let subject_name = subject_name.flatten();
+                let subject_code = subject_code.flatten();
+                let credits = credits.ok_or_else(|| VislogError::missing("credits").raise())?;
                 let is_narrative =
-                    is_narrative.ok_or_else(|| de::Error::missing_field("is_narrative"))?;
+                    is_narrative.ok_or_else(|| VislogError::missing("is_narrative").raise())?;
 
                 let entry = if is_narrative {
-                    let name = name.ok_or(de::Error::custom(
-                        "`name` field for `Label` should not be null",
-                    ))?;
+                    let name = name.ok_or_else(|| VislogError::missing("name").raise())?;
                     CourseEntry::Label(Label {
                         url,
                         guid,
@@ -429,13 +647,10 @@ impl<'de> Deserialize<'de> for CourseEntries {
                         number,
                     })
                 } else {
-                    let number = number.ok_or(de::Error::custom(
-                        "`number` field for `Course` should not be null",
-                    ))?;
-                    let subject_code = subject_code.ok_or(de::Error::custom(
-                        "`subject_code` field for `Course` should not be null",
-                    ))?;
-                    CourseEntry::Course(Course {
+                    let number = number.ok_or_else(|| VislogError::missing("number").raise())?;
+                    let subject_code =
+                        subject_code.ok_or_else(|| VislogError::missing("subject_code").raise())?;
+                    Course {
                         url,
                         path,
                         guid,
@@ -444,10 +659,13 @@ impl<'de> Deserialize<'de> for CourseEntries {
                         subject_name,
                         subject_code,
                         credits,
-                    })
+                        extra,
+                        details: None,
+                    }
+                    .into()
                 };
 
-                Ok(CourseEntries(vec![entry]))
+                Ok(CourseEntries::from(vec![entry]))
             }
         }
 
@@ -473,173 +691,114 @@ impl<'de> Deserialize<'de> for CourseDetails {
             where
                 A: de::MapAccess<'de>,
             {
+                error::clear_pending();
+
                 let mut url: Option<String> = None;
                 let mut guid: Option<String> = None;
                 let mut path: Option<String> = None;
-                let mut subject_code: Option<String> = None;
-                let mut subject_name: Option<Option<String>> = None;
-                let mut number: Option<String> = None;
+                let mut subject_code: Option<Symbol> = None;
+                let mut subject_name: Option<Option<Symbol>> = None;
+                let mut number: Option<ScalarString> = None;
                 let mut name: Option<String> = None;
-                let mut credits_min: Option<Option<String>> = None;
-                let mut credits_max: Option<Option<String>> = None;
+                let mut credits_min: Option<Option<ScalarString>> = None;
+                let mut credits_max: Option<Option<ScalarString>> = None;
                 let mut description: Option<String> = None;
                 let mut prerequisite_narrative: Option<Option<String>> = None;
-                let mut prerequisite: Option<Value> = None;
+                let mut prerequisite: Option<Option<RawRequisite>> = None;
                 let mut corequisite_narrative: Option<Option<String>> = None;
-                let mut corequisite: Option<Value> = None;
-
-                while let Some(key) = map.next_key::<&str>()? {
-                    match key {
-                        "url" => {
-                            if url.is_some() {
-                                return Err(de::Error::duplicate_field("url"));
-                            }
-                            url = Some(map.next_value()?);
-                        }
-                        "GUID" => {
-                            if guid.is_some() {
-                                return Err(de::Error::duplicate_field("guid"));
-                            }
-                            guid = Some(map.next_value()?);
-                        }
-                        "path" => {
-                            if path.is_some() {
-                                return Err(de::Error::duplicate_field("path"));
-                            }
-                            path = Some(map.next_value()?);
-                        }
+                let mut corequisite: Option<Option<RawRequisite>> = None;
+                let mut extra = Extensions::default();
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "url" => options::next_field(&mut map, &mut url, "url")?,
+                        "GUID" => options::next_field(&mut map, &mut guid, "guid")?,
+                        "path" => options::next_field(&mut map, &mut path, "path")?,
                         "subject_code" => {
-                            if subject_code.is_some() {
-                                return Err(de::Error::duplicate_field("subject_code"));
-                            }
-                            subject_code = Some(map.next_value()?);
+                            options::next_field(&mut map, &mut subject_code, "subject_code")?
                         }
                         "subject_name" => {
-                            if subject_name.is_some() {
-                                return Err(de::Error::duplicate_field("subject_name"));
-                            }
-                            subject_name = Some(map.next_value()?);
-                        }
-                        "number" => {
-                            if number.is_some() {
-                                return Err(de::Error::duplicate_field("number"));
-                            }
-                            number = Some(map.next_value()?);
-                        }
-                        "name" => {
-                            if name.is_some() {
-                                return Err(de::Error::duplicate_field("name"));
-                            }
-                            name = Some(map.next_value()?);
+                            options::next_field(&mut map, &mut subject_name, "subject_name")?
                         }
+                        "number" => options::next_field(&mut map, &mut number, "number")?,
+                        "name" => options::next_field(&mut map, &mut name, "name")?,
                         "credits_min" => {
-                            if credits_min.is_some() {
-                                return Err(de::Error::duplicate_field("credits_min"));
-                            }
-                            credits_min = Some(map.next_value()?);
+                            options::next_field(&mut map, &mut credits_min, "credits_min")?
                         }
                         "credits_max" => {
-                            if credits_max.is_some() {
-                                return Err(de::Error::duplicate_field("credits_max"));
-                            }
-                            credits_max = Some(map.next_value()?);
+                            options::next_field(&mut map, &mut credits_max, "credits_max")?
                         }
                         "description" => {
-                            if description.is_some() {
-                                return Err(de::Error::duplicate_field("description"));
-                            }
-                            description = Some(map.next_value()?);
-                        }
-                        "prerequisite_narrative" => {
-                            if prerequisite_narrative.is_some() {
-                                return Err(de::Error::duplicate_field("prerequisite_narrative"));
-                            }
-                            prerequisite_narrative = Some(map.next_value()?);
+                            options::next_field(&mut map, &mut description, "description")?
                         }
+                        "prerequisite_narrative" => options::next_field(
+                            &mut map,
+                            &mut prerequisite_narrative,
+                            "prerequisite_narrative",
+                        )?,
                         "prerequisite" => {
-                            if prerequisite.is_some() {
-                                return Err(de::Error::duplicate_field("prerequisite"));
-                            }
-                            prerequisite = Some(map.next_value()?);
-                        }
-                        "corequisite_narrative" => {
-                            if corequisite_narrative.is_some() {
-                                return Err(de::Error::duplicate_field("corequisite_narrative"));
-                            }
-                            corequisite_narrative = Some(map.next_value()?);
+                            options::next_field(&mut map, &mut prerequisite, "prerequisite")?
                         }
+                        "corequisite_narrative" => options::next_field(
+                            &mut map,
+                            &mut corequisite_narrative,
+                            "corequisite_narrative",
+                        )?,
                         "corequisite" => {
-                            if corequisite.is_some() {
-                                return Err(de::Error::duplicate_field("corequisite"));
-                            }
-                            corequisite = Some(map.next_value()?);
-                        }
-                        _ => {
-                            let _ = map.next_value::<de::IgnoredAny>();
+                            options::next_field(&mut map, &mut corequisite, "corequisite")?
                         }
+                        _ => extensions::next_unknown(
+                            &mut map,
+                            &mut extra,
+                            key,
+                            COURSE_DETAILS_FIELDS,
+                        )?,
                     }
                 }
 
-                let url = url.ok_or(de::Error::missing_field("url"))?;
-                let path = path.ok_or(de::Error::missing_field("path"))?;
-                let subject_code = subject_code.ok_or(de::Error::missing_field("subject_code"))?;
-                let subject_name = subject_name.ok_or(de::Error::missing_field("subject_name"))?;
-                let number = number.ok_or(de::Error::missing_field("number"))?;
-                let name = name.ok_or(de::Error::missing_field("name"))?;
-                let description = description.ok_or(de::Error::missing_field("description"))?;
-                let prerequisite_narrative = prerequisite_narrative
-                    .ok_or(de::Error::missing_field("prerequisite_narrative"))?;
-                let corequisite_narrative = corequisite_narrative
-                    .ok_or(de::Error::missing_field("corequisite_narrative"))?;
+                let url = url.ok_or_else(|| VislogError::missing("url").raise())?;
+                let path = path.ok_or_else(|| VislogError::missing("path").raise())?;
+                let subject_code =
+                    subject_code.ok_or_else(|| VislogError::missing("subject_code").raise())?;
+                let subject_name = subject_name.flatten();
+                let ScalarString(number) =
+                    number.ok_or_else(|| VislogError::missing("number").raise())?;
+                let name =
+                    options::clean_text(name.ok_or_else(|| VislogError::missing("name").raise())?);
+                let description = options::clean_text(
+                    description.ok_or_else(|| VislogError::missing("description").raise())?,
+                );
+                let prerequisite_narrative =
+                    prerequisite_narrative.flatten().map(options::clean_text);
+                let corequisite_narrative =
+                    corequisite_narrative.flatten().map(options::clean_text);
 
                 // Transform into integers
-                let credits_min = {
-                    let float_str = credits_min.ok_or(de::Error::missing_field("credits_min"))?;
-
-                    // NOTE: Assume credits equal zero when `credits_min` is `null` in JSON format
-                    if let Some(float_str) = float_str {
-                        let float: f32 = float_str.parse().map_err(|e| de::Error::custom(e))?;
-                        if float > u8::MAX as f32 {
-                            return Err(de::Error::custom(format!(
-                                "value of credits_max exceeded `u8::MAX` (255)"
-                            )));
-                        }
-                        float.trunc() as u8
-                    } else {
-                        0
-                    }
-                };
-
-                let credits_max = {
-                    let float_option =
-                        credits_max.ok_or(de::Error::missing_field("credits_max"))?;
-
-                    float_option
-                        .map(|float_str| float_str.parse::<f32>().map_err(|e| de::Error::custom(e)))
-                        .transpose()?
-                        .map(|float| {
-                            if float <= u8::MAX as f32 {
-                                Ok(float.trunc() as u8)
-                            } else {
-                                Err(de::Error::custom(format!(
-                                    "value of credits_max exceeded 255"
-                                )))
-                            }
-                        })
-                        .transpose()?
+                // NOTE: Assume credits equal zero when `credits_min` is `null` in JSON format
+                let credit_hours = |ScalarString(s): ScalarString| {
+                    dialect::parse_credit_hours(&s)
+                        .map_err(|err| VislogError::credits(&s, err).raise::<A::Error>())
                 };
+                let credits_min = credits_min
+                    .flatten()
+                    .map(credit_hours)
+                    .transpose()?
+                    .unwrap_or(0);
+                let credits_max = credits_max.flatten().map(credit_hours).transpose()?;
 
                 // These are optional fields
                 let prerequisite = prerequisite
-                    .map(|v| extract_guid_from_requisite(v).map_err(|e| de::Error::custom(e)))
+                    .flatten()
+                    .map(|requisite| requisite.guid().map_err(VislogError::raise))
                     .transpose()?;
                 let corequisite = corequisite
-                    .map(|v| extract_guid_from_requisite(v).map_err(|e| de::Error::custom(e)))
+                    .flatten()
+                    .map(|requisite| requisite.guid().map_err(VislogError::raise))
                     .transpose()?;
 
                 let guid_str = guid.ok_or(de::Error::missing_field("GUID"))?;
-                let guid = Guid::try_from(&guid_str[1..guid_str.len() - 1])
-                    .map_err(|e| de::Error::custom(e))?;
+                let guid = dialect::parse_guid(&guid_str)
+                    .map_err(|err| VislogError::guid(&guid_str, err).raise())?;
 
                 // Construct CourseDetails
                 let course_details = CourseDetails {
@@ -657,34 +816,104 @@ impl<'de> Deserialize<'de> for CourseDetails {
                     prerequisite,
                     corequisite_narrative,
                     corequisite,
+                    extra,
                 };
 
                 Ok(course_details)
             }
         }
 
-        /// Extracts only the `GUID` field from a [Value](serde_json::Value) constructed from
-        /// the `prerequisite` or `corequisite` field of an unparsed JSON object representing
-        /// the [CourseDetails](crate::CourseDetails) struct
-        fn extract_guid_from_requisite(requisite_json: Value) -> Result<Guid, String> {
-            let Value::Object(map) = requisite_json else {
-                return Err("expected JSON object".to_owned());
-            };
+        /// The `prerequisite` or `corequisite` field of an unparsed JSON object representing the
+        /// [CourseDetails](crate::CourseDetails) struct, of which only the `GUID` is kept
+        #[derive(Deserialize)]
+        struct RawRequisite {
+            #[serde(rename = "GUID")]
+            guid: String,
+        }
 
-            let guid_str = map.get("GUID").ok_or("missing field GUID")?;
-            let Value::String(guid_str) = guid_str else {
-                return Err("expected JSON string for field GUID".to_owned());
-            };
+        impl RawRequisite {
+            fn guid(&self) -> Result<Guid, VislogError> {
+                dialect::parse_guid(&self.guid).map_err(|err| VislogError::guid(&self.guid, err))
+            }
+        }
 
-            let guid_str_without_curly_braces = &guid_str[1..guid_str.len() - 1];
+        deserializer.deserialize_map(CourseDetailsVisitor)
+    }
+}
+
+/// A string that was written as a string, number or boolean. The catalog API quotes every scalar,
+/// but files authored by hand in formats like YAML or TOML usually don't.
+pub(crate) struct ScalarString(pub String);
+
+impl<'de> Deserialize<'de> for ScalarString {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ScalarStringVisitor;
 
-            Guid::try_from(guid_str_without_curly_braces).map_err(|e| e.to_string())
+        impl<'de> Visitor<'de> for ScalarStringVisitor {
+            type Value = ScalarString;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a string, number or boolean")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ScalarString(v.to_owned()))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ScalarString(v))
+            }
+
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                // Spelled the way the catalog API spells booleans, which every dialect accepts
+                Ok(ScalarString(if v { "True" } else { "False" }.to_owned()))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ScalarString(v.to_string()))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ScalarString(v.to_string()))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ScalarString(v.to_string()))
+            }
         }
 
-        deserializer.deserialize_map(CourseDetailsVisitor)
+        // Formats that aren't self-describing can't tell which kind of scalar comes next, so they
+        // only get the strings written by the catalog API
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ScalarStringVisitor)
+        } else {
+            deserializer.deserialize_string(ScalarStringVisitor)
+        }
     }
 }
 
+#[allow(dead_code)]
 pub(crate) fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
 where
     D: Deserializer<'de>,
@@ -692,9 +921,10 @@ where
     <T as FromStr>::Err: std::fmt::Display,
 {
     let s: String = Deserialize::deserialize(deserializer)?;
-    s.parse().map_err(|e| de::Error::custom(e))
+    s.parse().map_err(de::Error::custom)
 }
 
+#[allow(dead_code)]
 pub(crate) fn deserialize_and_floor_u8_from_float_str<'de, D>(
     deserializer: D,
 ) -> Result<u8, D::Error>
@@ -714,6 +944,7 @@ where
     }
 }
 
+#[allow(dead_code)]
 pub(crate) fn deserialize_extract_guid_only<'de, D>(
     deserializer: D,
 ) -> Result<Option<Guid>, D::Error>
@@ -735,14 +966,14 @@ where
         {
             let mut guid: Option<String> = None;
 
-            while let Ok(Some(key)) = map.next_key::<&str>() {
-                match key {
+            while let Some(key) = map.next_key::<String>()? {
+                match key.as_str() {
                     "GUID" => {
                         guid = map.next_value()?;
                         break;
                     }
                     _ => {
-                        let _ = map.next_value::<de::IgnoredAny>();
+                        map.next_value::<de::IgnoredAny>()?;
                     }
                 }
             }
@@ -751,9 +982,7 @@ where
                 Some(s) if s.len() < 32 => {
                     Err(de::Error::custom("string not long enough to be GUID"))
                 }
-                Some(s) => Ok(Some(
-                    Guid::try_from(&s[1..s.len() - 1]).map_err(|e| de::Error::custom(e))?,
-                )),
+                Some(s) => Ok(Some(dialect::parse_guid(&s).map_err(de::Error::custom)?)),
                 None => Ok(None),
             }
         }
@@ -761,3 +990,187 @@ where
 
     deserializer.deserialize_map(ExtractGuidVisitor)
 }
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use crate::Program;
+
+    use super::*;
+
+    const PROGRAM_FILES: [&str; 4] = [
+        "cs_major.json",
+        "cs_minor.json",
+        "digital_media_major.json",
+        "zoology_major.json",
+    ];
+
+    fn read_json(file_name: &str) -> (String, Value) {
+        let json = std::fs::read_to_string(format!("../data/{file_name}")).unwrap();
+        let value = serde_json::from_str(&json).unwrap();
+        (json, value)
+    }
+
+    /// TOML has no null, so fields that are null are left out instead
+    fn without_nulls(value: Value) -> Value {
+        match value {
+            Value::Object(map) => map
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect(),
+            Value::Array(values) => values.into_iter().map(without_nulls).collect(),
+            value => value,
+        }
+    }
+
+    #[test]
+    fn programs_parse_the_same_from_yaml() {
+        for file_name in PROGRAM_FILES {
+            let (json, value) = read_json(file_name);
+            let yaml = serde_yaml::to_string(&value).unwrap();
+
+            let from_json: Program = serde_json::from_str(&json).unwrap();
+            let from_yaml: Program = serde_yaml::from_str(&yaml).unwrap();
+            assert_eq!(from_json, from_yaml, "{file_name}");
+        }
+    }
+
+    #[test]
+    fn programs_parse_the_same_from_toml() {
+        for file_name in PROGRAM_FILES {
+            let (_, value) = read_json(file_name);
+            // TOML has no nulls, which unknown fields would otherwise keep
+            let value = without_nulls(value);
+            let toml = toml::to_string(&value).unwrap();
+
+            let from_json: Program = serde_json::from_str(&value.to_string()).unwrap();
+            let from_toml: Program = toml::from_str(&toml).unwrap();
+            assert_eq!(from_json, from_toml, "{file_name}");
+        }
+    }
+
+    #[test]
+    fn programs_parse_the_same_with_any_deserializer() {
+        for file_name in PROGRAM_FILES {
+            let (json, value) = read_json(file_name);
+            let from_str: Program = serde_json::from_str(&json).unwrap();
+
+            let mut from_slice = serde_json::Deserializer::from_slice(json.as_bytes());
+            assert_eq!(
+                Program::from_deserializer(&mut from_slice).unwrap(),
+                from_str,
+                "{file_name}"
+            );
+
+            // Readers can't lend strings out of their input
+            let mut from_reader = serde_json::Deserializer::from_reader(json.as_bytes());
+            assert_eq!(
+                Program::from_deserializer(&mut from_reader).unwrap(),
+                from_str,
+                "{file_name}"
+            );
+
+            let mut cbor = vec![];
+            ciborium::into_writer(&value, &mut cbor).unwrap();
+            let from_cbor: Program = ciborium::from_reader(cbor.as_slice()).unwrap();
+            assert_eq!(from_cbor, from_str, "{file_name}");
+        }
+    }
+
+    #[test]
+    fn errors_inside_of_requirements_are_reported() {
+        // Its first module has a single requirement instead of an array of them
+        let (json, _) = read_json("family_studies_major.json");
+
+        let err = serde_json::from_str::<Program>(&json).unwrap_err();
+        assert!(err.to_string().contains("expected a sequence"), "{err}");
+    }
+
+    #[test]
+    fn courses_parse_the_same_from_yaml_and_toml() {
+        let (_, value) = read_json("courses.json");
+        let courses = &value["courses"]["course"];
+
+        let from_json: Vec<CourseDetails> = serde_json::from_str(&courses.to_string()).unwrap();
+        let from_yaml: Vec<CourseDetails> =
+            serde_yaml::from_str(&serde_yaml::to_string(courses).unwrap()).unwrap();
+        assert_eq!(from_json.len(), 1870);
+        assert_eq!(from_json, from_yaml);
+
+        // TOML documents have to be tables, and have no nulls
+        let courses = without_nulls(courses.clone());
+        let from_json: Vec<CourseDetails> = serde_json::from_str(&courses.to_string()).unwrap();
+        let table = serde_json::json!({ "course": courses });
+        #[derive(Deserialize)]
+        struct Courses {
+            course: Vec<CourseDetails>,
+        }
+        let from_toml: Courses = toml::from_str(&toml::to_string(&table).unwrap()).unwrap();
+        assert_eq!(from_json, from_toml.course);
+    }
+
+    #[test]
+    fn requirements_naming_elective_hours_are_pools() {
+        let (catalog, _) = crate::catalog::Catalog::parse_file("../data/programs.json");
+        let requirements = |prefix: &str| {
+            catalog
+                .programs
+                .iter()
+                .find(|program| program.title.starts_with(prefix))
+                .unwrap()
+                .iter_requirements()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let political_science = requirements("Minor in Political Science");
+        assert!(political_science.iter().any(|requirement| matches!(
+            requirement,
+            Requirement::ElectivePool { title: Some(title), hours: ElectiveHours::Hours(15), .. }
+                if title == "PSC Electives: 15 hours"
+        )));
+
+        // Selections stay selections even when they are about electives
+        let social_work = requirements("Major in Social Work");
+        assert!(social_work.iter().any(|requirement| matches!(
+            requirement,
+            Requirement::SelectFromCourses { title, courses: None, .. }
+                if title == "Select 6 hours of SW electives"
+        )));
+    }
+
+    #[test]
+    fn hand_written_yaml_can_leave_scalars_unquoted() {
+        let yaml = r#"
+title: Degree Requirements
+requirement_list:
+  title: Core
+  course:
+    url: https://example.com/csc-115
+    path: /CSC-115
+    guid: "{860AF9C9-EAD9-45AC-AA92-BAF352C5288C}"
+    name: Computer Science
+    number: 115
+    subject_code: CSC
+    credits: 3
+    is_narrative: false
+"#;
+
+        let requirements: Requirements = serde_yaml::from_str(yaml).unwrap();
+        let Requirements::Single(module) = requirements else {
+            panic!("Expected requirements to be the `Single` variant");
+        };
+
+        let courses: Vec<&Course> = module.requirements()[0]
+            .course_entries()
+            .unwrap()
+            .iter_courses()
+            .collect();
+        assert_eq!(courses.len(), 1);
+        assert_eq!(courses[0].number, "115");
+        assert_eq!(courses[0].credits, (3, None));
+        assert_eq!(courses[0].subject_name, None);
+    }
+}
@@ -0,0 +1,377 @@
+//! Options for how forgiving parsing is of JSON that doesn't quite follow the catalog API.
+//!
+//! Like the [dialect](super::dialect), the `Deserialize` implementations of the data model can't
+//! take arguments, so [ParseOptions] are installed for the duration of a closure with
+//! [with_options]. Everything parsed outside of [with_options] uses [ParseOptions::default].
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{parsing::options::{self, DuplicateFields, ParseOptions}, Program};
+//! let json = std::fs::read_to_string("../data/cs_minor.json")
+//!     .unwrap()
+//!     .replacen(r#""title":"#, r#""title": "Old title", "title":"#, 1);
+//! assert!(serde_json::from_str::<Program>(&json).is_err());
+//!
+//! let options = ParseOptions {
+//!     duplicate_fields: DuplicateFields::LastWins,
+//!     ..Default::default()
+//! };
+//! let program: Program = options::with_options(options, || serde_json::from_str(&json)).unwrap();
+//! assert_ne!(program.title, "Old title");
+//! ```
+
+use std::cell::Cell;
+
+use serde::{
+    de::{self, MapAccess, SeqAccess},
+    Deserialize,
+};
+use serde_json::Value;
+
+use super::context::{self, Warning};
+
+/// How forgiving parsing is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    pub duplicate_fields: DuplicateFields,
+    /// Fail to parse objects with a field that the catalog API isn't known to send, as an early
+    /// warning that the format of the API changed. Fields that the API sends but vislog doesn't
+    /// parse, such as the `path` of a program, are still allowed.
+    ///
+    /// Only the visitors of [parsing](super) follow the option.
+    /// [ProgramRef](super::borrowed::ProgramRef), [LazyProgram](super::lazy::LazyProgram) and the
+    /// fields of a course that is the only one of its requirement always allow unknown fields.
+    pub strict_unknown_fields: bool,
+    /// Skip the modules, requirements and course entries that fail to parse instead of failing
+    /// the whole program. Each skipped entry raises a [Warning::SkippedEntry] with its index in
+    /// its list, which a [ParseContext](super::context::ParseContext) collects.
+    ///
+    /// Entries are read into a [Value] before being parsed, so duplicated fields in them keep
+    /// their last value whatever [duplicate_fields](Self::duplicate_fields) is set to.
+    pub skip_invalid_entries: bool,
+    /// Format of the names and narratives of programs, requirements and courses. The catalog API
+    /// sends them as HTML, which is kept by default.
+    pub text: TextFormat,
+}
+
+/// What to do with a field found more than once in the same JSON object.
+///
+/// Catalog dumps, such as `programs.json`, are read into a [serde_json::Value] before their
+/// programs are parsed, which already keeps the last value of a duplicated field. The option only
+/// makes a difference for files holding a single program or course, and input parsed directly.
+/// [ProgramRef](super::borrowed::ProgramRef), [LazyProgram](super::lazy::LazyProgram) and the
+/// fields of a course that is the only one of its requirement always reject duplicates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DuplicateFields {
+    /// Fail to parse the object
+    #[default]
+    Error,
+    /// Keep the first value and skip the others
+    FirstWins,
+    /// Keep the last value
+    LastWins,
+}
+
+/// Format to convert the HTML of names and narratives to while parsing. See [text](crate::text).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TextFormat {
+    /// Keep the text as the catalog API sends it
+    #[default]
+    Html,
+    /// Plain text, with [clean_narrative](crate::text::clean_narrative)
+    Plain,
+    /// Markdown, with [narrative_to_markdown](crate::text::narrative_to_markdown)
+    Markdown,
+}
+
+thread_local! {
+    static OPTIONS: Cell<ParseOptions> = const {
+        Cell::new(ParseOptions {
+            duplicate_fields: DuplicateFields::Error,
+            strict_unknown_fields: false,
+            skip_invalid_entries: false,
+            text: TextFormat::Html,
+        })
+    };
+}
+
+/// Parses everything in `f` with the `options` on the current thread, restoring the previous
+/// options afterwards
+pub fn with_options<R>(options: ParseOptions, f: impl FnOnce() -> R) -> R {
+    /// Restores the previous options even if `f` panics
+    struct Restore(ParseOptions);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            OPTIONS.with(|current| current.set(self.0));
+        }
+    }
+
+    let _restore = Restore(OPTIONS.with(|current| current.replace(options)));
+    f()
+}
+
+/// The options installed by [with_options] on the current thread. Used to carry the options over
+/// to other threads.
+pub fn current() -> ParseOptions {
+    OPTIONS.with(Cell::get)
+}
+
+/// The name or narrative `text` in the format of [ParseOptions::text]
+pub(crate) fn clean_text(text: String) -> String {
+    match current().text {
+        TextFormat::Html => text,
+        TextFormat::Plain => crate::text::clean_narrative(&text),
+        TextFormat::Markdown => crate::text::narrative_to_markdown(&text),
+    }
+}
+
+/// Reads the value of `field` into `slot`, following [ParseOptions::duplicate_fields] when the
+/// field was already read
+pub(crate) fn next_field<'de, A, T>(
+    map: &mut A,
+    slot: &mut Option<T>,
+    field: &'static str,
+) -> Result<(), A::Error>
+where
+    A: MapAccess<'de>,
+    T: Deserialize<'de>,
+{
+    read_field(map, slot, field, |map| map.next_value())
+}
+
+/// Same as [next_field] but the value is read with `read`
+pub(crate) fn read_field<'de, A, T>(
+    map: &mut A,
+    slot: &mut Option<T>,
+    field: &'static str,
+    read: impl FnOnce(&mut A) -> Result<T, A::Error>,
+) -> Result<(), A::Error>
+where
+    A: MapAccess<'de>,
+{
+    if slot.is_some() {
+        match current().duplicate_fields {
+            DuplicateFields::Error => return Err(de::Error::duplicate_field(field)),
+            DuplicateFields::FirstWins => {
+                map.next_value::<de::IgnoredAny>()?;
+                return Ok(());
+            }
+            DuplicateFields::LastWins => {}
+        }
+    }
+
+    *slot = Some(read(map)?);
+    Ok(())
+}
+
+/// Reads every element of `seq`, following [ParseOptions::skip_invalid_entries]
+pub(crate) fn elements<'de, A, T>(mut seq: A) -> Result<Vec<T>, A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Deserialize<'de>,
+{
+    let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or(4));
+    if !current().skip_invalid_entries {
+        while let Some(element) = seq.next_element()? {
+            elements.push(element);
+        }
+        return Ok(elements);
+    }
+
+    // Elements that fail to parse halfway leave the deserializer in the middle of them, so each
+    // one is read whole first
+    let mut index = 0;
+    while let Some(value) = seq.next_element::<Value>()? {
+        match context::attempt(|| T::deserialize(value)) {
+            Ok(element) => elements.push(element),
+            Err(err) => context::warn(Warning::SkippedEntry {
+                index,
+                reason: err.to_string(),
+            }),
+        }
+        index += 1;
+    }
+
+    Ok(elements)
+}
+
+/// Fails on the unknown `field` when [ParseOptions::strict_unknown_fields] is set and `field` isn't
+/// one of the `expected` fields of the catalog API
+pub(crate) fn check_unknown<E: de::Error>(
+    field: &str,
+    expected: &'static [&'static str],
+) -> Result<(), E> {
+    if current().strict_unknown_fields && !expected.contains(&field) {
+        return Err(E::unknown_field(field, expected));
+    }
+
+    Ok(())
+}
+
+/// Skips the value of the unknown `field` after [check_unknown]
+pub(crate) fn skip_unknown<'de, A>(
+    map: &mut A,
+    field: &str,
+    expected: &'static [&'static str],
+) -> Result<(), A::Error>
+where
+    A: MapAccess<'de>,
+{
+    check_unknown(field, expected)?;
+    map.next_value::<de::IgnoredAny>()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CourseDetails, CourseEntry, Program};
+
+    fn course_details(duplicate_fields: DuplicateFields) -> serde_json::Result<CourseDetails> {
+        let json = std::fs::read_to_string("../data/courses.json").unwrap();
+        let courses: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let json = courses["courses"]["course"][0].to_string().replacen(
+            r#""name":"#,
+            r#""name":"First","name":"#,
+            1,
+        );
+        let options = ParseOptions {
+            duplicate_fields,
+            ..Default::default()
+        };
+
+        with_options(options, || serde_json::from_str(&json))
+    }
+
+    #[test]
+    fn duplicate_fields_follow_the_options() {
+        let err = course_details(DuplicateFields::Error).unwrap_err();
+        assert!(err.to_string().contains("duplicate field `name`"));
+
+        assert_eq!(
+            course_details(DuplicateFields::FirstWins).unwrap().name,
+            "First"
+        );
+        assert_ne!(
+            course_details(DuplicateFields::LastWins).unwrap().name,
+            "First"
+        );
+
+        // The options only apply inside of the closure
+        assert_eq!(current(), ParseOptions::default());
+        assert!(course_details(DuplicateFields::Error).is_err());
+    }
+
+    #[test]
+    fn duplicate_fields_of_course_entries() {
+        let json = std::fs::read_to_string("../data/cs_major.json")
+            .unwrap()
+            .replacen(
+                r#""subject_code":"#,
+                r#""subject_code": "DUP", "subject_code":"#,
+                1,
+            );
+        assert!(serde_json::from_str::<Program>(&json).is_err());
+
+        let parse = |duplicate_fields| {
+            let options = ParseOptions {
+                duplicate_fields,
+                ..Default::default()
+            };
+            with_options(options, || serde_json::from_str::<Program>(&json)).unwrap()
+        };
+        let first_subject_code = |program: &Program| {
+            program
+                .iter_requirements()
+                .filter_map(|requirement| requirement.course_entries())
+                .flat_map(|entries| entries.iter())
+                .find_map(|entry| match entry {
+                    CourseEntry::Course(course) => Some(course.subject_code.clone()),
+                    CourseEntry::Label(label) => label.subject_code.clone(),
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        assert_eq!(
+            first_subject_code(&parse(DuplicateFields::FirstWins)),
+            "DUP"
+        );
+        assert_ne!(first_subject_code(&parse(DuplicateFields::LastWins)), "DUP");
+    }
+
+    #[test]
+    fn strict_mode_only_rejects_fields_the_api_doesnt_send() {
+        let strict = ParseOptions {
+            strict_unknown_fields: true,
+            ..Default::default()
+        };
+
+        let (_, errors) =
+            with_options(strict, || crate::catalog::Catalog::parse_dir("../data")).unwrap();
+        let (_, expected) = crate::catalog::Catalog::parse_dir("../data").unwrap();
+        assert_eq!(errors.len(), expected.len());
+
+        let json = std::fs::read_to_string("../data/cs_minor.json")
+            .unwrap()
+            .replacen('{', r#"{"tagline": null,"#, 1);
+        assert!(serde_json::from_str::<Program>(&json).is_ok());
+
+        let err = with_options(strict, || serde_json::from_str::<Program>(&json)).unwrap_err();
+        assert!(err.to_string().contains("unknown field `tagline`"), "{err}");
+    }
+
+    #[test]
+    fn invalid_entries_are_only_skipped_when_asked() {
+        let json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+        let expected: Program = serde_json::from_str(&json).unwrap();
+        let json = json.replacen(r#""credits": "3""#, r#""credits": "three""#, 1);
+        assert!(serde_json::from_str::<Program>(&json).is_err());
+
+        let options = ParseOptions {
+            skip_invalid_entries: true,
+            ..Default::default()
+        };
+        let mut context = context::ParseContext::new();
+        let program: Program = context
+            .collect(|| with_options(options, || serde_json::from_str(&json)))
+            .unwrap();
+
+        assert_eq!(
+            program.iter_requirements().count() + 1,
+            expected.iter_requirements().count()
+        );
+        match context.warnings() {
+            [Warning::SkippedEntry { reason, .. }] => {
+                assert!(reason.contains("invalid credits \"three\""), "{reason}")
+            }
+            other => panic!("unexpected warnings: {other:?}"),
+        }
+
+        // Valid programs are parsed the same either way
+        let json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+        assert_eq!(
+            with_options(options, || serde_json::from_str::<Program>(&json)).unwrap(),
+            serde_json::from_str::<Program>(&json).unwrap()
+        );
+    }
+
+    #[test]
+    fn names_and_narratives_are_converted() {
+        let json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+        let content = |text| {
+            let options = ParseOptions {
+                text,
+                ..Default::default()
+            };
+            let program: Program = with_options(options, || serde_json::from_str(&json)).unwrap();
+            program.content.unwrap()
+        };
+
+        assert!(content(TextFormat::Html).starts_with("<p style="));
+        assert!(content(TextFormat::Plain).starts_with("The Computer Science Minor is intended"));
+        assert_eq!(content(TextFormat::Markdown), content(TextFormat::Plain));
+    }
+}
@@ -0,0 +1,258 @@
+use std::io::{BufReader, Bytes, Read};
+
+use thiserror::Error;
+
+use crate::{
+    metrics::{self, Counter},
+    Program,
+};
+
+/// Iterator over the `Program`s in a JSON array of programs that only holds a single program in
+/// memory at a time.
+///
+/// The reader is split into the raw bytes of each element of the array, which is then deserialized
+/// on its own. This keeps memory usage flat no matter how large the whole catalog dump is, and a
+/// program that fails to parse does not prevent the following programs from being parsed.
+///
+/// # Example
+/// ```
+/// # use vislog_core::parsing::stream::ProgramStream;
+/// let json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+/// let dump = format!("[{json}, {json}]");
+///
+/// let programs = ProgramStream::from_reader(dump.as_bytes())
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(programs.len(), 2);
+/// ```
+pub struct ProgramStream<R: Read> {
+    bytes: Bytes<BufReader<R>>,
+    state: StreamState,
+    /// Index of the next element in the JSON array
+    index: usize,
+    /// Raw bytes of the element currently being read
+    buffer: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamState {
+    /// The opening `[` has not been read yet
+    Start,
+    /// Inside of the array and expecting another element
+    Elements,
+    /// The closing `]` has been read or an unrecoverable error occurred
+    Finished,
+}
+
+#[derive(Debug, Error)]
+pub enum ParseError {
+    #[error("failed to read catalog dump: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("malformed catalog dump: {0}")]
+    Format(&'static str),
+    #[error("failed to parse program at index {index}: {source}")]
+    Program {
+        index: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+impl<R: Read> ProgramStream<R> {
+    pub fn from_reader(reader: R) -> Self {
+        Self {
+            bytes: BufReader::new(reader).bytes(),
+            state: StreamState::Start,
+            index: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    fn next_byte(&mut self) -> Result<Option<u8>, ParseError> {
+        self.bytes.next().transpose().map_err(ParseError::Io)
+    }
+
+    fn next_non_whitespace_byte(&mut self) -> Result<Option<u8>, ParseError> {
+        while let Some(byte) = self.next_byte()? {
+            if !byte.is_ascii_whitespace() {
+                return Ok(Some(byte));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Reads the raw bytes of the next element into `self.buffer`. Returns `false` if the end of
+    /// the array has been reached instead.
+    fn read_element(&mut self) -> Result<bool, ParseError> {
+        self.buffer.clear();
+
+        let first = self
+            .next_non_whitespace_byte()?
+            .ok_or(ParseError::Format("unexpected end of input"))?;
+
+        if first == b']' {
+            // Only an empty array or a trailing comma can end up here
+            return match self.index {
+                0 => Ok(false),
+                _ => Err(ParseError::Format("trailing comma in array")),
+            };
+        }
+
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut byte = first;
+
+        loop {
+            if in_string {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+            } else {
+                match byte {
+                    b'"' => in_string = true,
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' if depth > 0 => depth -= 1,
+                    b',' if depth == 0 => {
+                        self.state = StreamState::Elements;
+                        return Ok(true);
+                    }
+                    b']' => {
+                        self.state = StreamState::Finished;
+                        return Ok(true);
+                    }
+                    _ => {}
+                }
+            }
+
+            self.buffer.push(byte);
+
+            byte = self
+                .next_byte()?
+                .ok_or(ParseError::Format("unexpected end of input"))?;
+        }
+    }
+}
+
+impl<R: Read> Iterator for ProgramStream<R> {
+    type Item = Result<Program, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == StreamState::Start {
+            match self.next_non_whitespace_byte() {
+                Ok(Some(b'[')) => self.state = StreamState::Elements,
+                Ok(_) => {
+                    self.state = StreamState::Finished;
+                    return Some(Err(ParseError::Format("expected a JSON array of programs")));
+                }
+                Err(err) => {
+                    self.state = StreamState::Finished;
+                    return Some(Err(err));
+                }
+            }
+        }
+
+        if self.state == StreamState::Finished {
+            return None;
+        }
+
+        match self.read_element() {
+            Ok(true) => {}
+            Ok(false) => {
+                self.state = StreamState::Finished;
+                return None;
+            }
+            Err(err) => {
+                self.state = StreamState::Finished;
+                return Some(Err(err));
+            }
+        }
+
+        let index = self.index;
+        self.index += 1;
+
+        let program = serde_json::from_slice::<Program>(&self.buffer).map_err(|source| {
+            metrics::global().increment(Counter::ProgramParseFailures);
+            ParseError::Program { index, source }
+        });
+
+        Some(program)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn read_program_json(file_name: &str) -> String {
+        std::fs::read_to_string(format!("../data/{file_name}")).unwrap()
+    }
+
+    #[test]
+    fn can_stream_programs_from_json_array() {
+        let dump = format!(
+            "[\n{},\n{}\n]",
+            read_program_json("cs_major.json"),
+            read_program_json("zoology_major.json")
+        );
+
+        let programs = ProgramStream::from_reader(dump.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to stream programs");
+
+        assert_eq!(programs.len(), 2);
+        assert_eq!(programs[0].title, "Major in Computer Science—42 hours");
+        assert_eq!(
+            programs[1],
+            serde_json::from_str::<Program>(&read_program_json("zoology_major.json")).unwrap()
+        );
+    }
+
+    #[test]
+    fn can_stream_empty_array() {
+        let mut stream = ProgramStream::from_reader(" [ ] ".as_bytes());
+
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn continues_after_program_that_fails_to_parse() {
+        let dump = format!(
+            r#"[{{"title": "Missing everything else", "nested": [1, "]"]}}, {}]"#,
+            read_program_json("cs_major.json")
+        );
+
+        let results: Vec<_> = ProgramStream::from_reader(dump.as_bytes()).collect();
+
+        assert_eq!(results.len(), 2);
+        assert!(matches!(
+            results[0],
+            Err(ParseError::Program { index: 0, .. })
+        ));
+        assert!(results[1].is_ok());
+    }
+
+    #[test]
+    fn error_when_input_is_not_an_array() {
+        let json = read_program_json("cs_major.json");
+        let mut stream = ProgramStream::from_reader(json.as_bytes());
+
+        assert!(matches!(stream.next(), Some(Err(ParseError::Format(_)))));
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn error_when_array_is_not_terminated() {
+        let dump = format!("[{}", read_program_json("cs_major.json"));
+
+        let results: Vec<_> = ProgramStream::from_reader(dump.as_bytes()).collect();
+
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(ParseError::Format(_))));
+    }
+}
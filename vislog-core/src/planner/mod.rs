@@ -0,0 +1,747 @@
+//! Plans of the courses of a [Program] over consecutive terms.
+//!
+//! [layered_plan] places every distinct course of a program in a term after all of its
+//! prerequisites, without going over a maximum number of credits per term. Terms are filled one
+//! at a time, preferring the courses with the longest chain of courses depending on them so that
+//! long prerequisite chains start as early as possible.
+//!
+//! Prerequisites outside of the program aren't planned and are assumed to be taken whenever they
+//! are needed. Every course listed by the program is planned, including each option of an `Or`
+//! group, so plans are an upper bound on the number of terms a program takes.
+//!
+//! Given an [OfferingCalendar], terms follow the seasons of the calendar and courses are only
+//! placed in terms they are offered in, leaving a term empty when none of the courses that are
+//! left can be taken in it. Courses the calendar never offers make the plan infeasible.
+//!
+//! [check_schedule] checks the courses a student proposes for a term against their transcript
+//! instead, reporting every prerequisite and corequisite that isn't met.
+//!
+//! [optimize] searches for better plans than [layered_plan], with fewer terms, more even credits
+//! or fewer heavy courses per term.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, graph::PrerequisiteGraph, planner, schedule::{OfferingCalendar, Season}};
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//! let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+//!
+//! let plan = planner::layered_plan(&catalog.programs[0], &graph, 15, None);
+//!
+//! assert!(plan.terms.iter().all(|term| term.credits <= 15 || term.courses.len() == 1));
+//! println!("{}", plan.to_dot());
+//!
+//! let calendar = OfferingCalendar::new("Fall 2024".parse().unwrap(), [Season::Fall, Season::Spring]);
+//! let plan = planner::layered_plan(&catalog.programs[0], &graph, 15, Some(&calendar));
+//!
+//! assert_eq!(plan.terms[1].term, "Spring 2025".parse().ok());
+//! assert!(plan.is_feasible());
+//! ```
+
+pub mod optimize;
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Write},
+};
+
+use serde::Serialize;
+
+use crate::{
+    audit::{code_key, CompletedCourse, CourseId, GradePolicy},
+    graph::{GraphEdge, PrerequisiteGraph},
+    parsing::guid::Guid,
+    requisites::NamedCourse,
+    schedule::{self, OfferingCalendar},
+    Course, CourseDetails, Program,
+};
+
+/// Courses of a program split into consecutive terms
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LayeredPlan {
+    pub title: String,
+    pub max_credits: u32,
+    pub terms: Vec<Term>,
+    /// Prerequisite edges between planned courses, from the prerequisite to the course requiring it
+    pub edges: Vec<GraphEdge>,
+    /// Courses that can't be planned because they are part of a prerequisite cycle, or depend on
+    /// a course that can't be planned
+    pub unscheduled: Vec<PlannedCourse>,
+    /// Courses that are never offered in the terms of the calendar the plan follows
+    pub unoffered: Vec<PlannedCourse>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Term {
+    /// Term of the calendar the plan follows, if any
+    pub term: Option<schedule::Term>,
+    pub courses: Vec<PlannedCourse>,
+    /// Sum of the minimum credits of the courses in the term
+    pub credits: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PlannedCourse {
+    pub guid: Guid,
+    /// Subject code and number of the course. Ex: "CSC 125"
+    pub label: String,
+    pub name: Option<String>,
+    /// Minimum credits of the course
+    pub credits: u8,
+}
+
+impl From<&Course> for PlannedCourse {
+    fn from(course: &Course) -> Self {
+        Self {
+            guid: course.guid,
+            label: format!("{} {}", course.subject_code, course.number),
+            name: course.name.clone(),
+            credits: course.credits.0,
+        }
+    }
+}
+
+/// Plans every distinct course of the `program` over as many terms as needed, taking at most
+/// `max_credits` per term. A course worth more than `max_credits` gets a term of its own.
+///
+/// With a `calendar`, the terms of the plan are the terms of the calendar and courses are only
+/// planned in terms they are offered in.
+pub fn layered_plan(
+    program: &Program,
+    graph: &PrerequisiteGraph,
+    max_credits: u32,
+    calendar: Option<&OfferingCalendar>,
+) -> LayeredPlan {
+    let filling = Filling {
+        max_credits,
+        max_heavy: None,
+        tie_break: &|_| 0,
+    };
+
+    fill_terms(program, graph, calendar, &filling)
+}
+
+/// How [fill_terms] fills every term
+pub(crate) struct Filling<'a> {
+    pub(crate) max_credits: u32,
+    /// Most courses worth at least the given credits in a term, if limited
+    pub(crate) max_heavy: Option<(usize, u8)>,
+    /// Order of the courses with chains of dependent courses of the same length, lowest first.
+    /// Courses with the same value keep the order of the program.
+    pub(crate) tie_break: &'a dyn Fn(&Course) -> u64,
+}
+
+/// Plans the courses of the `program` like [layered_plan], filling terms the way of the `filling`
+pub(crate) fn fill_terms(
+    program: &Program,
+    graph: &PrerequisiteGraph,
+    calendar: Option<&OfferingCalendar>,
+    filling: &Filling,
+) -> LayeredPlan {
+    let max_credits = filling.max_credits;
+    let mut seen = HashSet::new();
+    let courses: Vec<&Course> = program
+        .iter_courses()
+        .filter(|course| seen.insert(course.guid))
+        .collect();
+
+    // Prerequisites of every course that are part of the program
+    let prerequisites: HashMap<Guid, Vec<Guid>> = courses
+        .iter()
+        .map(|course| {
+            let prerequisites = graph
+                .prerequisites_of(&course.guid)
+                .iter()
+                .filter(|prerequisite| seen.contains(prerequisite))
+                .copied()
+                .collect();
+            (course.guid, prerequisites)
+        })
+        .collect();
+
+    let heights = heights(&courses, &prerequisites);
+
+    let mut planned: HashMap<Guid, usize> = HashMap::new();
+    let mut remaining = courses.clone();
+    let mut terms = vec![];
+    let mut calendar_terms = calendar.map(OfferingCalendar::terms);
+
+    loop {
+        let term_index = terms.len();
+        let calendar_term = match &mut calendar_terms {
+            Some(calendar_terms) => match calendar_terms.next() {
+                Some(term) => Some(term),
+                None => break,
+            },
+            None => None,
+        };
+
+        let ready: Vec<&Course> = remaining
+            .iter()
+            .filter(|course| {
+                prerequisites[&course.guid]
+                    .iter()
+                    .all(|prerequisite| planned.get(prerequisite).is_some_and(|t| *t < term_index))
+            })
+            .copied()
+            .collect();
+        if ready.is_empty() {
+            break;
+        }
+
+        let mut available: Vec<&Course> = match (calendar, calendar_term) {
+            (Some(calendar), Some(term)) => ready
+                .iter()
+                .filter(|course| calendar.is_offered(&course.subject_code, &course.number, term))
+                .copied()
+                .collect(),
+            _ => ready.clone(),
+        };
+        // Leave the term empty while waiting for a term that one of the courses is offered in
+        let offered_later = calendar.is_some_and(|calendar| {
+            ready
+                .iter()
+                .any(|course| calendar.is_ever_offered(&course.subject_code, &course.number))
+        });
+        if available.is_empty() && !offered_later {
+            break;
+        }
+
+        // Stable sort, so ties keep the order of the program
+        available.sort_by_key(|course| {
+            (
+                std::cmp::Reverse(heights[&course.guid]),
+                (filling.tie_break)(course),
+            )
+        });
+
+        let mut term = Term {
+            term: calendar_term,
+            ..Term::default()
+        };
+        let mut heavy = 0;
+        for course in available {
+            let credits = course.credits.0 as u32;
+            if !term.courses.is_empty() && term.credits + credits > max_credits {
+                continue;
+            }
+            if let Some((max_heavy, heavy_credits)) = filling.max_heavy {
+                if course.credits.0 >= heavy_credits {
+                    if !term.courses.is_empty() && heavy >= max_heavy {
+                        continue;
+                    }
+                    heavy += 1;
+                }
+            }
+
+            term.credits += credits;
+            term.courses.push(PlannedCourse::from(course));
+            planned.insert(course.guid, term_index);
+        }
+
+        remaining.retain(|course| !planned.contains_key(&course.guid));
+        terms.push(term);
+    }
+
+    let edges = courses
+        .iter()
+        .filter(|course| planned.contains_key(&course.guid))
+        .flat_map(|course| {
+            prerequisites[&course.guid]
+                .iter()
+                .map(|prerequisite| GraphEdge {
+                    from: *prerequisite,
+                    to: course.guid,
+                })
+        })
+        .collect();
+
+    let (unoffered, unscheduled): (Vec<&Course>, Vec<&Course>) =
+        remaining.into_iter().partition(|course| {
+            calendar.is_some_and(|calendar| {
+                !calendar.is_ever_offered(&course.subject_code, &course.number)
+            })
+        });
+
+    LayeredPlan {
+        title: program.title.clone(),
+        max_credits,
+        terms,
+        edges,
+        unscheduled: unscheduled.into_iter().map(PlannedCourse::from).collect(),
+        unoffered: unoffered.into_iter().map(PlannedCourse::from).collect(),
+    }
+}
+
+/// Number of courses in the longest chain of courses depending on each course, not counting the
+/// course itself
+pub(crate) fn heights(
+    courses: &[&Course],
+    prerequisites: &HashMap<Guid, Vec<Guid>>,
+) -> HashMap<Guid, u32> {
+    let mut dependents: HashMap<Guid, Vec<Guid>> = HashMap::new();
+    for (course, prerequisites) in prerequisites {
+        for prerequisite in prerequisites {
+            dependents.entry(*prerequisite).or_default().push(*course);
+        }
+    }
+
+    fn height(
+        guid: Guid,
+        dependents: &HashMap<Guid, Vec<Guid>>,
+        heights: &mut HashMap<Guid, u32>,
+        visiting: &mut HashSet<Guid>,
+    ) -> u32 {
+        if let Some(height) = heights.get(&guid) {
+            return *height;
+        }
+        // The edge closing a cycle doesn't add to the height
+        if !visiting.insert(guid) {
+            return 0;
+        }
+
+        let height = dependents
+            .get(&guid)
+            .into_iter()
+            .flatten()
+            .map(|dependent| height(*dependent, dependents, heights, visiting) + 1)
+            .max()
+            .unwrap_or(0);
+
+        visiting.remove(&guid);
+        heights.insert(guid, height);
+        height
+    }
+
+    let mut heights = HashMap::new();
+    for course in courses {
+        height(course.guid, &dependents, &mut heights, &mut HashSet::new());
+    }
+
+    heights
+}
+
+impl LayeredPlan {
+    /// Whether every course of the program is planned
+    pub fn is_feasible(&self) -> bool {
+        self.unscheduled.is_empty() && self.unoffered.is_empty()
+    }
+
+    /// The term the course with the `guid` is planned in, counting from 0
+    pub fn term_of(&self, guid: &Guid) -> Option<usize> {
+        self.terms
+            .iter()
+            .position(|term| term.courses.iter().any(|course| &course.guid == guid))
+    }
+
+    /// Renders the plan as a Graphviz DOT digraph with one column per term, left to right, and
+    /// the prerequisite edges between the courses
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!("digraph {} {{\n", dot_string(&self.title));
+        dot.push_str("    rankdir=LR;\n");
+
+        for (i, term) in self.terms.iter().enumerate() {
+            let _ = writeln!(dot, "    subgraph cluster_term_{} {{", i + 1);
+            let name = match term.term {
+                Some(term) => term.to_string(),
+                None => format!("Term {}", i + 1),
+            };
+            let _ = writeln!(
+                dot,
+                "        label={};",
+                dot_string(&format!("{name} ({} credits)", term.credits))
+            );
+            dot.push_str("        rank=same;\n");
+
+            for course in &term.courses {
+                let _ = writeln!(
+                    dot,
+                    "        {} [label={}, tooltip={}];",
+                    dot_string(&course.guid.to_string()),
+                    dot_string(&course.label),
+                    dot_string(course.name.as_deref().unwrap_or_default()),
+                );
+            }
+
+            dot.push_str("    }\n");
+        }
+
+        for edge in &self.edges {
+            let _ = writeln!(
+                dot,
+                "    {} -> {};",
+                dot_string(&edge.from.to_string()),
+                dot_string(&edge.to.to_string()),
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// A course proposed for a term whose requisites aren't met, see [check_schedule]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", content = "data")]
+pub enum ScheduleViolation {
+    /// A proposed course that isn't in the course catalog, so its requisites can't be checked
+    UnknownCourse { course: CourseId },
+    /// A proposed course whose prerequisite wasn't passed in a prior term
+    MissingPrerequisite {
+        course: NamedCourse,
+        prerequisite: NamedCourse,
+    },
+    /// A proposed course whose corequisite wasn't passed in a prior term, and isn't proposed for
+    /// the same term either
+    MissingCorequisite {
+        course: NamedCourse,
+        corequisite: NamedCourse,
+    },
+}
+
+impl fmt::Display for ScheduleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleViolation::UnknownCourse { course } => match course {
+                CourseId::Guid { guid } => write!(f, "course {guid} is not in the catalog"),
+                CourseId::Code {
+                    subject_code,
+                    number,
+                } => write!(f, "course {subject_code} {number} is not in the catalog"),
+            },
+            ScheduleViolation::MissingPrerequisite {
+                course,
+                prerequisite,
+            } => write!(
+                f,
+                "{course} requires {prerequisite} to be passed in a prior term"
+            ),
+            ScheduleViolation::MissingCorequisite {
+                course,
+                corequisite,
+            } => write!(
+                f,
+                "{course} requires {corequisite} to be passed in a prior term or taken along with it"
+            ),
+        }
+    }
+}
+
+/// Checks that the courses `proposed` for a term can be taken after the courses of the
+/// `transcript`, using the requisites of the `courses` of the catalog. Prerequisites must be
+/// passed in a prior term, while corequisites can also be taken in the same term. Courses of the
+/// transcript failed with an F don't meet any requisite.
+///
+/// Violations are in the order of the `proposed` courses. An empty list means the schedule can be
+/// registered for.
+pub fn check_schedule(
+    transcript: &[CompletedCourse],
+    proposed: &[CourseId],
+    courses: &[CourseDetails],
+) -> Vec<ScheduleViolation> {
+    let by_guid: HashMap<Guid, &CourseDetails> =
+        courses.iter().map(|course| (course.guid, course)).collect();
+    let by_code: HashMap<(String, String), Guid> = courses
+        .iter()
+        .map(|course| (code_key(&course.subject_code, &course.number), course.guid))
+        .collect();
+    let resolve = |id: &CourseId| match id {
+        CourseId::Guid { guid } => Some(*guid),
+        CourseId::Code {
+            subject_code,
+            number,
+        } => by_code.get(&code_key(subject_code, number)).copied(),
+    };
+    let named = |guid: Guid| NamedCourse {
+        guid,
+        code: match by_guid.get(&guid) {
+            Some(course) => format!("{} {}", course.subject_code, course.number),
+            None => guid.to_string(),
+        },
+    };
+
+    let passed: HashSet<Guid> = transcript
+        .iter()
+        .filter(|course| {
+            course
+                .grade
+                .is_none_or(|grade| grade.passes(GradePolicy::default().passing_grade))
+        })
+        .filter_map(|course| resolve(&course.id))
+        .collect();
+    let concurrent: HashSet<Guid> = proposed.iter().filter_map(resolve).collect();
+
+    let mut violations = vec![];
+    for id in proposed {
+        let Some(course) = resolve(id).and_then(|guid| by_guid.get(&guid)) else {
+            violations.push(ScheduleViolation::UnknownCourse { course: id.clone() });
+            continue;
+        };
+
+        if let Some(prerequisite) = course.prerequisite {
+            if !passed.contains(&prerequisite) {
+                violations.push(ScheduleViolation::MissingPrerequisite {
+                    course: named(course.guid),
+                    prerequisite: named(prerequisite),
+                });
+            }
+        }
+        if let Some(corequisite) = course.corequisite {
+            if !passed.contains(&corequisite) && !concurrent.contains(&corequisite) {
+                violations.push(ScheduleViolation::MissingCorequisite {
+                    course: named(course.guid),
+                    corequisite: named(corequisite),
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+/// Quoted DOT string literal
+fn dot_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        catalog::Catalog,
+        constraints::Grade,
+        schedule::{Availability, Season, Years},
+        CourseDetails,
+    };
+
+    fn cs_major() -> (Program, PrerequisiteGraph) {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let program = catalog
+            .programs
+            .into_iter()
+            .find(|program| program.title.starts_with("Major in Computer Science"))
+            .unwrap();
+
+        (
+            program,
+            PrerequisiteGraph::from_course_details(&catalog.courses),
+        )
+    }
+
+    #[test]
+    fn courses_come_after_their_prerequisites() {
+        let (program, graph) = cs_major();
+        let plan = layered_plan(&program, &graph, 12, None);
+
+        let distinct: HashSet<Guid> = program.iter_courses().map(|course| course.guid).collect();
+        let planned: usize = plan.terms.iter().map(|term| term.courses.len()).sum();
+        assert_eq!(planned + plan.unscheduled.len(), distinct.len());
+
+        for edge in &plan.edges {
+            assert!(plan.term_of(&edge.from).unwrap() < plan.term_of(&edge.to).unwrap());
+        }
+        for term in &plan.terms {
+            assert!(!term.courses.is_empty());
+            assert!(term.credits <= 12 || term.courses.len() == 1);
+        }
+    }
+
+    #[test]
+    fn cycles_are_left_unscheduled() {
+        let (program, _) = cs_major();
+        let mut courses = program.iter_courses();
+        let (a, b) = (courses.next().unwrap().guid, courses.next().unwrap().guid);
+
+        let details = |guid: Guid, prerequisite: Guid| CourseDetails {
+            url: String::new(),
+            guid,
+            path: String::new(),
+            subject_code: Default::default(),
+            subject_name: None,
+            number: String::new(),
+            name: String::new(),
+            credits_min: 3,
+            credits_max: None,
+            description: String::new(),
+            prerequisite_narrative: None,
+            prerequisite: Some(prerequisite),
+            corequisite_narrative: None,
+            corequisite: None,
+            extra: Default::default(),
+        };
+        let graph = PrerequisiteGraph::from_course_details(&[details(a, b), details(b, a)]);
+
+        let plan = layered_plan(&program, &graph, 18, None);
+        let unscheduled: HashSet<Guid> = plan.unscheduled.iter().map(|c| c.guid).collect();
+        assert_eq!(unscheduled, HashSet::from([a, b]));
+        assert!(plan.term_of(&a).is_none());
+    }
+
+    #[test]
+    fn schedules_are_checked_against_the_transcript() {
+        let guid = |n: u8| {
+            Guid::try_from(format!("00000000-0000-0000-0000-0000000000{n:02}").as_str()).unwrap()
+        };
+        let details = |n: u8, prerequisite: Option<u8>, corequisite: Option<u8>| CourseDetails {
+            url: String::new(),
+            guid: guid(n),
+            path: String::new(),
+            subject_code: "CSC".into(),
+            subject_name: None,
+            number: format!("{n}00"),
+            name: String::new(),
+            credits_min: 3,
+            credits_max: None,
+            description: String::new(),
+            prerequisite_narrative: None,
+            prerequisite: prerequisite.map(guid),
+            corequisite_narrative: None,
+            corequisite: corequisite.map(guid),
+            extra: Default::default(),
+        };
+        // 2 requires 1, 3 requires 2 and is taken along with 4
+        let courses = [
+            details(1, None, None),
+            details(2, Some(1), None),
+            details(3, Some(2), Some(4)),
+            details(4, None, None),
+        ];
+        let code = |number: &str| CourseId::Code {
+            subject_code: "csc".to_owned(),
+            number: number.to_owned(),
+        };
+
+        let transcript = [CompletedCourse::code("CSC", "100", 3)];
+        assert_eq!(
+            check_schedule(
+                &transcript,
+                &[code("200"), CourseId::Guid { guid: guid(4) }],
+                &courses
+            ),
+            []
+        );
+
+        let failed = [CompletedCourse::guid(guid(1), 3).with_grade(Grade::F)];
+        let violations =
+            check_schedule(&failed, &[code("200"), code("300"), code("999")], &courses);
+        assert_eq!(
+            violations,
+            [
+                ScheduleViolation::MissingPrerequisite {
+                    course: NamedCourse {
+                        guid: guid(2),
+                        code: "CSC 200".to_owned()
+                    },
+                    prerequisite: NamedCourse {
+                        guid: guid(1),
+                        code: "CSC 100".to_owned()
+                    },
+                },
+                ScheduleViolation::MissingPrerequisite {
+                    course: NamedCourse {
+                        guid: guid(3),
+                        code: "CSC 300".to_owned()
+                    },
+                    prerequisite: NamedCourse {
+                        guid: guid(2),
+                        code: "CSC 200".to_owned()
+                    },
+                },
+                ScheduleViolation::MissingCorequisite {
+                    course: NamedCourse {
+                        guid: guid(3),
+                        code: "CSC 300".to_owned()
+                    },
+                    corequisite: NamedCourse {
+                        guid: guid(4),
+                        code: "CSC 400".to_owned()
+                    },
+                },
+                ScheduleViolation::UnknownCourse {
+                    course: code("999")
+                },
+            ]
+        );
+        assert_eq!(
+            violations[2].to_string(),
+            format!(
+                "CSC 300 ({}) requires CSC 400 ({}) to be passed in a prior term or taken along \
+                 with it",
+                guid(3),
+                guid(4)
+            )
+        );
+    }
+
+    #[test]
+    fn courses_are_planned_in_terms_they_are_offered() {
+        let (program, graph) = cs_major();
+        let unconstrained = layered_plan(&program, &graph, 15, None);
+        let edge = &unconstrained.edges[0];
+        let course = |guid: Guid| program.iter_courses().find(|c| c.guid == guid).unwrap();
+        let (prerequisite, dependent) = (course(edge.from), course(edge.to));
+
+        let mut calendar = OfferingCalendar::new(
+            "Spring 2025".parse().unwrap(),
+            [Season::Fall, Season::Spring],
+        );
+        calendar.set_availability(
+            &prerequisite.subject_code,
+            &prerequisite.number,
+            Availability::new([Season::Fall], Years::Every),
+        );
+
+        let plan = layered_plan(&program, &graph, 15, Some(&calendar));
+        assert!(plan.is_feasible());
+        assert_eq!(plan.terms[0].term, "Spring 2025".parse().ok());
+        for term in &plan.terms {
+            let term_of_calendar = term.term.unwrap();
+            for planned in &term.courses {
+                let course = course(planned.guid);
+                assert!(calendar.is_offered(
+                    &course.subject_code,
+                    &course.number,
+                    term_of_calendar
+                ));
+            }
+        }
+        assert_eq!(
+            plan.terms[plan.term_of(&prerequisite.guid).unwrap()]
+                .term
+                .unwrap()
+                .season,
+            Season::Fall
+        );
+        assert!(plan.term_of(&prerequisite.guid) < plan.term_of(&dependent.guid));
+        assert!(plan.to_dot().contains("Fall 2025"));
+
+        // Never offered in the fall or spring
+        calendar.set_availability(
+            &prerequisite.subject_code,
+            &prerequisite.number,
+            Availability::new([Season::Summer], Years::Every),
+        );
+        let plan = layered_plan(&program, &graph, 15, Some(&calendar));
+        assert!(!plan.is_feasible());
+        assert_eq!(plan.unoffered.len(), 1);
+        assert_eq!(plan.unoffered[0].guid, prerequisite.guid);
+        assert!(plan.unscheduled.iter().any(|c| c.guid == dependent.guid));
+        assert!(plan.terms.iter().all(|term| !term.courses.is_empty()));
+    }
+
+    #[test]
+    fn dot_has_a_column_per_term() {
+        let (program, graph) = cs_major();
+        let plan = layered_plan(&program, &graph, 15, None);
+        let dot = plan.to_dot();
+
+        assert_eq!(
+            dot.matches("subgraph cluster_term_").count(),
+            plan.terms.len()
+        );
+        assert_eq!(dot.matches(" -> ").count(), plan.edges.len());
+    }
+}
@@ -0,0 +1,293 @@
+//! Search for the best [layered plans](super::layered_plan) of a program by an [Objective].
+//!
+//! [optimize] plans the program many times over, changing how terms are filled: the order of
+//! courses with prerequisite chains of the same length, the most credits taken in a term, down to
+//! an even split of the credits of the program, and whether terms take at most
+//! [max_heavy](OptimizeOptions::max_heavy) heavy courses. Every distinct plan is scored, and the
+//! best ones for the objective are returned first. Plans that leave courses out always come after
+//! the ones planning every course.
+//!
+//! The search is a heuristic, so the plans it finds are good rather than optimal, but every one of
+//! them keeps courses after their prerequisites and in terms they are offered in.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, graph::PrerequisiteGraph, planner::optimize::{self, Objective, OptimizeOptions}};
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//! let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+//!
+//! let options = OptimizeOptions {
+//!     objective: Objective::BalancedLoad,
+//!     ..Default::default()
+//! };
+//! let candidates = optimize::optimize(&catalog.programs[0], &graph, None, &options);
+//!
+//! let best = &candidates[0];
+//! println!("{} terms, {} credits apart", best.score.terms, best.score.credit_spread);
+//! ```
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use super::{fill_terms, Filling, LayeredPlan};
+use crate::{
+    graph::PrerequisiteGraph, hash::content_hash, parsing::guid::Guid, schedule::OfferingCalendar,
+    Course, Program,
+};
+
+/// Seeds of the shuffled orders of courses tried by [optimize]
+const SHUFFLES: u64 = 4;
+
+/// What makes a plan better than another
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Objective {
+    /// Fewest terms, then the most even credits
+    #[default]
+    MinTerms,
+    /// Most even credits between terms, then the fewest terms
+    BalancedLoad,
+    /// Fewest terms with more heavy courses than [max_heavy](OptimizeOptions::max_heavy), then
+    /// the fewest terms
+    FewHeavyTerms,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizeOptions {
+    pub objective: Objective,
+    /// Most credits taken in a term. A course worth more is planned alone.
+    pub max_credits: u32,
+    /// Most heavy courses a term should have
+    pub max_heavy: usize,
+    /// Minimum credits of heavy courses
+    pub heavy_credits: u8,
+    /// Number of plans to return
+    pub candidates: usize,
+}
+
+impl Default for OptimizeOptions {
+    fn default() -> Self {
+        Self {
+            objective: Objective::default(),
+            max_credits: 15,
+            max_heavy: 2,
+            heavy_credits: 4,
+            candidates: 3,
+        }
+    }
+}
+
+/// How a plan does on every objective
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PlanScore {
+    /// Whether every course of the program is planned
+    pub feasible: bool,
+    pub terms: usize,
+    /// Credits of the heaviest term minus credits of the lightest one
+    pub credit_spread: u32,
+    /// Terms with more than [max_heavy](OptimizeOptions::max_heavy) heavy courses
+    pub heavy_terms: usize,
+}
+
+impl PlanScore {
+    fn new(plan: &LayeredPlan, options: &OptimizeOptions, program: &Program) -> Self {
+        let credits = plan.terms.iter().map(|term| term.credits);
+        let heavy_guids: HashSet<Guid> = program
+            .iter_courses()
+            .filter(|course| course.credits.0 >= options.heavy_credits)
+            .map(|course| course.guid)
+            .collect();
+
+        Self {
+            feasible: plan.is_feasible(),
+            terms: plan.terms.len(),
+            credit_spread: credits.clone().max().unwrap_or(0) - credits.min().unwrap_or(0),
+            heavy_terms: plan
+                .terms
+                .iter()
+                .filter(|term| {
+                    term.courses
+                        .iter()
+                        .filter(|course| heavy_guids.contains(&course.guid))
+                        .count()
+                        > options.max_heavy
+                })
+                .count(),
+        }
+    }
+
+    /// Lower is better
+    fn rank(&self, objective: Objective) -> (bool, usize, usize, usize) {
+        let infeasible = !self.feasible;
+        let spread = self.credit_spread as usize;
+        match objective {
+            Objective::MinTerms => (infeasible, self.terms, spread, self.heavy_terms),
+            Objective::BalancedLoad => (infeasible, spread, self.terms, self.heavy_terms),
+            Objective::FewHeavyTerms => (infeasible, self.heavy_terms, self.terms, spread),
+        }
+    }
+}
+
+/// A plan found by [optimize], with its score
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RankedPlan {
+    pub plan: LayeredPlan,
+    pub score: PlanScore,
+}
+
+/// Up to [candidates](OptimizeOptions::candidates) distinct plans of the `program`, best first
+/// by the [objective](OptimizeOptions::objective). Ties keep the order the plans were found in,
+/// starting with the plan of [layered_plan](super::layered_plan).
+pub fn optimize(
+    program: &Program,
+    graph: &PrerequisiteGraph,
+    calendar: Option<&OfferingCalendar>,
+    options: &OptimizeOptions,
+) -> Vec<RankedPlan> {
+    let first = fill_terms(
+        program,
+        graph,
+        calendar,
+        &Filling {
+            max_credits: options.max_credits,
+            max_heavy: None,
+            tie_break: &|_| 0,
+        },
+    );
+
+    // Caps splitting the credits of the program evenly over as many terms as the first plan, or
+    // one more
+    let total: u32 = first.terms.iter().map(|term| term.credits).sum();
+    let mut caps = vec![options.max_credits];
+    for terms in [first.terms.len(), first.terms.len() + 1] {
+        let cap = total.div_ceil(terms.max(1) as u32).min(options.max_credits);
+        if cap > 0 && !caps.contains(&cap) {
+            caps.push(cap);
+        }
+    }
+
+    let heaviest_first = |course: &Course| u64::from(u8::MAX - course.credits.0);
+    let lightest_first = |course: &Course| u64::from(course.credits.0);
+    let shuffles: Vec<_> = (0..SHUFFLES)
+        .map(|seed| move |course: &Course| content_hash(&(seed, course.guid.to_string())))
+        .collect();
+    let mut tie_breaks: Vec<&dyn Fn(&Course) -> u64> =
+        vec![&|_| 0, &heaviest_first, &lightest_first];
+    tie_breaks.extend(
+        shuffles
+            .iter()
+            .map(|shuffle| shuffle as &dyn Fn(&Course) -> u64),
+    );
+
+    let heavy_limits = [None, Some((options.max_heavy, options.heavy_credits))];
+
+    let mut seen = HashSet::new();
+    let mut plans = vec![];
+    for max_credits in caps {
+        for max_heavy in heavy_limits {
+            for tie_break in &tie_breaks {
+                let filling = Filling {
+                    max_credits,
+                    max_heavy,
+                    tie_break: *tie_break,
+                };
+                let plan = fill_terms(program, graph, calendar, &filling);
+
+                let layout: Vec<Vec<Guid>> = plan
+                    .terms
+                    .iter()
+                    .map(|term| term.courses.iter().map(|course| course.guid).collect())
+                    .collect();
+                if seen.insert(layout) {
+                    let score = PlanScore::new(&plan, options, program);
+                    plans.push(RankedPlan { plan, score });
+                }
+            }
+        }
+    }
+
+    plans.sort_by_key(|ranked| ranked.score.rank(options.objective));
+    plans.truncate(options.candidates);
+
+    plans
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{catalog::Catalog, planner::layered_plan};
+
+    fn cs_major() -> (Program, PrerequisiteGraph) {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+        let program = catalog
+            .programs
+            .into_iter()
+            .max_by_key(|program| program.iter_courses().count())
+            .unwrap();
+
+        (program, graph)
+    }
+
+    #[test]
+    fn candidates_are_ranked_by_the_objective() {
+        let (program, graph) = cs_major();
+
+        for objective in [
+            Objective::MinTerms,
+            Objective::BalancedLoad,
+            Objective::FewHeavyTerms,
+        ] {
+            let options = OptimizeOptions {
+                objective,
+                candidates: 5,
+                ..Default::default()
+            };
+            let candidates = optimize(&program, &graph, None, &options);
+
+            assert!(!candidates.is_empty() && candidates.len() <= 5);
+            assert!(candidates
+                .windows(2)
+                .all(|pair| pair[0].score.rank(objective) <= pair[1].score.rank(objective)));
+            assert!(candidates.iter().all(|ranked| ranked
+                .plan
+                .terms
+                .iter()
+                .all(|term| term.credits <= 15 || term.courses.len() == 1)));
+        }
+    }
+
+    #[test]
+    fn balanced_plans_are_at_least_as_even_as_the_layered_plan() {
+        let (program, graph) = cs_major();
+        let options = OptimizeOptions::default();
+        let layered = PlanScore::new(
+            &layered_plan(&program, &graph, 15, None),
+            &options,
+            &program,
+        );
+
+        let min_terms = optimize(&program, &graph, None, &options);
+        assert!(min_terms[0].score.terms <= layered.terms);
+
+        let balanced = optimize(
+            &program,
+            &graph,
+            None,
+            &OptimizeOptions {
+                objective: Objective::BalancedLoad,
+                ..options
+            },
+        );
+        assert!(balanced[0].score.credit_spread <= layered.credit_spread);
+
+        let plans: HashSet<_> = balanced
+            .iter()
+            .map(|ranked| format!("{:?}", ranked.plan.terms))
+            .collect();
+        assert_eq!(plans.len(), balanced.len());
+    }
+}
@@ -0,0 +1,378 @@
+//! Everything a student must take before a single course, as a tree rooted at that course.
+//!
+//! The tree follows the structured `prerequisite` of every [CourseDetails], the same edges as
+//! [PrerequisiteGraph]. The catalog only structures a single prerequisite per course, which is
+//! always required, so every branch of the tree must be taken. Alternatives such as "CSC 115 or
+//! CSC 105" only exist in the prose of the `prerequisite_narrative`, which is kept on every node
+//! for people to read.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::catalog::Catalog;
+//! let (catalog, _errors) = Catalog::parse_file("../data/courses.json");
+//! let guid = catalog.courses[0].guid;
+//!
+//! let tree = catalog.prerequisite_tree(&guid, 3).unwrap();
+//!
+//! assert!(tree.to_dot().starts_with("digraph"));
+//! assert!(tree.to_mermaid().starts_with("flowchart BT"));
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use serde::Serialize;
+
+use crate::{catalog::Catalog, graph::PrerequisiteGraph, parsing::guid::Guid, CourseDetails};
+
+/// A course and, recursively, the prerequisites that must be taken before it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrerequisiteTree {
+    pub guid: Guid,
+    /// Subject code and number of the course. Ex: "CSC 125". The GUID of the course when the
+    /// catalog doesn't have it.
+    pub label: String,
+    pub name: Option<String>,
+    pub prerequisite_narrative: Option<String>,
+    pub prerequisites: Vec<PrerequisiteTree>,
+    /// Whether the course has prerequisites that were left out, either because the tree reached
+    /// its maximum depth or because the prerequisite closes a cycle back to one of the ancestors
+    /// of the course
+    pub truncated: bool,
+}
+
+impl Catalog {
+    /// The tree of prerequisites of the course with the `guid`, following at most `depth` levels
+    /// of prerequisites. A `depth` of 0 gives the course on its own.
+    ///
+    /// Returns `None` if the catalog doesn't have the course. Prerequisites missing from the
+    /// catalog are still part of the tree, labeled with their GUID.
+    pub fn prerequisite_tree(&self, guid: &Guid, depth: u32) -> Option<PrerequisiteTree> {
+        let courses: HashMap<Guid, &CourseDetails> = self
+            .courses
+            .iter()
+            .map(|course| (course.guid, course))
+            .collect();
+        if !courses.contains_key(guid) {
+            return None;
+        }
+
+        let graph = PrerequisiteGraph::from_course_details(&self.courses);
+
+        Some(PrerequisiteTree::build(
+            *guid,
+            depth,
+            &courses,
+            &graph,
+            &mut HashSet::new(),
+        ))
+    }
+
+    /// The courses of the catalog that have the course with the `guid` as a direct prerequisite,
+    /// in catalog order, answering "what does taking this course unlock?".
+    ///
+    /// Builds a [PrerequisiteGraph] for the query, so build one with
+    /// [PrerequisiteGraph::from_course_details] and use [PrerequisiteGraph::unlocked_by] instead
+    /// when asking about many courses.
+    pub fn unlocked_by(&self, guid: &Guid) -> Vec<&CourseDetails> {
+        let graph = PrerequisiteGraph::from_course_details(&self.courses);
+        let courses: HashMap<Guid, &CourseDetails> = self
+            .courses
+            .iter()
+            .map(|course| (course.guid, course))
+            .collect();
+
+        graph
+            .unlocked_by(guid)
+            .iter()
+            .filter_map(|guid| courses.get(guid).copied())
+            .collect()
+    }
+}
+
+impl PrerequisiteTree {
+    fn build(
+        guid: Guid,
+        depth: u32,
+        courses: &HashMap<Guid, &CourseDetails>,
+        graph: &PrerequisiteGraph,
+        ancestors: &mut HashSet<Guid>,
+    ) -> Self {
+        let course = courses.get(&guid);
+        let mut tree = Self {
+            guid,
+            label: course.map_or_else(
+                || guid.to_string(),
+                |course| format!("{} {}", course.subject_code, course.number),
+            ),
+            name: course.map(|course| course.name.clone()),
+            prerequisite_narrative: course.and_then(|course| course.prerequisite_narrative.clone()),
+            prerequisites: vec![],
+            truncated: false,
+        };
+
+        ancestors.insert(guid);
+        for prerequisite in graph.prerequisites_of(&guid) {
+            if depth == 0 || ancestors.contains(prerequisite) {
+                tree.truncated = true;
+                continue;
+            }
+
+            tree.prerequisites.push(Self::build(
+                *prerequisite,
+                depth - 1,
+                courses,
+                graph,
+                ancestors,
+            ));
+        }
+        ancestors.remove(&guid);
+
+        tree
+    }
+
+    /// The course and every prerequisite in the tree, each listed once, in depth-first order
+    pub fn courses(&self) -> Vec<&PrerequisiteTree> {
+        let mut seen = HashSet::new();
+        let mut courses = vec![];
+        self.collect(&mut seen, &mut courses);
+
+        courses
+    }
+
+    fn collect<'a>(&'a self, seen: &mut HashSet<Guid>, courses: &mut Vec<&'a PrerequisiteTree>) {
+        if seen.insert(self.guid) {
+            courses.push(self);
+        }
+
+        for prerequisite in &self.prerequisites {
+            prerequisite.collect(seen, courses);
+        }
+    }
+
+    /// Every edge from a prerequisite to the course requiring it, each listed once
+    pub fn edges(&self) -> Vec<(Guid, Guid)> {
+        let mut seen = HashSet::new();
+        let mut edges = vec![];
+        let mut stack = vec![self];
+
+        while let Some(tree) = stack.pop() {
+            for prerequisite in &tree.prerequisites {
+                if seen.insert((prerequisite.guid, tree.guid)) {
+                    edges.push((prerequisite.guid, tree.guid));
+                }
+                stack.push(prerequisite);
+            }
+        }
+
+        edges
+    }
+
+    /// Renders the tree as a Graphviz DOT digraph with edges from prerequisites to the courses
+    /// requiring them. Courses with prerequisites left out are drawn dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!("digraph {} {{\n", dot_string(&self.label));
+        dot.push_str("    rankdir=BT;\n");
+
+        for course in self.courses() {
+            let _ = writeln!(
+                dot,
+                "    {} [label={}, tooltip={}{}];",
+                dot_string(&course.guid.to_string()),
+                dot_string(&course.label),
+                dot_string(course.name.as_deref().unwrap_or_default()),
+                if course.truncated {
+                    ", style=dashed"
+                } else {
+                    ""
+                },
+            );
+        }
+
+        for (from, to) in self.edges() {
+            let _ = writeln!(
+                dot,
+                "    {} -> {};",
+                dot_string(&from.to_string()),
+                dot_string(&to.to_string()),
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the tree as a Mermaid flowchart with edges from prerequisites to the courses
+    /// requiring them. Courses with prerequisites left out are given the `truncated` class.
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart BT\n");
+
+        for course in self.courses() {
+            let label = match &course.name {
+                Some(name) => format!("{}<br/>{}", course.label, name),
+                None => course.label.clone(),
+            };
+            let _ = writeln!(
+                mermaid,
+                "    {}[\"{}\"]",
+                mermaid_id(&course.guid),
+                mermaid_string(&label)
+            );
+        }
+
+        for (from, to) in self.edges() {
+            let _ = writeln!(mermaid, "    {} --> {}", mermaid_id(&from), mermaid_id(&to));
+        }
+
+        let truncated: Vec<String> = self
+            .courses()
+            .into_iter()
+            .filter(|course| course.truncated)
+            .map(|course| mermaid_id(&course.guid))
+            .collect();
+        if !truncated.is_empty() {
+            mermaid.push_str("    classDef truncated stroke-dasharray: 5 5\n");
+            let _ = writeln!(mermaid, "    class {} truncated", truncated.join(","));
+        }
+
+        mermaid
+    }
+}
+
+/// Quoted DOT string literal
+fn dot_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Mermaid node IDs can't contain hyphens, so the GUID is written without them
+fn mermaid_id(guid: &Guid) -> String {
+    format!("c{}", guid.to_string().replace('-', ""))
+}
+
+/// Mermaid labels are quoted and escape quotes with HTML entity codes
+fn mermaid_string(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn course(guid: &str, number: &str, prerequisite: Option<&str>) -> CourseDetails {
+        CourseDetails {
+            url: String::new(),
+            guid: Guid::try_from(guid).unwrap(),
+            path: String::new(),
+            subject_code: "CSC".into(),
+            subject_name: None,
+            number: number.to_owned(),
+            name: format!("Course {number}"),
+            credits_min: 3,
+            credits_max: None,
+            description: String::new(),
+            prerequisite_narrative: prerequisite.map(|_| "Prerequisite: see catalog".to_owned()),
+            prerequisite: prerequisite.map(|guid| Guid::try_from(guid).unwrap()),
+            corequisite_narrative: None,
+            corequisite: None,
+            extra: Default::default(),
+        }
+    }
+
+    const A: &str = "860AF9C9-EAD9-45AC-AA92-BAF352C5288C";
+    const B: &str = "13A1385C-81AC-493D-ACE8-AA8AB37D2C81";
+    const C: &str = "BF3CF399-6D63-43AA-8064-2A86789B5A4E";
+
+    fn chain() -> Catalog {
+        Catalog {
+            courses: vec![
+                course(A, "115", None),
+                course(B, "215", Some(A)),
+                course(C, "315", Some(B)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tree_follows_prerequisites_up_to_depth() {
+        let catalog = chain();
+        let c = Guid::try_from(C).unwrap();
+
+        let tree = catalog.prerequisite_tree(&c, 5).unwrap();
+        assert_eq!(tree.label, "CSC 315");
+        assert_eq!(tree.prerequisites[0].label, "CSC 215");
+        assert_eq!(tree.prerequisites[0].prerequisites[0].label, "CSC 115");
+        assert!(tree.courses().iter().all(|course| !course.truncated));
+
+        let tree = catalog.prerequisite_tree(&c, 1).unwrap();
+        assert_eq!(tree.courses().len(), 2);
+        assert!(tree.prerequisites[0].truncated);
+
+        let tree = catalog.prerequisite_tree(&c, 0).unwrap();
+        assert!(tree.prerequisites.is_empty());
+        assert!(tree.truncated);
+
+        assert!(catalog
+            .prerequisite_tree(
+                &Guid::try_from("F17E3997-3E59-4B70-B662-E5AB1A40ADAF").unwrap(),
+                1
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn courses_unlocked_by_a_course() {
+        let mut catalog = chain();
+        catalog.courses.push(course(
+            "F17E3997-3E59-4B70-B662-E5AB1A40ADAF",
+            "220",
+            Some(A),
+        ));
+        let numbers = |guid: &str| {
+            catalog
+                .unlocked_by(&Guid::try_from(guid).unwrap())
+                .iter()
+                .map(|course| course.number.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(numbers(A), ["215", "220"]);
+        assert_eq!(numbers(B), ["315"]);
+        assert!(numbers(C).is_empty());
+    }
+
+    #[test]
+    fn cycles_are_cut() {
+        let mut catalog = chain();
+        catalog.courses[0].prerequisite = Some(Guid::try_from(C).unwrap());
+
+        let tree = catalog
+            .prerequisite_tree(&Guid::try_from(C).unwrap(), 10)
+            .unwrap();
+
+        assert_eq!(tree.courses().len(), 3);
+        assert!(tree.prerequisites[0].prerequisites[0].truncated);
+    }
+
+    #[test]
+    fn renderers_list_every_course_and_edge() {
+        let catalog = chain();
+        let tree = catalog
+            .prerequisite_tree(&Guid::try_from(C).unwrap(), 1)
+            .unwrap();
+
+        let dot = tree.to_dot();
+        assert!(dot.contains(&format!("\"{B}\" -> \"{C}\";")));
+        assert!(dot.contains("[label=\"CSC 215\", tooltip=\"Course 215\", style=dashed];"));
+        assert_eq!(dot.matches(" -> ").count(), 1);
+
+        let mermaid = tree.to_mermaid();
+        let b = mermaid_id(&Guid::try_from(B).unwrap());
+        let c = mermaid_id(&Guid::try_from(C).unwrap());
+        assert!(mermaid.contains(&format!("    {b} --> {c}\n")));
+        assert!(mermaid.contains(&format!("    {b}[\"CSC 215<br/>Course 215\"]\n")));
+        assert!(mermaid.ends_with(&format!("    class {b} truncated\n")));
+    }
+}
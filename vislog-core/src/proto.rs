@@ -0,0 +1,682 @@
+//! Protobuf encoding of the data model, following the schema in `proto/vislog.proto`.
+//!
+//! [pb] holds the types generated by prost. Converting from the data model never fails, while
+//! converting back fails for messages that leave out required fields or hold invalid values, since
+//! protobuf can't express either constraint.
+//!
+//! # Example
+//! ```
+//! # use prost::Message;
+//! # use vislog_core::{catalog::Catalog, proto::pb};
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//!
+//! let bytes = pb::Catalog::from(&catalog).encode_to_vec();
+//! let decoded = pb::Catalog::decode(bytes.as_slice()).unwrap();
+//!
+//! assert_eq!(Catalog::try_from(decoded).unwrap(), catalog);
+//! ```
+
+use thiserror::Error;
+
+use crate::{
+    catalog::Catalog,
+    constraints::{Constraints, Gpa, GradeParsingError},
+    electives::ElectiveHours,
+    extensions::Extensions,
+    parsing::guid::{GUIDParsingError, Guid},
+    references::CourseRef,
+    shared::SharedModule,
+    symbol::Symbol,
+    Course, CourseDetails, CourseEntries, CourseEntry, Label, Program, Requirement,
+    RequirementModule, Requirements,
+};
+
+/// Types generated from `proto/vislog.proto`
+#[allow(clippy::all)]
+pub mod pb {
+    include!(concat!(env!("OUT_DIR"), "/vislog.v1.rs"));
+}
+
+#[derive(Debug, Error)]
+pub enum ProtoError {
+    #[error("message {message} is missing field {field}")]
+    MissingField {
+        message: &'static str,
+        field: &'static str,
+    },
+    #[error("invalid guid {guid:?}: {source}")]
+    Guid {
+        guid: String,
+        #[source]
+        source: GUIDParsingError,
+    },
+    #[error("{credits} credits is more than the maximum of {}", u8::MAX)]
+    Credits { credits: u32 },
+    #[error(transparent)]
+    Grade(#[from] GradeParsingError),
+    #[error("{gpa} hundredths of a point is more than the maximum GPA")]
+    Gpa { gpa: u32 },
+    #[error("failed to parse the JSON of an unimplemented requirement module: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("failed to parse the JSON of unknown fields: {0}")]
+    Extensions(#[source] serde_json::Error),
+}
+
+fn missing(message: &'static str, field: &'static str) -> ProtoError {
+    ProtoError::MissingField { message, field }
+}
+
+fn parse_guid(guid: String) -> Result<Guid, ProtoError> {
+    Guid::try_from(guid.as_str()).map_err(|source| ProtoError::Guid { guid, source })
+}
+
+fn parse_extensions(json: String) -> Result<Extensions, ProtoError> {
+    Extensions::from_json(&json).map_err(ProtoError::Extensions)
+}
+
+fn parse_credits(credits: u32) -> Result<u8, ProtoError> {
+    u8::try_from(credits).map_err(|_| ProtoError::Credits { credits })
+}
+
+fn credits_from(
+    credits: Option<pb::Credits>,
+    message: &'static str,
+) -> Result<(u8, Option<u8>), ProtoError> {
+    let credits = credits.ok_or_else(|| missing(message, "credits"))?;
+
+    Ok((
+        parse_credits(credits.min)?,
+        credits.max.map(parse_credits).transpose()?,
+    ))
+}
+
+impl From<(u8, Option<u8>)> for pb::Credits {
+    fn from((min, max): (u8, Option<u8>)) -> Self {
+        Self {
+            min: min.into(),
+            max: max.map(Into::into),
+        }
+    }
+}
+
+impl From<&Catalog> for pb::Catalog {
+    fn from(catalog: &Catalog) -> Self {
+        Self {
+            programs: catalog.programs.iter().map(Into::into).collect(),
+            courses: catalog.courses.iter().map(Into::into).collect(),
+            shared_modules: catalog
+                .shared_modules
+                .iter()
+                .map(|shared| pb::SharedModule {
+                    guid: shared.guid.to_string(),
+                    module: Some((&shared.module).into()),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<pb::Catalog> for Catalog {
+    type Error = ProtoError;
+
+    fn try_from(catalog: pb::Catalog) -> Result<Self, Self::Error> {
+        let mut decoded = Self {
+            programs: catalog
+                .programs
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            courses: catalog
+                .courses
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            shared_modules: catalog
+                .shared_modules
+                .into_iter()
+                .map(|shared| {
+                    Ok(SharedModule {
+                        guid: parse_guid(shared.guid)?,
+                        module: shared
+                            .module
+                            .ok_or_else(|| missing("SharedModule", "module"))?
+                            .try_into()?,
+                    })
+                })
+                .collect::<Result<_, ProtoError>>()?,
+        };
+        decoded.intern_courses();
+
+        Ok(decoded)
+    }
+}
+
+impl From<&Program> for pb::Program {
+    fn from(program: &Program) -> Self {
+        Self {
+            url: program.url.clone(),
+            guid: program.guid.to_string(),
+            title: program.title.clone(),
+            content: program.content.clone(),
+            bottom_content: program.bottom_content.clone(),
+            requirements: program.requirements.as_ref().map(Into::into),
+            extra: program.extra.to_json(),
+        }
+    }
+}
+
+impl TryFrom<pb::Program> for Program {
+    type Error = ProtoError;
+
+    fn try_from(program: pb::Program) -> Result<Self, Self::Error> {
+        Ok(Self {
+            url: program.url,
+            guid: parse_guid(program.guid)?,
+            title: program.title,
+            content: program.content,
+            bottom_content: program.bottom_content,
+            requirements: program.requirements.map(TryInto::try_into).transpose()?,
+            extra: parse_extensions(program.extra)?,
+        })
+    }
+}
+
+impl From<&Requirements> for pb::Requirements {
+    fn from(requirements: &Requirements) -> Self {
+        use pb::requirements::Kind;
+
+        let kind = match requirements {
+            Requirements::Single(module) => Kind::Single(module.into()),
+            Requirements::Many(modules) => Kind::Many(pb::RequirementModules {
+                modules: modules.iter().map(Into::into).collect(),
+            }),
+            Requirements::SelectTrack => Kind::SelectTrack(pb::SelectTrack {}),
+        };
+
+        Self { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<pb::Requirements> for Requirements {
+    type Error = ProtoError;
+
+    fn try_from(requirements: pb::Requirements) -> Result<Self, Self::Error> {
+        use pb::requirements::Kind;
+
+        let requirements = match requirements.kind {
+            Some(Kind::Single(module)) => Requirements::Single(module.try_into()?),
+            Some(Kind::Many(modules)) => Requirements::Many(
+                modules
+                    .modules
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_, _>>()?,
+            ),
+            Some(Kind::SelectTrack(_)) => Requirements::SelectTrack,
+            None => return Err(missing("Requirements", "kind")),
+        };
+
+        Ok(requirements)
+    }
+}
+
+impl From<&RequirementModule> for pb::RequirementModule {
+    fn from(module: &RequirementModule) -> Self {
+        use pb::requirement_module::Kind;
+
+        let kind = match module {
+            RequirementModule::SingleBasicRequirement { title, requirement } => {
+                Kind::SingleBasicRequirement(pb::SingleBasicRequirement {
+                    title: title.clone(),
+                    requirement: Some(requirement.into()),
+                })
+            }
+            RequirementModule::BasicRequirements {
+                title,
+                requirements,
+            } => Kind::BasicRequirements(pb::BasicRequirements {
+                title: title.clone(),
+                requirements: requirements.iter().map(Into::into).collect(),
+            }),
+            RequirementModule::SelectOneEmphasis { emphases } => {
+                Kind::SelectOneEmphasis(pb::SelectOneEmphasis {
+                    emphases: emphases.iter().map(Into::into).collect(),
+                })
+            }
+            RequirementModule::Label { title } => Kind::Label(pb::ModuleLabel {
+                title: title.clone(),
+            }),
+            RequirementModule::Unimplemented(value) => Kind::UnimplementedJson(value.to_string()),
+            RequirementModule::Reference(guid) => Kind::Reference(guid.to_string()),
+        };
+
+        Self { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<pb::RequirementModule> for RequirementModule {
+    type Error = ProtoError;
+
+    fn try_from(module: pb::RequirementModule) -> Result<Self, Self::Error> {
+        use pb::requirement_module::Kind;
+
+        let requirements = |requirements: Vec<pb::Requirement>| {
+            requirements
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let module = match module.kind {
+            Some(Kind::SingleBasicRequirement(module)) => {
+                RequirementModule::SingleBasicRequirement {
+                    title: module.title,
+                    requirement: module
+                        .requirement
+                        .ok_or_else(|| missing("SingleBasicRequirement", "requirement"))?
+                        .try_into()?,
+                }
+            }
+            Some(Kind::BasicRequirements(module)) => RequirementModule::BasicRequirements {
+                title: module.title,
+                requirements: requirements(module.requirements)?,
+            },
+            Some(Kind::SelectOneEmphasis(module)) => RequirementModule::SelectOneEmphasis {
+                emphases: requirements(module.emphases)?,
+            },
+            Some(Kind::Label(label)) => RequirementModule::Label { title: label.title },
+            Some(Kind::UnimplementedJson(json)) => {
+                RequirementModule::Unimplemented(serde_json::from_str(&json)?)
+            }
+            Some(Kind::Reference(guid)) => RequirementModule::Reference(parse_guid(guid)?),
+            None => return Err(missing("RequirementModule", "kind")),
+        };
+
+        Ok(module)
+    }
+}
+
+impl From<Constraints> for Option<pb::Constraints> {
+    fn from(constraints: Constraints) -> Self {
+        (!constraints.is_empty()).then(|| pb::Constraints {
+            min_grade: constraints.min_grade.map(|grade| grade.to_string()),
+            min_gpa: constraints.min_gpa.map(|gpa| gpa.hundredths() as u32),
+        })
+    }
+}
+
+/// Messages without constraints are requirements without constraints
+fn constraints_from(constraints: Option<pb::Constraints>) -> Result<Constraints, ProtoError> {
+    let Some(constraints) = constraints else {
+        return Ok(Constraints::default());
+    };
+
+    Ok(Constraints {
+        min_grade: constraints
+            .min_grade
+            .map(|grade| grade.parse())
+            .transpose()?,
+        min_gpa: constraints
+            .min_gpa
+            .map(|gpa| {
+                u16::try_from(gpa)
+                    .map(Gpa::from_hundredths)
+                    .map_err(|_| ProtoError::Gpa { gpa })
+            })
+            .transpose()?,
+    })
+}
+
+impl From<&Requirement> for pb::Requirement {
+    fn from(requirement: &Requirement) -> Self {
+        use pb::requirement::Kind;
+
+        let kind = match requirement {
+            Requirement::Courses {
+                title,
+                courses,
+                constraints,
+                extra,
+            } => Kind::Courses(pb::CoursesRequirement {
+                title: title.clone(),
+                courses: courses.iter().map(Into::into).collect(),
+                constraints: (*constraints).into(),
+                extra: extra.to_json(),
+            }),
+            Requirement::SelectFromCourses {
+                title,
+                courses,
+                constraints,
+                extra,
+            } => Kind::SelectFromCourses(pb::SelectFromCourses {
+                title: title.clone(),
+                courses: courses.as_ref().map(Into::into),
+                constraints: (*constraints).into(),
+                extra: extra.to_json(),
+            }),
+            Requirement::Label {
+                title,
+                req_narrative,
+                constraints,
+                course_refs,
+                extra,
+            } => Kind::Label(pb::RequirementLabel {
+                title: title.clone(),
+                req_narrative: req_narrative.clone(),
+                constraints: (*constraints).into(),
+                course_refs: course_refs.iter().map(Into::into).collect(),
+                extra: extra.to_json(),
+            }),
+            Requirement::ElectivePool {
+                title,
+                req_narrative,
+                hours,
+                constraints,
+                extra,
+            } => Kind::ElectivePool(pb::ElectivePool {
+                title: title.clone(),
+                req_narrative: req_narrative.clone(),
+                amount: Some(match *hours {
+                    ElectiveHours::Hours(hours) => pb::elective_pool::Amount::Hours(hours),
+                    ElectiveHours::ToTotal(total) => pb::elective_pool::Amount::ToTotal(total),
+                }),
+                constraints: (*constraints).into(),
+                extra: extra.to_json(),
+            }),
+        };
+
+        Self { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<pb::Requirement> for Requirement {
+    type Error = ProtoError;
+
+    fn try_from(requirement: pb::Requirement) -> Result<Self, Self::Error> {
+        use pb::requirement::Kind;
+
+        let requirement = match requirement.kind {
+            Some(Kind::Courses(requirement)) => Requirement::Courses {
+                title: requirement.title,
+                courses: pb::CourseEntries {
+                    entries: requirement.courses,
+                }
+                .try_into()?,
+                constraints: constraints_from(requirement.constraints)?,
+                extra: parse_extensions(requirement.extra)?,
+            },
+            Some(Kind::SelectFromCourses(requirement)) => Requirement::SelectFromCourses {
+                title: requirement.title,
+                courses: requirement.courses.map(TryInto::try_into).transpose()?,
+                constraints: constraints_from(requirement.constraints)?,
+                extra: parse_extensions(requirement.extra)?,
+            },
+            Some(Kind::Label(label)) => Requirement::Label {
+                title: label.title,
+                req_narrative: label.req_narrative,
+                constraints: constraints_from(label.constraints)?,
+                course_refs: label
+                    .course_refs
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_, _>>()?,
+                extra: parse_extensions(label.extra)?,
+            },
+            Some(Kind::ElectivePool(pool)) => Requirement::ElectivePool {
+                title: pool.title,
+                req_narrative: pool.req_narrative,
+                hours: match pool.amount {
+                    Some(pb::elective_pool::Amount::Hours(hours)) => ElectiveHours::Hours(hours),
+                    Some(pb::elective_pool::Amount::ToTotal(total)) => {
+                        ElectiveHours::ToTotal(total)
+                    }
+                    None => return Err(missing("ElectivePool", "amount")),
+                },
+                constraints: constraints_from(pool.constraints)?,
+                extra: parse_extensions(pool.extra)?,
+            },
+            None => return Err(missing("Requirement", "kind")),
+        };
+
+        Ok(requirement)
+    }
+}
+
+impl From<&CourseRef> for pb::CourseRef {
+    fn from(course_ref: &CourseRef) -> Self {
+        Self {
+            subject_code: course_ref.subject_code.clone(),
+            number: course_ref.number.clone(),
+            guid: course_ref.guid.map(|guid| guid.to_string()),
+        }
+    }
+}
+
+impl TryFrom<pb::CourseRef> for CourseRef {
+    type Error = ProtoError;
+
+    fn try_from(course_ref: pb::CourseRef) -> Result<Self, Self::Error> {
+        Ok(CourseRef {
+            subject_code: course_ref.subject_code,
+            number: course_ref.number,
+            guid: course_ref.guid.map(parse_guid).transpose()?,
+        })
+    }
+}
+
+impl From<&CourseEntries> for pb::CourseEntries {
+    fn from(entries: &CourseEntries) -> Self {
+        Self {
+            entries: entries.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<pb::CourseEntries> for CourseEntries {
+    type Error = ProtoError;
+
+    fn try_from(entries: pb::CourseEntries) -> Result<Self, Self::Error> {
+        entries.entries.into_iter().map(TryInto::try_into).collect()
+    }
+}
+
+impl From<&CourseEntry> for pb::CourseEntry {
+    fn from(entry: &CourseEntry) -> Self {
+        use pb::course_entry::Kind;
+
+        let kind = match entry {
+            CourseEntry::And(entries) => Kind::And(entries.into()),
+            CourseEntry::Or(entries) => Kind::Or(entries.into()),
+            CourseEntry::Label(label) => Kind::Label(label.into()),
+            CourseEntry::Course(course) => Kind::Course(course.as_ref().into()),
+        };
+
+        Self { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<pb::CourseEntry> for CourseEntry {
+    type Error = ProtoError;
+
+    fn try_from(entry: pb::CourseEntry) -> Result<Self, Self::Error> {
+        use pb::course_entry::Kind;
+
+        let entry = match entry.kind {
+            Some(Kind::And(entries)) => CourseEntry::And(entries.try_into()?),
+            Some(Kind::Or(entries)) => CourseEntry::Or(entries.try_into()?),
+            Some(Kind::Label(label)) => CourseEntry::Label(label.try_into()?),
+            Some(Kind::Course(course)) => Course::try_from(course)?.into(),
+            None => return Err(missing("CourseEntry", "kind")),
+        };
+
+        Ok(entry)
+    }
+}
+
+impl From<&Course> for pb::Course {
+    fn from(course: &Course) -> Self {
+        Self {
+            url: course.url.clone(),
+            path: course.path.clone(),
+            guid: course.guid.to_string(),
+            name: course.name.clone(),
+            number: course.number.clone(),
+            subject_name: course.subject_name.as_deref().map(str::to_owned),
+            subject_code: course.subject_code.to_string(),
+            credits: Some(course.credits.into()),
+            extra: course.extra.to_json(),
+        }
+    }
+}
+
+impl TryFrom<pb::Course> for Course {
+    type Error = ProtoError;
+
+    fn try_from(course: pb::Course) -> Result<Self, Self::Error> {
+        Ok(Self {
+            url: course.url,
+            path: course.path,
+            guid: parse_guid(course.guid)?,
+            name: course.name,
+            number: course.number,
+            subject_name: course.subject_name.map(Symbol::from),
+            subject_code: course.subject_code.into(),
+            credits: credits_from(course.credits, "Course")?,
+            extra: parse_extensions(course.extra)?,
+            details: None,
+        })
+    }
+}
+
+impl From<&Label> for pb::Label {
+    fn from(label: &Label) -> Self {
+        Self {
+            url: label.url.clone(),
+            guid: label.guid.to_string(),
+            name: label.name.clone(),
+            number: label.number.clone(),
+            subject_code: label.subject_code.as_deref().map(str::to_owned),
+            credits: Some(label.credits.into()),
+        }
+    }
+}
+
+impl TryFrom<pb::Label> for Label {
+    type Error = ProtoError;
+
+    fn try_from(label: pb::Label) -> Result<Self, Self::Error> {
+        Ok(Self {
+            url: label.url,
+            guid: parse_guid(label.guid)?,
+            name: label.name,
+            number: label.number,
+            subject_code: label.subject_code.map(Symbol::from),
+            credits: credits_from(label.credits, "Label")?,
+        })
+    }
+}
+
+impl From<&CourseDetails> for pb::CourseDetails {
+    fn from(course: &CourseDetails) -> Self {
+        Self {
+            url: course.url.clone(),
+            guid: course.guid.to_string(),
+            path: course.path.clone(),
+            subject_code: course.subject_code.to_string(),
+            subject_name: course.subject_name.as_deref().map(str::to_owned),
+            number: course.number.clone(),
+            name: course.name.clone(),
+            credits: Some((course.credits_min, course.credits_max).into()),
+            description: course.description.clone(),
+            prerequisite_narrative: course.prerequisite_narrative.clone(),
+            prerequisite: course.prerequisite.map(|guid| guid.to_string()),
+            corequisite_narrative: course.corequisite_narrative.clone(),
+            corequisite: course.corequisite.map(|guid| guid.to_string()),
+            extra: course.extra.to_json(),
+        }
+    }
+}
+
+impl TryFrom<pb::CourseDetails> for CourseDetails {
+    type Error = ProtoError;
+
+    fn try_from(course: pb::CourseDetails) -> Result<Self, Self::Error> {
+        let (credits_min, credits_max) = credits_from(course.credits, "CourseDetails")?;
+
+        Ok(Self {
+            url: course.url,
+            guid: parse_guid(course.guid)?,
+            path: course.path,
+            subject_code: course.subject_code.into(),
+            subject_name: course.subject_name.map(Symbol::from),
+            number: course.number,
+            name: course.name,
+            credits_min,
+            credits_max,
+            description: course.description,
+            prerequisite_narrative: course.prerequisite_narrative,
+            prerequisite: course.prerequisite.map(parse_guid).transpose()?,
+            corequisite_narrative: course.corequisite_narrative,
+            corequisite: course.corequisite.map(parse_guid).transpose()?,
+            extra: parse_extensions(course.extra)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use prost::Message;
+
+    use super::*;
+
+    #[test]
+    fn round_trips_the_data_catalog() {
+        let (catalog, _errors) = Catalog::parse_dir("../data").expect("Failed to read data dir");
+
+        let bytes = pb::Catalog::from(&catalog).encode_to_vec();
+        let decoded = pb::Catalog::decode(bytes.as_slice()).unwrap();
+
+        assert_eq!(Catalog::try_from(decoded).unwrap(), catalog);
+
+        let mut shared = catalog;
+        shared.resolve_references();
+        let bytes = pb::Catalog::from(&shared).encode_to_vec();
+        let decoded = pb::Catalog::decode(bytes.as_slice()).unwrap();
+
+        assert_eq!(Catalog::try_from(decoded).unwrap(), shared);
+    }
+
+    #[test]
+    fn error_when_required_fields_are_missing() {
+        let entry = pb::CourseEntry { kind: None };
+        assert!(matches!(
+            CourseEntry::try_from(entry),
+            Err(ProtoError::MissingField {
+                message: "CourseEntry",
+                field: "kind"
+            })
+        ));
+
+        let course = pb::Course {
+            guid: "860AF9C9-EAD9-45AC-AA92-BAF352C5288C".to_owned(),
+            credits: Some(pb::Credits {
+                min: 256,
+                max: None,
+            }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            Course::try_from(course),
+            Err(ProtoError::Credits { credits: 256 })
+        ));
+
+        let program = pb::Program {
+            guid: "not a guid".to_owned(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            Program::try_from(program),
+            Err(ProtoError::Guid { .. })
+        ));
+    }
+}
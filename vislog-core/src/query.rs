@@ -0,0 +1,554 @@
+use std::{
+    borrow::Cow,
+    collections::{hash_map::Entry, HashMap},
+    ops::{Bound, RangeBounds},
+    sync::Arc,
+};
+
+use crate::{
+    catalog::Catalog,
+    code::{CourseCode, CourseCodeError},
+    metadata::{Degree, MetadataMapping, ProgramMetadata},
+    parsing::guid::Guid,
+    tags::TagMapping,
+    Course, CourseDetails, Program,
+};
+
+/// Everything the catalog knows about a single course
+#[derive(Debug, Clone, PartialEq)]
+pub struct CourseMatch<'a> {
+    pub guid: Guid,
+    /// The course as listed in the requirements of a program, if any program lists it
+    pub course: Option<&'a Course>,
+    /// The full details of the course, if the catalog has them
+    pub details: Option<&'a CourseDetails>,
+    /// Every program listing the course in its requirements
+    pub programs: Vec<&'a Program>,
+}
+
+impl<'a> CourseMatch<'a> {
+    pub fn subject_code(&self) -> &'a str {
+        match (self.details, self.course) {
+            (Some(details), _) => &details.subject_code,
+            (None, Some(course)) => &course.subject_code,
+            (None, None) => "",
+        }
+    }
+
+    pub fn number(&self) -> &'a str {
+        match (self.details, self.course) {
+            (Some(details), _) => &details.number,
+            (None, Some(course)) => &course.number,
+            (None, None) => "",
+        }
+    }
+
+    pub fn name(&self) -> Option<&'a str> {
+        match (self.details, self.course) {
+            (Some(details), _) => Some(&details.name),
+            (None, Some(course)) => course.name.as_deref(),
+            (None, None) => None,
+        }
+    }
+
+    pub fn code(&self) -> Result<CourseCode, CourseCodeError> {
+        CourseCode::new(self.subject_code(), self.number())
+    }
+
+    /// Inclusive range of credits that can be earned by the course
+    pub fn credits(&self) -> (u8, u8) {
+        match (self.details, self.course) {
+            (Some(details), _) => (
+                details.credits_min,
+                details.credits_max.unwrap_or(details.credits_min),
+            ),
+            (None, Some(Course { credits, .. })) => (credits.0, credits.1.unwrap_or(credits.0)),
+            (None, None) => (0, 0),
+        }
+    }
+}
+
+/// Reverse index from every course in a [Catalog] to the programs requiring it
+#[derive(Debug, Clone)]
+pub struct CatalogIndex<'a> {
+    courses: Vec<CourseMatch<'a>>,
+    by_guid: HashMap<Guid, usize>,
+}
+
+impl<'a> CatalogIndex<'a> {
+    pub fn new(catalog: &'a Catalog) -> Self {
+        let mut index = CatalogIndex {
+            courses: Vec::with_capacity(catalog.courses.len()),
+            by_guid: HashMap::with_capacity(catalog.courses.len()),
+        };
+
+        for details in &catalog.courses {
+            index.entry(details.guid).details = Some(details);
+        }
+
+        for program in &catalog.programs {
+            for course in program.iter_courses() {
+                let entry = index.entry(course.guid);
+                entry.course.get_or_insert(course);
+
+                // A program can list the same course more than once
+                if !entry
+                    .programs
+                    .last()
+                    .is_some_and(|last| std::ptr::eq(*last, program))
+                {
+                    entry.programs.push(program);
+                }
+            }
+        }
+
+        index
+    }
+
+    fn entry(&mut self, guid: Guid) -> &mut CourseMatch<'a> {
+        let idx = match self.by_guid.entry(guid) {
+            Entry::Occupied(entry) => *entry.get(),
+            Entry::Vacant(entry) => {
+                self.courses.push(CourseMatch {
+                    guid,
+                    course: None,
+                    details: None,
+                    programs: vec![],
+                });
+                *entry.insert(self.courses.len() - 1)
+            }
+        };
+
+        &mut self.courses[idx]
+    }
+
+    pub fn get(&self, guid: &Guid) -> Option<&CourseMatch<'a>> {
+        self.by_guid.get(guid).map(|idx| &self.courses[*idx])
+    }
+
+    /// Programs listing the course with the given `guid` in their requirements
+    pub fn programs_requiring(&self, guid: &Guid) -> &[&'a Program] {
+        self.get(guid)
+            .map(|course| course.programs.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn query(&self) -> CatalogQuery<'_, 'a> {
+        CatalogQuery {
+            index: Cow::Borrowed(self),
+            filters: Filters::default(),
+        }
+    }
+}
+
+impl Catalog {
+    /// Builds a [CatalogIndex] over the catalog. Build the index once and reuse it with
+    /// [CatalogIndex::query] when running many queries.
+    pub fn index(&self) -> CatalogIndex<'_> {
+        CatalogIndex::new(self)
+    }
+
+    /// Finds the courses matching all of the given filters along with the programs requiring them
+    ///
+    /// # Example
+    /// ```
+    /// # use vislog_core::catalog::Catalog;
+    /// let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+    ///
+    /// // Which programs require MAT 211?
+    /// let matches = catalog
+    ///     .query()
+    ///     .subject_code("MAT")
+    ///     .number_range(211..=211)
+    ///     .run();
+    ///
+    /// for course in matches {
+    ///     for program in &course.programs {
+    ///         println!("{}", program.title);
+    ///     }
+    /// }
+    /// ```
+    pub fn query(&self) -> CatalogQuery<'_, '_> {
+        CatalogQuery {
+            index: Cow::Owned(self.index()),
+            filters: Filters::default(),
+        }
+    }
+}
+
+/// Query for courses in a [CatalogIndex]. Every filter that is set must match for a course to be
+/// returned. Filters on the [metadata](crate::metadata) of programs match the courses listed by at
+/// least one program matching all of them.
+#[derive(Debug, Clone)]
+pub struct CatalogQuery<'i, 'a> {
+    index: Cow<'i, CatalogIndex<'a>>,
+    filters: Filters,
+}
+
+#[derive(Debug, Clone)]
+struct Filters {
+    guid: Option<Guid>,
+    subject_code: Option<String>,
+    number_range: (Bound<u16>, Bound<u16>),
+    credit_range: (Bound<u8>, Bound<u8>),
+    /// Lowercased
+    name_contains: Option<String>,
+    degree: Option<Degree>,
+    department: Option<String>,
+    cip_code: Option<String>,
+    metadata: Option<Arc<MetadataMapping>>,
+    with_tags: Vec<String>,
+    tags: Option<Arc<TagMapping>>,
+}
+
+impl Default for Filters {
+    fn default() -> Self {
+        Self {
+            guid: None,
+            subject_code: None,
+            number_range: (Bound::Unbounded, Bound::Unbounded),
+            credit_range: (Bound::Unbounded, Bound::Unbounded),
+            name_contains: None,
+            degree: None,
+            department: None,
+            cip_code: None,
+            metadata: None,
+            with_tags: vec![],
+            tags: None,
+        }
+    }
+}
+
+impl<'i, 'a> CatalogQuery<'i, 'a> {
+    pub fn guid(mut self, guid: Guid) -> Self {
+        self.filters.guid = Some(guid);
+        self
+    }
+
+    /// Case insensitive match on the subject code. Ex: "CSC"
+    pub fn subject_code(mut self, subject_code: impl Into<String>) -> Self {
+        self.filters.subject_code = Some(subject_code.into());
+        self
+    }
+
+    /// Range of the numeric part of the [code](CourseCode::number) of the course. Ex: `300..400`
+    /// for 300 level courses, or `300..` for every course above the 200 level
+    pub fn number_range(mut self, range: impl RangeBounds<u16>) -> Self {
+        self.filters.number_range = (range.start_bound().cloned(), range.end_bound().cloned());
+        self
+    }
+
+    /// Courses with variable credits match if any of their possible credit values is in range
+    pub fn credit_range(mut self, range: impl RangeBounds<u8>) -> Self {
+        self.filters.credit_range = (range.start_bound().cloned(), range.end_bound().cloned());
+        self
+    }
+
+    /// Case insensitive substring match on the name of the course
+    pub fn name_contains(mut self, needle: impl AsRef<str>) -> Self {
+        self.filters.name_contains = Some(needle.as_ref().to_lowercase());
+        self
+    }
+
+    /// Courses of programs leading to the `degree`
+    pub fn degree(mut self, degree: Degree) -> Self {
+        self.filters.degree = Some(degree);
+        self
+    }
+
+    /// Case insensitive match on the department or school offering the program. Ex:
+    /// "Department of Computer Science"
+    pub fn department(mut self, department: impl Into<String>) -> Self {
+        self.filters.department = Some(department.into());
+        self
+    }
+
+    /// Courses of programs with a CIP code starting with `cip_code`, so that "11" matches every
+    /// program in computer and information sciences. Programs only have a CIP code when the
+    /// [metadata](Self::metadata) mapping gives them one.
+    pub fn cip_code(mut self, cip_code: impl Into<String>) -> Self {
+        self.filters.cip_code = Some(cip_code.into());
+        self
+    }
+
+    /// Completes the metadata of programs with the `mapping`, see [MetadataMapping::metadata]
+    pub fn metadata(mut self, mapping: impl Into<Arc<MetadataMapping>>) -> Self {
+        self.filters.metadata = Some(mapping.into());
+        self
+    }
+
+    /// Courses tagged with `tag`, on their own or through a requirement listing them, by the
+    /// [tags](Self::tags) mapping. Case insensitive, and every tag asked for must match.
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.filters.with_tags.push(tag.into());
+        self
+    }
+
+    /// Tags courses and requirements with the `mapping`. Without a mapping, no course has a tag.
+    pub fn tags(mut self, mapping: impl Into<Arc<TagMapping>>) -> Self {
+        self.filters.tags = Some(mapping.into());
+        self
+    }
+
+    pub fn run(self) -> Vec<CourseMatch<'a>> {
+        // Only a single course can match when filtering by GUID
+        if let Some(guid) = self.filters.guid {
+            return self
+                .index
+                .get(&guid)
+                .filter(|course| self.filters.matches(course))
+                .cloned()
+                .into_iter()
+                .collect();
+        }
+
+        self.index
+            .courses
+            .iter()
+            .filter(|course| self.filters.matches(course))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Filters {
+    fn matches(&self, course: &CourseMatch) -> bool {
+        if self.guid.is_some_and(|guid| guid != course.guid) {
+            return false;
+        }
+
+        if let Some(subject_code) = &self.subject_code {
+            if !course.subject_code().eq_ignore_ascii_case(subject_code) {
+                return false;
+            }
+        }
+
+        if self.number_range != (Bound::Unbounded, Bound::Unbounded) {
+            match course.code() {
+                Ok(code) if self.number_range.contains(&code.number) => {}
+                _ => return false,
+            }
+        }
+
+        let (min_credits, max_credits) = course.credits();
+        if !(min_credits..=max_credits).any(|credits| self.credit_range.contains(&credits)) {
+            return false;
+        }
+
+        if let Some(needle) = &self.name_contains {
+            let matches_name = course
+                .name()
+                .is_some_and(|name| name.to_lowercase().contains(needle));
+            if !matches_name {
+                return false;
+            }
+        }
+
+        if !self.with_tags.is_empty() {
+            let Some(tags) = &self.tags else {
+                return false;
+            };
+            let tagged = self
+                .with_tags
+                .iter()
+                .all(|tag| tags.has_tag(&course.guid, course.programs.iter().copied(), tag));
+            if !tagged {
+                return false;
+            }
+        }
+
+        let filters_programs =
+            self.degree.is_some() || self.department.is_some() || self.cip_code.is_some();
+        if filters_programs
+            && !course
+                .programs
+                .iter()
+                .any(|program| self.matches_program(program))
+        {
+            return false;
+        }
+
+        true
+    }
+
+    fn matches_program(&self, program: &Program) -> bool {
+        let metadata = match &self.metadata {
+            Some(mapping) => mapping.metadata(program),
+            None => ProgramMetadata::of(program),
+        };
+
+        if self.degree.is_some() && metadata.degree != self.degree {
+            return false;
+        }
+
+        if let Some(department) = &self.department {
+            let offered_by = [&metadata.department, &metadata.school]
+                .into_iter()
+                .flatten()
+                .any(|unit| unit.eq_ignore_ascii_case(department));
+            if !offered_by {
+                return false;
+            }
+        }
+
+        if let Some(cip_code) = &self.cip_code {
+            if !metadata
+                .cip_code
+                .is_some_and(|code| code.starts_with(cip_code.as_str()))
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn guid(s: &str) -> Guid {
+        Guid::try_from(s).unwrap()
+    }
+
+    fn test_catalog() -> Catalog {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        catalog
+    }
+
+    #[test]
+    fn reverse_index_finds_programs_requiring_a_course() {
+        let catalog = test_catalog();
+        let index = catalog.index();
+
+        // CSC 115
+        let programs = index.programs_requiring(&guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C"));
+
+        assert!(programs
+            .iter()
+            .any(|program| program.title == "Major in Computer Science—42 hours"));
+    }
+
+    #[test]
+    fn filters_are_combined() {
+        let catalog = test_catalog();
+
+        let matches = catalog
+            .query()
+            .subject_code("csc")
+            .number_range(300..400)
+            .credit_range(3..=3)
+            .run();
+
+        assert!(!matches.is_empty());
+        for course in &matches {
+            assert_eq!(course.subject_code(), "CSC");
+            assert!(course.number().starts_with('3'));
+        }
+    }
+
+    #[test]
+    fn query_by_guid_and_name() {
+        let catalog = test_catalog();
+        let index = catalog.index();
+
+        let by_guid = index
+            .query()
+            .guid(guid("13A1385C-81AC-493D-ACE8-AA8AB37D2C81"))
+            .run();
+        assert_eq!(by_guid.len(), 1);
+        assert_eq!(by_guid[0].number(), "125");
+        assert!(by_guid[0].details.is_some());
+
+        let by_name = index.query().name_contains("programming in JAVA").run();
+        assert!(by_name.iter().any(|course| course.number() == "125"));
+    }
+
+    #[test]
+    fn query_by_program_metadata() {
+        let catalog = test_catalog();
+        let index = catalog.index();
+
+        let minors = index.query().degree(Degree::Minor).run();
+        assert!(!minors.is_empty());
+        assert!(minors.len() < index.query().run().len());
+        for course in &minors {
+            assert!(course
+                .programs
+                .iter()
+                .any(|program| program.title.starts_with("Minor")));
+        }
+
+        let computer_science = index
+            .query()
+            .department("department of computer science")
+            .run();
+        assert!(computer_science
+            .iter()
+            .any(|course| course.subject_code() == "CSC"));
+
+        // Only programs with a CIP code in the mapping match
+        assert!(index.query().cip_code("11").run().is_empty());
+        let major = catalog
+            .programs
+            .iter()
+            .find(|program| program.title == "Major in Computer Science—42 hours")
+            .unwrap();
+        let mut mapping = MetadataMapping::new();
+        mapping.insert(
+            major.guid,
+            ProgramMetadata {
+                cip_code: Some("11.0701".to_owned()),
+                ..Default::default()
+            },
+        );
+        let matches = index.query().metadata(mapping).cip_code("11").run();
+        let courses: HashSet<Guid> = major.iter_courses().map(|course| course.guid).collect();
+        assert_eq!(matches.len(), courses.len());
+    }
+
+    #[test]
+    fn tags_match_courses_and_the_requirements_listing_them() {
+        let catalog = test_catalog();
+        let index = catalog.index();
+        let minor = catalog
+            .programs
+            .iter()
+            .find(|program| program.title.starts_with("Minor in Computer Science"))
+            .unwrap();
+        let track = minor
+            .iter_requirements()
+            .find(|requirement| requirement.title() == Some("Select one track:"))
+            .unwrap();
+        let track_courses: HashSet<Guid> = track
+            .course_entries()
+            .unwrap()
+            .iter_courses()
+            .map(|course| course.guid)
+            .collect();
+
+        let mut tags = TagMapping::new();
+        tags.tag_requirement(minor.guid, "Select one track:", "capstone");
+        let first = *track_courses.iter().next().unwrap();
+        tags.tag_course(first, "writing-intensive");
+        let tags = Arc::new(tags);
+
+        let capstone = index.query().tags(tags.clone()).with_tag("Capstone").run();
+        let guids: HashSet<Guid> = capstone.iter().map(|course| course.guid).collect();
+        assert_eq!(guids, track_courses);
+
+        let both = index
+            .query()
+            .tags(tags)
+            .with_tag("capstone")
+            .with_tag("writing-intensive")
+            .run();
+        assert_eq!(both.len(), 1);
+        assert_eq!(both[0].guid, first);
+
+        assert!(index.query().with_tag("capstone").run().is_empty());
+    }
+}
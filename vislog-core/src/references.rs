@@ -0,0 +1,223 @@
+//! Courses mentioned in the prose of label requirements, such as "Students must complete BIO 200
+//! prior to enrolling in upper-level courses".
+//!
+//! Label requirements don't list any courses, so the courses they mention only exist in their
+//! title and `req_narrative`. A [CourseRefExtractor] finds the course codes of a list of subjects
+//! in that text and attaches them to the requirement as [CourseRef]s, which
+//! [ProgramGraph](crate::graph::ProgramGraph) includes along with the courses listed by the
+//! program.
+//!
+//! Extraction is opt-in. Parsing always leaves the `course_refs` of requirements empty, since a
+//! code in prose can just as well name a course that *doesn't* count towards the program, as in
+//! "HON 225 does not substitute for HIS 102".
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, references::CourseRefExtractor};
+//! let (mut catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//!
+//! let extractor = CourseRefExtractor::from_catalog(&catalog);
+//! for program in &mut catalog.programs {
+//!     extractor.attach(program);
+//! }
+//!
+//! let refs = extractor.extract("CSC 105 and CSC 106 may be taken in place of CSC 115.");
+//! assert_eq!(refs.len(), 3);
+//! assert!(refs.iter().all(|course_ref| course_ref.guid.is_some()));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{catalog::Catalog, parsing::guid::Guid, Program, Requirement};
+
+/// A course mentioned by its subject code and number in the prose of a requirement
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CourseRef {
+    pub subject_code: String,
+    pub number: String,
+    /// GUID of the course in the catalog. `None` when the extractor doesn't know the course.
+    pub guid: Option<Guid>,
+}
+
+/// Finds the course codes of a list of subjects in text
+#[derive(Debug, Clone)]
+pub struct CourseRefExtractor {
+    /// `None` when there are no subjects to look for
+    pattern: Option<Regex>,
+    /// GUIDs of the known courses by subject code and number
+    guids: HashMap<(String, String), Guid>,
+}
+
+impl CourseRefExtractor {
+    /// Extractor of the codes of the `subjects`, such as `["BIO", "CSC"]`. The references it finds
+    /// don't have GUIDs.
+    pub fn new<I, S>(subjects: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut subjects: Vec<String> = subjects
+            .into_iter()
+            .map(|subject| subject.as_ref().trim().to_uppercase())
+            .filter(|subject| !subject.is_empty())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        // Longest first so that a subject doesn't shadow a longer one starting with it
+        subjects.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+        let pattern = (!subjects.is_empty()).then(|| {
+            let subjects: Vec<String> = subjects.iter().map(|s| regex::escape(s)).collect();
+            Regex::new(&format!(
+                r"\b({})[ \u{{a0}}]?(\d{{3}}[A-Z]?)\b",
+                subjects.join("|")
+            ))
+            .expect("Escaped subject codes should form a valid pattern")
+        });
+
+        Self {
+            pattern,
+            guids: HashMap::new(),
+        }
+    }
+
+    /// Extractor of the codes of every subject in the courses of the `catalog`, resolving the
+    /// references it finds to the GUIDs of those courses
+    pub fn from_catalog(catalog: &Catalog) -> Self {
+        let subjects = catalog
+            .courses
+            .iter()
+            .map(|course| course.subject_code.as_str());
+
+        Self {
+            guids: catalog
+                .courses
+                .iter()
+                .map(|course| (code_key(&course.subject_code, &course.number), course.guid))
+                .collect(),
+            ..Self::new(subjects)
+        }
+    }
+
+    /// Every distinct course code in `text`, in the order they first appear
+    pub fn extract(&self, text: &str) -> Vec<CourseRef> {
+        let Some(pattern) = &self.pattern else {
+            return vec![];
+        };
+
+        let mut seen = HashSet::new();
+        pattern
+            .captures_iter(text)
+            .filter_map(|captures| {
+                let (subject_code, number) = (&captures[1], &captures[2]);
+                seen.insert(code_key(subject_code, number))
+                    .then(|| CourseRef {
+                        subject_code: subject_code.to_owned(),
+                        number: number.to_owned(),
+                        guid: self.guids.get(&code_key(subject_code, number)).copied(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Replaces the `course_refs` of every label requirement of the `program` with the course
+    /// codes in its title and narrative
+    pub fn attach(&self, program: &mut Program) {
+        let modules = program
+            .requirements
+            .iter_mut()
+            .flat_map(|requirements| requirements.modules_mut());
+
+        for module in modules {
+            for requirement in module.requirements_mut() {
+                if let Requirement::Label {
+                    title,
+                    req_narrative,
+                    course_refs,
+                    ..
+                } = requirement
+                {
+                    let text = [title.as_deref(), req_narrative.as_deref()]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    *course_refs = self.extract(&text);
+                }
+            }
+        }
+    }
+}
+
+fn code_key(subject_code: &str, number: &str) -> (String, String) {
+    (subject_code.to_uppercase(), number.to_uppercase())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn codes(refs: &[CourseRef]) -> Vec<String> {
+        refs.iter()
+            .map(|course_ref| format!("{} {}", course_ref.subject_code, course_ref.number))
+            .collect()
+    }
+
+    #[test]
+    fn only_codes_of_the_subjects_are_extracted() {
+        let extractor = CourseRefExtractor::new(["BIO", "egr", "BIOL"]);
+
+        let refs = extractor.extract(
+            "<p class='sc-RequirementNarrative'>EGR coursework to exclude EGR 391, EGR491, \
+             BIOL 210 and CSC 115. Students must complete BIO 200 prior to BIO 300; BIO 200 \
+             is offered each fall. EGR 12345 isn't a course.</p>",
+        );
+
+        assert_eq!(
+            codes(&refs),
+            ["EGR 391", "EGR 491", "BIOL 210", "BIO 200", "BIO 300"]
+        );
+        assert!(refs.iter().all(|course_ref| course_ref.guid.is_none()));
+        assert!(CourseRefExtractor::new([""; 0])
+            .extract("BIO 200")
+            .is_empty());
+    }
+
+    #[test]
+    fn references_are_attached_to_label_requirements() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let extractor = CourseRefExtractor::from_catalog(&catalog);
+
+        let mut program = catalog
+            .programs
+            .iter()
+            .find(|program| program.title.starts_with("Minor in Engineering"))
+            .cloned()
+            .unwrap();
+        assert_eq!(program.iter_course_refs().count(), 0);
+
+        extractor.attach(&mut program);
+        let label = program
+            .iter_requirements()
+            .find(|requirement| !requirement.course_refs().is_empty())
+            .unwrap();
+
+        assert!(matches!(label, Requirement::Label { .. }));
+        assert_eq!(
+            codes(label.course_refs()),
+            ["EGR 391", "EGR 491", "EGR 492", "EGR 498"]
+        );
+        assert!(label
+            .course_refs()
+            .iter()
+            .all(|course_ref| course_ref.guid.is_some()));
+
+        // Attaching again replaces the references instead of adding to them
+        extractor.attach(&mut program);
+        assert_eq!(program.iter_course_refs().count(), 4);
+    }
+}
@@ -0,0 +1,300 @@
+//! Contradictions in the requisites of the courses of a catalog.
+//!
+//! The catalog API doesn't check the structured `prerequisite` and `corequisite` of courses, so a
+//! catalog can have courses that can never be taken: prerequisites that lead back to the course
+//! requiring them, courses that are their own requisite, and corequisites that must also be taken
+//! before the course. [Catalog::check_requisites] finds every one of them, naming the courses by
+//! their code along with their GUID.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::catalog::Catalog;
+//! let (catalog, _errors) = Catalog::parse_file("../data/courses.json");
+//!
+//! for issue in catalog.check_requisites() {
+//!     println!("warning: {issue}");
+//! }
+//! ```
+
+use std::{collections::HashMap, fmt};
+
+use serde::Serialize;
+
+use crate::{catalog::Catalog, graph::PrerequisiteGraph, parsing::guid::Guid, CourseDetails};
+
+/// A course named by its code
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct NamedCourse {
+    pub guid: Guid,
+    /// Subject code and number of the course. Ex: "CSC 125". The GUID of the course when the
+    /// catalog doesn't have it.
+    pub code: String,
+}
+
+impl fmt::Display for NamedCourse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.code, self.guid)
+    }
+}
+
+/// A contradiction in the requisites of one or more courses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", content = "data")]
+pub enum RequisiteIssue {
+    /// Courses that each require the next one, the last requiring the first
+    Cycle { courses: Vec<NamedCourse> },
+    /// A course that is its own prerequisite
+    SelfPrerequisite { course: NamedCourse },
+    /// A course that is its own corequisite
+    SelfCorequisite { course: NamedCourse },
+    /// A course whose corequisite is also its prerequisite, so the requisite has to be taken both
+    /// before and along with the course
+    CorequisiteIsPrerequisite {
+        course: NamedCourse,
+        requisite: NamedCourse,
+    },
+}
+
+impl fmt::Display for RequisiteIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequisiteIssue::Cycle { courses } => {
+                write!(f, "prerequisite cycle: ")?;
+                for course in courses {
+                    write!(f, "{course} requires ")?;
+                }
+                match courses.first() {
+                    Some(first) => write!(f, "{}", first.code),
+                    None => Ok(()),
+                }
+            }
+            RequisiteIssue::SelfPrerequisite { course } => {
+                write!(f, "{course} is its own prerequisite")
+            }
+            RequisiteIssue::SelfCorequisite { course } => {
+                write!(f, "{course} is its own corequisite")
+            }
+            RequisiteIssue::CorequisiteIsPrerequisite { course, requisite } => write!(
+                f,
+                "{course} has {requisite} as both a prerequisite and a corequisite"
+            ),
+        }
+    }
+}
+
+impl Catalog {
+    /// Every contradiction in the requisites of the courses of the catalog. See [check_requisites].
+    pub fn check_requisites(&self) -> Vec<RequisiteIssue> {
+        check_requisites(&self.courses)
+    }
+}
+
+/// Every contradiction in the requisites of the `courses`, in the order of the courses. Cycles come
+/// last, each reported once starting from the first of its courses in `courses`.
+pub fn check_requisites(courses: &[CourseDetails]) -> Vec<RequisiteIssue> {
+    let codes: HashMap<Guid, String> = courses
+        .iter()
+        .map(|course| {
+            let code = format!("{} {}", course.subject_code, course.number);
+            (course.guid, code)
+        })
+        .collect();
+    let code = |guid: Guid| NamedCourse {
+        guid,
+        code: codes
+            .get(&guid)
+            .cloned()
+            .unwrap_or_else(|| guid.to_string()),
+    };
+
+    let mut issues = vec![];
+    for course in courses {
+        if course.prerequisite == Some(course.guid) {
+            issues.push(RequisiteIssue::SelfPrerequisite {
+                course: code(course.guid),
+            });
+        }
+        if course.corequisite == Some(course.guid) {
+            issues.push(RequisiteIssue::SelfCorequisite {
+                course: code(course.guid),
+            });
+        } else if let Some(corequisite) = course.corequisite {
+            if course.prerequisite == Some(corequisite) {
+                issues.push(RequisiteIssue::CorequisiteIsPrerequisite {
+                    course: code(course.guid),
+                    requisite: code(corequisite),
+                });
+            }
+        }
+    }
+
+    let graph = PrerequisiteGraph::from_course_details(courses);
+    let mut finder = CycleFinder {
+        graph: &graph,
+        states: HashMap::new(),
+        path: vec![],
+        cycles: vec![],
+    };
+    for course in courses {
+        finder.visit(course.guid);
+    }
+    issues.extend(
+        finder
+            .cycles
+            .into_iter()
+            .map(|cycle| RequisiteIssue::Cycle {
+                courses: cycle.into_iter().map(code).collect(),
+            }),
+    );
+
+    issues
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    /// On the path being followed
+    Visiting,
+    Done,
+}
+
+/// Depth-first search for the cycles of a [PrerequisiteGraph]
+struct CycleFinder<'a> {
+    graph: &'a PrerequisiteGraph,
+    states: HashMap<Guid, VisitState>,
+    /// Courses from the root of the search to the current course, each requiring the next
+    path: Vec<Guid>,
+    cycles: Vec<Vec<Guid>>,
+}
+
+impl CycleFinder<'_> {
+    fn visit(&mut self, guid: Guid) {
+        match self.states.get(&guid) {
+            Some(VisitState::Done) => return,
+            Some(VisitState::Visiting) => {
+                let start = self
+                    .path
+                    .iter()
+                    .position(|course| *course == guid)
+                    .expect("Courses being visited should be on the path");
+                // Courses requiring themselves are reported on their own
+                if self.path.len() - start > 1 {
+                    self.cycles.push(self.path[start..].to_vec());
+                }
+                return;
+            }
+            None => {}
+        }
+
+        self.states.insert(guid, VisitState::Visiting);
+        self.path.push(guid);
+        for prerequisite in self.graph.prerequisites_of(&guid) {
+            self.visit(*prerequisite);
+        }
+        self.path.pop();
+        self.states.insert(guid, VisitState::Done);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn course(guid: Guid, number: &str) -> CourseDetails {
+        CourseDetails {
+            url: Default::default(),
+            guid,
+            path: Default::default(),
+            subject_code: "CSC".into(),
+            subject_name: None,
+            number: number.to_owned(),
+            name: format!("Course {number}"),
+            credits_min: 3,
+            credits_max: None,
+            description: String::new(),
+            prerequisite_narrative: None,
+            prerequisite: None,
+            corequisite_narrative: None,
+            corequisite: None,
+            extra: Default::default(),
+        }
+    }
+
+    fn guid(s: &str) -> Guid {
+        Guid::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn contradictions_are_found() {
+        let a = guid("860AF9C9-EAD9-45AC-AA92-BAF352C5288C");
+        let b = guid("13A1385C-81AC-493D-ACE8-AA8AB37D2C81");
+        let c = guid("BF3CF399-6D63-43AA-8064-2A86789B5A4E");
+        let d = guid("F17E3997-3E59-4B70-B662-E5AB1A40ADAF");
+        let missing = guid("3F2C8A5E-7D41-4B9A-9E6F-1C0B2D3A4E5F");
+
+        let mut courses = vec![
+            course(a, "115"),
+            course(b, "215"),
+            course(c, "315"),
+            course(d, "415"),
+        ];
+        // A requires B requires C requires A
+        courses[0].prerequisite = Some(b);
+        courses[1].prerequisite = Some(c);
+        courses[2].prerequisite = Some(a);
+        courses[3].prerequisite = Some(d);
+        courses[3].corequisite = Some(d);
+        courses[1].corequisite = Some(c);
+
+        let issues = check_requisites(&courses);
+        let code = |guid, code: &str| NamedCourse {
+            guid,
+            code: code.to_owned(),
+        };
+        assert_eq!(
+            issues,
+            [
+                RequisiteIssue::CorequisiteIsPrerequisite {
+                    course: code(b, "CSC 215"),
+                    requisite: code(c, "CSC 315"),
+                },
+                RequisiteIssue::SelfPrerequisite {
+                    course: code(d, "CSC 415"),
+                },
+                RequisiteIssue::SelfCorequisite {
+                    course: code(d, "CSC 415"),
+                },
+                RequisiteIssue::Cycle {
+                    courses: vec![code(a, "CSC 115"), code(b, "CSC 215"), code(c, "CSC 315")],
+                },
+            ]
+        );
+        assert_eq!(
+            issues[3].to_string(),
+            format!(
+                "prerequisite cycle: CSC 115 ({a}) requires CSC 215 ({b}) requires \
+                 CSC 315 ({c}) requires CSC 115"
+            )
+        );
+
+        // Requisites missing from the catalog are named by their GUID
+        courses[2].prerequisite = None;
+        courses[3] = course(d, "415");
+        courses[1].prerequisite = Some(missing);
+        courses[1].corequisite = Some(missing);
+        assert_eq!(
+            check_requisites(&courses),
+            [RequisiteIssue::CorequisiteIsPrerequisite {
+                course: code(b, "CSC 215"),
+                requisite: code(missing, &missing.to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn the_catalog_has_no_contradictions() {
+        let (catalog, _) = Catalog::parse_file("../data/courses.json");
+        assert_eq!(catalog.check_requisites(), []);
+    }
+}
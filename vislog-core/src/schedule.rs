@@ -0,0 +1,674 @@
+//! Sections of courses offered in each term, with their meeting times and instructors.
+//!
+//! The catalog API only describes courses, so when they are taught comes from a separate feed
+//! exported by the registrar, either as a JSON array of [Section]s or as CSV with a row for every
+//! meeting of a section. [Offerings] joins the sections to [CourseDetails] by subject code and
+//! number and answers whether a course is offered in a season. An [OfferingCalendar] generalizes
+//! the sections of past terms into when each course is offered, such as fall only or alternate
+//! years, for [layered_plan](crate::planner::layered_plan) to plan courses only in those terms.
+//!
+//! The CSV feed has a header row with the columns `subject_code`, `number`, `section`, `term`,
+//! `instructors`, `days`, `start`, `end` and `location`. Instructors are separated by `;` and rows
+//! of the same section in the same term are merged into a single [Section].
+//!
+//! # Example
+//! ```
+//! # use vislog_core::schedule::{Offerings, Season};
+//! let csv = "\
+//! subject_code,number,section,term,instructors,days,start,end,location
+//! CSC,115,01,Fall 2024,Ada Lovelace,MWF,09:00,09:50,JSC 101
+//! CSC,115,01,Fall 2024,Ada Lovelace,T,14:00,15:50,JSC 105
+//! CSC,125,01,Spring 2025,Alan Turing,TR,10:00,11:15,JSC 101
+//! ";
+//!
+//! let offerings = Offerings::from_csv(csv.as_bytes()).unwrap();
+//!
+//! assert_eq!(offerings.sections_of("CSC", "115")[0].meetings.len(), 2);
+//! assert!(offerings.is_offered_in("CSC", "115", Season::Fall));
+//! assert!(!offerings.is_offered_in("CSC", "125", Season::Fall));
+//! ```
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+    io::Read,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::CourseDetails;
+
+/// Part of the academic year
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Season {
+    Spring,
+    Summer,
+    Fall,
+    Winter,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("{0:?} is not a term")]
+pub struct TermParsingError(String);
+
+impl FromStr for Season {
+    type Err = TermParsingError;
+
+    /// Parses the name of a season in any case, or the two letter code registrars use for it.
+    /// Ex: "Fall", "spring" or "SU"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let season = match s.trim().to_lowercase().as_str() {
+            "spring" | "sp" => Season::Spring,
+            "summer" | "su" => Season::Summer,
+            "fall" | "autumn" | "fa" => Season::Fall,
+            "winter" | "wi" => Season::Winter,
+            _ => return Err(TermParsingError(s.to_owned())),
+        };
+
+        Ok(season)
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// A season of a year, ordered chronologically. Serialized as a string such as `"Fall 2024"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Term {
+    pub season: Season,
+    pub year: u16,
+}
+
+impl PartialOrd for Term {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Term {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.year, self.season).cmp(&(other.year, other.season))
+    }
+}
+
+impl FromStr for Term {
+    type Err = TermParsingError;
+
+    /// Parses a season followed by a year, such as "Fall 2024", or a year followed by the code of
+    /// a season, such as "2024FA"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || TermParsingError(s.to_owned());
+        let s = s.trim();
+
+        let (season, year) = match s.split_once(char::is_whitespace) {
+            Some((season, year)) => (season, year.trim()),
+            None if s.len() > 4 && s.is_char_boundary(4) => (&s[4..], &s[..4]),
+            None => return Err(error()),
+        };
+
+        Ok(Term {
+            season: season.parse().map_err(|_| error())?,
+            year: year.parse().map_err(|_| error())?,
+        })
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.season, self.year)
+    }
+}
+
+impl Serialize for Term {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Term {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for Term {
+    fn schema_name() -> String {
+        "Term".to_owned()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
+/// A section of a course taught in a term
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Section {
+    pub subject_code: String,
+    pub number: String,
+    /// Identifier of the section among the sections of the course in the term. Ex: "01"
+    pub section: String,
+    pub term: Term,
+    #[serde(default)]
+    pub instructors: Vec<String>,
+    /// Empty for sections without set meeting times, such as online sections
+    #[serde(default)]
+    pub meetings: Vec<Meeting>,
+}
+
+/// A weekly meeting of a section
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Meeting {
+    /// Days of the week as letters. Ex: "MWF" or "TR"
+    pub days: String,
+    /// Time of day the meeting starts, in 24-hour time. Ex: "14:00"
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub location: Option<String>,
+}
+
+/// A row of the CSV feed
+#[derive(Debug, Deserialize)]
+struct SectionRow {
+    subject_code: String,
+    number: String,
+    section: String,
+    term: Term,
+    #[serde(default)]
+    instructors: String,
+    #[serde(default)]
+    days: String,
+    start: Option<String>,
+    end: Option<String>,
+    location: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum OfferingsError {
+    #[error("failed to parse JSON offerings: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("failed to parse CSV offerings: {0}")]
+    Csv(#[from] csv::Error),
+}
+
+/// Sections of courses, looked up by the subject code and number of the course
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Offerings {
+    sections: HashMap<(String, String), Vec<Section>>,
+}
+
+/// A course of the catalog along with its sections
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ScheduledCourse<'a> {
+    pub course: &'a CourseDetails,
+    pub sections: &'a [Section],
+}
+
+impl Offerings {
+    /// Sections are kept in the order given, per course
+    pub fn new(sections: impl IntoIterator<Item = Section>) -> Self {
+        let mut offerings = Self::default();
+        for section in sections {
+            offerings
+                .sections
+                .entry(course_key(&section.subject_code, &section.number))
+                .or_default()
+                .push(section);
+        }
+
+        offerings
+    }
+
+    /// Offerings of a JSON array of [Section]s
+    pub fn from_json(reader: impl Read) -> Result<Self, OfferingsError> {
+        let sections: Vec<Section> = serde_json::from_reader(reader)?;
+        Ok(Self::new(sections))
+    }
+
+    /// Offerings of CSV with a row for every meeting of a section, as described in the
+    /// [module](self) documentation
+    pub fn from_csv(reader: impl Read) -> Result<Self, OfferingsError> {
+        let mut sections: Vec<Section> = vec![];
+        let mut indices: HashMap<(String, String, String, Term), usize> = HashMap::new();
+
+        for row in csv::Reader::from_reader(reader).deserialize() {
+            let row: SectionRow = row?;
+            let (subject_code, number) = course_key(&row.subject_code, &row.number);
+            let key = (
+                subject_code,
+                number,
+                row.section.trim().to_owned(),
+                row.term,
+            );
+
+            let index = *indices.entry(key).or_insert_with(|| {
+                sections.push(Section {
+                    subject_code: row.subject_code.trim().to_owned(),
+                    number: row.number.trim().to_owned(),
+                    section: row.section.trim().to_owned(),
+                    term: row.term,
+                    instructors: vec![],
+                    meetings: vec![],
+                });
+                sections.len() - 1
+            });
+            let section = &mut sections[index];
+
+            for instructor in row.instructors.split(';').map(str::trim) {
+                if !instructor.is_empty() && !section.instructors.iter().any(|i| i == instructor) {
+                    section.instructors.push(instructor.to_owned());
+                }
+            }
+
+            let non_empty = |field: Option<String>| field.filter(|field| !field.trim().is_empty());
+            let days = row.days.trim();
+            if !days.is_empty() {
+                section.meetings.push(Meeting {
+                    days: days.to_owned(),
+                    start: non_empty(row.start),
+                    end: non_empty(row.end),
+                    location: non_empty(row.location),
+                });
+            }
+        }
+
+        Ok(Self::new(sections))
+    }
+
+    /// Number of sections of all courses
+    pub fn len(&self) -> usize {
+        self.sections.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Every section of the course with the `subject_code` and `number`, in any term
+    pub fn sections_of(&self, subject_code: &str, number: &str) -> &[Section] {
+        self.sections
+            .get(&course_key(subject_code, number))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Seasons in which the course with the `subject_code` and `number` has been offered
+    pub fn seasons_of(&self, subject_code: &str, number: &str) -> BTreeSet<Season> {
+        self.sections_of(subject_code, number)
+            .iter()
+            .map(|section| section.term.season)
+            .collect()
+    }
+
+    /// Whether the course with the `subject_code` and `number` has a section in any term of the
+    /// `season`
+    pub fn is_offered_in(&self, subject_code: &str, number: &str, season: Season) -> bool {
+        self.sections_of(subject_code, number)
+            .iter()
+            .any(|section| section.term.season == season)
+    }
+
+    /// Whether the course with the `subject_code` and `number` has a section in the `term`
+    pub fn is_offered_during(&self, subject_code: &str, number: &str, term: Term) -> bool {
+        self.sections_of(subject_code, number)
+            .iter()
+            .any(|section| section.term == term)
+    }
+
+    /// Every one of the `courses` along with its sections, which are empty for courses without
+    /// any
+    pub fn join<'a>(&'a self, courses: &'a [CourseDetails]) -> Vec<ScheduledCourse<'a>> {
+        courses
+            .iter()
+            .map(|course| ScheduledCourse {
+                course,
+                sections: self.sections_of(&course.subject_code, &course.number),
+            })
+            .collect()
+    }
+
+    /// Sections of courses that aren't any of the `courses`, usually from a typo in the feed or a
+    /// course missing from the catalog
+    pub fn unmatched(&self, courses: &[CourseDetails]) -> Vec<&Section> {
+        let known: HashSet<(String, String)> = courses
+            .iter()
+            .map(|course| course_key(&course.subject_code, &course.number))
+            .collect();
+
+        let mut unmatched: Vec<&Section> = self
+            .sections
+            .iter()
+            .filter(|(key, _)| !known.contains(*key))
+            .flat_map(|(_, sections)| sections)
+            .collect();
+        unmatched.sort_by(|a, b| {
+            (&a.subject_code, &a.number, a.term, &a.section).cmp(&(
+                &b.subject_code,
+                &b.number,
+                b.term,
+                &b.section,
+            ))
+        });
+
+        unmatched
+    }
+}
+
+/// Years in which a course is offered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Years {
+    #[default]
+    Every,
+    Even,
+    Odd,
+}
+
+impl Years {
+    fn contains(self, year: u16) -> bool {
+        match self {
+            Years::Every => true,
+            Years::Even => year.is_multiple_of(2),
+            Years::Odd => !year.is_multiple_of(2),
+        }
+    }
+}
+
+/// When a course is offered. Ex: fall only, or spring of odd years
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Availability {
+    pub seasons: BTreeSet<Season>,
+    #[serde(default)]
+    pub years: Years,
+}
+
+impl Availability {
+    pub fn new(seasons: impl IntoIterator<Item = Season>, years: Years) -> Self {
+        Self {
+            seasons: seasons.into_iter().collect(),
+            years,
+        }
+    }
+
+    pub fn includes(&self, term: Term) -> bool {
+        self.seasons.contains(&term.season) && self.years.contains(term.year)
+    }
+}
+
+/// Terms that courses are planned in, along with when each course is offered. Courses without an
+/// [Availability] are assumed to be offered every term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfferingCalendar {
+    /// The first term of plans
+    pub start: Term,
+    /// Seasons of every year that courses are planned in. Ex: fall and spring, leaving out summer
+    pub seasons: BTreeSet<Season>,
+    availability: HashMap<(String, String), Availability>,
+}
+
+impl OfferingCalendar {
+    pub fn new(start: Term, seasons: impl IntoIterator<Item = Season>) -> Self {
+        Self {
+            start,
+            seasons: seasons.into_iter().collect(),
+            availability: HashMap::new(),
+        }
+    }
+
+    /// Calendar with the availability of every course in the `offerings`, offered in the seasons
+    /// it has sections in. A course is offered in alternate years when all of its sections are in
+    /// even or odd years, while the `offerings` have sections in other years of those seasons.
+    pub fn from_offerings(
+        start: Term,
+        seasons: impl IntoIterator<Item = Season>,
+        offerings: &Offerings,
+    ) -> Self {
+        let mut years_of_season: HashMap<Season, BTreeSet<u16>> = HashMap::new();
+        for section in offerings.sections.values().flatten() {
+            years_of_season
+                .entry(section.term.season)
+                .or_default()
+                .insert(section.term.year);
+        }
+
+        let mut calendar = Self::new(start, seasons);
+        for (key, sections) in &offerings.sections {
+            let seasons: BTreeSet<Season> =
+                sections.iter().map(|section| section.term.season).collect();
+
+            let parities: HashSet<Option<u16>> = seasons
+                .iter()
+                .map(|season| {
+                    let years: BTreeSet<u16> = sections
+                        .iter()
+                        .filter(|section| section.term.season == *season)
+                        .map(|section| section.term.year % 2)
+                        .collect();
+                    let feed_years = years_of_season[season].iter().map(|year| year % 2);
+
+                    match years.iter().collect::<Vec<_>>()[..] {
+                        [parity] if feed_years.clone().any(|year| year != *parity) => Some(*parity),
+                        _ => None,
+                    }
+                })
+                .collect();
+
+            let years = match parities.into_iter().collect::<Vec<_>>()[..] {
+                [Some(0)] => Years::Even,
+                [Some(_)] => Years::Odd,
+                _ => Years::Every,
+            };
+            calendar
+                .availability
+                .insert(key.clone(), Availability { seasons, years });
+        }
+
+        calendar
+    }
+
+    /// Sets when the course with the `subject_code` and `number` is offered
+    pub fn set_availability(
+        &mut self,
+        subject_code: &str,
+        number: &str,
+        availability: Availability,
+    ) {
+        self.availability
+            .insert(course_key(subject_code, number), availability);
+    }
+
+    /// When the course with the `subject_code` and `number` is offered, `None` when it is offered
+    /// every term
+    pub fn availability_of(&self, subject_code: &str, number: &str) -> Option<&Availability> {
+        self.availability.get(&course_key(subject_code, number))
+    }
+
+    pub fn is_offered(&self, subject_code: &str, number: &str, term: Term) -> bool {
+        self.availability_of(subject_code, number)
+            .is_none_or(|availability| availability.includes(term))
+    }
+
+    /// Whether the course with the `subject_code` and `number` is offered in any of the terms of
+    /// the calendar
+    pub fn is_ever_offered(&self, subject_code: &str, number: &str) -> bool {
+        // Availability repeats every two years
+        self.terms()
+            .take(self.seasons.len() * 2)
+            .any(|term| self.is_offered(subject_code, number, term))
+    }
+
+    /// Every term courses can be planned in, in order, starting at the first one not before
+    /// `start`. Endless unless there are no `seasons`.
+    pub fn terms(&self) -> impl Iterator<Item = Term> + '_ {
+        let first = if self.seasons.contains(&self.start.season) {
+            Some(self.start)
+        } else {
+            self.next_term(self.start)
+        };
+
+        std::iter::successors(first, |term| self.next_term(*term))
+    }
+
+    fn next_term(&self, term: Term) -> Option<Term> {
+        match self
+            .seasons
+            .range(term.season..)
+            .find(|season| **season > term.season)
+        {
+            Some(season) => Some(Term {
+                season: *season,
+                year: term.year,
+            }),
+            None => self.seasons.first().map(|season| Term {
+                season: *season,
+                year: term.year + 1,
+            }),
+        }
+    }
+}
+
+fn course_key(subject_code: &str, number: &str) -> (String, String) {
+    (
+        subject_code.trim().to_uppercase(),
+        number.trim().to_uppercase(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::catalog::Catalog;
+
+    const CSV: &str = "\
+subject_code,number,section,term,instructors,days,start,end,location
+CSC,115,01,Fall 2024,Ada Lovelace; Grace Hopper,MWF,09:00,09:50,JSC 101
+CSC,115,01,Fall 2024,Ada Lovelace,T,14:00,15:50,JSC 105
+csc,115,02,2025SP,,,,,
+CSC,125,01,Spring 2025,Alan Turing,TR,10:00,11:15,JSC 101
+XYZ,999,01,Fall 2024,,MWF,08:00,08:50,
+";
+
+    #[test]
+    fn terms_round_trip() {
+        let term: Term = "2025SP".parse().unwrap();
+        assert_eq!(
+            term,
+            Term {
+                season: Season::Spring,
+                year: 2025
+            }
+        );
+        assert_eq!(term.to_string().parse::<Term>(), Ok(term));
+        assert!("Fall 2024".parse::<Term>().unwrap() < "Spring 2025".parse().unwrap());
+        assert!("Fall".parse::<Term>().is_err());
+        assert!("Fal 2024".parse::<Term>().is_err());
+    }
+
+    #[test]
+    fn csv_rows_are_merged_into_sections() {
+        let offerings = Offerings::from_csv(CSV.as_bytes()).unwrap();
+        assert_eq!(offerings.len(), 4);
+
+        let sections = offerings.sections_of("CSC", "115");
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].instructors, ["Ada Lovelace", "Grace Hopper"]);
+        assert_eq!(sections[0].meetings.len(), 2);
+        assert_eq!(sections[0].meetings[1].location.as_deref(), Some("JSC 105"));
+        assert!(sections[1].instructors.is_empty());
+        assert!(sections[1].meetings.is_empty());
+
+        assert_eq!(
+            offerings.seasons_of("csc", "115"),
+            BTreeSet::from([Season::Spring, Season::Fall])
+        );
+        assert!(!offerings.is_offered_in("CSC", "125", Season::Fall));
+        assert!(offerings.is_offered_during("CSC", "125", "Spring 2025".parse().unwrap()));
+        assert!(!offerings.is_offered_in("CSC", "000", Season::Spring));
+
+        // The JSON feed holds the same sections
+        let json = serde_json::to_string(sections).unwrap();
+        let from_json = Offerings::from_json(json.as_bytes()).unwrap();
+        assert_eq!(from_json.sections_of("CSC", "115"), sections);
+    }
+
+    #[test]
+    fn sections_are_joined_to_courses() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let offerings = Offerings::from_csv(CSV.as_bytes()).unwrap();
+
+        let joined = offerings.join(&catalog.courses);
+        assert_eq!(joined.len(), catalog.courses.len());
+
+        let csc_115 = joined
+            .iter()
+            .find(|scheduled| {
+                scheduled.course.subject_code == "CSC" && scheduled.course.number == "115"
+            })
+            .unwrap();
+        assert_eq!(csc_115.sections.len(), 2);
+
+        let unmatched = offerings.unmatched(&catalog.courses);
+        assert_eq!(unmatched.len(), 1);
+        assert_eq!(unmatched[0].subject_code, "XYZ");
+    }
+
+    #[test]
+    fn calendars_follow_the_terms_of_the_offerings() {
+        let csv = "\
+subject_code,number,section,term,instructors,days,start,end,location
+CSC,115,01,Fall 2023,,,,,
+CSC,115,01,Fall 2024,,,,,
+CSC,310,01,Fall 2024,,,,,
+CSC,320,01,Spring 2024,,,,,
+";
+        let offerings = Offerings::from_csv(csv.as_bytes()).unwrap();
+        let start = "Spring 2025".parse().unwrap();
+        let calendar =
+            OfferingCalendar::from_offerings(start, [Season::Fall, Season::Spring], &offerings);
+
+        let terms: Vec<String> = calendar.terms().take(4).map(|t| t.to_string()).collect();
+        assert_eq!(
+            terms,
+            ["Spring 2025", "Fall 2025", "Spring 2026", "Fall 2026"]
+        );
+
+        let availability = |number| calendar.availability_of("CSC", number).unwrap();
+        assert_eq!(
+            availability("115"),
+            &Availability::new([Season::Fall], Years::Every)
+        );
+        assert_eq!(
+            availability("310"),
+            &Availability::new([Season::Fall], Years::Even)
+        );
+        assert_eq!(
+            availability("320"),
+            &Availability::new([Season::Spring], Years::Every)
+        );
+
+        assert!(!calendar.is_offered("CSC", "310", "Fall 2025".parse().unwrap()));
+        assert!(calendar.is_offered("CSC", "310", "Fall 2026".parse().unwrap()));
+        assert!(calendar.is_offered("CSC", "125", start));
+
+        let mut summer_only = calendar.clone();
+        summer_only.set_availability(
+            "CSC",
+            "125",
+            Availability::new([Season::Summer], Years::Every),
+        );
+        assert!(!summer_only.is_ever_offered("CSC", "125"));
+        assert!(summer_only.is_ever_offered("CSC", "310"));
+    }
+}
@@ -0,0 +1,109 @@
+//! JSON Schemas of the JSON produced by serializing the public types, for validating payloads and
+//! generating types in other languages.
+//!
+//! The schemas describe the serialized form of the types, which is not the raw catalog API format
+//! that [Program] and [CourseDetails] are deserialized from.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{schema, Program};
+//! let schema = schema::schema_for::<Program>();
+//! let schema = serde_json::to_value(&schema).unwrap();
+//!
+//! assert_eq!(schema["title"], "Program");
+//! assert!(schema["required"].as_array().unwrap().contains(&"guid".into()));
+//! ```
+
+use std::collections::BTreeMap;
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, RootSchema, Schema, SchemaObject},
+    JsonSchema,
+};
+
+use crate::{
+    audit::{render::AuditDocument, AuditReport},
+    export::csv::CourseRow,
+    flatten::FlattenedCourse,
+    graph::ProgramGraph,
+    parsing::guid::Guid,
+    planner::LayeredPlan,
+    CourseDetails, Program,
+};
+
+/// The root schema of `T`, with every type it refers to under `definitions`
+pub fn schema_for<T: JsonSchema>() -> RootSchema {
+    schemars::schema_for!(T)
+}
+
+/// The root schema of every public type that gets serialized to JSON, keyed by the name of the
+/// type
+pub fn schemas() -> BTreeMap<&'static str, RootSchema> {
+    BTreeMap::from([
+        ("Program", schema_for::<Program>()),
+        ("Programs", schema_for::<Vec<Program>>()),
+        ("CourseDetails", schema_for::<CourseDetails>()),
+        ("CourseRow", schema_for::<CourseRow>()),
+        ("FlattenedCourse", schema_for::<FlattenedCourse>()),
+        ("ProgramGraph", schema_for::<ProgramGraph>()),
+        ("LayeredPlan", schema_for::<LayeredPlan>()),
+        ("AuditReport", schema_for::<AuditReport>()),
+        ("AuditDocument", schema_for::<AuditDocument>()),
+    ])
+}
+
+impl JsonSchema for Guid {
+    fn schema_name() -> String {
+        "Guid".to_owned()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("uuid".to_owned()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use super::*;
+
+    #[test]
+    fn serialized_programs_have_every_required_property() {
+        let program_json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+        let program: Program = serde_json::from_str(&program_json).unwrap();
+        let program = serde_json::to_value(&program).unwrap();
+
+        let schema = serde_json::to_value(schema_for::<Program>()).unwrap();
+        let required = schema["required"].as_array().unwrap();
+        assert!(!required.is_empty());
+        assert!(required
+            .iter()
+            .filter_map(Value::as_str)
+            .all(|property| program.get(property).is_some()));
+
+        assert_eq!(
+            schema["properties"]["guid"]["allOf"][0]["$ref"],
+            "#/definitions/Guid"
+        );
+        assert_eq!(schema["definitions"]["Guid"]["format"], "uuid");
+
+        // Enums are tagged the same way they are serialized
+        let requirements = schema["definitions"]["Requirements"].to_string();
+        assert!(requirements.contains(r#""required":["data","type"]"#));
+    }
+
+    #[test]
+    fn every_schema_has_a_title() {
+        for (name, schema) in schemas() {
+            let schema = serde_json::to_value(schema).unwrap();
+            assert!(schema["title"].is_string(), "{name} has no title");
+        }
+    }
+}
@@ -0,0 +1,594 @@
+//! Programs scraped from the HTML pages of catalogs without a JSON feed.
+//!
+//! Acalog lays out a program page as a heading followed by "cores", each with a heading, some
+//! narrative and a list of courses joined by "or" entries. Courseleaf lists the courses of a
+//! program in `sc_courselist` tables instead, with header rows between requirements and "or" rows
+//! joining alternatives. [parse_program_html] converts either layout into the same [Program] model
+//! parsed from the catalog API, so the rest of vislog works on scraped programs as well.
+//!
+//! The pages don't have GUIDs, so the GUID of a program is derived from its URL and the GUID of a
+//! course from its subject code and number. A course gets the same GUID in every scraped program,
+//! but never the GUID given to it by the catalog API. The `content` and `bottom_content` of
+//! scraped programs are left empty.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::scrape;
+//! let html = r#"
+//!     <h1 id="acalog-content">Minor in Computer Science</h1>
+//!     <div class="acalog-core">
+//!         <h2>Requirements</h2>
+//!         <ul>
+//!             <li class="acalog-course"><a href="preview_course.php?coid=1">CSC 115 - Computer Science I</a> Credits: 4</li>
+//!             <li class="acalog-course"><a href="preview_course.php?coid=2">CSC 125 - Computer Science II</a> Credits: 4</li>
+//!         </ul>
+//!     </div>
+//! "#;
+//!
+//! let program = scrape::parse_program_html("https://catalog.example.edu/preview_program.php", html)
+//!     .unwrap();
+//!
+//! assert_eq!(program.title, "Minor in Computer Science");
+//! assert_eq!(program.iter_courses().count(), 2);
+//! ```
+
+use regex::Regex;
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use thiserror::Error;
+
+use crate::{
+    constraints::Constraints, electives::ElectiveHours, extensions::Extensions, hash::derived_guid,
+    Course, CourseEntries, CourseEntry, Label, Program, Requirement, RequirementModule,
+    Requirements,
+};
+
+#[derive(Debug, Error)]
+pub enum ScrapeError {
+    #[error("failed to fetch {url}: {source}")]
+    Fetch {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("{url} is not an Acalog or Courseleaf program page")]
+    UnrecognizedFormat { url: String },
+}
+
+/// Downloads the program page at `url` and parses it with [parse_program_html]
+pub async fn fetch_program(client: &reqwest::Client, url: &str) -> Result<Program, ScrapeError> {
+    let fetch_error = |source| ScrapeError::Fetch {
+        url: url.to_owned(),
+        source,
+    };
+
+    let html = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(fetch_error)?
+        .text()
+        .await
+        .map_err(fetch_error)?;
+
+    parse_program_html(url, &html)
+}
+
+/// Parses the Acalog or Courseleaf program page found at `url`. Relative links to courses are
+/// resolved against `url`.
+pub fn parse_program_html(url: &str, html: &str) -> Result<Program, ScrapeError> {
+    let document = Html::parse_document(html);
+    let page = Page {
+        url,
+        base: Url::parse(url).ok(),
+        credits: Regex::new(r"(\d+(?:\.\d+)?)(?:\s*(?:-|–|to)\s*(\d+(?:\.\d+)?))?")
+            .expect("Credits pattern should be valid"),
+    };
+
+    let (title, modules) = if document
+        .select(&selector("table.sc_courselist"))
+        .next()
+        .is_some()
+    {
+        let title = first_text(&document, "h1.page-title, #page-title, h1");
+        (title, page.courseleaf_modules(&document))
+    } else {
+        let title = first_text(&document, "h1#acalog-content");
+        (title, page.acalog_modules(&document))
+    };
+
+    let title = title.ok_or_else(|| ScrapeError::UnrecognizedFormat {
+        url: url.to_owned(),
+    })?;
+
+    Ok(Program {
+        url: url.to_owned(),
+        guid: derived_guid(url),
+        title,
+        content: None,
+        bottom_content: None,
+        requirements: (!modules.is_empty()).then_some(Requirements::Many(modules)),
+        extra: Extensions::default(),
+    })
+}
+
+/// The page being scraped
+struct Page<'a> {
+    url: &'a str,
+    /// `None` when `url` isn't absolute, in which case links are kept as they are
+    base: Option<Url>,
+    credits: Regex,
+}
+
+impl Page<'_> {
+    /// Acalog starts a module at every core with an `h2` heading. Cores with deeper headings are
+    /// requirements of the module before them.
+    fn acalog_modules(&self, document: &Html) -> Vec<RequirementModule> {
+        let mut modules = vec![];
+
+        for core in document.select(&selector("div.acalog-core")) {
+            // Nested cores are selected on their own, so only the direct children of a core are
+            // part of it
+            let heading = core
+                .child_elements()
+                .find(|child| matches!(child.value().name(), "h2" | "h3" | "h4" | "h5" | "h6"));
+            let heading_text = heading.map(text_of).filter(|text| !text.is_empty());
+
+            let narrative = core
+                .child_elements()
+                .filter(|child| child.value().name() == "p")
+                .map(text_of)
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>();
+
+            let mut entries = Entries::default();
+            let items = core
+                .child_elements()
+                .filter(|child| child.value().name() == "ul")
+                .flat_map(|list| list.child_elements());
+            for item in items {
+                if has_class(item, "acalog-course") {
+                    let link = item.select(&selector("a")).next();
+                    let code = link.map(text_of).unwrap_or_else(|| text_of(item));
+                    let href = link.and_then(|link| link.value().attr("href"));
+                    if let Some(course) = self.course(&code, None, &text_of(item), href) {
+                        entries.push_course(course);
+                    }
+                } else if has_class(item, "acalog-adhoc") {
+                    entries.push_adhoc(self, &text_of(item));
+                }
+            }
+
+            let starts_module = heading.is_some_and(|heading| heading.value().name() == "h2");
+            if starts_module || modules.is_empty() {
+                modules.push(RequirementModule::BasicRequirements {
+                    title: if starts_module {
+                        heading_text.clone()
+                    } else {
+                        None
+                    },
+                    requirements: vec![],
+                });
+            }
+
+            // The heading of a core starting a module is the title of the module
+            let title = if starts_module { None } else { heading_text };
+            if let Some(requirement) = requirement(title, entries.0, narrative) {
+                if let Some(RequirementModule::BasicRequirements { requirements, .. }) =
+                    modules.last_mut()
+                {
+                    requirements.push(requirement);
+                }
+            }
+        }
+
+        modules
+    }
+
+    /// Courseleaf has a module for every course list, titled by the heading before it. Header rows
+    /// split the list into requirements.
+    fn courseleaf_modules(&self, document: &Html) -> Vec<RequirementModule> {
+        let mut modules = vec![];
+
+        for table in document.select(&selector("table.sc_courselist")) {
+            let module_title = table
+                .prev_siblings()
+                .filter_map(ElementRef::wrap)
+                .find(|sibling| matches!(sibling.value().name(), "h2" | "h3" | "h4"))
+                .map(text_of);
+
+            let mut requirements = vec![];
+            let mut current: (Option<String>, Entries) = (None, Entries::default());
+
+            for row in table.select(&selector("tr")) {
+                if has_class(row, "areaheader")
+                    || row.select(&selector(".areaheader")).next().is_some()
+                {
+                    let (title, entries) = std::mem::take(&mut current);
+                    requirements.extend(requirement(title, entries.0, vec![]));
+                    current.0 = Some(text_of(row)).filter(|text| !text.is_empty());
+                    continue;
+                }
+                if has_class(row, "listsum") {
+                    continue;
+                }
+
+                let cells: Vec<ElementRef> = row.child_elements().collect();
+                let code_cell = cells.iter().find(|cell| has_class(**cell, "codecol"));
+
+                match code_cell {
+                    Some(code_cell) => {
+                        let code = text_of(*code_cell);
+                        let (joined, code) = match code.strip_prefix("or ") {
+                            Some(code) => (true, code.to_owned()),
+                            None => (has_class(row, "orclass"), code),
+                        };
+                        let name = cells.get(1).map(|cell| text_of(*cell));
+                        let credits = cells
+                            .iter()
+                            .find(|cell| has_class(**cell, "hourscol"))
+                            .map(|cell| text_of(*cell))
+                            .unwrap_or_default();
+                        let href = code_cell
+                            .select(&selector("a"))
+                            .next()
+                            .and_then(|link| link.value().attr("href"));
+
+                        if let Some(course) = self.course(&code, name, &credits, href) {
+                            if joined {
+                                current.1.push_adhoc(self, "or");
+                            }
+                            current.1.push_course(course);
+                        }
+                    }
+                    None => {
+                        let comment = text_of(row);
+                        if !comment.is_empty() {
+                            current.1.push_adhoc(self, &comment);
+                        }
+                    }
+                }
+            }
+
+            let (title, entries) = current;
+            requirements.extend(requirement(title, entries.0, vec![]));
+
+            modules.push(RequirementModule::BasicRequirements {
+                title: module_title,
+                requirements,
+            });
+        }
+
+        modules
+    }
+
+    /// Course with the `code` (Ex: "CSC 115 - Computer Science I"), taking the name from the code
+    /// when `name` is `None` and the credits from the first number in `credits`
+    fn course(
+        &self,
+        code: &str,
+        name: Option<String>,
+        credits: &str,
+        href: Option<&str>,
+    ) -> Option<Course> {
+        let (code, code_name) = match code.split_once(" - ").or_else(|| code.split_once(" – ")) {
+            Some((code, name)) => (code.trim(), Some(name.trim().to_owned())),
+            None => (code.trim(), None),
+        };
+        let (subject_code, number) = code.rsplit_once(' ')?;
+        let (subject_code, number) = (subject_code.trim(), number.trim());
+        if subject_code.is_empty() || !number.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+
+        // "Credits: 4" in Acalog, where the credits follow the code and name
+        let credits = credits
+            .rsplit_once("Credits")
+            .map_or(credits, |(_, credits)| credits);
+        let credits = self
+            .credits
+            .captures(credits)
+            .map(|captures| {
+                let hours = |i| {
+                    captures
+                        .get(i)
+                        .and_then(|hours| hours.as_str().parse::<f32>().ok())
+                        .map(|hours| hours.trunc().min(u8::MAX as f32) as u8)
+                };
+                (hours(1).unwrap_or(0), hours(2))
+            })
+            .unwrap_or((0, None));
+
+        let url = href.map(|href| self.resolve(href)).unwrap_or_default();
+
+        Some(Course {
+            path: Url::parse(&url)
+                .map(|url| url.path().to_owned())
+                .unwrap_or_default(),
+            url,
+            guid: derived_guid(&format!("{subject_code} {number}").to_uppercase()),
+            name: name.or(code_name).filter(|name| !name.is_empty()),
+            number: number.to_owned(),
+            subject_name: None,
+            subject_code: subject_code.into(),
+            credits,
+            extra: Extensions::default(),
+            details: None,
+        })
+    }
+
+    fn resolve(&self, href: &str) -> String {
+        self.base
+            .as_ref()
+            .and_then(|base| base.join(href).ok())
+            .map_or_else(|| href.to_owned(), String::from)
+    }
+}
+
+/// Course entries of a requirement, in the order they are listed
+#[derive(Debug, Default)]
+struct Entries(Vec<CourseEntry>, bool);
+
+impl Entries {
+    /// Adds a course, grouping it with the entry before it if the two are joined by "or"
+    fn push_course(&mut self, course: Course) {
+        let course = CourseEntry::from(course);
+        let joined = std::mem::take(&mut self.1);
+
+        match self.0.last_mut() {
+            Some(CourseEntry::Or(group)) if joined => group.push(course),
+            Some(last) if joined => {
+                let first = std::mem::replace(last, CourseEntry::Or(CourseEntries::new()));
+                *last = CourseEntry::Or(CourseEntries::from(vec![first, course]));
+            }
+            _ => self.0.push(course),
+        }
+    }
+
+    /// Adds free-form text listed among the courses. "or" joins the courses around it and "and"
+    /// is left out, since the entries of a requirement are all required anyway.
+    fn push_adhoc(&mut self, page: &Page, text: &str) {
+        match text.to_lowercase().as_str() {
+            "or" => self.1 = !self.0.is_empty(),
+            "and" | "" => {}
+            _ => self.0.push(CourseEntry::Label(Label {
+                url: String::new(),
+                guid: derived_guid(&format!("{}#{}", page.url, text)),
+                name: text.to_owned(),
+                number: None,
+                subject_code: None,
+                credits: (0, None),
+            })),
+        }
+    }
+}
+
+/// Requirement with the `entries` the same way the catalog API would spell it, or `None` if there
+/// is nothing to it
+fn requirement(
+    title: Option<String>,
+    entries: Vec<CourseEntry>,
+    narrative: Vec<String>,
+) -> Option<Requirement> {
+    let req_narrative = (!narrative.is_empty()).then(|| narrative.join("\n"));
+    let constraints = [&title, &req_narrative]
+        .into_iter()
+        .flatten()
+        .map(|text| Constraints::from_text(text))
+        .fold(Constraints::default(), Constraints::merge);
+
+    let elective_hours = [&title, &req_narrative]
+        .into_iter()
+        .flatten()
+        .find_map(|text| ElectiveHours::from_text(text));
+
+    let requirement = match (title, entries, elective_hours) {
+        (None, entries, _) if entries.is_empty() && req_narrative.is_none() => return None,
+        (title, entries, Some(hours)) if entries.is_empty() && !is_selection(title.as_deref()) => {
+            Requirement::ElectivePool {
+                title,
+                req_narrative,
+                hours,
+                constraints,
+                extra: Extensions::default(),
+            }
+        }
+        (title, entries, _) if entries.is_empty() => Requirement::Label {
+            title,
+            req_narrative,
+            constraints,
+            course_refs: vec![],
+            extra: Extensions::default(),
+        },
+        (Some(title), entries, _) if title.contains("Select") => Requirement::SelectFromCourses {
+            title,
+            courses: Some(CourseEntries::from(entries)),
+            constraints,
+            extra: Extensions::default(),
+        },
+        (title, entries, _) => Requirement::Courses {
+            title,
+            courses: CourseEntries::from(entries),
+            constraints,
+            extra: Extensions::default(),
+        },
+    };
+
+    Some(requirement)
+}
+
+/// "Select" titles stay selections, since they usually narrow down electives to a subject
+fn is_selection(title: Option<&str>) -> bool {
+    title.is_some_and(|title| title.contains("Select"))
+}
+
+fn selector(selectors: &str) -> Selector {
+    Selector::parse(selectors).expect("Selectors should be valid")
+}
+
+fn first_text(document: &Html, selectors: &str) -> Option<String> {
+    document
+        .select(&selector(selectors))
+        .map(text_of)
+        .find(|text| !text.is_empty())
+}
+
+/// Text of the `element` with whitespace collapsed, including non-breaking spaces
+fn text_of(element: ElementRef) -> String {
+    element
+        .text()
+        .flat_map(|text| text.split(|c: char| c.is_whitespace()))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn has_class(element: ElementRef, class: &str) -> bool {
+    element.value().classes().any(|c| c == class)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ACALOG: &str = r#"
+        <html><body><table><tr><td class="block_content">
+        <h1 id="acalog-content">Computer Science,&nbsp;B.S.</h1>
+        <div class="acalog-core">
+            <h2><a name="Core"></a>Core Requirements (12 hours)</h2>
+            <p>A minimum grade of C is required in all core courses.</p>
+        </div>
+        <div class="acalog-core">
+            <h3>Introductory Courses</h3>
+            <ul>
+                <li class="acalog-course"><span><a href="preview_course_nopop.php?catoid=7&amp;coid=1">CSC 105 - Introduction to Programming</a> <strong>Credits: 3</strong></span></li>
+                <li class="acalog-adhoc acalog-adhoc-after">or</li>
+                <li class="acalog-course"><span><a href="preview_course_nopop.php?catoid=7&amp;coid=2">CSC&nbsp;115 - Computer Science I</a> <strong>Credits: 4</strong></span></li>
+                <li class="acalog-course"><span><a href="preview_course_nopop.php?catoid=7&amp;coid=3">CSC 125 - Computer Science II</a> <strong>Credits: 4</strong></span></li>
+            </ul>
+        </div>
+        <div class="acalog-core">
+            <h2>Electives</h2>
+        </div>
+        <div class="acalog-core">
+            <h3>Select two of the following</h3>
+            <ul>
+                <li class="acalog-course"><span><a href="/preview_course_nopop.php?coid=4">CSC 495 - Research</a> Credits: 1-3</span></li>
+                <li class="acalog-adhoc">Any CSC course at the 300 level</li>
+            </ul>
+        </div>
+        </td></tr></table></body></html>
+    "#;
+
+    const COURSELEAF: &str = r#"
+        <html><body>
+        <h1 class="page-title">Minor in Computer Science</h1>
+        <div id="requirementstextcontainer">
+            <h2>Requirements</h2>
+            <table class="sc_courselist"><tbody>
+                <tr class="areaheader"><td colspan="3"><span class="courselistcomment areaheader">Required Courses</span></td></tr>
+                <tr class="odd"><td class="codecol"><a href="/search/?P=CSC%20115" class="bubblelink code">CSC&nbsp;115</a></td><td>Computer Science I</td><td class="hourscol">4</td></tr>
+                <tr class="even orclass"><td class="codecol">or <a href="/search/?P=CSC%20105" class="bubblelink code">CSC&nbsp;105</a></td><td>Introduction to Programming</td><td class="hourscol"></td></tr>
+                <tr class="odd"><td class="codecol"><a href="/search/?P=CSC%20125" class="bubblelink code">CSC&nbsp;125</a></td><td>Computer Science II</td><td class="hourscol">4</td></tr>
+                <tr class="areaheader"><td colspan="3"><span class="courselistcomment areaheader">Select 6 hours of the following</span></td></tr>
+                <tr class="even"><td class="codecol"><a href="/search/?P=CSC%20310">CSC&nbsp;310</a></td><td>Databases</td><td class="hourscol">3</td></tr>
+                <tr class="listsum"><td colspan="2">Total Hours</td><td class="hourscol">14</td></tr>
+            </tbody></table>
+        </div>
+        </body></html>
+    "#;
+
+    fn codes(entries: &CourseEntries) -> Vec<String> {
+        entries
+            .iter_courses()
+            .map(|course| format!("{} {}", course.subject_code, course.number))
+            .collect()
+    }
+
+    #[test]
+    fn acalog_cores_become_requirements() {
+        let url = "https://catalog.example.edu/preview_program.php?catoid=7&poid=100";
+        let program = parse_program_html(url, ACALOG).unwrap();
+
+        assert_eq!(program.title, "Computer Science, B.S.");
+        assert_eq!(program.guid, derived_guid(url));
+
+        let modules = program.requirements.as_ref().unwrap().modules();
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0].title(), Some("Core Requirements (12 hours)"));
+
+        let core = modules[0].requirements();
+        assert!(matches!(
+            &core[0],
+            Requirement::Label { req_narrative: Some(narrative), constraints, .. }
+                if narrative.contains("grade of C") && constraints.min_grade.is_some()
+        ));
+        let Requirement::Courses { title, courses, .. } = &core[1] else {
+            panic!("Expected courses, got {:?}", core[1]);
+        };
+        assert_eq!(title.as_deref(), Some("Introductory Courses"));
+        assert!(matches!(&courses[0], CourseEntry::Or(group) if group.len() == 2));
+        assert_eq!(codes(courses), ["CSC 105", "CSC 115", "CSC 125"]);
+
+        let csc_115 = courses.iter_courses().nth(1).unwrap();
+        assert_eq!(csc_115.name.as_deref(), Some("Computer Science I"));
+        assert_eq!(csc_115.credits, (4, None));
+        assert_eq!(
+            csc_115.url,
+            "https://catalog.example.edu/preview_course_nopop.php?catoid=7&coid=2"
+        );
+
+        let Requirement::SelectFromCourses {
+            courses: Some(courses),
+            ..
+        } = &modules[1].requirements()[0]
+        else {
+            panic!("Expected a selection, got {:?}", modules[1].requirements());
+        };
+        assert_eq!(courses.iter_courses().next().unwrap().credits, (1, Some(3)));
+        assert!(
+            matches!(&courses[1], CourseEntry::Label(label) if label.name.contains("300 level"))
+        );
+    }
+
+    #[test]
+    fn courseleaf_rows_become_requirements() {
+        let program =
+            parse_program_html("https://catalog.example.edu/minors/cs/", COURSELEAF).unwrap();
+
+        assert_eq!(program.title, "Minor in Computer Science");
+        let modules = program.requirements.as_ref().unwrap().modules();
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0].title(), Some("Requirements"));
+
+        let requirements = modules[0].requirements();
+        assert_eq!(requirements.len(), 2);
+        let Requirement::Courses { courses, .. } = &requirements[0] else {
+            panic!("Expected courses, got {:?}", requirements[0]);
+        };
+        assert!(matches!(&courses[0], CourseEntry::Or(group) if group.len() == 2));
+        assert_eq!(codes(courses), ["CSC 115", "CSC 105", "CSC 125"]);
+        assert!(matches!(
+            &requirements[1],
+            Requirement::SelectFromCourses { .. }
+        ));
+
+        // Courses get the same GUID whichever catalog they are scraped from
+        let acalog = parse_program_html("https://catalog.example.edu/", ACALOG).unwrap();
+        let guid_of = |program: &Program, number: &str| {
+            program
+                .iter_courses()
+                .find(|course| course.number == number)
+                .map(|course| course.guid)
+        };
+        assert_eq!(guid_of(&program, "115"), guid_of(&acalog, "115"));
+        assert_ne!(guid_of(&program, "115"), guid_of(&program, "125"));
+    }
+
+    #[test]
+    fn other_pages_are_rejected() {
+        let error = parse_program_html("https://example.edu", "<html><h1>Home</h1></html>");
+        assert!(matches!(error, Err(ScrapeError::UnrecognizedFormat { .. })));
+    }
+}
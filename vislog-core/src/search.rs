@@ -0,0 +1,383 @@
+//! Ranked full-text search over the courses, programs and requirements of a [Catalog].
+//!
+//! Text is split into lowercase alphanumeric tokens (HTML tags are skipped) and stored in an
+//! inverted index. Every token of a query has to match a document for it to be returned, where a
+//! token matches a term of the document exactly, as a prefix of it (so results show up while
+//! typing), or within a small edit distance of it (so typos are forgiven). Documents are ranked by
+//! TF-IDF, with matches in titles and course codes weighing more than matches in descriptions.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound,
+};
+
+use serde::Serialize;
+
+use crate::{catalog::Catalog, parsing::guid::Guid, text};
+
+/// What a search result points to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum DocumentRef {
+    Course(Guid),
+    Program(Guid),
+    /// Requirement of the program with the given `Guid`, identified by its position in
+    /// [Program::iter_requirements](crate::Program::iter_requirements)
+    Requirement {
+        program: Guid,
+        index: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchHit {
+    pub document: DocumentRef,
+    /// Human readable title of the document. Ex: "CSC 125 Computer Science I"
+    pub title: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    /// Subject code and number of courses
+    Code,
+    Title,
+    Body,
+}
+
+impl Field {
+    fn boost(self) -> f32 {
+        match self {
+            Field::Code => 4.0,
+            Field::Title => 3.0,
+            Field::Body => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Posting {
+    document: usize,
+    field: Field,
+    term_frequency: u32,
+}
+
+/// How much a term matching a query token counts for, depending on how it matched
+const EXACT_MATCH_WEIGHT: f32 = 1.0;
+const PREFIX_MATCH_WEIGHT: f32 = 0.7;
+const FUZZY_MATCH_WEIGHT: f32 = 0.5;
+
+/// In-memory inverted index over a [Catalog]
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    documents: Vec<(DocumentRef, String)>,
+    /// Sorted so that all the terms sharing a prefix can be found with a range scan
+    postings: BTreeMap<String, Vec<Posting>>,
+}
+
+impl SearchIndex {
+    /// Indexes the names and descriptions of all courses, the titles of all programs, and the
+    /// titles and narratives of all requirements in the `catalog`
+    pub fn from_catalog(catalog: &Catalog) -> Self {
+        let mut index = SearchIndex::default();
+
+        for course in &catalog.courses {
+            let code = format!("{} {}", course.subject_code, course.number);
+            let document = index.add_document(
+                DocumentRef::Course(course.guid),
+                format!("{code} {}", course.name),
+            );
+
+            index.add_text(document, Field::Code, &code);
+            index.add_text(document, Field::Title, &course.name);
+            index.add_text(document, Field::Body, &course.description);
+            for narrative in [
+                &course.prerequisite_narrative,
+                &course.corequisite_narrative,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                index.add_text(document, Field::Body, narrative);
+            }
+        }
+
+        for program in &catalog.programs {
+            let document =
+                index.add_document(DocumentRef::Program(program.guid), program.title.clone());
+            index.add_text(document, Field::Title, &program.title);
+
+            for (idx, requirement) in program.iter_requirements().enumerate() {
+                let narrative = requirement.req_narrative();
+
+                let Some(title) = requirement.title().or(narrative) else {
+                    continue;
+                };
+
+                let document = index.add_document(
+                    DocumentRef::Requirement {
+                        program: program.guid,
+                        index: idx,
+                    },
+                    format!("{}: {}", program.title, text::clean_narrative(title)),
+                );
+                index.add_text(document, Field::Title, title);
+                if let Some(narrative) = narrative {
+                    index.add_text(document, Field::Body, narrative);
+                }
+            }
+        }
+
+        index
+    }
+
+    fn add_document(&mut self, document: DocumentRef, title: String) -> usize {
+        self.documents.push((document, title));
+        self.documents.len() - 1
+    }
+
+    fn add_text(&mut self, document: usize, field: Field, text: &str) {
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        for token in tokenize(text) {
+            *frequencies.entry(token).or_default() += 1;
+        }
+
+        for (term, term_frequency) in frequencies {
+            self.postings.entry(term).or_default().push(Posting {
+                document,
+                field,
+                term_frequency,
+            });
+        }
+    }
+
+    /// Number of indexed documents
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// The `limit` best matching documents for the `query`, best first
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let tokens = tokenize(query);
+        if tokens.is_empty() {
+            return vec![];
+        }
+
+        let document_count = self.documents.len() as f32;
+
+        // Score of every document matching all of the tokens seen so far
+        let mut scores: Option<HashMap<usize, f32>> = None;
+
+        for token in &tokens {
+            let mut token_scores: HashMap<usize, f32> = HashMap::new();
+
+            for (term, weight) in self.matching_terms(token) {
+                let postings = &self.postings[term];
+                let idf = (1.0 + document_count / postings.len() as f32).ln();
+
+                for posting in postings {
+                    let tf = 1.0 + (posting.term_frequency as f32).ln();
+                    let score = weight * posting.field.boost() * tf * idf;
+
+                    // Only the best matching term of each token counts towards a document
+                    let best = token_scores.entry(posting.document).or_default();
+                    *best = best.max(score);
+                }
+            }
+
+            scores = Some(match scores {
+                None => token_scores,
+                Some(scores) => scores
+                    .into_iter()
+                    .filter_map(|(document, score)| {
+                        token_scores
+                            .get(&document)
+                            .map(|token_score| (document, score + token_score))
+                    })
+                    .collect(),
+            });
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(document, score)| {
+                let (document, title) = self.documents[document].clone();
+                SearchHit {
+                    document,
+                    title,
+                    score,
+                }
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.title.cmp(&b.title))
+        });
+        hits.truncate(limit);
+
+        hits
+    }
+
+    /// Terms in the index matching the `token` along with the weight of the match
+    fn matching_terms<'a>(&'a self, token: &str) -> Vec<(&'a String, f32)> {
+        let mut terms: HashMap<&String, f32> = HashMap::new();
+
+        for (term, _) in self
+            .postings
+            .range::<str, _>((Bound::Included(token), Bound::Unbounded))
+        {
+            if !term.starts_with(token) {
+                break;
+            }
+
+            let weight = if term == token {
+                EXACT_MATCH_WEIGHT
+            } else {
+                PREFIX_MATCH_WEIGHT
+            };
+            terms.insert(term, weight);
+        }
+
+        let max_edits = max_edits(token);
+        if max_edits > 0 {
+            for term in self.postings.keys() {
+                if terms.contains_key(term) {
+                    continue;
+                }
+
+                if bounded_edit_distance(token, term, max_edits).is_some() {
+                    terms.insert(term, FUZZY_MATCH_WEIGHT);
+                }
+            }
+        }
+
+        terms.into_iter().collect()
+    }
+}
+
+/// Typos allowed in a token, scaled by its length so that short tokens don't match everything
+fn max_edits(token: &str) -> usize {
+    match token.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, or `None` if it is greater than `max`
+fn bounded_edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            row_min = row_min.min(current[j + 1]);
+        }
+
+        if row_min > max {
+            return None;
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    Some(previous[b.len()]).filter(|distance| *distance <= max)
+}
+
+/// Lowercase alphanumeric tokens of the `text`, skipping over HTML tags
+fn tokenize(text: &str) -> Vec<String> {
+    text::strip_tags(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn test_index() -> SearchIndex {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        SearchIndex::from_catalog(&catalog)
+    }
+
+    fn course_guid(s: &str) -> DocumentRef {
+        DocumentRef::Course(Guid::try_from(s).unwrap())
+    }
+
+    #[test]
+    fn tokenizer_skips_html_and_punctuation() {
+        assert_eq!(
+            tokenize("<p>Intro to <b>Java</b>-programming!</p>"),
+            ["intro", "to", "java", "programming"]
+        );
+    }
+
+    #[test]
+    fn edit_distance_is_bounded() {
+        assert_eq!(bounded_edit_distance("java", "jvaa", 2), Some(2));
+        assert_eq!(bounded_edit_distance("java", "java", 0), Some(0));
+        assert_eq!(bounded_edit_distance("java", "python", 2), None);
+    }
+
+    #[test]
+    fn course_code_search_ranks_course_first() {
+        let index = test_index();
+
+        let hits = index.search("CSC 125", 5);
+
+        assert_eq!(
+            hits[0].document,
+            course_guid("13A1385C-81AC-493D-ACE8-AA8AB37D2C81")
+        );
+    }
+
+    #[test]
+    fn prefix_and_fuzzy_matches() {
+        let index = test_index();
+        let csc_125 = course_guid("13A1385C-81AC-493D-ACE8-AA8AB37D2C81");
+
+        let prefix_hits = index.search("programming in jav", 20);
+        assert!(prefix_hits.iter().any(|hit| hit.document == csc_125));
+
+        let fuzzy_hits = index.search("progamming javva", 20);
+        assert!(fuzzy_hits.iter().any(|hit| hit.document == csc_125));
+    }
+
+    #[test]
+    fn programs_are_searchable_by_title() {
+        let index = test_index();
+
+        let hits = index.search("computer science major", 10);
+
+        assert!(hits
+            .iter()
+            .any(|hit| matches!(hit.document, DocumentRef::Program(_))
+                && hit.title == "Major in Computer Science—42 hours"));
+    }
+
+    #[test]
+    fn empty_query_has_no_hits() {
+        assert!(test_index().search("  ", 10).is_empty());
+    }
+}
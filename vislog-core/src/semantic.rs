@@ -0,0 +1,336 @@
+//! Equality of the data model that ignores volatile fields.
+//!
+//! The catalog moves pages around from one year to the next, so the `url` and `path` of a course
+//! can change while the course itself doesn't. [SemanticEq] compares everything else, and is what
+//! the [diff](crate::diff) engine uses to decide whether a program or a requirement changed.
+//!
+//! Ignored fields are:
+//! - `url` and `path` of programs, courses, labels and course details
+//! - the [attached](crate::details) `details` of courses, which are diffed on their own
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{semantic::SemanticEq, Program};
+//! let json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+//! let program: Program = serde_json::from_str(&json).unwrap();
+//!
+//! let mut moved = program.clone();
+//! moved.url = "https://example.edu/minor-in-computer-science".to_string();
+//!
+//! assert_ne!(program, moved);
+//! assert!(program.semantic_eq(&moved));
+//! ```
+
+use std::sync::Arc;
+
+use crate::{
+    Course, CourseDetails, CourseEntries, CourseEntry, Label, Program, Requirement,
+    RequirementModule, Requirements,
+};
+
+/// Equality that ignores the fields listed in the [module](self) documentation
+pub trait SemanticEq {
+    fn semantic_eq(&self, other: &Self) -> bool;
+}
+
+impl<T: SemanticEq + ?Sized> SemanticEq for &T {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        (**self).semantic_eq(*other)
+    }
+}
+
+impl<T: SemanticEq + ?Sized> SemanticEq for Arc<T> {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(self, other) || (**self).semantic_eq(other)
+    }
+}
+
+impl<T: SemanticEq> SemanticEq for Option<T> {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.semantic_eq(b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: SemanticEq> SemanticEq for [T] {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a.semantic_eq(b))
+    }
+}
+
+impl<T: SemanticEq> SemanticEq for Vec<T> {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self.as_slice().semantic_eq(other.as_slice())
+    }
+}
+
+impl SemanticEq for Program {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        let Program {
+            url: _,
+            guid,
+            title,
+            content,
+            bottom_content,
+            requirements,
+            extra,
+        } = self;
+
+        *guid == other.guid
+            && *title == other.title
+            && *content == other.content
+            && *bottom_content == other.bottom_content
+            && requirements.semantic_eq(&other.requirements)
+            && *extra == other.extra
+    }
+}
+
+impl SemanticEq for Requirements {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self.modules().semantic_eq(other.modules())
+            && matches!(self, Requirements::SelectTrack)
+                == matches!(other, Requirements::SelectTrack)
+    }
+}
+
+impl SemanticEq for RequirementModule {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        use RequirementModule as M;
+
+        match (self, other) {
+            (
+                M::SingleBasicRequirement { title, requirement },
+                M::SingleBasicRequirement {
+                    title: other_title,
+                    requirement: other_requirement,
+                },
+            ) => title == other_title && requirement.semantic_eq(other_requirement),
+            (
+                M::BasicRequirements {
+                    title,
+                    requirements,
+                },
+                M::BasicRequirements {
+                    title: other_title,
+                    requirements: other_requirements,
+                },
+            ) => title == other_title && requirements.semantic_eq(other_requirements),
+            (M::SelectOneEmphasis { emphases }, M::SelectOneEmphasis { emphases: other }) => {
+                emphases.semantic_eq(other)
+            }
+            (M::Label { .. }, M::Label { .. })
+            | (M::Unimplemented(_), M::Unimplemented(_))
+            | (M::Reference(_), M::Reference(_)) => self == other,
+            _ => false,
+        }
+    }
+}
+
+impl SemanticEq for Requirement {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        use Requirement as R;
+
+        match (self, other) {
+            (
+                R::Courses {
+                    title,
+                    courses,
+                    constraints,
+                    extra,
+                },
+                R::Courses {
+                    title: other_title,
+                    courses: other_courses,
+                    constraints: other_constraints,
+                    extra: other_extra,
+                },
+            ) => {
+                title == other_title
+                    && courses.semantic_eq(other_courses)
+                    && constraints == other_constraints
+                    && extra == other_extra
+            }
+            (
+                R::SelectFromCourses {
+                    title,
+                    courses,
+                    constraints,
+                    extra,
+                },
+                R::SelectFromCourses {
+                    title: other_title,
+                    courses: other_courses,
+                    constraints: other_constraints,
+                    extra: other_extra,
+                },
+            ) => {
+                title == other_title
+                    && courses.semantic_eq(other_courses)
+                    && constraints == other_constraints
+                    && extra == other_extra
+            }
+            (R::Label { .. }, R::Label { .. })
+            | (R::ElectivePool { .. }, R::ElectivePool { .. }) => self == other,
+            _ => false,
+        }
+    }
+}
+
+impl SemanticEq for CourseEntries {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self.as_slice().semantic_eq(other.as_slice())
+    }
+}
+
+impl SemanticEq for CourseEntry {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (CourseEntry::And(a), CourseEntry::And(b))
+            | (CourseEntry::Or(a), CourseEntry::Or(b)) => a.semantic_eq(b),
+            (CourseEntry::Label(a), CourseEntry::Label(b)) => a.semantic_eq(b),
+            (CourseEntry::Course(a), CourseEntry::Course(b)) => a.semantic_eq(b),
+            _ => false,
+        }
+    }
+}
+
+impl SemanticEq for Course {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        let Course {
+            url: _,
+            path: _,
+            guid,
+            name,
+            number,
+            subject_name,
+            subject_code,
+            credits,
+            extra,
+            details: _,
+        } = self;
+
+        *guid == other.guid
+            && *name == other.name
+            && *number == other.number
+            && *subject_name == other.subject_name
+            && *subject_code == other.subject_code
+            && *credits == other.credits
+            && *extra == other.extra
+    }
+}
+
+impl SemanticEq for Label {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        let Label {
+            url: _,
+            guid,
+            name,
+            number,
+            subject_code,
+            credits,
+        } = self;
+
+        *guid == other.guid
+            && *name == other.name
+            && *number == other.number
+            && *subject_code == other.subject_code
+            && *credits == other.credits
+    }
+}
+
+impl SemanticEq for CourseDetails {
+    fn semantic_eq(&self, other: &Self) -> bool {
+        let CourseDetails {
+            url: _,
+            guid,
+            path: _,
+            subject_code,
+            subject_name,
+            number,
+            name,
+            credits_min,
+            credits_max,
+            description,
+            prerequisite_narrative,
+            prerequisite,
+            corequisite_narrative,
+            corequisite,
+            extra,
+        } = self;
+
+        *guid == other.guid
+            && *subject_code == other.subject_code
+            && *subject_name == other.subject_name
+            && *number == other.number
+            && *name == other.name
+            && *credits_min == other.credits_min
+            && *credits_max == other.credits_max
+            && *description == other.description
+            && *prerequisite_narrative == other.prerequisite_narrative
+            && *prerequisite == other.prerequisite
+            && *corequisite_narrative == other.corequisite_narrative
+            && *corequisite == other.corequisite
+            && *extra == other.extra
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::catalog::Catalog;
+    use crate::test_support::read_program;
+
+    fn first_course_mut(program: &mut Program) -> &mut Course {
+        let requirement = program
+            .requirements
+            .as_mut()
+            .unwrap()
+            .modules_mut()
+            .iter_mut()
+            .flat_map(RequirementModule::requirements_mut)
+            .find(|requirement| requirement.course_entries().is_some())
+            .unwrap();
+        let entry = requirement
+            .course_entries_mut()
+            .unwrap()
+            .iter_mut()
+            .find(|entry| matches!(entry, CourseEntry::Course(_)))
+            .unwrap();
+
+        match entry {
+            CourseEntry::Course(course) => Arc::make_mut(course),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn moved_courses_are_semantically_equal() {
+        let program = read_program("cs_major.json");
+
+        let mut moved = program.clone();
+        let course = first_course_mut(&mut moved);
+        course.url = "https://example.edu/mat-205".into();
+        course.path = "/mat-205".into();
+        assert_ne!(program, moved);
+        assert!(program.semantic_eq(&moved));
+
+        let mut renamed = program.clone();
+        first_course_mut(&mut renamed).name = Some("Discrete Structures".to_string());
+        assert!(!program.semantic_eq(&renamed));
+        assert!(!program.semantic_eq(&read_program("cs_minor.json")));
+    }
+
+    #[test]
+    fn catalogs_with_moved_courses_have_no_diff() {
+        let (catalog, _) = Catalog::parse_file("../data/cs_major.json");
+
+        let mut moved = catalog.clone();
+        first_course_mut(&mut moved.programs[0]).url = "https://example.edu/mat-205".into();
+
+        assert!(crate::diff::diff_catalogs(&catalog, &moved).is_empty());
+        assert!(catalog.programs[0].diff(&moved.programs[0]).is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+//! Requirement modules shared by several programs, such as the general education core.
+//!
+//! The catalog API repeats shared modules inside of every program listing them.
+//! [Catalog::resolve_references] keeps a single copy of every module that is identical in at least
+//! two programs in the [shared_modules](Catalog::shared_modules) of the catalog, and replaces the
+//! copies with a [RequirementModule::Reference] to it. Besides saving memory, a shared module
+//! only has to be changed once for the change to apply to every program referring to it.
+//!
+//! Analyses of a single [Program], such as its [stats](crate::stats) or [audits](crate::audit),
+//! only see the modules of the program itself. Follow references with [Catalog::resolve], or put
+//! the copies back with [Catalog::inline_references] before running them.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::catalog::Catalog;
+//! let (mut catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//! let programs = catalog.programs.clone();
+//!
+//! let references = catalog.resolve_references();
+//! for shared in &catalog.shared_modules {
+//!     let count = catalog.programs_referencing(shared.guid).count();
+//!     println!("{:?} is shared by {count} programs", shared.module.title());
+//! }
+//!
+//! catalog.inline_references();
+//! assert_eq!(catalog.programs, programs);
+//! # assert!(references > 0);
+//! ```
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
+
+use serde::Serialize;
+
+use crate::{
+    catalog::Catalog, hash::derived_guid, parsing::guid::Guid, Program, RequirementModule,
+};
+
+/// A module kept once for every program referring to it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SharedModule {
+    /// Derived from the contents of the module, so the same module gets the same GUID every time
+    pub guid: Guid,
+    pub module: RequirementModule,
+}
+
+impl Catalog {
+    /// Replaces every module that is identical in at least two programs with a reference to a
+    /// single copy in [Catalog::shared_modules], returning the number of modules replaced.
+    ///
+    /// Modules without requirements, such as labels, are left as is. Modules equal to one that is
+    /// already shared are replaced even if a single program lists them, so references can be
+    /// resolved again after adding programs.
+    pub fn resolve_references(&mut self) -> usize {
+        let mut programs_listing: HashMap<&RequirementModule, HashSet<Guid>> = HashMap::new();
+        let mut order = vec![];
+        for program in &self.programs {
+            let modules = program
+                .requirements
+                .iter()
+                .flat_map(|requirements| requirements.modules())
+                .filter(|module| !module.requirements().is_empty());
+
+            for module in modules {
+                let programs = programs_listing.entry(module).or_insert_with(|| {
+                    order.push(module);
+                    HashSet::new()
+                });
+                programs.insert(program.guid);
+            }
+        }
+
+        let mut guids: HashMap<RequirementModule, Guid> = self
+            .shared_modules
+            .iter()
+            .map(|shared| (shared.module.clone(), shared.guid))
+            .collect();
+        let mut new_modules = vec![];
+        for module in order {
+            if programs_listing[module].len() > 1 && !guids.contains_key(module) {
+                let guid = derived_guid(module);
+                guids.insert(module.clone(), guid);
+                new_modules.push(SharedModule {
+                    guid,
+                    module: module.clone(),
+                });
+            }
+        }
+        self.shared_modules.extend(new_modules);
+
+        let mut replaced = 0;
+        let modules = self
+            .programs
+            .iter_mut()
+            .flat_map(|program| program.requirements.iter_mut())
+            .flat_map(|requirements| requirements.modules_mut());
+        for module in modules {
+            if let Some(guid) = guids.get(module) {
+                *module = RequirementModule::Reference(*guid);
+                replaced += 1;
+            }
+        }
+
+        replaced
+    }
+
+    /// The shared module with the `guid`
+    pub fn shared_module(&self, guid: Guid) -> Option<&RequirementModule> {
+        self.shared_modules
+            .iter()
+            .find(|shared| shared.guid == guid)
+            .map(|shared| &shared.module)
+    }
+
+    /// The module a [RequirementModule::Reference] refers to. Other modules, and references to
+    /// modules that aren't shared, are returned as is.
+    pub fn resolve<'a>(&'a self, module: &'a RequirementModule) -> &'a RequirementModule {
+        match module {
+            RequirementModule::Reference(guid) => self.shared_module(*guid).unwrap_or(module),
+            module => module,
+        }
+    }
+
+    /// Programs with a reference to the shared module with the `guid`, in catalog order
+    pub fn programs_referencing(&self, guid: Guid) -> impl Iterator<Item = &Program> {
+        self.programs.iter().filter(move |program| {
+            program
+                .requirements
+                .iter()
+                .flat_map(|requirements| requirements.modules())
+                .any(|module| *module == RequirementModule::Reference(guid))
+        })
+    }
+
+    /// The catalog with every reference replaced by a copy of the module it refers to, only cloned
+    /// when there are shared modules. Used by exports, which write shared modules into every
+    /// program referring to them.
+    pub fn inlined(&self) -> Cow<'_, Catalog> {
+        if self.shared_modules.is_empty() {
+            return Cow::Borrowed(self);
+        }
+
+        let mut catalog = self.clone();
+        catalog.inline_references();
+        Cow::Owned(catalog)
+    }
+
+    /// Replaces every reference with a copy of the module it refers to and empties
+    /// [Catalog::shared_modules], undoing [Catalog::resolve_references]
+    pub fn inline_references(&mut self) {
+        let shared: HashMap<Guid, RequirementModule> = std::mem::take(&mut self.shared_modules)
+            .into_iter()
+            .map(|shared| (shared.guid, shared.module))
+            .collect();
+
+        let modules = self
+            .programs
+            .iter_mut()
+            .flat_map(|program| program.requirements.iter_mut())
+            .flat_map(|requirements| requirements.modules_mut());
+        for module in modules {
+            if let RequirementModule::Reference(guid) = module {
+                if let Some(shared) = shared.get(guid) {
+                    *module = shared.clone();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn identical_modules_are_shared() {
+        let (mut catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let original = catalog.clone();
+
+        let replaced = catalog.resolve_references();
+        assert!(replaced >= 2 * catalog.shared_modules.len());
+        assert!(!catalog.shared_modules.is_empty());
+
+        for shared in &catalog.shared_modules {
+            assert!(catalog.programs_referencing(shared.guid).count() > 1);
+            assert_eq!(shared.guid, derived_guid(&shared.module));
+        }
+
+        // Resolving references of every program gives back the original modules
+        for (program, original) in catalog.programs.iter().zip(&original.programs) {
+            let modules = program.requirements.iter().flat_map(|r| r.modules());
+            let originals = original.requirements.iter().flat_map(|r| r.modules());
+            for (module, original) in modules.zip(originals) {
+                assert_eq!(catalog.resolve(module), original);
+            }
+        }
+
+        // Nothing is left to share the second time around
+        assert_eq!(catalog.clone().resolve_references(), 0);
+
+        catalog.inline_references();
+        assert_eq!(catalog, original);
+    }
+
+    #[test]
+    fn modules_equal_to_a_shared_one_are_replaced() {
+        let (mut catalog, _) = Catalog::parse_dir("../data").unwrap();
+        catalog.resolve_references();
+        let shared = catalog.shared_modules[0].clone();
+        let shared_count = catalog.shared_modules.len();
+
+        let mut program = catalog.programs[0].clone();
+        program.requirements = Some(crate::Requirements::Single(shared.module.clone()));
+        catalog.programs.push(program);
+
+        assert_eq!(catalog.resolve_references(), 1);
+        assert_eq!(catalog.shared_modules.len(), shared_count);
+        assert!(matches!(
+            &catalog.programs.last().unwrap().requirements,
+            Some(crate::Requirements::Single(RequirementModule::Reference(guid))) if *guid == shared.guid
+        ));
+    }
+}
@@ -0,0 +1,367 @@
+//! Passes making a [ProgramGraph] easier to read before it is exported.
+//!
+//! Programs with many courses give graphs with edges crossing everywhere. Every [GraphTransform]
+//! takes something out of the graph:
+//! - [TransitiveReduction] drops the edges implied by longer chains of prerequisites
+//! - [CollapseOr] merges the courses of an `Or` group into a single node
+//! - [PruneBelowLevel] drops the courses below a level, such as the introductory ones
+//!
+//! Transforms compose with [GraphTransform::then], or as a `Vec` of boxed transforms, and run in
+//! the order they are given.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, graph::{PrerequisiteGraph, ProgramGraph}, simplify::*};
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//! let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+//! let program = &catalog.programs[0];
+//!
+//! let mut program_graph = ProgramGraph::new(program, &graph);
+//! CollapseOr::new(program)
+//!     .then(TransitiveReduction)
+//!     .then(PruneBelowLevel(200))
+//!     .apply(&mut program_graph);
+//!
+//! println!("{}", program_graph.to_dot());
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    code::CourseCode,
+    graph::{GraphEdge, ProgramGraph},
+    parsing::guid::Guid,
+    visit::{self, Visit},
+    CourseEntry, Program,
+};
+
+/// A pass over a [ProgramGraph]
+pub trait GraphTransform {
+    fn apply(&self, graph: &mut ProgramGraph);
+
+    /// Runs the `next` transform after this one
+    fn then<T: GraphTransform>(self, next: T) -> Then<Self, T>
+    where
+        Self: Sized,
+    {
+        Then(self, next)
+    }
+}
+
+/// Two transforms run one after the other, see [GraphTransform::then]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Then<A, B>(pub A, pub B);
+
+impl<A: GraphTransform, B: GraphTransform> GraphTransform for Then<A, B> {
+    fn apply(&self, graph: &mut ProgramGraph) {
+        self.0.apply(graph);
+        self.1.apply(graph);
+    }
+}
+
+impl GraphTransform for Vec<Box<dyn GraphTransform>> {
+    fn apply(&self, graph: &mut ProgramGraph) {
+        for transform in self {
+            transform.apply(graph);
+        }
+    }
+}
+
+/// Drops every edge from a prerequisite to a course that also requires it through a longer chain.
+/// Ex: with `CSC 115 -> CSC 125 -> CSC 225`, an edge `CSC 115 -> CSC 225` is dropped.
+///
+/// Edges are only dropped while another path remains, so courses reachable from one another
+/// before the pass still are after it, even in prerequisite cycles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransitiveReduction;
+
+impl GraphTransform for TransitiveReduction {
+    fn apply(&self, graph: &mut ProgramGraph) {
+        let mut i = 0;
+        while i < graph.edges.len() {
+            if has_longer_path(&graph.edges, i) {
+                graph.edges.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+/// Whether the edge at `index` goes to a course that can be reached from its prerequisite through
+/// other edges
+fn has_longer_path(edges: &[GraphEdge], index: usize) -> bool {
+    let edge = &edges[index];
+
+    let mut seen = HashSet::from([edge.from]);
+    let mut stack = vec![edge.from];
+    while let Some(from) = stack.pop() {
+        for (i, next) in edges.iter().enumerate() {
+            if i == index || next.from != from {
+                continue;
+            }
+            if next.to == edge.to {
+                return true;
+            }
+            if seen.insert(next.to) {
+                stack.push(next.to);
+            }
+        }
+    }
+
+    false
+}
+
+/// Merges the courses of every `Or` group of a program into a single node, labeled with the codes
+/// of the courses joined by "or". The node keeps the GUID of the first course of the group and
+/// lists every course in its [alternatives](crate::graph::GraphNode::alternatives).
+///
+/// Only the courses of a group that are in the graph are merged, and groups with fewer than two
+/// of them are left alone. A course in several groups is merged into the first one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CollapseOr {
+    groups: Vec<Vec<Guid>>,
+}
+
+impl CollapseOr {
+    /// Collapses the `Or` groups of the `program`, which should be the program of the graph
+    pub fn new(program: &Program) -> Self {
+        let mut collector = OrGroups::default();
+        collector.visit_program(program);
+
+        Self {
+            groups: collector.groups,
+        }
+    }
+}
+
+#[derive(Default)]
+struct OrGroups {
+    groups: Vec<Vec<Guid>>,
+}
+
+impl<'a> Visit<'a> for OrGroups {
+    fn visit_course_entry(&mut self, entry: &'a CourseEntry) {
+        if let CourseEntry::Or(group) = entry {
+            self.groups.push(
+                group
+                    .iter()
+                    .filter_map(|entry| match entry {
+                        CourseEntry::Course(course) => Some(course.guid),
+                        _ => None,
+                    })
+                    .collect(),
+            );
+        }
+
+        visit::walk_course_entry(self, entry);
+    }
+}
+
+impl GraphTransform for CollapseOr {
+    fn apply(&self, graph: &mut ProgramGraph) {
+        // Node every merged course was merged into
+        let mut merged_into: HashMap<Guid, Guid> = HashMap::new();
+
+        for group in &self.groups {
+            let mut members: Vec<Guid> = vec![];
+            for guid in group {
+                let is_node = graph.nodes.iter().any(|node| node.guid == *guid);
+                if is_node && !merged_into.contains_key(guid) && !members.contains(guid) {
+                    members.push(*guid);
+                }
+            }
+            let [first, rest @ ..] = members.as_slice() else {
+                continue;
+            };
+            if rest.is_empty() {
+                continue;
+            }
+
+            let alternatives: Vec<_> = graph
+                .nodes
+                .iter()
+                .filter(|node| members.contains(&node.guid))
+                .cloned()
+                .collect();
+            graph
+                .nodes
+                .retain(|node| node.guid == *first || !members.contains(&node.guid));
+
+            let node = graph
+                .nodes
+                .iter_mut()
+                .find(|node| node.guid == *first)
+                .expect("the first member is a node of the graph");
+            node.label = alternatives
+                .iter()
+                .map(|node| node.label.as_str())
+                .collect::<Vec<_>>()
+                .join(" or ");
+            node.name = None;
+            node.depth = alternatives
+                .iter()
+                .map(|node| node.depth)
+                .min()
+                .unwrap_or(0);
+            node.alternatives = members.clone();
+
+            for guid in &members {
+                merged_into.insert(*guid, *first);
+            }
+        }
+
+        if merged_into.is_empty() {
+            return;
+        }
+
+        let mut seen = HashSet::new();
+        let edges = std::mem::take(&mut graph.edges);
+        graph.edges = edges
+            .into_iter()
+            .map(|edge| GraphEdge {
+                from: merged_into.get(&edge.from).copied().unwrap_or(edge.from),
+                to: merged_into.get(&edge.to).copied().unwrap_or(edge.to),
+            })
+            .filter(|edge| edge.from != edge.to && seen.insert((edge.from, edge.to)))
+            .collect();
+    }
+}
+
+/// Drops the courses below a level, and their edges. Ex: `PruneBelowLevel(200)` drops the 100
+/// level courses. Courses with numbers that don't [parse](CourseCode) are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruneBelowLevel(pub u16);
+
+impl GraphTransform for PruneBelowLevel {
+    fn apply(&self, graph: &mut ProgramGraph) {
+        let mut pruned = HashSet::new();
+        graph.nodes.retain(|node| {
+            let keep = CourseCode::new(&node.subject_code, &node.number)
+                .map_or(true, |code| code.level() >= self.0);
+            if !keep {
+                pruned.insert(node.guid);
+            }
+            keep
+        });
+
+        graph
+            .edges
+            .retain(|edge| !pruned.contains(&edge.from) && !pruned.contains(&edge.to));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{catalog::Catalog, graph::GraphNode};
+
+    fn node(guid: Guid, code: &str) -> GraphNode {
+        let (subject_code, number) = code.split_once(' ').unwrap();
+        GraphNode {
+            guid,
+            label: code.to_owned(),
+            name: None,
+            depth: 0,
+            subject_code: subject_code.to_owned(),
+            number: number.to_owned(),
+            color: None,
+            alternatives: vec![],
+        }
+    }
+
+    fn guid(n: u8) -> Guid {
+        Guid::try_from(format!("00000000-0000-0000-0000-0000000000{n:02}").as_str()).unwrap()
+    }
+
+    fn edge(from: u8, to: u8) -> GraphEdge {
+        GraphEdge {
+            from: guid(from),
+            to: guid(to),
+        }
+    }
+
+    /// 1 -> 2 -> 3, 1 -> 3 and 3 -> 4
+    fn chain() -> ProgramGraph {
+        ProgramGraph {
+            title: "Chain".to_owned(),
+            nodes: vec![
+                node(guid(1), "CSC 115"),
+                node(guid(2), "CSC 125"),
+                node(guid(3), "CSC 225"),
+                node(guid(4), "CSC 325"),
+            ],
+            edges: vec![edge(1, 2), edge(1, 3), edge(2, 3), edge(3, 4)],
+        }
+    }
+
+    #[test]
+    fn implied_edges_are_dropped() {
+        let mut graph = chain();
+        TransitiveReduction.apply(&mut graph);
+        assert_eq!(graph.edges, [edge(1, 2), edge(2, 3), edge(3, 4)]);
+
+        // Only one edge of a cycle with a shortcut is dropped
+        let mut cycle = chain();
+        cycle.edges = vec![edge(1, 2), edge(2, 1), edge(1, 3), edge(2, 3)];
+        TransitiveReduction.apply(&mut cycle);
+        assert_eq!(cycle.edges, [edge(1, 2), edge(2, 1), edge(2, 3)]);
+    }
+
+    #[test]
+    fn transforms_compose_in_order() {
+        let mut graph = chain();
+        TransitiveReduction
+            .then(PruneBelowLevel(200))
+            .apply(&mut graph);
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.edges, [edge(3, 4)]);
+
+        // Boxed transforms run in the order of the vec
+        let mut graph = chain();
+        let transforms: Vec<Box<dyn GraphTransform>> = vec![
+            Box::new(PruneBelowLevel(200)),
+            Box::new(TransitiveReduction),
+        ];
+        transforms.apply(&mut graph);
+        assert_eq!(graph.edges, [edge(3, 4)]);
+    }
+
+    #[test]
+    fn or_groups_become_a_single_node() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let (program, groups) = catalog
+            .programs
+            .iter()
+            .map(|program| (program, CollapseOr::new(program)))
+            .find(|(_, collapse)| collapse.groups.iter().any(|group| group.len() >= 2))
+            .unwrap();
+        let graph = crate::graph::PrerequisiteGraph::from_course_details(&catalog.courses);
+
+        let mut collapsed = ProgramGraph::new(program, &graph);
+        let before = collapsed.nodes.len();
+        groups.apply(&mut collapsed);
+
+        let merged: Vec<_> = collapsed
+            .nodes
+            .iter()
+            .filter(|node| !node.alternatives.is_empty())
+            .collect();
+        assert!(!merged.is_empty());
+        assert!(merged.iter().all(|node| node.label.contains(" or ")));
+        assert_eq!(
+            collapsed.nodes.len()
+                + merged
+                    .iter()
+                    .map(|node| node.alternatives.len() - 1)
+                    .sum::<usize>(),
+            before
+        );
+
+        let guids: HashSet<_> = collapsed.nodes.iter().map(|node| node.guid).collect();
+        assert!(collapsed
+            .edges
+            .iter()
+            .all(|edge| guids.contains(&edge.from) && guids.contains(&edge.to)));
+    }
+}
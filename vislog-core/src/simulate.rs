@@ -0,0 +1,349 @@
+//! Monte-Carlo simulation of a cohort of students going through a [Program].
+//!
+//! Every simulated student takes the courses of the program term after term, like a
+//! [layered plan](crate::planner::layered_plan) would: courses whose prerequisites are passed and
+//! that are offered in the term, longest chains of dependent courses first, up to a maximum number
+//! of credits. Each attempt of a course passes with the [pass rate](SimulationOptions::pass_rates)
+//! of the course, and failed courses are taken again in a later term. A student graduates once
+//! every course of the program is passed.
+//!
+//! Like for plans, every course listed by the program is taken, including each option of an `Or`
+//! group, and prerequisites outside of the program are assumed to be taken whenever they are
+//! needed.
+//!
+//! The [SimulationReport] gives the median number of terms it takes to graduate, and ranks the
+//! courses by the failures holding students back.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, graph::PrerequisiteGraph, simulate::{self, SimulationOptions}};
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//! let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+//!
+//! let options = SimulationOptions {
+//!     students: 200,
+//!     default_pass_rate: 0.85,
+//!     ..Default::default()
+//! };
+//! let report = simulate::simulate(&catalog.programs[0], &graph, None, &options);
+//!
+//! assert_eq!(report.students, 200);
+//! println!("Median time to degree: {:?} terms", report.median_terms);
+//! for course in report.bottlenecks.iter().take(5) {
+//!     println!("{}: {} failures", course.label, course.failures);
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::{
+    graph::PrerequisiteGraph, parsing::guid::Guid, planner::heights, schedule::OfferingCalendar,
+    Course, Program,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationOptions {
+    /// Number of students in the cohort
+    pub students: u32,
+    /// Most credits a student takes in a term. A course worth more is taken alone.
+    pub max_credits: u32,
+    /// Students that haven't graduated after this many terms drop out
+    pub max_terms: u32,
+    /// Chance of passing a course, between 0 and 1, by the GUID of the course
+    pub pass_rates: HashMap<Guid, f64>,
+    /// Chance of passing the courses that aren't in the `pass_rates`
+    pub default_pass_rate: f64,
+    /// Seed of the random numbers, so that simulations can be repeated
+    pub seed: u64,
+}
+
+impl Default for SimulationOptions {
+    fn default() -> Self {
+        Self {
+            students: 1000,
+            max_credits: 15,
+            max_terms: 24,
+            pass_rates: HashMap::new(),
+            default_pass_rate: 0.9,
+            seed: 0,
+        }
+    }
+}
+
+impl SimulationOptions {
+    fn pass_rate(&self, guid: &Guid) -> f64 {
+        self.pass_rates
+            .get(guid)
+            .copied()
+            .unwrap_or(self.default_pass_rate)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SimulationReport {
+    pub title: String,
+    pub students: u32,
+    /// Students that passed every course within the maximum number of terms
+    pub graduated: u32,
+    /// Median number of terms the graduates took, `None` when nobody graduated
+    pub median_terms: Option<u32>,
+    /// Number of graduates by the number of terms they took, from 0 terms up to the most terms
+    /// a graduate took
+    pub terms_histogram: Vec<u32>,
+    /// Every course of the program, ranked by [impact](CourseFailures::impact)
+    pub bottlenecks: Vec<CourseFailures>,
+}
+
+/// How often the students of a cohort failed a course
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CourseFailures {
+    pub guid: Guid,
+    /// Subject code and number of the course. Ex: "CSC 125"
+    pub label: String,
+    pub attempts: u32,
+    pub failures: u32,
+    /// Number of courses of the program in the longest chain of courses requiring the course
+    pub dependents: u32,
+    /// Failures weighted by the chain of courses waiting on the course, `failures * (1 +
+    /// dependents)`, since failing a course early in a long chain pushes back graduation the most
+    pub impact: u32,
+}
+
+/// Simulates a cohort of students going through the `program`, taking courses in the terms of
+/// the `calendar` they are offered in, or every term without one
+pub fn simulate(
+    program: &Program,
+    graph: &PrerequisiteGraph,
+    calendar: Option<&OfferingCalendar>,
+    options: &SimulationOptions,
+) -> SimulationReport {
+    let mut seen = HashSet::new();
+    let courses: Vec<&Course> = program
+        .iter_courses()
+        .filter(|course| seen.insert(course.guid))
+        .collect();
+
+    let prerequisites: HashMap<Guid, Vec<Guid>> = courses
+        .iter()
+        .map(|course| {
+            let prerequisites = graph
+                .prerequisites_of(&course.guid)
+                .iter()
+                .filter(|prerequisite| seen.contains(prerequisite))
+                .copied()
+                .collect();
+            (course.guid, prerequisites)
+        })
+        .collect();
+
+    // Longest chains first, ties in the order of the program
+    let heights = heights(&courses, &prerequisites);
+    let mut by_priority = courses.clone();
+    by_priority.sort_by_key(|course| std::cmp::Reverse(heights[&course.guid]));
+
+    // Whether each course is offered in each term, which is the same for every student
+    let offered: Vec<Vec<bool>> = match calendar {
+        Some(calendar) => calendar
+            .terms()
+            .take(options.max_terms as usize)
+            .map(|term| {
+                by_priority
+                    .iter()
+                    .map(|course| calendar.is_offered(&course.subject_code, &course.number, term))
+                    .collect()
+            })
+            .collect(),
+        None => vec![vec![true; by_priority.len()]; options.max_terms as usize],
+    };
+
+    let mut rng = SplitMix64(options.seed);
+    let mut attempts: HashMap<Guid, u32> = HashMap::new();
+    let mut failures: HashMap<Guid, u32> = HashMap::new();
+    let mut graduation_terms = vec![];
+
+    for _ in 0..options.students {
+        let mut passed: HashSet<Guid> = HashSet::new();
+        if by_priority.is_empty() {
+            graduation_terms.push(0);
+            continue;
+        }
+
+        for (term, offered) in offered.iter().enumerate() {
+            if passed.len() == by_priority.len() {
+                break;
+            }
+
+            let mut credits = 0;
+            let mut taken = vec![];
+            for (course, offered) in by_priority.iter().zip(offered) {
+                let ready = *offered
+                    && !passed.contains(&course.guid)
+                    && prerequisites[&course.guid]
+                        .iter()
+                        .all(|prerequisite| passed.contains(prerequisite));
+                let fits = taken.is_empty()
+                    || credits + u32::from(course.credits.0) <= options.max_credits;
+                if ready && fits {
+                    credits += u32::from(course.credits.0);
+                    taken.push(course.guid);
+                }
+            }
+
+            // Grades come out at the end of the term, after every course of it is taken
+            for guid in taken {
+                *attempts.entry(guid).or_default() += 1;
+                if rng.next_f64() < options.pass_rate(&guid) {
+                    passed.insert(guid);
+                } else {
+                    *failures.entry(guid).or_default() += 1;
+                }
+            }
+
+            if passed.len() == by_priority.len() {
+                graduation_terms.push(term as u32 + 1);
+            }
+        }
+    }
+
+    graduation_terms.sort_unstable();
+    let median_terms = match graduation_terms.len() {
+        0 => None,
+        len => Some(graduation_terms[(len - 1) / 2]),
+    };
+    let mut terms_histogram = vec![
+        0;
+        graduation_terms
+            .last()
+            .map_or(0, |terms| *terms as usize + 1)
+    ];
+    for terms in &graduation_terms {
+        terms_histogram[*terms as usize] += 1;
+    }
+
+    let mut bottlenecks: Vec<CourseFailures> = courses
+        .iter()
+        .map(|course| {
+            let failures = failures.get(&course.guid).copied().unwrap_or(0);
+            let dependents = heights[&course.guid];
+            CourseFailures {
+                guid: course.guid,
+                label: format!("{} {}", course.subject_code, course.number),
+                attempts: attempts.get(&course.guid).copied().unwrap_or(0),
+                failures,
+                dependents,
+                impact: failures * (1 + dependents),
+            }
+        })
+        .collect();
+    bottlenecks.sort_by_key(|course| std::cmp::Reverse(course.impact));
+
+    SimulationReport {
+        title: program.title.clone(),
+        students: options.students,
+        graduated: graduation_terms.len() as u32,
+        median_terms,
+        terms_histogram,
+        bottlenecks,
+    }
+}
+
+/// Small, seedable generator of random numbers, see
+/// [SplitMix64](https://prng.di.unimi.it/splitmix64.c)
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        catalog::Catalog,
+        schedule::{Availability, Season, Years},
+    };
+
+    fn cs_minor() -> (Program, PrerequisiteGraph) {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let graph = PrerequisiteGraph::from_course_details(&catalog.courses);
+        let program = catalog
+            .programs
+            .into_iter()
+            .find(|program| program.title.starts_with("Minor in Computer Science"))
+            .unwrap();
+
+        (program, graph)
+    }
+
+    #[test]
+    fn students_graduate_later_when_they_fail() {
+        let (program, graph) = cs_minor();
+        let simulate = |default_pass_rate| {
+            let options = SimulationOptions {
+                students: 300,
+                default_pass_rate,
+                ..Default::default()
+            };
+            simulate(&program, &graph, None, &options)
+        };
+
+        let perfect = simulate(1.0);
+        assert_eq!(perfect.graduated, 300);
+        assert!(perfect
+            .bottlenecks
+            .iter()
+            .all(|course| course.failures == 0));
+        assert_eq!(perfect.terms_histogram.iter().sum::<u32>(), 300);
+
+        let struggling = simulate(0.6);
+        assert!(struggling.median_terms > perfect.median_terms);
+        assert!(struggling.bottlenecks[0].impact > 0);
+        assert!(struggling
+            .bottlenecks
+            .windows(2)
+            .all(|pair| pair[0].impact >= pair[1].impact));
+
+        assert_eq!(simulate(0.6), struggling);
+        assert_eq!(simulate(0.0).graduated, 0);
+        assert_eq!(simulate(0.0).median_terms, None);
+    }
+
+    #[test]
+    fn courses_that_are_rarely_offered_slow_students_down() {
+        let (program, graph) = cs_minor();
+        let options = SimulationOptions {
+            students: 50,
+            default_pass_rate: 1.0,
+            ..Default::default()
+        };
+
+        let mut calendar =
+            OfferingCalendar::new("Fall 2024".parse().unwrap(), [Season::Fall, Season::Spring]);
+        let every_term = simulate(&program, &graph, Some(&calendar), &options);
+
+        let course = program.iter_courses().next().unwrap();
+        calendar.set_availability(
+            &course.subject_code,
+            &course.number,
+            Availability::new([Season::Spring], Years::Even),
+        );
+        let rarely = simulate(&program, &graph, Some(&calendar), &options);
+
+        assert!(rarely.median_terms > every_term.median_terms);
+    }
+}
@@ -0,0 +1,273 @@
+use std::{collections::HashSet, ops::Add};
+
+use serde::Serialize;
+
+use crate::{
+    electives::ElectiveHours,
+    visit::{self, Visit},
+    CourseEntries, CourseEntry, Label, Program, Requirement, RequirementModule, Requirements,
+};
+
+/// Summary of the size and shape of a [Program]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ProgramStats {
+    pub requirement_modules: usize,
+    pub requirements: usize,
+    /// Number of courses with a distinct `Guid`
+    pub distinct_courses: usize,
+    pub labels: usize,
+    pub credits: CreditRange,
+    /// Deepest nesting of `And`/`Or` groups. 0 when there are no groups at all.
+    pub max_nesting_depth: usize,
+}
+
+/// Inclusive range of credit hours
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CreditRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl CreditRange {
+    fn from_credits((min, max): (u8, Option<u8>)) -> Self {
+        Self {
+            min: min as u32,
+            max: max.unwrap_or(min) as u32,
+        }
+    }
+
+    /// The range of picking exactly one of the `options`
+    fn one_of(options: impl IntoIterator<Item = CreditRange>) -> Self {
+        options
+            .into_iter()
+            .reduce(|a, b| CreditRange {
+                min: a.min.min(b.min),
+                max: a.max.max(b.max),
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl Add for CreditRange {
+    type Output = CreditRange;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        CreditRange {
+            min: self.min + rhs.min,
+            max: self.max + rhs.max,
+        }
+    }
+}
+
+impl Program {
+    /// Computes the [ProgramStats] of the program.
+    ///
+    /// The credit range is an estimate of the credit hours needed to complete the program based
+    /// on the courses listed in its requirements: `And` groups need every entry, while `Or`
+    /// groups, `SelectFromCourses` requirements and `SelectOneEmphasis` modules need exactly one
+    /// of their entries. [ElectivePool](Requirement::ElectivePool) requirements add their hours,
+    /// or raise the range to the total they name.
+    pub fn stats(&self) -> ProgramStats {
+        let mut counter = NodeCounter::default();
+        counter.visit_program(self);
+
+        let distinct_courses = self
+            .iter_courses()
+            .map(|course| course.guid)
+            .collect::<HashSet<_>>()
+            .len();
+
+        let credits = self
+            .requirements
+            .iter()
+            .flat_map(Requirements::modules)
+            .map(module_credits)
+            .fold(CreditRange::default(), Add::add);
+        let credits = match self.total_hours() {
+            Some(total) => CreditRange {
+                min: credits.min.max(total),
+                max: credits.max.max(total),
+            },
+            None => credits,
+        };
+
+        ProgramStats {
+            requirement_modules: counter.requirement_modules,
+            requirements: counter.requirements,
+            distinct_courses,
+            labels: counter.labels,
+            credits,
+            max_nesting_depth: counter.max_nesting_depth,
+        }
+    }
+
+    /// The largest total of hours that the electives of the program make up for. Ex: "Electives
+    /// to total 128 hours"
+    pub(crate) fn total_hours(&self) -> Option<u32> {
+        self.iter_requirements()
+            .filter_map(|requirement| match requirement {
+                Requirement::ElectivePool {
+                    hours: ElectiveHours::ToTotal(total),
+                    ..
+                } => Some(*total),
+                _ => None,
+            })
+            .max()
+    }
+}
+
+#[derive(Default)]
+struct NodeCounter {
+    requirement_modules: usize,
+    requirements: usize,
+    labels: usize,
+    nesting_depth: usize,
+    max_nesting_depth: usize,
+}
+
+impl<'a> Visit<'a> for NodeCounter {
+    fn visit_requirement_module(&mut self, module: &'a RequirementModule) {
+        self.requirement_modules += 1;
+        visit::walk_requirement_module(self, module);
+    }
+
+    fn visit_requirement(&mut self, requirement: &'a Requirement) {
+        self.requirements += 1;
+        visit::walk_requirement(self, requirement);
+    }
+
+    fn visit_course_entry(&mut self, entry: &'a CourseEntry) {
+        let is_group = matches!(entry, CourseEntry::And(_) | CourseEntry::Or(_));
+
+        if is_group {
+            self.nesting_depth += 1;
+            self.max_nesting_depth = self.max_nesting_depth.max(self.nesting_depth);
+        }
+
+        visit::walk_course_entry(self, entry);
+
+        if is_group {
+            self.nesting_depth -= 1;
+        }
+    }
+
+    fn visit_label(&mut self, _label: &'a Label) {
+        self.labels += 1;
+    }
+}
+
+fn module_credits(module: &RequirementModule) -> CreditRange {
+    let requirements = module.requirements().iter().map(requirement_credits);
+
+    match module {
+        RequirementModule::SelectOneEmphasis { .. } => CreditRange::one_of(requirements),
+        _ => requirements.fold(CreditRange::default(), Add::add),
+    }
+}
+
+fn requirement_credits(requirement: &Requirement) -> CreditRange {
+    match requirement {
+        Requirement::Courses { courses, .. } => all_of(courses),
+        Requirement::SelectFromCourses {
+            courses: Some(courses),
+            ..
+        } => CreditRange::one_of(courses.iter().map(entry_credits)),
+        Requirement::ElectivePool {
+            hours: ElectiveHours::Hours(hours),
+            ..
+        } => CreditRange {
+            min: *hours,
+            max: *hours,
+        },
+        // Counted by `Program::stats` once the rest of the program is known
+        Requirement::ElectivePool {
+            hours: ElectiveHours::ToTotal(_),
+            ..
+        }
+        | Requirement::SelectFromCourses { courses: None, .. }
+        | Requirement::Label { .. } => CreditRange::default(),
+    }
+}
+
+fn all_of(entries: &CourseEntries) -> CreditRange {
+    entries
+        .iter()
+        .map(entry_credits)
+        .fold(CreditRange::default(), Add::add)
+}
+
+fn entry_credits(entry: &CourseEntry) -> CreditRange {
+    match entry {
+        CourseEntry::And(entries) => all_of(entries),
+        CourseEntry::Or(entries) => CreditRange::one_of(entries.iter().map(entry_credits)),
+        CourseEntry::Label(label) => CreditRange::from_credits(label.credits),
+        CourseEntry::Course(course) => CreditRange::from_credits(course.credits),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::read_program;
+
+    #[test]
+    fn stats_of_cs_minor() {
+        let stats = read_program("cs_minor.json").stats();
+
+        assert_eq!(stats.requirement_modules, 1);
+        assert_eq!(stats.requirements, 3);
+        // "Select one track" is an `Or` group of `And` groups
+        assert_eq!(stats.max_nesting_depth, 2);
+        assert!(stats.credits.min <= stats.credits.max);
+    }
+
+    #[test]
+    fn credits_of_flat_program_are_the_sum_of_its_courses() {
+        let program = read_program("cybersecurity_major.json");
+        let stats = program.stats();
+
+        let min_credits: u32 = program
+            .iter_courses()
+            .map(|course| course.credits.0 as u32)
+            .sum();
+
+        assert_eq!(stats.max_nesting_depth, 0);
+        assert_eq!(stats.labels, 0);
+        assert_eq!(stats.credits.min, min_credits);
+    }
+
+    #[test]
+    fn elective_pools_count_toward_credits() {
+        let mut program = read_program("cs_minor.json");
+        let credits = program.stats().credits;
+
+        let pool = |hours| Requirement::ElectivePool {
+            title: None,
+            req_narrative: None,
+            hours,
+            constraints: Default::default(),
+            extra: Default::default(),
+        };
+        let requirements =
+            program.requirements.as_mut().unwrap().modules_mut()[0].requirements_mut();
+        requirements[0] = pool(ElectiveHours::Hours(6));
+        let with_hours = program.stats().credits;
+        assert!(with_hours.min >= 6);
+
+        let requirements =
+            program.requirements.as_mut().unwrap().modules_mut()[0].requirements_mut();
+        requirements[0] = pool(ElectiveHours::ToTotal(128));
+        assert_eq!(program.stats().credits, CreditRange { min: 128, max: 128 });
+        assert!(credits.max < 128);
+    }
+
+    #[test]
+    fn or_group_needs_one_of_its_options() {
+        let credits = CreditRange::one_of([
+            CreditRange { min: 3, max: 3 },
+            CreditRange { min: 1, max: 4 },
+        ]);
+
+        assert_eq!(credits, CreditRange { min: 1, max: 4 });
+    }
+}
@@ -0,0 +1,1141 @@
+//! SQLite persistence of a [Catalog], so that a parsed catalog can be loaded back without parsing
+//! its JSON again.
+//!
+//! Unlike the [SQL export](crate::export::sql), the schema of the store keeps everything needed to
+//! rebuild the exact same [Catalog], including the order of the programs and courses. Courses are
+//! indexed by GUID and by subject code and number so that single courses can be looked up without
+//! loading the whole catalog.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, store::CatalogStore};
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//!
+//! let mut store = CatalogStore::open_in_memory().unwrap();
+//! store.save(&catalog).unwrap();
+//!
+//! assert_eq!(store.load().unwrap(), catalog);
+//! ```
+
+use std::{collections::HashMap, path::Path};
+
+use rusqlite::{
+    params,
+    types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef},
+    Connection, OptionalExtension, Row, ToSql, Transaction,
+};
+use thiserror::Error;
+
+use crate::{
+    catalog::Catalog,
+    constraints::{Constraints, Gpa},
+    electives::ElectiveHours,
+    extensions::Extensions,
+    parsing::guid::Guid,
+    references::CourseRef,
+    shared::SharedModule,
+    symbol::Symbol,
+    Course, CourseDetails, CourseEntries, CourseEntry, Label, Program, Requirement,
+    RequirementModule, Requirements,
+};
+
+/// Version of [SCHEMA], kept in the `user_version` of the database
+const SCHEMA_VERSION: i64 = 6;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS programs (
+    guid TEXT PRIMARY KEY,
+    position INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    url TEXT NOT NULL,
+    content TEXT,
+    bottom_content TEXT,
+    -- `Single`, `Many` or `SelectTrack`. NULL when the program has no requirements
+    requirements_kind TEXT,
+    -- JSON object of the fields vislog doesn't parse yet. NULL when there are none
+    extra TEXT
+);
+
+CREATE TABLE IF NOT EXISTS requirement_modules (
+    id INTEGER PRIMARY KEY,
+    -- NULL for shared modules, which belong to no program
+    program_guid TEXT REFERENCES programs (guid) ON DELETE CASCADE,
+    -- GUID of a shared module of the catalog
+    shared_guid TEXT UNIQUE,
+    position INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    title TEXT,
+    -- JSON of `Unimplemented` modules, or the GUID of the shared module of a `Reference`
+    data TEXT,
+    CHECK ((program_guid IS NULL) != (shared_guid IS NULL))
+);
+
+CREATE TABLE IF NOT EXISTS requirements (
+    id INTEGER PRIMARY KEY,
+    module_id INTEGER NOT NULL REFERENCES requirement_modules (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    title TEXT,
+    narrative TEXT,
+    -- Whether a `SelectFromCourses` requirement has a list of courses, even an empty one
+    has_courses INTEGER NOT NULL,
+    -- Letter grade such as `C` or `B+`
+    min_grade TEXT,
+    -- GPA in hundredths of a point
+    min_gpa INTEGER,
+    -- Hours of an `ElectivePool`, either on their own or the total the electives make up
+    elective_hours INTEGER,
+    elective_total INTEGER,
+    extra TEXT
+);
+
+CREATE TABLE IF NOT EXISTS course_entries (
+    id INTEGER PRIMARY KEY,
+    requirement_id INTEGER NOT NULL REFERENCES requirements (id) ON DELETE CASCADE,
+    -- The `And` or `Or` group containing the entry
+    parent_id INTEGER REFERENCES course_entries (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    guid TEXT,
+    url TEXT,
+    path TEXT,
+    subject_code TEXT,
+    subject_name TEXT,
+    number TEXT,
+    name TEXT,
+    credits_min INTEGER,
+    credits_max INTEGER,
+    extra TEXT
+);
+
+-- Courses mentioned in the prose of `Label` requirements
+CREATE TABLE IF NOT EXISTS course_refs (
+    requirement_id INTEGER NOT NULL REFERENCES requirements (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    subject_code TEXT NOT NULL,
+    number TEXT NOT NULL,
+    guid TEXT
+);
+
+CREATE TABLE IF NOT EXISTS courses (
+    guid TEXT PRIMARY KEY,
+    position INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    path TEXT NOT NULL,
+    subject_code TEXT NOT NULL,
+    subject_name TEXT,
+    number TEXT NOT NULL,
+    name TEXT NOT NULL,
+    credits_min INTEGER NOT NULL,
+    credits_max INTEGER,
+    description TEXT NOT NULL,
+    prerequisite_narrative TEXT,
+    prerequisite TEXT,
+    corequisite_narrative TEXT,
+    corequisite TEXT,
+    extra TEXT
+);
+
+CREATE INDEX IF NOT EXISTS courses_subject_number ON courses (subject_code, number);
+CREATE INDEX IF NOT EXISTS course_entries_guid ON course_entries (guid);
+CREATE INDEX IF NOT EXISTS course_entries_subject_number ON course_entries (subject_code, number);
+CREATE INDEX IF NOT EXISTS course_entries_requirement ON course_entries (requirement_id);
+CREATE INDEX IF NOT EXISTS course_refs_requirement ON course_refs (requirement_id);
+CREATE INDEX IF NOT EXISTS requirements_module ON requirements (module_id);
+CREATE INDEX IF NOT EXISTS requirement_modules_program ON requirement_modules (program_guid);
+";
+
+const COURSE_COLUMNS: &str = "url, path, guid, subject_code, subject_name, number, name, \
+    credits_min, credits_max, description, prerequisite_narrative, prerequisite, \
+    corequisite_narrative, corequisite, extra";
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("database has schema version {found} but version {SCHEMA_VERSION} is supported")]
+    SchemaVersion { found: i64 },
+    #[error("failed to parse the data of requirement module {id}: {source}")]
+    Json {
+        id: i64,
+        #[source]
+        source: serde_json::Error,
+    },
+    /// A row that could not have been written by [CatalogStore::save]
+    #[error("invalid row {id} of {table}: {reason}")]
+    InvalidRow {
+        table: &'static str,
+        id: String,
+        reason: String,
+    },
+}
+
+/// A [Catalog] persisted in a SQLite database
+#[derive(Debug)]
+pub struct CatalogStore {
+    conn: Connection,
+}
+
+impl CatalogStore {
+    /// Opens the database at `path`, creating it along with its tables if it doesn't exist yet
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Opens a database that only lives as long as the returned store
+    pub fn open_in_memory() -> Result<Self, StoreError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, StoreError> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+
+        let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        match version {
+            0 => {
+                conn.execute_batch(SCHEMA)?;
+                conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+            }
+            SCHEMA_VERSION => {}
+            found => return Err(StoreError::SchemaVersion { found }),
+        }
+
+        Ok(Self { conn })
+    }
+
+    /// Replaces everything in the store with the `catalog`. Nothing is changed when saving fails.
+    pub fn save(&mut self, catalog: &Catalog) -> Result<(), StoreError> {
+        let tx = self.conn.transaction()?;
+
+        tx.execute_batch(
+            "DELETE FROM programs;
+             DELETE FROM requirement_modules WHERE shared_guid IS NOT NULL;
+             DELETE FROM courses;",
+        )?;
+
+        let mut writer = Writer::new(&tx)?;
+        for (position, shared) in catalog.shared_modules.iter().enumerate() {
+            writer.push_module(Owner::Shared(shared.guid), position, &shared.module)?;
+        }
+        for (position, program) in catalog.programs.iter().enumerate() {
+            writer.push_program(position, program)?;
+        }
+        drop(writer);
+
+        {
+            let mut insert_course = tx.prepare(&format!(
+                "INSERT INTO courses (position, {COURSE_COLUMNS}) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            ))?;
+            for (position, course) in catalog.courses.iter().enumerate() {
+                insert_course.execute(params![
+                    position,
+                    course.url,
+                    course.path,
+                    course.guid,
+                    course.subject_code,
+                    course.subject_name,
+                    course.number,
+                    course.name,
+                    course.credits_min,
+                    course.credits_max,
+                    course.description,
+                    course.prerequisite_narrative,
+                    course.prerequisite,
+                    course.corequisite_narrative,
+                    course.corequisite,
+                    course.extra,
+                ])?;
+            }
+        }
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// The catalog last saved to the store. Empty when nothing was saved yet.
+    pub fn load(&self) -> Result<Catalog, StoreError> {
+        let mut reader = Reader::new(&self.conn)?;
+        let shared_modules = reader.shared_modules()?;
+        let programs = reader.programs()?;
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {COURSE_COLUMNS} FROM courses ORDER BY position"
+        ))?;
+        let courses = stmt
+            .query_map([], course_details)?
+            .collect::<Result<_, _>>()?;
+
+        let mut catalog = Catalog {
+            programs,
+            courses,
+            shared_modules,
+        };
+        catalog.intern_courses();
+
+        Ok(catalog)
+    }
+
+    /// The course with the `guid`
+    pub fn course(&self, guid: Guid) -> Result<Option<CourseDetails>, StoreError> {
+        let course = self
+            .conn
+            .query_row(
+                &format!("SELECT {COURSE_COLUMNS} FROM courses WHERE guid = ?"),
+                [guid],
+                course_details,
+            )
+            .optional()?;
+
+        Ok(course)
+    }
+
+    /// Every course with the `subject_code` and `number`, such as `CSC` and `105`, in catalog order
+    pub fn courses_by_code(
+        &self,
+        subject_code: &str,
+        number: &str,
+    ) -> Result<Vec<CourseDetails>, StoreError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {COURSE_COLUMNS} FROM courses \
+             WHERE subject_code = ? AND number = ? ORDER BY position"
+        ))?;
+        let courses = stmt
+            .query_map([subject_code, number], course_details)?
+            .collect::<Result<_, _>>()?;
+
+        Ok(courses)
+    }
+
+    /// GUIDs of the programs listing a course with the `guid` in their requirements, in catalog
+    /// order
+    pub fn programs_requiring(&self, guid: Guid) -> Result<Vec<Guid>, StoreError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT programs.guid, programs.position FROM course_entries
+             JOIN requirements ON requirements.id = course_entries.requirement_id
+             JOIN requirement_modules ON requirement_modules.id = requirements.module_id
+             JOIN programs ON programs.guid = requirement_modules.program_guid
+             WHERE course_entries.guid = ? AND course_entries.kind = 'Course'
+             ORDER BY programs.position",
+        )?;
+        let guids = stmt
+            .query_map([guid], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(guids)
+    }
+}
+
+impl ToSql for Guid {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for Guid {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let s = value.as_str()?;
+        Guid::try_from(s).map_err(|err| FromSqlError::Other(Box::new(err)))
+    }
+}
+
+impl ToSql for Symbol {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for Symbol {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value.as_str().map(Symbol::new)
+    }
+}
+
+/// JSON of the fields, or NULL when there are none
+impl ToSql for Extensions {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self.is_empty() {
+            true => ToSqlOutput::from(rusqlite::types::Null),
+            false => ToSqlOutput::from(self.to_json()),
+        })
+    }
+}
+
+impl FromSql for Extensions {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str_or_null()? {
+            Some(json) => {
+                Extensions::from_json(json).map_err(|err| FromSqlError::Other(Box::new(err)))
+            }
+            None => Ok(Extensions::default()),
+        }
+    }
+}
+
+fn course_details(row: &Row<'_>) -> rusqlite::Result<CourseDetails> {
+    Ok(CourseDetails {
+        url: row.get(0)?,
+        path: row.get(1)?,
+        guid: row.get(2)?,
+        subject_code: row.get(3)?,
+        subject_name: row.get(4)?,
+        number: row.get(5)?,
+        name: row.get(6)?,
+        credits_min: row.get(7)?,
+        credits_max: row.get(8)?,
+        description: row.get(9)?,
+        prerequisite_narrative: row.get(10)?,
+        prerequisite: row.get(11)?,
+        corequisite_narrative: row.get(12)?,
+        corequisite: row.get(13)?,
+        extra: row.get(14)?,
+    })
+}
+
+/// Inserts the rows of programs, giving modules, requirements and entries sequential ids
+struct Writer<'tx> {
+    program: rusqlite::Statement<'tx>,
+    module: rusqlite::Statement<'tx>,
+    requirement: rusqlite::Statement<'tx>,
+    entry: rusqlite::Statement<'tx>,
+    course_ref: rusqlite::Statement<'tx>,
+}
+
+impl<'tx> Writer<'tx> {
+    fn new(tx: &'tx Transaction<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            program: tx.prepare(
+                "INSERT INTO programs
+                 (guid, position, title, url, content, bottom_content, requirements_kind, extra)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )?,
+            module: tx.prepare(
+                "INSERT INTO requirement_modules
+                 (program_guid, shared_guid, position, kind, title, data)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )?,
+            requirement: tx.prepare(
+                "INSERT INTO requirements
+                 (module_id, position, kind, title, narrative, has_courses, min_grade, min_gpa,
+                  elective_hours, elective_total, extra)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )?,
+            entry: tx.prepare(
+                "INSERT INTO course_entries
+                 (requirement_id, parent_id, position, kind, guid, url, path, subject_code,
+                  subject_name, number, name, credits_min, credits_max, extra)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )?,
+            course_ref: tx.prepare(
+                "INSERT INTO course_refs (requirement_id, position, subject_code, number, guid)
+                 VALUES (?, ?, ?, ?, ?)",
+            )?,
+        })
+    }
+
+    fn push_program(&mut self, position: usize, program: &Program) -> rusqlite::Result<()> {
+        let requirements_kind = program.requirements.as_ref().map(|req| match req {
+            Requirements::Single(_) => "Single",
+            Requirements::Many(_) => "Many",
+            Requirements::SelectTrack => "SelectTrack",
+        });
+
+        self.program.execute(params![
+            program.guid,
+            position,
+            program.title,
+            program.url,
+            program.content,
+            program.bottom_content,
+            requirements_kind,
+            program.extra,
+        ])?;
+
+        let modules = program
+            .requirements
+            .iter()
+            .flat_map(|requirements| requirements.modules());
+
+        for (position, module) in modules.enumerate() {
+            self.push_module(Owner::Program(program.guid), position, module)?;
+        }
+
+        Ok(())
+    }
+
+    fn push_module(
+        &mut self,
+        owner: Owner,
+        position: usize,
+        module: &RequirementModule,
+    ) -> rusqlite::Result<()> {
+        let (kind, data) = match module {
+            RequirementModule::SingleBasicRequirement { .. } => ("SingleBasicRequirement", None),
+            RequirementModule::BasicRequirements { .. } => ("BasicRequirements", None),
+            RequirementModule::SelectOneEmphasis { .. } => ("SelectOneEmphasis", None),
+            RequirementModule::Label { .. } => ("Label", None),
+            RequirementModule::Unimplemented(value) => ("Unimplemented", Some(value.to_string())),
+            RequirementModule::Reference(guid) => ("Reference", Some(guid.to_string())),
+        };
+        let (program_guid, shared_guid) = match owner {
+            Owner::Program(guid) => (Some(guid), None),
+            Owner::Shared(guid) => (None, Some(guid)),
+        };
+
+        let module_id = self.module.insert(params![
+            program_guid,
+            shared_guid,
+            position,
+            kind,
+            module.title(),
+            data
+        ])?;
+
+        for (position, requirement) in module.requirements().iter().enumerate() {
+            self.push_requirement(module_id, position, requirement)?;
+        }
+
+        Ok(())
+    }
+
+    fn push_requirement(
+        &mut self,
+        module_id: i64,
+        position: usize,
+        requirement: &Requirement,
+    ) -> rusqlite::Result<()> {
+        let (kind, narrative) = match requirement {
+            Requirement::Courses { .. } => ("Courses", None),
+            Requirement::SelectFromCourses { .. } => ("SelectFromCourses", None),
+            Requirement::Label { req_narrative, .. } => ("Label", req_narrative.as_ref()),
+            Requirement::ElectivePool { req_narrative, .. } => {
+                ("ElectivePool", req_narrative.as_ref())
+            }
+        };
+        let constraints = requirement.constraints();
+        let (elective_hours, elective_total) = match requirement {
+            Requirement::ElectivePool {
+                hours: ElectiveHours::Hours(hours),
+                ..
+            } => (Some(*hours), None),
+            Requirement::ElectivePool {
+                hours: ElectiveHours::ToTotal(total),
+                ..
+            } => (None, Some(*total)),
+            _ => (None, None),
+        };
+
+        let requirement_id = self.requirement.insert(params![
+            module_id,
+            position,
+            kind,
+            requirement.title(),
+            narrative,
+            requirement.course_entries().is_some(),
+            constraints.min_grade.map(|grade| grade.to_string()),
+            constraints.min_gpa.map(Gpa::hundredths),
+            elective_hours,
+            elective_total,
+            requirement.extra(),
+        ])?;
+
+        if let Some(entries) = requirement.course_entries() {
+            self.push_entries(requirement_id, None, entries)?;
+        }
+        for (position, course_ref) in requirement.course_refs().iter().enumerate() {
+            self.course_ref.execute(params![
+                requirement_id,
+                position,
+                course_ref.subject_code,
+                course_ref.number,
+                course_ref.guid,
+            ])?;
+        }
+
+        Ok(())
+    }
+
+    fn push_entries(
+        &mut self,
+        requirement_id: i64,
+        parent_id: Option<i64>,
+        entries: &CourseEntries,
+    ) -> rusqlite::Result<()> {
+        for (position, entry) in entries.iter().enumerate() {
+            let entry_id = match entry {
+                CourseEntry::And(_) | CourseEntry::Or(_) => {
+                    let kind = if matches!(entry, CourseEntry::And(_)) {
+                        "And"
+                    } else {
+                        "Or"
+                    };
+                    self.entry.insert(params![
+                        requirement_id,
+                        parent_id,
+                        position,
+                        kind,
+                        None::<Guid>,
+                        None::<String>,
+                        None::<String>,
+                        None::<String>,
+                        None::<String>,
+                        None::<String>,
+                        None::<String>,
+                        None::<u8>,
+                        None::<u8>,
+                        None::<Extensions>,
+                    ])?
+                }
+                CourseEntry::Course(course) => self.entry.insert(params![
+                    requirement_id,
+                    parent_id,
+                    position,
+                    "Course",
+                    course.guid,
+                    course.url,
+                    course.path,
+                    course.subject_code,
+                    course.subject_name,
+                    course.number,
+                    course.name,
+                    course.credits.0,
+                    course.credits.1,
+                    course.extra,
+                ])?,
+                CourseEntry::Label(label) => self.entry.insert(params![
+                    requirement_id,
+                    parent_id,
+                    position,
+                    "Label",
+                    label.guid,
+                    label.url,
+                    None::<String>,
+                    label.subject_code,
+                    None::<String>,
+                    label.number,
+                    label.name,
+                    label.credits.0,
+                    label.credits.1,
+                    None::<Extensions>,
+                ])?,
+            };
+
+            if let CourseEntry::And(group) | CourseEntry::Or(group) = entry {
+                self.push_entries(requirement_id, Some(entry_id), group)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// What a requirement module belongs to
+#[derive(Clone, Copy)]
+enum Owner {
+    Program(Guid),
+    Shared(Guid),
+}
+
+/// Every row of the program tables, grouped by their parent
+struct Reader {
+    /// Requirement modules by the GUID of their program
+    modules: HashMap<Guid, Vec<ModuleRow>>,
+    /// Shared modules along with their GUID, in catalog order
+    shared_rows: Vec<(Guid, ModuleRow)>,
+    /// Requirements by the id of their module
+    requirements: HashMap<i64, Vec<RequirementRow>>,
+    /// Entries that aren't inside of a group by the id of their requirement
+    entries: HashMap<i64, Vec<EntryRow>>,
+    /// Entries inside of a group by the id of the group
+    children: HashMap<i64, Vec<EntryRow>>,
+    /// Course references by the id of their requirement
+    course_refs: HashMap<i64, Vec<CourseRef>>,
+    program_rows: Vec<ProgramRow>,
+}
+
+struct ProgramRow {
+    guid: Guid,
+    title: String,
+    url: String,
+    content: Option<String>,
+    bottom_content: Option<String>,
+    requirements_kind: Option<String>,
+    extra: Extensions,
+}
+
+struct ModuleRow {
+    id: i64,
+    kind: String,
+    title: Option<String>,
+    data: Option<String>,
+}
+
+struct RequirementRow {
+    id: i64,
+    kind: String,
+    title: Option<String>,
+    narrative: Option<String>,
+    has_courses: bool,
+    min_grade: Option<String>,
+    min_gpa: Option<u16>,
+    elective_hours: Option<u32>,
+    elective_total: Option<u32>,
+    extra: Extensions,
+}
+
+struct EntryRow {
+    id: i64,
+    kind: String,
+    guid: Option<Guid>,
+    url: Option<String>,
+    path: Option<String>,
+    subject_code: Option<Symbol>,
+    subject_name: Option<Symbol>,
+    number: Option<String>,
+    name: Option<String>,
+    credits: (Option<u8>, Option<u8>),
+    extra: Extensions,
+}
+
+impl Reader {
+    fn new(conn: &Connection) -> rusqlite::Result<Self> {
+        let mut reader = Reader {
+            modules: HashMap::new(),
+            shared_rows: vec![],
+            requirements: HashMap::new(),
+            entries: HashMap::new(),
+            children: HashMap::new(),
+            course_refs: HashMap::new(),
+            program_rows: vec![],
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT guid, title, url, content, bottom_content, requirements_kind, extra
+             FROM programs ORDER BY position",
+        )?;
+        reader.program_rows = stmt
+            .query_map([], |row| {
+                Ok(ProgramRow {
+                    guid: row.get(0)?,
+                    title: row.get(1)?,
+                    url: row.get(2)?,
+                    content: row.get(3)?,
+                    bottom_content: row.get(4)?,
+                    requirements_kind: row.get(5)?,
+                    extra: row.get(6)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT program_guid, shared_guid, id, kind, title, data
+             FROM requirement_modules ORDER BY position",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let module = ModuleRow {
+                id: row.get(2)?,
+                kind: row.get(3)?,
+                title: row.get(4)?,
+                data: row.get(5)?,
+            };
+
+            match row.get::<_, Option<Guid>>(0)? {
+                Some(program_guid) => reader.modules.entry(program_guid).or_default().push(module),
+                None => reader.shared_rows.push((row.get(1)?, module)),
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT module_id, id, kind, title, narrative, has_courses, min_grade, min_gpa,
+                    elective_hours, elective_total, extra
+             FROM requirements ORDER BY position",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            reader
+                .requirements
+                .entry(row.get(0)?)
+                .or_default()
+                .push(RequirementRow {
+                    id: row.get(1)?,
+                    kind: row.get(2)?,
+                    title: row.get(3)?,
+                    narrative: row.get(4)?,
+                    has_courses: row.get(5)?,
+                    min_grade: row.get(6)?,
+                    min_gpa: row.get(7)?,
+                    elective_hours: row.get(8)?,
+                    elective_total: row.get(9)?,
+                    extra: row.get(10)?,
+                });
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT requirement_id, parent_id, id, kind, guid, url, path, subject_code,
+                    subject_name, number, name, credits_min, credits_max, extra
+             FROM course_entries ORDER BY position",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let entry = EntryRow {
+                id: row.get(2)?,
+                kind: row.get(3)?,
+                guid: row.get(4)?,
+                url: row.get(5)?,
+                path: row.get(6)?,
+                subject_code: row.get(7)?,
+                subject_name: row.get(8)?,
+                number: row.get(9)?,
+                name: row.get(10)?,
+                credits: (row.get(11)?, row.get(12)?),
+                extra: row.get(13)?,
+            };
+
+            match row.get::<_, Option<i64>>(1)? {
+                Some(parent_id) => reader.children.entry(parent_id).or_default().push(entry),
+                None => reader.entries.entry(row.get(0)?).or_default().push(entry),
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT requirement_id, subject_code, number, guid
+             FROM course_refs ORDER BY position",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            reader
+                .course_refs
+                .entry(row.get(0)?)
+                .or_default()
+                .push(CourseRef {
+                    subject_code: row.get(1)?,
+                    number: row.get(2)?,
+                    guid: row.get(3)?,
+                });
+        }
+
+        Ok(reader)
+    }
+
+    fn shared_modules(&mut self) -> Result<Vec<SharedModule>, StoreError> {
+        std::mem::take(&mut self.shared_rows)
+            .into_iter()
+            .map(|(guid, row)| {
+                Ok(SharedModule {
+                    guid,
+                    module: self.module(row)?,
+                })
+            })
+            .collect()
+    }
+
+    fn programs(mut self) -> Result<Vec<Program>, StoreError> {
+        std::mem::take(&mut self.program_rows)
+            .into_iter()
+            .map(|row| self.program(row))
+            .collect()
+    }
+
+    fn program(&mut self, row: ProgramRow) -> Result<Program, StoreError> {
+        let modules = self
+            .modules
+            .remove(&row.guid)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|module| self.module(module))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let invalid = |reason: String| StoreError::InvalidRow {
+            table: "programs",
+            id: row.guid.to_string(),
+            reason,
+        };
+
+        let requirements = match (row.requirements_kind.as_deref(), modules.len()) {
+            (None, 0) => None,
+            (Some("SelectTrack"), 0) => Some(Requirements::SelectTrack),
+            (Some("Single"), 1) => Some(Requirements::Single(
+                modules
+                    .into_iter()
+                    .next()
+                    .expect("There should be one module"),
+            )),
+            (Some("Many"), _) => Some(Requirements::Many(modules)),
+            (kind, count) => {
+                return Err(invalid(format!(
+                    "requirements of kind {kind:?} can't have {count} modules"
+                )))
+            }
+        };
+
+        Ok(Program {
+            url: row.url,
+            guid: row.guid,
+            title: row.title,
+            content: row.content,
+            bottom_content: row.bottom_content,
+            requirements,
+            extra: row.extra,
+        })
+    }
+
+    fn module(&mut self, row: ModuleRow) -> Result<RequirementModule, StoreError> {
+        let mut requirements = self
+            .requirements
+            .remove(&row.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|requirement| self.requirement(requirement))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let invalid = |reason: &str| StoreError::InvalidRow {
+            table: "requirement_modules",
+            id: row.id.to_string(),
+            reason: reason.to_owned(),
+        };
+
+        let module = match row.kind.as_str() {
+            "SingleBasicRequirement" if requirements.len() == 1 => {
+                RequirementModule::SingleBasicRequirement {
+                    title: row.title,
+                    requirement: requirements.remove(0),
+                }
+            }
+            "SingleBasicRequirement" => return Err(invalid("expected exactly one requirement")),
+            "BasicRequirements" => RequirementModule::BasicRequirements {
+                title: row.title,
+                requirements,
+            },
+            "SelectOneEmphasis" => RequirementModule::SelectOneEmphasis {
+                emphases: requirements,
+            },
+            "Label" => RequirementModule::Label {
+                title: row.title.ok_or_else(|| invalid("labels need a title"))?,
+            },
+            "Unimplemented" => {
+                let data = row.data.ok_or_else(|| invalid("missing data"))?;
+                let value = serde_json::from_str(&data)
+                    .map_err(|source| StoreError::Json { id: row.id, source })?;
+                RequirementModule::Unimplemented(value)
+            }
+            "Reference" => {
+                let data = row.data.ok_or_else(|| invalid("missing data"))?;
+                let guid = Guid::try_from(data.as_str())
+                    .map_err(|_| invalid("data is not the GUID of a shared module"))?;
+                RequirementModule::Reference(guid)
+            }
+            kind => return Err(invalid(&format!("unknown kind {kind:?}"))),
+        };
+
+        Ok(module)
+    }
+
+    fn requirement(&mut self, row: RequirementRow) -> Result<Requirement, StoreError> {
+        let courses = if row.has_courses {
+            let rows = self.entries.remove(&row.id).unwrap_or_default();
+            Some(self.entries(rows)?)
+        } else {
+            None
+        };
+
+        let invalid = |reason: &str| StoreError::InvalidRow {
+            table: "requirements",
+            id: row.id.to_string(),
+            reason: reason.to_owned(),
+        };
+
+        let constraints = Constraints {
+            min_grade: row
+                .min_grade
+                .map(|grade| grade.parse())
+                .transpose()
+                .map_err(|_| invalid("invalid min_grade"))?,
+            min_gpa: row.min_gpa.map(Gpa::from_hundredths),
+        };
+
+        let requirement = match row.kind.as_str() {
+            "Courses" => Requirement::Courses {
+                title: row.title,
+                courses: courses.ok_or_else(|| invalid("missing courses"))?,
+                constraints,
+                extra: row.extra,
+            },
+            "SelectFromCourses" => Requirement::SelectFromCourses {
+                title: row.title.ok_or_else(|| invalid("missing title"))?,
+                courses,
+                constraints,
+                extra: row.extra,
+            },
+            "Label" => Requirement::Label {
+                title: row.title,
+                req_narrative: row.narrative,
+                constraints,
+                course_refs: self.course_refs.remove(&row.id).unwrap_or_default(),
+                extra: row.extra,
+            },
+            "ElectivePool" => Requirement::ElectivePool {
+                title: row.title,
+                req_narrative: row.narrative,
+                hours: match (row.elective_hours, row.elective_total) {
+                    (Some(hours), None) => ElectiveHours::Hours(hours),
+                    (None, Some(total)) => ElectiveHours::ToTotal(total),
+                    _ => return Err(invalid("expected one of elective_hours and elective_total")),
+                },
+                constraints,
+                extra: row.extra,
+            },
+            kind => return Err(invalid(&format!("unknown kind {kind:?}"))),
+        };
+
+        Ok(requirement)
+    }
+
+    fn entries(&mut self, rows: Vec<EntryRow>) -> Result<CourseEntries, StoreError> {
+        rows.into_iter().map(|row| self.entry(row)).collect()
+    }
+
+    fn entry(&mut self, row: EntryRow) -> Result<CourseEntry, StoreError> {
+        let id = row.id;
+        let missing = |column: &str| StoreError::InvalidRow {
+            table: "course_entries",
+            id: id.to_string(),
+            reason: format!("missing {column}"),
+        };
+
+        let entry = match row.kind.as_str() {
+            "And" => CourseEntry::And(self.group(id)?),
+            "Or" => CourseEntry::Or(self.group(id)?),
+            "Course" => CourseEntry::from(Course {
+                url: row.url.ok_or_else(|| missing("url"))?,
+                path: row.path.ok_or_else(|| missing("path"))?,
+                guid: row.guid.ok_or_else(|| missing("guid"))?,
+                name: row.name,
+                number: row.number.ok_or_else(|| missing("number"))?,
+                subject_name: row.subject_name,
+                subject_code: row.subject_code.ok_or_else(|| missing("subject_code"))?,
+                credits: (
+                    row.credits.0.ok_or_else(|| missing("credits_min"))?,
+                    row.credits.1,
+                ),
+                extra: row.extra,
+                details: None,
+            }),
+            "Label" => CourseEntry::Label(Label {
+                url: row.url.ok_or_else(|| missing("url"))?,
+                guid: row.guid.ok_or_else(|| missing("guid"))?,
+                name: row.name.ok_or_else(|| missing("name"))?,
+                number: row.number,
+                subject_code: row.subject_code,
+                credits: (
+                    row.credits.0.ok_or_else(|| missing("credits_min"))?,
+                    row.credits.1,
+                ),
+            }),
+            kind => {
+                return Err(StoreError::InvalidRow {
+                    table: "course_entries",
+                    id: id.to_string(),
+                    reason: format!("unknown kind {kind:?}"),
+                })
+            }
+        };
+
+        Ok(entry)
+    }
+
+    fn group(&mut self, id: i64) -> Result<CourseEntries, StoreError> {
+        let rows = self.children.remove(&id).unwrap_or_default();
+        self.entries(rows)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn data_catalog() -> Catalog {
+        let (catalog, _errors) = Catalog::parse_dir("../data").expect("Failed to read data dir");
+        catalog
+    }
+
+    #[test]
+    fn loads_the_saved_catalog() {
+        let catalog = data_catalog();
+
+        let mut store = CatalogStore::open_in_memory().unwrap();
+        assert_eq!(store.load().unwrap(), Catalog::default());
+
+        store.save(&catalog).unwrap();
+        assert_eq!(store.load().unwrap(), catalog);
+
+        let mut shared = catalog.clone();
+        shared.resolve_references();
+        store.save(&shared).unwrap();
+        assert_eq!(store.load().unwrap(), shared);
+
+        // Saving again replaces the previous catalog instead of adding to it
+        let (cs_minor, _) = Catalog::parse_file("../data/cs_minor.json");
+        store.save(&cs_minor).unwrap();
+        assert_eq!(store.load().unwrap(), cs_minor);
+    }
+
+    #[test]
+    fn persists_across_connections() {
+        let path = std::env::temp_dir().join(format!("vislog-{}.db", uuid::Uuid::new_v4()));
+
+        let (catalog, _) = Catalog::parse_file("../data/cs_major.json");
+        CatalogStore::open(&path).unwrap().save(&catalog).unwrap();
+        let loaded = CatalogStore::open(&path).unwrap().load();
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), catalog);
+    }
+
+    #[test]
+    fn looks_up_courses() {
+        let catalog = data_catalog();
+        let mut store = CatalogStore::open_in_memory().unwrap();
+        store.save(&catalog).unwrap();
+
+        let course = catalog
+            .courses
+            .iter()
+            .find(|course| course.subject_code == "CSC" && course.number == "105")
+            .unwrap();
+
+        assert_eq!(store.course(course.guid).unwrap().as_ref(), Some(course));
+        assert_eq!(
+            store.courses_by_code("CSC", "105").unwrap(),
+            vec![course.clone()]
+        );
+        assert!(store.courses_by_code("CSC", "0").unwrap().is_empty());
+
+        let requiring = store.programs_requiring(course.guid).unwrap();
+        let expected: Vec<Guid> = catalog
+            .programs
+            .iter()
+            .filter(|program| program.iter_courses().any(|c| c.guid == course.guid))
+            .map(|program| program.guid)
+            .collect();
+        assert!(!requiring.is_empty());
+        assert_eq!(requiring, expected);
+    }
+
+    #[test]
+    fn rejects_newer_schemas() {
+        let path = std::env::temp_dir().join(format!("vislog-{}.db", uuid::Uuid::new_v4()));
+        Connection::open(&path)
+            .unwrap()
+            .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+
+        let result = CatalogStore::open(&path);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(
+            matches!(result, Err(StoreError::SchemaVersion { found }) if found == SCHEMA_VERSION + 1)
+        );
+    }
+}
@@ -0,0 +1,386 @@
+//! The subjects courses are offered under, and the departments teaching them.
+//!
+//! Courses only carry their `subject_code` and, most of the time, a `subject_name`. [Subjects]
+//! gathers every subject of a catalog along with the department and school teaching it, so that
+//! courses missing a `subject_name` can be given one, courses can be grouped by department, and
+//! drawings can give the courses of a department the same [color](Subjects::color) in every
+//! program.
+//!
+//! The catalog API has no list of subjects of its own. [Subjects::from_catalog] names subjects
+//! after the courses of the catalog and assigns them to the department whose programs list their
+//! courses the most, according to the [metadata](crate::metadata) of the programs. A list of
+//! subjects maintained by hand can be [read](Subjects::from_json) and
+//! [merged](Subjects::merge) over it.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, subjects::Subjects};
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//! let subjects = Subjects::from_catalog(&catalog);
+//!
+//! let csc = subjects.get("CSC").unwrap();
+//! assert_eq!(csc.name.as_deref(), Some("Computer Science"));
+//! assert_eq!(csc.department.as_deref(), Some("Department of Computer Science"));
+//! assert_eq!(subjects.color("csc"), subjects.color("CSC"));
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    catalog::Catalog, hash, metadata::ProgramMetadata, symbol::Symbol, Course, CourseEntries,
+    CourseEntry, Program, Requirements,
+};
+
+/// Fills of [Subjects::color], light enough for dark text to be read on them
+pub const PALETTE: &[&str] = &[
+    "#ddf4ff", "#dafbe1", "#fff8c5", "#ffebe9", "#fbefff", "#ffeff7", "#fff1e5", "#d1f0f5",
+    "#e7f5d0", "#eae6ff", "#fdf0d5", "#e6eef7",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Subject {
+    /// Ex: "CSC"
+    pub code: String,
+    /// Ex: "Computer Science"
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Ex: "Department of Computer Science"
+    #[serde(default)]
+    pub department: Option<String>,
+    /// Ex: "College of Arts and Sciences"
+    #[serde(default)]
+    pub school: Option<String>,
+}
+
+/// Department and school offering a program
+type Units = (Option<String>, Option<String>);
+
+/// Registry of [Subject]s by their code, which is case insensitive
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Subjects {
+    /// By uppercased code
+    subjects: BTreeMap<String, Subject>,
+}
+
+impl Subject {
+    pub fn new(code: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            name: None,
+            department: None,
+            school: None,
+        }
+    }
+}
+
+impl Subjects {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subjects of the courses of the `catalog`, named after the name most of their courses give
+    /// them and assigned to the department and school whose programs list the most of them
+    pub fn from_catalog(catalog: &Catalog) -> Self {
+        let mut names: HashMap<&str, HashMap<&str, usize>> = HashMap::new();
+        let named = catalog
+            .courses
+            .iter()
+            .map(|course| (&course.subject_code, &course.subject_name))
+            .chain(
+                catalog
+                    .programs
+                    .iter()
+                    .flat_map(Program::iter_courses)
+                    .map(|course| (&course.subject_code, &course.subject_name)),
+            );
+        for (code, name) in named {
+            let names = names.entry(code.as_str()).or_default();
+            if let Some(name) = name {
+                *names.entry(name.as_str()).or_default() += 1;
+            }
+        }
+
+        let mut offered_by: HashMap<&str, HashMap<Units, usize>> = HashMap::new();
+        for program in &catalog.programs {
+            let metadata = ProgramMetadata::of(program);
+            for course in program.iter_courses() {
+                let units = (metadata.department.clone(), metadata.school.clone());
+                *offered_by
+                    .entry(course.subject_code.as_str())
+                    .or_default()
+                    .entry(units)
+                    .or_default() += 1;
+            }
+        }
+
+        let mut subjects = Self::new();
+        for (code, names) in names {
+            let (department, school) = offered_by
+                .remove(code)
+                .and_then(most_common)
+                .unwrap_or_default();
+            subjects.insert(Subject {
+                code: code.to_owned(),
+                name: most_common(names).map(str::to_owned),
+                department,
+                school,
+            });
+        }
+
+        subjects
+    }
+
+    /// Reads a JSON array of subjects. Ex:
+    /// `[{"code": "CSC", "name": "Computer Science", "department": "Department of Computer Science"}]`
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let list: Vec<Subject> = serde_json::from_str(json)?;
+        let mut subjects = Self::new();
+        for subject in list {
+            subjects.insert(subject);
+        }
+
+        Ok(subjects)
+    }
+
+    /// Adds the `subject`, returning the subject with the same code it replaces
+    pub fn insert(&mut self, subject: Subject) -> Option<Subject> {
+        self.subjects
+            .insert(subject.code.to_ascii_uppercase(), subject)
+    }
+
+    /// Adds the subjects of `other`, with the fields they set replacing the ones of the subjects
+    /// with the same code
+    pub fn merge(&mut self, other: Subjects) {
+        for (code, subject) in other.subjects {
+            match self.subjects.get_mut(&code) {
+                Some(existing) => {
+                    existing.name = subject.name.or(existing.name.take());
+                    existing.department = subject.department.or(existing.department.take());
+                    existing.school = subject.school.or(existing.school.take());
+                }
+                None => {
+                    self.subjects.insert(code, subject);
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, code: &str) -> Option<&Subject> {
+        self.subjects.get(&code.to_ascii_uppercase())
+    }
+
+    /// Subjects ordered by code
+    pub fn iter(&self) -> impl Iterator<Item = &Subject> {
+        self.subjects.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.subjects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subjects.is_empty()
+    }
+
+    /// Name of the subject with the `code`. Ex: "Computer Science" for "CSC"
+    pub fn name(&self, code: &str) -> Option<&str> {
+        self.get(code)?.name.as_deref()
+    }
+
+    /// Department teaching the subject with the `code`, or the school when the department isn't
+    /// known
+    pub fn department(&self, code: &str) -> Option<&str> {
+        let subject = self.get(code)?;
+        subject.department.as_deref().or(subject.school.as_deref())
+    }
+
+    /// Fill from the [PALETTE] for the courses of the subject with the `code`. Subjects of the
+    /// same department share their color, which stays the same across programs and runs.
+    pub fn color(&self, code: &str) -> &'static str {
+        let key = match self.department(code) {
+            Some(department) => department.to_owned(),
+            None => code.to_ascii_uppercase(),
+        };
+
+        PALETTE[(hash::content_hash(&key) % PALETTE.len() as u64) as usize]
+    }
+
+    /// Distinct courses of the `program` by the [department](Subjects::department) teaching them,
+    /// with `None` for the courses of unknown subjects
+    pub fn group_by_department<'a>(
+        &'a self,
+        program: &'a Program,
+    ) -> BTreeMap<Option<&'a str>, Vec<&'a Course>> {
+        let mut groups: BTreeMap<Option<&str>, Vec<&Course>> = BTreeMap::new();
+        for course in program.iter_courses() {
+            let group = groups
+                .entry(self.department(&course.subject_code))
+                .or_default();
+            if !group.iter().any(|grouped| grouped.guid == course.guid) {
+                group.push(course);
+            }
+        }
+
+        groups
+    }
+}
+
+impl Catalog {
+    /// Sets the `subject_name` of every course of the catalog to the [name](Subjects::name) of
+    /// its subject, for subjects that have one. Returns the number of courses and course entries
+    /// whose name changed.
+    pub fn normalize_subject_names(&mut self, subjects: &Subjects) -> usize {
+        let mut changed = 0;
+        for course in &mut self.courses {
+            if let Some(name) = renamed(&course.subject_code, &course.subject_name, subjects) {
+                course.subject_name = Some(name);
+                changed += 1;
+            }
+        }
+
+        let modules = self
+            .programs
+            .iter_mut()
+            .filter_map(|program| program.requirements.as_mut())
+            .flat_map(Requirements::modules_mut)
+            .chain(
+                self.shared_modules
+                    .iter_mut()
+                    .map(|shared| &mut shared.module),
+            );
+        for module in modules {
+            for requirement in module.requirements_mut() {
+                if let Some(entries) = requirement.course_entries_mut() {
+                    changed += normalize_entries(entries, subjects);
+                }
+            }
+        }
+
+        changed
+    }
+}
+
+/// The name a course of the `code` should have when it isn't `current`
+fn renamed(code: &str, current: &Option<Symbol>, subjects: &Subjects) -> Option<Symbol> {
+    let name = subjects.name(code)?;
+    match current.as_deref() {
+        Some(current) if current == name => None,
+        _ => Some(Symbol::from(name)),
+    }
+}
+
+fn normalize_entries(entries: &mut CourseEntries, subjects: &Subjects) -> usize {
+    entries
+        .iter_mut()
+        .map(|entry| match entry {
+            CourseEntry::And(group) | CourseEntry::Or(group) => normalize_entries(group, subjects),
+            CourseEntry::Label(_) => 0,
+            CourseEntry::Course(course) => {
+                match renamed(&course.subject_code, &course.subject_name, subjects) {
+                    Some(name) => {
+                        Arc::make_mut(course).subject_name = Some(name);
+                        1
+                    }
+                    None => 0,
+                }
+            }
+        })
+        .sum()
+}
+
+/// The key counted the most, the smallest of them on ties
+fn most_common<K: Ord>(counts: HashMap<K, usize>) -> Option<K> {
+    counts
+        .into_iter()
+        .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)))
+        .map(|(key, _)| key)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn catalog() -> Catalog {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        catalog
+    }
+
+    #[test]
+    fn subjects_are_gathered_from_the_catalog() {
+        let catalog = catalog();
+        let subjects = Subjects::from_catalog(&catalog);
+
+        assert_eq!(subjects.len(), 68);
+        assert_eq!(subjects.name("cmu"), Some("Commercial Music"));
+        assert_eq!(
+            subjects.department("MAT"),
+            Some("Department of Mathematics")
+        );
+        assert!(subjects.iter().all(|subject| subject.name.is_some()));
+
+        let major = catalog
+            .programs
+            .iter()
+            .find(|program| program.title == "Major in Computer Science—42 hours")
+            .unwrap();
+        let groups = subjects.group_by_department(major);
+        let computer_science = &groups[&Some("Department of Computer Science")];
+        assert!(computer_science
+            .iter()
+            .all(|course| &*course.subject_code == "CSC"));
+        assert_eq!(
+            groups.values().map(Vec::len).sum::<usize>(),
+            major
+                .iter_courses()
+                .map(|course| course.guid)
+                .collect::<std::collections::HashSet<_>>()
+                .len()
+        );
+    }
+
+    #[test]
+    fn lists_are_merged_over_the_catalog() {
+        let mut subjects = Subjects::from_catalog(&catalog());
+        let color = subjects.color("CSC");
+        subjects.merge(
+            Subjects::from_json(r#"[{"code": "pews", "name": "Physical Education, Wellness and Sport"}, {"code": "XYZ"}]"#)
+                .unwrap(),
+        );
+
+        let pews = subjects.get("PEWS").unwrap();
+        assert_eq!(
+            pews.name.as_deref(),
+            Some("Physical Education, Wellness and Sport")
+        );
+        assert!(pews.department.is_some());
+        assert_eq!(subjects.department("XYZ"), None);
+        assert_eq!(subjects.color("CSC"), color);
+        assert!(PALETTE.contains(&subjects.color("XYZ")));
+    }
+
+    #[test]
+    fn subject_names_are_normalized() {
+        let mut catalog = catalog();
+        let subjects = Subjects::from_catalog(&catalog);
+        let missing = catalog
+            .programs
+            .iter()
+            .flat_map(Program::iter_courses)
+            .filter(|course| course.subject_name.is_none())
+            .count();
+
+        assert!(catalog.normalize_subject_names(&subjects) >= missing);
+        assert!(catalog
+            .programs
+            .iter()
+            .flat_map(Program::iter_courses)
+            .all(|course| course.subject_name.as_deref() == subjects.name(&course.subject_code)));
+        assert_eq!(catalog.normalize_subject_names(&subjects), 0);
+    }
+}
@@ -0,0 +1,273 @@
+//! Strings that repeat across a whole catalog, such as subject codes and subject names.
+//!
+//! A [Symbol] is an immutable string kept once for the whole process. Every symbol spelled the
+//! same way points to the same allocation, so parsing "CSC" for the thousandth time doesn't
+//! allocate, and comparing two symbols is a pointer comparison, which matters when building
+//! [graphs](crate::graph) over every course of a catalog.
+//!
+//! Symbols deref to [str], so most code reading them doesn't change. Symbols are never freed, which
+//! is fine for the few hundred subjects of a catalog but makes them a poor fit for values that
+//! differ for every course, such as URLs, paths and descriptions. Those stay owned strings.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::symbol::Symbol;
+//! let code = Symbol::new("CSC");
+//! let parsed: Symbol = serde_json::from_str(r#""CSC""#).unwrap();
+//!
+//! assert_eq!(code, parsed);
+//! assert!(code.ptr_eq(&parsed));
+//! assert_eq!(code, "CSC");
+//! assert_eq!(code.len(), 3);
+//! ```
+
+use std::{
+    borrow::Borrow,
+    collections::HashSet,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// An interned string
+#[derive(Clone)]
+pub struct Symbol(Arc<str>);
+
+impl Symbol {
+    /// The symbol spelled `s`, shared with every other symbol spelled the same way
+    pub fn new(s: &str) -> Self {
+        static SYMBOLS: OnceLock<Mutex<HashSet<Arc<str>>>> = OnceLock::new();
+
+        let mut symbols = SYMBOLS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match symbols.get(s) {
+            Some(symbol) => Symbol(symbol.clone()),
+            None => {
+                let symbol: Arc<str> = Arc::from(s);
+                symbols.insert(symbol.clone());
+                Symbol(symbol)
+            }
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether both symbols point to the same string, which is the case for every pair of equal
+    /// symbols
+    pub fn ptr_eq(&self, other: &Symbol) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Default for Symbol {
+    fn default() -> Self {
+        Symbol::new("")
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Symbol {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(s: &str) -> Self {
+        Symbol::new(s)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(s: String) -> Self {
+        Symbol::new(&s)
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(s: &String) -> Self {
+        Symbol::new(s)
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(symbol: Symbol) -> Self {
+        symbol.0.to_string()
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        // Interning makes equal symbols share their string
+        self.ptr_eq(other)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Symbol {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl PartialEq<Symbol> for str {
+    fn eq(&self, other: &Symbol) -> bool {
+        self == &*other.0
+    }
+}
+
+impl PartialEq<Symbol> for &str {
+    fn eq(&self, other: &Symbol) -> bool {
+        *self == &*other.0
+    }
+}
+
+/// Hashes the string so symbols can be looked up by `&str` in maps
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SymbolVisitor;
+
+        impl de::Visitor<'_> for SymbolVisitor {
+            type Value = Symbol;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Symbol, E> {
+                Ok(Symbol::new(v))
+            }
+        }
+
+        deserializer.deserialize_str(SymbolVisitor)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for Symbol {
+    fn schema_name() -> String {
+        "Symbol".to_owned()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::catalog::Catalog;
+
+    #[test]
+    fn equal_symbols_share_their_string() {
+        let a = Symbol::new("MAT");
+        let b = Symbol::from("MAT".to_string());
+        let c = Symbol::new("MATH");
+
+        assert!(a.ptr_eq(&b));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(a < c);
+
+        let mut map = HashMap::new();
+        map.insert(a, 1);
+        assert_eq!(map.get("MAT"), Some(&1));
+    }
+
+    #[test]
+    fn parsed_subject_codes_are_shared() {
+        let (catalog, _) = Catalog::parse_dir("../data").unwrap();
+        let csc = Symbol::new("CSC");
+
+        let courses: Vec<_> = catalog
+            .courses
+            .iter()
+            .map(|course| &course.subject_code)
+            .chain(
+                catalog
+                    .programs
+                    .iter()
+                    .flat_map(|program| program.iter_courses().map(|course| &course.subject_code)),
+            )
+            .filter(|code| **code == "CSC")
+            .collect();
+        assert!(courses.len() > 1);
+        assert!(courses.iter().all(|code| code.ptr_eq(&csc)));
+    }
+}
@@ -0,0 +1,171 @@
+//! Tags such as "writing-intensive", "capstone" or "gen-ed: humanities" attached to courses and
+//! requirements.
+//!
+//! The catalog has no room for the labels a registrar or a department puts on its courses, so
+//! they come from a [TagMapping] kept next to the catalog, like a
+//! [MetadataMapping](crate::metadata::MetadataMapping). Courses are tagged by their GUID, and
+//! requirements by the GUID of their program and their title:
+//!
+//! ```json
+//! {
+//!   "courses": { "860AF9C9-EAD9-45AC-AA92-BAF352C5288C": ["intro", "writing-intensive"] },
+//!   "requirements": {
+//!     "5B72AC3A-9A84-4CF5-B1BE-B3E0B48163A5": { "Select one track:": ["capstone"] }
+//!   }
+//! }
+//! ```
+//!
+//! Titles are compared once their HTML is removed and their whitespace collapsed, the way
+//! [clean_narrative] shows them. [Queries](crate::catalog::Catalog::query) find the courses with
+//! a tag with [with_tag](crate::query::CatalogQuery::with_tag), and the tags are carried to the
+//! nodes of [requirement graphs](crate::export::graph::RequirementGraph::with_tags) and
+//! [d3 hierarchies](crate::viz::d3::D3Node::with_tags).
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, tags::TagMapping};
+//! let (catalog, _errors) = Catalog::parse_dir("../data").unwrap();
+//!
+//! let tags = TagMapping::from_json(
+//!     r#"{ "courses": { "860AF9C9-EAD9-45AC-AA92-BAF352C5288C": ["writing-intensive"] } }"#,
+//! )
+//! .unwrap();
+//! let matches = catalog
+//!     .query()
+//!     .tags(tags)
+//!     .with_tag("writing-intensive")
+//!     .run();
+//!
+//! assert_eq!(matches.len(), 1);
+//! assert_eq!(matches[0].number(), "115");
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{parsing::guid::Guid, text::clean_narrative, Program, Requirement};
+
+/// Tags of courses and requirements, such as a JSON file maintained next to the catalog
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TagMapping {
+    /// Tags of courses by their GUID
+    pub courses: HashMap<Guid, BTreeSet<String>>,
+    /// Tags of requirements by the GUID of their program, then by their title
+    pub requirements: HashMap<Guid, HashMap<String, BTreeSet<String>>>,
+}
+
+impl TagMapping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a JSON object with the tags of `courses` and `requirements`, see the
+    /// [module](self) documentation
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn tag_course(&mut self, guid: Guid, tag: impl Into<String>) {
+        self.courses.entry(guid).or_default().insert(tag.into());
+    }
+
+    /// Tags the requirement titled `title` in the program with the GUID `program`
+    pub fn tag_requirement(&mut self, program: Guid, title: &str, tag: impl Into<String>) {
+        self.requirements
+            .entry(program)
+            .or_default()
+            .entry(title.to_owned())
+            .or_default()
+            .insert(tag.into());
+    }
+
+    /// Tags of the course with the `guid`, in alphabetical order
+    pub fn course_tags(&self, guid: &Guid) -> Vec<&str> {
+        self.courses
+            .get(guid)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Tags of the requirement with the `title` in the program with the GUID `program`, in
+    /// alphabetical order
+    pub fn requirement_tags(&self, program: &Guid, title: &str) -> Vec<&str> {
+        let Some(requirements) = self.requirements.get(program) else {
+            return vec![];
+        };
+
+        let title = clean_narrative(title);
+        let tags: BTreeSet<&str> = requirements
+            .iter()
+            .filter(|(tagged, _)| clean_narrative(tagged) == title)
+            .flat_map(|(_, tags)| tags.iter().map(String::as_str))
+            .collect();
+
+        tags.into_iter().collect()
+    }
+
+    /// Tags of the `requirement` of the `program`, found by its title or, for requirements
+    /// without one, its narrative
+    pub fn tags_of(&self, program: &Program, requirement: &Requirement) -> Vec<&str> {
+        match requirement.title().or(requirement.req_narrative()) {
+            Some(title) => self.requirement_tags(&program.guid, title),
+            None => vec![],
+        }
+    }
+
+    /// Whether the course with the `guid` has the `tag`, either on its own or through a
+    /// requirement of one of the `programs` listing it. Tags are compared without case.
+    pub fn has_tag<'p>(
+        &self,
+        guid: &Guid,
+        programs: impl IntoIterator<Item = &'p Program>,
+        tag: &str,
+    ) -> bool {
+        let matches = |tags: Vec<&str>| tags.iter().any(|other| other.eq_ignore_ascii_case(tag));
+        if matches(self.course_tags(guid)) {
+            return true;
+        }
+
+        programs.into_iter().any(|program| {
+            program.iter_requirements().any(|requirement| {
+                requirement
+                    .course_entries()
+                    .is_some_and(|entries| entries.iter_courses().any(|c| c.guid == *guid))
+                    && matches(self.tags_of(program, requirement))
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::catalog::Catalog;
+
+    #[test]
+    fn requirements_are_tagged_by_their_cleaned_title() {
+        let (catalog, _) = Catalog::parse_file("../data/cs_minor.json");
+        let program = &catalog.programs[0];
+        let csc_115 = Guid::try_from("860AF9C9-EAD9-45AC-AA92-BAF352C5288C").unwrap();
+
+        let mut tags = TagMapping::new();
+        tags.tag_requirement(program.guid, " Minor  Requirements:", "core");
+        tags.tag_requirement(program.guid, "Minor Requirements:", "Gen-Ed: Humanities");
+        tags.tag_course(csc_115, "intro");
+
+        let minor = program.iter_requirements().next().unwrap();
+        assert_eq!(tags.tags_of(program, minor), ["Gen-Ed: Humanities", "core"]);
+        assert_eq!(tags.course_tags(&csc_115), ["intro"]);
+
+        assert!(tags.has_tag(&csc_115, [program], "intro"));
+        assert!(tags.has_tag(&csc_115, [program], "gen-ed: humanities"));
+        assert!(!tags.has_tag(&csc_115, [], "core"));
+
+        let json = serde_json::to_string(&tags).unwrap();
+        assert_eq!(TagMapping::from_json(&json).unwrap(), tags);
+    }
+}
@@ -0,0 +1,28 @@
+//! Fixtures from the `data` directory shared by the unit tests
+
+use crate::{catalog::Catalog, Program};
+
+/// Program parsed from a file in the `data` directory, such as `"cs_minor.json"`
+pub(crate) fn read_program(file_name: &str) -> Program {
+    let program_json = std::fs::read_to_string(format!("../data/{file_name}")).unwrap();
+    serde_json::from_str(&program_json).unwrap()
+}
+
+pub(crate) fn cs_minor() -> Program {
+    read_program("cs_minor.json")
+}
+
+pub(crate) fn cs_major() -> Program {
+    read_program("cs_major.json")
+}
+
+/// Catalog of the CS minor along with every course
+pub(crate) fn cs_minor_catalog() -> Catalog {
+    let (mut catalog, errors) = Catalog::parse_file("../data/cs_minor.json");
+    assert!(errors.is_empty());
+
+    let (courses, _) = Catalog::parse_file("../data/courses.json");
+    catalog.courses = courses.courses;
+
+    catalog
+}
@@ -0,0 +1,400 @@
+//! [Arbitrary] implementations of the data model for property tests and fuzzing, behind the
+//! `testing` feature.
+//!
+//! Generated values are shaped like the ones parsing makes, so downstream crates can run their
+//! traversals on them:
+//! - courses have a subject code of two to four capital letters and a three digit number,
+//!   sometimes followed by a suffix such as "L"
+//! - credits are fixed or variable, like `(1, Some(3))`
+//! - course lists nest `And` and `Or` groups up to [MAX_DEPTH] levels deep
+//!
+//! Requirement modules are never [Unimplemented](RequirementModule::Unimplemented) or
+//! [references](RequirementModule::Reference) to shared modules, extensions are always empty and
+//! course details are never attached, since parsing alone doesn't make those.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::Program;
+//! use arbitrary::{Arbitrary, Unstructured};
+//!
+//! let bytes: Vec<u8> = (0..4096u32).map(|i| (i.wrapping_mul(2654435761) >> 13) as u8).collect();
+//! let program = Program::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+//!
+//! for course in program.iter_courses() {
+//!     assert!(course.code().is_ok());
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{
+    constraints::{Constraints, Gpa, Grade},
+    electives::ElectiveHours,
+    extensions::Extensions,
+    parsing::guid::Guid,
+    references::CourseRef,
+    symbol::Symbol,
+    Course, CourseDetails, CourseEntries, CourseEntry, Label, Program, Requirement,
+    RequirementModule, Requirements,
+};
+
+/// How deep `And` and `Or` groups are nested in generated course lists
+pub const MAX_DEPTH: usize = 3;
+
+const GRADES: [Grade; 13] = [
+    Grade::F,
+    Grade::DMinus,
+    Grade::D,
+    Grade::DPlus,
+    Grade::CMinus,
+    Grade::C,
+    Grade::CPlus,
+    Grade::BMinus,
+    Grade::B,
+    Grade::BPlus,
+    Grade::AMinus,
+    Grade::A,
+    Grade::APlus,
+];
+
+fn subject_code(u: &mut Unstructured<'_>) -> Result<String> {
+    let len = u.int_in_range(2..=4)?;
+    (0..len)
+        .map(|_| Ok(char::from(u.int_in_range(b'A'..=b'Z')?)))
+        .collect()
+}
+
+fn number(u: &mut Unstructured<'_>) -> Result<String> {
+    let mut number = format!("{:03}", u.int_in_range(100..=499u16)?);
+    if u.ratio(1, 5)? {
+        number.push(*u.choose(&['L', 'H', 'K'])?);
+    }
+
+    Ok(number)
+}
+
+fn credits(u: &mut Unstructured<'_>) -> Result<(u8, Option<u8>)> {
+    let min = u.int_in_range(0..=4)?;
+    let max = if u.ratio(1, 4)? {
+        Some(u.int_in_range(min + 1..=6)?)
+    } else {
+        None
+    };
+
+    Ok((min, max))
+}
+
+fn url(path: &str) -> String {
+    format!("https://catalog.example.edu{path}")
+}
+
+/// Between `min` and `max` values made with `make`
+fn many<'a, T>(
+    u: &mut Unstructured<'a>,
+    min: usize,
+    max: usize,
+    mut make: impl FnMut(&mut Unstructured<'a>) -> Result<T>,
+) -> Result<Vec<T>> {
+    let len = u.int_in_range(min..=max)?;
+    (0..len).map(|_| make(u)).collect()
+}
+
+/// Entries of a course list whose groups are at most `depth` levels deep
+fn entries(u: &mut Unstructured<'_>, depth: usize) -> Result<CourseEntries> {
+    let entries = many(u, 1, 5, |u| entry(u, depth))?;
+
+    Ok(entries.into_iter().collect())
+}
+
+fn entry(u: &mut Unstructured<'_>, depth: usize) -> Result<CourseEntry> {
+    let kinds = if depth == 0 { 2 } else { 4 };
+
+    Ok(match u.choose_index(kinds)? {
+        0 => CourseEntry::Course(Arc::new(Course::arbitrary(u)?)),
+        1 => CourseEntry::Label(Label::arbitrary(u)?),
+        2 => CourseEntry::And(entries(u, depth - 1)?),
+        _ => CourseEntry::Or(entries(u, depth - 1)?),
+    })
+}
+
+impl<'a> Arbitrary<'a> for Guid {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let guid = format!("{:032X}", u128::arbitrary(u)?);
+
+        Ok(Guid::try_from(guid.as_str()).expect("32 hexadecimal digits should be a valid GUID"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Symbol {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Symbol::from(String::arbitrary(u)?))
+    }
+}
+
+/// Always empty, see the [module](self) documentation
+impl<'a> Arbitrary<'a> for Extensions {
+    fn arbitrary(_: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Extensions::new())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Grade {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&GRADES).copied()
+    }
+}
+
+impl<'a> Arbitrary<'a> for Gpa {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Gpa::from_hundredths(u.int_in_range(0..=400)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Constraints {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Constraints {
+            min_grade: Option::arbitrary(u)?,
+            min_gpa: Option::arbitrary(u)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for ElectiveHours {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(if u.arbitrary()? {
+            ElectiveHours::Hours(u.int_in_range(1..=24)?)
+        } else {
+            ElectiveHours::ToTotal(u.int_in_range(120..=136)?)
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for CourseRef {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(CourseRef {
+            subject_code: subject_code(u)?,
+            number: number(u)?,
+            guid: Option::arbitrary(u)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Course {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let subject_code = subject_code(u)?;
+        let number = number(u)?;
+        let path = format!("/{}-{number}", subject_code.to_lowercase());
+
+        Ok(Course {
+            url: url(&path),
+            path,
+            guid: Guid::arbitrary(u)?,
+            name: Option::arbitrary(u)?,
+            number,
+            subject_name: Option::arbitrary(u)?,
+            subject_code: subject_code.into(),
+            credits: credits(u)?,
+            extra: Extensions::new(),
+            details: None,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Label {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let guid = Guid::arbitrary(u)?;
+
+        Ok(Label {
+            url: url(&format!("/{guid}")),
+            guid,
+            name: String::arbitrary(u)?,
+            number: Option::arbitrary(u)?,
+            subject_code: Option::arbitrary(u)?,
+            credits: credits(u)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for CourseDetails {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let course = Course::arbitrary(u)?;
+
+        Ok(CourseDetails {
+            url: course.url,
+            guid: course.guid,
+            path: course.path,
+            subject_code: course.subject_code,
+            subject_name: course.subject_name,
+            number: course.number,
+            name: course.name.unwrap_or_default(),
+            credits_min: course.credits.0,
+            credits_max: course.credits.1,
+            description: String::arbitrary(u)?,
+            prerequisite_narrative: Option::arbitrary(u)?,
+            prerequisite: Option::arbitrary(u)?,
+            corequisite_narrative: Option::arbitrary(u)?,
+            corequisite: Option::arbitrary(u)?,
+            extra: Extensions::new(),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for CourseEntry {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        entry(u, MAX_DEPTH)
+    }
+}
+
+impl<'a> Arbitrary<'a> for CourseEntries {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        entries(u, MAX_DEPTH)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Requirement {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.choose_index(4)? {
+            0 => Requirement::Courses {
+                title: Option::arbitrary(u)?,
+                courses: CourseEntries::arbitrary(u)?,
+                constraints: Constraints::arbitrary(u)?,
+                extra: Extensions::new(),
+            },
+            1 => Requirement::SelectFromCourses {
+                title: String::arbitrary(u)?,
+                courses: Option::arbitrary(u)?,
+                constraints: Constraints::arbitrary(u)?,
+                extra: Extensions::new(),
+            },
+            2 => Requirement::Label {
+                title: Option::arbitrary(u)?,
+                req_narrative: Option::arbitrary(u)?,
+                constraints: Constraints::arbitrary(u)?,
+                course_refs: many(u, 0, 2, CourseRef::arbitrary)?,
+                extra: Extensions::new(),
+            },
+            _ => Requirement::ElectivePool {
+                title: Option::arbitrary(u)?,
+                req_narrative: Option::arbitrary(u)?,
+                hours: ElectiveHours::arbitrary(u)?,
+                constraints: Constraints::arbitrary(u)?,
+                extra: Extensions::new(),
+            },
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for RequirementModule {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.choose_index(4)? {
+            0 => RequirementModule::SingleBasicRequirement {
+                title: Option::arbitrary(u)?,
+                requirement: Requirement::arbitrary(u)?,
+            },
+            1 => RequirementModule::BasicRequirements {
+                title: Option::arbitrary(u)?,
+                requirements: many(u, 1, 4, Requirement::arbitrary)?,
+            },
+            2 => RequirementModule::SelectOneEmphasis {
+                emphases: many(u, 1, 3, Requirement::arbitrary)?,
+            },
+            _ => RequirementModule::Label {
+                title: String::arbitrary(u)?,
+            },
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Requirements {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.choose_index(3)? {
+            0 => Requirements::Single(RequirementModule::arbitrary(u)?),
+            1 => Requirements::Many(many(u, 1, 3, RequirementModule::arbitrary)?),
+            _ => Requirements::SelectTrack,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Program {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let guid = Guid::arbitrary(u)?;
+
+        Ok(Program {
+            url: url(&format!("/programs/{guid}")),
+            guid,
+            title: String::arbitrary(u)?,
+            content: Option::arbitrary(u)?,
+            bottom_content: Option::arbitrary(u)?,
+            requirements: Option::arbitrary(u)?,
+            extra: Extensions::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{catalog::Catalog, hash::content_hash, semantic::SemanticEq, wire::WireCatalog};
+
+    /// Programs made out of deterministic pseudo-random bytes
+    fn programs() -> impl Iterator<Item = Program> {
+        (0..64u64).map(|seed| {
+            let bytes: Vec<u8> = (0..8192u64)
+                .map(|i| content_hash(&(seed, i)) as u8)
+                .collect();
+            Program::arbitrary(&mut Unstructured::new(&bytes)).unwrap()
+        })
+    }
+
+    fn depth(entries: &CourseEntries) -> usize {
+        entries
+            .iter()
+            .map(|entry| match entry {
+                CourseEntry::And(group) | CourseEntry::Or(group) => 1 + depth(group),
+                CourseEntry::Course(_) | CourseEntry::Label(_) => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn generated_programs_are_structurally_valid() {
+        let mut groups = 0;
+        let mut variable_credits = 0;
+
+        for program in programs() {
+            for requirement in program.iter_requirements() {
+                if let Some(entries) = requirement.course_entries() {
+                    assert!(!entries.is_empty());
+                    assert!(depth(entries) <= MAX_DEPTH);
+                    groups += depth(entries).min(1);
+                }
+            }
+
+            for course in program.iter_courses() {
+                assert!(course.code().is_ok(), "{}", course.code_label());
+                if let (min, Some(max)) = course.credits {
+                    assert!(min < max);
+                    variable_credits += 1;
+                }
+            }
+        }
+
+        assert!(groups > 0);
+        assert!(variable_credits > 0);
+    }
+
+    #[test]
+    fn generated_programs_survive_the_wire_format() {
+        let catalog = Catalog {
+            programs: programs().collect(),
+            ..Default::default()
+        };
+
+        let decoded = Catalog::try_from(WireCatalog::from(&catalog)).unwrap();
+        assert_eq!(decoded, catalog);
+        assert!(decoded.programs.semantic_eq(&catalog.programs));
+    }
+}
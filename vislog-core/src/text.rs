@@ -0,0 +1,444 @@
+//! Cleanup of the HTML found in the names and narratives of the catalog.
+//!
+//! The catalog API sends text the way the catalog website shows it. Narratives are HTML fragments
+//! (`<p>Choose <strong>one</strong> of the following:</p>`) and even names carry entities
+//! (`Art &amp; Design`). [clean_narrative] turns such text into a single line of plain text, and
+//! [narrative_to_markdown] keeps its emphasis, links, line breaks and lists as Markdown.
+//!
+//! Both can be applied to every name and narrative of what is parsed by setting
+//! [ParseOptions::text](crate::parsing::options::ParseOptions::text).
+//!
+//! # Example
+//! ```
+//! # use vislog_core::text::{clean_narrative, narrative_to_markdown};
+//! let narrative = "<p>Choose <em>one</em> of the following&nbsp;courses:</p>\n<ul><li>Art &amp; Design</li></ul>";
+//! assert_eq!(
+//!     clean_narrative(narrative),
+//!     "Choose one of the following courses: Art & Design"
+//! );
+//! assert_eq!(
+//!     narrative_to_markdown(narrative),
+//!     "Choose *one* of the following courses:\n\n- Art & Design"
+//! );
+//! ```
+
+/// Named entities found in the catalog, and the characters they stand for
+const ENTITIES: &[(&str, &str)] = &[
+    ("amp", "&"),
+    ("lt", "<"),
+    ("gt", ">"),
+    ("quot", "\""),
+    ("apos", "'"),
+    ("nbsp", " "),
+    ("ndash", "–"),
+    ("mdash", "—"),
+    ("lsquo", "‘"),
+    ("rsquo", "’"),
+    ("ldquo", "“"),
+    ("rdquo", "”"),
+    ("hellip", "…"),
+    ("bull", "•"),
+    ("middot", "·"),
+    ("deg", "°"),
+    ("times", "×"),
+    ("copy", "©"),
+    ("reg", "®"),
+    ("trade", "™"),
+];
+
+/// Tags that don't break the flow of the text, and are removed without leaving a space
+const INLINE_TAGS: &[&str] = &[
+    "a", "abbr", "b", "cite", "code", "em", "font", "i", "small", "span", "strong", "sub", "sup",
+    "u",
+];
+
+/// The `text` with its HTML tags removed, its entities decoded and its whitespace collapsed into
+/// single spaces
+pub fn clean_narrative(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    for token in tokens(text) {
+        match token {
+            Token::Text(text) => plain.push_str(&decode_entities(text)),
+            Token::Tag(tag) if INLINE_TAGS.contains(&tag.name.as_str()) => {}
+            Token::Tag(_) => plain.push(' '),
+        }
+    }
+
+    collapse_whitespace(&plain)
+}
+
+/// The `text` with its entities decoded and its HTML converted to Markdown. Emphasis, links,
+/// line breaks, paragraphs, headings and lists are kept; other tags are removed.
+pub fn narrative_to_markdown(text: &str) -> String {
+    let mut markdown = Markdown::default();
+    for token in tokens(text) {
+        match token {
+            Token::Text(text) => markdown.text(&decode_entities(text)),
+            Token::Tag(tag) => markdown.tag(&tag),
+        }
+    }
+
+    markdown.finish()
+}
+
+/// The `text` with its HTML tags replaced by spaces
+pub fn strip_tags(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    for token in tokens(text) {
+        match token {
+            Token::Text(text) => stripped.push_str(text),
+            Token::Tag(_) => stripped.push(' '),
+        }
+    }
+
+    stripped
+}
+
+/// The `text` with its named and numeric character references replaced by the characters they
+/// stand for. Unknown entities are kept as they are.
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .map(|end| &rest[1..=end]);
+        match entity.and_then(decode_entity) {
+            Some(c) => {
+                decoded.push(c);
+                rest = &rest[entity.map_or(0, str::len) + 2..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+/// The character of the entity named `name`, such as "amp", "#39" or "#x2014"
+fn decode_entity(name: &str) -> Option<char> {
+    let code = match name.strip_prefix('#') {
+        Some(hex) if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16).ok(),
+        Some(decimal) => decimal.parse().ok(),
+        None => {
+            let (_, replacement) = ENTITIES.iter().find(|(entity, _)| *entity == name)?;
+            return replacement.chars().next();
+        }
+    };
+
+    code.and_then(char::from_u32)
+        .map(|c| if c == '\u{a0}' { ' ' } else { c })
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    Tag(Tag<'a>),
+}
+
+struct Tag<'a> {
+    /// Lowercased
+    name: String,
+    closing: bool,
+    attributes: &'a str,
+}
+
+impl Tag<'_> {
+    /// Value of the `attribute` of the tag, when it is quoted
+    fn attribute(&self, attribute: &str) -> Option<&str> {
+        let mut rest = self.attributes;
+        while let Some(start) = rest.find(attribute) {
+            let value = rest[start + attribute.len()..].trim_start();
+            rest = &rest[start + attribute.len()..];
+            let Some(value) = value.strip_prefix('=').map(str::trim_start) else {
+                continue;
+            };
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            return value[1..].split(quote).next();
+        }
+
+        None
+    }
+}
+
+/// Splits `text` into runs of text and tags. A `<` that doesn't open a tag, as in "GPA < 2.5",
+/// is text.
+fn tokens(text: &str) -> impl Iterator<Item = Token<'_>> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        if let Some(tag) = rest.strip_prefix('<').and_then(|tag| {
+            let end = tag.find('>')?;
+            let opens_tag =
+                tag.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!');
+            opens_tag.then(|| &tag[..end])
+        }) {
+            rest = &rest[tag.len() + 2..];
+            let (closing, tag) = match tag.strip_prefix('/') {
+                Some(tag) => (true, tag),
+                None => (false, tag),
+            };
+            let tag = tag.trim_end_matches('/');
+            let name_end = tag
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(tag.len());
+            return Some(Token::Tag(Tag {
+                name: tag[..name_end].to_ascii_lowercase(),
+                closing,
+                attributes: &tag[name_end..],
+            }));
+        }
+
+        let end = rest
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '<')
+            .map_or(rest.len(), |(end, _)| end);
+        let (text, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(Token::Text(text))
+    })
+}
+
+/// Markdown written out of the tokens of an HTML fragment
+#[derive(Default)]
+struct Markdown {
+    out: String,
+    /// Target of each link being written, `None` for anchors without one
+    links: Vec<Option<String>>,
+    /// Number of the next item of each list being written, `None` for unordered lists
+    lists: Vec<Option<usize>>,
+    /// Where the last emphasis marker was opened, while no text followed it yet
+    opened: Option<usize>,
+}
+
+impl Markdown {
+    fn text(&mut self, text: &str) {
+        let mut text = text;
+        // Whitespace right after an opening marker goes before it: "** Note**" isn't emphasis
+        if let Some(opened) = self.opened {
+            let trimmed = text.trim_start();
+            if trimmed.len() < text.len() && !self.out[..opened].ends_with(char::is_whitespace) {
+                self.out.insert(opened, ' ');
+            }
+            text = trimmed;
+            if text.is_empty() {
+                return;
+            }
+        }
+        self.opened = None;
+
+        let mut words = text.split_whitespace();
+        if text.starts_with(char::is_whitespace) {
+            self.space();
+        }
+        if let Some(first) = words.next() {
+            self.out.push_str(first);
+            for word in words {
+                self.out.push(' ');
+                self.out.push_str(word);
+            }
+            if text.ends_with(char::is_whitespace) {
+                self.space();
+            }
+        }
+    }
+
+    /// Separates what comes next from what was written, unless something already does
+    fn space(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with(char::is_whitespace) {
+            self.out.push(' ');
+        }
+    }
+
+    fn tag(&mut self, tag: &Tag) {
+        if !matches!(tag.name.as_str(), "em" | "i" | "strong" | "b") {
+            self.opened = None;
+        }
+
+        match (tag.name.as_str(), tag.closing) {
+            ("em" | "i", _) => self.emphasis("*", tag.closing),
+            ("strong" | "b", _) => self.emphasis("**", tag.closing),
+            ("br", _) => self.out.push('\n'),
+            ("a", false) => {
+                let href = tag.attribute("href").map(str::to_owned);
+                if href.is_some() {
+                    self.out.push('[');
+                }
+                self.links.push(href);
+            }
+            ("a", true) => {
+                if let Some(Some(href)) = self.links.pop() {
+                    self.out.push_str("](");
+                    self.out.push_str(&href);
+                    self.out.push(')');
+                }
+            }
+            ("ul", false) => self.open_list(None),
+            ("ol", false) => self.open_list(Some(1)),
+            ("ul" | "ol", true) => {
+                self.lists.pop();
+                self.out.push_str("\n\n");
+            }
+            ("li", false) => {
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "- ".to_owned(),
+                };
+                self.out.push('\n');
+                self.out
+                    .push_str(&"  ".repeat(self.lists.len().saturating_sub(1)));
+                self.out.push_str(&marker);
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                let level = tag.name[1..].parse().unwrap_or(1);
+                self.out.push_str("\n\n");
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+            }
+            ("p" | "div" | "table" | "blockquote" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6", _) => {
+                self.out.push_str("\n\n")
+            }
+            ("tr", _) => self.out.push('\n'),
+            ("td" | "th", _) => self.space(),
+            _ => {}
+        }
+    }
+
+    fn open_list(&mut self, start: Option<usize>) {
+        self.lists.push(start);
+        if self.lists.len() == 1 {
+            self.out.push_str("\n\n");
+        }
+    }
+
+    fn emphasis(&mut self, marker: &str, closing: bool) {
+        if !closing {
+            self.opened = Some(self.out.len());
+            self.out.push_str(marker);
+            return;
+        }
+
+        // Whitespace right before a closing marker goes after it
+        let trimmed = self.out.trim_end_matches([' ', '\t']).len();
+        let trailing = self.out.split_off(trimmed);
+        if self.opened.take().is_some() && self.out.ends_with(marker) {
+            // Nothing was emphasized
+            self.out.truncate(self.out.len() - marker.len());
+        } else {
+            self.out.push_str(marker);
+        }
+        self.out.push_str(&trailing);
+    }
+
+    /// The Markdown with trailing whitespace removed from its lines and no more than one blank
+    /// line in a row
+    fn finish(self) -> String {
+        let mut markdown = String::with_capacity(self.out.len());
+        let mut blank = false;
+        for line in self.out.lines() {
+            // Only nested list items start with whitespace, which they keep
+            let line = line.trim_end();
+            if line.is_empty() {
+                blank = !markdown.is_empty();
+                continue;
+            }
+            if !markdown.is_empty() {
+                markdown.push('\n');
+                if blank {
+                    markdown.push('\n');
+                }
+            }
+            markdown.push_str(line);
+            blank = false;
+        }
+
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn entities_are_decoded() {
+        assert_eq!(
+            decode_entities("Art &amp; Design&mdash;Women&rsquo;s &#39;Studies&#x27; &copy"),
+            "Art & Design—Women’s 'Studies' &copy"
+        );
+        assert_eq!(
+            decode_entities("A&B &unknown; &#xZZ;"),
+            "A&B &unknown; &#xZZ;"
+        );
+        assert_eq!(decode_entities("1&nbsp;2&#160;3"), "1 2 3");
+    }
+
+    #[test]
+    fn narratives_are_cleaned() {
+        assert_eq!(
+            clean_narrative(
+                "<p>A GPA < 2.5 in <strong>CSC</strong>&nbsp;courses</p><p>is\n  not allowed</p>"
+            ),
+            "A GPA < 2.5 in CSC courses is not allowed"
+        );
+        assert_eq!(clean_narrative("&lt;em&gt; is text"), "<em> is text");
+        assert_eq!(strip_tags("<p>a</p>b"), " a b");
+    }
+
+    #[test]
+    fn narratives_are_converted_to_markdown() {
+        assert_eq!(
+            narrative_to_markdown(
+                "<p><strong>Note: </strong>see <a href=\"https://www.uu.edu\">the catalog</a>.<br />Then<em></em> choose:</p>\n<ol><li>One</li><li>Two<ul><li>Nested</li></ul></li></ol><p>Done</p>"
+            ),
+            "**Note:** see [the catalog](https://www.uu.edu).\nThen choose:\n\n1. One\n2. Two\n  - Nested\n\nDone"
+        );
+        assert_eq!(
+            narrative_to_markdown("<h3>Core</h3>Text"),
+            "### Core\n\nText"
+        );
+    }
+
+    #[test]
+    fn catalog_narratives_are_cleaned() {
+        let json = std::fs::read_to_string("../data/programs.json").unwrap();
+        for content in json.split("\"content\":").skip(1) {
+            let Ok(serde_json::Value::String(content)) =
+                serde_json::Deserializer::from_str(content)
+                    .into_iter::<serde_json::Value>()
+                    .next()
+                    .unwrap_or(Ok(serde_json::Value::Null))
+            else {
+                continue;
+            };
+            let plain = clean_narrative(&content);
+            assert!(
+                !plain.contains("<p>") && !plain.contains("&amp;"),
+                "{plain}"
+            );
+            let markdown = narrative_to_markdown(&content);
+            assert!(
+                !markdown.contains("<strong>") && !markdown.contains("&nbsp;"),
+                "{markdown}"
+            );
+        }
+    }
+}
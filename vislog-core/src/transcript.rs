@@ -0,0 +1,510 @@
+//! Course histories exported by registrar systems, read into the [CompletedCourse]s and
+//! [CourseSet] that [audits](crate::audit) check programs against.
+//!
+//! [Transcript::from_csv] reads the CSV course history exports of Banner and Colleague, or CSV with
+//! plain column names, telling them apart by their header row:
+//!
+//! | Format                         | Term        | Subject       | Number          | Credits        | Grade                |
+//! |--------------------------------|-------------|---------------|-----------------|----------------|----------------------|
+//! | [Banner][TranscriptFormat]     | `TERM_CODE` | `SUBJ_CODE`   | `CRSE_NUMB`     | `CREDIT_HOURS` | `GRDE_CODE`          |
+//! | [Colleague][TranscriptFormat]  | `STC.TERM`  | `STC.SUBJECT` | `STC.COURSE.NO` | `STC.CRED`     | `STC.VERIFIED.GRADE` |
+//! | [Generic][TranscriptFormat]    | `term`      | `subject`     | `number`        | `credits`      | `grade`              |
+//!
+//! Header names are compared without case. Only these five columns are read, so exports keep
+//! their student IDs, names and other columns out of the transcript.
+//!
+//! Terms are read like [Term]s, such as "Fall 2024" or "2024FA", and Banner term codes such as
+//! "202430" are read as a year followed by `10` for spring, `20` for summer, `30` for fall and
+//! `40` for winter. Grades are letter grades or the codes registrars use for other outcomes, see
+//! [TranscriptGrade].
+//!
+//! A course taken several times gives a single [CompletedCourse], picked by a [RepeatPolicy].
+//! Attempts that were withdrawn, audited or failed without a letter grade are never picked, and
+//! courses still in progress or incomplete are [in progress](Progress::InProgress).
+//! [Transcript::attempts] keeps every attempt instead, for
+//! [audit_with](crate::audit::audit_with) to apply a whole
+//! [GradePolicy](crate::audit::GradePolicy) to them.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{audit::{self, RepeatPolicy, Status}, transcript::{Transcript, TranscriptFormat}, Program};
+//! let csv = "\
+//! STUDENT_ID,TERM_CODE,SUBJ_CODE,CRSE_NUMB,CREDIT_HOURS,GRDE_CODE
+//! 000123,202330,CSC,115,3.000,D
+//! 000123,202410,CSC,115,3.000,B+
+//! 000123,202410,ENG,111,3.000,W
+//! 000123,202430,CSC,125,3.000,
+//! ";
+//!
+//! let transcript = Transcript::from_csv(csv.as_bytes()).unwrap();
+//! assert_eq!(transcript.format, TranscriptFormat::Banner);
+//!
+//! let completed = transcript.completed_courses(RepeatPolicy::Latest);
+//! assert_eq!(completed.len(), 2);
+//!
+//! let program_json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+//! let program: Program = serde_json::from_str(&program_json).unwrap();
+//! let report = audit::audit(&program, &completed);
+//! assert_eq!(report.status, Status::Partial);
+//! ```
+
+use std::{collections::HashMap, fmt, io::Read};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    audit::{code_key, CompletedCourse, CourseId, CourseSet, Progress, RepeatPolicy},
+    constraints::{CourseGrade, Grade},
+    schedule::{Season, Term},
+};
+
+/// Registrar system a course history was exported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TranscriptFormat {
+    Banner,
+    Colleague,
+    /// Plain column names, for histories put together by hand
+    Generic,
+}
+
+impl TranscriptFormat {
+    const ALL: [TranscriptFormat; 3] = [
+        TranscriptFormat::Banner,
+        TranscriptFormat::Colleague,
+        TranscriptFormat::Generic,
+    ];
+
+    /// Names of the term, subject, number, credits and grade columns
+    pub fn columns(self) -> [&'static str; 5] {
+        match self {
+            TranscriptFormat::Banner => [
+                "TERM_CODE",
+                "SUBJ_CODE",
+                "CRSE_NUMB",
+                "CREDIT_HOURS",
+                "GRDE_CODE",
+            ],
+            TranscriptFormat::Colleague => [
+                "STC.TERM",
+                "STC.SUBJECT",
+                "STC.COURSE.NO",
+                "STC.CRED",
+                "STC.VERIFIED.GRADE",
+            ],
+            TranscriptFormat::Generic => ["term", "subject", "number", "credits", "grade"],
+        }
+    }
+
+    /// Format with every one of its columns in the `header`, and the indices of the columns
+    fn detect(header: &csv::StringRecord) -> Option<(Self, [usize; 5])> {
+        Self::ALL.into_iter().find_map(|format| {
+            let mut indices = [0; 5];
+            for (index, column) in indices.iter_mut().zip(format.columns()) {
+                *index = header
+                    .iter()
+                    .position(|name| name.trim().eq_ignore_ascii_case(column))?;
+            }
+
+            Some((format, indices))
+        })
+    }
+}
+
+/// Outcome of an attempt of a course, as registrars record it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TranscriptGrade {
+    Letter(Grade),
+    /// Passed without a letter grade: `P`, `S`, `CR` or `PASS`
+    Pass,
+    /// Failed without a letter grade: `NP`, `U`, `NC` or `FAIL`
+    NoCredit,
+    /// Credit transferred from another institution: `T` or `TR`
+    Transfer,
+    /// `W`, `WP`, `WF` or `WD`
+    Withdrawn,
+    /// `AU`
+    Audit,
+    /// `I` or `INC`, completed once the grade is turned in
+    Incomplete,
+    /// No grade yet, or `IP`
+    InProgress,
+}
+
+impl TranscriptGrade {
+    /// Parses the grade column of a row, `None` when it isn't a grade
+    pub fn parse(grade: &str) -> Option<Self> {
+        let grade = grade.trim().to_uppercase();
+        if let Ok(letter) = grade.parse() {
+            return Some(TranscriptGrade::Letter(letter));
+        }
+
+        let grade = match grade.as_str() {
+            "P" | "S" | "CR" | "PASS" => TranscriptGrade::Pass,
+            "NP" | "U" | "NC" | "FAIL" => TranscriptGrade::NoCredit,
+            "T" | "TR" => TranscriptGrade::Transfer,
+            "W" | "WP" | "WF" | "WD" => TranscriptGrade::Withdrawn,
+            "AU" => TranscriptGrade::Audit,
+            "I" | "INC" => TranscriptGrade::Incomplete,
+            "" | "IP" => TranscriptGrade::InProgress,
+            _ => return None,
+        };
+
+        Some(grade)
+    }
+
+    /// Whether the attempt can count towards requirements, now or once it is graded
+    fn counts(self) -> bool {
+        !matches!(
+            self,
+            TranscriptGrade::NoCredit | TranscriptGrade::Withdrawn | TranscriptGrade::Audit
+        )
+    }
+
+    fn is_pending(self) -> bool {
+        matches!(
+            self,
+            TranscriptGrade::Incomplete | TranscriptGrade::InProgress
+        )
+    }
+
+    /// Rank of the grade among the attempts of a course that count, higher is better. Grades
+    /// without a letter pass like a C.
+    fn rank(self) -> Grade {
+        match self {
+            TranscriptGrade::Letter(grade) => grade,
+            _ => Grade::C,
+        }
+    }
+}
+
+/// An attempt of a course in the history of a student
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TranscriptEntry {
+    pub term: Term,
+    pub subject_code: String,
+    pub number: String,
+    /// Credits attempted, rounded to the nearest credit
+    pub credits: u8,
+    pub grade: TranscriptGrade,
+}
+
+/// Course history of a student, with an entry for every attempt of a course
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Transcript {
+    pub format: TranscriptFormat,
+    /// Attempts in the order of the export
+    pub entries: Vec<TranscriptEntry>,
+}
+
+#[derive(Debug, Error)]
+pub enum TranscriptError {
+    #[error("failed to read CSV transcript: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("the header {0:?} doesn't have the columns of a known transcript format")]
+    UnknownFormat(Vec<String>),
+    #[error("row {row}: {value:?} is not a valid {field}")]
+    Value {
+        /// Row of the CSV, from 1 for the header row
+        row: u64,
+        field: &'static str,
+        value: String,
+    },
+}
+
+impl Transcript {
+    /// Reads a course history exported as CSV, in any of the formats of the [module](self)
+    /// documentation. Rows without a subject code are skipped.
+    pub fn from_csv(reader: impl Read) -> Result<Self, TranscriptError> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+        let header = reader.headers()?.clone();
+        let (format, [term, subject, number, credits, grade]) = TranscriptFormat::detect(&header)
+            .ok_or_else(|| {
+            TranscriptError::UnknownFormat(header.iter().map(Into::into).collect())
+        })?;
+
+        let mut entries = vec![];
+        for record in reader.records() {
+            let record = record?;
+            let row = record.position().map_or(0, |position| position.line());
+            let field = |index: usize| record.get(index).unwrap_or_default().trim();
+            let invalid = |field: &'static str, value: &str| TranscriptError::Value {
+                row,
+                field,
+                value: value.to_owned(),
+            };
+
+            if field(subject).is_empty() {
+                continue;
+            }
+
+            entries.push(TranscriptEntry {
+                term: parse_term(field(term)).ok_or_else(|| invalid("term", field(term)))?,
+                subject_code: field(subject).to_owned(),
+                number: field(number).to_owned(),
+                credits: field(credits)
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|credits| (0.0..=f32::from(u8::MAX)).contains(credits))
+                    .map(|credits| credits.round() as u8)
+                    .ok_or_else(|| invalid("number of credits", field(credits)))?,
+                grade: TranscriptGrade::parse(field(grade))
+                    .ok_or_else(|| invalid("grade", field(grade)))?,
+            });
+        }
+
+        Ok(Self { format, entries })
+    }
+
+    /// A course for every course of the history with an attempt that counts, in the order of
+    /// their first attempt. When a course was taken several times, the `policy` picks the graded
+    /// attempt that counts. A course with an attempt still pending is in progress, unless an
+    /// earlier attempt passed it.
+    pub fn completed_courses(&self, policy: RepeatPolicy) -> Vec<CompletedCourse> {
+        let mut order: Vec<(String, String)> = vec![];
+        let mut attempts: HashMap<(String, String), Vec<&TranscriptEntry>> = HashMap::new();
+        for entry in &self.entries {
+            let key = code_key(&entry.subject_code, &entry.number);
+            if !attempts.contains_key(&key) {
+                order.push(key.clone());
+            }
+            attempts.entry(key).or_default().push(entry);
+        }
+
+        order
+            .iter()
+            .filter_map(|key| {
+                let mut attempts: Vec<&TranscriptEntry> = attempts[key]
+                    .iter()
+                    .filter(|entry| entry.grade.counts())
+                    .copied()
+                    .collect();
+                // Stable, so attempts in the same term keep the order of the export
+                attempts.sort_by_key(|entry| entry.term);
+
+                let (pending, graded): (Vec<_>, Vec<_>) = attempts
+                    .into_iter()
+                    .partition(|entry| entry.grade.is_pending());
+                let graded = match policy {
+                    RepeatPolicy::Latest => graded.last(),
+                    RepeatPolicy::Highest => graded.iter().max_by_key(|entry| entry.grade.rank()),
+                };
+
+                match (graded, pending.last()) {
+                    (Some(graded), Some(pending))
+                        if graded.grade == TranscriptGrade::Letter(Grade::F) =>
+                    {
+                        Some(completed_course(pending))
+                    }
+                    (Some(graded), _) => Some(completed_course(graded)),
+                    (None, Some(pending)) => Some(completed_course(pending)),
+                    (None, None) => None,
+                }
+            })
+            .collect()
+    }
+
+    /// A course for every attempt of the history in the order of their terms, with failed and
+    /// withdrawn attempts, for [audit_with] to pick the attempts that count. Audited attempts are
+    /// left out, and pending ones are in progress.
+    ///
+    /// [audit_with]: crate::audit::audit_with
+    pub fn attempts(&self) -> Vec<CompletedCourse> {
+        let mut entries: Vec<&TranscriptEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.grade != TranscriptGrade::Audit)
+            .collect();
+        entries.sort_by_key(|entry| entry.term);
+
+        entries
+            .into_iter()
+            .map(|entry| {
+                let mut course = completed_course(entry);
+                course.grade = match entry.grade {
+                    TranscriptGrade::NoCredit => Some(CourseGrade::Fail),
+                    TranscriptGrade::Withdrawn => Some(CourseGrade::Withdrawn),
+                    _ => course.grade,
+                };
+                course
+            })
+            .collect()
+    }
+
+    /// The [completed courses](Self::completed_courses) that count, for evaluating requirements
+    /// on their own. Courses in progress are left out.
+    pub fn course_set(&self, policy: RepeatPolicy) -> CourseSet {
+        let completed: Vec<CompletedCourse> = self
+            .completed_courses(policy)
+            .into_iter()
+            .filter(|course| course.progress == Progress::Completed)
+            .collect();
+
+        CourseSet::new(&completed)
+    }
+}
+
+fn completed_course(entry: &TranscriptEntry) -> CompletedCourse {
+    CompletedCourse {
+        id: CourseId::Code {
+            subject_code: entry.subject_code.clone(),
+            number: entry.number.clone(),
+        },
+        credits: entry.credits,
+        grade: match entry.grade {
+            TranscriptGrade::Letter(grade) => Some(CourseGrade::Letter(grade)),
+            TranscriptGrade::Pass => Some(CourseGrade::Pass),
+            _ => None,
+        },
+        progress: if entry.grade.is_pending() {
+            Progress::InProgress
+        } else {
+            Progress::Completed
+        },
+        transfer: entry.grade == TranscriptGrade::Transfer,
+    }
+}
+
+/// Reads terms like [Term] does, or Banner term codes such as "202430"
+fn parse_term(term: &str) -> Option<Term> {
+    if let Ok(term) = term.parse() {
+        return Some(term);
+    }
+
+    if term.len() != 6 || !term.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let season = match &term[4..] {
+        "10" => Season::Spring,
+        "20" => Season::Summer,
+        "30" => Season::Fall,
+        "40" => Season::Winter,
+        _ => return None,
+    };
+
+    Some(Term {
+        season,
+        year: term[..4].parse().ok()?,
+    })
+}
+
+impl fmt::Display for TranscriptGrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptGrade::Letter(grade) => write!(f, "{grade}"),
+            TranscriptGrade::Pass => write!(f, "P"),
+            TranscriptGrade::NoCredit => write!(f, "NC"),
+            TranscriptGrade::Transfer => write!(f, "TR"),
+            TranscriptGrade::Withdrawn => write!(f, "W"),
+            TranscriptGrade::Audit => write!(f, "AU"),
+            TranscriptGrade::Incomplete => write!(f, "I"),
+            TranscriptGrade::InProgress => write!(f, "IP"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn code(subject_code: &str, number: &str) -> CourseId {
+        CourseId::Code {
+            subject_code: subject_code.to_owned(),
+            number: number.to_owned(),
+        }
+    }
+
+    #[test]
+    fn colleague_exports_are_detected() {
+        let csv = "\
+stc.person.id,STC.TERM,STC.SUBJECT,STC.COURSE.NO,STC.CRED,STC.VERIFIED.GRADE
+0012345,2024FA,CSC,115,3.00,a-
+0012345,2024FA,MAT,211,4.00,P
+0012345,2025SP,CSC,125,3.00,IP
+";
+        let transcript = Transcript::from_csv(csv.as_bytes()).unwrap();
+        assert_eq!(transcript.format, TranscriptFormat::Colleague);
+        assert_eq!(
+            transcript.entries[0],
+            TranscriptEntry {
+                term: "Fall 2024".parse().unwrap(),
+                subject_code: "CSC".to_owned(),
+                number: "115".to_owned(),
+                credits: 3,
+                grade: TranscriptGrade::Letter(Grade::AMinus),
+            }
+        );
+
+        let completed = transcript.completed_courses(RepeatPolicy::default());
+        assert_eq!(completed[1].grade, Some(CourseGrade::Pass));
+        assert_eq!(completed[1].credits, 4);
+        assert_eq!(completed[2].progress, Progress::InProgress);
+
+        let courses = transcript.course_set(RepeatPolicy::default());
+        assert_eq!(courses, CourseSet::new(&completed[..2]));
+    }
+
+    #[test]
+    fn repeats_follow_the_policy() {
+        let csv = "\
+term,subject,number,credits,grade
+Spring 2024,CSC,115,3,B
+Fall 2023,CSC,115,3,C
+Fall 2024,CSC,115,3,C+
+Fall 2024,CSC,125,3,W
+Fall 2024,CSC,205,3,F
+Spring 2025,CSC,205,3,
+";
+        let transcript = Transcript::from_csv(csv.as_bytes()).unwrap();
+
+        let latest = transcript.completed_courses(RepeatPolicy::Latest);
+        assert_eq!(
+            latest,
+            [
+                CompletedCourse {
+                    id: code("CSC", "115"),
+                    credits: 3,
+                    grade: Some(Grade::CPlus.into()),
+                    progress: Progress::Completed,
+                    transfer: false,
+                },
+                CompletedCourse {
+                    id: code("CSC", "205"),
+                    credits: 3,
+                    grade: None,
+                    progress: Progress::InProgress,
+                    transfer: false,
+                },
+            ]
+        );
+
+        let highest = transcript.completed_courses(RepeatPolicy::Highest);
+        assert_eq!(highest[0].grade, Some(Grade::B.into()));
+
+        let attempts = transcript.attempts();
+        assert_eq!(attempts.len(), 6);
+        assert_eq!(attempts[0].grade, Some(Grade::C.into()));
+        assert_eq!(attempts[3].grade, Some(CourseGrade::Withdrawn));
+        assert_eq!(attempts[5].progress, Progress::InProgress);
+    }
+
+    #[test]
+    fn invalid_rows_are_reported() {
+        let error = Transcript::from_csv("TERM_CODE,SUBJ_CODE\n202410,CSC\n".as_bytes());
+        assert!(matches!(error, Err(TranscriptError::UnknownFormat(_))));
+
+        let csv = "term,subject,number,credits,grade\n202450,CSC,115,3,A\n";
+        let error = Transcript::from_csv(csv.as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "row 2: \"202450\" is not a valid term");
+
+        let csv = "term,subject,number,credits,grade\nFall 2024,CSC,115,3,Z\n";
+        assert!(matches!(
+            Transcript::from_csv(csv.as_bytes()),
+            Err(TranscriptError::Value { field: "grade", .. })
+        ));
+    }
+}
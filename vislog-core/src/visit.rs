@@ -0,0 +1,230 @@
+//! Traversal of the requirement tree of a [Program].
+//!
+//! Implement [Visit] and override the hooks for the nodes you care about. Every hook defaults to
+//! walking into the children of its node, so an overriding hook should call the matching `walk_*`
+//! function to keep traversing deeper.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{visit::Visit, Course, Program};
+//! #[derive(Default)]
+//! struct CreditCounter {
+//!     min_credits: u32,
+//! }
+//!
+//! impl<'a> Visit<'a> for CreditCounter {
+//!     fn visit_course(&mut self, course: &'a Course) {
+//!         self.min_credits += course.credits.0 as u32;
+//!     }
+//! }
+//!
+//! let program_json = std::fs::read_to_string("../data/cs_major.json").unwrap();
+//! let program: Program = serde_json::from_str(&program_json).unwrap();
+//!
+//! let mut counter = CreditCounter::default();
+//! counter.visit_program(&program);
+//!
+//! assert!(counter.min_credits > 0);
+//! ```
+
+use std::slice;
+
+use crate::{
+    Course, CourseEntries, CourseEntry, Label, Program, Requirement, RequirementModule,
+    Requirements,
+};
+
+pub trait Visit<'a> {
+    fn visit_program(&mut self, program: &'a Program) {
+        walk_program(self, program)
+    }
+
+    fn visit_requirement_module(&mut self, module: &'a RequirementModule) {
+        walk_requirement_module(self, module)
+    }
+
+    fn visit_requirement(&mut self, requirement: &'a Requirement) {
+        walk_requirement(self, requirement)
+    }
+
+    fn visit_course_entry(&mut self, entry: &'a CourseEntry) {
+        walk_course_entry(self, entry)
+    }
+
+    fn visit_course(&mut self, _course: &'a Course) {}
+
+    fn visit_label(&mut self, _label: &'a Label) {}
+}
+
+pub fn walk_program<'a, V>(visitor: &mut V, program: &'a Program)
+where
+    V: Visit<'a> + ?Sized,
+{
+    let modules = program
+        .requirements
+        .as_ref()
+        .map(Requirements::modules)
+        .unwrap_or_default();
+
+    for module in modules {
+        visitor.visit_requirement_module(module);
+    }
+}
+
+pub fn walk_requirement_module<'a, V>(visitor: &mut V, module: &'a RequirementModule)
+where
+    V: Visit<'a> + ?Sized,
+{
+    for requirement in module.requirements() {
+        visitor.visit_requirement(requirement);
+    }
+}
+
+pub fn walk_requirement<'a, V>(visitor: &mut V, requirement: &'a Requirement)
+where
+    V: Visit<'a> + ?Sized,
+{
+    for entry in requirement
+        .course_entries()
+        .into_iter()
+        .flat_map(|e| e.iter())
+    {
+        visitor.visit_course_entry(entry);
+    }
+}
+
+pub fn walk_course_entry<'a, V>(visitor: &mut V, entry: &'a CourseEntry)
+where
+    V: Visit<'a> + ?Sized,
+{
+    match entry {
+        CourseEntry::And(entries) | CourseEntry::Or(entries) => {
+            for entry in entries.iter() {
+                visitor.visit_course_entry(entry);
+            }
+        }
+        CourseEntry::Label(label) => visitor.visit_label(label),
+        CourseEntry::Course(course) => visitor.visit_course(course),
+    }
+}
+
+/// Depth first iterator over every [Course] in [CourseEntries], including the ones nested inside
+/// of operator groups
+#[derive(Debug, Clone)]
+pub struct Courses<'a> {
+    stack: Vec<slice::Iter<'a, CourseEntry>>,
+}
+
+impl<'a> Courses<'a> {
+    pub(crate) fn new(entries: &'a CourseEntries) -> Self {
+        Self {
+            stack: vec![entries.iter()],
+        }
+    }
+}
+
+impl<'a> Iterator for Courses<'a> {
+    type Item = &'a Course;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(entries) = self.stack.last_mut() {
+            match entries.next() {
+                Some(CourseEntry::Course(course)) => return Some(course),
+                Some(CourseEntry::And(nested) | CourseEntry::Or(nested)) => {
+                    self.stack.push(nested.iter())
+                }
+                Some(CourseEntry::Label(_)) => {}
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::read_program;
+
+    #[derive(Default)]
+    struct Counter {
+        modules: usize,
+        requirements: usize,
+        courses: usize,
+        labels: usize,
+        groups: usize,
+    }
+
+    impl<'a> Visit<'a> for Counter {
+        fn visit_requirement_module(&mut self, module: &'a RequirementModule) {
+            self.modules += 1;
+            walk_requirement_module(self, module);
+        }
+
+        fn visit_requirement(&mut self, requirement: &'a Requirement) {
+            self.requirements += 1;
+            walk_requirement(self, requirement);
+        }
+
+        fn visit_course_entry(&mut self, entry: &'a CourseEntry) {
+            if matches!(entry, CourseEntry::And(_) | CourseEntry::Or(_)) {
+                self.groups += 1;
+            }
+            walk_course_entry(self, entry);
+        }
+
+        fn visit_course(&mut self, _course: &'a Course) {
+            self.courses += 1;
+        }
+
+        fn visit_label(&mut self, _label: &'a Label) {
+            self.labels += 1;
+        }
+    }
+
+    #[test]
+    fn visitor_reaches_every_node() {
+        let program = read_program("cs_minor.json");
+
+        let mut counter = Counter::default();
+        counter.visit_program(&program);
+
+        assert_eq!(counter.modules, 1);
+        assert_eq!(counter.requirements, 3);
+        assert!(counter.groups > 0);
+        assert_eq!(counter.courses, program.iter_courses().count());
+    }
+
+    #[test]
+    fn iter_courses_includes_nested_courses() {
+        let program = read_program("cs_minor.json");
+
+        let numbers: Vec<_> = program
+            .iter_courses()
+            .map(|course| course.number.as_str())
+            .collect();
+
+        // CSC 205 and CSC 347 are nested inside of the "Select one track" `Or` group
+        assert!(numbers.contains(&"205"));
+        assert!(numbers.contains(&"347"));
+    }
+
+    #[test]
+    fn iter_requirements_of_cs_major() {
+        let program = read_program("cs_major.json");
+
+        let titles: Vec<_> = program
+            .iter_requirements()
+            .filter_map(|requirement| match requirement {
+                Requirement::Courses { title, .. } => title.as_deref(),
+                _ => None,
+            })
+            .collect();
+
+        assert!(!titles.is_empty());
+        assert_eq!(program.iter_courses().count(), 16);
+    }
+}
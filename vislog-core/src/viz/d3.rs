@@ -0,0 +1,297 @@
+//! Requirement tree of a [Program] shaped for [d3-hierarchy](https://d3js.org/d3-hierarchy).
+//!
+//! [Program::to_d3_hierarchy] gives a tree of `{name, children, value}` nodes that
+//! `d3.hierarchy(root).sum(d => d.value)` reads as is, so sunbursts, treemaps and tree layouts
+//! don't need a transform of their own. Only leaves have a value, as `sum` adds up the values of
+//! the descendants of every node. The [HierarchyValue] decides what a leaf is worth.
+//!
+//! Every option of an `Or` group is part of the tree and counts towards the value of its
+//! ancestors, so the value of a requirement is an upper bound of what it takes to complete it.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, viz::d3::HierarchyValue};
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//!
+//! let root = catalog.programs[0].to_d3_hierarchy(HierarchyValue::Credits);
+//! let json = serde_json::to_value(&root).unwrap();
+//!
+//! assert_eq!(json["kind"], "program");
+//! assert!(json["children"][0]["children"].is_array());
+//! ```
+
+use serde::Serialize;
+
+use crate::{
+    electives::ElectiveHours, parsing::guid::Guid, tags::TagMapping, text::clean_narrative,
+    CourseEntries, CourseEntry, Program, Requirement, RequirementModule,
+};
+
+/// What the leaves of a hierarchy are worth
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HierarchyValue {
+    /// Minimum credits of courses and labels, and hours of elective pools
+    #[default]
+    Credits,
+    /// One for every course and label
+    CourseCount,
+}
+
+/// Node of the hierarchy. Fields that don't apply to the kind of the node are left out of the
+/// JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct D3Node {
+    pub name: String,
+    pub kind: D3NodeKind,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<D3Node>,
+    /// Value of leaves, see [HierarchyValue]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guid: Option<Guid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credits: Option<(u8, Option<u8>)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Tags of courses, labels and requirements, see [D3Node::with_tags]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum D3NodeKind {
+    Program,
+    Module,
+    Requirement,
+    AllOf,
+    OneOf,
+    Course,
+    Label,
+}
+
+impl D3Node {
+    fn new(name: impl Into<String>, kind: D3NodeKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            children: vec![],
+            value: None,
+            guid: None,
+            subject_code: None,
+            number: None,
+            credits: None,
+            url: None,
+            tags: vec![],
+        }
+    }
+
+    /// Tags the courses, labels and requirements under the node, the root of the hierarchy of the
+    /// `program`, with their `tags`
+    pub fn with_tags(mut self, program: &Program, tags: &TagMapping) -> Self {
+        self.tag(program, tags);
+        self
+    }
+
+    fn tag(&mut self, program: &Program, tags: &TagMapping) {
+        let found = match (self.kind, self.guid) {
+            (D3NodeKind::Course | D3NodeKind::Label, Some(guid)) => tags.course_tags(&guid),
+            (D3NodeKind::Requirement, _) => tags.requirement_tags(&program.guid, &self.name),
+            _ => vec![],
+        };
+        self.tags = found.into_iter().map(str::to_owned).collect();
+
+        for child in &mut self.children {
+            child.tag(program, tags);
+        }
+    }
+
+    /// Sum of the values of the leaves under the node, like `d3.hierarchy(...).sum(...)` computes
+    pub fn total(&self) -> u32 {
+        self.value.unwrap_or(0) + self.children.iter().map(D3Node::total).sum::<u32>()
+    }
+}
+
+impl Program {
+    /// The requirement tree of the program as nested `{name, children, value}` nodes for
+    /// `d3.hierarchy`, with leaves worth their `value`
+    pub fn to_d3_hierarchy(&self, value: HierarchyValue) -> D3Node {
+        let mut root = D3Node::new(clean_narrative(&self.title), D3NodeKind::Program);
+        root.guid = Some(self.guid);
+        root.url = Some(self.url.clone());
+        root.children = self
+            .requirements
+            .iter()
+            .flat_map(|requirements| requirements.modules())
+            .filter_map(|module| module_node(module, value))
+            .collect();
+
+        root
+    }
+}
+
+fn module_node(module: &RequirementModule, value: HierarchyValue) -> Option<D3Node> {
+    let title = match module {
+        RequirementModule::Unimplemented(_) => return None,
+        RequirementModule::SelectOneEmphasis { .. } => {
+            module.title().unwrap_or("Select one emphasis")
+        }
+        _ => module.title().unwrap_or("Requirements"),
+    };
+
+    let mut node = D3Node::new(clean_narrative(title), D3NodeKind::Module);
+    node.children = module
+        .requirements()
+        .iter()
+        .map(|requirement| requirement_node(requirement, value))
+        .collect();
+
+    Some(node)
+}
+
+fn requirement_node(requirement: &Requirement, value: HierarchyValue) -> D3Node {
+    let title = requirement
+        .title()
+        .or(requirement.req_narrative())
+        .map(clean_narrative)
+        .unwrap_or_else(|| "Requirement".to_owned());
+
+    let mut node = D3Node::new(title, D3NodeKind::Requirement);
+    match requirement {
+        Requirement::ElectivePool { hours, .. } => {
+            node.value = Some(match (value, hours) {
+                (HierarchyValue::Credits, ElectiveHours::Hours(hours)) => *hours,
+                _ => 0,
+            });
+        }
+        _ => {
+            if let Some(entries) = requirement.course_entries() {
+                node.children = entry_nodes(entries, value);
+            }
+        }
+    }
+
+    node
+}
+
+fn entry_nodes(entries: &CourseEntries, value: HierarchyValue) -> Vec<D3Node> {
+    let leaf_value = |credits: (u8, Option<u8>)| match value {
+        HierarchyValue::Credits => u32::from(credits.0),
+        HierarchyValue::CourseCount => 1,
+    };
+
+    entries
+        .iter()
+        .map(|entry| match entry {
+            CourseEntry::Course(course) => {
+                let name = match &course.name {
+                    Some(name) => format!(
+                        "{} {} {}",
+                        course.subject_code,
+                        course.number,
+                        clean_narrative(name)
+                    ),
+                    None => format!("{} {}", course.subject_code, course.number),
+                };
+                let mut node = D3Node::new(name, D3NodeKind::Course);
+                node.value = Some(leaf_value(course.credits));
+                node.guid = Some(course.guid);
+                node.subject_code = Some(course.subject_code.to_string());
+                node.number = Some(course.number.clone());
+                node.credits = Some(course.credits);
+                node.url = Some(course.url.to_string());
+                node
+            }
+            CourseEntry::Label(label) => {
+                let mut node = D3Node::new(clean_narrative(&label.name), D3NodeKind::Label);
+                node.value = Some(leaf_value(label.credits));
+                node.guid = Some(label.guid);
+                node.subject_code = label.subject_code.as_ref().map(ToString::to_string);
+                node.number = label.number.clone();
+                node.credits = Some(label.credits);
+                node
+            }
+            CourseEntry::And(group) | CourseEntry::Or(group) => {
+                let (name, kind) = match entry {
+                    CourseEntry::And(_) => ("All of", D3NodeKind::AllOf),
+                    _ => ("One of", D3NodeKind::OneOf),
+                };
+                let mut node = D3Node::new(name, kind);
+                node.children = entry_nodes(group, value);
+                node
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::cs_minor;
+
+    #[test]
+    fn leaves_are_worth_their_credits_or_one() {
+        let program = cs_minor();
+        let credits = program.to_d3_hierarchy(HierarchyValue::Credits);
+        let count = program.to_d3_hierarchy(HierarchyValue::CourseCount);
+
+        let min_credits: u32 = program
+            .iter_courses()
+            .map(|course| u32::from(course.credits.0))
+            .sum();
+        assert!(credits.total() >= min_credits);
+        assert!(count.total() >= program.iter_courses().count() as u32);
+        assert!(credits.value.is_none() && credits.children.iter().all(|c| c.value.is_none()));
+    }
+
+    #[test]
+    fn nodes_are_serialized_for_d3() {
+        let root = cs_minor().to_d3_hierarchy(HierarchyValue::Credits);
+        let json = serde_json::to_value(&root).unwrap();
+
+        let requirement = &json["children"][0]["children"][0];
+        assert_eq!(requirement["kind"], "requirement");
+        assert!(requirement.get("value").is_none());
+        let course = &requirement["children"][0];
+        assert_eq!(course["kind"], "course");
+        assert_eq!(course["subject_code"], "CSC");
+        assert!(course["value"].is_u64());
+        assert!(course.get("children").is_none());
+    }
+
+    #[test]
+    fn tags_are_carried_to_the_nodes() {
+        let program = cs_minor();
+        let csc_115 = Guid::try_from("860AF9C9-EAD9-45AC-AA92-BAF352C5288C").unwrap();
+        let mut tags = TagMapping::new();
+        tags.tag_course(csc_115, "intro");
+        tags.tag_requirement(program.guid, "Minor Requirements:", "core");
+
+        let root = program
+            .to_d3_hierarchy(HierarchyValue::Credits)
+            .with_tags(&program, &tags);
+        let json = serde_json::to_value(&root).unwrap();
+
+        let requirement = &json["children"][0]["children"][0];
+        assert_eq!(requirement["name"], "Minor Requirements:");
+        assert_eq!(requirement["tags"], serde_json::json!(["core"]));
+        assert!(json.get("tags").is_none());
+
+        let mut stack = vec![&root];
+        let mut tagged = vec![];
+        while let Some(node) = stack.pop() {
+            if node.guid == Some(csc_115) {
+                tagged.push(node.tags.clone());
+            }
+            stack.extend(&node.children);
+        }
+        assert_eq!(tagged, [vec!["intro".to_owned()]]);
+    }
+}
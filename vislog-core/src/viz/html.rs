@@ -0,0 +1,230 @@
+//! Self-contained HTML page of the requirement tree of a [Program], for sharing programs with
+//! people who don't have any of the vislog tools.
+//!
+//! Modules, requirements and `And`/`Or` groups are `<details>` elements that can be collapsed and
+//! expanded, and courses link to their page in the catalog. The styles and the script behind the
+//! "Expand all" and "Collapse all" buttons are embedded in the page, so the single file works
+//! offline and as an email attachment.
+//!
+//! The descriptions of the program and of label requirements are HTML from the catalog and are
+//! included as is. Every other piece of text is escaped.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::catalog::Catalog;
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//!
+//! let html = catalog.programs[0].to_html();
+//!
+//! assert!(html.starts_with("<!DOCTYPE html>"));
+//! assert!(html.contains("<title>Minor in Computer Science—21 or 22 hours</title>"));
+//! ```
+
+use std::fmt::Write;
+
+use crate::{CourseEntries, CourseEntry, Program, Requirement, RequirementModule};
+
+const STYLE: &str = r#"
+body { font-family: Helvetica, Arial, sans-serif; color: #1f2328; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; }
+details { margin: 0.25rem 0 0.25rem 1.25rem; }
+summary { cursor: pointer; padding: 0.2rem 0; }
+details.module > summary { font-weight: bold; font-size: 1.1rem; }
+details.requirement > summary { font-weight: bold; }
+details.all-of > summary, details.any-of > summary { font-style: italic; color: #57606a; }
+ul { list-style: none; margin: 0.25rem 0 0.25rem 1.25rem; padding: 0; }
+li { padding: 0.15rem 0; }
+a { color: #0969da; text-decoration: none; }
+a:hover { text-decoration: underline; }
+.code { font-weight: bold; }
+.credits { color: #57606a; font-size: 0.9rem; }
+.narrative { margin-left: 1.25rem; }
+.controls { margin: 1rem 0; }
+"#;
+
+const SCRIPT: &str = r#"
+function setAll(open) {
+  document.querySelectorAll("details").forEach(function (details) { details.open = open; });
+}
+"#;
+
+impl Program {
+    /// Renders the requirement tree of the program as a self-contained HTML page, with every part
+    /// of the tree expanded
+    pub fn to_html(&self) -> String {
+        let title = escape(&self.title);
+
+        let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+        html.push_str("<meta charset=\"utf-8\">\n");
+        html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+        let _ = writeln!(html, "<title>{title}</title>");
+        let _ = writeln!(html, "<style>{STYLE}</style>");
+        let _ = writeln!(html, "<script>{SCRIPT}</script>");
+        html.push_str("</head>\n<body>\n");
+
+        let _ = writeln!(
+            html,
+            "<h1><a href=\"{}\">{title}</a></h1>",
+            escape(&self.url)
+        );
+        if let Some(content) = &self.content {
+            let _ = writeln!(html, "<div class=\"content\">{content}</div>");
+        }
+        html.push_str(
+            "<div class=\"controls\">\
+             <button type=\"button\" onclick=\"setAll(true)\">Expand all</button> \
+             <button type=\"button\" onclick=\"setAll(false)\">Collapse all</button>\
+             </div>\n",
+        );
+
+        let modules = self
+            .requirements
+            .iter()
+            .flat_map(|requirements| requirements.modules());
+        for module in modules {
+            write_module(&mut html, module);
+        }
+
+        if let Some(bottom_content) = &self.bottom_content {
+            let _ = writeln!(html, "<div class=\"content\">{bottom_content}</div>");
+        }
+        html.push_str("</body>\n</html>\n");
+
+        html
+    }
+}
+
+fn write_module(html: &mut String, module: &RequirementModule) {
+    let title = match module {
+        RequirementModule::Unimplemented(_) => return,
+        RequirementModule::SelectOneEmphasis { .. } => {
+            module.title().unwrap_or("Select one emphasis")
+        }
+        _ => module.title().unwrap_or("Requirements"),
+    };
+
+    let _ = writeln!(
+        html,
+        "<details class=\"module\" open>\n<summary>{}</summary>",
+        escape(title)
+    );
+    for requirement in module.requirements() {
+        write_requirement(html, requirement);
+    }
+    html.push_str("</details>\n");
+}
+
+fn write_requirement(html: &mut String, requirement: &Requirement) {
+    let _ = writeln!(
+        html,
+        "<details class=\"requirement\" open>\n<summary>{}</summary>",
+        escape(requirement.title().unwrap_or("Requirement"))
+    );
+
+    if let Some(narrative) = requirement.req_narrative() {
+        let _ = writeln!(html, "<div class=\"narrative\">{narrative}</div>");
+    }
+    if let Some(entries) = requirement.course_entries() {
+        write_entries(html, entries);
+    }
+
+    html.push_str("</details>\n");
+}
+
+fn write_entries(html: &mut String, entries: &CourseEntries) {
+    html.push_str("<ul>\n");
+
+    for entry in entries.iter() {
+        html.push_str("<li>");
+
+        match entry {
+            CourseEntry::Course(course) => {
+                let _ = write!(
+                    html,
+                    "<a href=\"{}\"><span class=\"code\">{} {}</span>",
+                    escape(&course.url),
+                    escape(&course.subject_code),
+                    escape(&course.number)
+                );
+                if let Some(name) = &course.name {
+                    let _ = write!(html, " {}", escape(name));
+                }
+                let _ = write!(
+                    html,
+                    "</a> <span class=\"credits\">{}</span>",
+                    credits(course.credits)
+                );
+            }
+            CourseEntry::Label(label) => {
+                html.push_str(&escape(&label.name));
+            }
+            CourseEntry::And(group) | CourseEntry::Or(group) => {
+                let (class, summary) = match entry {
+                    CourseEntry::And(_) => ("all-of", "All of"),
+                    _ => ("any-of", "One of"),
+                };
+
+                let _ = writeln!(
+                    html,
+                    "\n<details class=\"{class}\" open>\n<summary>{summary}</summary>"
+                );
+                write_entries(html, group);
+                html.push_str("</details>\n");
+            }
+        }
+
+        html.push_str("</li>\n");
+    }
+
+    html.push_str("</ul>\n");
+}
+
+pub(crate) fn credits(credits: (u8, Option<u8>)) -> String {
+    match credits {
+        (1, None) => "1 credit".to_owned(),
+        (credits, None) => format!("{credits} credits"),
+        (min, Some(max)) => format!("{min}-{max} credits"),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::catalog::Catalog;
+
+    #[test]
+    fn courses_link_to_the_catalog() {
+        let (catalog, errors) = Catalog::parse_file("../data/cs_minor.json");
+        assert!(errors.is_empty());
+
+        let program = &catalog.programs[0];
+        let html = program.to_html();
+
+        for course in program.iter_courses() {
+            assert!(html.contains(&format!("<a href=\"{}\">", escape(&course.url))));
+        }
+        assert!(html.contains("<details class=\"any-of\" open>\n<summary>One of</summary>"));
+        assert_eq!(
+            html.matches("<details").count(),
+            html.matches("</details>").count()
+        );
+        assert!(html.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn text_is_escaped_and_credits_are_spelled_out() {
+        assert_eq!(
+            escape("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+        assert_eq!(credits((1, None)), "1 credit");
+        assert_eq!(credits((3, None)), "3 credits");
+        assert_eq!(credits((1, Some(4))), "1-4 credits");
+    }
+}
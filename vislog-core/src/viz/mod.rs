@@ -0,0 +1,6 @@
+//! Drawings of programs that don't depend on tools outside of the crate
+
+pub mod d3;
+pub mod html;
+pub mod render;
+pub mod svg;
@@ -0,0 +1,266 @@
+//! Options to restyle drawings and exports without changing the code that lays them out.
+//!
+//! [RenderOptions] decides the fill of courses, the text of their boxes or nodes, and how `And`
+//! and `Or` groups are told apart. It is taken by [svg::to_svg_with](super::svg::to_svg_with)
+//! and [ProgramGraph::to_dot_with](crate::graph::ProgramGraph::to_dot_with), while the
+//! [Theme](super::svg::Theme) of a drawing still sets its fonts, spacing and remaining colors.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, viz::{render::*, svg::{self, Theme}}};
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//!
+//! let options = RenderOptions {
+//!     colors: ColorScheme::RequirementType,
+//!     course_label: LabelTemplate::new("{number}: {name}"),
+//!     ..Default::default()
+//! };
+//! let svg = svg::to_svg_with(&catalog.programs[0], &Theme::default(), &options);
+//!
+//! assert!(svg.contains(">115: Computer Science: Introduction and Overview</text>"));
+//! ```
+
+use crate::{
+    subjects::{Subjects, PALETTE},
+    text::clean_narrative,
+    viz::html::credits,
+    Course, Requirement,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    pub colors: ColorScheme,
+    /// Text of courses, which may span several lines. The SVG drawing writes the first line in
+    /// bold and adds the name of the course below when the template doesn't have a `{name}`.
+    pub course_label: LabelTemplate,
+    /// Border of `And` groups
+    pub and_style: EdgeStyle,
+    /// Border of `Or` groups
+    pub or_style: EdgeStyle,
+}
+
+impl Default for RenderOptions {
+    /// Fills of the theme, courses labeled with their subject code and number, and `Or` groups
+    /// drawn dashed
+    fn default() -> Self {
+        Self {
+            colors: ColorScheme::default(),
+            course_label: LabelTemplate::default(),
+            and_style: EdgeStyle::default(),
+            or_style: EdgeStyle {
+                line: LineStyle::Dashed,
+                color: None,
+            },
+        }
+    }
+}
+
+/// How courses are filled
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ColorScheme {
+    /// With the fill of courses of the theme
+    #[default]
+    Theme,
+    /// With the [color](Subjects::color) of their subject, so that the courses of a department
+    /// look the same in every program
+    Subject(Subjects),
+    /// With a color for every kind of [Requirement] listing them, so that required courses stand
+    /// out from the ones to select from
+    RequirementType,
+}
+
+impl ColorScheme {
+    /// Fill of a course of the subject with the `subject_code`, listed by the `requirement` when
+    /// it is known. `None` when the fill of the theme should be kept.
+    pub fn fill(
+        &self,
+        subject_code: &str,
+        requirement: Option<&Requirement>,
+    ) -> Option<&'static str> {
+        match self {
+            ColorScheme::Theme => None,
+            ColorScheme::Subject(subjects) => Some(subjects.color(subject_code)),
+            ColorScheme::RequirementType => requirement.map(requirement_color),
+        }
+    }
+}
+
+/// Fill of the courses of a kind of requirement
+pub fn requirement_color(requirement: &Requirement) -> &'static str {
+    match requirement {
+        Requirement::Courses { .. } => PALETTE[7],
+        Requirement::SelectFromCourses { .. } => PALETTE[8],
+        Requirement::Label { .. } => PALETTE[9],
+        Requirement::ElectivePool { .. } => PALETTE[6],
+    }
+}
+
+/// Text with `{placeholder}`s filled in for every course. Known placeholders are:
+/// - `{subject_code}`: Ex: "CSC"
+/// - `{number}`: Ex: "115"
+/// - `{name}`: Ex: "Introduction to Computer Science", or nothing for courses without a name
+/// - `{credits}`: Ex: "3 credits", or "1-3 credits"
+/// - `{guid}`
+///
+/// Nodes of [ProgramGraph](crate::graph::ProgramGraph) also know `{depth}`, but not `{credits}`.
+///
+/// Other text, including unknown placeholders, is kept as is. Whitespace left at the ends by
+/// empty placeholders is trimmed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelTemplate(String);
+
+impl Default for LabelTemplate {
+    fn default() -> Self {
+        Self::new("{subject_code} {number}")
+    }
+}
+
+impl LabelTemplate {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self(template.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the `placeholder`, given without its braces, is part of the template
+    pub fn uses(&self, placeholder: &str) -> bool {
+        self.0.contains(&format!("{{{placeholder}}}"))
+    }
+
+    /// The template filled in with the values of the `course`
+    pub fn course(&self, course: &Course) -> String {
+        self.render(|placeholder| match placeholder {
+            "subject_code" => Some(course.subject_code.to_string()),
+            "number" => Some(course.number.clone()),
+            "name" => Some(
+                course
+                    .name
+                    .as_deref()
+                    .map(clean_narrative)
+                    .unwrap_or_default(),
+            ),
+            "credits" => Some(credits(course.credits)),
+            "guid" => Some(course.guid.to_string()),
+            _ => None,
+        })
+    }
+
+    /// The template with every placeholder that `value` knows replaced by its value
+    pub fn render(&self, value: impl Fn(&str) -> Option<String>) -> String {
+        let mut output = String::with_capacity(self.0.len());
+        let mut rest = self.0.as_str();
+
+        while let Some(start) = rest.find('{') {
+            output.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let replaced = rest
+                .find('}')
+                .and_then(|end| Some((value(&rest[1..end])?, end)));
+            match replaced {
+                Some((value, end)) => {
+                    output.push_str(&value);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    output.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        output.push_str(rest);
+
+        output.trim().to_owned()
+    }
+}
+
+/// Border of groups, or line of edges
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EdgeStyle {
+    pub line: LineStyle,
+    /// Any CSS color, or the stroke of the theme when `None`
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+    /// Thicker than solid
+    Bold,
+}
+
+impl LineStyle {
+    /// Value of the `style` attribute of Graphviz nodes and edges
+    pub fn dot_style(self) -> &'static str {
+        match self {
+            LineStyle::Solid => "solid",
+            LineStyle::Dashed => "dashed",
+            LineStyle::Dotted => "dotted",
+            LineStyle::Bold => "bold",
+        }
+    }
+
+    /// SVG attributes drawing a stroke in the style
+    pub fn svg_attributes(self) -> &'static str {
+        match self {
+            LineStyle::Solid => "",
+            LineStyle::Dashed => " stroke-dasharray=\"4 3\"",
+            LineStyle::Dotted => " stroke-dasharray=\"1 2\"",
+            LineStyle::Bold => " stroke-width=\"2\"",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::catalog::Catalog;
+
+    #[test]
+    fn placeholders_are_filled_in() {
+        let (catalog, _) = Catalog::parse_file("../data/cs_minor.json");
+        let course = catalog.programs[0].iter_courses().next().unwrap();
+
+        assert_eq!(LabelTemplate::default().course(course), "CSC 115");
+        assert_eq!(
+            LabelTemplate::new("{number} ({credits}) {unknown} {").course(course),
+            format!("115 ({}) {{unknown}} {{", credits(course.credits))
+        );
+
+        let template = LabelTemplate::new("{subject_code}{number} {name}");
+        assert_eq!(
+            template.render(|placeholder| (placeholder != "name").then(|| "X".to_owned())),
+            "XX {name}"
+        );
+        assert_eq!(template.render(|_| Some(String::new())), "");
+    }
+
+    #[test]
+    fn courses_are_colored_by_the_scheme() {
+        let requirement = Requirement::ElectivePool {
+            title: None,
+            req_narrative: None,
+            hours: crate::electives::ElectiveHours::Hours(3),
+            constraints: Default::default(),
+            extra: Default::default(),
+        };
+
+        assert_eq!(ColorScheme::Theme.fill("CSC", Some(&requirement)), None);
+        assert_eq!(
+            ColorScheme::RequirementType.fill("CSC", Some(&requirement)),
+            Some(PALETTE[6])
+        );
+        assert_eq!(ColorScheme::RequirementType.fill("CSC", None), None);
+
+        let subjects = Subjects::default();
+        assert_eq!(
+            ColorScheme::Subject(subjects.clone()).fill("csc", None),
+            Some(subjects.color("CSC"))
+        );
+    }
+}
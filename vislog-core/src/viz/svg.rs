@@ -0,0 +1,498 @@
+//! Standalone SVG drawing of the requirement tree of a [Program].
+//!
+//! Every part of the tree is drawn as a box nested inside of the box of its parent: the program
+//! contains its requirement modules, which contain their requirements, which contain their
+//! courses. Modules and requirements stack their children vertically, while `And` and `Or` groups
+//! line theirs up side by side under an "All of" or "One of" heading.
+//!
+//! The layout is computed in the crate, so rendering doesn't need Graphviz or any other tool to be
+//! installed. Text isn't measured with the real font, instead every character is assumed to be
+//! [CHAR_WIDTH] times the font size, which is slightly wider than most sans-serif fonts.
+//!
+//! [to_svg_with] also takes [RenderOptions] to color courses by subject or by requirement, change
+//! the text of their boxes, and restyle the borders of `And` and `Or` groups.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, viz::svg::{self, Theme}};
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//!
+//! let svg = svg::to_svg(&catalog.programs[0], &Theme::default());
+//!
+//! assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+//! assert!(svg.contains(">CSC 115</text>"));
+//! ```
+
+use std::fmt::Write;
+
+use super::render::{EdgeStyle, RenderOptions};
+use crate::{CourseEntries, CourseEntry, Program, Requirement, RequirementModule};
+
+/// Width of a character relative to the font size, used to size the boxes around text
+pub const CHAR_WIDTH: f64 = 0.6;
+
+/// Colors, font and spacing of the drawing. Colors are any CSS color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub font_family: String,
+    /// Font size in pixels. Lines of text are a third taller than the font size.
+    pub font_size: u32,
+    /// Space between the border of a box and its content
+    pub padding: u32,
+    /// Space between boxes next to each other, and around the whole drawing
+    pub spacing: u32,
+    pub corner_radius: u32,
+    pub background: String,
+    pub text: String,
+    pub stroke: String,
+    pub program_fill: String,
+    pub module_fill: String,
+    pub requirement_fill: String,
+    /// Fill of `And` and `Or` groups. `Or` groups are told apart by a dashed border, unless
+    /// [RenderOptions] say otherwise.
+    pub group_fill: String,
+    pub course_fill: String,
+    /// Fill of label entries, which stand in for courses that aren't in the catalog
+    pub label_fill: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            font_family: "Helvetica, Arial, sans-serif".to_owned(),
+            font_size: 14,
+            padding: 8,
+            spacing: 8,
+            corner_radius: 4,
+            background: "#ffffff".to_owned(),
+            text: "#1f2328".to_owned(),
+            stroke: "#8c959f".to_owned(),
+            program_fill: "#f6f8fa".to_owned(),
+            module_fill: "#ddf4ff".to_owned(),
+            requirement_fill: "#ffffff".to_owned(),
+            group_fill: "#fff8c5".to_owned(),
+            course_fill: "#dafbe1".to_owned(),
+            label_fill: "#eaeef2".to_owned(),
+        }
+    }
+}
+
+impl Theme {
+    /// Light text on dark boxes, with the same font and spacing as the default theme
+    pub fn dark() -> Self {
+        Self {
+            background: "#0d1117".to_owned(),
+            text: "#e6edf3".to_owned(),
+            stroke: "#6e7681".to_owned(),
+            program_fill: "#161b22".to_owned(),
+            module_fill: "#0c2d6b".to_owned(),
+            requirement_fill: "#21262d".to_owned(),
+            group_fill: "#3b2300".to_owned(),
+            course_fill: "#033a16".to_owned(),
+            label_fill: "#30363d".to_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        self.font_size * 4 / 3
+    }
+
+    fn text_width(&self, text: &str) -> u32 {
+        (text.chars().count() as f64 * self.font_size as f64 * CHAR_WIDTH).ceil() as u32
+    }
+}
+
+/// Renders the requirement tree of the `program` as a standalone SVG document
+pub fn to_svg(program: &Program, theme: &Theme) -> String {
+    to_svg_with(program, theme, &RenderOptions::default())
+}
+
+/// Renders the requirement tree of the `program` as a standalone SVG document, with courses and
+/// groups styled by the `options`
+pub fn to_svg_with(program: &Program, theme: &Theme, options: &RenderOptions) -> String {
+    let root = Block::program(program, theme, options);
+    let width = root.width + 2 * theme.spacing;
+    let height = root.height + 2 * theme.spacing;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family={} font-size=\"{}\">\n",
+        attribute(&theme.font_family),
+        theme.font_size,
+    );
+    let _ = writeln!(svg, "<title>{}</title>", escape(&program.title));
+    let _ = writeln!(
+        svg,
+        "<rect width=\"100%\" height=\"100%\" fill={}/>",
+        attribute(&theme.background)
+    );
+
+    root.draw(&mut svg, theme.spacing, theme.spacing, theme, options);
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Program,
+    Module,
+    Requirement,
+    AllOf,
+    AnyOf,
+    Course,
+    Label,
+}
+
+impl Kind {
+    fn class(self) -> &'static str {
+        match self {
+            Kind::Program => "program",
+            Kind::Module => "module",
+            Kind::Requirement => "requirement",
+            Kind::AllOf => "all-of",
+            Kind::AnyOf => "any-of",
+            Kind::Course => "course",
+            Kind::Label => "label",
+        }
+    }
+
+    fn fill(self, theme: &Theme) -> &str {
+        match self {
+            Kind::Program => &theme.program_fill,
+            Kind::Module => &theme.module_fill,
+            Kind::Requirement => &theme.requirement_fill,
+            Kind::AllOf | Kind::AnyOf => &theme.group_fill,
+            Kind::Course => &theme.course_fill,
+            Kind::Label => &theme.label_fill,
+        }
+    }
+
+    /// Whether the children are lined up side by side rather than stacked
+    fn is_horizontal(self) -> bool {
+        matches!(self, Kind::AllOf | Kind::AnyOf)
+    }
+}
+
+/// A box of the drawing, sized to fit its lines of text above its children
+#[derive(Debug, Clone, PartialEq)]
+struct Block {
+    kind: Kind,
+    /// The first line is drawn in bold
+    lines: Vec<String>,
+    children: Vec<Block>,
+    /// Fill replacing the one of the theme for the kind of block
+    fill: Option<&'static str>,
+    width: u32,
+    height: u32,
+}
+
+impl Block {
+    fn new(kind: Kind, lines: Vec<String>, children: Vec<Block>, theme: &Theme) -> Self {
+        let text_width = lines
+            .iter()
+            .map(|line| theme.text_width(line))
+            .max()
+            .unwrap_or(0);
+        let text_height = lines.len() as u32 * theme.line_height();
+
+        let gaps = children.len().saturating_sub(1) as u32 * theme.spacing;
+        let (children_width, children_height) = if kind.is_horizontal() {
+            (
+                children.iter().map(|child| child.width).sum::<u32>() + gaps,
+                children.iter().map(|child| child.height).max().unwrap_or(0),
+            )
+        } else {
+            (
+                children.iter().map(|child| child.width).max().unwrap_or(0),
+                children.iter().map(|child| child.height).sum::<u32>() + gaps,
+            )
+        };
+
+        let separator = if lines.is_empty() || children.is_empty() {
+            0
+        } else {
+            theme.spacing
+        };
+
+        Self {
+            kind,
+            width: text_width.max(children_width) + 2 * theme.padding,
+            height: text_height + separator + children_height + 2 * theme.padding,
+            lines,
+            children,
+            fill: None,
+        }
+    }
+
+    fn program(program: &Program, theme: &Theme, options: &RenderOptions) -> Self {
+        let modules = program
+            .requirements
+            .iter()
+            .flat_map(|requirements| requirements.modules())
+            .filter_map(|module| Block::module(module, theme, options))
+            .collect();
+
+        Block::new(Kind::Program, vec![program.title.clone()], modules, theme)
+    }
+
+    /// `None` for modules the crate doesn't know how to parse
+    fn module(module: &RequirementModule, theme: &Theme, options: &RenderOptions) -> Option<Self> {
+        let title = match module {
+            RequirementModule::Unimplemented(_) => return None,
+            RequirementModule::SelectOneEmphasis { .. } => {
+                module.title().unwrap_or("Select one emphasis")
+            }
+            _ => module.title().unwrap_or_default(),
+        };
+
+        let requirements = module
+            .requirements()
+            .iter()
+            .map(|requirement| Block::requirement(requirement, theme, options))
+            .collect();
+
+        Some(Block::new(Kind::Module, lines(title), requirements, theme))
+    }
+
+    fn requirement(requirement: &Requirement, theme: &Theme, options: &RenderOptions) -> Self {
+        let entries = requirement
+            .course_entries()
+            .map(|entries| Block::entries(entries, requirement, theme, options))
+            .unwrap_or_default();
+
+        Block::new(
+            Kind::Requirement,
+            lines(requirement.title().unwrap_or_default()),
+            entries,
+            theme,
+        )
+    }
+
+    /// Blocks of the `entries` listed by the `requirement`
+    fn entries(
+        entries: &CourseEntries,
+        requirement: &Requirement,
+        theme: &Theme,
+        options: &RenderOptions,
+    ) -> Vec<Self> {
+        entries
+            .iter()
+            .map(|entry| match entry {
+                CourseEntry::And(group) => Block::new(
+                    Kind::AllOf,
+                    lines("All of"),
+                    Block::entries(group, requirement, theme, options),
+                    theme,
+                ),
+                CourseEntry::Or(group) => Block::new(
+                    Kind::AnyOf,
+                    lines("One of"),
+                    Block::entries(group, requirement, theme, options),
+                    theme,
+                ),
+                CourseEntry::Course(course) => {
+                    let label = options.course_label.course(course);
+                    let mut lines: Vec<String> = label
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty())
+                        .map(str::to_owned)
+                        .collect();
+                    if !options.course_label.uses("name") {
+                        lines.extend(course.name.clone());
+                    }
+
+                    let mut block = Block::new(Kind::Course, lines, vec![], theme);
+                    block.fill = options.colors.fill(&course.subject_code, Some(requirement));
+                    block
+                }
+                CourseEntry::Label(label) => {
+                    Block::new(Kind::Label, lines(&label.name), vec![], theme)
+                }
+            })
+            .collect()
+    }
+
+    /// Draws the block with its top left corner at `x`, `y`
+    fn draw(&self, svg: &mut String, x: u32, y: u32, theme: &Theme, options: &RenderOptions) {
+        let border = match self.kind {
+            Kind::AllOf => &options.and_style,
+            Kind::AnyOf => &options.or_style,
+            _ => &EdgeStyle::default(),
+        };
+
+        let _ = writeln!(svg, "<g class=\"{}\">", self.kind.class());
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill={} stroke={}{}/>",
+            self.width,
+            self.height,
+            theme.corner_radius,
+            attribute(self.fill.unwrap_or(self.kind.fill(theme))),
+            attribute(border.color.as_deref().unwrap_or(&theme.stroke)),
+            border.line.svg_attributes(),
+        );
+
+        let line_height = theme.line_height();
+        for (i, line) in self.lines.iter().enumerate() {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" fill={}{}>{}</text>",
+                x + theme.padding,
+                y + theme.padding + i as u32 * line_height + theme.font_size,
+                attribute(&theme.text),
+                if i == 0 { " font-weight=\"bold\"" } else { "" },
+                escape(line),
+            );
+        }
+
+        let mut child_x = x + theme.padding;
+        let mut child_y = y + theme.padding + self.lines.len() as u32 * line_height;
+        if !self.lines.is_empty() {
+            child_y += theme.spacing;
+        }
+
+        for child in &self.children {
+            child.draw(svg, child_x, child_y, theme, options);
+
+            if self.kind.is_horizontal() {
+                child_x += child.width + theme.spacing;
+            } else {
+                child_y += child.height + theme.spacing;
+            }
+        }
+
+        svg.push_str("</g>\n");
+    }
+}
+
+/// A single line of `text`, or none if it's empty
+fn lines(text: &str) -> Vec<String> {
+    if text.is_empty() {
+        vec![]
+    } else {
+        vec![text.to_owned()]
+    }
+}
+
+/// Quoted attribute value
+fn attribute(value: &str) -> String {
+    format!("\"{}\"", escape(value).replace('"', "&quot;"))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_support::cs_minor;
+    use crate::viz::render::{ColorScheme, LabelTemplate, LineStyle};
+
+    #[test]
+    fn every_course_is_drawn() {
+        let program = cs_minor();
+        let svg = to_svg(&program, &Theme::default());
+
+        assert_eq!(
+            svg.matches("<g class=\"course\">").count(),
+            program.iter_courses().count()
+        );
+        assert_eq!(svg.matches("<g ").count(), svg.matches("</g>").count());
+        assert!(svg.contains(&format!("<title>{}</title>", program.title)));
+        assert!(svg.ends_with("</g>\n</svg>\n"));
+    }
+
+    #[test]
+    fn children_fit_inside_of_their_parents() {
+        fn check(block: &Block, theme: &Theme) {
+            let (width, height) = if block.kind.is_horizontal() {
+                (
+                    block.children.iter().map(|child| child.width).sum::<u32>(),
+                    block.children.iter().map(|child| child.height).max(),
+                )
+            } else {
+                (
+                    block
+                        .children
+                        .iter()
+                        .map(|child| child.width)
+                        .max()
+                        .unwrap_or(0),
+                    Some(block.children.iter().map(|child| child.height).sum()),
+                )
+            };
+
+            assert!(width + 2 * theme.padding <= block.width);
+            assert!(height.unwrap_or(0) + 2 * theme.padding <= block.height);
+            block.children.iter().for_each(|child| check(child, theme));
+        }
+
+        let theme = Theme::default();
+        let root = Block::program(&cs_minor(), &theme, &RenderOptions::default());
+
+        assert_eq!(root.kind, Kind::Program);
+        assert!(!root.children.is_empty());
+        check(&root, &theme);
+    }
+
+    #[test]
+    fn theme_is_applied() {
+        let theme = Theme {
+            font_family: "\"Fira Sans\", sans-serif".to_owned(),
+            ..Theme::dark()
+        };
+        let svg = to_svg(&cs_minor(), &theme);
+
+        assert!(svg.contains("font-family=\"&quot;Fira Sans&quot;, sans-serif\""));
+        assert!(svg.contains("<rect width=\"100%\" height=\"100%\" fill=\"#0d1117\"/>"));
+        assert!(svg.contains("fill=\"#033a16\""));
+        assert!(svg.contains("stroke-dasharray"));
+    }
+
+    #[test]
+    fn render_options_are_applied() {
+        let program = cs_minor();
+        let options = RenderOptions {
+            colors: ColorScheme::RequirementType,
+            course_label: LabelTemplate::new("{number}\n{name}"),
+            and_style: EdgeStyle {
+                line: LineStyle::Dotted,
+                color: Some("red".to_owned()),
+            },
+            or_style: EdgeStyle::default(),
+        };
+        let svg = to_svg_with(&program, &Theme::default(), &options);
+
+        let course = program.iter_courses().next().unwrap();
+        assert!(svg.contains(&format!(" font-weight=\"bold\">{}</text>", course.number)));
+        assert!(!svg.contains(&format!(
+            ">{} {}</text>",
+            course.subject_code, course.number
+        )));
+        assert!(!svg.contains("stroke-dasharray=\"4 3\""));
+        assert!(svg.contains("stroke=\"red\" stroke-dasharray=\"1 2\""));
+        assert!(!svg.contains(&format!("fill=\"{}\"", Theme::default().course_fill)));
+
+        let default = to_svg(&program, &Theme::default());
+        assert_eq!(
+            default,
+            to_svg_with(&program, &Theme::default(), &RenderOptions::default())
+        );
+        assert!(default.contains(&format!(
+            ">{} {}</text>",
+            course.subject_code, course.number
+        )));
+    }
+
+    #[test]
+    fn text_is_escaped() {
+        assert_eq!(escape("Math & <Science>"), "Math &amp; &lt;Science&gt;");
+        assert_eq!(attribute("a \"b\""), "\"a &quot;b&quot;\"");
+    }
+}
@@ -0,0 +1,759 @@
+//! Mirrors of the data model for caching a [Catalog] in binary formats such as bincode, CBOR or
+//! MessagePack.
+//!
+//! The `Deserialize` implementations of the data model read the catalog API format, which needs a
+//! self-describing format like JSON. The wire types derive both `Serialize` and `Deserialize` with
+//! serde's default representations instead, so they can be decoded from any format. Converting to
+//! the wire types and back is lossless.
+//!
+//! # Example
+//! ```
+//! # use vislog_core::{catalog::Catalog, wire::WireCatalog};
+//! let (catalog, _errors) = Catalog::parse_file("../data/cs_minor.json");
+//!
+//! let wire = WireCatalog::from(&catalog);
+//! assert_eq!(Catalog::try_from(wire).unwrap(), catalog);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    catalog::Catalog, constraints::Constraints, electives::ElectiveHours, extensions::Extensions,
+    parsing::guid::Guid, references::CourseRef, shared::SharedModule, symbol::Symbol, Course,
+    CourseDetails, CourseEntries, CourseEntry, Label, Program, Requirement, RequirementModule,
+    Requirements,
+};
+
+/// Version of the wire types, bumped whenever they change in a way that breaks decoding snapshots
+/// written by an earlier version
+pub const WIRE_VERSION: u32 = 6;
+
+#[derive(Debug, Error)]
+pub enum WireError {
+    #[error("snapshot has wire version {found} but version {WIRE_VERSION} is supported")]
+    Version { found: u32 },
+    #[error("failed to parse the JSON of an unimplemented requirement module: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WireCatalog {
+    /// [WIRE_VERSION] of the types the snapshot was written with
+    pub version: u32,
+    pub programs: Vec<WireProgram>,
+    pub courses: Vec<WireCourseDetails>,
+    pub shared_modules: Vec<WireSharedModule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WireSharedModule {
+    pub guid: Guid,
+    pub module: WireRequirementModule,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WireProgram {
+    pub url: String,
+    pub guid: Guid,
+    pub title: String,
+    pub content: Option<String>,
+    pub bottom_content: Option<String>,
+    pub requirements: Option<WireRequirements>,
+    pub extra: WireExtensions,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WireRequirements {
+    Single(WireRequirementModule),
+    Many(Vec<WireRequirementModule>),
+    SelectTrack,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WireRequirementModule {
+    SingleBasicRequirement {
+        title: Option<String>,
+        requirement: WireRequirement,
+    },
+    BasicRequirements {
+        title: Option<String>,
+        requirements: Vec<WireRequirement>,
+    },
+    SelectOneEmphasis {
+        emphases: Vec<WireRequirement>,
+    },
+    Label {
+        title: String,
+    },
+    /// JSON of the module, since a [serde_json::Value] can only be decoded from self-describing
+    /// formats
+    Unimplemented(String),
+    Reference(Guid),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WireRequirement {
+    Courses {
+        title: Option<String>,
+        courses: Vec<WireCourseEntry>,
+        constraints: Constraints,
+        extra: WireExtensions,
+    },
+    SelectFromCourses {
+        title: String,
+        courses: Option<Vec<WireCourseEntry>>,
+        constraints: Constraints,
+        extra: WireExtensions,
+    },
+    Label {
+        title: Option<String>,
+        req_narrative: Option<String>,
+        constraints: Constraints,
+        course_refs: Vec<CourseRef>,
+        extra: WireExtensions,
+    },
+    ElectivePool {
+        title: Option<String>,
+        req_narrative: Option<String>,
+        hours: WireElectiveHours,
+        constraints: Constraints,
+        extra: WireExtensions,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WireElectiveHours {
+    Hours(u32),
+    ToTotal(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WireCourseEntry {
+    And(Vec<WireCourseEntry>),
+    Or(Vec<WireCourseEntry>),
+    Label(WireLabel),
+    Course(WireCourse),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WireCourse {
+    pub url: String,
+    pub path: String,
+    pub guid: Guid,
+    pub name: Option<String>,
+    pub number: String,
+    pub subject_name: Option<Symbol>,
+    pub subject_code: Symbol,
+    pub credits: (u8, Option<u8>),
+    pub extra: WireExtensions,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WireLabel {
+    pub url: String,
+    pub guid: Guid,
+    pub name: String,
+    pub number: Option<String>,
+    pub subject_code: Option<Symbol>,
+    pub credits: (u8, Option<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WireCourseDetails {
+    pub url: String,
+    pub guid: Guid,
+    pub path: String,
+    pub subject_code: Symbol,
+    pub subject_name: Option<Symbol>,
+    pub number: String,
+    pub name: String,
+    pub credits_min: u8,
+    pub credits_max: Option<u8>,
+    pub description: String,
+    pub prerequisite_narrative: Option<String>,
+    pub prerequisite: Option<Guid>,
+    pub corequisite_narrative: Option<String>,
+    pub corequisite: Option<Guid>,
+    pub extra: WireExtensions,
+}
+
+/// [Extensions] by name
+pub type WireExtensions = Vec<(String, WireValue)>;
+
+/// Mirror of a [Value], which can only be decoded from self-describing formats
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WireValue {
+    Null,
+    Bool(bool),
+    U64(u64),
+    I64(i64),
+    F64(f64),
+    String(String),
+    Array(Vec<WireValue>),
+    Object(Vec<(String, WireValue)>),
+}
+
+/// Numbers are always finite since they come from JSON
+impl Eq for WireValue {}
+
+impl From<&Catalog> for WireCatalog {
+    fn from(catalog: &Catalog) -> Self {
+        Self {
+            version: WIRE_VERSION,
+            programs: catalog.programs.iter().map(Into::into).collect(),
+            courses: catalog.courses.iter().map(Into::into).collect(),
+            shared_modules: catalog
+                .shared_modules
+                .iter()
+                .map(|shared| WireSharedModule {
+                    guid: shared.guid,
+                    module: (&shared.module).into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<WireCatalog> for Catalog {
+    type Error = WireError;
+
+    fn try_from(wire: WireCatalog) -> Result<Self, Self::Error> {
+        if wire.version != WIRE_VERSION {
+            return Err(WireError::Version {
+                found: wire.version,
+            });
+        }
+
+        let mut catalog = Self {
+            programs: wire
+                .programs
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            courses: wire.courses.into_iter().map(Into::into).collect(),
+            shared_modules: wire
+                .shared_modules
+                .into_iter()
+                .map(|shared| {
+                    Ok(SharedModule {
+                        guid: shared.guid,
+                        module: shared.module.try_into()?,
+                    })
+                })
+                .collect::<Result<_, WireError>>()?,
+        };
+        catalog.intern_courses();
+
+        Ok(catalog)
+    }
+}
+
+impl From<&Program> for WireProgram {
+    fn from(program: &Program) -> Self {
+        Self {
+            url: program.url.clone(),
+            guid: program.guid,
+            title: program.title.clone(),
+            content: program.content.clone(),
+            bottom_content: program.bottom_content.clone(),
+            requirements: program.requirements.as_ref().map(Into::into),
+            extra: wire_extensions(&program.extra),
+        }
+    }
+}
+
+impl TryFrom<WireProgram> for Program {
+    type Error = WireError;
+
+    fn try_from(wire: WireProgram) -> Result<Self, Self::Error> {
+        Ok(Self {
+            url: wire.url,
+            guid: wire.guid,
+            title: wire.title,
+            content: wire.content,
+            bottom_content: wire.bottom_content,
+            requirements: wire.requirements.map(TryInto::try_into).transpose()?,
+            extra: extensions(wire.extra),
+        })
+    }
+}
+
+impl From<&Requirements> for WireRequirements {
+    fn from(requirements: &Requirements) -> Self {
+        match requirements {
+            Requirements::Single(module) => WireRequirements::Single(module.into()),
+            Requirements::Many(modules) => {
+                WireRequirements::Many(modules.iter().map(Into::into).collect())
+            }
+            Requirements::SelectTrack => WireRequirements::SelectTrack,
+        }
+    }
+}
+
+impl TryFrom<WireRequirements> for Requirements {
+    type Error = WireError;
+
+    fn try_from(wire: WireRequirements) -> Result<Self, Self::Error> {
+        let requirements = match wire {
+            WireRequirements::Single(module) => Requirements::Single(module.try_into()?),
+            WireRequirements::Many(modules) => Requirements::Many(
+                modules
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_, _>>()?,
+            ),
+            WireRequirements::SelectTrack => Requirements::SelectTrack,
+        };
+
+        Ok(requirements)
+    }
+}
+
+impl From<&RequirementModule> for WireRequirementModule {
+    fn from(module: &RequirementModule) -> Self {
+        match module {
+            RequirementModule::SingleBasicRequirement { title, requirement } => {
+                WireRequirementModule::SingleBasicRequirement {
+                    title: title.clone(),
+                    requirement: requirement.into(),
+                }
+            }
+            RequirementModule::BasicRequirements {
+                title,
+                requirements,
+            } => WireRequirementModule::BasicRequirements {
+                title: title.clone(),
+                requirements: requirements.iter().map(Into::into).collect(),
+            },
+            RequirementModule::SelectOneEmphasis { emphases } => {
+                WireRequirementModule::SelectOneEmphasis {
+                    emphases: emphases.iter().map(Into::into).collect(),
+                }
+            }
+            RequirementModule::Label { title } => WireRequirementModule::Label {
+                title: title.clone(),
+            },
+            RequirementModule::Unimplemented(value) => {
+                WireRequirementModule::Unimplemented(value.to_string())
+            }
+            RequirementModule::Reference(guid) => WireRequirementModule::Reference(*guid),
+        }
+    }
+}
+
+impl TryFrom<WireRequirementModule> for RequirementModule {
+    type Error = WireError;
+
+    fn try_from(wire: WireRequirementModule) -> Result<Self, Self::Error> {
+        let module = match wire {
+            WireRequirementModule::SingleBasicRequirement { title, requirement } => {
+                RequirementModule::SingleBasicRequirement {
+                    title,
+                    requirement: requirement.into(),
+                }
+            }
+            WireRequirementModule::BasicRequirements {
+                title,
+                requirements,
+            } => RequirementModule::BasicRequirements {
+                title,
+                requirements: requirements.into_iter().map(Into::into).collect(),
+            },
+            WireRequirementModule::SelectOneEmphasis { emphases } => {
+                RequirementModule::SelectOneEmphasis {
+                    emphases: emphases.into_iter().map(Into::into).collect(),
+                }
+            }
+            WireRequirementModule::Label { title } => RequirementModule::Label { title },
+            WireRequirementModule::Unimplemented(json) => {
+                RequirementModule::Unimplemented(serde_json::from_str(&json)?)
+            }
+            WireRequirementModule::Reference(guid) => RequirementModule::Reference(guid),
+        };
+
+        Ok(module)
+    }
+}
+
+impl From<&Requirement> for WireRequirement {
+    fn from(requirement: &Requirement) -> Self {
+        match requirement {
+            Requirement::Courses {
+                title,
+                courses,
+                constraints,
+                extra,
+            } => WireRequirement::Courses {
+                title: title.clone(),
+                courses: wire_entries(courses),
+                constraints: *constraints,
+                extra: wire_extensions(extra),
+            },
+            Requirement::SelectFromCourses {
+                title,
+                courses,
+                constraints,
+                extra,
+            } => WireRequirement::SelectFromCourses {
+                title: title.clone(),
+                courses: courses.as_ref().map(wire_entries),
+                constraints: *constraints,
+                extra: wire_extensions(extra),
+            },
+            Requirement::Label {
+                title,
+                req_narrative,
+                constraints,
+                course_refs,
+                extra,
+            } => WireRequirement::Label {
+                title: title.clone(),
+                req_narrative: req_narrative.clone(),
+                constraints: *constraints,
+                course_refs: course_refs.clone(),
+                extra: wire_extensions(extra),
+            },
+            Requirement::ElectivePool {
+                title,
+                req_narrative,
+                hours,
+                constraints,
+                extra,
+            } => WireRequirement::ElectivePool {
+                title: title.clone(),
+                req_narrative: req_narrative.clone(),
+                hours: (*hours).into(),
+                constraints: *constraints,
+                extra: wire_extensions(extra),
+            },
+        }
+    }
+}
+
+impl From<WireRequirement> for Requirement {
+    fn from(wire: WireRequirement) -> Self {
+        match wire {
+            WireRequirement::Courses {
+                title,
+                courses,
+                constraints,
+                extra,
+            } => Requirement::Courses {
+                title,
+                courses: course_entries(courses),
+                constraints,
+                extra: extensions(extra),
+            },
+            WireRequirement::SelectFromCourses {
+                title,
+                courses,
+                constraints,
+                extra,
+            } => Requirement::SelectFromCourses {
+                title,
+                courses: courses.map(course_entries),
+                constraints,
+                extra: extensions(extra),
+            },
+            WireRequirement::Label {
+                title,
+                req_narrative,
+                constraints,
+                course_refs,
+                extra,
+            } => Requirement::Label {
+                title,
+                req_narrative,
+                constraints,
+                course_refs,
+                extra: extensions(extra),
+            },
+            WireRequirement::ElectivePool {
+                title,
+                req_narrative,
+                hours,
+                constraints,
+                extra,
+            } => Requirement::ElectivePool {
+                title,
+                req_narrative,
+                hours: hours.into(),
+                constraints,
+                extra: extensions(extra),
+            },
+        }
+    }
+}
+
+fn wire_entries(entries: &CourseEntries) -> Vec<WireCourseEntry> {
+    entries.iter().map(Into::into).collect()
+}
+
+fn course_entries(wire: Vec<WireCourseEntry>) -> CourseEntries {
+    wire.into_iter().map(Into::into).collect()
+}
+
+impl From<&CourseEntry> for WireCourseEntry {
+    fn from(entry: &CourseEntry) -> Self {
+        match entry {
+            CourseEntry::And(entries) => WireCourseEntry::And(wire_entries(entries)),
+            CourseEntry::Or(entries) => WireCourseEntry::Or(wire_entries(entries)),
+            CourseEntry::Label(label) => WireCourseEntry::Label(label.into()),
+            CourseEntry::Course(course) => WireCourseEntry::Course(course.as_ref().into()),
+        }
+    }
+}
+
+impl From<WireCourseEntry> for CourseEntry {
+    fn from(wire: WireCourseEntry) -> Self {
+        match wire {
+            WireCourseEntry::And(entries) => CourseEntry::And(course_entries(entries)),
+            WireCourseEntry::Or(entries) => CourseEntry::Or(course_entries(entries)),
+            WireCourseEntry::Label(label) => CourseEntry::Label(label.into()),
+            WireCourseEntry::Course(course) => Course::from(course).into(),
+        }
+    }
+}
+
+impl From<&Course> for WireCourse {
+    fn from(course: &Course) -> Self {
+        Self {
+            url: course.url.clone(),
+            path: course.path.clone(),
+            guid: course.guid,
+            name: course.name.clone(),
+            number: course.number.clone(),
+            subject_name: course.subject_name.clone(),
+            subject_code: course.subject_code.clone(),
+            credits: course.credits,
+            extra: wire_extensions(&course.extra),
+        }
+    }
+}
+
+impl From<WireCourse> for Course {
+    fn from(wire: WireCourse) -> Self {
+        Self {
+            url: wire.url,
+            path: wire.path,
+            guid: wire.guid,
+            name: wire.name,
+            number: wire.number,
+            subject_name: wire.subject_name,
+            subject_code: wire.subject_code,
+            credits: wire.credits,
+            extra: extensions(wire.extra),
+            details: None,
+        }
+    }
+}
+
+impl From<ElectiveHours> for WireElectiveHours {
+    fn from(hours: ElectiveHours) -> Self {
+        match hours {
+            ElectiveHours::Hours(hours) => WireElectiveHours::Hours(hours),
+            ElectiveHours::ToTotal(total) => WireElectiveHours::ToTotal(total),
+        }
+    }
+}
+
+impl From<WireElectiveHours> for ElectiveHours {
+    fn from(wire: WireElectiveHours) -> Self {
+        match wire {
+            WireElectiveHours::Hours(hours) => ElectiveHours::Hours(hours),
+            WireElectiveHours::ToTotal(total) => ElectiveHours::ToTotal(total),
+        }
+    }
+}
+
+impl From<&Label> for WireLabel {
+    fn from(label: &Label) -> Self {
+        Self {
+            url: label.url.clone(),
+            guid: label.guid,
+            name: label.name.clone(),
+            number: label.number.clone(),
+            subject_code: label.subject_code.clone(),
+            credits: label.credits,
+        }
+    }
+}
+
+impl From<WireLabel> for Label {
+    fn from(wire: WireLabel) -> Self {
+        Self {
+            url: wire.url,
+            guid: wire.guid,
+            name: wire.name,
+            number: wire.number,
+            subject_code: wire.subject_code,
+            credits: wire.credits,
+        }
+    }
+}
+
+impl From<&CourseDetails> for WireCourseDetails {
+    fn from(course: &CourseDetails) -> Self {
+        Self {
+            url: course.url.clone(),
+            guid: course.guid,
+            path: course.path.clone(),
+            subject_code: course.subject_code.clone(),
+            subject_name: course.subject_name.clone(),
+            number: course.number.clone(),
+            name: course.name.clone(),
+            credits_min: course.credits_min,
+            credits_max: course.credits_max,
+            description: course.description.clone(),
+            prerequisite_narrative: course.prerequisite_narrative.clone(),
+            prerequisite: course.prerequisite,
+            corequisite_narrative: course.corequisite_narrative.clone(),
+            corequisite: course.corequisite,
+            extra: wire_extensions(&course.extra),
+        }
+    }
+}
+
+impl From<WireCourseDetails> for CourseDetails {
+    fn from(wire: WireCourseDetails) -> Self {
+        Self {
+            url: wire.url,
+            guid: wire.guid,
+            path: wire.path,
+            subject_code: wire.subject_code,
+            subject_name: wire.subject_name,
+            number: wire.number,
+            name: wire.name,
+            credits_min: wire.credits_min,
+            credits_max: wire.credits_max,
+            description: wire.description,
+            prerequisite_narrative: wire.prerequisite_narrative,
+            prerequisite: wire.prerequisite,
+            corequisite_narrative: wire.corequisite_narrative,
+            corequisite: wire.corequisite,
+            extra: extensions(wire.extra),
+        }
+    }
+}
+
+fn wire_extensions(extra: &Extensions) -> WireExtensions {
+    extra
+        .iter()
+        .map(|(name, value)| (name.clone(), value.into()))
+        .collect()
+}
+
+fn extensions(wire: WireExtensions) -> Extensions {
+    wire.into_iter()
+        .map(|(name, value)| (name, value.into()))
+        .collect()
+}
+
+impl From<&Value> for WireValue {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Null => WireValue::Null,
+            Value::Bool(bool) => WireValue::Bool(*bool),
+            Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+                (Some(n), _) => WireValue::U64(n),
+                (None, Some(n)) => WireValue::I64(n),
+                (None, None) => WireValue::F64(number.as_f64().unwrap_or_default()),
+            },
+            Value::String(string) => WireValue::String(string.clone()),
+            Value::Array(values) => WireValue::Array(values.iter().map(Into::into).collect()),
+            Value::Object(fields) => WireValue::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.into()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<WireValue> for Value {
+    fn from(wire: WireValue) -> Self {
+        match wire {
+            WireValue::Null => Value::Null,
+            WireValue::Bool(bool) => Value::Bool(bool),
+            WireValue::U64(n) => Value::from(n),
+            WireValue::I64(n) => Value::from(n),
+            WireValue::F64(n) => Value::from(n),
+            WireValue::String(string) => Value::String(string),
+            WireValue::Array(values) => Value::Array(values.into_iter().map(Into::into).collect()),
+            WireValue::Object(fields) => Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(name, value)| (name, value.into()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn data_catalog() -> Catalog {
+        let (catalog, _errors) = Catalog::parse_dir("../data").expect("Failed to read data dir");
+        catalog
+    }
+
+    #[test]
+    fn round_trips_through_bincode() {
+        let catalog = data_catalog();
+
+        let bytes = bincode::serialize(&WireCatalog::from(&catalog)).unwrap();
+        let wire: WireCatalog = bincode::deserialize(&bytes).unwrap();
+
+        assert_eq!(Catalog::try_from(wire).unwrap(), catalog);
+    }
+
+    #[test]
+    fn shared_modules_round_trip() {
+        let mut catalog = data_catalog();
+        catalog.resolve_references();
+        assert!(!catalog.shared_modules.is_empty());
+
+        let bytes = bincode::serialize(&WireCatalog::from(&catalog)).unwrap();
+        let wire: WireCatalog = bincode::deserialize(&bytes).unwrap();
+
+        assert_eq!(Catalog::try_from(wire).unwrap(), catalog);
+    }
+
+    #[test]
+    fn round_trips_through_cbor() {
+        let catalog = data_catalog();
+
+        let mut bytes = vec![];
+        ciborium::into_writer(&WireCatalog::from(&catalog), &mut bytes).unwrap();
+        let wire: WireCatalog = ciborium::from_reader(bytes.as_slice()).unwrap();
+
+        assert_eq!(Catalog::try_from(wire).unwrap(), catalog);
+    }
+
+    #[test]
+    fn unimplemented_modules_keep_their_json() {
+        let module = RequirementModule::Unimplemented(serde_json::json!({ "title": "Track" }));
+
+        let wire = WireRequirementModule::from(&module);
+        let bytes = bincode::serialize(&wire).unwrap();
+        let wire: WireRequirementModule = bincode::deserialize(&bytes).unwrap();
+
+        assert_eq!(RequirementModule::try_from(wire).unwrap(), module);
+    }
+
+    #[test]
+    fn error_when_version_does_not_match() {
+        let mut wire = WireCatalog::from(&Catalog::default());
+        wire.version = WIRE_VERSION + 1;
+
+        assert!(matches!(
+            Catalog::try_from(wire),
+            Err(WireError::Version { found }) if found == WIRE_VERSION + 1
+        ));
+    }
+}
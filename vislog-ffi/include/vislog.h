@@ -0,0 +1,43 @@
+/*
+ * C interface to the vislog catalog parser.
+ *
+ * Every function takes NUL-terminated UTF-8 JSON and writes a newly allocated NUL-terminated
+ * string to `out`: the resulting JSON when VISLOG_STATUS_OK is returned, otherwise a message
+ * describing the error. Strings written to `out` are owned by the caller and must be released
+ * with vislog_free_string.
+ */
+
+#ifndef VISLOG_H
+#define VISLOG_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum VislogStatus {
+    VISLOG_STATUS_OK = 0,
+    /* A pointer argument was NULL. Nothing is written to `out` if `out` itself is NULL. */
+    VISLOG_STATUS_NULL_ARGUMENT = 1,
+    /* The input was not valid UTF-8 */
+    VISLOG_STATUS_INVALID_UTF8 = 2,
+    /* The input was not valid JSON or not a valid program */
+    VISLOG_STATUS_PARSE_ERROR = 3,
+    /* Bug in the library, such as a panic. Should be reported along with the input. */
+    VISLOG_STATUS_INTERNAL_ERROR = 4,
+} VislogStatus;
+
+/* Parses the JSON of a single program as returned by the catalog API */
+VislogStatus vislog_parse_program(const char *json, char **out);
+
+/* Same as vislog_parse_program but canonical: compact, with the keys of every object sorted and
+ * every string in Unicode NFC with \n line endings */
+VislogStatus vislog_program_to_canonical_json(const char *json, char **out);
+
+/* Releases a string written to `out`. Does nothing when `s` is NULL. */
+void vislog_free_string(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* VISLOG_H */
@@ -0,0 +1,201 @@
+//! C ABI for embedding the parser in services written in other languages, such as .NET through
+//! P/Invoke. The declarations are in `include/vislog.h`.
+//!
+//! Every function takes NUL-terminated UTF-8 JSON and writes a newly allocated NUL-terminated
+//! string to `out`: the resulting JSON when [VislogStatus::Ok] is returned, otherwise a message
+//! describing the error. Strings written to `out` are owned by the caller and must be released
+//! with [vislog_free_string].
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+};
+
+use vislog_core::{error, Program};
+
+/// Result of every function. The values are part of the ABI and never change meaning.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VislogStatus {
+    Ok = 0,
+    /// A pointer argument was NULL. Nothing is written to `out` if `out` itself is NULL.
+    NullArgument = 1,
+    /// The input was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// The input was not valid JSON or not a valid program
+    ParseError = 3,
+    /// Bug in the library, such as a panic. Should be reported along with the input.
+    InternalError = 4,
+}
+
+type Failure = (VislogStatus, String);
+
+/// Parses the JSON of a single program as returned by the catalog API and writes the parsed
+/// program as JSON to `out`, in the same shape the server responds with.
+///
+/// # Safety
+/// `json` must be NULL or point to a NUL-terminated string, and `out` must be NULL or point to
+/// memory a pointer can be written to.
+#[no_mangle]
+pub unsafe extern "C" fn vislog_parse_program(
+    json: *const c_char,
+    out: *mut *mut c_char,
+) -> VislogStatus {
+    run(json, out, |json| {
+        let program = parse_program(json)?;
+        serde_json::to_string(&program)
+            .map_err(|err| (VislogStatus::InternalError, err.to_string()))
+    })
+}
+
+/// Same as [vislog_parse_program] but the output is canonical, so that parsing the same program
+/// always produces byte-for-byte the same output. See [Program::to_canonical_json].
+///
+/// # Safety
+/// Same as [vislog_parse_program].
+#[no_mangle]
+pub unsafe extern "C" fn vislog_program_to_canonical_json(
+    json: *const c_char,
+    out: *mut *mut c_char,
+) -> VislogStatus {
+    run(json, out, |json| {
+        let program = parse_program(json)?;
+        Ok(program.to_canonical_json())
+    })
+}
+
+/// Releases a string written to `out` by any of the other functions. Does nothing when `s` is
+/// NULL.
+///
+/// # Safety
+/// `s` must be NULL or a string returned by this library that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn vislog_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+fn parse_program(json: &str) -> Result<Program, Failure> {
+    error::from_str(json).map_err(|err| (VislogStatus::ParseError, err.to_string()))
+}
+
+/// Checks the arguments, runs `f` on the input without letting panics cross the FFI boundary,
+/// and writes its result or error message to `out`
+unsafe fn run<F>(input: *const c_char, out: *mut *mut c_char, f: F) -> VislogStatus
+where
+    F: FnOnce(&str) -> Result<String, Failure>,
+{
+    if out.is_null() {
+        return VislogStatus::NullArgument;
+    }
+    *out = ptr::null_mut();
+
+    let result = if input.is_null() {
+        Err((VislogStatus::NullArgument, "input is NULL".to_owned()))
+    } else {
+        match CStr::from_ptr(input).to_str() {
+            Ok(input) => panic::catch_unwind(AssertUnwindSafe(|| f(input))).unwrap_or_else(|_| {
+                Err((
+                    VislogStatus::InternalError,
+                    "panicked while handling the input".to_owned(),
+                ))
+            }),
+            Err(err) => Err((VislogStatus::InvalidUtf8, err.to_string())),
+        }
+    };
+
+    let (status, output) = match result {
+        Ok(output) => (VislogStatus::Ok, output),
+        Err(failure) => failure,
+    };
+
+    // JSON escapes NUL, so only error messages could contain one
+    let output = CString::new(output.replace('\0', "\\0")).expect("NULs were replaced");
+    *out = output.into_raw();
+
+    status
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Calls `f` with `input` and an output pointer, then takes back the string written to it
+    fn call(
+        f: unsafe extern "C" fn(*const c_char, *mut *mut c_char) -> VislogStatus,
+        input: *const c_char,
+    ) -> (VislogStatus, String) {
+        let mut out = ptr::null_mut();
+        let status = unsafe { f(input, &mut out) };
+        assert!(!out.is_null());
+
+        let output = unsafe { CStr::from_ptr(out) }.to_str().unwrap().to_owned();
+        unsafe { vislog_free_string(out) };
+
+        (status, output)
+    }
+
+    fn cs_major() -> CString {
+        CString::new(std::fs::read("../data/cs_major.json").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn null_output_pointer_is_a_null_argument() {
+        let json = cs_major();
+
+        let status = unsafe { vislog_parse_program(json.as_ptr(), ptr::null_mut()) };
+
+        assert_eq!(status, VislogStatus::NullArgument);
+    }
+
+    #[test]
+    fn null_input_is_a_null_argument() {
+        let (status, message) = call(vislog_parse_program, ptr::null());
+
+        assert_eq!(status, VislogStatus::NullArgument);
+        assert_eq!(message, "input is NULL");
+    }
+
+    #[test]
+    fn error_when_input_is_not_utf8() {
+        let input = CString::new(vec![b'"', 0xFF, 0xFE, b'"']).unwrap();
+
+        let (status, _message) = call(vislog_parse_program, input.as_ptr());
+
+        assert_eq!(status, VislogStatus::InvalidUtf8);
+    }
+
+    #[test]
+    fn error_when_input_is_malformed_json() {
+        let input = CString::new(r#"{"title": "#).unwrap();
+
+        let (status, message) = call(vislog_parse_program, input.as_ptr());
+
+        assert_eq!(status, VislogStatus::ParseError);
+        assert!(!message.is_empty());
+    }
+
+    #[test]
+    fn can_parse_program() {
+        let json = cs_major();
+
+        let (status, output) = call(vislog_parse_program, json.as_ptr());
+
+        assert_eq!(status, VislogStatus::Ok);
+        let expected: Program = serde_json::from_slice(json.as_bytes()).unwrap();
+        assert_eq!(output, serde_json::to_string(&expected).unwrap());
+    }
+
+    #[test]
+    fn can_write_program_as_canonical_json() {
+        let json = cs_major();
+
+        let (status, output) = call(vislog_program_to_canonical_json, json.as_ptr());
+
+        assert_eq!(status, VislogStatus::Ok);
+        let expected: Program = serde_json::from_slice(json.as_bytes()).unwrap();
+        assert_eq!(output, expected.to_canonical_json());
+    }
+}
@@ -1,6 +1,12 @@
+use std::sync::Arc;
+
 use serde_json::{self, Value};
 use thiserror::Error;
-use vislog_core::{Course, CourseDetails, Program};
+use vislog_core::{
+    error::{self, VislogError},
+    metrics::{self, Counter},
+    CourseDetails, Program,
+};
 
 #[derive(Debug, Clone, Error)]
 pub enum ParsingError {
@@ -9,10 +15,11 @@ pub enum ParsingError {
         title: Option<String>,
         err_msg: String,
     },
-    #[error("failed to convert {:?} from value to string because {}", .title, .err_msg)]
+    #[error("failed to parse {:?} because {}", .title, .source)]
     Deserialization {
         title: Option<String>,
-        err_msg: String,
+        #[source]
+        source: Arc<VislogError>,
     },
 }
 
@@ -39,12 +46,15 @@ where
                 continue;
             }
         };
-        match serde_json::from_str::<Program>(&json_str) {
+        match error::from_str::<Program>(&json_str) {
             Ok(program) => programs.push(program),
-            Err(err) => errors.push(ParsingError::Deserialization {
-                title: program_title,
-                err_msg: err.to_string(),
-            }),
+            Err(err) => {
+                metrics::global().increment(Counter::ProgramParseFailures);
+                errors.push(ParsingError::Deserialization {
+                    title: program_title,
+                    source: Arc::new(err),
+                })
+            }
         }
     }
 
@@ -89,12 +99,15 @@ where
             }
         };
 
-        match serde_json::from_str::<CourseDetails>(&json_str) {
+        match error::from_str::<CourseDetails>(&json_str) {
             Ok(course) => courses.push(course),
-            Err(err) => errors.push(ParsingError::Deserialization {
-                title: course_name,
-                err_msg: err.to_string(),
-            }),
+            Err(err) => {
+                metrics::global().increment(Counter::CourseParseFailures);
+                errors.push(ParsingError::Deserialization {
+                    title: course_name,
+                    source: Arc::new(err),
+                })
+            }
         }
     }
 
@@ -0,0 +1,333 @@
+//! Python bindings for `vislog-core`, built as the `vislog` extension module with
+//! [maturin](https://www.maturin.rs/).
+//!
+//! ```python
+//! import pandas as pd
+//! import vislog
+//!
+//! catalog = vislog.Catalog.parse_dir("data")
+//! courses = pd.DataFrame(catalog.program_courses())
+//! ```
+
+use std::path::PathBuf;
+
+use pyo3::{
+    exceptions::{PyOSError, PyValueError},
+    prelude::*,
+};
+use serde::Serialize;
+use vislog_core::{catalog::Catalog, catalog::CatalogError, error, Program};
+
+/// Converts anything serializable into the plain dicts, lists and scalars that `json.loads` would
+/// return for its JSON
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let json =
+        serde_json::to_string(value).map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+    Ok(PyModule::import(py, "json")?
+        .call_method1("loads", (json,))?
+        .unbind())
+}
+
+fn catalog_error(err: CatalogError) -> PyErr {
+    match err {
+        CatalogError::Io { .. } => PyOSError::new_err(err.to_string()),
+        _ => PyValueError::new_err(err.to_string()),
+    }
+}
+
+/// A program of the catalog along with its requirements
+#[pyclass(name = "Program", module = "vislog", frozen)]
+#[derive(Clone)]
+struct PyProgram {
+    inner: Program,
+}
+
+#[pymethods]
+impl PyProgram {
+    /// Parses the JSON of a single program as returned by the catalog API
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        let inner = error::from_str(json).map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn guid(&self) -> String {
+        self.inner.guid.to_string()
+    }
+
+    #[getter]
+    fn title(&self) -> &str {
+        &self.inner.title
+    }
+
+    #[getter]
+    fn url(&self) -> &str {
+        &self.inner.url
+    }
+
+    #[getter]
+    fn content(&self) -> Option<&str> {
+        self.inner.content.as_deref()
+    }
+
+    /// Every requirement of the program as a dict, in the order they appear in the catalog
+    fn requirements(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.inner
+            .iter_requirements()
+            .map(|requirement| to_py(py, requirement))
+            .collect()
+    }
+
+    /// Every course of the program as a dict, including the ones nested inside of operator
+    /// groups
+    fn courses(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.inner
+            .iter_courses()
+            .map(|course| to_py(py, course))
+            .collect()
+    }
+
+    /// The whole program as nested dicts, in the same shape the server responds with
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Program(guid='{}', title={:?})",
+            self.inner.guid, self.inner.title
+        )
+    }
+}
+
+/// One row of [PyCatalog::program_courses]
+#[derive(Serialize)]
+struct ProgramCourseRow<'a> {
+    program_guid: String,
+    program_title: &'a str,
+    requirement: Option<&'a str>,
+    guid: String,
+    subject_code: &'a str,
+    number: &'a str,
+    name: Option<&'a str>,
+    credits_min: u8,
+    credits_max: Option<u8>,
+}
+
+/// All the programs and courses of a catalog
+#[pyclass(name = "Catalog", module = "vislog", frozen)]
+struct PyCatalog {
+    inner: Catalog,
+    errors: Vec<String>,
+}
+
+impl PyCatalog {
+    fn new((inner, errors): (Catalog, Vec<CatalogError>)) -> Self {
+        Self {
+            inner,
+            errors: errors.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyCatalog {
+    /// Parses every `.json` file directly inside of `path`. Entries that fail to parse are left
+    /// out and listed in `errors`.
+    #[staticmethod]
+    fn parse_dir(path: PathBuf) -> PyResult<Self> {
+        Catalog::parse_dir(path)
+            .map(Self::new)
+            .map_err(catalog_error)
+    }
+
+    /// Parses a single file. Entries that fail to parse are left out and listed in `errors`.
+    #[staticmethod]
+    fn parse_file(path: PathBuf) -> Self {
+        Self::new(Catalog::parse_file(path))
+    }
+
+    /// Parses a program, a course, a JSON array of programs or a whole catalog dump. Entries that
+    /// fail to parse are left out and listed in `errors`.
+    #[staticmethod]
+    fn parse_json(json: &str) -> Self {
+        Self::new(Catalog::parse_json("<input>", json))
+    }
+
+    #[getter]
+    fn programs(&self) -> Vec<PyProgram> {
+        self.inner
+            .programs
+            .iter()
+            .map(|program| PyProgram {
+                inner: program.clone(),
+            })
+            .collect()
+    }
+
+    /// Messages of everything that failed to parse
+    #[getter]
+    fn errors(&self) -> Vec<String> {
+        self.errors.clone()
+    }
+
+    /// The program with the given GUID, if the catalog has it
+    fn program(&self, guid: &str) -> Option<PyProgram> {
+        self.inner
+            .programs
+            .iter()
+            .find(|program| program.guid.to_string().eq_ignore_ascii_case(guid))
+            .map(|program| PyProgram {
+                inner: program.clone(),
+            })
+    }
+
+    /// The details of every course in the catalog as dicts
+    fn courses(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.inner
+            .courses
+            .iter()
+            .map(|course| to_py(py, course))
+            .collect()
+    }
+
+    /// One flat dict for every course listed by every program, along with the program and
+    /// requirement listing it. Ready to be loaded into a `pandas.DataFrame`.
+    fn program_courses(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        let mut rows = vec![];
+
+        for program in &self.inner.programs {
+            for requirement in program.iter_requirements() {
+                let courses = requirement
+                    .course_entries()
+                    .into_iter()
+                    .flat_map(|entries| entries.iter_courses());
+
+                for course in courses {
+                    let row = ProgramCourseRow {
+                        program_guid: program.guid.to_string(),
+                        program_title: &program.title,
+                        requirement: requirement.title(),
+                        guid: course.guid.to_string(),
+                        subject_code: &course.subject_code,
+                        number: &course.number,
+                        name: course.name.as_deref(),
+                        credits_min: course.credits.0,
+                        credits_max: course.credits.1,
+                    };
+                    rows.push(to_py(py, &row)?);
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Catalog(programs={}, courses={}, errors={})",
+            self.inner.programs.len(),
+            self.inner.courses.len(),
+            self.errors.len()
+        )
+    }
+}
+
+#[pymodule]
+fn vislog(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyProgram>()?;
+    m.add_class::<PyCatalog>()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use pyo3::types::{PyDict, PyList};
+
+    use super::*;
+
+    fn with_gil<R>(f: impl FnOnce(Python<'_>) -> R) -> R {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(f)
+    }
+
+    fn cs_minor() -> PyProgram {
+        let json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+        PyProgram::from_json(&json).unwrap()
+    }
+
+    #[test]
+    fn programs_become_nested_dicts() {
+        with_gil(|py| {
+            let program = cs_minor();
+            let dict = program.to_dict(py).unwrap();
+            let dict = dict.downcast_bound::<PyDict>(py).unwrap();
+
+            let title: String = dict.get_item("title").unwrap().unwrap().extract().unwrap();
+            assert_eq!(title, "Minor in Computer Science—21 or 22 hours");
+            assert_eq!(
+                dict.get_item("guid").unwrap().unwrap().to_string(),
+                program.guid()
+            );
+
+            let requirements = dict.get_item("requirements").unwrap().unwrap();
+            assert!(requirements.downcast::<PyDict>().is_ok());
+        });
+    }
+
+    #[test]
+    fn courses_become_flat_dicts() {
+        with_gil(|py| {
+            let program = cs_minor();
+            let courses = program.courses(py).unwrap();
+            assert_eq!(courses.len(), program.inner.iter_courses().count());
+
+            let course = courses[0].downcast_bound::<PyDict>(py).unwrap();
+            let credits = course.get_item("credits").unwrap().unwrap();
+            let credits = credits.downcast::<PyList>().unwrap();
+            assert_eq!(credits.get_item(0).unwrap().extract::<u8>().unwrap(), 3);
+            assert!(credits.get_item(1).unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn program_courses_carry_their_program_and_requirement() {
+        with_gil(|py| {
+            let json = std::fs::read_to_string("../data/cs_minor.json").unwrap();
+            let catalog = PyCatalog::parse_json(&json);
+            assert!(catalog.errors().is_empty());
+
+            let rows = catalog.program_courses(py).unwrap();
+            assert_eq!(rows.len(), cs_minor().inner.iter_courses().count());
+
+            let row = rows[0].downcast_bound::<PyDict>(py).unwrap();
+            let field = |key: &str| row.get_item(key).unwrap().unwrap().to_string();
+            assert_eq!(
+                field("program_title"),
+                "Minor in Computer Science—21 or 22 hours"
+            );
+            assert_eq!(field("subject_code"), "CSC");
+            assert!(row.get_item("requirement").unwrap().is_some());
+        });
+    }
+
+    #[test]
+    fn catalog_errors_become_python_exceptions() {
+        with_gil(|py| {
+            let err = PyCatalog::parse_dir("../data/missing".into())
+                .err()
+                .unwrap();
+            assert!(err.is_instance_of::<PyOSError>(py));
+
+            let catalog = PyCatalog::parse_json("not json");
+            assert_eq!(catalog.errors().len(), 1);
+
+            let err = PyProgram::from_json("{}").err().unwrap();
+            assert!(err.is_instance_of::<PyValueError>(py));
+        });
+    }
+}
@@ -1,4 +1,4 @@
-use std::{net::Ipv4Addr, ops::Deref, path::PathBuf, str::FromStr};
+use std::{net::Ipv4Addr, path::PathBuf, str::FromStr};
 
 use config::{Config, ConfigError, File, FileFormat};
 use serde::Deserialize;
@@ -12,7 +12,10 @@ pub struct ServerConfig {
     pub data: Data,
     pub fetching: Fetching,
     pub cors: Option<Cors>,
-    pub static_assets: Option<StaticAssets>
+    pub static_assets: Option<StaticAssets>,
+    pub metrics: Option<Metrics>,
+    pub cache: Option<Cache>,
+    pub admin: Option<Admin>,
 }
 
 impl ServerConfig {
@@ -21,7 +24,7 @@ impl ServerConfig {
             .add_source(File::new(CONFIG_FILE_PATH, FileFormat::Toml))
             .build()?;
 
-        Ok(s.try_deserialize()?)
+        s.try_deserialize()
     }
 }
 
@@ -43,7 +46,7 @@ impl Default for ServerConfig {
             with_target: Some(true),
         };
 
-        let fetching = Fetching { 
+        let fetching = Fetching {
             programs_url: "https://iq5prod1.smartcatalogiq.com/apis/progAPI?path=/sitecore/content/Catalogs/Union-University/2023/Academic-Catalogue-Undergraduate-Catalogue&format=json".to_owned() ,
             courses_url: "https://iq5prod1.smartcatalogiq.com/APIs/courseAPI?path=/sitecore/content/Catalogs/Union-University/2023/Academic-Catalogue-Undergraduate-Catalogue&format=json".to_owned(),
         };
@@ -52,6 +55,12 @@ impl Default for ServerConfig {
 
         let static_assets = None;
 
+        let metrics = None;
+
+        let cache = None;
+
+        let admin = None;
+
         Self {
             server,
             data,
@@ -59,6 +68,9 @@ impl Default for ServerConfig {
             fetching,
             cors,
             static_assets,
+            metrics,
+            cache,
+            admin,
         }
     }
 }
@@ -114,31 +126,41 @@ pub struct Cors {
     pub origins: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct Origins(Vec<String>);
-
-impl Deref for Origins {
-    type Target = Vec<String>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
 impl Cors {
     pub fn origins_to_string(&self) -> String {
         let len = self.origins.len();
-        self.origins.iter().enumerate().fold(String::new(), |mut acc, (idx, origin)| {
-            acc.push_str(&origin);
-            if idx < len - 1 {
-                acc.push_str(", ");
-            }
-            acc
-        })
+        self.origins
+            .iter()
+            .enumerate()
+            .fold(String::new(), |mut acc, (idx, origin)| {
+                acc.push_str(origin);
+                if idx < len - 1 {
+                    acc.push_str(", ");
+                }
+                acc
+            })
     }
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct StaticAssets {
-    pub dir: PathBuf
+    pub dir: PathBuf,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Metrics {
+    /// Record parse and query telemetry and expose it at `/metrics` in the Prometheus format
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Cache {
+    /// Value of the `Cache-Control` header of API responses. Ex: "public, max-age=300"
+    pub cache_control: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Admin {
+    /// Bearer token required by the `/api/admin` routes, which are only mounted when it is set
+    pub token: String,
 }
@@ -1,14 +1,14 @@
+use std::sync::Arc;
+
 use serde_json::Value;
 
 use tokio::{fs::File, io::AsyncWriteExt};
-use tracing::debug;
-use vislog_core::{Course, CourseDetails, Program};
 
 use crate::{data::providers::programs::ProgramsProvider, CONFIGS};
 
 use self::error::Result;
 
-use super::providers::courses::CoursesProvider;
+use super::providers::{courses::CoursesProvider, CatalogSnapshot};
 
 pub mod error {
     use std::fmt::Display;
@@ -37,7 +37,9 @@ pub mod error {
 
 // TODO: Remove programs_provider dependency and refresh it's cache elsewhere
 // TODO: Do something with the Errors
-pub async fn fetch_all_programs(programs_provider: &ProgramsProvider) -> Result<Vec<Program>> {
+pub async fn fetch_all_programs(
+    programs_provider: &ProgramsProvider,
+) -> Result<Arc<CatalogSnapshot>> {
     // Fetch data from api
     let data_url = &CONFIGS.fetching.programs_url;
     let body: Value = reqwest::get(data_url).await?.json().await?;
@@ -50,16 +52,15 @@ pub async fn fetch_all_programs(programs_provider: &ProgramsProvider) -> Result<
         .await?;
     f.flush().await?;
 
-    // Refresh cache and fetch new results from cache
-    programs_provider.refresh_cache().await?;
-    let (programs, _errors) = programs_provider.get_all_programs().await?;
+    // Refresh cache and return the snapshot with the new programs
+    let (_, snapshot) = programs_provider.refresh_cache().await?;
 
-    Ok(programs)
+    Ok(snapshot)
 }
 
 // TODO: Remove programs_provider dependency and refresh it's cache elsewhere
 // TODO: Do something with the Errors
-pub async fn fetch_all_courses(courses_provider: &CoursesProvider) -> Result<Vec<CourseDetails>> {
+pub async fn fetch_all_courses(courses_provider: &CoursesProvider) -> Result<Arc<CatalogSnapshot>> {
     // Fetch data from api
     let data_url = &CONFIGS.fetching.courses_url;
     let body: Value = reqwest::get(data_url).await?.json().await?;
@@ -72,9 +73,8 @@ pub async fn fetch_all_courses(courses_provider: &CoursesProvider) -> Result<Vec
         .await?;
     f.flush().await?;
 
-    // Refresh cache and fetch new results from cache
-    courses_provider.refresh_cache().await?;
-    let (courses, _errors) = courses_provider.get_all_courses().await?;
+    // Refresh cache and return the snapshot with the new courses
+    let (_, snapshot) = courses_provider.refresh_cache().await?;
 
-    Ok(courses)
+    Ok(snapshot)
 }
@@ -1,2 +1,3 @@
 pub mod fetching;
+pub mod notifications;
 pub mod providers;
@@ -0,0 +1,261 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::debug;
+use vislog_core::{catalog::Catalog, parsing::guid::Guid};
+
+/// Events buffered for each subscriber before the slowest ones start missing events
+const CHANNEL_CAPACITY: usize = 16;
+
+/// Change to the catalog that connected clients should know about
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogEvent {
+    /// Programs that were added, removed or modified
+    ProgramUpdated(Vec<Guid>),
+    /// Courses that were added, removed or modified
+    CourseUpdated(Vec<Guid>),
+}
+
+impl CatalogEvent {
+    /// Name of the event as sent to clients. Ex: "program_updated"
+    pub fn name(&self) -> &'static str {
+        match self {
+            CatalogEvent::ProgramUpdated(_) => "program_updated",
+            CatalogEvent::CourseUpdated(_) => "course_updated",
+        }
+    }
+
+    pub fn guids(&self) -> &[Guid] {
+        match self {
+            CatalogEvent::ProgramUpdated(guids) | CatalogEvent::CourseUpdated(guids) => guids,
+        }
+    }
+}
+
+/// Broadcasts [CatalogEvent]s to every subscriber
+#[derive(Debug, Clone)]
+pub struct CatalogNotifier {
+    sender: broadcast::Sender<CatalogEvent>,
+}
+
+impl Default for CatalogNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CatalogNotifier {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<CatalogEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Broadcasts an event for every kind of item that changed between the `old` and `new`
+    /// snapshots of the catalog, returning the events that were sent
+    pub fn notify_changes(&self, old: &Catalog, new: &Catalog) -> Vec<CatalogEvent> {
+        let events = catalog_events(old, new);
+
+        for event in &events {
+            // Sending only fails when nobody is subscribed, in which case there is nobody to tell
+            let receivers = self.sender.send(event.clone()).unwrap_or(0);
+            debug!(
+                "Sent {} with {} GUIDs to {receivers} subscribers",
+                event.name(),
+                event.guids().len()
+            );
+        }
+
+        events
+    }
+}
+
+fn catalog_events(old: &Catalog, new: &Catalog) -> Vec<CatalogEvent> {
+    // Anything about an item can change, so items are compared by their content hashes
+    let programs = changed(
+        old.programs.iter().map(|p| (p.guid, p.content_hash())),
+        new.programs.iter().map(|p| (p.guid, p.content_hash())),
+    );
+    let courses = changed(
+        old.courses.iter().map(|c| (c.guid, c.content_hash())),
+        new.courses.iter().map(|c| (c.guid, c.content_hash())),
+    );
+
+    let mut events = vec![];
+    if !programs.is_empty() {
+        events.push(CatalogEvent::ProgramUpdated(programs));
+    }
+    if !courses.is_empty() {
+        events.push(CatalogEvent::CourseUpdated(courses));
+    }
+
+    events
+}
+
+/// GUIDs that were added, removed or whose content hash differs between the snapshots, in that
+/// order
+fn changed(
+    old: impl Iterator<Item = (Guid, u64)>,
+    new: impl Iterator<Item = (Guid, u64)>,
+) -> Vec<Guid> {
+    let old: Vec<(Guid, u64)> = old.collect();
+    let new: Vec<(Guid, u64)> = new.collect();
+    let old_hashes: HashMap<Guid, u64> = old.iter().copied().collect();
+    let new_hashes: HashMap<Guid, u64> = new.iter().copied().collect();
+
+    let mut guids = UniqueGuids::default();
+    guids.extend(
+        new.iter()
+            .filter(|(guid, _)| !old_hashes.contains_key(guid))
+            .map(|(guid, _)| *guid),
+    );
+    guids.extend(
+        old.iter()
+            .filter(|(guid, _)| !new_hashes.contains_key(guid))
+            .map(|(guid, _)| *guid),
+    );
+    guids.extend(
+        new.iter()
+            .filter(|(guid, hash)| {
+                old_hashes
+                    .get(guid)
+                    .is_some_and(|old_hash| old_hash != hash)
+            })
+            .map(|(guid, _)| *guid),
+    );
+
+    guids.guids
+}
+
+/// GUIDs in the order they were first seen
+#[derive(Default)]
+struct UniqueGuids {
+    guids: Vec<Guid>,
+    seen: HashSet<Guid>,
+}
+
+impl Extend<Guid> for UniqueGuids {
+    fn extend<T: IntoIterator<Item = Guid>>(&mut self, iter: T) {
+        for guid in iter {
+            if self.seen.insert(guid) {
+                self.guids.push(guid);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use vislog_core::{CourseDetails, Program};
+
+    use super::*;
+
+    fn read_json(name: &str) -> Value {
+        let json = std::fs::read_to_string(format!("../data/{name}")).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn catalog() -> Catalog {
+        let programs = ["cs_major.json", "cs_minor.json"]
+            .map(|name| serde_json::from_value::<Program>(read_json(name)).unwrap());
+        let courses = read_json("courses.json")["courses"]["course"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .take(3)
+            .map(|json| serde_json::from_value::<CourseDetails>(json.clone()).unwrap())
+            .collect();
+
+        Catalog {
+            programs: programs.into(),
+            courses,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unchanged_catalogs_have_no_events() {
+        assert_eq!(catalog_events(&catalog(), &catalog()), []);
+    }
+
+    #[test]
+    fn added_programs_and_courses_are_updated() {
+        let mut old = catalog();
+        let new = catalog();
+        old.programs.remove(0);
+        old.courses.remove(0);
+
+        assert_eq!(
+            catalog_events(&old, &new),
+            [
+                CatalogEvent::ProgramUpdated(vec![new.programs[0].guid]),
+                CatalogEvent::CourseUpdated(vec![new.courses[0].guid]),
+            ]
+        );
+    }
+
+    #[test]
+    fn removed_programs_and_courses_are_updated() {
+        let old = catalog();
+        let mut new = catalog();
+        let program = new.programs.remove(1);
+        let course = new.courses.remove(2);
+
+        assert_eq!(
+            catalog_events(&old, &new),
+            [
+                CatalogEvent::ProgramUpdated(vec![program.guid]),
+                CatalogEvent::CourseUpdated(vec![course.guid]),
+            ]
+        );
+    }
+
+    #[test]
+    fn modified_programs_and_courses_are_updated() {
+        let old = catalog();
+        let mut new = catalog();
+        new.programs[1].title.push_str(" (revised)");
+        new.courses[1].description.push_str(" Revised.");
+
+        assert_eq!(
+            catalog_events(&old, &new),
+            [
+                CatalogEvent::ProgramUpdated(vec![new.programs[1].guid]),
+                CatalogEvent::CourseUpdated(vec![new.courses[1].guid]),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_kinds_that_changed_have_events() {
+        let old = catalog();
+        let mut new = catalog();
+        new.courses[0].name.push_str(" II");
+
+        assert_eq!(
+            catalog_events(&old, &new),
+            [CatalogEvent::CourseUpdated(vec![new.courses[0].guid])]
+        );
+    }
+
+    #[test]
+    fn subscribers_receive_the_events() {
+        let notifier = CatalogNotifier::new();
+        let mut receiver = notifier.subscribe();
+        let old = catalog();
+        let mut new = catalog();
+        new.programs.clear();
+
+        let events = notifier.notify_changes(&old, &new);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(receiver.try_recv().unwrap(), events[0]);
+        assert!(receiver.try_recv().is_err());
+    }
+}
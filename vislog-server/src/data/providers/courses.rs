@@ -1,119 +1,78 @@
-use std::{collections::HashMap, fmt::Display, io::Write, sync::Arc};
+use std::{fmt::Display, sync::Arc};
 
 use thiserror::Error;
-use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
-use tracing::{debug, instrument, Level};
-use vislog_core::{parsing::guid::Guid, CourseDetails};
-use vislog_parser::{parse_courses, ParsingError};
+use tokio::sync::RwLock;
+use tracing::{debug, instrument, warn, Level};
+use vislog_core::{
+    metrics::{self, Counter},
+    requisites::check_requisites,
+    CourseDetails,
+};
+use vislog_parser::parse_courses;
 
 use super::{
     json_providers::{self, JsonProvider},
-    ProviderCache,
+    CatalogCache, CatalogSnapshot, Parsed,
 };
 
 #[derive(Clone)]
 pub struct CoursesProvider {
     json_provider: Arc<RwLock<Box<dyn JsonProvider>>>,
-    cache: Arc<RwLock<ProviderCache<Guid, CourseDetails, ParsingError>>>,
+    cache: Arc<CatalogCache>,
 }
 
 impl CoursesProvider {
-    pub fn with(json_provider: Box<dyn JsonProvider>) -> Self {
+    /// Creates a provider reading its JSON from `json_provider` and caching the parsed courses in
+    /// `cache`, which is shared with the other provider of the catalog
+    pub fn with(json_provider: Box<dyn JsonProvider>, cache: Arc<CatalogCache>) -> Self {
         let json_provider = Arc::new(RwLock::new(json_provider));
-        let cache = ProviderCache {
-            items: HashMap::new(),
-            errors: Vec::new(),
-        };
-        let cache = Arc::new(RwLock::new(cache));
         Self {
             json_provider,
             cache,
         }
     }
 
-    #[instrument(skip(self))]
-    pub async fn get_all_courses(&self) -> Result<(Vec<CourseDetails>, Vec<ParsingError>)> {
-        let cache = {
-            let read_cache_guard = self.cache.read().await;
-
-            if read_cache_guard.items.is_empty() && read_cache_guard.errors.is_empty() {
-                debug!("cache empty");
-                drop(read_cache_guard);
-                let json_provider_read_guard = self.json_provider.read().await;
-                let write_cache_guard = self.cache.write().await;
-                Self::_refresh_cache(json_provider_read_guard, write_cache_guard).await?;
-
-                // Reacquire read lock
-                self.cache.read().await
-            } else {
-                debug!("cache populated");
-                read_cache_guard
-            }
-        };
-
-        let courses: Vec<CourseDetails> = cache.items.values().cloned().collect();
-        let errors = cache.errors.to_vec();
-
-        Ok((courses, errors))
-    }
-
+    /// The current snapshot of the catalog, with the courses parsed if they weren't yet
     #[instrument(level = Level::DEBUG, skip(self))]
-    pub async fn get_course(&self, guid: &Guid) -> Result<Option<CourseDetails>> {
-        let cache = {
-            let read_cache_guard = self.cache.read().await;
-
-            if read_cache_guard.items.is_empty() && read_cache_guard.errors.is_empty() {
-                debug!("cache empty");
-
-                drop(read_cache_guard);
-                let json_provider_read_guard = self.json_provider.read().await;
-                let write_cache_guard = self.cache.write().await;
-                Self::_refresh_cache(json_provider_read_guard, write_cache_guard).await?;
-
-                // Reacquire read lock
-                self.cache.read().await
-            } else {
-                debug!("cache populated");
-                read_cache_guard
-            }
-        };
-
-        Ok(cache.items.get(guid).map(|p| p.clone()))
-    }
+    pub async fn snapshot(&self) -> Result<Arc<CatalogSnapshot>> {
+        let snapshot = self.cache.snapshot();
+        if snapshot.courses_loaded {
+            debug!("cache populated");
+            metrics::global().increment(Counter::CacheHits);
+            return Ok(snapshot);
+        }
 
-    pub async fn refresh_cache(&self) -> Result<()> {
-        let json_provider_read_guard = self.json_provider.read().await;
-        let cache_write_guard = self.cache.write().await;
+        debug!("cache empty");
+        metrics::global().increment(Counter::CacheMisses);
+        let (_, snapshot) = self.refresh_cache().await?;
 
-        Self::_refresh_cache(json_provider_read_guard, cache_write_guard).await
+        Ok(snapshot)
     }
 
-    /// SAFETY: There must not be a another read guard for `RwLockReadGuard<'a, ProviderCache>` in
-    /// the same execution "thread" to avoid deadlocks
-    async fn _refresh_cache<'a>(
-        json_provider_read_guard: RwLockReadGuard<'a, Box<dyn JsonProvider>>,
-        mut cache_write_guard: RwLockWriteGuard<
-            'a,
-            ProviderCache<Guid, CourseDetails, ParsingError>,
-        >,
-    ) -> Result<()> {
-        let course_jsons = json_provider_read_guard.get_all_course_jsons()?;
+    /// Re-parses the courses and swaps them into the cache all at once. Readers keep getting the
+    /// previous courses while parsing, and the cache is left untouched if reading the JSON fails.
+    /// Returns the snapshot that was replaced and the one that replaced it.
+    pub async fn refresh_cache(&self) -> Result<(Arc<CatalogSnapshot>, Arc<CatalogSnapshot>)> {
+        let courses = self.load_courses().await?;
 
-        std::io::stdout().lock().flush().unwrap();
-        let (courses, errors) = parse_courses(course_jsons);
+        Ok(self.cache.update(|snapshot| snapshot.with_courses(courses)))
+    }
 
-        let programs = courses
-            .into_iter()
-            .map(|course| (course.guid.clone(), course))
-            .collect::<Vec<(Guid, CourseDetails)>>();
+    /// Parses the courses without touching the cache, for [reload_catalog](super::reload_catalog)
+    pub(super) async fn load_courses(&self) -> Result<Parsed<CourseDetails>> {
+        let jsons = self.json_provider.read().await.get_all_course_jsons()?;
+        let (items, errors) = parse_courses(jsons);
 
-        cache_write_guard.items.clear();
-        cache_write_guard.errors.clear();
+        // Courses that can never be taken still load, but are worth fixing in the catalog
+        for issue in check_requisites(&items) {
+            warn!("Contradictory requisites: {issue}");
+        }
 
-        cache_write_guard.items.extend(programs);
-        cache_write_guard.errors.extend(errors);
+        Ok(Parsed { items, errors })
+    }
 
-        Ok(())
+    pub(super) fn cache(&self) -> &Arc<CatalogCache> {
+        &self.cache
     }
 }
 
@@ -5,51 +5,26 @@ use thiserror::{self, Error};
 
 pub trait JsonProvider: Send + Sync {
     fn get_all_program_jsons(&self) -> Result<Vec<Value>, Error>;
-    fn get_program_json(&self, url: &str) -> Result<Value, Error>;
     fn get_all_course_jsons(&self) -> Result<Vec<Value>, Error>;
-    fn get_course_json(&self, url: &str) -> Result<Value, Error>;
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
     /// Error happened when reading from the file specified by the `path` when
     /// initializing the (FileJsonProvider)[FileJsonProvider]
+    #[error("failed to read JSON: {0}")]
     Io(#[from] std::io::Error),
     /// Error happened when deserializing into (Value)[serde_json::Value]
+    #[error("invalid JSON: {0}")]
     DeserializeFromStr(#[from] serde_json::Error),
     /// Error happened because the format of given JSON didn't fit the expected layout
+    #[error("unexpected JSON layout: {0}")]
     Format(&'static str),
     /// File given doesn't exist
+    #[error("{0:?} doesn't exist")]
     FileNotFound(PathBuf),
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{self:?}")
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct WebJsonProvider;
-
-impl JsonProvider for WebJsonProvider {
-    fn get_all_program_jsons(&self) -> Result<Vec<Value>, Error> {
-        todo!()
-    }
-
-    fn get_program_json(&self, _url: &str) -> Result<Value, Error> {
-        todo!()
-    }
-
-    fn get_all_course_jsons(&self) -> Result<Vec<Value>, Error> {
-        todo!()
-    }
-
-    fn get_course_json(&self, _url: &str) -> Result<Value, Error> {
-        todo!()
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct FileJsonProvider {
     data_root: PathBuf,
@@ -65,7 +40,7 @@ impl FileJsonProvider {
         path.push(&all_programs_file);
 
         // Check if the file exists
-        if path.try_exists()? == false {
+        if !path.try_exists()? {
             return Err(Error::FileNotFound(path));
         }
 
@@ -94,8 +69,7 @@ impl JsonProvider for FileJsonProvider {
 
             let (_, programs_json) = json
                 .into_iter()
-                .filter(|(key, _)| key == "programs")
-                .next()
+                .find(|(key, _)| key == "programs")
                 .ok_or(Error::Format("missing field `programs`"))?;
 
             let Value::Object(program_json) = programs_json else {
@@ -106,8 +80,7 @@ impl JsonProvider for FileJsonProvider {
 
             let (_, programs_json) = program_json
                 .into_iter()
-                .filter(|(key, _)| key == "program")
-                .next()
+                .find(|(key, _)| key == "program")
                 .ok_or(Error::Format("missing field `program`"))?;
 
             let Value::Array(program_jsons) = programs_json else {
@@ -120,17 +93,6 @@ impl JsonProvider for FileJsonProvider {
         Ok(program_jsons)
     }
 
-    fn get_program_json(&self, url: &str) -> Result<Value, Error> {
-        let mut path = self.data_root.clone();
-        path.push(url);
-
-        let json_str = std::fs::read_to_string(path)?;
-
-        let program_json: Value = serde_json::from_str(&json_str)?;
-
-        Ok(program_json)
-    }
-
     fn get_all_course_jsons(&self) -> Result<Vec<Value>, Error> {
         let mut path = self.data_root.clone();
         path.push(&self.all_jsons_file);
@@ -148,8 +110,7 @@ impl JsonProvider for FileJsonProvider {
 
             let (_, courses_json) = json
                 .into_iter()
-                .filter(|(key, _)| key == "courses")
-                .next()
+                .find(|(key, _)| key == "courses")
                 .ok_or(Error::Format("missing field `courses`"))?;
 
             let Value::Object(course_json) = courses_json else {
@@ -160,8 +121,7 @@ impl JsonProvider for FileJsonProvider {
 
             let (_, courses_json) = course_json
                 .into_iter()
-                .filter(|(key, _)| key == "course")
-                .next()
+                .find(|(key, _)| key == "course")
                 .ok_or(Error::Format("missing field `course`"))?;
 
             let Value::Array(course_jsons) = courses_json else {
@@ -173,8 +133,23 @@ impl JsonProvider for FileJsonProvider {
 
         Ok(course_jsons)
     }
+}
+
+/// Serves JSON kept in memory, for tests
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct MemoryJsonProvider {
+    pub programs: Vec<Value>,
+    pub courses: Vec<Value>,
+}
 
-    fn get_course_json(&self, _url: &str) -> Result<Value, Error> {
-        todo!()
+#[cfg(test)]
+impl JsonProvider for MemoryJsonProvider {
+    fn get_all_program_jsons(&self) -> Result<Vec<Value>, Error> {
+        Ok(self.programs.clone())
+    }
+
+    fn get_all_course_jsons(&self) -> Result<Vec<Value>, Error> {
+        Ok(self.courses.clone())
     }
 }
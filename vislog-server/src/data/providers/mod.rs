@@ -1,14 +1,278 @@
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use arc_swap::ArcSwap;
+use thiserror::Error;
+use vislog_core::{
+    catalog::Catalog, graph::PrerequisiteGraph, parsing::guid::Guid, CourseDetails, Program,
+};
+use vislog_parser::ParsingError;
+
+use self::{courses::CoursesProvider, programs::ProgramsProvider};
 
 pub mod courses;
 pub mod json_providers;
 pub mod programs;
 
-struct ProviderCache<K, T, E>
-where
-    K: Hash,
-    E: std::error::Error,
-{
-    items: HashMap<K, T>,
-    errors: Vec<E>,
+/// Everything parsed from storage at one point in time. Snapshots are never modified, a new one is
+/// swapped into the [CatalogCache] instead.
+#[derive(Debug, Default)]
+pub struct CatalogSnapshot {
+    /// Programs sorted by title and courses in the order of the data file
+    catalog: Catalog,
+    program_errors: Vec<ParsingError>,
+    course_errors: Vec<ParsingError>,
+    programs_by_guid: HashMap<Guid, usize>,
+    courses_by_guid: HashMap<Guid, usize>,
+    /// Built once per load of the courses rather than for every request that needs it
+    prerequisites: Arc<PrerequisiteGraph>,
+    programs_loaded: bool,
+    courses_loaded: bool,
+}
+
+impl CatalogSnapshot {
+    pub fn catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+
+    pub fn programs(&self) -> &[Program] {
+        &self.catalog.programs
+    }
+
+    pub fn courses(&self) -> &[CourseDetails] {
+        &self.catalog.courses
+    }
+
+    pub fn program(&self, guid: &Guid) -> Option<&Program> {
+        self.program_index(guid)
+            .map(|index| &self.catalog.programs[index])
+    }
+
+    pub fn course(&self, guid: &Guid) -> Option<&CourseDetails> {
+        self.course_index(guid)
+            .map(|index| &self.catalog.courses[index])
+    }
+
+    /// Position of the program with `guid` in [Self::programs]
+    pub fn program_index(&self, guid: &Guid) -> Option<usize> {
+        self.programs_by_guid.get(guid).copied()
+    }
+
+    /// Position of the course with `guid` in [Self::courses]
+    pub fn course_index(&self, guid: &Guid) -> Option<usize> {
+        self.courses_by_guid.get(guid).copied()
+    }
+
+    /// Prerequisites between all of the courses
+    pub fn prerequisites(&self) -> &PrerequisiteGraph {
+        &self.prerequisites
+    }
+
+    pub fn program_errors(&self) -> &[ParsingError] {
+        &self.program_errors
+    }
+
+    pub fn course_errors(&self) -> &[ParsingError] {
+        &self.course_errors
+    }
+
+    /// Same snapshot with its programs replaced
+    fn with_programs(&self, programs: Parsed<Program>) -> Self {
+        let mut snapshot = Self::default();
+        snapshot.set_programs(programs);
+        snapshot.catalog.courses = self.catalog.courses.clone();
+        snapshot.course_errors = self.course_errors.clone();
+        snapshot.courses_by_guid = self.courses_by_guid.clone();
+        snapshot.prerequisites = Arc::clone(&self.prerequisites);
+        snapshot.courses_loaded = self.courses_loaded;
+
+        snapshot
+    }
+
+    /// Same snapshot with its courses replaced
+    fn with_courses(&self, courses: Parsed<CourseDetails>) -> Self {
+        let mut snapshot = Self::default();
+        snapshot.set_programs(Parsed {
+            items: self.catalog.programs.clone(),
+            errors: self.program_errors.clone(),
+        });
+        snapshot.programs_loaded = self.programs_loaded;
+        snapshot.set_courses(courses);
+
+        snapshot
+    }
+
+    fn set_programs(&mut self, Parsed { mut items, errors }: Parsed<Program>) {
+        items.sort();
+        self.programs_by_guid = index_by_guid(items.iter().map(|program| program.guid));
+        self.catalog.programs = items;
+        self.program_errors = errors;
+        self.programs_loaded = true;
+    }
+
+    fn set_courses(&mut self, Parsed { items, errors }: Parsed<CourseDetails>) {
+        self.courses_by_guid = index_by_guid(items.iter().map(|course| course.guid));
+        self.prerequisites = Arc::new(PrerequisiteGraph::from_course_details(&items));
+        self.catalog.courses = items;
+        self.course_errors = errors;
+        self.courses_loaded = true;
+    }
+}
+
+/// Later items win over earlier ones with the same GUID, like inserting them into a map would
+fn index_by_guid(guids: impl Iterator<Item = Guid>) -> HashMap<Guid, usize> {
+    guids
+        .enumerate()
+        .map(|(index, guid)| (guid, index))
+        .collect()
+}
+
+/// Items parsed from a data file along with the errors of the ones that failed to parse
+struct Parsed<T> {
+    items: Vec<T>,
+    errors: Vec<ParsingError>,
+}
+
+/// The current [CatalogSnapshot], shared by a [ProgramsProvider] and a [CoursesProvider]. Readers
+/// never wait, while writers take turns so that no update is lost.
+#[derive(Debug, Default)]
+pub struct CatalogCache {
+    current: ArcSwap<CatalogSnapshot>,
+    writer: Mutex<()>,
+}
+
+impl CatalogCache {
+    pub fn shared() -> Arc<CatalogCache> {
+        Arc::default()
+    }
+
+    pub fn snapshot(&self) -> Arc<CatalogSnapshot> {
+        self.current.load_full()
+    }
+
+    /// Swaps in the snapshot made by `update` out of the current one, returning both
+    fn update(
+        &self,
+        update: impl FnOnce(&CatalogSnapshot) -> CatalogSnapshot,
+    ) -> (Arc<CatalogSnapshot>, Arc<CatalogSnapshot>) {
+        let _writer = self.writer.lock().unwrap_or_else(|err| err.into_inner());
+
+        let old = self.current.load_full();
+        let new = Arc::new(update(&old));
+        self.current.store(Arc::clone(&new));
+
+        (old, new)
+    }
+}
+
+/// Re-parses both the programs and the courses, then swaps them into the shared [CatalogCache]
+/// at once. Readers keep getting the previous snapshot while parsing and never see new programs
+/// with old courses. The cache is left untouched if reading either JSON fails.
+///
+/// Returns the snapshot that was replaced and the one that replaced it.
+pub async fn reload_catalog(
+    programs_provider: &ProgramsProvider,
+    courses_provider: &CoursesProvider,
+) -> Result<(Arc<CatalogSnapshot>, Arc<CatalogSnapshot>), ReloadError> {
+    debug_assert!(
+        Arc::ptr_eq(programs_provider.cache(), courses_provider.cache()),
+        "providers should share the same `CatalogCache`"
+    );
+
+    let programs = programs_provider.load_programs().await?;
+    let courses = courses_provider.load_courses().await?;
+
+    Ok(programs_provider.cache().update(|_| {
+        let mut snapshot = CatalogSnapshot::default();
+        snapshot.set_programs(programs);
+        snapshot.set_courses(courses);
+        snapshot
+    }))
+}
+
+#[derive(Debug, Error)]
+pub enum ReloadError {
+    Programs(#[from] programs::Error),
+    Courses(#[from] courses::Error),
+}
+
+impl std::fmt::Display for ReloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use super::{json_providers::MemoryJsonProvider, *};
+
+    fn read_json(name: &str) -> Value {
+        let json = std::fs::read_to_string(format!("../data/{name}")).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn providers(json_provider: MemoryJsonProvider) -> (ProgramsProvider, CoursesProvider) {
+        let cache = CatalogCache::shared();
+        (
+            ProgramsProvider::with(Box::new(json_provider.clone()), cache.clone()),
+            CoursesProvider::with(Box::new(json_provider), cache),
+        )
+    }
+
+    #[tokio::test]
+    async fn reload_returns_the_snapshots_it_swapped() {
+        let (programs_provider, courses_provider) = providers(MemoryJsonProvider {
+            programs: vec![read_json("cs_major.json")],
+            courses: vec![],
+        });
+
+        let (empty, first) = reload_catalog(&programs_provider, &courses_provider)
+            .await
+            .unwrap();
+        assert!(empty.programs().is_empty());
+        assert_eq!(first.programs().len(), 1);
+
+        let (old, new) = reload_catalog(&programs_provider, &courses_provider)
+            .await
+            .unwrap();
+        assert!(Arc::ptr_eq(&old, &first));
+        assert!(Arc::ptr_eq(
+            &new,
+            &programs_provider.snapshot().await.unwrap()
+        ));
+    }
+
+    #[tokio::test]
+    async fn snapshots_find_items_by_guid() {
+        let cs_major = read_json("cs_major.json");
+        let cs_minor = read_json("cs_minor.json");
+        let (programs_provider, courses_provider) = providers(MemoryJsonProvider {
+            programs: vec![cs_minor, cs_major],
+            courses: vec![],
+        });
+
+        let snapshot = courses_provider.snapshot().await.unwrap();
+        assert!(snapshot.programs().is_empty(), "only courses are loaded");
+
+        let snapshot = programs_provider.snapshot().await.unwrap();
+        let titles: Vec<&str> = snapshot
+            .programs()
+            .iter()
+            .map(|p| p.title.as_str())
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                "Major in Computer Science—42 hours",
+                "Minor in Computer Science—21 or 22 hours"
+            ]
+        );
+        for program in snapshot.programs() {
+            assert_eq!(snapshot.program(&program.guid), Some(program));
+        }
+    }
 }
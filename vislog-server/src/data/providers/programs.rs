@@ -1,14 +1,17 @@
-use std::{collections::HashMap, fmt::Display, sync::Arc};
+use std::{fmt::Display, sync::Arc};
 
 use thiserror::Error;
-use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
-use tracing::{field::debug, instrument, Level};
-use vislog_core::{parsing::guid::Guid, Program};
-use vislog_parser::{parse_programs, ParsingError};
+use tokio::sync::RwLock;
+use tracing::{debug, instrument, Level};
+use vislog_core::{
+    metrics::{self, Counter},
+    Program,
+};
+use vislog_parser::parse_programs;
 
 use super::{
     json_providers::{self, JsonProvider},
-    ProviderCache,
+    CatalogCache, CatalogSnapshot, Parsed,
 };
 
 /// Provides program struct parsing
@@ -21,7 +24,7 @@ use super::{
 /// # use vislog_parser::{parse_programs, ProgramParsingError};
 /// # use self::json_providers::JsonProviderError;
 /// let json_provider = FileJsonProvider::init("../data".into(), "programs.json".into());
-/// let program_provider = ProgramsProvider::with(Box::new(json_provider.clone()));
+/// let program_provider = ProgramsProvider::with(Box::new(json_provider.clone()), CatalogCache::shared());
 /// ```
 ///
 /// ## Get all programs
@@ -30,10 +33,10 @@ use super::{
 /// # use vislog_parser::{parse_programs, ProgramParsingError};
 /// # use self::json_providers::JsonProviderError;
 /// # let json_provider = FileJsonProvider::init("../data".into(), "programs.json".into());
-/// # let program_provider = ProgramsProvider::with(Box::new(json_provider.clone()));
+/// # let program_provider = ProgramsProvider::with(Box::new(json_provider.clone()), CatalogCache::shared());
 ///
-/// let (programs, errors) = program_provider.get_all_programs()?;
-/// dbg!((programs.len(), errors.len()));
+/// let snapshot = program_provider.snapshot().await?;
+/// dbg!((snapshot.programs().len(), snapshot.program_errors().len()));
 /// ```
 ///
 /// ## Get one program
@@ -43,110 +46,66 @@ use super::{
 /// # use vislog_parser::{parse_programs, ProgramParsingError};
 /// # use self::json_providers::JsonProviderError;
 /// # let json_provider = FileJsonProvider::init("../data".into(), "programs.json".into());
-/// # let program_provider = ProgramsProvider::with(Box::new(json_provider.clone()));
-/// let cs_major_json = json_provider.get_program_json("cs_major.json")?;
-/// let cs_major: Program = serde_json::from_str(&(serde_json::to_string(&cs_major_json)?))?;
-/// dbg!(cs_major.title);
+/// # let program_provider = ProgramsProvider::with(Box::new(json_provider.clone()), CatalogCache::shared());
+/// let guid = GUID::try_from("{5B72AC3A-9A84-4CF5-B1BE-B3E0B48163A5}")?;
+/// let snapshot = program_provider.snapshot().await?;
+/// dbg!(snapshot.program(&guid).map(|cs_major| &cs_major.title));
 /// ```
 #[derive(Clone)]
 pub struct ProgramsProvider {
     json_provider: Arc<RwLock<Box<dyn JsonProvider>>>,
-    cache: Arc<RwLock<ProviderCache<Guid, Program, ParsingError>>>,
+    cache: Arc<CatalogCache>,
 }
 
 impl ProgramsProvider {
-    pub fn with(json_provider: Box<dyn JsonProvider>) -> Self {
+    /// Creates a provider reading its JSON from `json_provider` and caching the parsed programs in
+    /// `cache`, which is shared with the other provider of the catalog
+    pub fn with(json_provider: Box<dyn JsonProvider>, cache: Arc<CatalogCache>) -> Self {
         let json_provider = Arc::new(RwLock::new(json_provider));
-        let cache = ProviderCache {
-            items: HashMap::new(),
-            errors: Vec::new(),
-        };
-        let cache = Arc::new(RwLock::new(cache));
         Self {
             json_provider,
             cache,
         }
     }
 
-    #[instrument(skip(self))]
-    pub async fn get_all_programs(&self) -> Result<(Vec<Program>, Vec<ParsingError>)> {
-        let cache = {
-            let read_cache_guard = self.cache.read().await;
-
-            if read_cache_guard.items.is_empty() && read_cache_guard.errors.is_empty() {
-                debug("cache empty");
-                drop(read_cache_guard);
-                let json_provider_read_guard = self.json_provider.read().await;
-                let write_cache_guard = self.cache.write().await;
-                Self::_refresh_cache(json_provider_read_guard, write_cache_guard).await?;
-
-                // Reacquire read lock
-                self.cache.read().await
-            } else {
-                debug("cache populated");
-                read_cache_guard
-            }
-        };
-
-        let mut programs: Vec<Program> = cache.items.values().cloned().collect();
-        programs.sort();
-        let errors = cache.errors.to_vec();
-
-        Ok((programs, errors))
-    }
-
+    /// The current snapshot of the catalog, with the programs parsed if they weren't yet
     #[instrument(level = Level::DEBUG, skip(self))]
-    pub async fn get_program(&self, guid: &Guid) -> Result<Option<Program>> {
-        let cache = {
-            let read_cache_guard = self.cache.read().await;
-
-            if read_cache_guard.items.is_empty() && read_cache_guard.errors.is_empty() {
-                debug("cache empty");
-
-                drop(read_cache_guard);
-                let json_provider_read_guard = self.json_provider.read().await;
-                let write_cache_guard = self.cache.write().await;
-                Self::_refresh_cache(json_provider_read_guard, write_cache_guard).await?;
+    pub async fn snapshot(&self) -> Result<Arc<CatalogSnapshot>> {
+        let snapshot = self.cache.snapshot();
+        if snapshot.programs_loaded {
+            debug!("cache populated");
+            metrics::global().increment(Counter::CacheHits);
+            return Ok(snapshot);
+        }
 
-                // Reacquire read lock
-                self.cache.read().await
-            } else {
-                debug("cache populated");
-                read_cache_guard
-            }
-        };
+        debug!("cache empty");
+        metrics::global().increment(Counter::CacheMisses);
+        let (_, snapshot) = self.refresh_cache().await?;
 
-        Ok(cache.items.get(guid).map(|p| p.clone()))
+        Ok(snapshot)
     }
 
-    pub async fn refresh_cache(&self) -> Result<()> {
-        let json_provider_read_guard = self.json_provider.read().await;
-        let cache_write_guard = self.cache.write().await;
+    /// Re-parses the programs and swaps them into the cache all at once. Readers keep getting the
+    /// previous programs while parsing, and the cache is left untouched if reading the JSON fails.
+    /// Returns the snapshot that was replaced and the one that replaced it.
+    pub async fn refresh_cache(&self) -> Result<(Arc<CatalogSnapshot>, Arc<CatalogSnapshot>)> {
+        let programs = self.load_programs().await?;
 
-        Self::_refresh_cache(json_provider_read_guard, cache_write_guard).await
+        Ok(self
+            .cache
+            .update(|snapshot| snapshot.with_programs(programs)))
     }
 
-    /// SAFETY: There must not be a another read guard for `RwLockReadGuard<'a, ProviderCache>` in
-    /// the same execution "thread" to avoid deadlocks
-    async fn _refresh_cache<'a>(
-        json_provider_read_guard: RwLockReadGuard<'a, Box<dyn JsonProvider>>,
-        mut cache_write_guard: RwLockWriteGuard<'a, ProviderCache<Guid, Program, ParsingError>>,
-    ) -> Result<()> {
-        let program_jsons = json_provider_read_guard.get_all_program_jsons()?;
-        let (programs, errors) = parse_programs(program_jsons);
+    /// Parses the programs without touching the cache, for [reload_catalog](super::reload_catalog)
+    pub(super) async fn load_programs(&self) -> Result<Parsed<Program>> {
+        let jsons = self.json_provider.read().await.get_all_program_jsons()?;
+        let (items, errors) = parse_programs(jsons);
 
-        let programs = programs
-            .into_iter()
-            .map(|p| (p.guid.clone(), p))
-            .collect::<Vec<(Guid, Program)>>();
-
-        cache_write_guard.items.clear();
-        cache_write_guard.errors.clear();
-
-        cache_write_guard.items.extend(programs);
-        cache_write_guard.errors.extend(errors);
+        Ok(Parsed { items, errors })
+    }
 
-        Ok(())
+    pub(super) fn cache(&self) -> &Arc<CatalogCache> {
+        &self.cache
     }
 }
 
@@ -15,16 +15,19 @@ use crate::configs::ServerConfig;
 use crate::data::providers::courses::CoursesProvider;
 use crate::data::providers::json_providers;
 use crate::data::providers::programs::ProgramsProvider;
+use crate::data::providers::{reload_catalog, CatalogCache};
 
 mod configs;
 mod data;
 mod web;
 
 lazy_static! {
-    pub static ref CONFIGS: ServerConfig = ServerConfig::new().expect(&format!(
-        "Failed to load config file '{}'",
-        configs::CONFIG_FILE_PATH
-    ));
+    pub static ref CONFIGS: ServerConfig = ServerConfig::new().unwrap_or_else(|err| {
+        panic!(
+            "Failed to load config file '{}': {err}",
+            configs::CONFIG_FILE_PATH
+        )
+    });
 }
 
 #[tokio::main]
@@ -50,8 +53,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with(fmt_layer)
         .init();
 
+    if CONFIGS.metrics.as_ref().is_some_and(|m| m.enabled) {
+        info!("Metrics enabled at /metrics");
+        vislog_core::metrics::global().enable();
+    }
+
     let (programs_provider, courses_provider) = init_programs_and_courses_providers().await?;
 
+    // Load the catalog into memory before serving the first request
+    let (_, snapshot) = reload_catalog(&programs_provider, &courses_provider).await?;
+    info!(
+        "Loaded {} programs ({} errors) and {} courses ({} errors)",
+        snapshot.programs().len(),
+        snapshot.program_errors().len(),
+        snapshot.courses().len(),
+        snapshot.course_errors().len()
+    );
+
     let addr = format!("{}:{}", CONFIGS.server.host, CONFIGS.server.port);
     let listener = TcpListener::bind(&addr).await?;
     let server = init_server(
@@ -63,7 +81,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Listening at {addr}");
 
     if let Some(cors) = &CONFIGS.cors {
-        if cors.origins.len() >= 1 {
+        if !cors.origins.is_empty() {
             info!(
                 "Allowing requests from origins: \"{}\"",
                 cors.origins_to_string()
@@ -83,6 +101,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 async fn init_programs_and_courses_providers(
 ) -> Result<(ProgramsProvider, CoursesProvider), Box<dyn std::error::Error>> {
     // TODO: Figure out why logs in this code block doesn't work
+    let cache = CatalogCache::shared();
+
     let programs_provider = {
         let (json_provider, need_refetch) = {
             match FileJsonProvider::init(&CONFIGS.data.storage, &CONFIGS.data.all_programs_file) {
@@ -93,7 +113,7 @@ async fn init_programs_and_courses_providers(
 
                     tokio::fs::File::create(&path)
                         .await
-                        .expect(&format!("Should be able to create file at {path:?}"));
+                        .unwrap_or_else(|_| panic!("Should be able to create file at {path:?}"));
 
                     // Try to initialize file provider again. Hard fail if creating data file doesn't
                     // fix the issue
@@ -112,7 +132,7 @@ async fn init_programs_and_courses_providers(
             }
         };
 
-        let programs_provider = ProgramsProvider::with(Box::new(json_provider));
+        let programs_provider = ProgramsProvider::with(Box::new(json_provider), cache.clone());
 
         if need_refetch {
             info!("Fetching data from {}", CONFIGS.fetching.programs_url);
@@ -134,7 +154,7 @@ async fn init_programs_and_courses_providers(
 
                     tokio::fs::File::create(&path)
                         .await
-                        .expect(&format!("Should be able to create file at {path:?}"));
+                        .unwrap_or_else(|_| panic!("Should be able to create file at {path:?}"));
 
                     // Try to initialize file provider again. Hard fail if creating data file doesn't
                     // fix the issue
@@ -153,7 +173,7 @@ async fn init_programs_and_courses_providers(
             }
         };
 
-        let courses_provider = CoursesProvider::with(Box::new(json_provider));
+        let courses_provider = CoursesProvider::with(Box::new(json_provider), cache);
 
         if need_refetch {
             info!("Fetching data from {}", CONFIGS.fetching.programs_url);
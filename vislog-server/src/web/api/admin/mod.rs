@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, Response, StatusCode},
+    middleware::{from_fn_with_state, Next},
+    response::IntoResponse,
+    routing::post,
+    Json, Router,
+};
+use serde::Serialize;
+use tracing::{info, instrument, warn};
+
+use crate::data::{
+    notifications::CatalogNotifier,
+    providers::{courses::CoursesProvider, programs::ProgramsProvider, reload_catalog},
+};
+use crate::web::error::Result;
+
+#[derive(Clone)]
+struct AdminState {
+    programs_provider: ProgramsProvider,
+    courses_provider: CoursesProvider,
+    notifier: CatalogNotifier,
+}
+
+/// Every route requires the `Authorization: Bearer <token>` header with the configured `token`
+pub fn routes(
+    programs_provider: ProgramsProvider,
+    courses_provider: CoursesProvider,
+    notifier: CatalogNotifier,
+    token: String,
+) -> Router {
+    Router::new()
+        .route("/reload", post(reload_handler))
+        .route_layer(from_fn_with_state(
+            Arc::<str>::from(token),
+            mw_require_token,
+        ))
+        .with_state(AdminState {
+            programs_provider,
+            courses_provider,
+            notifier,
+        })
+}
+
+async fn mw_require_token(
+    State(token): State<Arc<str>>,
+    req: Request,
+    next: Next,
+) -> Response<Body> {
+    let authorized = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()));
+
+    if authorized {
+        next.run(req).await
+    } else {
+        warn!("Rejected unauthorized request to {}", req.uri());
+        StatusCode::UNAUTHORIZED.into_response()
+    }
+}
+
+/// Compares without returning early so the response time doesn't leak how much of the token matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Debug, Serialize)]
+struct ReloadResponse {
+    programs: usize,
+    program_errors: usize,
+    courses: usize,
+    course_errors: usize,
+}
+
+/// Re-parses the data files in storage without restarting the server. Requests keep being served
+/// from the previous data until both the programs and the courses are parsed, then both are swapped
+/// in at once. Subscribers to `/api/events` are
+/// notified of the programs and courses that changed.
+#[instrument(skip(state), err)]
+async fn reload_handler(State(state): State<AdminState>) -> Result<Json<ReloadResponse>> {
+    info!("Reloading programs and courses from storage");
+
+    let (old, new) = reload_catalog(&state.programs_provider, &state.courses_provider).await?;
+
+    let response = ReloadResponse {
+        programs: new.programs().len(),
+        program_errors: new.program_errors().len(),
+        courses: new.courses().len(),
+        course_errors: new.course_errors().len(),
+    };
+    info!("Reloaded {response:?}");
+
+    state.notifier.notify_changes(old.catalog(), new.catalog());
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod test {
+    use axum::http::Method;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::data::providers::{json_providers::MemoryJsonProvider, CatalogCache};
+
+    fn router() -> Router {
+        let json_provider = MemoryJsonProvider::default();
+        let cache = CatalogCache::shared();
+
+        routes(
+            ProgramsProvider::with(Box::new(json_provider.clone()), cache.clone()),
+            CoursesProvider::with(Box::new(json_provider), cache),
+            CatalogNotifier::new(),
+            "secret".to_string(),
+        )
+    }
+
+    async fn reload_status(authorization: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().method(Method::POST).uri("/reload");
+        if let Some(authorization) = authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+
+        router()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn requests_with_the_token_are_authorized() {
+        assert_eq!(reload_status(Some("Bearer secret")).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn requests_without_the_token_are_rejected() {
+        assert_eq!(reload_status(None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            reload_status(Some("Bearer secreT")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            reload_status(Some("Bearer secret2")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            reload_status(Some("Basic secret")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            reload_status(Some("secret")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[test]
+    fn constant_time_eq_compares_every_byte() {
+        assert!(constant_time_eq(b"", b""));
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"Secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secret "));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+}
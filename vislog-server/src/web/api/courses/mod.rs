@@ -1,14 +1,20 @@
 use axum::{
+    body::Body,
     extract::{Path, State},
+    http::{HeaderMap, Response},
+    response::IntoResponse,
     routing::get,
     Json, Router,
 };
 
 use tracing::{debug, info, instrument};
-use vislog_core::{parsing::guid::Guid, CourseDetails};
+use vislog_core::parsing::guid::Guid;
 
 use crate::data::{fetching, providers::courses::CoursesProvider};
-use crate::web::error::{Error, Result};
+use crate::web::{
+    error::{Error, Result},
+    etag::json_with_etag,
+};
 
 pub fn routes(courses_provider: CoursesProvider) -> Router {
     Router::new()
@@ -18,42 +24,49 @@ pub fn routes(courses_provider: CoursesProvider) -> Router {
         .with_state(courses_provider)
 }
 
-#[instrument(skip(courses_provider))]
+#[instrument(skip(headers, courses_provider))]
 async fn get_all_courses_handler(
+    headers: HeaderMap,
     State(courses_provider): State<CoursesProvider>,
-) -> Result<Json<Vec<CourseDetails>>> {
+) -> Result<Response<Body>> {
     info!("Getting all courses");
 
-    let (courses, errors) = courses_provider.get_all_courses().await?;
+    let snapshot = courses_provider.snapshot().await?;
 
-    debug!("courses: {}, errors: {}", courses.len(), errors.len());
+    debug!(
+        "courses: {}, errors: {}",
+        snapshot.courses().len(),
+        snapshot.course_errors().len()
+    );
 
-    Ok(Json(courses))
+    Ok(json_with_etag(&headers, snapshot.courses()))
 }
 
-#[instrument(skip(courses_provider))]
+#[instrument(skip(headers, courses_provider))]
 async fn get_course_handler(
+    headers: HeaderMap,
     Path(guid): Path<Guid>,
     State(courses_provider): State<CoursesProvider>,
-) -> Result<Json<CourseDetails>> {
+) -> Result<Response<Body>> {
     info!("Getting course with guid: {}", guid);
 
-    let course = courses_provider
-        .get_course(&guid)
-        .await?
-        .ok_or(Error::CourseNotFound(guid))?;
+    let snapshot = courses_provider.snapshot().await?;
+    let course = snapshot.course(&guid).ok_or(Error::CourseNotFound(guid))?;
 
-    Ok(Json(course))
+    Ok(json_with_etag(&headers, course))
 }
 
 #[instrument(skip(courses_provider))]
 async fn refresh_courses_handler(
     State(courses_provider): State<CoursesProvider>,
-) -> Result<Json<Vec<CourseDetails>>> {
+) -> Result<Response<Body>> {
     info!("Refreshing all courses");
-    let courses = fetching::fetch_all_courses(&courses_provider).await?;
+    let snapshot = fetching::fetch_all_courses(&courses_provider).await?;
 
-    debug!("Number of courses after refresh: {}", courses.len());
+    debug!(
+        "Number of courses after refresh: {}",
+        snapshot.courses().len()
+    );
 
-    Ok(Json(courses))
+    Ok(Json(snapshot.courses()).into_response())
 }
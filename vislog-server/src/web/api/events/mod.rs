@@ -0,0 +1,52 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::State,
+    response::{
+        sse::{Event, KeepAlive},
+        Sse,
+    },
+    routing::get,
+    Router,
+};
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    Stream, StreamExt,
+};
+use tracing::{info, instrument, warn};
+
+use crate::data::notifications::CatalogNotifier;
+
+pub fn routes(notifier: CatalogNotifier) -> Router {
+    Router::new()
+        .route("/", get(events_handler))
+        .with_state(notifier)
+}
+
+/// Streams catalog change notifications as Server-Sent Events. Each event is named after the kind
+/// of change (`program_updated` or `course_updated`) and its data is the JSON list of GUIDs that
+/// changed. Clients that fall too far behind are sent a `resync` event instead of the events they
+/// missed and should refetch everything.
+#[instrument(skip(notifier))]
+async fn events_handler(
+    State(notifier): State<CatalogNotifier>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    info!("Client subscribed to catalog events");
+
+    let stream = BroadcastStream::new(notifier.subscribe()).map(|event| {
+        let event = match event {
+            Ok(event) => Event::default()
+                .event(event.name())
+                .json_data(event.guids())
+                .expect("GUIDs should serialize to JSON"),
+            Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                warn!("Subscriber missed {missed} catalog events");
+                Event::default().event("resync").data(missed.to_string())
+            }
+        };
+
+        Ok(event)
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
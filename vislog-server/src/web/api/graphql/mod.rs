@@ -0,0 +1,26 @@
+use axum::{extract::State, routing::post, Json, Router};
+use tracing::{info, instrument};
+
+use crate::data::providers::{courses::CoursesProvider, programs::ProgramsProvider};
+
+use self::schema::VislogSchema;
+
+mod schema;
+
+pub fn routes(programs_provider: ProgramsProvider, courses_provider: CoursesProvider) -> Router {
+    Router::new()
+        .route("/", post(graphql_handler))
+        .with_state(schema::build(programs_provider, courses_provider))
+}
+
+/// Runs a GraphQL query so that clients can fetch only the parts of the requirement tree they
+/// need. Errors in the query are reported in the `errors` field of the response.
+#[instrument(skip(schema, request))]
+async fn graphql_handler(
+    State(schema): State<VislogSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    info!("Running GraphQL query");
+
+    Json(schema.execute(request).await)
+}
@@ -0,0 +1,616 @@
+//! GraphQL view of the programs and courses. Every type borrows its `vislog_core` counterpart from
+//! the current [CatalogSnapshot] and courses resolve into their [CourseDetails] on demand, so a
+//! query only pays for the parts of the requirement tree it asks for.
+
+use std::sync::Arc;
+
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Enum, Object, Result, Schema, ID};
+use vislog_core::{
+    electives::ElectiveHours, parsing::guid::Guid, Course, CourseDetails, CourseEntry, Label,
+    Program, Requirement, RequirementModule,
+};
+
+use crate::data::providers::{
+    courses::CoursesProvider, programs::ProgramsProvider, CatalogSnapshot,
+};
+
+pub type VislogSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Deepest nesting of fields a query may select. Prerequisites link courses to each other, so
+/// without a limit a single query could walk arbitrarily long prerequisite chains.
+const MAX_DEPTH: usize = 16;
+
+/// Most fields a query may select in total
+const MAX_COMPLEXITY: usize = 1000;
+
+pub fn build(
+    programs_provider: ProgramsProvider,
+    courses_provider: CoursesProvider,
+) -> VislogSchema {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(programs_provider)
+        .data(courses_provider)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+fn parse_guid(guid: &ID) -> Result<Guid> {
+    Ok(Guid::try_from(guid.as_str())?)
+}
+
+async fn course_details(ctx: &Context<'_>, guid: &Guid) -> Result<Option<CourseDetailsObject>> {
+    let snapshot = ctx.data::<CoursesProvider>()?.snapshot().await?;
+
+    Ok(CourseDetailsObject::find(&snapshot, guid))
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// Every program, optionally only the ones whose title contains `title_contains` (case
+    /// insensitive)
+    async fn programs(
+        &self,
+        ctx: &Context<'_>,
+        title_contains: Option<String>,
+    ) -> Result<Vec<ProgramObject>> {
+        let snapshot = ctx.data::<ProgramsProvider>()?.snapshot().await?;
+        let needle = title_contains.map(|needle| needle.to_lowercase());
+
+        // Snapshots keep their programs sorted by title already
+        Ok(snapshot
+            .programs()
+            .iter()
+            .enumerate()
+            .filter(|(_, program)| {
+                needle
+                    .as_ref()
+                    .is_none_or(|needle| program.title.to_lowercase().contains(needle))
+            })
+            .map(|(index, _)| ProgramObject {
+                snapshot: Arc::clone(&snapshot),
+                index,
+            })
+            .collect())
+    }
+
+    async fn program(&self, ctx: &Context<'_>, guid: ID) -> Result<Option<ProgramObject>> {
+        let guid = parse_guid(&guid)?;
+        let snapshot = ctx.data::<ProgramsProvider>()?.snapshot().await?;
+
+        Ok(snapshot
+            .program_index(&guid)
+            .map(|index| ProgramObject { snapshot, index }))
+    }
+
+    async fn course(&self, ctx: &Context<'_>, guid: ID) -> Result<Option<CourseDetailsObject>> {
+        course_details(ctx, &parse_guid(&guid)?).await
+    }
+}
+
+/// A program of a [CatalogSnapshot], kept alive by the snapshot instead of cloned out of it
+pub struct ProgramObject {
+    snapshot: Arc<CatalogSnapshot>,
+    index: usize,
+}
+
+impl ProgramObject {
+    fn program(&self) -> &Program {
+        &self.snapshot.programs()[self.index]
+    }
+}
+
+#[Object(name = "Program")]
+impl ProgramObject {
+    async fn guid(&self) -> ID {
+        ID(self.program().guid.to_string())
+    }
+
+    async fn url(&self) -> &str {
+        &self.program().url
+    }
+
+    async fn title(&self) -> &str {
+        &self.program().title
+    }
+
+    async fn content(&self) -> Option<&str> {
+        self.program().content.as_deref()
+    }
+
+    async fn bottom_content(&self) -> Option<&str> {
+        self.program().bottom_content.as_deref()
+    }
+
+    async fn modules(&self) -> Vec<RequirementModuleObject<'_>> {
+        self.program()
+            .requirements
+            .iter()
+            .flat_map(|requirements| requirements.modules())
+            .map(RequirementModuleObject)
+            .collect()
+    }
+
+    /// Every course of the program, including the ones nested inside of operator groups
+    async fn courses(&self) -> Vec<CourseObject<'_>> {
+        self.program().iter_courses().map(CourseObject).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum RequirementModuleKind {
+    SingleBasicRequirement,
+    BasicRequirements,
+    SelectOneEmphasis,
+    Label,
+    Unimplemented,
+    Reference,
+}
+
+pub struct RequirementModuleObject<'a>(&'a RequirementModule);
+
+#[Object(name = "RequirementModule")]
+impl<'a> RequirementModuleObject<'a> {
+    async fn kind(&self) -> RequirementModuleKind {
+        match self.0 {
+            RequirementModule::SingleBasicRequirement { .. } => {
+                RequirementModuleKind::SingleBasicRequirement
+            }
+            RequirementModule::BasicRequirements { .. } => RequirementModuleKind::BasicRequirements,
+            RequirementModule::SelectOneEmphasis { .. } => RequirementModuleKind::SelectOneEmphasis,
+            RequirementModule::Label { .. } => RequirementModuleKind::Label,
+            RequirementModule::Unimplemented(_) => RequirementModuleKind::Unimplemented,
+            RequirementModule::Reference(_) => RequirementModuleKind::Reference,
+        }
+    }
+
+    async fn title(&self) -> Option<&str> {
+        self.0.title()
+    }
+
+    /// Requirements of the module, or its emphases when its kind is `SELECT_ONE_EMPHASIS`. Empty
+    /// for `LABEL`, `UNIMPLEMENTED` and `REFERENCE` modules.
+    async fn requirements(&self) -> Vec<RequirementObject<'a>> {
+        self.0
+            .requirements()
+            .iter()
+            .map(RequirementObject)
+            .collect()
+    }
+
+    /// GUID of the shared module when the kind is `REFERENCE`
+    async fn reference(&self) -> Option<ID> {
+        match self.0 {
+            RequirementModule::Reference(guid) => Some(ID(guid.to_string())),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum RequirementKind {
+    Courses,
+    SelectFromCourses,
+    Label,
+    ElectivePool,
+}
+
+pub struct RequirementObject<'a>(&'a Requirement);
+
+#[Object(name = "Requirement")]
+impl<'a> RequirementObject<'a> {
+    async fn kind(&self) -> RequirementKind {
+        match self.0 {
+            Requirement::Courses { .. } => RequirementKind::Courses,
+            Requirement::SelectFromCourses { .. } => RequirementKind::SelectFromCourses,
+            Requirement::Label { .. } => RequirementKind::Label,
+            Requirement::ElectivePool { .. } => RequirementKind::ElectivePool,
+        }
+    }
+
+    async fn title(&self) -> Option<&str> {
+        self.0.title()
+    }
+
+    async fn narrative(&self) -> Option<&str> {
+        self.0.req_narrative()
+    }
+
+    /// Top level course entries of the requirement. Operator groups hold their own entries.
+    async fn entries(&self) -> Vec<CourseEntryObject<'a>> {
+        self.0
+            .course_entries()
+            .into_iter()
+            .flat_map(|entries| entries.iter())
+            .map(CourseEntryObject)
+            .collect()
+    }
+
+    /// Hours of electives to select when the kind is `ELECTIVE_POOL` and the pool is a fixed
+    /// number of hours
+    async fn elective_hours(&self) -> Option<u32> {
+        match self.0 {
+            Requirement::ElectivePool {
+                hours: ElectiveHours::Hours(hours),
+                ..
+            } => Some(*hours),
+            _ => None,
+        }
+    }
+
+    /// Total hours of the program that electives make up when the kind is `ELECTIVE_POOL` and the
+    /// pool fills the rest of the program
+    async fn total_hours(&self) -> Option<u32> {
+        match self.0 {
+            Requirement::ElectivePool {
+                hours: ElectiveHours::ToTotal(total),
+                ..
+            } => Some(*total),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum CourseEntryKind {
+    And,
+    Or,
+    Label,
+    Course,
+}
+
+pub struct CourseEntryObject<'a>(&'a CourseEntry);
+
+#[Object(name = "CourseEntry")]
+impl<'a> CourseEntryObject<'a> {
+    async fn kind(&self) -> CourseEntryKind {
+        match self.0 {
+            CourseEntry::And(_) => CourseEntryKind::And,
+            CourseEntry::Or(_) => CourseEntryKind::Or,
+            CourseEntry::Label(_) => CourseEntryKind::Label,
+            CourseEntry::Course(_) => CourseEntryKind::Course,
+        }
+    }
+
+    /// Entries of the group when the kind is `AND` or `OR`
+    async fn entries(&self) -> Vec<CourseEntryObject<'a>> {
+        match self.0 {
+            CourseEntry::And(entries) | CourseEntry::Or(entries) => {
+                entries.iter().map(CourseEntryObject).collect()
+            }
+            CourseEntry::Label(_) | CourseEntry::Course(_) => vec![],
+        }
+    }
+
+    async fn course(&self) -> Option<CourseObject<'a>> {
+        match self.0 {
+            CourseEntry::Course(course) => Some(CourseObject(course)),
+            _ => None,
+        }
+    }
+
+    async fn label(&self) -> Option<LabelObject<'a>> {
+        match self.0 {
+            CourseEntry::Label(label) => Some(LabelObject(label)),
+            _ => None,
+        }
+    }
+}
+
+pub struct CourseObject<'a>(&'a Course);
+
+#[Object(name = "Course")]
+impl CourseObject<'_> {
+    async fn guid(&self) -> ID {
+        ID(self.0.guid.to_string())
+    }
+
+    async fn url(&self) -> &str {
+        &self.0.url
+    }
+
+    async fn subject_code(&self) -> &str {
+        &self.0.subject_code
+    }
+
+    async fn number(&self) -> &str {
+        &self.0.number
+    }
+
+    async fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
+
+    async fn credits_min(&self) -> u8 {
+        self.0.credits.0
+    }
+
+    async fn credits_max(&self) -> Option<u8> {
+        self.0.credits.1
+    }
+
+    /// Full details of the course, if the course catalog has them
+    async fn details(&self, ctx: &Context<'_>) -> Result<Option<CourseDetailsObject>> {
+        course_details(ctx, &self.0.guid).await
+    }
+}
+
+pub struct LabelObject<'a>(&'a Label);
+
+#[Object(name = "Label")]
+impl LabelObject<'_> {
+    async fn guid(&self) -> ID {
+        ID(self.0.guid.to_string())
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn subject_code(&self) -> Option<&str> {
+        self.0.subject_code.as_deref()
+    }
+
+    async fn number(&self) -> Option<&str> {
+        self.0.number.as_deref()
+    }
+
+    async fn credits_min(&self) -> u8 {
+        self.0.credits.0
+    }
+
+    async fn credits_max(&self) -> Option<u8> {
+        self.0.credits.1
+    }
+}
+
+/// Details of a course of a [CatalogSnapshot], kept alive by the snapshot instead of cloned out of
+/// it
+pub struct CourseDetailsObject {
+    snapshot: Arc<CatalogSnapshot>,
+    index: usize,
+}
+
+impl CourseDetailsObject {
+    fn find(snapshot: &Arc<CatalogSnapshot>, guid: &Guid) -> Option<Self> {
+        snapshot
+            .course_index(guid)
+            .map(|index| CourseDetailsObject {
+                snapshot: Arc::clone(snapshot),
+                index,
+            })
+    }
+
+    fn details(&self) -> &CourseDetails {
+        &self.snapshot.courses()[self.index]
+    }
+}
+
+#[Object(name = "CourseDetails")]
+impl CourseDetailsObject {
+    async fn guid(&self) -> ID {
+        ID(self.details().guid.to_string())
+    }
+
+    async fn url(&self) -> &str {
+        &self.details().url
+    }
+
+    async fn subject_code(&self) -> &str {
+        &self.details().subject_code
+    }
+
+    async fn subject_name(&self) -> Option<&str> {
+        self.details().subject_name.as_deref()
+    }
+
+    async fn number(&self) -> &str {
+        &self.details().number
+    }
+
+    async fn name(&self) -> &str {
+        &self.details().name
+    }
+
+    async fn credits_min(&self) -> u8 {
+        self.details().credits_min
+    }
+
+    async fn credits_max(&self) -> Option<u8> {
+        self.details().credits_max
+    }
+
+    async fn description(&self) -> &str {
+        &self.details().description
+    }
+
+    async fn prerequisite_narrative(&self) -> Option<&str> {
+        self.details().prerequisite_narrative.as_deref()
+    }
+
+    async fn prerequisite(&self) -> Option<CourseDetailsObject> {
+        let guid = self.details().prerequisite.as_ref()?;
+        CourseDetailsObject::find(&self.snapshot, guid)
+    }
+
+    async fn corequisite_narrative(&self) -> Option<&str> {
+        self.details().corequisite_narrative.as_deref()
+    }
+
+    async fn corequisite(&self) -> Option<CourseDetailsObject> {
+        let guid = self.details().corequisite.as_ref()?;
+        CourseDetailsObject::find(&self.snapshot, guid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::data::providers::{json_providers::MemoryJsonProvider, CatalogCache};
+
+    const CSC_125: &str = "13A1385C-81AC-493D-ACE8-AA8AB37D2C81";
+
+    fn read_json(name: &str) -> Value {
+        let json = std::fs::read_to_string(format!("../data/{name}")).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn schema(programs: Vec<Value>) -> VislogSchema {
+        let courses = read_json("courses.json")["courses"]["course"]
+            .as_array()
+            .unwrap()
+            .clone();
+        let json_provider = MemoryJsonProvider { programs, courses };
+        let cache = CatalogCache::shared();
+
+        build(
+            ProgramsProvider::with(Box::new(json_provider.clone()), cache.clone()),
+            CoursesProvider::with(Box::new(json_provider), cache),
+        )
+    }
+
+    async fn execute(schema: &VislogSchema, query: &str) -> Value {
+        let response = schema.execute(query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+        response.data.into_json().unwrap()
+    }
+
+    #[tokio::test]
+    async fn programs_are_filtered_by_title() {
+        let schema = schema(vec![
+            read_json("zoology_major.json"),
+            read_json("cs_minor.json"),
+            read_json("cs_major.json"),
+        ]);
+
+        let data = execute(
+            &schema,
+            r#"{ programs(titleContains: "computer SCIENCE") { title } }"#,
+        )
+        .await;
+
+        assert_eq!(
+            data,
+            json!({ "programs": [
+                { "title": "Major in Computer Science—42 hours" },
+                { "title": "Minor in Computer Science—21 or 22 hours" },
+            ]})
+        );
+    }
+
+    #[tokio::test]
+    async fn courses_resolve_into_their_details() {
+        let schema = schema(vec![]);
+
+        let query = format!(
+            "{{ course(guid: \"{CSC_125}\") {{ name prerequisite {{ subjectCode number }} }} }}"
+        );
+        let data = execute(&schema, &query).await;
+
+        assert_eq!(
+            data,
+            json!({ "course": {
+                "name": "Computer Science I: Programming in Java",
+                "prerequisite": { "subjectCode": "CSC", "number": "115" },
+            }})
+        );
+    }
+
+    #[tokio::test]
+    async fn program_courses_link_to_their_details() {
+        let schema = schema(vec![read_json("cs_minor.json")]);
+
+        let data = execute(
+            &schema,
+            r#"{ program(guid: "814DE35B-2CF0-458F-99D4-9100C9D2CA69") {
+                courses { subjectCode number details { name } }
+            } }"#,
+        )
+        .await;
+
+        let courses = data["program"]["courses"].as_array().unwrap();
+        assert!(courses.contains(&json!({
+            "subjectCode": "CSC",
+            "number": "125",
+            "details": { "name": "Computer Science I: Programming in Java" },
+        })));
+    }
+
+    #[tokio::test]
+    async fn deeply_nested_queries_are_rejected() {
+        let schema = schema(vec![]);
+
+        let nested = (0..MAX_DEPTH).fold("name".to_string(), |fields, _| {
+            format!("prerequisite {{ {fields} }}")
+        });
+        let query = format!("{{ course(guid: \"{CSC_125}\") {{ {nested} }} }}");
+        let response = schema.execute(query).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.errors[0].message, "Query is nested too deep.");
+    }
+
+    #[tokio::test]
+    async fn elective_pools_expose_their_hours() {
+        let programs = read_json("programs.json")["programs"]["program"]
+            .as_array()
+            .unwrap()
+            .clone();
+        let schema = schema(programs);
+
+        let data = execute(
+            &schema,
+            r#"{ programs(titleContains: "Applied Psychology") {
+                modules { requirements { kind electiveHours totalHours } }
+            } }"#,
+        )
+        .await;
+
+        let requirements: Vec<&Value> = data["programs"][0]["modules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|module| module["requirements"].as_array().unwrap())
+            .collect();
+        assert!(requirements.contains(&&json!({
+            "kind": "ELECTIVE_POOL",
+            "electiveHours": 18,
+            "totalHours": null,
+        })));
+    }
+
+    /// Catalogs only hold references to shared modules once they are resolved, which the server
+    /// never does, so the module is served on its own
+    struct ModuleQuery(RequirementModule);
+
+    #[Object]
+    impl ModuleQuery {
+        async fn module(&self) -> RequirementModuleObject<'_> {
+            RequirementModuleObject(&self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn reference_modules_expose_their_guid() {
+        let module = RequirementModule::Reference(Guid::try_from(CSC_125).unwrap());
+        let schema = Schema::new(ModuleQuery(module), EmptyMutation, EmptySubscription);
+
+        let response = schema
+            .execute("{ module { kind reference requirements { kind } } }")
+            .await;
+
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({ "module": {
+                "kind": "REFERENCE",
+                "reference": CSC_125,
+                "requirements": [],
+            }})
+        );
+    }
+}
@@ -1,14 +1,54 @@
 use axum::Router;
 
-use crate::data::providers::{courses::CoursesProvider, programs::ProgramsProvider};
-
-pub mod error;
+use crate::{
+    data::{
+        notifications::CatalogNotifier,
+        providers::{courses::CoursesProvider, programs::ProgramsProvider},
+    },
+    CONFIGS,
+};
 
+mod admin;
 mod courses;
+mod events;
+#[cfg(feature = "graphql")]
+mod graphql;
 mod programs;
 
 pub fn routes(programs_provider: ProgramsProvider, courses_provider: CoursesProvider) -> Router {
-    Router::new()
-        .nest("/programs", programs::routes(programs_provider))
-        .nest("/courses", courses::routes(courses_provider))
+    let notifier = CatalogNotifier::new();
+
+    let router = Router::new()
+        .nest(
+            "/programs",
+            programs::routes(programs_provider.clone(), courses_provider.clone()),
+        )
+        .nest("/courses", courses::routes(courses_provider.clone()))
+        .nest("/events", events::routes(notifier.clone()));
+
+    // Reloading re-parses the whole catalog, so it is only exposed to holders of the admin token
+    let router = match CONFIGS
+        .admin
+        .as_ref()
+        .filter(|admin| !admin.token.is_empty())
+    {
+        Some(admin) => router.nest(
+            "/admin",
+            admin::routes(
+                programs_provider.clone(),
+                courses_provider.clone(),
+                notifier,
+                admin.token.clone(),
+            ),
+        ),
+        None => router,
+    };
+
+    #[cfg(feature = "graphql")]
+    let router = router.nest(
+        "/graphql",
+        graphql::routes(programs_provider, courses_provider),
+    );
+
+    router
 }
@@ -1,56 +1,161 @@
+use std::collections::BTreeMap;
+
 use axum::{
+    body::Body,
     extract::{Path, Query, State},
+    http::{header::CONTENT_TYPE, HeaderMap, Response},
+    response::IntoResponse,
     routing::get,
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, instrument};
+use vislog_core::graph::{self, ProgramGraph};
 use vislog_core::parsing::guid::Guid;
-use vislog_core::Program;
 
-use crate::web::error::{Error, Result};
+use crate::web::{
+    error::{Error, Result},
+    etag::{json_with_etag, with_etag},
+};
 
-use crate::data::{fetching, providers::programs::ProgramsProvider};
+use crate::data::{
+    fetching,
+    providers::{courses::CoursesProvider, programs::ProgramsProvider},
+};
+
+/// State for the routes that need to combine programs with the details of their courses
+#[derive(Clone)]
+struct ProgramGraphState {
+    programs_provider: ProgramsProvider,
+    courses_provider: CoursesProvider,
+}
+
+pub fn routes(program_provider: ProgramsProvider, courses_provider: CoursesProvider) -> Router {
+    let graph_routes = Router::new()
+        .route("/:guid/depths", get(get_program_depths_handler))
+        .route("/:guid/graph", get(get_program_graph_handler))
+        .with_state(ProgramGraphState {
+            programs_provider: program_provider.clone(),
+            courses_provider,
+        });
 
-pub fn routes(program_provider: ProgramsProvider) -> Router {
     Router::new()
         .route("/", get(get_all_programs_handler))
         .route("/:guid", get(get_program_handler))
         .route("/titles", get(get_all_program_titles_handler))
         .route("/refresh", get(refresh_all_programs_handler))
         .with_state(program_provider)
+        .merge(graph_routes)
 }
 
-#[instrument(skip(programs_provider), err)]
+#[instrument(skip(headers, programs_provider), err)]
 async fn get_all_programs_handler(
+    headers: HeaderMap,
     State(programs_provider): State<ProgramsProvider>,
-) -> Result<Json<Vec<Program>>> {
+) -> Result<Response<Body>> {
     info!("Getting all programs");
 
-    let (programs, errors) = programs_provider.get_all_programs().await?;
+    let snapshot = programs_provider.snapshot().await?;
 
     debug!(
         "Program count: {}, Error count: {}",
-        programs.len(),
-        errors.len()
+        snapshot.programs().len(),
+        snapshot.program_errors().len()
     );
 
-    Ok(Json(programs))
+    Ok(json_with_etag(&headers, snapshot.programs()))
 }
 
-#[instrument(skip(programs_provider, guid), err)]
+#[instrument(skip(headers, programs_provider, guid), err)]
 async fn get_program_handler(
+    headers: HeaderMap,
     State(programs_provider): State<ProgramsProvider>,
     Path(guid): Path<Guid>,
-) -> Result<Json<Program>> {
+) -> Result<Response<Body>> {
     info!("Getting program with guid: {}", guid);
 
-    let program = programs_provider
-        .get_program(&guid)
-        .await?
+    let snapshot = programs_provider.snapshot().await?;
+    let program = snapshot
+        .program(&guid)
+        .ok_or(Error::ProgramNotFound(guid))?;
+
+    Ok(json_with_etag(&headers, program))
+}
+
+/// Prerequisite depth (semesters of lead time) of every course in a program keyed by course GUID
+#[instrument(skip(headers, state, guid), err)]
+async fn get_program_depths_handler(
+    headers: HeaderMap,
+    State(state): State<ProgramGraphState>,
+    Path(guid): Path<Guid>,
+) -> Result<Response<Body>> {
+    info!("Getting course depths for program with guid: {}", guid);
+
+    state.programs_provider.snapshot().await?;
+    let snapshot = state.courses_provider.snapshot().await?;
+    let program = snapshot
+        .program(&guid)
+        .ok_or(Error::ProgramNotFound(guid))?;
+
+    // Sorted so that the same depths always hash to the same `ETag`
+    let depths: BTreeMap<Guid, u32> = graph::depth_map(program, snapshot.prerequisites())
+        .into_iter()
+        .collect();
+
+    debug!("Course depth count: {}", depths.len());
+
+    Ok(json_with_etag(&headers, depths))
+}
+
+#[derive(Debug, Default, Clone, Copy, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum GraphFormat {
+    Dot,
+    #[default]
+    Json,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProgramGraphParam {
+    format: Option<GraphFormat>,
+}
+
+/// Prerequisite graph of the courses in a program, either as JSON or as a Graphviz DOT digraph
+#[instrument(skip(headers, state, guid), err)]
+async fn get_program_graph_handler(
+    headers: HeaderMap,
+    Query(params): Query<ProgramGraphParam>,
+    State(state): State<ProgramGraphState>,
+    Path(guid): Path<Guid>,
+) -> Result<Response<Body>> {
+    info!("Getting prerequisite graph for program with guid: {}", guid);
+
+    state.programs_provider.snapshot().await?;
+    let snapshot = state.courses_provider.snapshot().await?;
+    let program = snapshot
+        .program(&guid)
         .ok_or(Error::ProgramNotFound(guid))?;
 
-    Ok(Json(program))
+    let program_graph = ProgramGraph::new(program, snapshot.prerequisites());
+
+    debug!(
+        "Node count: {}, Edge count: {}",
+        program_graph.nodes.len(),
+        program_graph.edges.len()
+    );
+
+    let response = match params.format.unwrap_or_default() {
+        GraphFormat::Json => json_with_etag(&headers, program_graph),
+        GraphFormat::Dot => with_etag(&headers, &(GraphFormat::Dot, &program_graph), || {
+            (
+                [(CONTENT_TYPE, "text/vnd.graphviz")],
+                program_graph.to_dot(),
+            )
+                .into_response()
+        }),
+    };
+
+    Ok(response)
 }
 
 #[derive(Debug, Deserialize)]
@@ -58,51 +163,56 @@ struct ProgramTitlesParam {
     with_guid: Option<bool>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Hash, Serialize)]
 #[serde(untagged)]
-enum ProgramTitlesResponse {
-    WithGuid { guid: Guid, title: String },
-    WithoutGuid(String),
+enum ProgramTitlesResponse<'a> {
+    WithGuid { guid: Guid, title: &'a str },
+    WithoutGuid(&'a str),
 }
 
-#[instrument(skip(programs_provider), err)]
+#[instrument(skip(headers, programs_provider), err)]
 async fn get_all_program_titles_handler(
+    headers: HeaderMap,
     Query(with_guid): Query<ProgramTitlesParam>,
     State(programs_provider): State<ProgramsProvider>,
-) -> Result<Json<Vec<ProgramTitlesResponse>>> {
+) -> Result<Response<Body>> {
     info!("Getting all program titles");
 
-    let (programs, _errors) = programs_provider.get_all_programs().await?;
+    let snapshot = programs_provider.snapshot().await?;
     let with_guid = with_guid.with_guid.unwrap_or(false);
 
-    let responses: Vec<ProgramTitlesResponse> = programs
-        .into_iter()
+    let responses: Vec<ProgramTitlesResponse> = snapshot
+        .programs()
+        .iter()
         .map(|p| {
             if with_guid {
                 ProgramTitlesResponse::WithGuid {
                     guid: p.guid,
-                    title: p.title,
+                    title: &p.title,
                 }
             } else {
-                ProgramTitlesResponse::WithoutGuid(p.title)
+                ProgramTitlesResponse::WithoutGuid(&p.title)
             }
         })
         .collect();
 
     debug!("Title count: {}", responses.len());
 
-    Ok(Json(responses))
+    Ok(json_with_etag(&headers, responses))
 }
 
 // TODO: Update state of ProgramsProvider after fetching the lastest data
 #[instrument(skip(programs_provider), err)]
 async fn refresh_all_programs_handler(
     State(programs_provider): State<ProgramsProvider>,
-) -> Result<Json<Vec<Program>>> {
+) -> Result<Response<Body>> {
     info!("Refreshing all programs");
-    let programs = fetching::fetch_all_programs(&programs_provider).await?;
+    let snapshot = fetching::fetch_all_programs(&programs_provider).await?;
 
-    debug!("Programs count after refresh: {}", programs.len());
+    debug!(
+        "Programs count after refresh: {}",
+        snapshot.programs().len()
+    );
 
-    Ok(Json(programs))
+    Ok(Json(snapshot.programs()).into_response())
 }
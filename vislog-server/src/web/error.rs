@@ -11,38 +11,30 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("{0}")]
     ProgramsParsing(#[from] providers::programs::Error),
+    #[error("{0}")]
     CoursesParsing(#[from] providers::courses::Error),
+    #[error("{0}")]
     Fetching(#[from] fetching::error::Error),
+    #[error("{0}")]
+    Reloading(#[from] providers::ReloadError),
+    #[error("no program with GUID {0}")]
     ProgramNotFound(Guid),
+    #[error("no course with GUID {0}")]
     CourseNotFound(Guid),
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{self:?}")
-    }
-}
-
 impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
-        let mut response = StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        let status = match self {
+            Error::ProgramNotFound(_) | Error::CourseNotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let mut response = status.into_response();
 
         response.extensions_mut().insert(Arc::new(self));
 
         response
     }
 }
-
-#[macro_export]
-macro_rules! impl_generic_error_and_display_for_error_type {
-    ($name:ident) => {
-        impl std::error::Error for $name {}
-
-        impl std::fmt::Display for $name {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                write!(f, "{self:?}")
-            }
-        }
-    };
-}
@@ -0,0 +1,68 @@
+use std::hash::Hash;
+
+use axum::{
+    body::Body,
+    http::{
+        header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH},
+        HeaderMap, HeaderValue, Response, StatusCode,
+    },
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+use vislog_core::hash::content_hash;
+
+use crate::CONFIGS;
+
+/// Responds with `value` as JSON along with an `ETag` computed from its content hash, or with a
+/// `304 Not Modified` when the client already has the same content according to the
+/// `If-None-Match` header of the request
+pub fn json_with_etag<T>(request_headers: &HeaderMap, value: T) -> Response<Body>
+where
+    T: Serialize + Hash,
+{
+    with_etag(request_headers, &value, || Json(&value).into_response())
+}
+
+/// Same as [json_with_etag] for responses that aren't JSON. `content` has to hash differently for
+/// every representation of the same value, so that they don't share an `ETag`.
+pub fn with_etag<T>(
+    request_headers: &HeaderMap,
+    content: &T,
+    respond: impl FnOnce() -> Response<Body>,
+) -> Response<Body>
+where
+    T: Hash + ?Sized,
+{
+    let etag = format!("\"{:016x}\"", content_hash(content));
+
+    let mut response = match if_none_match(request_headers, &etag) {
+        true => StatusCode::NOT_MODIFIED.into_response(),
+        false => respond(),
+    };
+
+    let headers = response.headers_mut();
+    headers.insert(
+        ETAG,
+        HeaderValue::from_str(&etag).expect("Hex digits in quotes are a valid header value"),
+    );
+    if let Some(cache) = &CONFIGS.cache {
+        if let Ok(cache_control) = HeaderValue::from_str(&cache.cache_control) {
+            headers.insert(CACHE_CONTROL, cache_control);
+        }
+    }
+
+    response
+}
+
+/// Whether any of the entity tags in the `If-None-Match` header matches the `etag`
+fn if_none_match(request_headers: &HeaderMap, etag: &str) -> bool {
+    request_headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        // Weak comparison, since the body is the same regardless of encoding
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
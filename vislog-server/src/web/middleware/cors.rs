@@ -8,7 +8,7 @@ pub async fn mw_set_access_control_allow_origin(mut res: Response<Body>) -> Resp
     let cors_header_key = "Access-Control-Allow-Origin";
 
     if let Some(cors) = &CONFIGS.cors {
-        if cors.origins.len() >= 1 {
+        if !cors.origins.is_empty() {
             res.headers_mut().insert(
                 cors_header_key,
                 cors.origins_to_string()
@@ -0,0 +1,24 @@
+use std::time::Instant;
+
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request},
+    http::Response,
+    middleware::Next,
+};
+use vislog_core::metrics;
+
+/// Records the latency of every request against the route it matched, e.g. `/api/programs/:guid`
+pub async fn mw_record_query_latency(req: Request, next: Next) -> Response<Body> {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_owned())
+        .unwrap_or_else(|| req.uri().path().to_owned());
+
+    let start = Instant::now();
+    let res = next.run(req).await;
+    metrics::global().record_latency(&route, start.elapsed());
+
+    res
+}
@@ -10,8 +10,8 @@ use std::{
 use axum::{
     body::Body,
     extract::ConnectInfo,
-    http::{HeaderName, Response, StatusCode},
-    middleware::map_response,
+    http::{header::CONTENT_TYPE, HeaderName, Response, StatusCode},
+    middleware::{from_fn, map_response},
     response::IntoResponse,
     routing::get,
     Router,
@@ -24,7 +24,10 @@ use tower_http::{
 };
 use tracing::{info, instrument};
 
-use crate::data::providers::{courses::CoursesProvider, programs::ProgramsProvider};
+use crate::{
+    data::providers::{courses::CoursesProvider, programs::ProgramsProvider},
+    CONFIGS,
+};
 
 #[instrument(skip(addr))]
 async fn check_health_handler(ConnectInfo(addr): ConnectInfo<SocketAddr>) -> Response<Body> {
@@ -32,8 +35,16 @@ async fn check_health_handler(ConnectInfo(addr): ConnectInfo<SocketAddr>) -> Res
     StatusCode::OK.into_response()
 }
 
+#[instrument]
+async fn metrics_handler() -> Response<Body> {
+    let body = vislog_core::metrics::global().snapshot().to_prometheus();
+
+    ([(CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response()
+}
+
 mod api;
 mod error;
+mod etag;
 mod middleware;
 
 #[derive(Debug, Clone, Default)]
@@ -68,7 +79,18 @@ pub fn init_server(
 
     let server = Router::new()
         .route("/check_health", get(check_health_handler))
-        .nest("/api", api::routes(programs_provider, courses_provider));
+        .nest(
+            "/api",
+            api::routes(programs_provider, courses_provider)
+                .route_layer(from_fn(middleware::metrics::mw_record_query_latency)),
+        );
+
+    // Nothing is recorded while metrics are disabled, so there would only be zeros to serve
+    let server = if CONFIGS.metrics.as_ref().is_some_and(|m| m.enabled) {
+        server.route("/metrics", get(metrics_handler))
+    } else {
+        server
+    };
 
     let server = if let Some(path) = static_dir_path {
         server.nest_service("/", ServeDir::new(path))
@@ -0,0 +1,129 @@
+//! WebAssembly bindings for `vislog-core` so that catalog JSON can be parsed, validated and
+//! turned into graphs in the browser without a round trip to the server.
+//!
+//! Build with `wasm-pack build vislog-wasm --target web`.
+
+use serde::Serialize;
+use vislog_core::{
+    catalog::Catalog,
+    error,
+    graph::{self, PrerequisiteGraph},
+    CourseDetails, Program,
+};
+use wasm_bindgen::prelude::*;
+
+/// Name standing in for a file path in the errors of JSON passed in from JavaScript
+const SOURCE: &str = "<input>";
+
+/// Everything parsed out of catalog JSON along with the messages of the entries that failed to
+/// parse
+#[derive(Debug, Serialize)]
+struct ParsedCatalog {
+    programs: Vec<Program>,
+    courses: Vec<CourseDetails>,
+    errors: Vec<String>,
+}
+
+/// Parses the JSON of a single program as returned by the catalog API into the same shape the
+/// server responds with
+#[wasm_bindgen]
+pub fn parse_program(json: &str) -> Result<JsValue, JsError> {
+    let program: Program = error::from_str(json)?;
+
+    to_js_value(&program)
+}
+
+/// Parses a program, a course, a JSON array of programs or a whole catalog dump. Returns
+/// `{ programs, courses, errors }` where `errors` lists the messages of everything that failed to
+/// parse.
+#[wasm_bindgen]
+pub fn parse_catalog(json: &str) -> Result<JsValue, JsError> {
+    let (catalog, errors) = Catalog::parse_json(SOURCE, json);
+
+    to_js_value(&ParsedCatalog {
+        programs: catalog.programs,
+        courses: catalog.courses,
+        errors: errors.iter().map(ToString::to_string).collect(),
+    })
+}
+
+/// Messages of everything in the catalog JSON that fails to parse. Empty when all of it parses.
+#[wasm_bindgen]
+pub fn validate(json: &str) -> Vec<String> {
+    let (_, errors) = Catalog::parse_json(SOURCE, json);
+
+    errors.iter().map(ToString::to_string).collect()
+}
+
+/// Renders the prerequisite graph of a program as a Graphviz DOT digraph. Prerequisite edges are
+/// only drawn when `courses_json`, the JSON of the course catalog, is given.
+#[wasm_bindgen]
+pub fn program_to_dot(json: &str, courses_json: Option<String>) -> Result<String, JsError> {
+    let program: Program = error::from_str(json)?;
+
+    let courses = match courses_json {
+        Some(courses_json) => {
+            let (catalog, errors) = Catalog::parse_json(SOURCE, &courses_json);
+            if let Some(err) = errors.into_iter().next() {
+                return Err(err.into());
+            }
+            catalog.courses
+        }
+        None => vec![],
+    };
+
+    let graph = PrerequisiteGraph::from_course_details(&courses);
+
+    Ok(graph::to_dot(&program, &graph))
+}
+
+/// Converts to a plain JavaScript object with the same shape as the JSON the server responds with
+fn to_js_value<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+
+    Ok(value.serialize(&serializer)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn read_data(file_name: &str) -> String {
+        std::fs::read_to_string(format!("../data/{file_name}")).unwrap()
+    }
+
+    #[test]
+    fn valid_catalog_json_has_no_errors() {
+        assert!(validate(&read_data("cs_minor.json")).is_empty());
+        assert!(validate(&read_data("cs_major.json")).is_empty());
+    }
+
+    #[test]
+    fn invalid_catalog_json_is_reported() {
+        let errors = validate(r#"{"guid": "not a program"}"#);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains(SOURCE), "{errors:?}");
+
+        assert_eq!(validate("not json").len(), 1);
+    }
+
+    #[test]
+    fn prerequisite_edges_are_only_drawn_with_the_courses() {
+        let program = read_data("cs_minor.json");
+
+        let dot = program_to_dot(&program, None).ok().unwrap();
+        assert!(dot.starts_with("digraph \"Minor in Computer Science"));
+        assert!(dot.contains("label=\"CSC 115"), "{dot}");
+        assert!(!dot.contains("->"), "{dot}");
+
+        let dot = program_to_dot(&program, Some(read_data("courses.json")))
+            .ok()
+            .unwrap();
+        // CSC 115 is the prerequisite of CSC 125
+        assert!(
+            dot.contains("\"860AF9C9")
+                && dot.contains("-> \"13A1385C-81AC-493D-ACE8-AA8AB37D2C81\""),
+            "{dot}"
+        );
+    }
+}